@@ -59,6 +59,13 @@ impl EpochState {
         }
     }
 
+    /// Replace the committee that signs checkpoints in this epoch, e.g. when the Simulacrum does
+    /// not hold the keys of the validators in the system state.
+    pub fn with_committee(mut self, committee: Committee) -> Self {
+        self.committee = committee;
+        self
+    }
+
     pub fn epoch(&self) -> EpochId {
         self.epoch_start_state.epoch()
     }
@@ -73,6 +80,15 @@ impl EpochState {
         round
     }
 
+    /// The round `next_consensus_round` will return, without advancing it.
+    pub fn peek_next_consensus_round(&self) -> u64 {
+        self.next_consensus_round
+    }
+
+    pub fn set_next_consensus_round(&mut self, round: u64) {
+        self.next_consensus_round = round;
+    }
+
     pub fn committee(&self) -> &Committee {
         &self.committee
     }
@@ -0,0 +1,214 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Starting a [`Simulacrum`] from the state of another network instead of from genesis.
+//!
+//! A forked Simulacrum continues from a checkpoint of another network (e.g. mainnet), reading the
+//! objects it needs from a [`ForkSource`] as they are accessed. This allows executing transactions
+//! against real on-chain state, without the rest of that network.
+//!
+//! As the Simulacrum doesn't hold the keys of the forked network's validators, its checkpoints are
+//! signed by a local committee. Its accounts are funded with gas coins that only exist in the
+//! fork.
+
+use std::collections::BTreeSet;
+use std::num::NonZeroUsize;
+
+use rand::rngs::OsRng;
+use sui_config::{
+    transaction_deny_config::TransactionDenyConfig, verifier_signing_config::VerifierSigningConfig,
+};
+use sui_swarm_config::network_config_builder::ConfigBuilder;
+use sui_types::{
+    base_types::{SuiAddress, VerifiedExecutionData},
+    committee::Committee,
+    messages_checkpoint::{CheckpointContents, CheckpointSequenceNumber},
+    mock_checkpoint_builder::MockCheckpointBuilder,
+    object::Owner,
+    sui_system_state::SuiSystemStateTrait,
+};
+
+use crate::{
+    epoch_state::EpochState,
+    store::{
+        forked_store::{ForkSource, ForkedStore},
+        in_mem_store::KeyStore,
+        SimulatorStore,
+    },
+    CommitteeWithKeys, Simulacrum,
+};
+
+impl<F: ForkSource> Simulacrum<OsRng, ForkedStore<F>> {
+    /// Create a Simulacrum forked from another network at `checkpoint`, reading its state from
+    /// `source`, using an `OsRng` as the source of randomness.
+    pub fn new_forked(source: F, checkpoint: CheckpointSequenceNumber) -> Self {
+        Self::new_forked_with_rng(OsRng, source, checkpoint)
+    }
+}
+
+impl<R, F> Simulacrum<R, ForkedStore<F>>
+where
+    R: rand::RngCore + rand::CryptoRng,
+    F: ForkSource,
+{
+    /// Create a Simulacrum forked from another network at `checkpoint`, reading its state from
+    /// `source`, using the provided `rng`.
+    ///
+    /// The next checkpoint created is `checkpoint + 1`, in the epoch of the forked system state,
+    /// and the clock continues from the forked clock.
+    pub fn new_forked_with_rng(
+        mut rng: R,
+        source: F,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Self {
+        // The local network only provides the validator and account keys.
+        let config = ConfigBuilder::new_with_temp_dir()
+            .rng(&mut rng)
+            .deterministic_committee_size(NonZeroUsize::new(1).unwrap())
+            .build();
+        let keystore = KeyStore::from_network_config(&config);
+        let mut store = ForkedStore::new(source);
+
+        let system_state = store.get_system_state();
+        let epoch = system_state.epoch();
+        let committee = Committee::new(
+            epoch,
+            config
+                .genesis
+                .committee()
+                .expect("genesis committee must exist")
+                .voting_rights
+                .into_iter()
+                .collect(),
+        );
+        let epoch_state = EpochState::new(system_state).with_committee(committee.clone());
+
+        let checkpoint_builder = MockCheckpointBuilder::new_at_checkpoint(
+            epoch,
+            checkpoint,
+            store.get_clock().timestamp_ms(),
+            &CommitteeWithKeys::new(&keystore, &committee),
+        );
+        let fork_checkpoint = checkpoint_builder
+            .previous_checkpoint()
+            .expect("fork checkpoint was just created")
+            .clone();
+        store.insert_committee(committee);
+        store.insert_checkpoint(fork_checkpoint);
+        store.insert_checkpoint_contents(
+            CheckpointContents::new_with_causally_ordered_execution_data(std::iter::empty::<
+                &VerifiedExecutionData,
+            >()),
+        );
+
+        // Fund the local accounts with their gas coins from the local genesis.
+        let accounts: BTreeSet<SuiAddress> =
+            keystore.accounts().map(|(address, _)| *address).collect();
+        let gas_coins = config
+            .genesis
+            .objects()
+            .iter()
+            .filter(|object| {
+                object.is_gas_coin()
+                    && matches!(object.owner, Owner::AddressOwner(owner) if accounts.contains(&owner))
+            })
+            .map(|object| (object.id(), object.clone()))
+            .collect();
+        store.update_objects(gas_coins, vec![]);
+
+        Self {
+            rng,
+            keystore,
+            genesis: config.genesis,
+            store,
+            checkpoint_builder,
+            epoch_state,
+            deny_config: TransactionDenyConfig::default(),
+            data_ingestion_path: None,
+            verifier_signing_config: VerifierSigningConfig::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_types::{
+        base_types::{ObjectID, SequenceNumber},
+        effects::TransactionEffectsAPI,
+        gas_coin::MIST_PER_SUI,
+        object::Object,
+        sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait,
+    };
+
+    use crate::AdvanceEpochConfig;
+
+    use super::*;
+
+    /// Forks from another Simulacrum.
+    struct SimulacrumSource(Simulacrum);
+
+    impl ForkSource for SimulacrumSource {
+        fn get_object(&self, id: &ObjectID) -> anyhow::Result<Option<Object>> {
+            Ok(self.0.store().get_object(id))
+        }
+
+        fn get_object_at_version(
+            &self,
+            id: &ObjectID,
+            version: SequenceNumber,
+        ) -> anyhow::Result<Option<Object>> {
+            Ok(self.0.store().get_object_at_version(id, version))
+        }
+
+        fn owned_objects(&self, owner: SuiAddress) -> anyhow::Result<Vec<Object>> {
+            Ok(self.0.store().owned_objects(owner).collect())
+        }
+    }
+
+    #[test]
+    fn fork() {
+        let mut source = Simulacrum::new();
+        let recipient = SuiAddress::random_for_testing_only();
+        let (transaction, _) = source.transfer_txn(recipient);
+        let (effects, _) = source.execute_transaction(transaction).unwrap();
+        let checkpoint = source.create_checkpoint();
+        let timestamp_ms = source.store().get_clock().timestamp_ms();
+        let received = source.store().owned_objects(recipient).next().unwrap();
+
+        let mut fork = Simulacrum::new_forked(SimulacrumSource(source), checkpoint.sequence_number);
+        assert_eq!(fork.store().get_clock().timestamp_ms(), timestamp_ms);
+        assert_eq!(
+            fork.epoch_start_state().epoch(),
+            checkpoint.epoch,
+            "fork continues in the epoch of the source",
+        );
+
+        // State from before the fork is fetched from the source.
+        assert_eq!(
+            fork.store().get_object(&received.id()),
+            Some(received.clone())
+        );
+        assert!(fork
+            .store()
+            .get_transaction(effects.transaction_digest())
+            .is_none());
+        assert_eq!(
+            fork.store().owned_objects(recipient).collect::<Vec<_>>(),
+            vec![received.clone()],
+        );
+
+        // Transactions can be executed and checkpointed on top of the forked state.
+        let effects = fork.request_gas(recipient, MIST_PER_SUI).unwrap();
+        assert!(effects.status().is_ok());
+        assert_eq!(fork.store().owned_objects(recipient).count(), 2);
+        let next = fork.create_checkpoint();
+        assert_eq!(next.sequence_number, checkpoint.sequence_number + 1);
+
+        fork.advance_epoch(AdvanceEpochConfig::default());
+        assert_eq!(fork.epoch_start_state().epoch(), checkpoint.epoch + 1);
+        assert!(fork
+            .store()
+            .get_committee_by_epoch(checkpoint.epoch + 1)
+            .is_some());
+    }
+}
@@ -47,8 +47,11 @@ use sui_types::{
 };
 
 use self::epoch_state::EpochState;
+pub use self::snapshot::{SimulationProgress, SimulationState};
+pub use self::store::forked_store::{ForkSource, ForkedStore};
 pub use self::store::in_mem_store::InMemoryStore;
 use self::store::in_mem_store::KeyStore;
+pub use self::store::rocks_db_store::RocksDbStore;
 pub use self::store::SimulatorStore;
 use sui_types::messages_checkpoint::{CheckpointContents, CheckpointSequenceNumber};
 use sui_types::mock_checkpoint_builder::{MockCheckpointBuilder, ValidatorKeypairProvider};
@@ -83,6 +86,8 @@ pub struct AdvanceEpochConfig {
 }
 
 mod epoch_state;
+mod fork;
+mod snapshot;
 pub mod store;
 
 /// A `Simulacrum` of Sui.
@@ -176,7 +181,7 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
         let genesis = &config.genesis;
         let epoch_state = EpochState::new(genesis.sui_system_object());
 
-        Self {
+        let mut simulacrum = Self {
             rng,
             keystore,
             genesis: genesis.clone(),
@@ -186,7 +191,9 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
             deny_config: TransactionDenyConfig::default(),
             verifier_signing_config: VerifierSigningConfig::default(),
             data_ingestion_path: None,
-        }
+        };
+        simulacrum.persist_simulation_state();
+        simulacrum
     }
 
     /// Attempts to execute the provided Transaction.
@@ -219,16 +226,28 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
             written, events, ..
         } = inner_temporary_store;
 
-        self.store.insert_executed_transaction(
-            transaction.clone(),
-            effects.clone(),
-            events,
-            written,
-        );
-
         // Insert into checkpoint builder
         self.checkpoint_builder
-            .push_transaction(transaction, effects.clone());
+            .push_transaction(transaction.clone(), effects.clone());
+
+        // The transaction is stored along with the simulation progress that includes it in the
+        // pending checkpoint, so a persistent store saves both atomically.
+        match self.persisted_simulation_progress() {
+            Some(progress) => self.store.insert_executed_transaction_with_progress(
+                transaction,
+                effects.clone(),
+                events,
+                written,
+                &progress,
+            ),
+            None => self.store.insert_executed_transaction(
+                transaction,
+                effects.clone(),
+                events,
+                written,
+            ),
+        }
+
         Ok((effects, execution_error_opt.err()))
     }
 
@@ -239,13 +258,26 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
         let (checkpoint, contents, _) = self
             .checkpoint_builder
             .build(&committee, self.store.get_clock().timestamp_ms());
-        self.store.insert_checkpoint(checkpoint.clone());
-        self.store.insert_checkpoint_contents(contents.clone());
+        self.insert_checkpoint(checkpoint.clone(), contents.clone());
         self.process_data_ingestion(checkpoint.clone(), contents)
             .unwrap();
         checkpoint
     }
 
+    /// Store a checkpoint that was just built, along with the simulation progress after building
+    /// it, so a persistent store saves both atomically.
+    fn insert_checkpoint(&mut self, checkpoint: VerifiedCheckpoint, contents: CheckpointContents) {
+        match self.persisted_simulation_progress() {
+            Some(progress) => self
+                .store
+                .insert_checkpoint_with_progress(checkpoint, contents, &progress),
+            None => {
+                self.store.insert_checkpoint(checkpoint);
+                self.store.insert_checkpoint_contents(contents);
+            }
+        }
+    }
+
     /// Advances the clock by `duration`.
     ///
     /// This creates and executes a ConsensusCommitPrologue transaction which advances the chain
@@ -349,7 +381,7 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
         self.execute_transaction(tx.into())
             .expect("advancing the epoch cannot fail");
 
-        let new_epoch_state = EpochState::new(self.store.get_system_state());
+        let new_epoch_state = self.next_epoch_state();
         let end_of_epoch_data = EndOfEpochData {
             next_epoch_committee: new_epoch_state.committee().voting_rights.clone(),
             next_epoch_protocol_version,
@@ -363,10 +395,35 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
             end_of_epoch_data,
         );
 
-        self.store.insert_checkpoint(checkpoint.clone());
-        self.store.insert_checkpoint_contents(contents.clone());
-        self.process_data_ingestion(checkpoint, contents).unwrap();
+        // The progress saved with the checkpoint is already in the new epoch.
         self.epoch_state = new_epoch_state;
+        self.insert_checkpoint(checkpoint.clone(), contents.clone());
+        self.process_data_ingestion(checkpoint, contents).unwrap();
+    }
+
+    /// The state of the epoch that was just started. The committee comes from the system state,
+    /// unless this Simulacrum does not hold the keys of its validators (e.g. when it was forked
+    /// from another network), in which case the current committee carries over.
+    fn next_epoch_state(&self) -> EpochState {
+        let epoch_state = EpochState::new(self.store.get_system_state());
+        if epoch_state
+            .committee()
+            .names()
+            .all(|name| self.keystore.validator(name).is_some())
+        {
+            return epoch_state;
+        }
+
+        let committee = Committee::new(
+            epoch_state.epoch(),
+            self.epoch_state
+                .committee()
+                .voting_rights
+                .iter()
+                .cloned()
+                .collect(),
+        );
+        epoch_state.with_committee(committee)
     }
 
     pub fn store(&self) -> &dyn SimulatorStore {
@@ -497,6 +554,7 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
         let committee = CommitteeWithKeys::new(&self.keystore, self.epoch_state.committee());
         self.checkpoint_builder
             .override_next_checkpoint_number(number, &committee);
+        self.persist_simulation_progress();
     }
 
     fn process_data_ingestion(
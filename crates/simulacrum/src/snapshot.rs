@@ -0,0 +1,322 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Saving the state of a [`Simulacrum`] and resuming it later.
+//!
+//! Besides the contents of its store (including the clock and system state objects), a
+//! Simulacrum is made of a [`SimulationState`]: the validator and account keys, the consensus
+//! round of the current epoch and the state of the checkpoint builder, including any transactions
+//! that were executed but not yet included in a checkpoint.
+//!
+//! An in-memory Simulacrum can be written to a file with [`Simulacrum::save`], and read back with
+//! [`Simulacrum::load`]. A persistent store is handed the simulation state when the Simulacrum is
+//! created, with [`SimulatorStore::save_simulation_state`], and only the [`SimulationProgress`]
+//! (the state without the keys, which never change) from then on, along with the transactions and
+//! checkpoints that change it. The simulation can then be resumed from the store alone (see
+//! [`Simulacrum::resume`]). In both cases the data ingestion path is not saved, and can be set
+//! again after resuming.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use sui_config::{
+    genesis, transaction_deny_config::TransactionDenyConfig,
+    verifier_signing_config::VerifierSigningConfig,
+};
+use sui_types::{
+    base_types::VerifiedExecutionData, digests::TransactionDigest, gas::GasCostSummary,
+    messages_checkpoint::TrustedCheckpoint, mock_checkpoint_builder::MockCheckpointBuilder,
+};
+
+use crate::{
+    epoch_state::EpochState,
+    store::{
+        in_mem_store::{InMemoryStoreSnapshot, KeyStore, KeyStoreSnapshot},
+        rocks_db_store::RocksDbStore,
+    },
+    InMemoryStore, Simulacrum, SimulatorStore,
+};
+
+/// The state of a Simulacrum that is not part of its store. It is opaque, and only meant to be
+/// persisted by stores and handed back to resume the simulation.
+#[derive(Serialize, Deserialize)]
+pub struct SimulationState {
+    keystore: KeyStoreSnapshot,
+    progress: SimulationProgress,
+}
+
+/// The part of a [`SimulationState`] that changes as the simulation runs. It is opaque, like the
+/// rest of the state.
+#[derive(Serialize, Deserialize)]
+pub struct SimulationProgress {
+    next_consensus_round: u64,
+    checkpoint_builder: CheckpointBuilderSnapshot,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CheckpointBuilderSnapshot {
+    previous_checkpoint: Option<TrustedCheckpoint>,
+    epoch: u64,
+    epoch_rolling_gas_cost_summary: GasCostSummary,
+    /// Transactions executed since the last checkpoint. Their contents are part of the store.
+    pending_transactions: Vec<TransactionDigest>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SimulacrumSnapshot {
+    genesis: genesis::Genesis,
+    store: InMemoryStoreSnapshot,
+    state: SimulationState,
+}
+
+impl SimulationState {
+    pub(crate) fn new(keystore: KeyStoreSnapshot, progress: SimulationProgress) -> Self {
+        Self { keystore, progress }
+    }
+
+    pub(crate) fn keystore(&self) -> &KeyStoreSnapshot {
+        &self.keystore
+    }
+
+    pub(crate) fn progress(&self) -> &SimulationProgress {
+        &self.progress
+    }
+}
+
+impl<R, S: SimulatorStore> Simulacrum<R, S> {
+    pub(crate) fn simulation_state(&self) -> SimulationState {
+        SimulationState {
+            keystore: self.keystore.snapshot(),
+            progress: self.simulation_progress(),
+        }
+    }
+
+    pub(crate) fn simulation_progress(&self) -> SimulationProgress {
+        let builder = &self.checkpoint_builder;
+        SimulationProgress {
+            next_consensus_round: self.epoch_state.peek_next_consensus_round(),
+            checkpoint_builder: CheckpointBuilderSnapshot {
+                previous_checkpoint: builder
+                    .previous_checkpoint()
+                    .map(|checkpoint| checkpoint.serializable_ref().clone()),
+                epoch: builder.epoch(),
+                epoch_rolling_gas_cost_summary: builder.epoch_rolling_gas_cost_summary().clone(),
+                pending_transactions: builder
+                    .transactions()
+                    .iter()
+                    .map(|data| *data.transaction.digest())
+                    .collect(),
+            },
+        }
+    }
+
+    /// The current simulation progress, if the store persists it. Gathering the progress clones
+    /// the pending transactions, so it is skipped for stores that would ignore it.
+    pub(crate) fn persisted_simulation_progress(&self) -> Option<SimulationProgress> {
+        self.store
+            .persists_simulation_state()
+            .then(|| self.simulation_progress())
+    }
+
+    /// Hand the whole simulation state to the store, if it persists it. This is only needed once,
+    /// when the simulation starts: afterwards, only its progress changes.
+    pub(crate) fn persist_simulation_state(&mut self) {
+        if self.store.persists_simulation_state() {
+            let state = self.simulation_state();
+            self.store.save_simulation_state(&state);
+        }
+    }
+
+    /// Hand the current simulation progress to the store, if it persists it.
+    pub(crate) fn persist_simulation_progress(&mut self) {
+        if let Some(progress) = self.persisted_simulation_progress() {
+            self.store.save_simulation_progress(&progress);
+        }
+    }
+
+    fn from_simulation_state(
+        rng: R,
+        genesis: genesis::Genesis,
+        store: S,
+        state: SimulationState,
+    ) -> Result<Self> {
+        let SimulationState {
+            keystore,
+            progress:
+                SimulationProgress {
+                    next_consensus_round,
+                    checkpoint_builder,
+                },
+        } = state;
+
+        let keystore = KeyStore::from_snapshot(keystore)?;
+
+        let mut epoch_state = EpochState::new(store.get_system_state());
+        epoch_state.set_next_consensus_round(next_consensus_round);
+
+        let transactions = checkpoint_builder
+            .pending_transactions
+            .iter()
+            .map(|digest| {
+                let transaction = store
+                    .get_transaction(digest)
+                    .ok_or_else(|| anyhow!("Missing pending transaction {digest}"))?;
+                let effects = store
+                    .get_transaction_effects(digest)
+                    .ok_or_else(|| anyhow!("Missing effects of pending transaction {digest}"))?;
+                Ok(VerifiedExecutionData::new(transaction, effects))
+            })
+            .collect::<Result<_>>()?;
+
+        let checkpoint_builder = MockCheckpointBuilder::new_with_state(
+            checkpoint_builder.previous_checkpoint.map(Into::into),
+            checkpoint_builder.epoch,
+            checkpoint_builder.epoch_rolling_gas_cost_summary,
+            transactions,
+        );
+
+        Ok(Self {
+            rng,
+            keystore,
+            genesis,
+            store,
+            checkpoint_builder,
+            epoch_state,
+            deny_config: TransactionDenyConfig::default(),
+            data_ingestion_path: None,
+            verifier_signing_config: VerifierSigningConfig::default(),
+        })
+    }
+}
+
+impl Simulacrum {
+    /// Load a Simulacrum previously written with [`Simulacrum::save`], using an `OsRng` as the
+    /// source of randomness from then on.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::load_with_rng(path, OsRng)
+    }
+}
+
+impl<R> Simulacrum<R, InMemoryStore> {
+    /// Write the state of this Simulacrum to the file at `path`, so that it can be resumed later
+    /// with [`Simulacrum::load`].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let snapshot = SimulacrumSnapshot {
+            genesis: self.genesis.clone(),
+            store: self.store.snapshot(),
+            state: self.simulation_state(),
+        };
+
+        let bytes = bcs::to_bytes(&snapshot).context("Failed to serialize Simulacrum")?;
+        std::fs::write(path, bytes)
+            .with_context(|| format!("Failed to write Simulacrum to {}", path.display()))
+    }
+
+    /// Load a Simulacrum previously written with [`Simulacrum::save`], using the provided `rng`
+    /// as the source of randomness from then on.
+    pub fn load_with_rng(path: impl AsRef<Path>, rng: R) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read Simulacrum from {}", path.display()))?;
+        let SimulacrumSnapshot {
+            genesis,
+            store,
+            state,
+        } = bcs::from_bytes(&bytes).context("Failed to deserialize Simulacrum")?;
+
+        Self::from_simulation_state(rng, genesis, InMemoryStore::from_snapshot(store), state)
+    }
+}
+
+impl Simulacrum<OsRng, RocksDbStore> {
+    /// Resume the simulation persisted in `store`, using an `OsRng` as the source of randomness
+    /// from then on.
+    pub fn resume(store: RocksDbStore) -> Result<Self> {
+        Self::resume_with_rng(store, OsRng)
+    }
+}
+
+impl<R> Simulacrum<R, RocksDbStore> {
+    /// Resume the simulation persisted in `store`, using the provided `rng` as the source of
+    /// randomness from then on.
+    pub fn resume_with_rng(store: RocksDbStore, rng: R) -> Result<Self> {
+        let genesis = store.genesis()?;
+        let state = store
+            .simulation_state()?
+            .context("Store does not contain a simulation to resume")?;
+        Self::from_simulation_state(rng, genesis, store, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sui_types::{
+        base_types::SuiAddress, effects::TransactionEffectsAPI,
+        sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait,
+    };
+
+    use crate::AdvanceEpochConfig;
+
+    use super::*;
+
+    #[test]
+    fn save_and_load() {
+        let mut sim = Simulacrum::new();
+        let recipient = SuiAddress::random_for_testing_only();
+
+        let (transaction, _) = sim.transfer_txn(recipient);
+        sim.execute_transaction(transaction).unwrap();
+        sim.advance_clock(Duration::from_millis(100));
+        sim.create_checkpoint();
+        sim.advance_epoch(AdvanceEpochConfig::default());
+
+        // Leave a transaction pending, to be included in the first checkpoint after loading.
+        let (transaction, _) = sim.transfer_txn(recipient);
+        let (pending, _) = sim.execute_transaction(transaction).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("simulacrum.bcs");
+        sim.save(&path).unwrap();
+
+        let mut loaded = Simulacrum::load(&path).unwrap();
+        assert_eq!(
+            sim.store().get_highest_checkpint(),
+            loaded.store().get_highest_checkpint(),
+        );
+        assert_eq!(
+            sim.store().get_clock().timestamp_ms(),
+            loaded.store().get_clock().timestamp_ms(),
+        );
+        assert_eq!(
+            sim.epoch_start_state().epoch(),
+            loaded.epoch_start_state().epoch(),
+        );
+        assert_eq!(
+            sim.store().get_committee_by_epoch(1),
+            loaded.store().get_committee_by_epoch(1),
+        );
+
+        // Both instances continue identically from the saved state.
+        let expected = sim.create_checkpoint();
+        let actual = loaded.create_checkpoint();
+        assert_eq!(expected, actual);
+
+        let contents = loaded
+            .store()
+            .get_checkpoint_contents(&actual.content_digest)
+            .unwrap();
+        assert!(contents
+            .iter()
+            .any(|digests| digests.transaction == *pending.transaction_digest()));
+
+        assert_eq!(
+            sim.advance_clock(Duration::from_millis(1)),
+            loaded.advance_clock(Duration::from_millis(1)),
+        );
+    }
+}
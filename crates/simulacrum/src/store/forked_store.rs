@@ -0,0 +1,361 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::RwLock;
+
+use sui_types::storage::{load_package_object_from_object_store, PackageObject};
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
+    committee::{Committee, EpochId},
+    digests::{ObjectDigest, TransactionDigest},
+    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    error::SuiError,
+    messages_checkpoint::{
+        CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
+        VerifiedCheckpoint,
+    },
+    object::{Object, Owner},
+    storage::{BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync},
+    transaction::VerifiedTransaction,
+};
+
+use super::{in_mem_store::InMemoryStore, SimulatorStore};
+
+/// The state of another network that a [`ForkedStore`] reads objects from, as of the checkpoint
+/// it was forked at. Implementations can read from RPC, GraphQL, a formal snapshot, etc.
+///
+/// Errors are not treated as missing objects: a [`ForkedStore`] panics if its source fails, like
+/// the other stores do on a failed read, rather than executing against incomplete state.
+pub trait ForkSource {
+    /// The latest version of object `id` as of the forked checkpoint, if it was live then.
+    fn get_object(&self, id: &ObjectID) -> anyhow::Result<Option<Object>>;
+
+    /// Object `id` at `version`.
+    fn get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> anyhow::Result<Option<Object>>;
+
+    /// The objects owned by `owner` as of the forked checkpoint. Sources that can only look up
+    /// objects by ID don't implement this, and a [`ForkedStore`] over them only knows about the
+    /// objects an address owns if they were written since the fork.
+    fn owned_objects(&self, _owner: SuiAddress) -> anyhow::Result<Vec<Object>> {
+        Ok(vec![])
+    }
+}
+
+/// A store for a Simulacrum forked from the state of another network.
+///
+/// Everything that happens after the fork (checkpoints, transactions, written objects) is kept in
+/// an [`InMemoryStore`]. Objects that have not been touched since the fork are fetched from the
+/// [`ForkSource`] the first time they are accessed, and cached.
+///
+/// Objects owned by an address are the ones written to it since the fork, followed by the ones
+/// the source reports it owned at the fork (see [`ForkSource::owned_objects`]) that have not been
+/// touched since.
+pub struct ForkedStore<F> {
+    local: InMemoryStore,
+    source: F,
+
+    /// Objects deleted or wrapped since the fork, which must not be fetched from the source.
+    removed: HashSet<ObjectID>,
+
+    /// Objects fetched from the source, as of the fork (`None` if it did not exist).
+    latest: RwLock<HashMap<ObjectID, Option<Object>>>,
+    versions: RwLock<HashMap<(ObjectID, SequenceNumber), Option<Object>>>,
+}
+
+impl<F: ForkSource> ForkedStore<F> {
+    pub fn new(source: F) -> Self {
+        Self {
+            local: InMemoryStore::default(),
+            source,
+            removed: HashSet::new(),
+            latest: RwLock::new(HashMap::new()),
+            versions: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn source(&self) -> &F {
+        &self.source
+    }
+
+    fn fetch_object(&self, id: &ObjectID) -> Option<Object> {
+        if let Some(object) = self.latest.read().unwrap().get(id) {
+            return object.clone();
+        }
+
+        let object = self
+            .source
+            .get_object(id)
+            .unwrap_or_else(|e| panic!("Failed to fetch object {id} from fork source: {e:#}"));
+
+        self.latest.write().unwrap().insert(*id, object.clone());
+        object
+    }
+
+    fn fetch_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        if let Some(object) = self.versions.read().unwrap().get(&(*id, version)) {
+            return object.clone();
+        }
+
+        if let Some(Some(object)) = self.latest.read().unwrap().get(id) {
+            if object.version() == version {
+                return Some(object.clone());
+            }
+        }
+
+        let object = self
+            .source
+            .get_object_at_version(id, version)
+            .unwrap_or_else(|e| {
+                panic!("Failed to fetch object {id} at version {version} from fork source: {e:#}")
+            });
+
+        self.versions
+            .write()
+            .unwrap()
+            .insert((*id, version), object.clone());
+        object
+    }
+}
+
+impl<F: ForkSource> SimulatorStore for ForkedStore<F> {
+    fn get_checkpoint_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Option<VerifiedCheckpoint> {
+        self.local
+            .get_checkpoint_by_sequence_number(sequence_number)
+            .cloned()
+    }
+
+    fn get_checkpoint_by_digest(&self, digest: &CheckpointDigest) -> Option<VerifiedCheckpoint> {
+        self.local.get_checkpoint_by_digest(digest).cloned()
+    }
+
+    fn get_highest_checkpint(&self) -> Option<VerifiedCheckpoint> {
+        self.local.get_highest_checkpint().cloned()
+    }
+
+    fn get_checkpoint_contents(
+        &self,
+        digest: &CheckpointContentsDigest,
+    ) -> Option<CheckpointContents> {
+        self.local.get_checkpoint_contents(digest).cloned()
+    }
+
+    fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<Committee> {
+        self.local.get_committee_by_epoch(epoch).cloned()
+    }
+
+    fn get_transaction(&self, digest: &TransactionDigest) -> Option<VerifiedTransaction> {
+        self.local.get_transaction(digest).cloned()
+    }
+
+    fn get_transaction_effects(&self, digest: &TransactionDigest) -> Option<TransactionEffects> {
+        self.local.get_transaction_effects(digest).cloned()
+    }
+
+    fn get_transaction_events(&self, digest: &TransactionDigest) -> Option<TransactionEvents> {
+        self.local.get_transaction_events(digest).cloned()
+    }
+
+    fn get_object(&self, id: &ObjectID) -> Option<Object> {
+        if let Some(object) = self.local.get_object(id) {
+            return Some(object.clone());
+        }
+
+        if self.removed.contains(id) {
+            return None;
+        }
+
+        self.fetch_object(id)
+    }
+
+    fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        if let Some(object) = self.local.get_object_at_version(id, version) {
+            return Some(object.clone());
+        }
+
+        self.fetch_object_at_version(id, version)
+    }
+
+    fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState {
+        sui_types::sui_system_state::get_sui_system_state(self).expect("system state must exist")
+    }
+
+    fn get_clock(&self) -> sui_types::clock::Clock {
+        SimulatorStore::get_object(self, &sui_types::SUI_CLOCK_OBJECT_ID)
+            .expect("clock should exist")
+            .to_rust()
+            .expect("clock object should deserialize")
+    }
+
+    fn owned_objects(&self, owner: SuiAddress) -> Box<dyn Iterator<Item = Object> + '_> {
+        let forked = self
+            .source
+            .owned_objects(owner)
+            .unwrap_or_else(|e| {
+                panic!("Failed to fetch objects owned by {owner} from fork source: {e:#}")
+            })
+            .into_iter()
+            .filter(move |object| {
+                let id = object.id();
+                !self.removed.contains(&id) && self.local.get_object(&id).is_none()
+            });
+
+        Box::new(self.local.owned_objects(owner).cloned().chain(forked))
+    }
+
+    fn insert_checkpoint(&mut self, checkpoint: VerifiedCheckpoint) {
+        self.local.insert_checkpoint(checkpoint)
+    }
+
+    fn insert_checkpoint_contents(&mut self, contents: CheckpointContents) {
+        self.local.insert_checkpoint_contents(contents)
+    }
+
+    fn insert_committee(&mut self, committee: Committee) {
+        self.local.insert_committee(committee)
+    }
+
+    fn insert_executed_transaction(
+        &mut self,
+        transaction: VerifiedTransaction,
+        effects: TransactionEffects,
+        events: TransactionEvents,
+        written_objects: BTreeMap<ObjectID, Object>,
+    ) {
+        let deleted_objects = effects.deleted();
+        let tx_digest = *effects.transaction_digest();
+
+        // Wrapped objects are not live anymore, but their pre-fork version must not be fetched.
+        self.removed
+            .extend(effects.wrapped().into_iter().map(|(id, _, _)| id));
+
+        self.insert_transaction(transaction);
+        self.insert_transaction_effects(effects);
+        self.insert_events(&tx_digest, events);
+        self.update_objects(written_objects, deleted_objects);
+    }
+
+    fn insert_transaction(&mut self, transaction: VerifiedTransaction) {
+        self.local.insert_transaction(transaction)
+    }
+
+    fn insert_transaction_effects(&mut self, effects: TransactionEffects) {
+        self.local.insert_transaction_effects(effects)
+    }
+
+    fn insert_events(&mut self, tx_digest: &TransactionDigest, events: TransactionEvents) {
+        self.local.insert_events(tx_digest, events)
+    }
+
+    fn update_objects(
+        &mut self,
+        written_objects: BTreeMap<ObjectID, Object>,
+        deleted_objects: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+    ) {
+        for (object_id, _, _) in &deleted_objects {
+            self.removed.insert(*object_id);
+        }
+
+        for object_id in written_objects.keys() {
+            self.removed.remove(object_id);
+        }
+
+        self.local.update_objects(written_objects, deleted_objects)
+    }
+
+    fn backing_store(&self) -> &dyn sui_types::storage::BackingStore {
+        self
+    }
+}
+
+impl<F: ForkSource> BackingPackageStore for ForkedStore<F> {
+    fn get_package_object(
+        &self,
+        package_id: &ObjectID,
+    ) -> sui_types::error::SuiResult<Option<PackageObject>> {
+        load_package_object_from_object_store(self, package_id)
+    }
+}
+
+impl<F: ForkSource> ChildObjectResolver for ForkedStore<F> {
+    fn read_child_object(
+        &self,
+        parent: &ObjectID,
+        child: &ObjectID,
+        child_version_upper_bound: SequenceNumber,
+    ) -> sui_types::error::SuiResult<Option<Object>> {
+        let child_object = match SimulatorStore::get_object(self, child) {
+            None => return Ok(None),
+            Some(obj) => obj,
+        };
+
+        let parent = *parent;
+        if child_object.owner != Owner::ObjectOwner(parent.into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: parent,
+                actual_owner: child_object.owner.clone(),
+            });
+        }
+
+        if child_object.version() > child_version_upper_bound {
+            return Err(SuiError::UnsupportedFeatureError {
+                error: "TODO ForkedStore::read_child_object does not yet support bounded reads"
+                    .to_owned(),
+            });
+        }
+
+        Ok(Some(child_object))
+    }
+
+    fn get_object_received_at_version(
+        &self,
+        owner: &ObjectID,
+        receiving_object_id: &ObjectID,
+        receive_object_at_version: SequenceNumber,
+        _epoch_id: EpochId,
+    ) -> sui_types::error::SuiResult<Option<Object>> {
+        let recv_object = match SimulatorStore::get_object(self, receiving_object_id) {
+            None => return Ok(None),
+            Some(obj) => obj,
+        };
+        if recv_object.owner != Owner::AddressOwner((*owner).into()) {
+            return Ok(None);
+        }
+
+        if recv_object.version() != receive_object_at_version {
+            return Ok(None);
+        }
+        Ok(Some(recv_object))
+    }
+}
+
+impl<F: ForkSource> ObjectStore for ForkedStore<F> {
+    fn get_object(&self, object_id: &ObjectID) -> Option<Object> {
+        SimulatorStore::get_object(self, object_id)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: sui_types::base_types::VersionNumber,
+    ) -> Option<Object> {
+        SimulatorStore::get_object_at_version(self, object_id, version)
+    }
+}
+
+impl<F> ParentSync for ForkedStore<F> {
+    fn get_latest_parent_entry_ref_deprecated(
+        &self,
+        _object_id: ObjectID,
+    ) -> Option<sui_types::base_types::ObjectRef> {
+        panic!("Never called in newer protocol versions")
+    }
+}
@@ -1,9 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::anyhow;
+use fastcrypto::traits::{EncodeDecodeBase64, KeyPair};
 use move_binary_format::CompiledModule;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::{language_storage::ModuleId, resolver::ModuleResolver};
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 use sui_config::genesis;
 use sui_types::storage::{get_module, load_package_object_from_object_store, PackageObject};
@@ -16,11 +19,11 @@ use sui_types::{
     error::SuiError,
     messages_checkpoint::{
         CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
-        VerifiedCheckpoint,
+        TrustedCheckpoint, VerifiedCheckpoint,
     },
     object::{Object, Owner},
     storage::{BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync},
-    transaction::VerifiedTransaction,
+    transaction::{TrustedTransaction, VerifiedTransaction},
 };
 
 use super::SimulatorStore;
@@ -37,8 +40,8 @@ pub struct InMemoryStore {
     effects: HashMap<TransactionDigest, TransactionEffects>,
     events: HashMap<TransactionDigest, TransactionEvents>,
 
-    // Committee data
-    epoch_to_committee: Vec<Committee>,
+    // Committee data. Epochs are contiguous, but don't necessarily start at genesis.
+    epoch_to_committee: BTreeMap<EpochId, Committee>,
 
     // Object data
     live_objects: HashMap<ObjectID, SequenceNumber>,
@@ -82,7 +85,7 @@ impl InMemoryStore {
     }
 
     pub fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<&Committee> {
-        self.epoch_to_committee.get(epoch as usize)
+        self.epoch_to_committee.get(&epoch)
     }
     pub fn get_transaction(&self, digest: &TransactionDigest) -> Option<&VerifiedTransaction> {
         self.transactions.get(digest)
@@ -156,14 +159,18 @@ impl InMemoryStore {
     }
 
     pub fn insert_committee(&mut self, committee: Committee) {
-        let epoch = committee.epoch as usize;
+        let epoch = committee.epoch;
 
-        if self.epoch_to_committee.get(epoch).is_some() {
+        if self.epoch_to_committee.contains_key(&epoch) {
             return;
         }
 
-        if self.epoch_to_committee.len() == epoch {
-            self.epoch_to_committee.push(committee);
+        let next_epoch = self
+            .epoch_to_committee
+            .last_key_value()
+            .map(|(last, _)| last + 1);
+        if next_epoch.is_none_or(|next| next == epoch) {
+            self.epoch_to_committee.insert(epoch, committee);
         } else {
             panic!("committee was inserted into EpochCommitteeMap out of order");
         }
@@ -216,6 +223,99 @@ impl InMemoryStore {
     }
 }
 
+/// The serializable contents of an [`InMemoryStore`], used to save a store to disk and load it
+/// back.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct InMemoryStoreSnapshot {
+    checkpoints: Vec<TrustedCheckpoint>,
+    checkpoint_contents: Vec<CheckpointContents>,
+    transactions: Vec<TrustedTransaction>,
+    effects: Vec<TransactionEffects>,
+    events: Vec<(TransactionDigest, TransactionEvents)>,
+    epoch_to_committee: Vec<Committee>,
+    live_objects: Vec<(ObjectID, SequenceNumber)>,
+    objects: Vec<Object>,
+}
+
+impl InMemoryStore {
+    pub(crate) fn snapshot(&self) -> InMemoryStoreSnapshot {
+        InMemoryStoreSnapshot {
+            checkpoints: self
+                .checkpoints
+                .values()
+                .map(|checkpoint| checkpoint.serializable_ref().clone())
+                .collect(),
+            checkpoint_contents: self.checkpoint_contents.values().cloned().collect(),
+            transactions: self
+                .transactions
+                .values()
+                .map(|transaction| transaction.serializable_ref().clone())
+                .collect(),
+            effects: self.effects.values().cloned().collect(),
+            events: self
+                .events
+                .iter()
+                .map(|(digest, events)| (*digest, events.clone()))
+                .collect(),
+            epoch_to_committee: self.epoch_to_committee.values().cloned().collect(),
+            live_objects: self
+                .live_objects
+                .iter()
+                .map(|(id, version)| (*id, *version))
+                .collect(),
+            objects: self
+                .objects
+                .values()
+                .flat_map(|versions| versions.values().cloned())
+                .collect(),
+        }
+    }
+
+    pub(crate) fn from_snapshot(snapshot: InMemoryStoreSnapshot) -> Self {
+        // Committees must be in place before the checkpoints, as inserting an end of epoch
+        // checkpoint inserts the committee of the next epoch.
+        let mut store = Self {
+            epoch_to_committee: snapshot
+                .epoch_to_committee
+                .into_iter()
+                .map(|committee| (committee.epoch, committee))
+                .collect(),
+            live_objects: snapshot.live_objects.into_iter().collect(),
+            ..Self::default()
+        };
+
+        for checkpoint in snapshot.checkpoints {
+            store.insert_checkpoint(checkpoint.into());
+        }
+
+        for contents in snapshot.checkpoint_contents {
+            store.insert_checkpoint_contents(contents);
+        }
+
+        for transaction in snapshot.transactions {
+            store.insert_transaction(transaction.into());
+        }
+
+        for effects in snapshot.effects {
+            store.insert_transaction_effects(effects);
+        }
+
+        for (digest, events) in snapshot.events {
+            store.insert_events(&digest, events);
+        }
+
+        for object in snapshot.objects {
+            store
+                .objects
+                .entry(object.id())
+                .or_default()
+                .insert(object.version(), object);
+        }
+
+        store
+    }
+}
+
 impl BackingPackageStore for InMemoryStore {
     fn get_package_object(
         &self,
@@ -332,8 +432,6 @@ impl KeyStore {
     pub fn from_network_config(
         network_config: &sui_swarm_config::network_config::NetworkConfig,
     ) -> Self {
-        use fastcrypto::traits::KeyPair;
-
         let validator_keys = network_config
             .validator_configs()
             .iter()
@@ -363,6 +461,55 @@ impl KeyStore {
     pub fn accounts(&self) -> impl Iterator<Item = (&SuiAddress, &AccountKeyPair)> {
         self.account_keys.iter()
     }
+
+    pub(crate) fn snapshot(&self) -> KeyStoreSnapshot {
+        KeyStoreSnapshot {
+            validator_keys: self
+                .validator_keys
+                .values()
+                .map(|key| key.encode_base64())
+                .collect(),
+            account_keys: self
+                .account_keys
+                .values()
+                .map(|key| key.encode_base64())
+                .collect(),
+        }
+    }
+
+    pub(crate) fn from_snapshot(snapshot: KeyStoreSnapshot) -> anyhow::Result<Self> {
+        let validator_keys = snapshot
+            .validator_keys
+            .iter()
+            .map(|key| {
+                let key = AuthorityKeyPair::decode_base64(key)
+                    .map_err(|e| anyhow!("Invalid validator key: {e}"))?;
+                Ok((key.public().into(), key))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let account_keys = snapshot
+            .account_keys
+            .iter()
+            .map(|key| {
+                let key = AccountKeyPair::decode_base64(key)
+                    .map_err(|e| anyhow!("Invalid account key: {e}"))?;
+                Ok((key.public().into(), key))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self {
+            validator_keys,
+            account_keys,
+        })
+    }
+}
+
+/// The serializable contents of a [`KeyStore`], with each key pair encoded as Base64.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct KeyStoreSnapshot {
+    validator_keys: Vec<String>,
+    account_keys: Vec<String>,
 }
 
 impl SimulatorStore for InMemoryStore {
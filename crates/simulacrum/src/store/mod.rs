@@ -23,7 +23,12 @@ use sui_types::{
     storage::{BackingStore, ChildObjectResolver, ParentSync},
     transaction::{InputObjectKind, VerifiedTransaction},
 };
+
+use crate::{SimulationProgress, SimulationState};
+
+pub mod forked_store;
 pub mod in_mem_store;
+pub mod rocks_db_store;
 
 pub trait SimulatorStore:
     sui_types::storage::BackingPackageStore
@@ -114,6 +119,51 @@ pub trait SimulatorStore:
 
     fn backing_store(&self) -> &dyn BackingStore;
 
+    /// Whether this store saves the state of the Simulacrum driving it, so that the simulation can
+    /// be resumed after a restart. The Simulacrum only gathers its state to hand to the store when
+    /// this is true.
+    fn persists_simulation_state(&self) -> bool {
+        false
+    }
+
+    /// Called with the state of the Simulacrum driving this store when the simulation starts, if
+    /// the store [persists it](Self::persists_simulation_state), so that it can be saved alongside
+    /// the chain state. Only the [progress](Self::save_simulation_progress) changes afterwards.
+    fn save_simulation_state(&mut self, _state: &SimulationState) {}
+
+    /// Called with the progress of the Simulacrum driving this store whenever it changes outside
+    /// of an insert that carries it, if the store persists its state.
+    fn save_simulation_progress(&mut self, _progress: &SimulationProgress) {}
+
+    /// Insert an executed transaction, along with the progress of the Simulacrum after executing
+    /// it. Persistent stores should write both atomically, so that a resumed simulation never
+    /// sees a transaction without the progress that includes it, or vice versa.
+    fn insert_executed_transaction_with_progress(
+        &mut self,
+        transaction: VerifiedTransaction,
+        effects: TransactionEffects,
+        events: TransactionEvents,
+        written_objects: BTreeMap<ObjectID, Object>,
+        progress: &SimulationProgress,
+    ) {
+        self.insert_executed_transaction(transaction, effects, events, written_objects);
+        self.save_simulation_progress(progress);
+    }
+
+    /// Insert a checkpoint and its contents, along with the progress of the Simulacrum after
+    /// building it. Persistent stores should write all of them atomically, so that a resumed
+    /// simulation never includes the transactions of a stored checkpoint in the next one.
+    fn insert_checkpoint_with_progress(
+        &mut self,
+        checkpoint: VerifiedCheckpoint,
+        contents: CheckpointContents,
+        progress: &SimulationProgress,
+    ) {
+        self.insert_checkpoint(checkpoint);
+        self.insert_checkpoint_contents(contents);
+        self.save_simulation_progress(progress);
+    }
+
     // TODO: This function is now out-of-sync with read_objects_for_execution from transaction_input_loader.rs.
     // For instance, it does not support the use of deleted shared objects.
     // We will need to make SimulatorStore implement ExecutionCacheRead, and keep track of deleted shared objects
@@ -0,0 +1,647 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use sui_config::genesis;
+use sui_types::storage::{
+    load_package_object_from_object_store, ObjectKey, PackageObject, ParentSync,
+};
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
+    committee::{Committee, EpochId},
+    digests::{ObjectDigest, TransactionDigest},
+    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    error::SuiError,
+    messages_checkpoint::{
+        CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
+        TrustedCheckpoint, VerifiedCheckpoint,
+    },
+    object::{Object, Owner},
+    storage::{BackingPackageStore, ChildObjectResolver, ObjectStore},
+    transaction::{TrustedTransaction, VerifiedTransaction},
+};
+use typed_store::rocks::{DBBatch, DBMap, MetricConf};
+use typed_store::{DBMapUtils, Map, TypedStoreError};
+
+use super::{in_mem_store::KeyStoreSnapshot, SimulatorStore};
+use crate::{SimulationProgress, SimulationState};
+
+/// A [`SimulatorStore`] that keeps the chain in RocksDB, so that simulations are not bounded by
+/// memory, and can be resumed after a restart with [`Simulacrum::resume`].
+///
+/// Every version of every object is kept, like [`InMemoryStore`] does.
+///
+/// [`Simulacrum::resume`]: crate::Simulacrum::resume
+/// [`InMemoryStore`]: super::in_mem_store::InMemoryStore
+pub struct RocksDbStore {
+    path: PathBuf,
+    tables: RocksDbStoreTables,
+}
+
+#[derive(DBMapUtils)]
+struct RocksDbStoreTables {
+    // Checkpoint data
+    checkpoints: DBMap<CheckpointSequenceNumber, TrustedCheckpoint>,
+    checkpoint_digest_to_sequence_number: DBMap<CheckpointDigest, CheckpointSequenceNumber>,
+    checkpoint_contents: DBMap<CheckpointContentsDigest, CheckpointContents>,
+
+    // Transaction data
+    transactions: DBMap<TransactionDigest, TrustedTransaction>,
+    effects: DBMap<TransactionDigest, TransactionEffects>,
+    events: DBMap<TransactionDigest, TransactionEvents>,
+
+    // Committee data
+    epoch_to_committee: DBMap<EpochId, Committee>,
+
+    // Object data
+    live_objects: DBMap<ObjectID, SequenceNumber>,
+    objects: DBMap<ObjectKey, Object>,
+    /// Index of the live objects owned by each address, for `owned_objects`.
+    owned_objects: DBMap<(SuiAddress, ObjectID), SequenceNumber>,
+
+    // Simulation data
+    genesis: DBMap<(), genesis::Genesis>,
+    /// The keys of the simulation, which are saved once, when it starts.
+    keystore: DBMap<(), KeyStoreSnapshot>,
+    /// The rest of the simulation state, saved whenever it changes.
+    simulation_progress: DBMap<(), SimulationProgress>,
+}
+
+impl RocksDbStore {
+    /// Create a store for a new chain starting at `genesis`, in a new database at `path`.
+    pub fn new(genesis: &genesis::Genesis, path: PathBuf) -> anyhow::Result<Self> {
+        let mut store = Self::open_tables(path);
+        if !store.tables.genesis.is_empty() {
+            bail!(
+                "A simulation already exists at {}, resume it instead",
+                store.path.display()
+            );
+        }
+
+        store.tables.genesis.insert(&(), genesis)?;
+        store.init_with_genesis(genesis);
+        Ok(store)
+    }
+
+    /// Open the store of an existing chain, created with [`RocksDbStore::new`] at `path`.
+    pub fn open(path: PathBuf) -> anyhow::Result<Self> {
+        let store = Self::open_tables(path);
+        if store.tables.genesis.is_empty() {
+            bail!("No simulation found at {}", store.path.display());
+        }
+
+        Ok(store)
+    }
+
+    fn open_tables(path: PathBuf) -> Self {
+        let tables = RocksDbStoreTables::open_tables_read_write(
+            path.clone(),
+            MetricConf::new("simulacrum"),
+            None,
+            None,
+        );
+        Self { path, tables }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn genesis(&self) -> anyhow::Result<genesis::Genesis> {
+        self.tables
+            .genesis
+            .get(&())?
+            .context("Simulation store is missing its genesis")
+    }
+
+    /// The simulation state last saved by the Simulacrum driving this store.
+    pub fn simulation_state(&self) -> anyhow::Result<Option<SimulationState>> {
+        let Some(keystore) = self.tables.keystore.get(&())? else {
+            return Ok(None);
+        };
+
+        let progress = self
+            .tables
+            .simulation_progress
+            .get(&())?
+            .context("Simulation store has keys but no simulation progress")?;
+        Ok(Some(SimulationState::new(keystore, progress)))
+    }
+
+    /// A batch that writes `transaction`, its effects and events, and the objects it wrote and
+    /// deleted, so that an interrupted simulation is never resumed from a partially applied
+    /// transaction.
+    fn executed_transaction_batch(
+        &self,
+        transaction: VerifiedTransaction,
+        effects: TransactionEffects,
+        events: TransactionEvents,
+        written_objects: BTreeMap<ObjectID, Object>,
+    ) -> Result<DBBatch, TypedStoreError> {
+        let deleted_objects = effects.deleted();
+        let tx_digest = *effects.transaction_digest();
+
+        let mut batch = self.tables.transactions.batch();
+        batch
+            .insert_batch(
+                &self.tables.transactions,
+                [(tx_digest, transaction.serializable_ref())],
+            )?
+            .insert_batch(&self.tables.effects, [(tx_digest, &effects)])?
+            .insert_batch(&self.tables.events, [(tx_digest, &events)])?;
+        self.batch_update_objects(&mut batch, written_objects, deleted_objects)?;
+        Ok(batch)
+    }
+
+    /// Add the writes and deletes of objects to `batch`, keeping the live object and owner
+    /// indices up to date.
+    fn batch_update_objects(
+        &self,
+        batch: &mut DBBatch,
+        written_objects: BTreeMap<ObjectID, Object>,
+        deleted_objects: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+    ) -> Result<(), TypedStoreError> {
+        for (object_id, _, _) in deleted_objects {
+            if let Some(owner) = self.live_address_owner(&object_id)? {
+                batch.delete_batch(&self.tables.owned_objects, [(owner, object_id)])?;
+            }
+            batch.delete_batch(&self.tables.live_objects, [object_id])?;
+        }
+
+        for (object_id, object) in written_objects {
+            let version = object.version();
+            if let Some(owner) = self.live_address_owner(&object_id)? {
+                batch.delete_batch(&self.tables.owned_objects, [(owner, object_id)])?;
+            }
+            if let Owner::AddressOwner(owner) = object.owner {
+                batch.insert_batch(&self.tables.owned_objects, [((owner, object_id), version)])?;
+            }
+            batch.insert_batch(&self.tables.live_objects, [(object_id, version)])?;
+            batch.insert_batch(
+                &self.tables.objects,
+                [(ObjectKey(object_id, version), object)],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Add `checkpoint` to `batch`, along with the committee of the next epoch if it is the last
+    /// checkpoint of its epoch.
+    fn batch_insert_checkpoint(
+        &self,
+        batch: &mut DBBatch,
+        checkpoint: &VerifiedCheckpoint,
+    ) -> Result<(), TypedStoreError> {
+        if let Some(end_of_epoch_data) = &checkpoint.data().end_of_epoch_data {
+            let next_committee = end_of_epoch_data
+                .next_epoch_committee
+                .iter()
+                .cloned()
+                .collect();
+            let committee =
+                Committee::new(checkpoint.epoch().checked_add(1).unwrap(), next_committee);
+            self.batch_insert_committee(batch, &committee)?;
+        }
+
+        batch
+            .insert_batch(
+                &self.tables.checkpoint_digest_to_sequence_number,
+                [(checkpoint.digest(), checkpoint.sequence_number())],
+            )?
+            .insert_batch(
+                &self.tables.checkpoints,
+                [(checkpoint.sequence_number(), checkpoint.serializable_ref())],
+            )?;
+        Ok(())
+    }
+
+    /// Add `committee` to `batch`, unless it is already stored. Committees must be added in epoch
+    /// order.
+    fn batch_insert_committee(
+        &self,
+        batch: &mut DBBatch,
+        committee: &Committee,
+    ) -> Result<(), TypedStoreError> {
+        let epoch = committee.epoch;
+        if self.tables.epoch_to_committee.contains_key(&epoch)? {
+            return Ok(());
+        }
+
+        let last_epoch = self
+            .tables
+            .epoch_to_committee
+            .reversed_safe_iter_with_bounds(None, None)?
+            .next()
+            .transpose()?
+            .map(|(epoch, _)| epoch);
+        if last_epoch.is_some_and(|last| last + 1 != epoch) {
+            panic!("committee was inserted into EpochCommitteeMap out of order");
+        }
+
+        batch.insert_batch(&self.tables.epoch_to_committee, [(epoch, committee)])?;
+        Ok(())
+    }
+
+    /// The address owning the live version of `object_id`, if any.
+    fn live_address_owner(
+        &self,
+        object_id: &ObjectID,
+    ) -> Result<Option<SuiAddress>, TypedStoreError> {
+        let Some(version) = self.tables.live_objects.get(object_id)? else {
+            return Ok(None);
+        };
+
+        let object = self.tables.objects.get(&ObjectKey(*object_id, version))?;
+        Ok(object.and_then(|object| match object.owner {
+            Owner::AddressOwner(owner) => Some(owner),
+            _ => None,
+        }))
+    }
+}
+
+impl SimulatorStore for RocksDbStore {
+    fn get_checkpoint_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Option<VerifiedCheckpoint> {
+        self.tables
+            .checkpoints
+            .get(&sequence_number)
+            .expect("Fatal: DB read failed")
+            .map(|checkpoint| checkpoint.into())
+    }
+
+    fn get_checkpoint_by_digest(&self, digest: &CheckpointDigest) -> Option<VerifiedCheckpoint> {
+        self.tables
+            .checkpoint_digest_to_sequence_number
+            .get(digest)
+            .expect("Fatal: DB read failed")
+            .and_then(|sequence_number| self.get_checkpoint_by_sequence_number(sequence_number))
+    }
+
+    fn get_highest_checkpint(&self) -> Option<VerifiedCheckpoint> {
+        self.tables
+            .checkpoints
+            .reversed_safe_iter_with_bounds(None, None)
+            .expect("Fatal: DB read failed")
+            .next()
+            .transpose()
+            .expect("Fatal: DB read failed")
+            .map(|(_, checkpoint)| checkpoint.into())
+    }
+
+    fn get_checkpoint_contents(
+        &self,
+        digest: &CheckpointContentsDigest,
+    ) -> Option<CheckpointContents> {
+        self.tables
+            .checkpoint_contents
+            .get(digest)
+            .expect("Fatal: DB read failed")
+    }
+
+    fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<Committee> {
+        self.tables
+            .epoch_to_committee
+            .get(&epoch)
+            .expect("Fatal: DB read failed")
+    }
+
+    fn get_transaction(&self, digest: &TransactionDigest) -> Option<VerifiedTransaction> {
+        self.tables
+            .transactions
+            .get(digest)
+            .expect("Fatal: DB read failed")
+            .map(|transaction| transaction.into())
+    }
+
+    fn get_transaction_effects(&self, digest: &TransactionDigest) -> Option<TransactionEffects> {
+        self.tables
+            .effects
+            .get(digest)
+            .expect("Fatal: DB read failed")
+    }
+
+    fn get_transaction_events(&self, digest: &TransactionDigest) -> Option<TransactionEvents> {
+        self.tables
+            .events
+            .get(digest)
+            .expect("Fatal: DB read failed")
+    }
+
+    fn get_object(&self, id: &ObjectID) -> Option<Object> {
+        let version = self
+            .tables
+            .live_objects
+            .get(id)
+            .expect("Fatal: DB read failed")?;
+        self.get_object_at_version(id, version)
+    }
+
+    fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        self.tables
+            .objects
+            .get(&ObjectKey(*id, version))
+            .expect("Fatal: DB read failed")
+    }
+
+    fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState {
+        sui_types::sui_system_state::get_sui_system_state(self).expect("system state must exist")
+    }
+
+    fn get_clock(&self) -> sui_types::clock::Clock {
+        SimulatorStore::get_object(self, &sui_types::SUI_CLOCK_OBJECT_ID)
+            .expect("clock should exist")
+            .to_rust()
+            .expect("clock object should deserialize")
+    }
+
+    fn owned_objects(&self, owner: SuiAddress) -> Box<dyn Iterator<Item = Object> + '_> {
+        Box::new(
+            self.tables
+                .owned_objects
+                .safe_range_iter((owner, ObjectID::ZERO)..=(owner, ObjectID::MAX))
+                .map(|result| result.expect("Fatal: DB read failed"))
+                .flat_map(|((_, id), version)| self.get_object_at_version(&id, version)),
+        )
+    }
+
+    fn insert_checkpoint(&mut self, checkpoint: VerifiedCheckpoint) {
+        let mut batch = self.tables.checkpoints.batch();
+        self.batch_insert_checkpoint(&mut batch, &checkpoint)
+            .and_then(|()| batch.write())
+            .expect("Fatal: DB write failed");
+    }
+
+    fn insert_checkpoint_contents(&mut self, contents: CheckpointContents) {
+        self.tables
+            .checkpoint_contents
+            .insert(contents.digest(), &contents)
+            .expect("Fatal: DB write failed");
+    }
+
+    fn insert_committee(&mut self, committee: Committee) {
+        let mut batch = self.tables.epoch_to_committee.batch();
+        self.batch_insert_committee(&mut batch, &committee)
+            .and_then(|()| batch.write())
+            .expect("Fatal: DB write failed");
+    }
+
+    fn insert_executed_transaction(
+        &mut self,
+        transaction: VerifiedTransaction,
+        effects: TransactionEffects,
+        events: TransactionEvents,
+        written_objects: BTreeMap<ObjectID, Object>,
+    ) {
+        self.executed_transaction_batch(transaction, effects, events, written_objects)
+            .and_then(|batch| batch.write())
+            .expect("Fatal: DB write failed");
+    }
+
+    fn insert_executed_transaction_with_progress(
+        &mut self,
+        transaction: VerifiedTransaction,
+        effects: TransactionEffects,
+        events: TransactionEvents,
+        written_objects: BTreeMap<ObjectID, Object>,
+        progress: &SimulationProgress,
+    ) {
+        self.executed_transaction_batch(transaction, effects, events, written_objects)
+            .and_then(|mut batch| {
+                batch.insert_batch(&self.tables.simulation_progress, [((), progress)])?;
+                batch.write()
+            })
+            .expect("Fatal: DB write failed");
+    }
+
+    fn insert_checkpoint_with_progress(
+        &mut self,
+        checkpoint: VerifiedCheckpoint,
+        contents: CheckpointContents,
+        progress: &SimulationProgress,
+    ) {
+        let mut batch = self.tables.checkpoints.batch();
+        self.batch_insert_checkpoint(&mut batch, &checkpoint)
+            .and_then(|()| {
+                batch
+                    .insert_batch(
+                        &self.tables.checkpoint_contents,
+                        [(contents.digest(), &contents)],
+                    )?
+                    .insert_batch(&self.tables.simulation_progress, [((), progress)])?;
+                batch.write()
+            })
+            .expect("Fatal: DB write failed");
+    }
+
+    fn insert_transaction(&mut self, transaction: VerifiedTransaction) {
+        self.tables
+            .transactions
+            .insert(transaction.digest(), transaction.serializable_ref())
+            .expect("Fatal: DB write failed");
+    }
+
+    fn insert_transaction_effects(&mut self, effects: TransactionEffects) {
+        self.tables
+            .effects
+            .insert(effects.transaction_digest(), &effects)
+            .expect("Fatal: DB write failed");
+    }
+
+    fn insert_events(&mut self, tx_digest: &TransactionDigest, events: TransactionEvents) {
+        self.tables
+            .events
+            .insert(tx_digest, &events)
+            .expect("Fatal: DB write failed");
+    }
+
+    fn update_objects(
+        &mut self,
+        written_objects: BTreeMap<ObjectID, Object>,
+        deleted_objects: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+    ) {
+        let mut batch = self.tables.objects.batch();
+        self.batch_update_objects(&mut batch, written_objects, deleted_objects)
+            .expect("Fatal: DB write failed");
+        batch.write().expect("Fatal: DB write failed");
+    }
+
+    fn backing_store(&self) -> &dyn sui_types::storage::BackingStore {
+        self
+    }
+
+    fn persists_simulation_state(&self) -> bool {
+        true
+    }
+
+    fn save_simulation_state(&mut self, state: &SimulationState) {
+        let mut batch = self.tables.keystore.batch();
+        batch
+            .insert_batch(&self.tables.keystore, [((), state.keystore())])
+            .and_then(|batch| {
+                batch.insert_batch(&self.tables.simulation_progress, [((), state.progress())])
+            })
+            .expect("Fatal: DB write failed");
+        batch.write().expect("Fatal: DB write failed");
+    }
+
+    fn save_simulation_progress(&mut self, progress: &SimulationProgress) {
+        self.tables
+            .simulation_progress
+            .insert(&(), progress)
+            .expect("Fatal: DB write failed");
+    }
+}
+
+impl BackingPackageStore for RocksDbStore {
+    fn get_package_object(
+        &self,
+        package_id: &ObjectID,
+    ) -> sui_types::error::SuiResult<Option<PackageObject>> {
+        load_package_object_from_object_store(self, package_id)
+    }
+}
+
+impl ChildObjectResolver for RocksDbStore {
+    fn read_child_object(
+        &self,
+        parent: &ObjectID,
+        child: &ObjectID,
+        child_version_upper_bound: SequenceNumber,
+    ) -> sui_types::error::SuiResult<Option<Object>> {
+        let child_object = match SimulatorStore::get_object(self, child) {
+            None => return Ok(None),
+            Some(obj) => obj,
+        };
+
+        let parent = *parent;
+        if child_object.owner != Owner::ObjectOwner(parent.into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: parent,
+                actual_owner: child_object.owner.clone(),
+            });
+        }
+
+        if child_object.version() > child_version_upper_bound {
+            return Err(SuiError::UnsupportedFeatureError {
+                error: "TODO RocksDbStore::read_child_object does not yet support bounded reads"
+                    .to_owned(),
+            });
+        }
+
+        Ok(Some(child_object))
+    }
+
+    fn get_object_received_at_version(
+        &self,
+        owner: &ObjectID,
+        receiving_object_id: &ObjectID,
+        receive_object_at_version: SequenceNumber,
+        _epoch_id: EpochId,
+    ) -> sui_types::error::SuiResult<Option<Object>> {
+        let recv_object = match SimulatorStore::get_object(self, receiving_object_id) {
+            None => return Ok(None),
+            Some(obj) => obj,
+        };
+        if recv_object.owner != Owner::AddressOwner((*owner).into()) {
+            return Ok(None);
+        }
+
+        if recv_object.version() != receive_object_at_version {
+            return Ok(None);
+        }
+        Ok(Some(recv_object))
+    }
+}
+
+impl ObjectStore for RocksDbStore {
+    fn get_object(&self, object_id: &ObjectID) -> Option<Object> {
+        SimulatorStore::get_object(self, object_id)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: sui_types::base_types::VersionNumber,
+    ) -> Option<Object> {
+        SimulatorStore::get_object_at_version(self, object_id, version)
+    }
+}
+
+impl ParentSync for RocksDbStore {
+    fn get_latest_parent_entry_ref_deprecated(
+        &self,
+        _object_id: ObjectID,
+    ) -> Option<sui_types::base_types::ObjectRef> {
+        panic!("Never called in newer protocol versions")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use rand::rngs::OsRng;
+    use sui_swarm_config::network_config_builder::ConfigBuilder;
+    use sui_types::{
+        gas_coin::MIST_PER_SUI,
+        sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait,
+    };
+
+    use crate::{AdvanceEpochConfig, Simulacrum};
+
+    use super::*;
+
+    #[test]
+    fn resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("simulacrum");
+        let config = ConfigBuilder::new_with_temp_dir()
+            .rng(OsRng)
+            .deterministic_committee_size(NonZeroUsize::new(1).unwrap())
+            .build();
+        let recipient = SuiAddress::random_for_testing_only();
+
+        let (checkpoint, pending) = {
+            let store = RocksDbStore::new(&config.genesis, path.clone()).unwrap();
+            let mut sim = Simulacrum::new_with_network_config_store(&config, OsRng, store);
+
+            sim.request_gas(recipient, MIST_PER_SUI).unwrap();
+            sim.create_checkpoint();
+            sim.advance_epoch(AdvanceEpochConfig::default());
+
+            // Leave a transaction pending, to be included in the first checkpoint after resuming.
+            let pending = sim.request_gas(recipient, MIST_PER_SUI).unwrap();
+            (sim.store().get_highest_checkpint().unwrap(), pending)
+        };
+
+        assert!(
+            RocksDbStore::new(&config.genesis, path.clone()).is_err(),
+            "existing simulation is not overwritten",
+        );
+
+        let mut sim = Simulacrum::resume(RocksDbStore::open(path).unwrap()).unwrap();
+        assert_eq!(
+            sim.store().get_highest_checkpint(),
+            Some(checkpoint.clone())
+        );
+        assert_eq!(sim.epoch_start_state().epoch(), 1);
+        assert_eq!(sim.store().owned_objects(recipient).count(), 2);
+
+        let next = sim.create_checkpoint();
+        assert_eq!(next.sequence_number, checkpoint.sequence_number + 1);
+        let contents = sim
+            .store()
+            .get_checkpoint_contents(&next.content_digest)
+            .unwrap();
+        assert!(contents
+            .iter()
+            .any(|digests| digests.transaction == *pending.transaction_digest()));
+    }
+}
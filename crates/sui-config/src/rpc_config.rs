@@ -42,6 +42,16 @@ pub struct RpcConfig {
     /// Configuration for RPC index initialization and bulk loading
     #[serde(skip_serializing_if = "Option::is_none")]
     pub index_initialization: Option<RpcIndexInitConfig>,
+
+    /// Maximum number of checkpoints behind the latest executed checkpoint that a filtered
+    /// subscription can resume from.
+    ///
+    /// Subscriptions that resume from further back need to replay that many checkpoints from the
+    /// store before catching up, so they are rejected instead.
+    ///
+    /// Defaults to `1000` if not specified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_subscription_catch_up: Option<u64>,
 }
 
 impl RpcConfig {
@@ -65,6 +75,10 @@ impl RpcConfig {
     pub fn index_initialization_config(&self) -> Option<&RpcIndexInitConfig> {
         self.index_initialization.as_ref()
     }
+
+    pub fn max_subscription_catch_up(&self) -> u64 {
+        self.max_subscription_catch_up.unwrap_or(1000)
+    }
 }
 
 #[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
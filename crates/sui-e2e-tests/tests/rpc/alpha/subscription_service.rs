@@ -0,0 +1,194 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use crate::{stake_with_validator, transfer_coin};
+use sui_macros::sim_test;
+use sui_rpc::field::FieldMask;
+use sui_rpc::field::FieldMaskUtil;
+use sui_rpc_api::alpha::proto::subscription_service_client::SubscriptionServiceClient;
+use sui_rpc_api::alpha::proto::{
+    Cursor, EventFilter, SubscribeEventsRequest, SubscribeTransactionsRequest, TransactionFilter,
+};
+use test_cluster::TestClusterBuilder;
+use tokio_stream::StreamExt;
+
+const STREAM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Resume from the start of the chain, so that transactions that have already been executed are
+/// replayed from the store.
+fn from_genesis() -> Option<Cursor> {
+    Some(Cursor {
+        checkpoint: Some(0),
+        transaction: None,
+        event: None,
+    })
+}
+
+#[sim_test]
+async fn subscribe_transactions() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+
+    let transaction_digest = transfer_coin(&test_cluster.wallet).await.to_string();
+
+    let mut client = SubscriptionServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap();
+
+    let mut stream = client
+        .subscribe_transactions(SubscribeTransactionsRequest {
+            filter: None,
+            read_mask: Some(FieldMask::from_paths([
+                "digest",
+                "checkpoint",
+                "transaction.sender",
+            ])),
+            after: from_genesis(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    // Transactions are streamed in order, and each is labeled with its position.
+    let mut last = None;
+    let sender = tokio::time::timeout(STREAM_TIMEOUT, async {
+        while let Some(item) = stream.next().await {
+            let response = item.unwrap();
+            let cursor = response.cursor.unwrap();
+            let transaction = response.transaction.unwrap();
+
+            assert_eq!(cursor.checkpoint, transaction.checkpoint);
+            assert!(cursor.event.is_none());
+
+            let position = (cursor.checkpoint(), cursor.transaction());
+            assert!(
+                last.is_none_or(|last| last < position),
+                "{last:?} >= {position:?}"
+            );
+            last = Some(position);
+
+            if transaction.digest() == transaction_digest {
+                return transaction.transaction.unwrap().sender.unwrap();
+            }
+        }
+
+        panic!("Stream ended before transaction {transaction_digest}");
+    })
+    .await
+    .unwrap();
+
+    // Filtering by sender only returns transactions from that sender.
+    let mut stream = client
+        .subscribe_transactions(SubscribeTransactionsRequest {
+            filter: Some(TransactionFilter {
+                sender: Some(sender.clone()),
+                ..Default::default()
+            }),
+            read_mask: Some(FieldMask::from_paths(["digest", "transaction.sender"])),
+            after: from_genesis(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    tokio::time::timeout(STREAM_TIMEOUT, async {
+        while let Some(item) = stream.next().await {
+            let transaction = item.unwrap().transaction.unwrap();
+            assert_eq!(transaction.transaction.as_ref().unwrap().sender(), sender);
+
+            if transaction.digest() == transaction_digest {
+                return;
+            }
+        }
+
+        panic!("Stream ended before transaction {transaction_digest}");
+    })
+    .await
+    .unwrap();
+}
+
+#[sim_test]
+async fn subscribe_events() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+
+    let transaction_digest = stake_with_validator(&test_cluster).await.to_string();
+
+    let mut client = SubscriptionServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap();
+
+    let mut stream = client
+        .subscribe_events(SubscribeEventsRequest {
+            filter: Some(EventFilter {
+                event_type: Some("0x3::validator::StakingRequestEvent".to_owned()),
+                ..Default::default()
+            }),
+            read_mask: Some(FieldMask::from_str("event_type")),
+            after: from_genesis(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    tokio::time::timeout(STREAM_TIMEOUT, async {
+        while let Some(item) = stream.next().await {
+            let response = item.unwrap();
+            let cursor = response.cursor.unwrap();
+            assert!(cursor.event.is_some());
+
+            let event = response.event.unwrap();
+            assert!(event
+                .event_type()
+                .ends_with("::validator::StakingRequestEvent"));
+
+            if response.transaction_digest() == transaction_digest {
+                return;
+            }
+        }
+
+        panic!("Stream ended before transaction {transaction_digest}");
+    })
+    .await
+    .unwrap();
+}
+
+#[sim_test]
+async fn subscribe_with_invalid_request() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+
+    let mut client = SubscriptionServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap();
+
+    // A cursor must include a checkpoint.
+    let status = client
+        .subscribe_transactions(SubscribeTransactionsRequest {
+            filter: None,
+            read_mask: None,
+            after: Some(Cursor {
+                checkpoint: None,
+                transaction: Some(0),
+                event: None,
+            }),
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    // Filters must be well-formed.
+    let status = client
+        .subscribe_events(SubscribeEventsRequest {
+            filter: Some(EventFilter {
+                emitting_module: Some("0x2::coin::split".to_owned()),
+                ..Default::default()
+            }),
+            read_mask: None,
+            after: None,
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}
@@ -0,0 +1,134 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sui_indexer_alt_framework::{
+    pipeline::{concurrent::Handler, Processor},
+    types::full_checkpoint_content::CheckpointData,
+    FieldCount,
+};
+
+use crate::{FileConnection, FileRow, FileStore};
+
+/// Runs an existing [Processor] as a concurrent pipeline that writes to a [FileStore].
+///
+/// The processor's logic is used unchanged, under the same pipeline name, and its rows are written
+/// with [FileConnection::write] and pruned with [FileConnection::prune]. Only the processor's
+/// `Value` needs to implement [FileRow], to describe its columns and which checkpoint it came
+/// from.
+pub struct FileHandler<P>(pub P);
+
+impl<P> Processor for FileHandler<P>
+where
+    P: Processor,
+    P::Value: FileRow + FieldCount,
+{
+    const NAME: &'static str = P::NAME;
+    const FANOUT: usize = P::FANOUT;
+
+    type Value = P::Value;
+
+    fn process(&self, checkpoint: &Arc<CheckpointData>) -> anyhow::Result<Vec<Self::Value>> {
+        self.0.process(checkpoint)
+    }
+}
+
+#[async_trait]
+impl<P> Handler for FileHandler<P>
+where
+    P: Processor + Send + Sync,
+    P::Value: FileRow + FieldCount,
+{
+    type Store = FileStore;
+
+    async fn commit<'a>(
+        values: &[Self::Value],
+        conn: &mut FileConnection<'a>,
+    ) -> anyhow::Result<usize> {
+        conn.write(Self::NAME, values).await
+    }
+
+    async fn prune<'a>(
+        &self,
+        from: u64,
+        to_exclusive: u64,
+        conn: &mut FileConnection<'a>,
+    ) -> anyhow::Result<usize> {
+        conn.prune(Self::NAME, from, to_exclusive).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::{DataType, Field, Schema};
+    use serde::Serialize;
+    use sui_indexer_alt_framework::store::Store;
+
+    use crate::FileStoreArgs;
+
+    use super::*;
+
+    #[derive(Serialize, FieldCount)]
+    struct Row {
+        cp_sequence_number: u64,
+    }
+
+    impl FileRow for Row {
+        fn schema() -> Schema {
+            Schema::new(vec![Field::new(
+                "cp_sequence_number",
+                DataType::UInt64,
+                false,
+            )])
+        }
+
+        fn checkpoint(&self) -> u64 {
+            self.cp_sequence_number
+        }
+    }
+
+    /// A processor that could equally back a handler for another store.
+    struct Checkpoints;
+
+    impl Processor for Checkpoints {
+        const NAME: &'static str = "checkpoints";
+        type Value = Row;
+
+        fn process(&self, checkpoint: &Arc<CheckpointData>) -> anyhow::Result<Vec<Row>> {
+            Ok(vec![Row {
+                cp_sequence_number: checkpoint.checkpoint_summary.sequence_number,
+            }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_handler() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = FileStoreArgs {
+            checkpoints_per_partition: 10,
+            ..Default::default()
+        };
+        let store = FileStore::new(dir.path().to_owned(), args).await.unwrap();
+        let mut conn = store.connect().await.unwrap();
+
+        let rows: Vec<_> = (0..15)
+            .map(|cp| Row {
+                cp_sequence_number: cp,
+            })
+            .collect();
+        let written = FileHandler::<Checkpoints>::commit(&rows, &mut conn)
+            .await
+            .unwrap();
+        assert_eq!(written, 15);
+
+        // Rows are written under the processor's name, split by partition.
+        assert_eq!(store.files("checkpoints").await.unwrap().len(), 2);
+
+        let handler = FileHandler(Checkpoints);
+        let pruned = handler.prune(0, 10, &mut conn).await.unwrap();
+        assert_eq!(pruned, 1);
+        assert_eq!(store.files("checkpoints").await.unwrap().len(), 1);
+    }
+}
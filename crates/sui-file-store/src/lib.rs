@@ -0,0 +1,672 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Context;
+use arrow::datatypes::Schema;
+use arrow::json::reader::ReaderBuilder;
+use arrow::record_batch::RecordBatch;
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+pub mod handler;
+pub mod store;
+
+/// Name of the sidecar file, at the root of the store, that holds the watermarks for all
+/// pipelines.
+const WATERMARKS_FILE: &str = "_watermarks.json";
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct FileStoreArgs {
+    /// Format to write pipeline batches in.
+    #[arg(long, value_enum, default_value_t = Self::default().file_format)]
+    pub file_format: FileFormat,
+
+    /// Number of checkpoints covered by each partition directory. Rows are written to the
+    /// partition that contains the checkpoint they came from.
+    #[arg(long, default_value_t = Self::default().checkpoints_per_partition)]
+    pub checkpoints_per_partition: u64,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Parquet,
+    Csv,
+}
+
+/// A row that can be written to a [FileStore]. Rows are partitioned by the checkpoint they came
+/// from, and converted to columns through their `Serialize` implementation: each field of their
+/// JSON representation populates the column with the same name in their [FileRow::schema].
+pub trait FileRow: Serialize + Send + Sync {
+    /// The columns of the files that rows of this type are written to. Every file written for a
+    /// pipeline shares this schema, regardless of which fields are set on the rows it holds.
+    fn schema() -> Schema;
+
+    /// The sequence number of the checkpoint this row came from.
+    fn checkpoint(&self) -> u64;
+}
+
+/// A [Store](store::Store) that writes pipeline data to files on the local file system, for use
+/// in building analytical datasets.
+///
+/// Each batch of rows written to the store is split by partition, and written to a file at
+/// `{root}/{pipeline}/checkpoint_partition={partition}/{lo}_{hi}_{digest}.{ext}`, where `lo` and
+/// `hi` are the (inclusive) bounds of the checkpoints in the file and `digest` is a prefix of the
+/// Blake2b256 hash of its contents. The digest distinguishes batches that cover the same range of
+/// checkpoints, and is stable across runs, so that re-writing the same batch (e.g. on retry)
+/// overwrites the same file. Watermarks for all pipelines are kept in a JSON sidecar file at the
+/// root of the store.
+///
+/// Pipelines write to the store through handlers whose `Store` is [FileStore], calling
+/// [FileConnection::write] and [FileConnection::prune] from their `commit` and `prune`
+/// implementations. Existing processors can be run unchanged as concurrent pipelines by wrapping
+/// them in a [FileHandler](handler::FileHandler), as long as their rows implement [FileRow]. Only
+/// the processing logic carries over: a handler's Postgres `commit` and `prune` issue SQL, so
+/// they are replaced by the file store's own.
+///
+/// Files are written to a hidden temporary path and then moved into place, so readers never see a
+/// partially written file. Writes are at-least-once: after a restart, the indexer resumes from
+/// the committer watermark, and may re-write rows beyond it that had already been written, so
+/// readers should only consume rows up to the committer watermark and de-duplicate as necessary.
+///
+/// Instances of [FileStore] can be cloned to share access to the same store.
+#[derive(Clone)]
+pub struct FileStore(Arc<Inner>);
+
+struct Inner {
+    root: PathBuf,
+    format: FileFormat,
+    checkpoints_per_partition: u64,
+    watermarks: Mutex<Watermarks>,
+}
+
+/// A connection to a [FileStore]. Connections used inside a transaction stage their writes, and
+/// only apply them if the transaction succeeds.
+pub struct FileConnection<'c> {
+    store: &'c FileStore,
+    txn: Option<Transaction>,
+}
+
+/// Watermarks for all pipelines, keyed by pipeline name, as stored in the sidecar file.
+type Watermarks = BTreeMap<String, Watermark>;
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+struct Watermark {
+    epoch_hi_inclusive: u64,
+    checkpoint_hi_inclusive: u64,
+    tx_hi: u64,
+    timestamp_ms_hi_inclusive: u64,
+    reader_lo: u64,
+    pruner_timestamp_ms: u64,
+    pruner_hi: u64,
+}
+
+/// Changes staged by a connection inside a transaction.
+#[derive(Default)]
+struct Transaction {
+    /// Files that have been written to a temporary path, paired with their final path.
+    files: Vec<(PathBuf, PathBuf)>,
+
+    /// Files to delete.
+    pruned: Vec<PathBuf>,
+
+    /// Watermarks that have been modified.
+    watermarks: Watermarks,
+}
+
+impl FileFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            FileFormat::Parquet => "parquet",
+            FileFormat::Csv => "csv",
+        }
+    }
+
+    fn write(&self, path: &Path, batch: &RecordBatch) -> anyhow::Result<()> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+
+        match self {
+            FileFormat::Parquet => {
+                let properties = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+
+                let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties))?;
+                writer.write(batch)?;
+                writer.close()?;
+            }
+
+            FileFormat::Csv => {
+                let mut writer = arrow::csv::WriterBuilder::new()
+                    .with_header(true)
+                    .build(file);
+                writer.write(batch)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FileStore {
+    /// Open the store rooted at `root`, creating it if it does not exist yet, and loading any
+    /// existing watermarks from its sidecar file.
+    pub async fn new(root: PathBuf, args: FileStoreArgs) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            args.checkpoints_per_partition > 0,
+            "checkpoints_per_partition must be positive",
+        );
+
+        tokio::fs::create_dir_all(&root)
+            .await
+            .with_context(|| format!("Failed to create file store at {}", root.display()))?;
+
+        let watermarks = match tokio::fs::read(root.join(WATERMARKS_FILE)).await {
+            Ok(bytes) => serde_json::from_slice(&bytes).context("Failed to parse watermarks")?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Watermarks::new(),
+            Err(e) => return Err(e).context("Failed to read watermarks"),
+        };
+
+        Ok(Self(Arc::new(Inner {
+            root,
+            format: args.file_format,
+            checkpoints_per_partition: args.checkpoints_per_partition,
+            watermarks: Mutex::new(watermarks),
+        })))
+    }
+
+    /// The directory that the store writes to.
+    pub fn root(&self) -> &Path {
+        &self.0.root
+    }
+
+    /// All the data files written for `pipeline`, in no particular order.
+    pub async fn files(&self, pipeline: &str) -> anyhow::Result<Vec<PathBuf>> {
+        Ok(self
+            .ranged_files(pipeline)
+            .await?
+            .into_iter()
+            .map(|(_, _, path)| path)
+            .collect())
+    }
+
+    /// All the data files written for `pipeline`, along with the inclusive range of checkpoints
+    /// they hold data for.
+    async fn ranged_files(&self, pipeline: &str) -> anyhow::Result<Vec<(u64, u64, PathBuf)>> {
+        let mut files = vec![];
+        let mut partitions = match tokio::fs::read_dir(self.0.root.join(pipeline)).await {
+            Ok(partitions) => partitions,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(files),
+            Err(e) => return Err(e.into()),
+        };
+
+        while let Some(partition) = partitions.next_entry().await? {
+            if !partition.file_type().await?.is_dir() {
+                continue;
+            }
+
+            let mut entries = tokio::fs::read_dir(partition.path()).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let name = entry.file_name();
+                let Some((lo, hi)) = name.to_str().and_then(|n| self.parse_file_name(n)) else {
+                    continue;
+                };
+
+                files.push((lo, hi, entry.path()));
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Extract the checkpoint bounds from a data file's name. Returns `None` for temporary files
+    /// and files in a different format.
+    fn parse_file_name(&self, name: &str) -> Option<(u64, u64)> {
+        let stem = name
+            .strip_suffix(self.0.format.extension())?
+            .strip_suffix('.')?;
+        let mut parts = stem.splitn(3, '_');
+        let lo = parts.next()?.parse().ok()?;
+        let hi = parts.next()?.parse().ok()?;
+        Some((lo, hi))
+    }
+
+    /// Write `watermarks` to the sidecar file, replacing it atomically.
+    async fn persist_watermarks(&self, watermarks: &Watermarks) -> anyhow::Result<()> {
+        let path = self.0.root.join(WATERMARKS_FILE);
+        let tmp = self.0.root.join(format!(".{WATERMARKS_FILE}.tmp"));
+        tokio::fs::write(&tmp, serde_json::to_vec_pretty(watermarks)?).await?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .context("Failed to update watermarks")
+    }
+}
+
+impl<'c> FileConnection<'c> {
+    fn new(store: &'c FileStore, txn: Option<Transaction>) -> Self {
+        Self { store, txn }
+    }
+
+    /// Write `rows` for `pipeline` to the store, returning the number of rows written. Rows are
+    /// split into one file per partition. Inside a transaction, the files only become visible
+    /// once the transaction commits.
+    pub async fn write<R: FileRow>(&mut self, pipeline: &str, rows: &[R]) -> anyhow::Result<usize> {
+        let inner = &self.store.0;
+
+        let mut partitions: BTreeMap<u64, Vec<&R>> = BTreeMap::new();
+        for row in rows {
+            partitions
+                .entry(row.checkpoint() / inner.checkpoints_per_partition)
+                .or_default()
+                .push(row);
+        }
+
+        for (partition, rows) in partitions {
+            let lo = rows.iter().map(|r| r.checkpoint()).min().unwrap();
+            let hi = rows.iter().map(|r| r.checkpoint()).max().unwrap();
+
+            let values = rows
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<Result<Vec<_>, _>>()
+                .context("Failed to serialize rows")?;
+
+            let digest = Blake2b256::digest(serde_json::to_vec(&values)?);
+            let digest: String = digest.digest[..8]
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect();
+
+            let batch = record_batch(R::schema(), &values)?;
+            let dir = inner
+                .root
+                .join(pipeline)
+                .join(format!("checkpoint_partition={partition}"));
+
+            let name = format!("{lo}_{hi}_{digest}.{}", inner.format.extension());
+            let path = dir.join(&name);
+            let tmp = dir.join(format!(".{name}.tmp"));
+
+            let format = inner.format;
+            let tmp_path = tmp.clone();
+            tokio::task::spawn_blocking(move || {
+                std::fs::create_dir_all(&dir)?;
+                format.write(&tmp_path, &batch)
+            })
+            .await??;
+
+            if let Some(txn) = &mut self.txn {
+                txn.files.push((tmp, path));
+            } else {
+                tokio::fs::rename(&tmp, &path).await?;
+            }
+        }
+
+        Ok(rows.len())
+    }
+
+    /// Delete the files for `pipeline` that only hold data for checkpoints before `to_exclusive`.
+    /// Data before `_from` has already been pruned, so a file that starts before `_from` is deleted
+    /// once the rest of its checkpoints are pruned, while a file that holds data for checkpoints at
+    /// or after `to_exclusive` is kept. Returns the number of files deleted (or staged for
+    /// deletion, inside a transaction).
+    pub async fn prune(
+        &mut self,
+        pipeline: &str,
+        _from: u64,
+        to_exclusive: u64,
+    ) -> anyhow::Result<usize> {
+        let mut pruned = 0;
+        for (_, hi, path) in self.store.ranged_files(pipeline).await? {
+            if hi >= to_exclusive {
+                continue;
+            }
+
+            if let Some(txn) = &mut self.txn {
+                txn.pruned.push(path);
+            } else {
+                remove_file_if_exists(&path).await?;
+            }
+
+            pruned += 1;
+        }
+
+        Ok(pruned)
+    }
+
+    /// The watermark for `pipeline`, including any changes staged by this connection.
+    async fn watermark(&self, pipeline: &str) -> Option<Watermark> {
+        if let Some(watermark) = self.txn.as_ref().and_then(|t| t.watermarks.get(pipeline)) {
+            return Some(watermark.clone());
+        }
+
+        self.store.0.watermarks.lock().await.get(pipeline).cloned()
+    }
+
+    /// Update the watermark for `pipeline` with `f`, which is passed `None` if the pipeline does
+    /// not have a watermark yet, and returns whether it modified the watermark. Outside a
+    /// transaction, modifications are persisted to the sidecar file immediately.
+    async fn update_watermark(
+        &mut self,
+        pipeline: &str,
+        f: impl FnOnce(&mut Option<Watermark>) -> bool,
+    ) -> anyhow::Result<bool> {
+        if self.txn.is_some() {
+            let mut watermark = self.watermark(pipeline).await;
+            if !f(&mut watermark) {
+                return Ok(false);
+            }
+
+            if let (Some(txn), Some(watermark)) = (&mut self.txn, watermark) {
+                txn.watermarks.insert(pipeline.to_owned(), watermark);
+            }
+
+            return Ok(true);
+        }
+
+        let mut watermarks = self.store.0.watermarks.lock().await;
+        let mut watermark = watermarks.get(pipeline).cloned();
+        if !f(&mut watermark) {
+            return Ok(false);
+        }
+
+        let Some(watermark) = watermark else {
+            return Ok(true);
+        };
+
+        let mut updated = watermarks.clone();
+        updated.insert(pipeline.to_owned(), watermark);
+        self.store.persist_watermarks(&updated).await?;
+        *watermarks = updated;
+        Ok(true)
+    }
+}
+
+impl Transaction {
+    /// Move the files written in this transaction into place, delete the files it pruned, and then
+    /// apply its watermark changes, so that watermarks never refer to data that is not visible.
+    async fn commit(self, store: &FileStore) -> anyhow::Result<()> {
+        for (tmp, path) in &self.files {
+            tokio::fs::rename(tmp, path).await?;
+        }
+
+        for path in &self.pruned {
+            remove_file_if_exists(path).await?;
+        }
+
+        if self.watermarks.is_empty() {
+            return Ok(());
+        }
+
+        let mut watermarks = store.0.watermarks.lock().await;
+        let mut updated = watermarks.clone();
+        updated.extend(self.watermarks);
+        store.persist_watermarks(&updated).await?;
+        *watermarks = updated;
+        Ok(())
+    }
+
+    /// Clean up the temporary files written in this transaction.
+    async fn abort(self) {
+        for (tmp, _) in &self.files {
+            let _ = tokio::fs::remove_file(tmp).await;
+        }
+    }
+}
+
+/// Convert rows, in their JSON representation, into a record batch with the given `schema`.
+fn record_batch(schema: Schema, values: &[serde_json::Value]) -> anyhow::Result<RecordBatch> {
+    let mut decoder = ReaderBuilder::new(Arc::new(schema))
+        .with_batch_size(values.len().max(1))
+        .build_decoder()?;
+
+    decoder.serialize(values)?;
+    decoder.flush()?.context("No rows to write")
+}
+
+async fn remove_file_if_exists(path: &Path) -> anyhow::Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e).with_context(|| format!("Failed to remove {}", path.display())),
+    }
+}
+
+impl Default for FileStoreArgs {
+    fn default() -> Self {
+        Self {
+            file_format: FileFormat::Parquet,
+            checkpoints_per_partition: 10_000,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::{DataType, Field};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use scoped_futures::ScopedFutureExt;
+    use sui_indexer_alt_framework_store_traits::{
+        CommitterWatermark, Connection, Store, TransactionalStore,
+    };
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct TestRow {
+        cp_sequence_number: u64,
+        digest: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sender: Option<String>,
+    }
+
+    impl FileRow for TestRow {
+        fn schema() -> Schema {
+            Schema::new(vec![
+                Field::new("cp_sequence_number", DataType::UInt64, false),
+                Field::new("digest", DataType::Utf8, false),
+                Field::new("sender", DataType::Utf8, true),
+            ])
+        }
+
+        fn checkpoint(&self) -> u64 {
+            self.cp_sequence_number
+        }
+    }
+
+    fn rows(checkpoints: impl IntoIterator<Item = u64>) -> Vec<TestRow> {
+        checkpoints
+            .into_iter()
+            .map(|cp| TestRow {
+                cp_sequence_number: cp,
+                digest: format!("digest-{cp}"),
+                sender: None,
+            })
+            .collect()
+    }
+
+    async fn store(root: &Path) -> FileStore {
+        let args = FileStoreArgs {
+            checkpoints_per_partition: 10,
+            ..Default::default()
+        };
+
+        FileStore::new(root.to_owned(), args).await.unwrap()
+    }
+
+    fn schema(file: &Path) -> Schema {
+        ParquetRecordBatchReaderBuilder::try_new(File::open(file).unwrap())
+            .unwrap()
+            .schema()
+            .as_ref()
+            .clone()
+    }
+
+    fn count_rows(files: &[PathBuf]) -> usize {
+        files
+            .iter()
+            .flat_map(|f| {
+                ParquetRecordBatchReaderBuilder::try_new(File::open(f).unwrap())
+                    .unwrap()
+                    .build()
+                    .unwrap()
+            })
+            .map(|b| b.unwrap().num_rows())
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn test_write_partitioned() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path()).await;
+        let mut conn = store.connect().await.unwrap();
+
+        assert_eq!(conn.write("test", &rows(5..25)).await.unwrap(), 20);
+
+        let mut files = store.files("test").await.unwrap();
+        files.sort();
+        assert_eq!(files.len(), 3);
+        assert_eq!(count_rows(&files), 20);
+
+        let partition = files[0].parent().unwrap().file_name().unwrap();
+        assert_eq!(partition, "checkpoint_partition=0");
+        assert!(files[0]
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .starts_with("5_9_"));
+
+        // Writing the same batch again overwrites the same files.
+        conn.write("test", &rows(5..25)).await.unwrap();
+        assert_eq!(store.files("test").await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_schema_per_pipeline() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path()).await;
+        let mut conn = store.connect().await.unwrap();
+
+        // The optional field is missing from every row of the first batch, and set in the second.
+        conn.write("test", &rows(0..5)).await.unwrap();
+        let mut with_sender = rows(5..10);
+        for row in &mut with_sender {
+            row.sender = Some("0x1".to_owned());
+        }
+        conn.write("test", &with_sender).await.unwrap();
+
+        let files = store.files("test").await.unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(schema(&files[0]), schema(&files[1]));
+        assert_eq!(schema(&files[0]).fields(), TestRow::schema().fields(),);
+    }
+
+    #[tokio::test]
+    async fn test_watermarks_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+
+        {
+            let store = store(dir.path()).await;
+            let mut conn = store.connect().await.unwrap();
+            let watermark = CommitterWatermark::new_for_testing(10);
+            assert!(conn
+                .set_committer_watermark("test", watermark)
+                .await
+                .unwrap());
+            assert!(conn.set_reader_watermark("test", 5).await.unwrap());
+
+            // Watermarks only move forward.
+            let watermark = CommitterWatermark::new_for_testing(8);
+            assert!(!conn
+                .set_committer_watermark("test", watermark)
+                .await
+                .unwrap());
+            assert!(!conn.set_reader_watermark("test", 3).await.unwrap());
+        }
+
+        let store = store(dir.path()).await;
+        let mut conn = store.connect().await.unwrap();
+        let watermark = conn.reader_watermark("test").await.unwrap().unwrap();
+        assert_eq!(watermark.checkpoint_hi_inclusive, 10);
+        assert_eq!(watermark.reader_lo, 5);
+        assert!(conn.committer_watermark("other").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transaction_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path()).await;
+
+        let result: anyhow::Result<()> = store
+            .transaction(|conn| {
+                async move {
+                    conn.write("test", &rows(0..5)).await?;
+                    let watermark = CommitterWatermark::new_for_testing(4);
+                    conn.set_committer_watermark("test", watermark).await?;
+                    anyhow::bail!("Rollback");
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert!(store.files("test").await.unwrap().is_empty());
+
+        let mut conn = store.connect().await.unwrap();
+        assert!(conn.committer_watermark("test").await.unwrap().is_none());
+
+        store
+            .transaction(|conn| {
+                async move {
+                    conn.write("test", &rows(0..5)).await?;
+                    let watermark = CommitterWatermark::new_for_testing(4);
+                    conn.set_committer_watermark("test", watermark).await?;
+                    Ok(())
+                }
+                .scope_boxed()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(store.files("test").await.unwrap().len(), 1);
+        let watermark = conn.committer_watermark("test").await.unwrap().unwrap();
+        assert_eq!(watermark.checkpoint_hi_inclusive, 4);
+    }
+
+    #[tokio::test]
+    async fn test_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path()).await;
+        let mut conn = store.connect().await.unwrap();
+
+        conn.write("test", &rows(0..10)).await.unwrap();
+        conn.write("test", &rows(10..15)).await.unwrap();
+        conn.write("test", &rows(15..30)).await.unwrap();
+
+        // Only the first two files fall entirely within the pruned range.
+        assert_eq!(conn.prune("test", 0, 17).await.unwrap(), 2);
+
+        let files = store.files("test").await.unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(count_rows(&files), 15);
+
+        // The file for checkpoints 15..20 straddled the last bound, and is deleted once the rest
+        // of it is pruned.
+        assert_eq!(conn.prune("test", 17, 25).await.unwrap(), 1);
+
+        let files = store.files("test").await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(count_rows(&files), 10);
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use scoped_futures::ScopedBoxFuture;
+use sui_indexer_alt_framework_store_traits as store;
+
+use crate::{FileConnection, FileStore, Transaction, Watermark};
+
+pub use sui_indexer_alt_framework_store_traits::Store;
+
+#[async_trait]
+impl store::Connection for FileConnection<'_> {
+    async fn committer_watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<store::CommitterWatermark>> {
+        Ok(self
+            .watermark(pipeline)
+            .await
+            .map(|w| store::CommitterWatermark {
+                epoch_hi_inclusive: w.epoch_hi_inclusive,
+                checkpoint_hi_inclusive: w.checkpoint_hi_inclusive,
+                tx_hi: w.tx_hi,
+                timestamp_ms_hi_inclusive: w.timestamp_ms_hi_inclusive,
+            }))
+    }
+
+    async fn reader_watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<store::ReaderWatermark>> {
+        Ok(self
+            .watermark(pipeline)
+            .await
+            .map(|w| store::ReaderWatermark {
+                checkpoint_hi_inclusive: w.checkpoint_hi_inclusive,
+                reader_lo: w.reader_lo,
+            }))
+    }
+
+    async fn pruner_watermark(
+        &mut self,
+        pipeline: &'static str,
+        delay: Duration,
+    ) -> anyhow::Result<Option<store::PrunerWatermark>> {
+        //     |---------- + delay ---------------------|
+        //                             |--- wait_for ---|
+        //     |-----------------------|----------------|
+        //     ^                       ^
+        //     pruner_timestamp        now
+        let now = now_ms();
+        Ok(self
+            .watermark(pipeline)
+            .await
+            .map(|w| store::PrunerWatermark {
+                wait_for_ms: (w.pruner_timestamp_ms + delay.as_millis() as u64) as i64 - now as i64,
+                reader_lo: w.reader_lo,
+                pruner_hi: w.pruner_hi,
+            }))
+    }
+
+    async fn set_committer_watermark(
+        &mut self,
+        pipeline: &'static str,
+        watermark: store::CommitterWatermark,
+    ) -> anyhow::Result<bool> {
+        self.update_watermark(pipeline, |w| {
+            if w.as_ref()
+                .is_some_and(|w| w.checkpoint_hi_inclusive >= watermark.checkpoint_hi_inclusive)
+            {
+                return false;
+            }
+
+            let w = w.get_or_insert_with(Watermark::default);
+            w.epoch_hi_inclusive = watermark.epoch_hi_inclusive;
+            w.checkpoint_hi_inclusive = watermark.checkpoint_hi_inclusive;
+            w.tx_hi = watermark.tx_hi;
+            w.timestamp_ms_hi_inclusive = watermark.timestamp_ms_hi_inclusive;
+            true
+        })
+        .await
+    }
+
+    async fn set_reader_watermark(
+        &mut self,
+        pipeline: &'static str,
+        reader_lo: u64,
+    ) -> anyhow::Result<bool> {
+        let now = now_ms();
+        self.update_watermark(pipeline, |w| match w {
+            Some(w) if w.reader_lo < reader_lo => {
+                w.reader_lo = reader_lo;
+                w.pruner_timestamp_ms = now;
+                true
+            }
+            _ => false,
+        })
+        .await
+    }
+
+    async fn set_pruner_watermark(
+        &mut self,
+        pipeline: &'static str,
+        pruner_hi: u64,
+    ) -> anyhow::Result<bool> {
+        self.update_watermark(pipeline, |w| match w {
+            Some(w) => {
+                w.pruner_hi = pruner_hi;
+                true
+            }
+            None => false,
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl store::Store for FileStore {
+    type Connection<'c> = FileConnection<'c>;
+
+    async fn connect<'c>(&'c self) -> anyhow::Result<Self::Connection<'c>> {
+        Ok(FileConnection::new(self, None))
+    }
+}
+
+#[async_trait]
+impl store::TransactionalStore for FileStore {
+    async fn transaction<'a, R, F>(&self, f: F) -> anyhow::Result<R>
+    where
+        R: Send + 'a,
+        F: Send + 'a,
+        F: for<'r> FnOnce(
+            &'r mut Self::Connection<'_>,
+        ) -> ScopedBoxFuture<'a, 'r, anyhow::Result<R>>,
+    {
+        let mut conn = FileConnection::new(self, Some(Transaction::default()));
+        let result = f(&mut conn).await;
+        let txn = conn
+            .txn
+            .take()
+            .expect("transaction connection has staged changes");
+
+        match result {
+            Ok(r) => {
+                txn.commit(self).await?;
+                Ok(r)
+            }
+            Err(e) => {
+                txn.abort().await;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// The current time in milliseconds since the Unix epoch.
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
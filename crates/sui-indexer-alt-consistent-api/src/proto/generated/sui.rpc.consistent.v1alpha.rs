@@ -86,6 +86,31 @@ pub struct ListBalancesResponse {
     pub balances: ::prost::alloc::vec::Vec<Balance>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListDynamicFieldsRequest {
+    /// Required. The ID of the object whose dynamic fields are listed.
+    #[prost(string, optional, tag = "1")]
+    pub parent: ::core::option::Option<::prost::alloc::string::String>,
+    /// The maximum number of entries to return. The service may return fewer than
+    /// this value.
+    ///
+    /// Consult `sui.rpc.consistent.v1alpha/ServiceConfig` for default and maximum
+    /// page sizes.
+    #[prost(uint32, optional, tag = "100")]
+    pub page_size: ::core::option::Option<u32>,
+    /// A page token, received from a previous `ListDynamicFields` call.
+    /// Provide this to retrieve the next page.
+    #[prost(bytes = "bytes", optional, tag = "101")]
+    pub after_token: ::core::option::Option<::prost::bytes::Bytes>,
+    /// A page token, received from a previous `ListDynamicFields` call.
+    /// Provide this to retrieve the previous page.
+    #[prost(bytes = "bytes", optional, tag = "102")]
+    pub before_token: ::core::option::Option<::prost::bytes::Bytes>,
+    /// Whether to fetch the next page from the front or back of the filtered
+    /// range.
+    #[prost(enumeration = "End", optional, tag = "103")]
+    pub end: ::core::option::Option<i32>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListObjectsByTypeRequest {
     /// Required. Type filter to limit the types of objects listed.
     ///
@@ -502,6 +527,35 @@ pub mod consistent_service_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        pub async fn list_dynamic_fields(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListDynamicFieldsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListObjectsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/sui.rpc.consistent.v1alpha.ConsistentService/ListDynamicFields",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "sui.rpc.consistent.v1alpha.ConsistentService",
+                        "ListDynamicFields",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn list_objects_by_type(
             &mut self,
             request: impl tonic::IntoRequest<super::ListObjectsByTypeRequest>,
@@ -629,6 +683,13 @@ pub mod consistent_service_server {
             tonic::Response<super::ListBalancesResponse>,
             tonic::Status,
         >;
+        async fn list_dynamic_fields(
+            &self,
+            request: tonic::Request<super::ListDynamicFieldsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListObjectsResponse>,
+            tonic::Status,
+        >;
         async fn list_objects_by_type(
             &self,
             request: tonic::Request<super::ListObjectsByTypeRequest>,
@@ -913,6 +974,55 @@ pub mod consistent_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/sui.rpc.consistent.v1alpha.ConsistentService/ListDynamicFields" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListDynamicFieldsSvc<T: ConsistentService>(pub Arc<T>);
+                    impl<
+                        T: ConsistentService,
+                    > tonic::server::UnaryService<super::ListDynamicFieldsRequest>
+                    for ListDynamicFieldsSvc<T> {
+                        type Response = super::ListObjectsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListDynamicFieldsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ConsistentService>::list_dynamic_fields(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListDynamicFieldsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/sui.rpc.consistent.v1alpha.ConsistentService/ListObjectsByType" => {
                     #[allow(non_camel_case_types)]
                     struct ListObjectsByTypeSvc<T: ConsistentService>(pub Arc<T>);
@@ -0,0 +1,99 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The admin service exposes operations on a running service over HTTP, on a separate address
+//! from the RPC, so that they are not reachable by the RPC's clients.
+//!
+//! Supported operations:
+//!
+//! - `POST /backup/{checkpoint}` backs up the database at its snapshot for `checkpoint` (which must
+//!   still be in its buffer of snapshots), to the destination configured for backups.
+
+use std::net::SocketAddr;
+
+use anyhow::Context;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::post,
+    Router,
+};
+use tokio::{net::TcpListener, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+use crate::backup::Backups;
+
+#[derive(clap::Args, Clone, Debug, Default)]
+pub struct AdminArgs {
+    /// Address to accept incoming admin requests on. The admin service is disabled if this is not
+    /// set.
+    #[arg(long)]
+    pub admin_listen_address: Option<SocketAddr>,
+}
+
+/// Start the admin service, if `args` provides an address for it to listen on, until it is
+/// signalled to stop on `cancel`. `backups` is used to serve requests for backups, which fail if
+/// backups are not configured.
+pub(crate) async fn start_admin(
+    args: AdminArgs,
+    backups: Option<Backups>,
+    cancel: CancellationToken,
+) -> anyhow::Result<Option<JoinHandle<()>>> {
+    let Some(admin_listen_address) = args.admin_listen_address else {
+        return Ok(None);
+    };
+
+    let router = Router::new()
+        .route("/backup/{checkpoint}", post(backup))
+        .with_state(backups);
+
+    info!("Starting admin service on {admin_listen_address}");
+    let listener = TcpListener::bind(admin_listen_address)
+        .await
+        .context("Failed to bind admin service to listen address")?;
+
+    Ok(Some(tokio::spawn(async move {
+        let res = axum::serve(listener, router)
+            .with_graceful_shutdown(async move {
+                cancel.cancelled().await;
+                info!("Shutting down admin service");
+            })
+            .await;
+
+        if let Err(e) = res {
+            error!("Failed to start admin service: {e:?}");
+        }
+    })))
+}
+
+/// Back up the database at its snapshot for `checkpoint`.
+async fn backup(
+    State(backups): State<Option<Backups>>,
+    Path(checkpoint): Path<u64>,
+) -> (StatusCode, String) {
+    let Some(backups) = backups else {
+        return (
+            StatusCode::CONFLICT,
+            "Backups are not configured".to_owned(),
+        );
+    };
+
+    if backups.snapshot(checkpoint).is_none() {
+        return (
+            StatusCode::NOT_FOUND,
+            format!("No snapshot at checkpoint {checkpoint}"),
+        );
+    }
+
+    match backups.backup_at(checkpoint).await {
+        Ok(_) => (StatusCode::OK, format!("Backed up checkpoint {checkpoint}")),
+        Err(e) => {
+            error!(checkpoint, "Failed to back up database: {e:#}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to back up checkpoint {checkpoint}: {e:#}"),
+            )
+        }
+    }
+}
@@ -5,7 +5,10 @@ use std::path::PathBuf;
 
 use sui_indexer_alt_framework::{ingestion::ClientArgs, IndexerArgs};
 use sui_indexer_alt_metrics::MetricsArgs;
+use url::Url;
 
+pub use crate::admin::AdminArgs;
+pub use crate::formal_snapshot::FormalSnapshotArgs;
 pub use crate::rpc::{RpcArgs, TlsArgs};
 
 #[derive(clap::Parser, Debug, Clone)]
@@ -33,6 +36,9 @@ pub enum Command {
         #[clap(flatten)]
         rpc_args: RpcArgs,
 
+        #[clap(flatten)]
+        admin_args: AdminArgs,
+
         #[clap(flatten)]
         metrics_args: MetricsArgs,
 
@@ -40,6 +46,39 @@ pub enum Command {
         /// the configuration will be set.
         #[arg(long)]
         config: Option<PathBuf>,
+
+        /// Bootstrap the database from the latest backup at this location (a local directory, or
+        /// an object store URL), if it does not exist yet. The service resumes indexing from the
+        /// checkpoint the backup was taken at. Backups are taken by the service itself, as
+        /// configured in the `[backup]` section of its configuration, or on demand with the
+        /// `backup` command.
+        #[arg(long)]
+        restore_from: Option<String>,
+    },
+
+    /// Seed a new database from the live object set in a formal snapshot, taken at the end of an
+    /// epoch. Running the service on the resulting database resumes indexing from the first
+    /// checkpoint of the next epoch.
+    Bootstrap {
+        /// The path where the RocksDB database will be created. It must not already exist.
+        #[arg(long)]
+        database_path: PathBuf,
+
+        #[clap(flatten)]
+        snapshot_args: FormalSnapshotArgs,
+    },
+
+    /// Ask a running service to back up its database at the snapshot for a given checkpoint, to
+    /// the destination configured in the `[backup]` section of its configuration. The service
+    /// must have its admin service enabled, and still have a snapshot at that checkpoint.
+    Backup {
+        /// URL of the running service's admin service.
+        #[arg(long, default_value = "http://localhost:7002")]
+        admin_url: Url,
+
+        /// The checkpoint to back up the database at.
+        #[arg(long)]
+        checkpoint: u64,
     },
 
     /// Output the contents of the default configuration to STDOUT.
@@ -0,0 +1,1056 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Backups export the contents of a consistent store's database to a local directory or an object
+//! store, so that a new instance of the service can be bootstrapped from them, rather than
+//! indexing from genesis.
+//!
+//! Backups are taken by the running service, from snapshots taken by its synchronizer, so every
+//! pipeline's data (and watermark) in the backup is at the same checkpoint. They are taken
+//! periodically from the latest snapshot (skipping snapshots that have already been backed up), or
+//! on demand, at a chosen checkpoint, through the admin service. The contents of each column
+//! family (including watermarks) are written out as a sequence of SST files under a directory
+//! named after that checkpoint, followed by a manifest describing those files and the watermark
+//! they were taken at. Finally, the destination's `LATEST` file is updated to point to the backup
+//! (if it is the latest), to mark the backup as complete, and older backups beyond the configured
+//! retention are deleted.
+//!
+//! Restoring ingests the SST files from the latest complete backup into a fresh database, which
+//! the service can then open and resume indexing from, starting from the checkpoint recorded in
+//! its watermarks.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, ensure, Context};
+use futures::TryStreamExt;
+use object_store::{buffered::BufWriter, path::Path as ObjectPath, ObjectStore};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs,
+    io::{AsyncWriteExt, BufReader},
+    sync::{mpsc, Mutex},
+    task::{self, JoinHandle},
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info};
+use url::Url;
+
+use crate::{
+    config::BackupConfig,
+    db::{Db, Watermark, WATERMARK_CF},
+    schema::Schema,
+    store::Schema as _,
+};
+
+/// Name of the file describing the contents of a backup.
+const MANIFEST: &str = "MANIFEST.toml";
+
+/// Name of the file at the root of a backup destination, containing the checkpoint of the latest
+/// complete backup.
+const LATEST: &str = "LATEST";
+
+/// Name of the directory inside the database's directory that backup files are staged in while
+/// they are being restored.
+const RESTORE_STAGING: &str = "RESTORE";
+
+/// Version of the backup format, bumped whenever the layout of a backup changes incompatibly.
+const FORMAT_VERSION: u64 = 1;
+
+/// SST files are rotated once they reach this size, in bytes, to bound the size of each upload.
+const MAX_FILE_SIZE: u64 = 256 << 20;
+
+/// Describes the files that make up a backup.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct Manifest {
+    version: u64,
+
+    /// The watermark for each pipeline, at the time the backup was taken. All pipelines are at
+    /// the same checkpoint.
+    watermarks: BTreeMap<String, Watermark>,
+
+    /// The SST files (relative to the backup's location) containing the data for each column
+    /// family, in key order. Column families with no data have no files.
+    column_families: BTreeMap<String, Vec<String>>,
+}
+
+/// Backs up a database to a destination (a local directory or an object store URL, see
+/// [`object_store`]). Backups can be taken periodically by a background task (see
+/// [`Backups::start`]), or on demand (e.g. from the admin service). Backups are taken one at a
+/// time.
+#[derive(Clone)]
+pub(crate) struct Backups {
+    db: Arc<Db>,
+
+    /// The names of the column families to back up (the watermarks column family is always
+    /// included).
+    cfs: Vec<&'static str>,
+
+    destination: String,
+    staging_path: Option<PathBuf>,
+    retention: usize,
+
+    /// Held for the duration of each backup, so that pruning old backups does not race with
+    /// writing a new one.
+    lock: Arc<Mutex<()>>,
+}
+
+impl Backups {
+    /// Set-up backups of `db` to `config.destination`. Returns `None` if the configuration does
+    /// not specify a destination for backups.
+    pub(crate) fn new(db: Arc<Db>, cfs: Vec<&'static str>, config: &BackupConfig) -> Option<Self> {
+        Some(Self {
+            db,
+            cfs,
+            destination: config.destination.clone()?,
+            staging_path: config.staging_path.clone(),
+            retention: config.retention,
+            lock: Arc::new(Mutex::new(())),
+        })
+    }
+
+    /// Start a task that backs up the database's latest snapshot every `interval`, until it is
+    /// signalled to stop on `cancel`. Snapshots that have already been backed up are skipped.
+    /// Failed backups are logged and retried on the next interval.
+    pub(crate) fn start(self, interval: Duration, cancel: CancellationToken) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tokio::time::sleep(interval) => {}
+                }
+
+                tokio::select! {
+                    _ = cancel.cancelled() => {
+                        info!("Shutdown received during backup");
+                        break;
+                    }
+
+                    res = self.backup_latest() => {
+                        if let Err(e) = res {
+                            error!("Failed to back up database: {e:#}");
+                        }
+                    }
+                }
+            }
+
+            info!("Backups gracefully shut down");
+        })
+    }
+
+    /// Back up the database at its latest snapshot, unless the destination already has a backup
+    /// at (or after) that checkpoint. Returns the watermark the backup was taken at, or `None` if
+    /// it was skipped.
+    pub(crate) async fn backup_latest(&self) -> anyhow::Result<Option<Watermark>> {
+        let Some(watermark) = self.db.snapshot_range(u64::MAX).map(|r| *r.end()) else {
+            bail!("No snapshot to back up from");
+        };
+
+        let _guard = self.lock.lock().await;
+        let checkpoint = watermark.checkpoint_hi_inclusive;
+        let (store, root) = object_store(&self.destination, /* create */ true).await?;
+        if let Some(latest) = latest_backup(&store, &root).await? {
+            if latest >= checkpoint {
+                debug!(checkpoint, latest, "Snapshot already backed up, skipping");
+                return Ok(None);
+            }
+        }
+
+        self.backup(&store, &root, watermark).await.map(Some)
+    }
+
+    /// Back up the database at its snapshot for `checkpoint`, which must still be in the
+    /// database's buffer of snapshots. Returns the watermark the backup was taken at.
+    pub(crate) async fn backup_at(&self, checkpoint: u64) -> anyhow::Result<Watermark> {
+        let Some(watermark) = self.snapshot(checkpoint) else {
+            bail!("No snapshot at checkpoint {checkpoint} to back up from");
+        };
+
+        let _guard = self.lock.lock().await;
+        let (store, root) = object_store(&self.destination, /* create */ true).await?;
+        self.backup(&store, &root, watermark).await
+    }
+
+    /// The watermark of the database's snapshot at `checkpoint`, if it has one.
+    pub(crate) fn snapshot(&self, checkpoint: u64) -> Option<Watermark> {
+        let watermark = *self.db.snapshot_range(checkpoint)?.end();
+        (watermark.checkpoint_hi_inclusive == checkpoint).then_some(watermark)
+    }
+
+    /// Export the contents of the database at the snapshot for `watermark` to `store`, under a
+    /// directory named after its checkpoint, in `root`. Once the backup is complete, `LATEST` is
+    /// updated to point to it (unless it already points to a later backup), and backups beyond
+    /// the most recent `retention` are deleted.
+    ///
+    /// SST files are written to a temporary directory under `staging_path` (or the system's
+    /// temporary directory, if it is not provided) before being uploaded, and are removed once
+    /// uploaded.
+    async fn backup(
+        &self,
+        store: &Arc<dyn ObjectStore>,
+        root: &ObjectPath,
+        watermark: Watermark,
+    ) -> anyhow::Result<Watermark> {
+        let checkpoint = watermark.checkpoint_hi_inclusive;
+        let prefix = root.child(checkpoint.to_string());
+
+        let staging = match &self.staging_path {
+            Some(path) => tempfile::tempdir_in(path),
+            None => tempfile::tempdir(),
+        }
+        .context("Failed to create staging directory")?;
+
+        // Files are handed over from the exporter as soon as they are written, so that at most a
+        // couple of them are on disk at any time.
+        let (tx, mut rx) = mpsc::channel(1);
+        let h_export = task::spawn_blocking({
+            let db = self.db.clone();
+            let cfs = self.cfs.clone();
+            let staging_dir = staging.path().to_owned();
+            move || export(&db, &cfs, watermark, &staging_dir, tx)
+        });
+
+        while let Some(file) = rx.recv().await {
+            upload(store, &prefix, staging.path(), &file).await?;
+        }
+
+        let manifest = h_export
+            .await
+            .context("Export task panicked")?
+            .context("Failed to export database")?;
+
+        let contents = toml::to_string_pretty(&manifest).context("Failed to serialize manifest")?;
+        store
+            .put(&prefix.child(MANIFEST), contents.into())
+            .await
+            .context("Failed to write manifest")?;
+
+        if latest_backup(store, root)
+            .await?
+            .is_none_or(|latest| latest < checkpoint)
+        {
+            store
+                .put(&root.child(LATEST), checkpoint.to_string().into())
+                .await
+                .context("Failed to mark backup as complete")?;
+        }
+
+        info!(
+            destination = self.destination,
+            checkpoint,
+            pipelines = manifest.watermarks.len(),
+            "Backup complete"
+        );
+
+        prune(store, root, self.retention).await?;
+        Ok(watermark)
+    }
+}
+
+/// Restore the database at `database_path` from the latest complete backup at `source` (a local
+/// directory or an object store URL, as configured for backups).
+///
+/// This is a no-op if a database already exists at `database_path` (an empty directory is treated
+/// as if it does not exist), so that a service configured to bootstrap from a backup can be
+/// restarted without losing progress. Otherwise, the backup is restored directly into
+/// `database_path` (which may be a mount point), with files staged in a directory inside it. The
+/// staging directory is removed last, so if it is found on a later attempt, the previous restore
+/// was interrupted, and its partial database is cleared out before starting again.
+pub async fn restore(database_path: impl AsRef<Path>, source: &str) -> anyhow::Result<()> {
+    let database_path = database_path.as_ref();
+    let staging = database_path.join(RESTORE_STAGING);
+
+    let interrupted = match fs::read_dir(database_path).await {
+        Ok(mut entries) => {
+            let mut empty = true;
+            let mut interrupted = false;
+            while let Some(entry) = entries.next_entry().await? {
+                empty = false;
+                interrupted |= entry.file_name() == RESTORE_STAGING;
+            }
+
+            if !empty && !interrupted {
+                info!(
+                    path = %database_path.display(),
+                    "Database already exists, skipping restore"
+                );
+                return Ok(());
+            }
+
+            interrupted
+        }
+
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+        Err(e) => return Err(e).context("Failed to check for existing database"),
+    };
+
+    let (store, root) = object_store(source, /* create */ false).await?;
+
+    let Some(checkpoint) = latest_backup(&store, &root).await? else {
+        bail!("Failed to find latest backup, backup may be incomplete");
+    };
+
+    let prefix = root.child(checkpoint.to_string());
+    let manifest: Manifest = {
+        let contents = fetch_string(&store, &prefix.child(MANIFEST))
+            .await
+            .context("Failed to fetch manifest")?;
+
+        toml::from_str(&contents).context("Failed to parse manifest")?
+    };
+
+    ensure!(
+        manifest.version == FORMAT_VERSION,
+        "Unsupported backup format version: {}, expected {FORMAT_VERSION}",
+        manifest.version,
+    );
+
+    if interrupted {
+        info!(
+            path = %database_path.display(),
+            "Clearing out interrupted restore"
+        );
+
+        clear_dir(database_path)
+            .await
+            .context("Failed to clear out interrupted restore")?;
+    }
+
+    // Files are downloaded inside the database's directory, so that they can be moved into the
+    // database on ingestion.
+    fs::create_dir_all(&staging)
+        .await
+        .context("Failed to create staging directory")?;
+
+    let db = Arc::new({
+        let database_path = database_path.to_owned();
+        let cfs: Vec<_> = manifest.column_families.keys().cloned().collect();
+        task::spawn_blocking(move || {
+            let mut opts = rocksdb::Options::default();
+            opts.create_if_missing(true);
+            opts.create_missing_column_families(true);
+
+            // Column families are created with the same options the service opens them with.
+            let mut schema: BTreeMap<_, _> = Schema::cfs().into_iter().collect();
+            let cfs: Vec<_> = cfs
+                .into_iter()
+                .map(|cf| {
+                    let opts = schema.remove(cf.as_str()).unwrap_or_default();
+                    (cf, opts)
+                })
+                .collect();
+
+            rocksdb::DB::open_cf_with_opts(&opts, database_path, cfs)
+        })
+        .await
+        .context("Open task panicked")?
+        .context("Failed to create database")?
+    });
+
+    for (cf, files) in &manifest.column_families {
+        for file in files {
+            let local = download(&store, &prefix, &staging, file).await?;
+
+            let db = db.clone();
+            let cf = cf.clone();
+            task::spawn_blocking(move || ingest(&db, &cf, local))
+                .await
+                .context("Ingest task panicked")??;
+        }
+    }
+
+    // Close the database before marking the restore as complete.
+    let Ok(db) = Arc::try_unwrap(db) else {
+        bail!("Database still in use after restore");
+    };
+
+    drop(db);
+
+    fs::remove_dir_all(&staging)
+        .await
+        .context("Failed to mark restore as complete")?;
+
+    info!(
+        source,
+        path = %database_path.display(),
+        checkpoint,
+        "Restore complete"
+    );
+
+    Ok(())
+}
+
+/// Write the contents of `db`'s column families, `cfs`, and its watermarks, at the snapshot for
+/// `watermark` out as SST files in `staging`, sending the name of each file over `tx` once it has
+/// been written. Returns the manifest describing the files.
+fn export(
+    db: &Db,
+    cfs: &[&'static str],
+    watermark: Watermark,
+    staging: &Path,
+    tx: mpsc::Sender<String>,
+) -> anyhow::Result<Manifest> {
+    let checkpoint = watermark.checkpoint_hi_inclusive;
+
+    // Hold onto the snapshot for the duration of the export, so that it stays readable even if
+    // the database drops it from its buffer in the meantime. Reads go through the column
+    // families' own options, so merge operands are resolved into the values that are exported.
+    let snapshot = db
+        .at_snapshot(checkpoint)
+        .with_context(|| format!("Snapshot at checkpoint {checkpoint} no longer available"))?;
+
+    let opts = rocksdb::Options::default();
+    let mut manifest = Manifest {
+        version: FORMAT_VERSION,
+        watermarks: BTreeMap::new(),
+        column_families: BTreeMap::new(),
+    };
+
+    for (i, &name) in cfs.iter().chain([&WATERMARK_CF]).enumerate() {
+        let cf = db
+            .cf(name)
+            .ok_or_else(|| anyhow!("Column family {name:?} not found"))?;
+
+        let mut files = vec![];
+        let mut writer: Option<(String, rocksdb::SstFileWriter)> = None;
+
+        let mut iter = snapshot.raw_iterator_cf(&cf);
+        iter.seek_to_first();
+        while let (Some(key), Some(value)) = (iter.key(), iter.value()) {
+            if name == WATERMARK_CF {
+                let pipeline = String::from_utf8(key.to_vec()).context("Bad pipeline name")?;
+                let w: Watermark =
+                    bcs::from_bytes(value).context("Failed to deserialize watermark")?;
+
+                ensure!(
+                    w.checkpoint_hi_inclusive == checkpoint,
+                    "Pipeline {pipeline} is at checkpoint {}, but snapshot is at {checkpoint}",
+                    w.checkpoint_hi_inclusive,
+                );
+
+                manifest.watermarks.insert(pipeline, w);
+            }
+
+            let (_, w) = match &mut writer {
+                Some(w) => w,
+                w @ None => {
+                    let file = format!("{i:03}-{:05}.sst", files.len());
+                    let sst = rocksdb::SstFileWriter::create(&opts);
+                    sst.open(staging.join(&file))
+                        .with_context(|| format!("Failed to create {file}"))?;
+                    w.insert((file, sst))
+                }
+            };
+
+            w.put(key, value)
+                .with_context(|| format!("Failed to write to {name:?}"))?;
+
+            if let Some((file, w)) = writer.take_if(|(_, w)| w.file_size() >= MAX_FILE_SIZE) {
+                files.push(finish(file, w, &tx)?);
+            }
+
+            iter.next();
+        }
+
+        iter.status()
+            .with_context(|| format!("Failed to iterate over {name:?}"))?;
+
+        if let Some((file, w)) = writer.take() {
+            files.push(finish(file, w, &tx)?);
+        }
+
+        info!(cf = name, files = files.len(), "Exported column family");
+        manifest.column_families.insert(name.to_owned(), files);
+    }
+
+    Ok(manifest)
+}
+
+/// Finish writing SST `file`, and hand it over to be uploaded.
+fn finish(
+    file: String,
+    mut w: rocksdb::SstFileWriter,
+    tx: &mpsc::Sender<String>,
+) -> anyhow::Result<String> {
+    w.finish()
+        .with_context(|| format!("Failed to finish {file}"))?;
+
+    tx.blocking_send(file.clone())
+        .context("Upload task stopped")?;
+
+    Ok(file)
+}
+
+/// Ingest SST file at `path` into column family `cf` of `db`. The file is moved into the database.
+fn ingest(db: &rocksdb::DB, cf: &str, path: PathBuf) -> anyhow::Result<()> {
+    let handle = db
+        .cf_handle(cf)
+        .with_context(|| format!("Column family {cf:?} not found"))?;
+
+    let mut opts = rocksdb::IngestExternalFileOptions::default();
+    opts.set_move_files(true);
+
+    db.ingest_external_file_cf_opts(&handle, &opts, vec![&path])
+        .with_context(|| format!("Failed to ingest {} into {cf:?}", path.display()))
+}
+
+/// Upload `file` from the `staging` directory to `store`, under `prefix`, and then delete the
+/// local copy.
+async fn upload(
+    store: &Arc<dyn ObjectStore>,
+    prefix: &ObjectPath,
+    staging: &Path,
+    file: &str,
+) -> anyhow::Result<()> {
+    let local = staging.join(file);
+    let mut reader = BufReader::new(
+        fs::File::open(&local)
+            .await
+            .with_context(|| format!("Failed to open {file}"))?,
+    );
+
+    let mut writer = BufWriter::new(store.clone(), prefix.child(file));
+    tokio::io::copy_buf(&mut reader, &mut writer)
+        .await
+        .with_context(|| format!("Failed to upload {file}"))?;
+
+    writer
+        .shutdown()
+        .await
+        .with_context(|| format!("Failed to complete upload of {file}"))?;
+
+    fs::remove_file(&local)
+        .await
+        .with_context(|| format!("Failed to clean up {file}"))?;
+
+    info!(file, "Uploaded");
+    Ok(())
+}
+
+/// Download `file` from `store`, under `prefix`, into the `staging` directory, returning the path
+/// to the local copy.
+async fn download(
+    store: &Arc<dyn ObjectStore>,
+    prefix: &ObjectPath,
+    staging: &Path,
+    file: &str,
+) -> anyhow::Result<PathBuf> {
+    let local = staging.join(file);
+    let mut out = fs::File::create(&local)
+        .await
+        .with_context(|| format!("Failed to create {file}"))?;
+
+    let mut stream = store
+        .get(&prefix.child(file))
+        .await
+        .with_context(|| format!("Failed to fetch {file}"))?
+        .into_stream();
+
+    while let Some(chunk) = stream
+        .try_next()
+        .await
+        .with_context(|| format!("Failed to download {file}"))?
+    {
+        out.write_all(&chunk).await?;
+    }
+
+    out.sync_all().await?;
+
+    info!(file, "Downloaded");
+    Ok(local)
+}
+
+/// The checkpoint of the latest complete backup in `store`, under `root`, or `None` if there
+/// isn't one.
+async fn latest_backup(
+    store: &Arc<dyn ObjectStore>,
+    root: &ObjectPath,
+) -> anyhow::Result<Option<u64>> {
+    let path = root.child(LATEST);
+    let latest = match store.get(&path).await {
+        Ok(result) => result
+            .bytes()
+            .await
+            .with_context(|| format!("Failed to read {path}"))?,
+        Err(object_store::Error::NotFound { .. }) => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to fetch {path}")),
+    };
+
+    let latest = std::str::from_utf8(&latest)
+        .with_context(|| format!("{path} is not valid UTF-8"))?
+        .trim()
+        .parse()
+        .context("Failed to parse latest backup checkpoint")?;
+
+    Ok(Some(latest))
+}
+
+/// Delete all but the most recent `retention` backups in `store`, under `root`. The backup that
+/// `LATEST` points to is always kept.
+async fn prune(
+    store: &Arc<dyn ObjectStore>,
+    root: &ObjectPath,
+    retention: usize,
+) -> anyhow::Result<()> {
+    let latest = latest_backup(store, root).await?;
+    let listing = store
+        .list_with_delimiter(Some(root))
+        .await
+        .context("Failed to list backups")?;
+
+    let mut checkpoints: Vec<u64> = listing
+        .common_prefixes
+        .iter()
+        .filter_map(|prefix| prefix.filename()?.parse().ok())
+        .collect();
+
+    checkpoints.sort_unstable();
+    for checkpoint in checkpoints.into_iter().rev().skip(retention) {
+        if Some(checkpoint) == latest {
+            continue;
+        }
+
+        let prefix = root.child(checkpoint.to_string());
+        let files: Vec<_> = store
+            .list(Some(&prefix))
+            .map_ok(|meta| meta.location)
+            .try_collect()
+            .await
+            .with_context(|| format!("Failed to list backup at checkpoint {checkpoint}"))?;
+
+        // Delete the manifest first, so that a partially deleted backup is not mistaken for a
+        // complete one.
+        let (manifest, rest): (Vec<_>, Vec<_>) = files
+            .into_iter()
+            .partition(|path| path.filename() == Some(MANIFEST));
+
+        for path in manifest.into_iter().chain(rest) {
+            store
+                .delete(&path)
+                .await
+                .with_context(|| format!("Failed to delete {path}"))?;
+        }
+
+        info!(checkpoint, "Pruned backup");
+    }
+
+    Ok(())
+}
+
+/// Remove everything inside the directory at `path`, but not the directory itself.
+async fn clear_dir(path: &Path) -> anyhow::Result<()> {
+    let mut entries = fs::read_dir(path).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        if entry.file_type().await?.is_dir() {
+            fs::remove_dir_all(entry.path()).await?;
+        } else {
+            fs::remove_file(entry.path()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetch the contents of `path` from `store`, as a UTF-8 string.
+async fn fetch_string(store: &Arc<dyn ObjectStore>, path: &ObjectPath) -> anyhow::Result<String> {
+    let bytes = store
+        .get(path)
+        .await
+        .with_context(|| format!("Failed to fetch {path}"))?
+        .bytes()
+        .await
+        .with_context(|| format!("Failed to read {path}"))?;
+
+    String::from_utf8(bytes.to_vec()).with_context(|| format!("{path} is not valid UTF-8"))
+}
+
+/// Interpret `location` as an object store URL, or failing that, as a path to a local directory,
+/// which is created if it does not exist and `create` is set.
+async fn object_store(
+    location: &str,
+    create: bool,
+) -> anyhow::Result<(Arc<dyn ObjectStore>, ObjectPath)> {
+    if let Ok(url) = Url::parse(location) {
+        let env = std::env::vars().map(|(k, v)| (k.to_ascii_lowercase(), v));
+        let (store, prefix) = object_store::parse_url_opts(&url, env)
+            .with_context(|| format!("Failed to configure object store for {location}"))?;
+
+        return Ok((store.into(), prefix));
+    }
+
+    if create {
+        fs::create_dir_all(location)
+            .await
+            .with_context(|| format!("Failed to create {location}"))?;
+    }
+
+    let store = object_store::local::LocalFileSystem::new_with_prefix(location)
+        .with_context(|| format!("Failed to access {location}"))?;
+
+    Ok((Arc::new(store), ObjectPath::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::{tests::wm, Db},
+        schema::balances,
+    };
+
+    use super::*;
+
+    fn backups(db: &Arc<Db>, cfs: &[&'static str], destination: &str) -> Backups {
+        let config = BackupConfig {
+            destination: Some(destination.to_owned()),
+            ..Default::default()
+        };
+
+        Backups::new(db.clone(), cfs.to_vec(), &config).unwrap()
+    }
+
+    fn cfs() -> Vec<(&'static str, rocksdb::Options)> {
+        vec![
+            ("a", rocksdb::Options::default()),
+            ("b", rocksdb::Options::default()),
+        ]
+    }
+
+    fn opts() -> rocksdb::Options {
+        let mut opts = rocksdb::Options::default();
+        opts.create_if_missing(true);
+        opts
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore() {
+        let d = tempfile::tempdir().unwrap();
+        let dst = d.path().join("dst");
+        let backup_dir = d.path().join("backup");
+        let backup_dir = backup_dir.to_str().unwrap();
+
+        {
+            let db = Arc::new(Db::open(d.path().join("src"), opts(), 4, cfs()).unwrap());
+            let a = db.cf("a").unwrap();
+
+            let mut batch = rocksdb::WriteBatch::default();
+            for i in 0u64..100 {
+                batch.put_cf(&a, i.to_be_bytes(), bcs::to_bytes(&(i * 2)).unwrap());
+            }
+
+            db.write("a", wm(10), batch).unwrap();
+            db.write("b", wm(10), rocksdb::WriteBatch::default())
+                .unwrap();
+            db.take_snapshot(wm(10));
+
+            // Writes after the latest snapshot are not included in the backup.
+            let mut batch = rocksdb::WriteBatch::default();
+            batch.put_cf(&a, 100u64.to_be_bytes(), bcs::to_bytes(&200u64).unwrap());
+            db.write("a", wm(11), batch).unwrap();
+
+            let w = backups(&db, &["a", "b"], backup_dir)
+                .backup_latest()
+                .await
+                .unwrap();
+            assert_eq!(w, Some(wm(10)));
+        }
+
+        restore(&dst, backup_dir).await.unwrap();
+
+        let db = Db::open(&dst, rocksdb::Options::default(), 4, cfs()).unwrap();
+        assert_eq!(db.watermark("a").unwrap(), Some(wm(10)));
+        assert_eq!(db.watermark("b").unwrap(), Some(wm(10)));
+
+        db.take_snapshot(wm(10));
+        let a = db.cf("a").unwrap();
+        let b = db.cf("b").unwrap();
+        for i in 0u64..100 {
+            let v: Option<u64> = db.get(10, &a, &i.to_be_bytes()).unwrap();
+            assert_eq!(v, Some(i * 2));
+        }
+
+        let v: Option<u64> = db.get(10, &a, &100u64.to_be_bytes()).unwrap();
+        assert_eq!(v, None);
+
+        let v: Option<u64> = db.get(10, &b, &0u64.to_be_bytes()).unwrap();
+        assert_eq!(v, None);
+    }
+
+    #[tokio::test]
+    async fn test_backup_merge_operands() {
+        let d = tempfile::tempdir().unwrap();
+        let dst = d.path().join("dst");
+        let backup_dir = d.path().join("backup");
+        let backup_dir = backup_dir.to_str().unwrap();
+
+        let cfs = || vec![("balances", balances::options())];
+
+        {
+            let db = Arc::new(Db::open(d.path().join("src"), opts(), 4, cfs()).unwrap());
+            let cf = db.cf("balances").unwrap();
+
+            // Balances are written as merge operands, which the backup must resolve.
+            for (cp, delta) in [(1, 100i128), (2, -30), (3, 5)] {
+                let mut batch = rocksdb::WriteBatch::default();
+                batch.merge_cf(&cf, b"key", bcs::to_bytes(&delta).unwrap());
+                db.write("balances", wm(cp), batch).unwrap();
+            }
+
+            db.take_snapshot(wm(3));
+            backups(&db, &["balances"], backup_dir)
+                .backup_latest()
+                .await
+                .unwrap();
+        }
+
+        restore(&dst, backup_dir).await.unwrap();
+
+        let db = Db::open(&dst, rocksdb::Options::default(), 4, cfs()).unwrap();
+        assert_eq!(db.watermark("balances").unwrap(), Some(wm(3)));
+
+        // The restored balance can continue to be merged into.
+        let cf = db.cf("balances").unwrap();
+        let mut batch = rocksdb::WriteBatch::default();
+        batch.merge_cf(&cf, b"key", bcs::to_bytes(&1i128).unwrap());
+        db.write("balances", wm(4), batch).unwrap();
+
+        db.take_snapshot(wm(4));
+        let raw = db.at_snapshot(4).unwrap().get_cf(&cf, b"key").unwrap();
+        let balance: i128 = bcs::from_bytes(&raw.unwrap()).unwrap();
+        assert_eq!(balance, 76);
+    }
+
+    #[tokio::test]
+    async fn test_backup_no_snapshot() {
+        let d = tempfile::tempdir().unwrap();
+        let db = Arc::new(Db::open(d.path().join("src"), opts(), 4, cfs()).unwrap());
+        let backup_dir = d.path().join("backup");
+
+        // Backups are only taken from snapshots.
+        db.write("a", wm(10), rocksdb::WriteBatch::default())
+            .unwrap();
+        let backups = backups(&db, &["a", "b"], backup_dir.to_str().unwrap());
+        assert!(backups.backup_latest().await.is_err());
+        assert!(backups.backup_at(10).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_backup_latest() {
+        let d = tempfile::tempdir().unwrap();
+        let dst = d.path().join("dst");
+        let backup_dir = d.path().join("backup");
+        let backup_dir = backup_dir.to_str().unwrap();
+
+        {
+            let db = Arc::new(Db::open(d.path().join("src"), opts(), 4, cfs()).unwrap());
+            for cp in [10, 20] {
+                db.write("a", wm(cp), rocksdb::WriteBatch::default())
+                    .unwrap();
+                db.take_snapshot(wm(cp));
+                backups(&db, &["a", "b"], backup_dir)
+                    .backup_latest()
+                    .await
+                    .unwrap();
+            }
+        }
+
+        // The restore picks the latest backup.
+        restore(&dst, backup_dir).await.unwrap();
+        let db = Db::open(&dst, rocksdb::Options::default(), 4, cfs()).unwrap();
+        assert_eq!(db.watermark("a").unwrap(), Some(wm(20)));
+    }
+
+    #[tokio::test]
+    async fn test_backup_skips_existing() {
+        let d = tempfile::tempdir().unwrap();
+        let db = Arc::new(Db::open(d.path().join("src"), opts(), 4, cfs()).unwrap());
+        let backup_dir = d.path().join("backup");
+        let backups = backups(&db, &["a", "b"], backup_dir.to_str().unwrap());
+
+        db.write("a", wm(10), rocksdb::WriteBatch::default())
+            .unwrap();
+        db.take_snapshot(wm(10));
+        assert_eq!(backups.backup_latest().await.unwrap(), Some(wm(10)));
+
+        // The latest snapshot has already been backed up.
+        assert_eq!(backups.backup_latest().await.unwrap(), None);
+
+        db.write("a", wm(20), rocksdb::WriteBatch::default())
+            .unwrap();
+        db.take_snapshot(wm(20));
+        assert_eq!(backups.backup_latest().await.unwrap(), Some(wm(20)));
+    }
+
+    #[tokio::test]
+    async fn test_backup_at() {
+        let d = tempfile::tempdir().unwrap();
+        let dst = d.path().join("dst");
+        let backup_dir = d.path().join("backup");
+        let backup_dir = backup_dir.to_str().unwrap();
+
+        {
+            let db = Arc::new(Db::open(d.path().join("src"), opts(), 4, cfs()).unwrap());
+            let a = db.cf("a").unwrap();
+            for cp in [10u64, 20] {
+                let mut batch = rocksdb::WriteBatch::default();
+                batch.put_cf(&a, cp.to_be_bytes(), bcs::to_bytes(&cp).unwrap());
+                db.write("a", wm(cp), batch).unwrap();
+                db.take_snapshot(wm(cp));
+            }
+
+            let backups = backups(&db, &["a", "b"], backup_dir);
+            assert_eq!(backups.backup_at(10).await.unwrap(), wm(10));
+
+            // There is no snapshot at this checkpoint.
+            assert!(backups.backup_at(15).await.is_err());
+        }
+
+        // The backup is taken from the requested snapshot, not the latest one.
+        restore(&dst, backup_dir).await.unwrap();
+        let db = Db::open(&dst, rocksdb::Options::default(), 4, cfs()).unwrap();
+        assert_eq!(db.watermark("a").unwrap(), Some(wm(10)));
+
+        db.take_snapshot(wm(10));
+        let a = db.cf("a").unwrap();
+        let v: Option<u64> = db.get(10, &a, &10u64.to_be_bytes()).unwrap();
+        assert_eq!(v, Some(10));
+        let v: Option<u64> = db.get(10, &a, &20u64.to_be_bytes()).unwrap();
+        assert_eq!(v, None);
+    }
+
+    #[tokio::test]
+    async fn test_backup_at_does_not_replace_latest() {
+        let d = tempfile::tempdir().unwrap();
+        let dst = d.path().join("dst");
+        let backup_dir = d.path().join("backup");
+        let backup_dir = backup_dir.to_str().unwrap();
+
+        {
+            let db = Arc::new(Db::open(d.path().join("src"), opts(), 4, cfs()).unwrap());
+            for cp in [10, 20] {
+                db.write("a", wm(cp), rocksdb::WriteBatch::default())
+                    .unwrap();
+                db.take_snapshot(wm(cp));
+            }
+
+            let backups = backups(&db, &["a", "b"], backup_dir);
+            backups.backup_latest().await.unwrap();
+            backups.backup_at(10).await.unwrap();
+        }
+
+        // Restoring still picks the backup at the latest checkpoint.
+        restore(&dst, backup_dir).await.unwrap();
+        let db = Db::open(&dst, rocksdb::Options::default(), 4, cfs()).unwrap();
+        assert_eq!(db.watermark("a").unwrap(), Some(wm(20)));
+    }
+
+    #[tokio::test]
+    async fn test_backup_retention() {
+        let d = tempfile::tempdir().unwrap();
+        let db = Arc::new(Db::open(d.path().join("src"), opts(), 4, cfs()).unwrap());
+        let backup_dir = d.path().join("backup");
+
+        let config = BackupConfig {
+            destination: Some(backup_dir.to_str().unwrap().to_owned()),
+            retention: 2,
+            ..Default::default()
+        };
+
+        let backups = Backups::new(db.clone(), vec!["a", "b"], &config).unwrap();
+        for cp in [10, 20, 30] {
+            db.write("a", wm(cp), rocksdb::WriteBatch::default())
+                .unwrap();
+            db.take_snapshot(wm(cp));
+            backups.backup_latest().await.unwrap();
+        }
+
+        // Only the two most recent backups are kept.
+        assert!(!backup_dir.join("10").join(MANIFEST).exists());
+        assert!(backup_dir.join("20").join(MANIFEST).exists());
+        assert!(backup_dir.join("30").join(MANIFEST).exists());
+    }
+
+    #[tokio::test]
+    async fn test_restore_existing() {
+        let d = tempfile::tempdir().unwrap();
+        let db = d.path().join("db");
+        std::fs::create_dir_all(&db).unwrap();
+        std::fs::write(db.join("CURRENT"), "MANIFEST-000001\n").unwrap();
+
+        // The backup does not exist, but the restore should not try to read it, because the
+        // database already exists.
+        restore(&db, d.path().join("missing").to_str().unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_empty_directory() {
+        let d = tempfile::tempdir().unwrap();
+        let backup_dir = d.path().join("backup");
+        let backup_dir = backup_dir.to_str().unwrap();
+
+        {
+            let db = Arc::new(Db::open(d.path().join("src"), opts(), 4, cfs()).unwrap());
+            db.write("a", wm(10), rocksdb::WriteBatch::default())
+                .unwrap();
+            db.take_snapshot(wm(10));
+            backups(&db, &["a", "b"], backup_dir)
+                .backup_latest()
+                .await
+                .unwrap();
+        }
+
+        // An empty directory (e.g. a freshly mounted volume) is treated as a missing database.
+        let dst = d.path().join("dst");
+        std::fs::create_dir_all(&dst).unwrap();
+        restore(&dst, backup_dir).await.unwrap();
+
+        let db = Db::open(&dst, rocksdb::Options::default(), 4, cfs()).unwrap();
+        assert_eq!(db.watermark("a").unwrap(), Some(wm(10)));
+    }
+
+    #[tokio::test]
+    async fn test_restore_interrupted() {
+        let d = tempfile::tempdir().unwrap();
+        let backup_dir = d.path().join("backup");
+        let backup_dir = backup_dir.to_str().unwrap();
+
+        {
+            let db = Arc::new(Db::open(d.path().join("src"), opts(), 4, cfs()).unwrap());
+            db.write("a", wm(10), rocksdb::WriteBatch::default())
+                .unwrap();
+            db.take_snapshot(wm(10));
+            backups(&db, &["a", "b"], backup_dir)
+                .backup_latest()
+                .await
+                .unwrap();
+        }
+
+        // A database directory with a leftover staging directory is from an interrupted restore,
+        // so it is cleared out and restored again.
+        let dst = d.path().join("dst");
+        std::fs::create_dir_all(dst.join(RESTORE_STAGING)).unwrap();
+        std::fs::write(dst.join("CURRENT"), "MANIFEST-000001\n").unwrap();
+        restore(&dst, backup_dir).await.unwrap();
+        assert!(!dst.join(RESTORE_STAGING).exists());
+
+        let db = Db::open(&dst, rocksdb::Options::default(), 4, cfs()).unwrap();
+        assert_eq!(db.watermark("a").unwrap(), Some(wm(10)));
+    }
+
+    #[tokio::test]
+    async fn test_restore_incomplete() {
+        let d = tempfile::tempdir().unwrap();
+        let backup_dir = d.path().join("backup");
+        std::fs::create_dir_all(&backup_dir).unwrap();
+
+        // A backup without a pointer to its latest complete backup should not be restored from.
+        let db = d.path().join("db");
+        assert!(restore(&db, backup_dir.to_str().unwrap()).await.is_err());
+        assert!(!db.exists());
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::path::PathBuf;
+
 use sui_default_config::DefaultConfig;
 use sui_indexer_alt_framework::{self as framework, pipeline::CommitterConfig};
 
@@ -28,6 +30,9 @@ pub struct ServiceConfig {
 
     /// Configuration for the read side of the service.
     pub rpc: RpcConfig,
+
+    /// Backups of the database, taken periodically, or on demand through the admin service.
+    pub backup: BackupConfig,
 }
 
 /// This type is identical to [`framework::ingestion::IngestionConfig`], but is set-up to be
@@ -54,10 +59,33 @@ pub struct ConsistencyConfig {
     pub buffer_size: usize,
 }
 
+#[DefaultConfig]
+#[serde(deny_unknown_fields)]
+pub struct BackupConfig {
+    /// Where to write backups: either a local directory (created if it does not exist) or an
+    /// object store URL (e.g. `s3://bucket/path`, `gs://bucket/path`). Object store credentials
+    /// are read from the environment. Backups are disabled if this is not set.
+    pub destination: Option<String>,
+
+    /// Time between backups, in milliseconds. Each backup is taken from the latest snapshot,
+    /// where all pipelines are at the same checkpoint, unless that snapshot has already been
+    /// backed up.
+    pub interval_ms: u64,
+
+    /// The number of most recent backups to keep at the destination. Older backups are deleted
+    /// after each new backup completes. The latest complete backup is always kept.
+    pub retention: usize,
+
+    /// Directory to stage backup files in before they are written to the destination. Defaults
+    /// to the system's temporary directory.
+    pub staging_path: Option<PathBuf>,
+}
+
 #[DefaultConfig]
 #[derive(Default)]
 pub struct PipelineLayer {
     pub balances: Option<CommitterLayer>,
+    pub dynamic_fields: Option<CommitterLayer>,
     pub object_by_owner: Option<CommitterLayer>,
     pub object_by_type: Option<CommitterLayer>,
 }
@@ -114,6 +142,7 @@ impl PipelineLayer {
     pub fn example() -> Self {
         Self {
             balances: Some(CommitterLayer::default()),
+            dynamic_fields: Some(CommitterLayer::default()),
             object_by_owner: Some(CommitterLayer::default()),
             object_by_type: Some(CommitterLayer::default()),
         }
@@ -177,3 +206,14 @@ impl Default for ConsistencyConfig {
         }
     }
 }
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            destination: None,
+            interval_ms: 6 * 60 * 60 * 1000,
+            retention: 3,
+            staging_path: None,
+        }
+    }
+}
@@ -26,7 +26,7 @@ pub(crate) mod key;
 pub(crate) mod map;
 
 /// Name of the column family the database adds, to manage the checkpoint watermark.
-const WATERMARK_CF: &str = "$watermark";
+pub(crate) const WATERMARK_CF: &str = "$watermark";
 
 /// A wrapper around RocksDB that provides arbitrary writes and snapshot-based reads (reads must
 /// specify the checkpoint they want to read from). Keys and values are encoded (using Bincode and
@@ -418,7 +418,11 @@ impl Db {
         Ok(iter::RevIter::new(Some(inner)))
     }
 
-    fn at_snapshot(&self, checkpoint: u64) -> Result<Arc<rocksdb::Snapshot<'_>>, Error> {
+    /// Access the snapshot at `checkpoint`. The snapshot remains readable for as long as it is
+    /// held, even if it is dropped from the database's buffer in the meantime.
+    ///
+    /// Fails if the database does not have a snapshot at `checkpoint`.
+    pub(crate) fn at_snapshot(&self, checkpoint: u64) -> Result<Arc<rocksdb::Snapshot<'_>>, Error> {
         self.0.read().expect("poisoned").with(|f| {
             let Some((snapshot, _)) = f.snapshots.get(&checkpoint) else {
                 return Err(Error::NotInRange { checkpoint });
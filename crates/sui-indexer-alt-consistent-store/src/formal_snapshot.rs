@@ -0,0 +1,481 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bootstrapping a consistent store from a formal snapshot, rather than indexing from genesis.
+//!
+//! Formal snapshots contain the live object set as of the end of an epoch. Each object in the
+//! snapshot is fed through the same batching and commit logic that the indexer's pipelines use,
+//! and each pipeline's watermark is set to the last checkpoint in the epoch, so that when the
+//! service is started on the resulting database, it tails checkpoints from the next epoch onward.
+
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+use sui_core::authority::authority_store_tables::LiveObject;
+use sui_data_ingestion_core::{create_remote_store_client, end_of_epoch_data, CheckpointReader};
+use sui_indexer_alt_framework::{
+    pipeline::{sequential::Handler, Processor},
+    store::Store as _,
+    task::TrySpawnStreamExt,
+    types::{base_types::VersionDigest, object::Object},
+};
+use sui_snapshot::{
+    reader::{download_bytes, LiveObjectIter, StateSnapshotReaderV1},
+    FileMetadata,
+};
+use tokio::fs;
+use tracing::{debug, info};
+use url::Url;
+
+use crate::{
+    db::{config::DbConfig, Watermark},
+    handlers::{
+        balances::{Balances, Delta},
+        dynamic_fields::{self, DynamicFields},
+        object_by_owner::{self, ObjectByOwner},
+        object_by_type::{self, ObjectByType},
+    },
+    schema::{self, Schema},
+    store::Store,
+};
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct FormalSnapshotArgs {
+    /// Restore the live object set as of the end of this epoch.
+    #[arg(long)]
+    pub epoch: u64,
+
+    /// URL of the endpoint to fetch formal snapshot files from, for example
+    /// <https://formal-snapshot.mainnet.sui.io>, or a `file://` URL for a snapshot on the local
+    /// filesystem.
+    #[arg(long)]
+    pub endpoint: String,
+
+    /// URL of the checkpoint archive, used to find the last checkpoint in each epoch, and its
+    /// contents.
+    #[arg(long)]
+    pub archive_url: String,
+
+    /// Local directory to temporarily store formal snapshot files in.
+    #[arg(long)]
+    pub snapshot_local_dir: PathBuf,
+
+    /// Number of snapshot files to download and restore concurrently.
+    #[arg(long, default_value_t = 50)]
+    pub concurrency: usize,
+}
+
+/// Seed the database at `database_path` from a formal snapshot, configured by `args`. The
+/// database must not already exist.
+///
+/// The database is built in a temporary sibling directory, and moved into place once it has been
+/// fully seeded, so that an interrupted bootstrap does not leave a partial database behind.
+pub async fn bootstrap(
+    database_path: impl AsRef<Path>,
+    args: FormalSnapshotArgs,
+) -> anyhow::Result<()> {
+    let database_path = database_path.as_ref();
+    if fs::try_exists(database_path).await? {
+        bail!(
+            "Cannot bootstrap from a formal snapshot, database already exists at {}",
+            database_path.display(),
+        );
+    }
+
+    let checkpoints = end_of_epoch_data(args.archive_url.clone(), vec![], 5)
+        .await
+        .context("Failed to fetch end of epoch checkpoints")?;
+
+    let Some(&checkpoint) = checkpoints.get(args.epoch as usize) else {
+        bail!("Epoch {} has not finished yet", args.epoch);
+    };
+
+    info!(
+        epoch = args.epoch,
+        checkpoint, "Bootstrapping from formal snapshot"
+    );
+
+    // The watermark reflects the last checkpoint in the epoch, so that the service reports the
+    // right transaction and timestamp bounds before it indexes its first checkpoint.
+    let archive = create_remote_store_client(args.archive_url.clone(), vec![], 60)
+        .context("Failed to connect to checkpoint archive")?;
+    let (data, _) = CheckpointReader::fetch_from_object_store(archive.as_ref(), checkpoint)
+        .await
+        .with_context(|| format!("Failed to fetch end of epoch checkpoint {checkpoint}"))?;
+
+    let summary = &data.checkpoint_summary;
+    let watermark = Watermark {
+        epoch_hi_inclusive: args.epoch,
+        checkpoint_hi_inclusive: checkpoint,
+        tx_hi: summary.network_total_transactions,
+        timestamp_ms_hi_inclusive: summary.timestamp_ms,
+    };
+
+    let parent = match database_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    fs::create_dir_all(parent)
+        .await
+        .context("Failed to create database's parent directory")?;
+
+    let staging = tempfile::tempdir_in(parent).context("Failed to create staging directory")?;
+    let partial = staging.path().join("db");
+
+    {
+        let store: Store<Schema> =
+            Store::open(&partial, DbConfig::default(), 1).context("Failed to create store")?;
+
+        restore_objects(&store, &args, watermark).await?;
+
+        // Make sure every pipeline has a watermark, even if the snapshot contained no data for
+        // it.
+        for pipeline in [
+            Balances::NAME,
+            DynamicFields::NAME,
+            ObjectByOwner::NAME,
+            ObjectByType::NAME,
+        ] {
+            store
+                .db()
+                .write(pipeline, watermark, rocksdb::WriteBatch::default())
+                .with_context(|| format!("Failed to write {pipeline} watermark"))?;
+        }
+    }
+
+    fs::rename(&partial, database_path)
+        .await
+        .context("Failed to move bootstrapped database into place")?;
+
+    info!(
+        epoch = args.epoch,
+        checkpoint,
+        path = %database_path.display(),
+        "Finished bootstrapping from formal snapshot"
+    );
+
+    Ok(())
+}
+
+/// Download every object file in the formal snapshot, and write the live objects it contains to
+/// `store`.
+async fn restore_objects(
+    store: &Store<Schema>,
+    args: &FormalSnapshotArgs,
+    watermark: Watermark,
+) -> anyhow::Result<()> {
+    let remote_store_config = match Url::parse(&args.endpoint) {
+        Ok(url) if url.scheme() == "file" => ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(
+                url.to_file_path()
+                    .map_err(|_| anyhow::anyhow!("Invalid file URL: {url}"))?,
+            ),
+            ..Default::default()
+        },
+
+        _ => ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::S3),
+            aws_endpoint: Some(args.endpoint.clone()),
+            aws_virtual_hosted_style_request: true,
+            object_store_connection_limit: args.concurrency,
+            no_sign_request: true,
+            ..Default::default()
+        },
+    };
+
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(args.snapshot_local_dir.join("snapshot")),
+        ..Default::default()
+    };
+
+    let concurrency =
+        NonZeroUsize::new(args.concurrency).context("Concurrency must be non-zero")?;
+    let mut reader = StateSnapshotReaderV1::new(
+        args.epoch,
+        &remote_store_config,
+        &local_store_config,
+        concurrency,
+        MultiProgress::new(),
+        /* skip_reset_local_store */ true,
+    )
+    .await
+    .context("Failed to create formal snapshot reader")?;
+
+    let (sha3_digests, num_part_files) = reader.compute_checksum().await?;
+    let (input_files, epoch_dir, remote_object_store, _) = reader.export_metadata().await?;
+    let input_files: Vec<(u32, (u32, FileMetadata))> = input_files
+        .into_iter()
+        .map(|(bucket, (part, metadata))| (*bucket, (part, metadata)))
+        .collect();
+
+    let progress = reader.get_multi_progress().add(
+        ProgressBar::new(num_part_files as u64).with_style(
+            ProgressStyle::with_template(
+                "[{elapsed_precise}] {wide_bar} {pos} out of {len} object files restored ({msg})",
+            )
+            .unwrap(),
+        ),
+    );
+
+    futures::stream::iter(input_files)
+        .try_for_each_spawned(args.concurrency, |(bucket, (part, metadata))| {
+            let store = store.clone();
+            let epoch_dir = epoch_dir.clone();
+            let remote_object_store = remote_object_store.clone();
+            let sha3_digests = sha3_digests.clone();
+            let progress = progress.clone();
+
+            async move {
+                debug!(bucket, part, "Downloading object file");
+                let (bytes, _) = download_bytes(
+                    remote_object_store,
+                    &metadata,
+                    epoch_dir,
+                    sha3_digests,
+                    &&bucket,
+                    &part,
+                    Some(512), // max_timeout_secs
+                )
+                .await;
+
+                // Wrapped objects are not accessible by ID, so they are not indexed.
+                let objects: Vec<Object> = LiveObjectIter::new(&metadata, bytes)?
+                    .filter_map(|object| match object {
+                        LiveObject::Normal(obj) => Some(obj),
+                        LiveObject::Wrapped(_) => None,
+                    })
+                    .collect();
+
+                debug!(bucket, part, objects = objects.len(), "Restoring objects");
+                restore_file(&store, &objects, watermark)
+                    .await
+                    .with_context(|| format!("Failed to restore bucket {bucket}, part {part}"))?;
+
+                progress.inc(1);
+                progress.set_message(format!("Bucket: {bucket}, Part: {part}"));
+                Ok::<_, anyhow::Error>(())
+            }
+        })
+        .await
+}
+
+/// Write the index entries for `objects` for every pipeline.
+async fn restore_file(
+    store: &Store<Schema>,
+    objects: &[Object],
+    watermark: Watermark,
+) -> anyhow::Result<()> {
+    let balances = objects
+        .iter()
+        .filter_map(|obj| Delta::from_object(obj).transpose())
+        .collect::<anyhow::Result<_>>()?;
+
+    let dynamic_fields = puts(
+        objects,
+        schema::dynamic_fields::Key::from_object,
+        dynamic_fields::Value::Put,
+    );
+
+    let object_by_owner = puts(
+        objects,
+        schema::object_by_owner::Key::from_object,
+        object_by_owner::Value::Put,
+    );
+
+    let object_by_type = puts(
+        objects,
+        schema::object_by_type::Key::from_object,
+        object_by_type::Value::Put,
+    );
+
+    write::<Balances>(store, balances, watermark).await?;
+    write::<DynamicFields>(store, dynamic_fields, watermark).await?;
+    write::<ObjectByOwner>(store, object_by_owner, watermark).await?;
+    write::<ObjectByType>(store, object_by_type, watermark).await?;
+    Ok(())
+}
+
+/// Values for an index that maps the `key` of each object in `objects` that has one, to its
+/// version and digest, constructed with `put`.
+fn puts<K, V>(
+    objects: &[Object],
+    key: impl Fn(&Object) -> Option<K>,
+    put: impl Fn(K, VersionDigest) -> V,
+) -> Vec<V> {
+    objects
+        .iter()
+        .filter_map(|obj| Some(put(key(obj)?, (obj.version(), obj.digest()))))
+        .collect()
+}
+
+/// Batch and commit `values` using pipeline `H`'s logic, and write them to the database along with
+/// `watermark`.
+async fn write<H>(
+    store: &Store<Schema>,
+    values: Vec<H::Value>,
+    watermark: Watermark,
+) -> anyhow::Result<()>
+where
+    H: Handler<Store = Store<Schema>>,
+{
+    let mut batch = H::Batch::default();
+    H::batch(&mut batch, values);
+
+    let mut conn = store.connect().await?;
+    H::commit(&batch, &mut conn).await?;
+
+    let batch = std::mem::take(&mut conn.batch);
+    store
+        .db()
+        .write(H::NAME, watermark, batch)
+        .with_context(|| format!("Failed to write {}", H::NAME))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+    use sui_indexer_alt_framework::types::{
+        base_types::{ObjectID, SuiAddress},
+        digests::{ChainIdentifier, Digest},
+        gas_coin::GAS,
+        messages_checkpoint::ECMHLiveObjectSetDigest,
+        test_checkpoint_data_builder::TestCheckpointDataBuilder,
+    };
+    use sui_snapshot::{writer::StateSnapshotWriterV1, FileCompression};
+    use sui_storage::blob::{Blob, BlobEncoding};
+    use sui_swarm_config::network_config_builder::ConfigBuilder;
+
+    use super::*;
+
+    fn file_store(path: PathBuf) -> ObjectStoreConfig {
+        ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(path),
+            ..Default::default()
+        }
+    }
+
+    /// Write a formal snapshot for epoch 0 containing the genesis objects and `objects` to
+    /// `snapshot`, and an archive to `archive` in which epoch 0 ends at `checkpoint`, with the given
+    /// transaction count and timestamp.
+    async fn write_snapshot(
+        d: &Path,
+        snapshot: &Path,
+        archive: &Path,
+        objects: Vec<Object>,
+        checkpoint: u64,
+        network_total_transactions: u64,
+        timestamp_ms: u64,
+    ) {
+        let genesis = ConfigBuilder::new_with_temp_dir().build().genesis;
+        let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&d.join("perpetual"), None));
+        for object in genesis.objects().iter().cloned().chain(objects) {
+            perpetual_db.insert_object_test_only(object).unwrap();
+        }
+
+        // Bootstrapping does not check the snapshot's root state hash.
+        let root_state_hash = ECMHLiveObjectSetDigest {
+            digest: Digest::new([0; 32]),
+        };
+
+        StateSnapshotWriterV1::new(
+            &file_store(d.join("staging")),
+            &file_store(snapshot.to_owned()),
+            FileCompression::Zstd,
+            NonZeroUsize::new(1).unwrap(),
+        )
+        .await
+        .unwrap()
+        .write(
+            0,
+            perpetual_db,
+            root_state_hash,
+            ChainIdentifier::from(*genesis.checkpoint().digest()),
+        )
+        .await
+        .unwrap();
+
+        let data = TestCheckpointDataBuilder::new(checkpoint)
+            .with_epoch(0)
+            .with_network_total_transactions(network_total_transactions)
+            .with_timestamp_ms(timestamp_ms)
+            .build_checkpoint();
+
+        std::fs::create_dir_all(archive).unwrap();
+        std::fs::write(archive.join("epochs.json"), format!("[{checkpoint}]")).unwrap();
+        std::fs::write(
+            archive.join(format!("{checkpoint}.chk")),
+            Blob::encode(&data, BlobEncoding::Bcs).unwrap().to_bytes(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_local_snapshot() {
+        let d = tempfile::tempdir().unwrap();
+        let snapshot = d.path().join("snapshot");
+        let archive = d.path().join("archive");
+        let db_path = d.path().join("db");
+
+        let owner = SuiAddress::random_for_testing_only();
+        let coin = Object::with_id_owner_gas_for_testing(ObjectID::random(), owner, 42);
+        write_snapshot(d.path(), &snapshot, &archive, vec![coin], 5, 100, 1234).await;
+
+        let args = FormalSnapshotArgs {
+            epoch: 0,
+            endpoint: Url::from_directory_path(&snapshot).unwrap().to_string(),
+            archive_url: Url::from_directory_path(&archive).unwrap().to_string(),
+            snapshot_local_dir: d.path().join("local"),
+            concurrency: 1,
+        };
+
+        bootstrap(&db_path, args.clone()).await.unwrap();
+
+        // Bootstrapping again fails, rather than overwriting the database.
+        assert!(bootstrap(&db_path, args).await.is_err());
+
+        let store: Store<Schema> = Store::open(&db_path, DbConfig::default(), 1).unwrap();
+        let expect = Watermark {
+            epoch_hi_inclusive: 0,
+            checkpoint_hi_inclusive: 5,
+            tx_hi: 100,
+            timestamp_ms_hi_inclusive: 1234,
+        };
+
+        for pipeline in [
+            Balances::NAME,
+            DynamicFields::NAME,
+            ObjectByOwner::NAME,
+            ObjectByType::NAME,
+        ] {
+            assert_eq!(store.db().watermark(pipeline).unwrap(), Some(expect));
+        }
+
+        store.db().take_snapshot(expect);
+        let balance = store
+            .schema()
+            .balances
+            .get(
+                5,
+                schema::balances::Key {
+                    owner,
+                    type_: GAS::type_tag(),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(balance, Some(42));
+    }
+}
@@ -34,6 +34,27 @@ pub struct Delta {
 }
 
 impl Delta {
+    /// The contribution `obj` makes to its owner's balance, if it is a coin owned by an address.
+    pub(crate) fn from_object(obj: &Object) -> anyhow::Result<Option<Self>> {
+        // Balances are only tracked for address owners. Balances are combined for coins that
+        // are address-owned and consensus address-owned for the same address.
+        let &owner = match obj.owner() {
+            Owner::AddressOwner(owner) | Owner::ConsensusAddressOwner { owner, .. } => owner,
+            Owner::ObjectOwner(_) | Owner::Shared { .. } | Owner::Immutable => return Ok(None),
+        };
+
+        // Only track coins.
+        let Some((type_, balance)) = Coin::extract_balance_if_coin(obj)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Delta {
+            owner,
+            type_,
+            delta: balance as i128,
+        }))
+    }
+
     fn negated(self) -> Self {
         Self {
             owner: self.owner,
@@ -50,34 +71,14 @@ impl Processor for Balances {
     fn process(&self, checkpoint: &Arc<CheckpointData>) -> anyhow::Result<Vec<Delta>> {
         let mut deltas = vec![];
 
-        fn delta(obj: &Object) -> anyhow::Result<Option<Delta>> {
-            // Balances are only tracked for address owners. Balances are combined for coins that
-            // are address-owned and consensus address-owned for the same address.
-            let &owner = match obj.owner() {
-                Owner::AddressOwner(owner) | Owner::ConsensusAddressOwner { owner, .. } => owner,
-                Owner::ObjectOwner(_) | Owner::Shared { .. } | Owner::Immutable => return Ok(None),
-            };
-
-            // Only track coins.
-            let Some((type_, balance)) = Coin::extract_balance_if_coin(obj)? else {
-                return Ok(None);
-            };
-
-            Ok(Some(Delta {
-                owner,
-                type_,
-                delta: balance as i128,
-            }))
-        }
-
         for (_, (i, _)) in checkpoint_input_objects(checkpoint)? {
-            if let Some(d) = delta(i)? {
+            if let Some(d) = Delta::from_object(i)? {
                 deltas.push(d.negated())
             };
         }
 
         for (_, (o, _)) in checkpoint_output_objects(checkpoint)? {
-            if let Some(d) = delta(o)? {
+            if let Some(d) = Delta::from_object(o)? {
                 deltas.push(d)
             };
         }
@@ -10,6 +10,7 @@ use sui_indexer_alt_framework::types::{
 };
 
 pub(crate) mod balances;
+pub(crate) mod dynamic_fields;
 pub(crate) mod object_by_owner;
 pub(crate) mod object_by_type;
 
@@ -29,11 +29,17 @@
 //! The indexer and RPC agree on a `Schema` which describes the key types, value types and options
 //! for all column families to be set-up in the database.
 
-use std::{path::Path, sync::Arc};
+use std::{path::Path, sync::Arc, time::Duration};
 
+use admin::AdminArgs;
+use anyhow::ensure;
+use backup::Backups;
 use config::{PipelineLayer, ServiceConfig};
-use db::config::DbConfig;
-use handlers::{balances::Balances, object_by_owner::ObjectByOwner, object_by_type::ObjectByType};
+use db::{config::DbConfig, Db};
+use handlers::{
+    balances::Balances, dynamic_fields::DynamicFields, object_by_owner::ObjectByOwner,
+    object_by_type::ObjectByType,
+};
 use indexer::Indexer;
 use prometheus::Registry;
 use rpc::{state::State, RpcArgs, RpcService};
@@ -43,14 +49,17 @@ use sui_indexer_alt_consistent_api::proto::{
 };
 use sui_indexer_alt_framework::{
     ingestion::ClientArgs, pipeline::sequential::SequentialConfig, pipeline::CommitterConfig,
-    IndexerArgs,
+    pipeline::Processor, IndexerArgs,
 };
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 
+mod admin;
 pub mod args;
+pub mod backup;
 pub mod config;
 mod db;
+pub mod formal_snapshot;
 mod handlers;
 mod indexer;
 mod rpc;
@@ -63,7 +72,7 @@ mod store;
 ///
 /// `path` is the path to the RocksDB database,which will be created if it does not exist.
 /// `indexer_args` and `client_args` control the behavior of the Indexer, while `rpc_args` controls
-/// the behavior of the RPC service.
+/// the behavior of the RPC service, and `admin_args` controls the behavior of the admin service.
 ///
 /// The service spins up auxiliary services (to expose metrics, run the indexer, and the RPC), and
 /// will clean these up on shutdown as well.
@@ -72,6 +81,7 @@ pub async fn start_service(
     indexer_args: IndexerArgs,
     client_args: ClientArgs,
     rpc_args: RpcArgs,
+    admin_args: AdminArgs,
     version: &'static str,
     config: ServiceConfig,
     registry: &Registry,
@@ -85,10 +95,12 @@ pub async fn start_service(
         pipeline:
             PipelineLayer {
                 balances,
+                dynamic_fields,
                 object_by_owner,
                 object_by_type,
             },
         rpc,
+        backup: backup_config,
     } = config;
 
     let committer = committer.finish(CommitterConfig::default());
@@ -132,14 +144,77 @@ pub async fn start_service(
         };
     }
 
+    check_new_pipelines(
+        indexer.store().db(),
+        &[
+            balances.as_ref().map(|_| Balances::NAME),
+            dynamic_fields.as_ref().map(|_| DynamicFields::NAME),
+            object_by_owner.as_ref().map(|_| ObjectByOwner::NAME),
+            object_by_type.as_ref().map(|_| ObjectByType::NAME),
+        ],
+    )?;
+
     add_sequential!(Balances, balances);
+    add_sequential!(DynamicFields, dynamic_fields);
     add_sequential!(ObjectByOwner, object_by_owner);
     add_sequential!(ObjectByType, object_by_type);
 
+    let backups = Backups::new(
+        indexer.store().db().clone(),
+        <Schema as store::Schema>::cfs()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect(),
+        &backup_config,
+    );
+
+    let h_backup = backups.clone().map(|b| {
+        b.start(
+            Duration::from_millis(backup_config.interval_ms),
+            cancel.child_token(),
+        )
+    });
+
+    let h_admin = admin::start_admin(admin_args, backups, cancel.child_token()).await?;
     let h_rpc = rpc.run().await?;
     let h_indexer = indexer.run().await?;
 
     Ok(tokio::spawn(async move {
         let (_, _) = futures::join!(h_rpc, h_indexer);
+        if let Some(h_backup) = h_backup {
+            let _ = h_backup.await;
+        }
+
+        if let Some(h_admin) = h_admin {
+            let _ = h_admin.await;
+        }
     }))
 }
+
+/// Check that no pipelines are being added to a database that other pipelines have already written
+/// to. A new pipeline starts from the first checkpoint, and the synchronizer holds every other
+/// pipeline back at the next snapshot until it has caught up, which could take as long as
+/// re-indexing the whole chain. Instead, a new database needs to be created with all the pipelines
+/// enabled, e.g. by bootstrapping it from a formal snapshot.
+///
+/// `pipelines` contains the names of the enabled pipelines (`None` for disabled pipelines).
+fn check_new_pipelines(db: &Db, pipelines: &[Option<&'static str>]) -> anyhow::Result<()> {
+    let mut existing = vec![];
+    let mut new = vec![];
+    for pipeline in pipelines.iter().flatten() {
+        if db.watermark(pipeline)?.is_some() {
+            existing.push(*pipeline);
+        } else {
+            new.push(*pipeline);
+        }
+    }
+
+    ensure!(
+        existing.is_empty() || new.is_empty(),
+        "Pipelines {new:?} have not written to this database, but {existing:?} have. New pipelines \
+         would stall the others until they index the chain from the start: bootstrap a new \
+         database with all pipelines enabled instead.",
+    );
+
+    Ok(())
+}
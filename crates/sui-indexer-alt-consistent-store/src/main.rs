@@ -1,12 +1,14 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Context;
+use anyhow::{ensure, Context};
 use clap::Parser;
 use prometheus::Registry;
 use sui_indexer_alt_consistent_store::{
     args::{Args, Command},
+    backup::restore,
     config::ServiceConfig,
+    formal_snapshot::bootstrap,
     start_service,
 };
 use sui_indexer_alt_metrics::{uptime, MetricsService};
@@ -42,8 +44,10 @@ async fn main() -> anyhow::Result<()> {
             indexer_args,
             client_args,
             rpc_args,
+            admin_args,
             metrics_args,
             config,
+            restore_from,
         } => {
             let config = if let Some(path) = config {
                 let contents = fs::read_to_string(path)
@@ -55,6 +59,12 @@ async fn main() -> anyhow::Result<()> {
                 ServiceConfig::default()
             };
 
+            if let Some(source) = restore_from {
+                restore(&database_path, &source)
+                    .await
+                    .context("Failed to restore database from backup")?;
+            }
+
             let cancel = CancellationToken::new();
             let registry = Registry::new_custom(Some("consistent_store".into()), None)
                 .context("Failed to create Prometheus registry")?;
@@ -84,6 +94,7 @@ async fn main() -> anyhow::Result<()> {
                 indexer_args,
                 client_args,
                 rpc_args,
+                admin_args,
                 VERSION,
                 config,
                 metrics.registry(),
@@ -99,6 +110,39 @@ async fn main() -> anyhow::Result<()> {
             let _ = h_ctrl_c.await;
         }
 
+        Command::Bootstrap {
+            database_path,
+            snapshot_args,
+        } => {
+            bootstrap(database_path, snapshot_args)
+                .await
+                .context("Failed to bootstrap database from formal snapshot")?;
+        }
+
+        Command::Backup {
+            admin_url,
+            checkpoint,
+        } => {
+            let url = admin_url
+                .join(&format!("backup/{checkpoint}"))
+                .context("Failed to build admin URL")?;
+
+            let response = reqwest::Client::new()
+                .post(url)
+                .send()
+                .await
+                .context("Failed to send backup request")?;
+
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .context("Failed to read backup response")?;
+
+            ensure!(status.is_success(), "Backup failed ({status}): {body}");
+            println!("{body}");
+        }
+
         Command::GenerateConfig => {
             let config = ServiceConfig::example();
             let config_toml = toml::to_string_pretty(&config)
@@ -0,0 +1,69 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use bincode::serde::BorrowCompat;
+use sui_indexer_alt_consistent_api::proto::rpc::consistent::v1alpha as grpc;
+use sui_indexer_alt_framework::types::base_types::ObjectID;
+
+use crate::rpc::{
+    error::{RpcError, StatusCode},
+    pagination::Page,
+};
+
+use super::{list_owned_objects::addr, State};
+
+#[derive(thiserror::Error, Debug)]
+pub(super) enum Error {
+    #[error("Invalid 'parent': {0:?}")]
+    InvalidParent(String),
+
+    #[error("Missing 'parent'")]
+    MissingParent,
+}
+
+impl StatusCode for Error {
+    fn code(&self) -> tonic::Code {
+        match self {
+            Error::InvalidParent(_) | Error::MissingParent => tonic::Code::InvalidArgument,
+        }
+    }
+}
+
+pub(super) fn list_dynamic_fields(
+    state: &State,
+    checkpoint: u64,
+    request: grpc::ListDynamicFieldsRequest,
+) -> Result<grpc::ListObjectsResponse, RpcError<Error>> {
+    let parent: ObjectID = match request.parent() {
+        "" => return Err(Error::MissingParent.into()),
+        parent => addr(parent)
+            .map_err(|_| Error::InvalidParent(parent.to_owned()))?
+            .into(),
+    };
+
+    let page = Page::from_request(
+        &state.rpc_config.pagination,
+        request.after_token(),
+        request.before_token(),
+        request.page_size(),
+        request.end(),
+    );
+
+    let index = &state.store.schema().dynamic_fields;
+    let resp = page.paginate_prefix(index, checkpoint, &BorrowCompat(parent))?;
+
+    Ok(grpc::ListObjectsResponse {
+        has_previous_page: Some(resp.has_prev),
+        has_next_page: Some(resp.has_next),
+        objects: resp
+            .results
+            .into_iter()
+            .map(|(token, key, (version, digest))| grpc::Object {
+                object_id: Some(key.field_id.to_canonical_string(/* with_prefix */ true)),
+                version: Some(version.value()),
+                digest: Some(digest.base58_encode()),
+                page_token: Some(token.into()),
+            })
+            .collect(),
+    })
+}
@@ -122,7 +122,7 @@ pub(super) fn list_owned_objects(
 ///
 /// TODO: Switch to using `sui_sdk_types::Address`, once the indexing framework is ported to the
 /// new SDK.
-fn addr(input: &str) -> Result<SuiAddress, Error> {
+pub(super) fn addr(input: &str) -> Result<SuiAddress, Error> {
     let Some(s) = input.strip_prefix("0x") else {
         return Err(Error::InvalidAddress(input.to_owned()));
     };
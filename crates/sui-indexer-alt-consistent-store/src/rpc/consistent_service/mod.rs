@@ -5,20 +5,22 @@ use sui_indexer_alt_consistent_api::proto::rpc::consistent::v1alpha::consistent_
 use sui_indexer_alt_consistent_api::proto::rpc::consistent::v1alpha::{
     AvailableRangeRequest, AvailableRangeResponse, Balance, BatchGetBalancesRequest,
     BatchGetBalancesResponse, GetBalanceRequest, ListBalancesRequest, ListBalancesResponse,
-    ListObjectsByTypeRequest, ListObjectsResponse, ListOwnedObjectsRequest, ServiceConfigRequest,
-    ServiceConfigResponse,
+    ListDynamicFieldsRequest, ListObjectsByTypeRequest, ListObjectsResponse,
+    ListOwnedObjectsRequest, ServiceConfigRequest, ServiceConfigResponse,
 };
 
 use super::state::{checkpointed_response, State};
 
 use self::available_range::available_range;
 use self::balances::{batch_get_balances, get_balance, list_balances};
+use self::list_dynamic_fields::list_dynamic_fields;
 use self::list_objects_by_type::list_objects_by_type;
 use self::list_owned_objects::list_owned_objects;
 use self::service_config::service_config;
 
 mod available_range;
 mod balances;
+mod list_dynamic_fields;
 mod list_objects_by_type;
 mod list_owned_objects;
 mod service_config;
@@ -61,6 +63,15 @@ impl ConsistentService for State {
         Ok(checkpointed_response(checkpoint, response)?)
     }
 
+    async fn list_dynamic_fields(
+        &self,
+        request: tonic::Request<ListDynamicFieldsRequest>,
+    ) -> Result<tonic::Response<ListObjectsResponse>, tonic::Status> {
+        let checkpoint = self.checkpoint(&request)?;
+        let response = list_dynamic_fields(self, checkpoint, request.into_inner())?;
+        Ok(checkpointed_response(checkpoint, response)?)
+    }
+
     async fn list_objects_by_type(
         &self,
         request: tonic::Request<ListObjectsByTypeRequest>,
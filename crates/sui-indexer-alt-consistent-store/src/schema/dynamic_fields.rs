@@ -0,0 +1,43 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use bincode::{Decode, Encode};
+use sui_indexer_alt_framework::types::{
+    base_types::ObjectID,
+    object::{Object, Owner},
+};
+
+/// Key for the index that supports fetching the dynamic fields of a parent object.
+#[derive(Encode, Decode, PartialEq, Eq)]
+pub(crate) struct Key {
+    /// The ID of the object that owns the dynamic field.
+    #[bincode(with_serde)]
+    pub(crate) parent: ObjectID,
+
+    /// The ID of the dynamic field's `Field` object.
+    #[bincode(with_serde)]
+    pub(crate) field_id: ObjectID,
+}
+
+impl Key {
+    /// Only `0x2::dynamic_field::Field` objects that are owned by another object are indexed.
+    pub(crate) fn from_object(obj: &Object) -> Option<Key> {
+        if !obj.type_()?.is_dynamic_field() {
+            return None;
+        }
+
+        let Owner::ObjectOwner(parent) = obj.owner() else {
+            return None;
+        };
+
+        Some(Key {
+            parent: (*parent).into(),
+            field_id: obj.id(),
+        })
+    }
+}
+
+/// Options for creating this index's column family in RocksDB.
+pub(crate) fn options() -> rocksdb::Options {
+    rocksdb::Options::default()
+}
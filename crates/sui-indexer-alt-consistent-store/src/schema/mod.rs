@@ -11,6 +11,7 @@ use crate::{
 };
 
 pub(crate) mod balances;
+pub(crate) mod dynamic_fields;
 pub(crate) mod object_by_owner;
 pub(crate) mod object_by_type;
 
@@ -19,6 +20,9 @@ pub(crate) struct Schema {
     /// The balances of all coin-like objects owned by an account, indexed by owner and type.
     pub(crate) balances: DbMap<balances::Key, i128>,
 
+    /// Fetch the dynamic fields of a parent object.
+    pub(crate) dynamic_fields: DbMap<dynamic_fields::Key, VersionDigest>,
+
     /// Fetch objects by their owner, optionally filtered by type. Coin-like objects are returned
     /// in descending balance order.
     pub(crate) object_by_owner: DbMap<object_by_owner::Key, VersionDigest>,
@@ -31,6 +35,7 @@ impl store::Schema for Schema {
     fn cfs() -> Vec<(&'static str, rocksdb::Options)> {
         vec![
             ("balances", balances::options()),
+            ("dynamic_fields", dynamic_fields::options()),
             ("object_by_owner", object_by_owner::options()),
             ("object_by_type", object_by_type::options()),
         ]
@@ -39,6 +44,7 @@ impl store::Schema for Schema {
     fn open(db: &Arc<Db>) -> anyhow::Result<Self> {
         Ok(Self {
             balances: DbMap::new(db.clone(), "balances"),
+            dynamic_fields: DbMap::new(db.clone(), "dynamic_fields"),
             object_by_owner: DbMap::new(db.clone(), "object_by_owner"),
             object_by_type: DbMap::new(db.clone(), "object_by_type"),
         })
@@ -19,8 +19,9 @@ use sui_indexer_alt_consistent_api::proto::rpc::consistent::v1alpha::{
     consistent_service_client::ConsistentServiceClient, AvailableRangeRequest,
 };
 use sui_indexer_alt_consistent_store::{
-    args::RpcArgs as ConsistentArgs, args::TlsArgs as ConsistentTlsArgs,
-    config::ServiceConfig as ConsistentConfig, start_service as start_consistent_store,
+    args::AdminArgs as ConsistentAdminArgs, args::RpcArgs as ConsistentArgs,
+    args::TlsArgs as ConsistentTlsArgs, config::ServiceConfig as ConsistentConfig,
+    start_service as start_consistent_store,
 };
 use sui_indexer_alt_framework::{ingestion::ClientArgs, postgres::schema::watermarks, IndexerArgs};
 use sui_indexer_alt_graphql::{
@@ -161,10 +162,7 @@ impl FullCluster {
 
         let client_args = ClientArgs {
             local_ingestion_path: Some(temp_dir.path().to_owned()),
-            remote_store_url: None,
-            rpc_api_url: None,
-            rpc_username: None,
-            rpc_password: None,
+            ..Default::default()
         };
 
         let offchain = OffchainCluster::new(
@@ -388,6 +386,7 @@ impl OffchainCluster {
             consistent_indexer_args,
             client_args,
             consistent_args,
+            ConsistentAdminArgs::default(),
             "0.0.0",
             consistent_config,
             registry,
@@ -396,11 +395,20 @@ impl OffchainCluster {
         .await
         .context("Failed to start Consistent Store")?;
 
+        let consistent_reader_args = ConsistentReaderArgs {
+            consistent_store_url: Some(
+                Url::parse(&format!("http://{consistent_listen_address}")).unwrap(),
+            ),
+            consistent_store_statement_timeout_ms: None,
+        };
+
         let jsonrpc = start_jsonrpc(
             Some(database_url.clone()),
             None,
+            None,
             DbArgs::default(),
             BigtableArgs::default(),
+            consistent_reader_args.clone(),
             jsonrpc_args,
             JsonRpcNodeArgs::default(),
             SystemPackageTaskArgs::default(),
@@ -411,16 +419,10 @@ impl OffchainCluster {
         .await
         .context("Failed to start JSON-RPC server")?;
 
-        let consistent_reader_args = ConsistentReaderArgs {
-            consistent_store_url: Some(
-                Url::parse(&format!("http://{consistent_listen_address}")).unwrap(),
-            ),
-            consistent_store_statement_timeout_ms: None,
-        };
-
         let graphql = start_graphql(
             Some(database_url.clone()),
             None,
+            None,
             FullNodeArgs::default(),
             DbArgs::default(),
             BigtableArgs::default(),
@@ -0,0 +1,262 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use move_core_types::ident_str;
+use sui_indexer_alt_consistent_api::proto::rpc::consistent::v1alpha::{
+    consistent_service_client::ConsistentServiceClient, ListDynamicFieldsRequest,
+};
+use sui_indexer_alt_e2e_tests::{find_address_owned, FullCluster};
+use sui_types::{
+    base_types::{ObjectID, ObjectRef, SuiAddress},
+    crypto::AccountKeyPair,
+    effects::TransactionEffectsAPI,
+    object::Owner,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{ObjectArg, Transaction, TransactionData},
+    TypeTag, SUI_FRAMEWORK_PACKAGE_ID,
+};
+
+/// 5 SUI gas budget
+const DEFAULT_GAS_BUDGET: u64 = 5_000_000_000;
+
+#[tokio::test]
+async fn test_list_dynamic_fields() {
+    let mut cluster = FullCluster::new().await.unwrap();
+
+    let (sender, kp, gas) = cluster
+        .funded_account(DEFAULT_GAS_BUDGET)
+        .expect("Failed to fund account");
+
+    // Two tables, so that fields from one don't show up when listing the other's.
+    let (table, mut fields, gas) = create_table(&mut cluster, sender, &kp, gas, 10);
+    let (other, other_fields, gas) = create_table(&mut cluster, sender, &kp, gas, 3);
+
+    cluster.create_checkpoint().await;
+
+    assert_eq!(
+        list_dynamic_fields(&cluster, &table.0, None, Some(50))
+            .await
+            .unwrap(),
+        (fields.values().map(repr).collect(), None),
+    );
+
+    assert_eq!(
+        list_dynamic_fields(&cluster, &other.0, None, Some(50))
+            .await
+            .unwrap(),
+        (other_fields.values().map(repr).collect(), None),
+    );
+
+    // Paginating returns the same fields as fetching them all at once.
+    let mut after = None;
+    let mut results = vec![];
+    loop {
+        let (page, next) = list_dynamic_fields(&cluster, &table.0, after.clone(), Some(3))
+            .await
+            .unwrap();
+
+        assert!(page.len() <= 3);
+        results.extend(page);
+        after = next;
+        if after.is_none() {
+            break;
+        }
+    }
+
+    assert_eq!(results, fields.values().map(repr).collect::<Vec<_>>());
+
+    // Removing entries from the table removes their fields from the index, once the next
+    // checkpoint is indexed. The table has not been modified since it was created, so its
+    // reference is still current.
+    let removed = remove_from_table(&mut cluster, sender, &kp, gas, table, &[0, 1, 2]);
+    for id in &removed {
+        fields.remove(id);
+    }
+
+    cluster.create_checkpoint().await;
+
+    assert_eq!(
+        list_dynamic_fields(&cluster, &table.0, None, Some(50))
+            .await
+            .unwrap(),
+        (fields.values().map(repr).collect(), None),
+    );
+
+    // Objects without dynamic fields have an empty listing.
+    assert_eq!(
+        list_dynamic_fields(&cluster, &ObjectID::ZERO, None, Some(50))
+            .await
+            .unwrap(),
+        (vec![], None),
+    );
+}
+
+#[tokio::test]
+async fn test_bad_parent() {
+    let mut cluster = FullCluster::new().await.unwrap();
+    cluster.create_checkpoint().await;
+
+    let mut client = ConsistentServiceClient::connect(cluster.consistent_store_url().to_string())
+        .await
+        .expect("Failed to connect to Consistent Store");
+
+    let err = client
+        .list_dynamic_fields(ListDynamicFieldsRequest::default())
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+    let err = client
+        .list_dynamic_fields(ListDynamicFieldsRequest {
+            parent: Some("not an object ID".to_owned()),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::InvalidArgument);
+}
+
+/// Fetch a page of the dynamic fields of `parent`, returning the fields and the token to fetch
+/// the next page with, if there is one.
+async fn list_dynamic_fields(
+    cluster: &FullCluster,
+    parent: &ObjectID,
+    after_token: Option<Vec<u8>>,
+    page_size: Option<u32>,
+) -> Result<(Vec<(String, u64, String)>, Option<Vec<u8>>), tonic::Status> {
+    let mut client = ConsistentServiceClient::connect(cluster.consistent_store_url().to_string())
+        .await
+        .expect("Failed to connect to Consistent Store");
+
+    let request = tonic::Request::new(ListDynamicFieldsRequest {
+        parent: Some(parent.to_canonical_string(/* with_prefix */ true)),
+        page_size,
+        after_token: after_token.map(Into::into),
+        ..Default::default()
+    });
+
+    let response = client.list_dynamic_fields(request).await?.into_inner();
+
+    let after_token = response
+        .has_next_page()
+        .then(|| response.objects.last().map(|o| o.page_token().to_owned()))
+        .flatten();
+
+    let objects = response
+        .objects
+        .into_iter()
+        .map(|o| (o.object_id().to_owned(), o.version(), o.digest().to_owned()))
+        .collect();
+
+    Ok((objects, after_token))
+}
+
+fn repr((i, v, d): &ObjectRef) -> (String, u64, String) {
+    (
+        i.to_canonical_string(/* with_prefix */ true),
+        v.value(),
+        d.base58_encode(),
+    )
+}
+
+/// Create a `Table<u64, u64>` owned by `sender` with `size` entries. Returns a reference to the
+/// table, references to its dynamic fields (keyed by their IDs), and the updated gas object.
+fn create_table(
+    cluster: &mut FullCluster,
+    sender: SuiAddress,
+    kp: &AccountKeyPair,
+    gas: ObjectRef,
+    size: u64,
+) -> (ObjectRef, BTreeMap<ObjectID, ObjectRef>, ObjectRef) {
+    let mut builder = ProgrammableTransactionBuilder::new();
+
+    let table = builder.programmable_move_call(
+        SUI_FRAMEWORK_PACKAGE_ID,
+        ident_str!("table").to_owned(),
+        ident_str!("new").to_owned(),
+        vec![TypeTag::U64, TypeTag::U64],
+        vec![],
+    );
+
+    for i in 0..size {
+        let kv = builder.pure(i).expect("Failed to create pure value");
+        builder.programmable_move_call(
+            SUI_FRAMEWORK_PACKAGE_ID,
+            ident_str!("table").to_owned(),
+            ident_str!("add").to_owned(),
+            vec![TypeTag::U64, TypeTag::U64],
+            vec![table, kv, kv],
+        );
+    }
+
+    builder.transfer_arg(sender, table);
+
+    let data = TransactionData::new_programmable(
+        sender,
+        vec![gas],
+        builder.finish(),
+        DEFAULT_GAS_BUDGET,
+        cluster.reference_gas_price(),
+    );
+
+    let (fx, _) = cluster
+        .execute_transaction(Transaction::from_data_and_signer(data, vec![kp]))
+        .expect("Failed to execute transaction");
+
+    let table = find_address_owned(&fx).expect("Failed to find created table");
+    let fields = fx
+        .created()
+        .into_iter()
+        .filter(
+            |(_, owner)| matches!(owner, Owner::ObjectOwner(parent) if *parent == table.0.into()),
+        )
+        .map(|(obj_ref, _)| (obj_ref.0, obj_ref))
+        .collect();
+
+    (table, fields, fx.gas_object().0)
+}
+
+/// Remove the entries with the given `keys` from `table`, returning the IDs of the dynamic fields
+/// that were deleted.
+fn remove_from_table(
+    cluster: &mut FullCluster,
+    sender: SuiAddress,
+    kp: &AccountKeyPair,
+    gas: ObjectRef,
+    table: ObjectRef,
+    keys: &[u64],
+) -> Vec<ObjectID> {
+    let mut builder = ProgrammableTransactionBuilder::new();
+
+    let table = builder
+        .obj(ObjectArg::ImmOrOwnedObject(table))
+        .expect("Failed to add table input");
+
+    for key in keys {
+        let key = builder.pure(*key).expect("Failed to create pure value");
+        builder.programmable_move_call(
+            SUI_FRAMEWORK_PACKAGE_ID,
+            ident_str!("table").to_owned(),
+            ident_str!("remove").to_owned(),
+            vec![TypeTag::U64, TypeTag::U64],
+            vec![table, key],
+        );
+    }
+
+    let data = TransactionData::new_programmable(
+        sender,
+        vec![gas],
+        builder.finish(),
+        DEFAULT_GAS_BUDGET,
+        cluster.reference_gas_price(),
+    );
+
+    let (fx, _) = cluster
+        .execute_transaction(Transaction::from_data_and_signer(data, vec![kp]))
+        .expect("Failed to execute transaction");
+
+    assert!(fx.status().is_ok(), "remove from table transaction failed");
+    fx.deleted().into_iter().map(|obj_ref| obj_ref.0).collect()
+}
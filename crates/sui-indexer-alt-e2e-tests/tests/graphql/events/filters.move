@@ -0,0 +1,163 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//# init --protocol-version 70 --accounts A B --addresses test=0x0 --simulator
+
+// Checkpoint 1:
+// - A emits TestEvent { value: 42 }
+// - A emits TestEvent { value: 1 }, TestEvent { value: 2 }
+// - A transfers SUI to B (no events)
+//
+// Checkpoint 2:
+// - B emits TestEvent { value: 7 }
+
+//# publish
+module test::events_test {
+    use std::ascii;
+    use sui::event;
+
+    public struct TestEvent has copy, drop {
+        message: ascii::String,
+        value: u64,
+    }
+
+    public entry fun emit_event(value: u64) {
+        event::emit(TestEvent {
+            message: ascii::string(b"Hello from test event"),
+            value,
+        });
+    }
+
+    public entry fun emit_multiple_events() {
+        event::emit(TestEvent {
+            message: ascii::string(b"First event"),
+            value: 1,
+        });
+
+        event::emit(TestEvent {
+            message: ascii::string(b"Second event"),
+            value: 2,
+        });
+    }
+}
+
+//# run test::events_test::emit_event --sender A --args 42
+
+//# run test::events_test::emit_multiple_events --sender A
+
+//# programmable --sender A --inputs 100 @B
+//> 0: SplitCoins(Gas, [Input(0)]);
+//> 1: TransferObjects([Result(0)], Input(1))
+
+//# create-checkpoint
+
+//# run test::events_test::emit_event --sender B --args 7
+
+//# create-checkpoint
+
+//# run-graphql
+{ # Filter by type
+  package: events(filter: { type: "@{test}" }) { ...E }
+  module: events(filter: { type: "@{test}::events_test" }) { ...E }
+  type: events(filter: { type: "@{test}::events_test::TestEvent" }) { ...E }
+  otherType: events(filter: { type: "0x2::coin::Coin" }) { ...E }
+}
+
+fragment E on EventConnection {
+  pageInfo { hasPreviousPage hasNextPage }
+  nodes {
+    sequenceNumber
+    sender { address }
+    contents {
+      type { repr }
+      json
+    }
+  }
+}
+
+//# run-graphql
+{ # Filter by the module that emitted the event
+  package: events(filter: { module: "@{test}" }) { ...E }
+  module: events(filter: { module: "@{test}::events_test" }) { ...E }
+  otherModule: events(filter: { module: "@{test}::other" }) { ...E }
+}
+
+fragment E on EventConnection {
+  pageInfo { hasPreviousPage hasNextPage }
+  nodes {
+    sequenceNumber
+    sender { address }
+    contents { json }
+  }
+}
+
+//# run-graphql
+{ # Filter by sender, alone and combined with other filters
+  a: events(filter: { sender: "@{A}" }) { ...E }
+  b: events(filter: { sender: "@{B}" }) { ...E }
+  bType: events(filter: { sender: "@{B}", type: "@{test}::events_test::TestEvent" }) { ...E }
+  bModule: events(filter: { sender: "@{B}", module: "@{test}::events_test" }) { ...E }
+}
+
+fragment E on EventConnection {
+  pageInfo { hasPreviousPage hasNextPage }
+  nodes {
+    sequenceNumber
+    sender { address }
+    contents { json }
+  }
+}
+
+//# run-graphql
+{ # Filter by checkpoint range
+  atCp1: events(filter: { atCheckpoint: 1, type: "@{test}" }) { ...E }
+  afterCp1: events(filter: { afterCheckpoint: 1, type: "@{test}" }) { ...E }
+  beforeCp2: events(filter: { beforeCheckpoint: 2, module: "@{test}" }) { ...E }
+  betweenCp0AndCp2: events(filter: { afterCheckpoint: 0, beforeCheckpoint: 2, sender: "@{A}" }) { ...E }
+  atCp2WithSenderA: events(filter: { atCheckpoint: 2, sender: "@{A}" }) { ...E }
+}
+
+fragment E on EventConnection {
+  pageInfo { hasPreviousPage hasNextPage }
+  nodes {
+    sequenceNumber
+    sender { address }
+    contents { json }
+  }
+}
+
+//# run-graphql
+{ # Paginate events, including splitting the events from a single transaction across pages
+  first: events(first: 2, filter: { type: "@{test}" }) { ...E }
+  last: events(last: 2, filter: { type: "@{test}" }) { ...E }
+}
+
+fragment E on EventConnection {
+  pageInfo { hasPreviousPage hasNextPage }
+  nodes {
+    sequenceNumber
+    contents { json }
+  }
+}
+
+//# run-graphql --cursors bcs(3,0) bcs(5,0)
+{ # Paginate events between cursors, which are exclusive
+  after: events(after: "@{cursor_0}", filter: { type: "@{test}" }) { ...E }
+  before: events(before: "@{cursor_0}", filter: { type: "@{test}" }) { ...E }
+  between: events(after: "@{cursor_0}", before: "@{cursor_1}", filter: { type: "@{test}" }) { ...E }
+}
+
+fragment E on EventConnection {
+  pageInfo { hasPreviousPage hasNextPage }
+  nodes {
+    sequenceNumber
+    contents { json }
+  }
+}
+
+//# run-graphql
+{ # Filtering by both module and type is not supported
+  events(filter: { module: "@{test}", type: "@{test}" }) {
+    nodes { sequenceNumber }
+  }
+}
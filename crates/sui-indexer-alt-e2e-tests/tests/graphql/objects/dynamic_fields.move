@@ -0,0 +1,198 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//# init --protocol-version 70 --accounts A --addresses P=0x0 --simulator
+
+// 1. Fetch all dynamic fields on an object, and pages of them from either end.
+// 2. Fetch dynamic fields and dynamic object fields by name, and traverse into the value of a
+//    dynamic object field.
+// 3. Fetch dynamic fields as of an earlier checkpoint.
+
+//# publish
+module P::M {
+  use sui::dynamic_field as df;
+  use sui::dynamic_object_field as dof;
+
+  public struct Object has key, store {
+    id: UID,
+  }
+
+  public struct Name has copy, drop, store {
+    x: u64,
+    y: u32,
+  }
+
+  public fun object(ctx: &mut TxContext): Object {
+    Object { id: object::new(ctx) }
+  }
+
+  public fun dfield<K: copy + drop + store, V: store>(o: &mut Object, k: K, v: V) {
+    df::add<K, V>(&mut o.id, k, v);
+  }
+
+  public fun ofield<K: copy + drop + store, V: key + store>(o: &mut Object, k: K, v: V) {
+    dof::add<K, V>(&mut o.id, k, v);
+  }
+
+  public fun dmodify<K: copy + drop + store>(o: &mut Object, k: K) {
+    let v: &mut u64 = df::borrow_mut<K, u64>(&mut o.id, k);
+    *v = *v + 1;
+  }
+
+  public fun dremove<K: copy + drop + store, V: drop + store>(o: &mut Object, k: K) {
+    df::remove<K, V>(&mut o.id, k);
+  }
+
+  public fun name(x: u64, y: u32): Name {
+    Name { x, y }
+  }
+}
+
+//# programmable --sender A --inputs @A
+//> 0: P::M::object();
+//> 1: TransferObjects([Result(0)], Input(0))
+
+//# programmable --sender A --inputs object(2,0) 42 43u32 44
+//> 0: P::M::name(Input(1), Input(2));
+//> 1: P::M::dfield<P::M::Name, u64>(Input(0), Result(0), Input(3))
+
+//# create-checkpoint
+
+//# programmable --sender A --inputs @A
+//> 0: P::M::object();
+//> 1: TransferObjects([Result(0)], Input(0))
+
+//# programmable --sender A --inputs object(2,0) 45 object(5,0)
+//> 0: P::M::ofield<u64, P::M::Object>(Input(0), Input(1), Input(2))
+
+//# create-checkpoint
+
+//# programmable --sender A --inputs object(2,0) 46 47
+//> 0: P::M::dfield<u64, u64>(Input(0), Input(1), Input(2))
+
+//# create-checkpoint
+
+//# programmable --sender A --inputs object(2,0) true 48
+//> 0: P::M::dfield<bool, u64>(Input(0), Input(1), Input(2))
+
+//# create-checkpoint
+
+//# programmable --sender A --inputs object(2,0) false 49
+//> 0: P::M::dfield<bool, u64>(Input(0), Input(1), Input(2))
+
+//# create-checkpoint
+
+//# run-graphql
+{ # All dynamic fields
+  object(address: "@{obj_2_0}") {
+    dynamicFields {
+      pageInfo { hasPreviousPage hasNextPage }
+      nodes { ...DF }
+    }
+  }
+}
+
+fragment DF on DynamicField {
+  address
+  version
+  name {
+    type { repr }
+    json
+  }
+  value {
+    __typename
+    ... on MoveValue {
+      type { repr }
+      json
+    }
+    ... on MoveObject {
+      address
+      contents {
+        type { repr }
+        json
+      }
+    }
+  }
+}
+
+//# run-graphql
+{ # Pages of dynamic fields from the front and the back
+  front: object(address: "@{obj_2_0}") {
+    dynamicFields(first: 2) {
+      pageInfo { hasPreviousPage hasNextPage }
+      nodes { name { json } }
+    }
+  }
+
+  back: object(address: "@{obj_2_0}") {
+    dynamicFields(last: 2) {
+      pageInfo { hasPreviousPage hasNextPage }
+      nodes { name { json } }
+    }
+  }
+}
+
+//# run-graphql
+{ # Dynamic fields by name
+  object(address: "@{obj_2_0}") {
+    # 46u64
+    u64: dynamicField(name: { type: "u64", bcs: "LgAAAAAAAAA=" }) {
+      address
+      value { ... on MoveValue { json } }
+    }
+
+    # true
+    bool: dynamicField(name: { type: "bool", bcs: "AQ==" }) {
+      address
+      value { ... on MoveValue { json } }
+    }
+
+    # P::M::Name { x: 42, y: 43 }
+    struct: dynamicField(name: { type: "@{P}::M::Name", bcs: "KgAAAAAAAAArAAAA" }) {
+      address
+      value { ... on MoveValue { json } }
+    }
+
+    # 45u64 is a dynamic object field, not a dynamic field
+    missing: dynamicField(name: { type: "u64", bcs: "LQAAAAAAAAA=" }) {
+      address
+    }
+  }
+}
+
+//# run-graphql
+{ # Dynamic object fields by name, traversing into their values
+  object(address: "@{obj_2_0}") {
+    # 46u64 is a dynamic field, not a dynamic object field
+    missing: dynamicObjectField(name: { type: "u64", bcs: "LgAAAAAAAAA=" }) {
+      address
+    }
+
+    # 45u64
+    present: dynamicObjectField(name: { type: "u64", bcs: "LQAAAAAAAAA=" }) {
+      address
+      value {
+        ... on MoveObject {
+          address
+          version
+          contents { type { repr } }
+          dynamicFields { nodes { address } }
+        }
+      }
+    }
+  }
+}
+
+//# run-graphql
+{ # Dynamic fields as of an earlier checkpoint
+  checkpoint(sequenceNumber: 3) {
+    query {
+      object(address: "@{obj_2_0}") {
+        dynamicFields {
+          pageInfo { hasPreviousPage hasNextPage }
+          nodes { name { json } }
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,307 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! These tests check that GraphQL's `simulateTransaction` delegates to a full node, reporting the
+//! effects of transactions that can be simulated (including ones that fail during execution), and
+//! the reason for rejecting transactions that cannot.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use anyhow::Context;
+use move_core_types::ident_str;
+use prometheus::Registry;
+use reqwest::Client;
+use serde_json::{json, Value};
+use sui_indexer_alt_graphql::{config::RpcConfig, start_rpc, RpcArgs};
+use sui_indexer_alt_reader::{
+    bigtable_reader::BigtableArgs, consistent_reader::ConsistentReaderArgs,
+    full_node_client::FullNodeArgs, system_package_task::SystemPackageTaskArgs,
+};
+use sui_macros::sim_test;
+use sui_pg_db::{temp::get_available_port, DbArgs};
+use sui_swarm_config::genesis_config::AccountConfig;
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
+    digests::ObjectDigest,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{Transaction, TransactionData},
+    SUI_FRAMEWORK_PACKAGE_ID,
+};
+use test_cluster::{TestCluster, TestClusterBuilder};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+const SIMULATE: &str = r#"
+    query($txBytes: String!, $checksEnabled: Boolean) {
+        simulateTransaction(txBytes: $txBytes, checksEnabled: $checksEnabled) {
+            effects {
+                digest
+                status
+            }
+            events { sender { address } }
+            error
+        }
+    }
+"#;
+
+struct SimulateTestCluster {
+    onchain_cluster: TestCluster,
+    graphql_url: Url,
+    graphql_handle: JoinHandle<()>,
+    client: Client,
+    cancel: CancellationToken,
+}
+
+impl SimulateTestCluster {
+    /// Creates a new test cluster with a GraphQL service that simulates transactions on its full
+    /// node. The GraphQL service is not backed by a database.
+    async fn new() -> anyhow::Result<Self> {
+        let onchain_cluster = TestClusterBuilder::new()
+            .with_num_validators(1)
+            .with_accounts(vec![
+                AccountConfig {
+                    address: None,
+                    gas_amounts: vec![1_000_000_000_000; 2],
+                };
+                2
+            ])
+            .build()
+            .await;
+
+        let cancel = CancellationToken::new();
+
+        let rpc_listen_address =
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), get_available_port());
+        let graphql_url = Url::parse(&format!("http://{rpc_listen_address}/graphql"))
+            .expect("Failed to parse RPC URL");
+
+        // We don't expose metrics in these tests, but we create a registry to collect them anyway.
+        let registry = Registry::new();
+
+        let graphql_handle = start_rpc(
+            None,
+            None,
+            None,
+            FullNodeArgs {
+                full_node_rpc_url: Some(onchain_cluster.rpc_url().to_owned()),
+            },
+            DbArgs::default(),
+            BigtableArgs::default(),
+            ConsistentReaderArgs::default(),
+            RpcArgs {
+                rpc_listen_address,
+                no_ide: true,
+            },
+            SystemPackageTaskArgs::default(),
+            "0.0.0",
+            RpcConfig::default(),
+            vec![],
+            &registry,
+            cancel.child_token(),
+        )
+        .await
+        .context("Failed to start GraphQL server")?;
+
+        Ok(Self {
+            onchain_cluster,
+            graphql_url,
+            graphql_handle,
+            client: Client::new(),
+            cancel,
+        })
+    }
+
+    /// Base64-encodes the BCS representation of `tx`.
+    async fn tx_bytes(&self, tx: &TransactionData) -> String {
+        let signed = self.onchain_cluster.sign_transaction(tx).await;
+        let (tx_bytes, _) = signed.to_tx_bytes_and_signatures();
+        tx_bytes.encoded()
+    }
+
+    async fn simulate(&self, tx_bytes: &str) -> anyhow::Result<Value> {
+        self.simulate_with_checks(tx_bytes, true).await
+    }
+
+    async fn simulate_with_checks(
+        &self,
+        tx_bytes: &str,
+        checks_enabled: bool,
+    ) -> anyhow::Result<Value> {
+        let query = json!({
+            "query": SIMULATE,
+            "variables": { "txBytes": tx_bytes, "checksEnabled": checks_enabled },
+        });
+
+        let response = self
+            .client
+            .post(self.graphql_url.clone())
+            .json(&query)
+            .send()
+            .await
+            .context("Request to GraphQL server failed")?;
+
+        let body: Value = response
+            .json()
+            .await
+            .context("Failed to parse GraphQL response")?;
+
+        Ok(body)
+    }
+
+    async fn stopped(self) {
+        self.cancel.cancel();
+        let _ = self.graphql_handle.await;
+    }
+}
+
+/// Simulating a transaction reports its effects, without executing it.
+#[sim_test]
+async fn test_simulate_transaction() {
+    let cluster = SimulateTestCluster::new().await.unwrap();
+    let addresses = cluster.onchain_cluster.wallet.get_addresses();
+
+    let tx = cluster
+        .onchain_cluster
+        .test_transaction_builder()
+        .await
+        .transfer_sui(Some(1_000), addresses[1])
+        .build();
+
+    let tx_bytes = cluster.tx_bytes(&tx).await;
+    let body = cluster.simulate(&tx_bytes).await.unwrap();
+
+    assert!(body.get("errors").is_none(), "Unexpected errors: {body}");
+    let result = &body["data"]["simulateTransaction"];
+    assert_eq!(result["effects"]["digest"], tx.digest().to_string());
+    assert_eq!(result["effects"]["status"], "SUCCESS");
+    assert_eq!(result["events"], json!([]));
+    assert_eq!(result["error"], Value::Null);
+
+    // The simulation did not consume the transaction's gas coin, so the same transaction can
+    // still be executed.
+    let response = cluster
+        .onchain_cluster
+        .sign_and_execute_transaction(&tx)
+        .await;
+    assert_eq!(response.digest, tx.digest());
+
+    cluster.stopped().await;
+}
+
+/// A transaction that fails during execution can still be simulated, and its failure is reported
+/// in its effects.
+#[sim_test]
+async fn test_simulate_execution_failure() {
+    let cluster = SimulateTestCluster::new().await.unwrap();
+    let addresses = cluster.onchain_cluster.wallet.get_addresses();
+
+    // Try to transfer more SUI than is available in the gas coin.
+    let tx = cluster
+        .onchain_cluster
+        .test_transaction_builder()
+        .await
+        .transfer_sui(Some(u64::MAX), addresses[1])
+        .build();
+
+    let tx_bytes = cluster.tx_bytes(&tx).await;
+    let body = cluster.simulate(&tx_bytes).await.unwrap();
+
+    assert!(body.get("errors").is_none(), "Unexpected errors: {body}");
+    let result = &body["data"]["simulateTransaction"];
+    assert_eq!(result["effects"]["status"], "FAILURE");
+    assert_eq!(result["error"], Value::Null);
+
+    cluster.stopped().await;
+}
+
+/// A transaction that the full node refuses to simulate has no effects, and reports why it was
+/// rejected.
+#[sim_test]
+async fn test_simulate_rejected() {
+    let cluster = SimulateTestCluster::new().await.unwrap();
+    let addresses = cluster.onchain_cluster.wallet.get_addresses();
+    let rgp = cluster.onchain_cluster.get_reference_gas_price().await;
+
+    // Pay for gas with an object that does not exist.
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+
+    let tx = TestTransactionBuilder::new(addresses[0], gas, rgp)
+        .transfer_sui(Some(1_000), addresses[1])
+        .build();
+
+    let tx_bytes = cluster.tx_bytes(&tx).await;
+    let body = cluster.simulate(&tx_bytes).await.unwrap();
+
+    assert!(body.get("errors").is_none(), "Unexpected errors: {body}");
+    let result = &body["data"]["simulateTransaction"];
+    assert_eq!(result["effects"], Value::Null);
+    assert_eq!(result["events"], Value::Null);
+    assert!(result["error"].is_string(), "Expected an error: {result}");
+
+    cluster.stopped().await;
+}
+
+/// With checks disabled, a transaction can be simulated from any sender, without a gas payment or
+/// signature, like a dev-inspect.
+#[sim_test]
+async fn test_simulate_checks_disabled() {
+    let cluster = SimulateTestCluster::new().await.unwrap();
+    let rgp = cluster.onchain_cluster.get_reference_gas_price().await;
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder
+        .move_call(
+            SUI_FRAMEWORK_PACKAGE_ID,
+            ident_str!("tx_context").to_owned(),
+            ident_str!("epoch").to_owned(),
+            vec![],
+            vec![],
+        )
+        .unwrap();
+
+    let tx = TransactionData::new_programmable(
+        SuiAddress::random_for_testing_only(),
+        vec![],
+        builder.finish(),
+        1_000_000 * rgp,
+        rgp,
+    );
+
+    // The sender is not one of the cluster's accounts, so the transaction can't be signed, but
+    // simulation doesn't need a signature.
+    let (tx_bytes, _) = Transaction::from_data(tx, vec![]).to_tx_bytes_and_signatures();
+    let body = cluster
+        .simulate_with_checks(&tx_bytes.encoded(), false)
+        .await
+        .unwrap();
+
+    assert!(body.get("errors").is_none(), "Unexpected errors: {body}");
+    let result = &body["data"]["simulateTransaction"];
+    assert_eq!(result["effects"]["status"], "SUCCESS");
+    assert_eq!(result["error"], Value::Null);
+
+    cluster.stopped().await;
+}
+
+/// Transaction bytes that can't be decoded are a user error, and are not sent to the full node.
+#[sim_test]
+async fn test_simulate_bad_bytes() {
+    let cluster = SimulateTestCluster::new().await.unwrap();
+
+    let body = cluster.simulate("not base64!").await.unwrap();
+
+    let errors = body["errors"].as_array().expect("Expected errors");
+    assert_eq!(errors.len(), 1, "Unexpected errors: {body}");
+    assert_eq!(
+        errors[0]["extensions"]["code"], "BAD_USER_INPUT",
+        "Unexpected error: {body}"
+    );
+
+    cluster.stopped().await;
+}
@@ -0,0 +1,277 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! These tests check that GraphQL subscriptions stream data over a WebSocket connection, and that
+//! they respect the limits on how far back they can catch up from, and how many can be active on
+//! one connection.
+
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use simulacrum::Simulacrum;
+use sui_indexer_alt::config::IndexerConfig;
+use sui_indexer_alt_consistent_store::config::ServiceConfig as ConsistentConfig;
+use sui_indexer_alt_e2e_tests::FullCluster;
+use sui_indexer_alt_framework::IndexerArgs;
+use sui_indexer_alt_graphql::config::{Limits, RpcConfig as GraphQlConfig};
+use sui_indexer_alt_jsonrpc::config::RpcConfig as JsonRpcConfig;
+use sui_types::{base_types::SuiAddress, effects::TransactionEffectsAPI};
+use tokio::{net::TcpStream, time::timeout};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, http::HeaderValue, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+use tokio_util::sync::CancellationToken;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// A subscription that has caught up to the latest checkpoint streams transactions as they are
+/// indexed, and ends at its upper bound.
+#[tokio::test]
+async fn test_transactions_subscription() {
+    let mut cluster = FullCluster::new().await.unwrap();
+
+    let fx0 = cluster.request_gas(SuiAddress::ZERO, 1).unwrap();
+    cluster.create_checkpoint().await;
+
+    let mut socket = connect(&cluster).await.unwrap();
+    subscribe(
+        &mut socket,
+        "1",
+        "subscription { transactions(filter: { afterCheckpoint: 0, beforeCheckpoint: 3 }) { digest } }",
+    )
+    .await
+    .unwrap();
+
+    let msg = next_message(&mut socket).await.unwrap();
+    assert_eq!(msg["type"], "next", "Unexpected message: {msg}");
+    assert_eq!(
+        msg["payload"]["data"]["transactions"]["digest"],
+        fx0.transaction_digest().to_string(),
+    );
+
+    let fx1 = cluster.request_gas(SuiAddress::ZERO, 2).unwrap();
+    cluster.create_checkpoint().await;
+
+    let msg = next_message(&mut socket).await.unwrap();
+    assert_eq!(msg["type"], "next", "Unexpected message: {msg}");
+    assert_eq!(
+        msg["payload"]["data"]["transactions"]["digest"],
+        fx1.transaction_digest().to_string(),
+    );
+
+    // The next checkpoint is at the subscription's upper bound, so it completes.
+    let msg = next_message(&mut socket).await.unwrap();
+    assert_eq!(msg["type"], "complete", "Unexpected message: {msg}");
+
+    cluster.stopped().await;
+}
+
+/// Subscriptions cannot start further behind the latest checkpoint than the configured limit.
+#[tokio::test]
+async fn test_catch_up_limit() {
+    let mut cluster = cluster_with_limits(Limits {
+        max_subscription_catch_up: 1,
+        ..Default::default()
+    })
+    .await;
+
+    for _ in 0..3 {
+        cluster.create_checkpoint().await;
+    }
+
+    let mut socket = connect(&cluster).await.unwrap();
+
+    // Checkpoint 2 is within the window, so it can be streamed.
+    subscribe(
+        &mut socket,
+        "1",
+        "subscription { transactions(filter: { atCheckpoint: 2 }) { digest } }",
+    )
+    .await
+    .unwrap();
+
+    let msg = next_message(&mut socket).await.unwrap();
+    assert_eq!(msg["type"], "complete", "Unexpected message: {msg}");
+
+    // Checkpoint 1 is too far behind.
+    subscribe(
+        &mut socket,
+        "2",
+        "subscription { transactions(filter: { atCheckpoint: 1 }) { digest } }",
+    )
+    .await
+    .unwrap();
+
+    let error = next_error(&mut socket).await.unwrap();
+    assert!(
+        error.contains("earliest checkpoint that can be streamed is 2"),
+        "Unexpected error: {error}"
+    );
+
+    cluster.stopped().await;
+}
+
+/// A connection cannot have more subscriptions active at once than the configured limit, but can
+/// open more once existing ones end.
+#[tokio::test]
+async fn test_subscriptions_per_connection_limit() {
+    let mut cluster = cluster_with_limits(Limits {
+        max_subscriptions_per_connection: 1,
+        ..Default::default()
+    })
+    .await;
+
+    cluster.create_checkpoint().await;
+
+    let mut socket = connect(&cluster).await.unwrap();
+
+    // This subscription waits for future checkpoints, so it remains active.
+    subscribe(
+        &mut socket,
+        "1",
+        "subscription { checkpoints { sequenceNumber } }",
+    )
+    .await
+    .unwrap();
+
+    subscribe(
+        &mut socket,
+        "2",
+        "subscription { checkpoints { sequenceNumber } }",
+    )
+    .await
+    .unwrap();
+
+    let error = next_error(&mut socket).await.unwrap();
+    assert!(
+        error.contains("more than 1 active subscriptions"),
+        "Unexpected error: {error}"
+    );
+
+    // Stopping the first subscription frees up its slot.
+    send(&mut socket, json!({ "id": "1", "type": "complete" }))
+        .await
+        .unwrap();
+
+    subscribe(
+        &mut socket,
+        "3",
+        "subscription { transactions(filter: { atCheckpoint: 1 }) { digest } }",
+    )
+    .await
+    .unwrap();
+
+    let msg = next_message_for(&mut socket, "3").await.unwrap();
+    assert_eq!(msg["type"], "complete", "Unexpected message: {msg}");
+
+    cluster.stopped().await;
+}
+
+/// Set-up a cluster where GraphQL is configured with the given `limits`.
+async fn cluster_with_limits(limits: Limits) -> FullCluster {
+    FullCluster::new_with_configs(
+        Simulacrum::new(),
+        IndexerArgs::default(),
+        IndexerArgs::default(),
+        IndexerConfig::for_test(),
+        ConsistentConfig::for_test(),
+        JsonRpcConfig::default(),
+        GraphQlConfig {
+            limits,
+            ..Default::default()
+        },
+        &prometheus::Registry::new(),
+        CancellationToken::new(),
+    )
+    .await
+    .expect("Failed to set-up cluster")
+}
+
+/// Open a WebSocket connection to the cluster's GraphQL subscription endpoint, and initialize it
+/// using the `graphql-transport-ws` protocol.
+async fn connect(cluster: &FullCluster) -> anyhow::Result<Socket> {
+    let mut url = cluster.graphql_url().join("graphql/subscriptions")?;
+    url.set_scheme("ws")
+        .map_err(|_| anyhow::anyhow!("Failed to set WebSocket scheme"))?;
+
+    let mut request = url.as_str().into_client_request()?;
+    request.headers_mut().insert(
+        "Sec-WebSocket-Protocol",
+        HeaderValue::from_static("graphql-transport-ws"),
+    );
+
+    let (mut socket, _) = connect_async(request).await?;
+    send(&mut socket, json!({ "type": "connection_init" })).await?;
+
+    let msg = next_message(&mut socket).await?;
+    if msg["type"] != "connection_ack" {
+        bail!("Expected connection_ack, got: {msg}");
+    }
+
+    Ok(socket)
+}
+
+/// Start a subscription on `socket` with the given `id`.
+async fn subscribe(socket: &mut Socket, id: &str, query: &str) -> anyhow::Result<()> {
+    send(
+        socket,
+        json!({
+            "id": id,
+            "type": "subscribe",
+            "payload": { "query": query },
+        }),
+    )
+    .await
+}
+
+async fn send(socket: &mut Socket, msg: Value) -> anyhow::Result<()> {
+    socket.send(Message::Text(msg.to_string())).await?;
+    Ok(())
+}
+
+/// Wait for the next protocol message on `socket`, skipping keep-alives.
+async fn next_message(socket: &mut Socket) -> anyhow::Result<Value> {
+    loop {
+        let msg = timeout(Duration::from_secs(10), socket.next())
+            .await
+            .context("Timed out waiting for message")?
+            .context("Connection closed")??;
+
+        match msg {
+            Message::Text(text) => return Ok(serde_json::from_str(&text)?),
+            Message::Ping(_) | Message::Pong(_) => continue,
+            msg => bail!("Unexpected message: {msg:?}"),
+        }
+    }
+}
+
+/// Wait for the next protocol message on `socket` for the subscription with the given `id`,
+/// skipping messages for other subscriptions.
+async fn next_message_for(socket: &mut Socket, id: &str) -> anyhow::Result<Value> {
+    loop {
+        let msg = next_message(socket).await?;
+        if msg["id"] == id {
+            return Ok(msg);
+        }
+    }
+}
+
+/// Wait for the next message on `socket`, expecting it to report an error, and return that error's
+/// message. Errors can be reported in a `next` message's payload, or as an `error` message.
+async fn next_error(socket: &mut Socket) -> anyhow::Result<String> {
+    let msg = next_message(socket).await?;
+    let errors = match msg["type"].as_str() {
+        Some("next") => &msg["payload"]["errors"],
+        Some("error") => &msg["payload"],
+        _ => bail!("Expected an error, got: {msg}"),
+    };
+
+    errors[0]["message"]
+        .as_str()
+        .map(str::to_owned)
+        .with_context(|| format!("No error message in: {msg}"))
+}
@@ -0,0 +1,111 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//# init --protocol-version 70 --accounts A B --addresses test=0x0 --simulator
+
+//# publish
+module test::events_test {
+    use std::ascii;
+    use sui::event;
+
+    public struct TestEvent has copy, drop {
+        message: ascii::String,
+        value: u64,
+    }
+
+    public entry fun emit_event(value: u64) {
+        event::emit(TestEvent {
+            message: ascii::string(b"Hello from test event"),
+            value,
+        });
+    }
+
+    public entry fun emit_multiple_events() {
+        event::emit(TestEvent {
+            message: ascii::string(b"First event"),
+            value: 1,
+        });
+
+        event::emit(TestEvent {
+            message: ascii::string(b"Second event"),
+            value: 2,
+        });
+    }
+}
+
+// Transaction that emits a single event
+//# run test::events_test::emit_event --sender A --args 42
+
+// Transaction that emits multiple events
+//# run test::events_test::emit_multiple_events --sender A
+
+// Transaction with no events (transfer)
+//# programmable --sender A --inputs 100 @B
+//> 0: SplitCoins(Gas, [Input(0)]);
+//> 1: TransferObjects([Result(0)], Input(1))
+
+//# create-checkpoint
+
+// Fetch the first page of events
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "MoveEventType": "@{test}::events_test::TestEvent" }, null, 2]
+}
+
+// The cursor points into the middle of a transaction's events
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [
+    { "MoveEventType": "@{test}::events_test::TestEvent" },
+    { "txDigest": "@{digest_3}", "eventSeq": "0" },
+    2
+  ]
+}
+
+// Fetch pages in descending order
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "MoveEventType": "@{test}::events_test::TestEvent" }, null, 2, true]
+}
+
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [
+    { "MoveEventType": "@{test}::events_test::TestEvent" },
+    { "txDigest": "@{digest_3}", "eventSeq": "0" },
+    2,
+    true
+  ]
+}
+
+// The cursor points to the last event, so the page is empty and the cursor is returned
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [
+    { "MoveEventType": "@{test}::events_test::TestEvent" },
+    { "txDigest": "@{digest_3}", "eventSeq": "1" },
+    1
+  ]
+}
+
+// Requesting a page that is too large
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "MoveEventType": "@{test}::events_test::TestEvent" }, null, 101]
+}
+
+// The cursor points to a transaction that doesn't exist
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [
+    { "MoveEventType": "@{test}::events_test::TestEvent" },
+    { "txDigest": "11111111111111111111111111111111", "eventSeq": "0" }
+  ]
+}
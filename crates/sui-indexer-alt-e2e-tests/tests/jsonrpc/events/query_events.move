@@ -0,0 +1,119 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//# init --protocol-version 70 --accounts A B --addresses test=0x0 --simulator
+
+//# publish
+module test::events_test {
+    use std::ascii;
+    use sui::event;
+
+    public struct TestEvent has copy, drop {
+        message: ascii::String,
+        value: u64,
+    }
+
+    public entry fun emit_event(value: u64) {
+        event::emit(TestEvent {
+            message: ascii::string(b"Hello from test event"),
+            value,
+        });
+    }
+
+    public entry fun emit_multiple_events() {
+        event::emit(TestEvent {
+            message: ascii::string(b"First event"),
+            value: 1,
+        });
+
+        event::emit(TestEvent {
+            message: ascii::string(b"Second event"),
+            value: 2,
+        });
+    }
+}
+
+// Transaction that emits a single event
+//# run test::events_test::emit_event --sender A --args 42
+
+// Transaction that emits multiple events
+//# run test::events_test::emit_multiple_events --sender A
+
+// Transaction with no events (transfer)
+//# programmable --sender A --inputs 100 @B
+//> 0: SplitCoins(Gas, [Input(0)]);
+//> 1: TransferObjects([Result(0)], Input(1))
+
+//# create-checkpoint
+
+// Events emitted by A, and by B (who hasn't emitted any)
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "Sender": "@{A}" }]
+}
+
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "Sender": "@{B}" }]
+}
+
+// Events emitted by a transaction with multiple events, and by one with none
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "Transaction": "@{digest_3}" }]
+}
+
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "Transaction": "@{digest_4}" }]
+}
+
+// Events emitted from a module, and from a module that doesn't exist
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "MoveModule": { "package": "@{test}", "module": "events_test" } }]
+}
+
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "MoveModule": { "package": "@{test}", "module": "other" } }]
+}
+
+// Events by type, and by a type that was never emitted
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "MoveEventType": "@{test}::events_test::TestEvent" }]
+}
+
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "MoveEventType": "@{test}::events_test::OtherEvent" }]
+}
+
+// Events whose type is defined in a module, and in a module from a different package
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "MoveEventModule": { "package": "@{test}", "module": "events_test" } }]
+}
+
+//# run-jsonrpc
+{
+  "method": "suix_queryEvents",
+  "params": [{ "MoveEventModule": { "package": "0x2", "module": "events_test" } }]
+}
+
+// Fetch all the events emitted by a transaction
+//# run-jsonrpc
+{
+  "method": "sui_getEvents",
+  "params": ["@{digest_3}"]
+}
@@ -0,0 +1,351 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use anyhow::Context;
+use prometheus::Registry;
+use reqwest::Client;
+use serde_json::{json, Value};
+use sui_indexer_alt_e2e_tests::{find_address_owned, FullCluster};
+use sui_indexer_alt_jsonrpc::{
+    args::SystemPackageTaskArgs,
+    config::{CoinsLayer, RpcLayer},
+    start_rpc, NodeArgs, RpcArgs,
+};
+use sui_indexer_alt_reader::{
+    bigtable_reader::BigtableArgs, consistent_reader::ConsistentReaderArgs,
+};
+use sui_pg_db::{temp::get_available_port, DbArgs};
+use sui_types::{
+    base_types::{ObjectID, ObjectRef, SuiAddress},
+    crypto::{get_account_key_pair, AccountKeyPair},
+    effects::TransactionEffectsAPI,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{Transaction, TransactionData},
+};
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+/// 5 SUI gas budget
+const DEFAULT_GAS_BUDGET: u64 = 5_000_000_000;
+
+/// Reading an object at a checkpoint returns the version it had at the end of that checkpoint,
+/// even after it has been modified.
+#[tokio::test]
+async fn test_get_object_at_checkpoint() {
+    let mut cluster = FullCluster::new().await.unwrap();
+    let client = Client::new();
+    let (a, kp) = get_account_key_pair();
+
+    let coin = create_coin(&mut cluster, a, DEFAULT_GAS_BUDGET);
+    let cp0 = cluster.create_checkpoint().await.sequence_number;
+
+    let (b, _) = get_account_key_pair();
+    let modified = pay_from(&mut cluster, a, &kp, coin, b, 1);
+    let cp1 = cluster.create_checkpoint().await.sequence_number;
+
+    let version = |response: Value| response["result"]["data"]["version"].clone();
+
+    let before = get_object(&client, cluster.jsonrpc_url(), coin.0, Some(cp0))
+        .await
+        .unwrap();
+    assert_eq!(version(before), coin.1.value().to_string());
+
+    let after = get_object(&client, cluster.jsonrpc_url(), coin.0, Some(cp1))
+        .await
+        .unwrap();
+    assert_eq!(version(after), modified.1.value().to_string());
+
+    let latest = get_object(&client, cluster.jsonrpc_url(), coin.0, None)
+        .await
+        .unwrap();
+    assert_eq!(version(latest), modified.1.value().to_string());
+
+    // Checkpoints that have not been indexed yet are out of range.
+    let future = get_object(&client, cluster.jsonrpc_url(), coin.0, Some(cp1 + 100))
+        .await
+        .unwrap();
+    assert!(future["error"].is_object(), "Response: {future:#?}");
+
+    cluster.stopped().await;
+}
+
+/// Reading owned objects at a checkpoint returns the objects owned at the end of that checkpoint,
+/// and paginating at a fixed checkpoint is not affected by later changes.
+#[tokio::test]
+async fn test_get_owned_objects_at_checkpoint() {
+    let mut cluster = FullCluster::new().await.unwrap();
+    let client = Client::new();
+    let (a, _) = get_account_key_pair();
+
+    let mut coins: Vec<_> = (1..=3)
+        .map(|amount| create_coin(&mut cluster, a, amount).0)
+        .collect();
+    coins.sort();
+    let cp0 = cluster.create_checkpoint().await.sequence_number;
+
+    // Fetch the first page, then add another coin before fetching the second page.
+    let first = get_owned_objects(&client, cluster.jsonrpc_url(), a, None, Some(2), Some(cp0))
+        .await
+        .unwrap();
+
+    create_coin(&mut cluster, a, 4);
+    cluster.create_checkpoint().await;
+
+    let cursor = first["result"]["nextCursor"].as_str().map(str::to_owned);
+    assert_eq!(first["result"]["hasNextPage"], true, "Response: {first:#?}");
+
+    let second = get_owned_objects(
+        &client,
+        cluster.jsonrpc_url(),
+        a,
+        cursor,
+        Some(2),
+        Some(cp0),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        second["result"]["hasNextPage"], false,
+        "Response: {second:#?}"
+    );
+
+    let paginated: Vec<_> = [&first, &second].into_iter().flat_map(object_ids).collect();
+    assert_eq!(paginated, coins);
+
+    // The latest view includes the new coin.
+    let latest = get_owned_objects(&client, cluster.jsonrpc_url(), a, None, Some(10), None)
+        .await
+        .unwrap();
+    assert_eq!(object_ids(&latest).len(), 4, "Response: {latest:#?}");
+
+    cluster.stopped().await;
+}
+
+/// Reading a balance at a checkpoint sums the coins owned at the end of that checkpoint, and
+/// counts them up to the configured maximum.
+#[tokio::test]
+async fn test_get_balance_at_checkpoint() {
+    let mut cluster = FullCluster::new().await.unwrap();
+    let client = Client::new();
+    let (a, _) = get_account_key_pair();
+
+    for amount in 1..=3 {
+        create_coin(&mut cluster, a, amount);
+    }
+    let cp0 = cluster.create_checkpoint().await.sequence_number;
+
+    create_coin(&mut cluster, a, 4);
+    let cp1 = cluster.create_checkpoint().await.sequence_number;
+
+    // A separate RPC is started for this test, to limit the number of coins counted. It is not
+    // configured with a fullnode: reads at a checkpoint are served from the consistent store.
+    let cancel = CancellationToken::new();
+    let rpc_url = start_rpc_with_max_coins(&cluster, 3, cancel.child_token())
+        .await
+        .unwrap();
+
+    let cp0_balance = get_balance(&client, rpc_url.clone(), a, Some(cp0))
+        .await
+        .unwrap();
+    assert_eq!(cp0_balance["result"]["totalBalance"], "6");
+    assert_eq!(cp0_balance["result"]["coinObjectCount"], 3);
+
+    // There are four coins at this checkpoint, but the count saturates at three.
+    let cp1_balance = get_balance(&client, rpc_url.clone(), a, Some(cp1))
+        .await
+        .unwrap();
+    assert_eq!(cp1_balance["result"]["totalBalance"], "10");
+    assert_eq!(cp1_balance["result"]["coinObjectCount"], 3);
+
+    let future = get_balance(&client, rpc_url.clone(), a, Some(cp1 + 100))
+        .await
+        .unwrap();
+    assert!(future["error"].is_object(), "Response: {future:#?}");
+
+    // Latest balances are read from the fullnode, so they are not available from this RPC.
+    let latest = get_balance(&client, rpc_url, a, None).await.unwrap();
+    assert!(latest["error"].is_object(), "Response: {latest:#?}");
+
+    cancel.cancel();
+    cluster.stopped().await;
+}
+
+/// Start a JSON-RPC server that reads from `cluster`'s database and consistent store, with at most
+/// `max_coins_counted` coins counted per balance, and no fullnode. Returns the URL to send requests
+/// to.
+async fn start_rpc_with_max_coins(
+    cluster: &FullCluster,
+    max_coins_counted: usize,
+    cancel: CancellationToken,
+) -> anyhow::Result<Url> {
+    let rpc_listen_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), get_available_port());
+    let rpc_url = Url::parse(&format!("http://{rpc_listen_address}/"))?;
+
+    let rpc_config = RpcLayer {
+        coins: CoinsLayer {
+            max_coins_counted_at_checkpoint: Some(max_coins_counted),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+    .finish();
+
+    start_rpc(
+        Some(cluster.db_url()),
+        None,
+        None,
+        DbArgs::default(),
+        BigtableArgs::default(),
+        ConsistentReaderArgs {
+            consistent_store_url: Some(cluster.consistent_store_url()),
+            consistent_store_statement_timeout_ms: None,
+        },
+        RpcArgs {
+            rpc_listen_address,
+            ..Default::default()
+        },
+        NodeArgs::default(),
+        SystemPackageTaskArgs::default(),
+        rpc_config,
+        &Registry::new(),
+        cancel,
+    )
+    .await
+    .context("Failed to start JSON-RPC server")?;
+
+    Ok(rpc_url)
+}
+
+/// Create a SUI coin with balance `amount`, owned by `owner`.
+fn create_coin(cluster: &mut FullCluster, owner: SuiAddress, amount: u64) -> ObjectRef {
+    let (sender, kp, gas) = cluster
+        .funded_account(DEFAULT_GAS_BUDGET + amount)
+        .expect("Failed to fund account");
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.transfer_sui(owner, Some(amount));
+
+    let data = TransactionData::new_programmable(
+        sender,
+        vec![gas],
+        builder.finish(),
+        DEFAULT_GAS_BUDGET,
+        cluster.reference_gas_price(),
+    );
+
+    let (fx, _) = cluster
+        .execute_transaction(Transaction::from_data_and_signer(data, vec![&kp]))
+        .expect("Failed to execute transaction");
+
+    assert!(fx.status().is_ok(), "create coin transaction failed");
+    find_address_owned(&fx).expect("Failed to find created coin")
+}
+
+/// Send `amount` from `sender`'s `coin` to `recipient`, paying for gas with the same coin. Returns
+/// the new reference to the coin.
+fn pay_from(
+    cluster: &mut FullCluster,
+    sender: SuiAddress,
+    kp: &AccountKeyPair,
+    coin: ObjectRef,
+    recipient: SuiAddress,
+    amount: u64,
+) -> ObjectRef {
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.transfer_sui(recipient, Some(amount));
+
+    let data = TransactionData::new_programmable(
+        sender,
+        vec![coin],
+        builder.finish(),
+        DEFAULT_GAS_BUDGET / 2,
+        cluster.reference_gas_price(),
+    );
+
+    let (fx, _) = cluster
+        .execute_transaction(Transaction::from_data_and_signer(data, vec![kp]))
+        .expect("Failed to execute transaction");
+
+    assert!(fx.status().is_ok(), "pay transaction failed");
+    fx.gas_object().0
+}
+
+/// The IDs of the objects in a page of `getOwnedObjects` results.
+fn object_ids(page: &Value) -> Vec<ObjectID> {
+    page["result"]["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|o| o["data"]["objectId"].as_str().unwrap().parse().unwrap())
+        .collect()
+}
+
+async fn get_object(
+    client: &Client,
+    url: Url,
+    id: ObjectID,
+    at_checkpoint: Option<u64>,
+) -> anyhow::Result<Value> {
+    jsonrpc(
+        client,
+        url,
+        "sui_getObject",
+        json!([id.to_string(), null, at_checkpoint]),
+    )
+    .await
+}
+
+async fn get_owned_objects(
+    client: &Client,
+    url: Url,
+    owner: SuiAddress,
+    cursor: Option<String>,
+    limit: Option<usize>,
+    at_checkpoint: Option<u64>,
+) -> anyhow::Result<Value> {
+    jsonrpc(
+        client,
+        url,
+        "suix_getOwnedObjects",
+        json!([owner.to_string(), null, cursor, limit, at_checkpoint]),
+    )
+    .await
+}
+
+async fn get_balance(
+    client: &Client,
+    url: Url,
+    owner: SuiAddress,
+    at_checkpoint: Option<u64>,
+) -> anyhow::Result<Value> {
+    jsonrpc(
+        client,
+        url,
+        "suix_getBalance",
+        json!([owner.to_string(), null, at_checkpoint]),
+    )
+    .await
+}
+
+async fn jsonrpc(client: &Client, url: Url, method: &str, params: Value) -> anyhow::Result<Value> {
+    let query = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    });
+
+    let response = client
+        .post(url)
+        .json(&query)
+        .send()
+        .await
+        .context("Request to JSON-RPC server failed")?;
+
+    response
+        .json()
+        .await
+        .context("Failed to parse JSON-RPC response")
+}
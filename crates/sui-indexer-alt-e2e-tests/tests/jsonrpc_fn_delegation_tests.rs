@@ -11,7 +11,9 @@ use serde_json::{json, Value};
 use sui_indexer_alt_jsonrpc::{
     args::SystemPackageTaskArgs, config::RpcConfig, start_rpc, NodeArgs, RpcArgs,
 };
-use sui_indexer_alt_reader::bigtable_reader::BigtableArgs;
+use sui_indexer_alt_reader::{
+    bigtable_reader::BigtableArgs, consistent_reader::ConsistentReaderArgs,
+};
 use sui_macros::sim_test;
 use sui_pg_db::{temp::get_available_port, DbArgs};
 use sui_swarm_config::genesis_config::AccountConfig;
@@ -65,10 +67,12 @@ impl FnDelegationTestCluster {
         };
 
         let rpc_handle = start_rpc(
+            None,
             None,
             None,
             DbArgs::default(),
             BigtableArgs::default(),
+            ConsistentReaderArgs::default(),
             rpc_args,
             NodeArgs {
                 fullnode_rpc_url: Some(fullnode_rpc_url),
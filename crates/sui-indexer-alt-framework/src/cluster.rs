@@ -346,10 +346,7 @@ mod tests {
         let args = Args {
             client_args: Some(ClientArgs {
                 local_ingestion_path: Some(checkpoint_dir.path().to_owned()),
-                remote_store_url: None,
-                rpc_api_url: None,
-                rpc_username: None,
-                rpc_password: None,
+                ..Default::default()
             }),
             indexer_args: IndexerArgs {
                 first_checkpoint: Some(0),
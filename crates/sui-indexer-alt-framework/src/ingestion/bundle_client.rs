@@ -0,0 +1,172 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use tokio::sync::OnceCell;
+use tokio_util::bytes::Bytes;
+
+use crate::ingestion::client::{FetchData, FetchError, FetchResult, IngestionClientTrait};
+
+/// The first four bytes of a zstd frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// An ingestion client that serves checkpoints from a bundle: a tar archive, optionally
+/// compressed with zstd, containing `{sequence_number}.chk` files, as written to a local
+/// ingestion directory.
+///
+/// The bundle is read into memory in full on the first fetch. Checkpoints that are not in the
+/// bundle are reported as not found.
+pub struct BundleIngestionClient {
+    path: PathBuf,
+    checkpoints: OnceCell<BTreeMap<u64, Bytes>>,
+}
+
+impl BundleIngestionClient {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            checkpoints: OnceCell::new(),
+        }
+    }
+
+    async fn checkpoints(&self) -> anyhow::Result<&BTreeMap<u64, Bytes>> {
+        self.checkpoints
+            .get_or_try_init(|| async {
+                let path = self.path.clone();
+                tokio::task::spawn_blocking(move || read_bundle(&path)).await?
+            })
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl IngestionClientTrait for BundleIngestionClient {
+    async fn fetch(&self, checkpoint: u64) -> FetchResult {
+        let checkpoints = self
+            .checkpoints()
+            .await
+            .map_err(|error| FetchError::Transient {
+                reason: "bundle",
+                error,
+            })?;
+
+        checkpoints
+            .get(&checkpoint)
+            .cloned()
+            .map(FetchData::Raw)
+            .ok_or(FetchError::NotFound)
+    }
+}
+
+/// Read all the checkpoint files out of the bundle at `path`. Entries whose file name is not of
+/// the form `{sequence_number}.chk` are ignored.
+fn read_bundle(path: &Path) -> anyhow::Result<BTreeMap<u64, Bytes>> {
+    let mut file = File::open(path)
+        .with_context(|| format!("Failed to open checkpoint bundle {}", path.display()))?;
+
+    let mut magic = [0u8; 4];
+    let is_zstd = file.read_exact(&mut magic).is_ok() && magic == ZSTD_MAGIC;
+    let file = File::open(path)?;
+
+    let reader: Box<dyn Read> = if is_zstd {
+        Box::new(zstd::stream::read::Decoder::new(file)?)
+    } else {
+        Box::new(BufReader::new(file))
+    };
+
+    let mut checkpoints = BTreeMap::new();
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path()?;
+        let Some(checkpoint) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".chk"))
+            .and_then(|seq| seq.parse::<u64>().ok())
+        else {
+            continue;
+        };
+
+        let mut bytes = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut bytes)?;
+        checkpoints.insert(checkpoint, Bytes::from(bytes));
+    }
+
+    Ok(checkpoints)
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_storage::blob::{Blob, BlobEncoding};
+
+    use crate::ingestion::client::IngestionClient;
+    use crate::ingestion::error::Error;
+    use crate::ingestion::test_utils::test_checkpoint_data;
+    use crate::metrics::tests::test_metrics;
+
+    use super::*;
+
+    fn write_bundle(path: &Path, checkpoints: &[u64], compress: bool) {
+        let mut builder = tar::Builder::new(Vec::new());
+        for cp in checkpoints {
+            let bytes = test_checkpoint_data(*cp);
+            let mut header = tar::Header::new_gnu();
+            header.set_size(bytes.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, format!("{cp}.chk"), bytes.as_slice())
+                .unwrap();
+        }
+
+        let tarball = builder.into_inner().unwrap();
+        let contents = if compress {
+            zstd::encode_all(tarball.as_slice(), 0).unwrap()
+        } else {
+            tarball
+        };
+
+        std::fs::write(path, contents).unwrap();
+    }
+
+    async fn assert_bundle_contents(path: PathBuf) {
+        let client = IngestionClient::new_bundle(path, test_metrics());
+        for cp in [1, 2, 3] {
+            let checkpoint = client.fetch(cp).await.unwrap();
+            assert_eq!(
+                Blob::encode(&*checkpoint, BlobEncoding::Bcs)
+                    .unwrap()
+                    .to_bytes(),
+                test_checkpoint_data(cp)
+            );
+        }
+
+        assert!(matches!(client.fetch(4).await, Err(Error::NotFound(4))));
+    }
+
+    #[tokio::test]
+    async fn bundle_test_fetch() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("checkpoints.tar");
+        write_bundle(&path, &[1, 2, 3], false);
+        assert_bundle_contents(path).await;
+    }
+
+    #[tokio::test]
+    async fn bundle_test_fetch_zstd() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("checkpoints.tar.zst");
+        write_bundle(&path, &[1, 2, 3], true);
+        assert_bundle_contents(path).await;
+    }
+}
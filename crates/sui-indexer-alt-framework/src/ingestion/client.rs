@@ -15,6 +15,8 @@ use tokio_util::bytes::Bytes;
 use tracing::{debug, warn};
 use url::Url;
 
+use crate::ingestion::bundle_client::BundleIngestionClient;
+use crate::ingestion::failover_client::FailoverIngestionClient;
 use crate::ingestion::local_client::LocalIngestionClient;
 use crate::ingestion::remote_client::RemoteIngestionClient;
 use crate::ingestion::Error as IngestionError;
@@ -34,8 +36,18 @@ const MAX_TRANSIENT_RETRY_INTERVAL: Duration = Duration::from_secs(60);
 /// slow remote stores without interrupting the ingestion process.
 const SLOW_OPERATION_WARNING_THRESHOLD: Duration = Duration::from_secs(60);
 
+/// A source of checkpoints for the ingestion service.
+///
+/// Implement this trait to feed an indexer from a source that the framework does not support out
+/// of the box, and pass it to the indexer through [crate::ingestion::ClientArgs::custom_client].
+/// Retries, metrics and deserialization of raw checkpoint bytes are handled by [IngestionClient],
+/// so implementations only need to classify their failures:
+///
+/// - [FetchError::NotFound] if the checkpoint does not exist (yet). Ingestion will poll for it
+///   again later.
+/// - [FetchError::Transient] for any other failure, which will be retried with backoff.
 #[async_trait::async_trait]
-pub(crate) trait IngestionClientTrait: Send + Sync {
+pub trait IngestionClientTrait: Send + Sync {
     async fn fetch(&self, checkpoint: u64) -> FetchResult;
 }
 
@@ -68,16 +80,42 @@ pub struct IngestionClient {
 }
 
 impl IngestionClient {
+    /// Create a client that fetches checkpoints from a custom source.
+    pub fn new(client: Arc<dyn IngestionClientTrait>, metrics: Arc<IndexerMetrics>) -> Self {
+        Self::new_impl(client, metrics)
+    }
+
     pub(crate) fn new_remote(url: Url, metrics: Arc<IndexerMetrics>) -> IngestionResult<Self> {
         let client = Arc::new(RemoteIngestionClient::new(url)?);
         Ok(Self::new_impl(client, metrics))
     }
 
+    /// Create a client that fetches checkpoints from the remote store at `url`, falling back to
+    /// the stores at `fallbacks`, in order, when it fails.
+    pub(crate) fn new_remote_with_fallbacks(
+        url: Url,
+        fallbacks: Vec<Url>,
+        metrics: Arc<IndexerMetrics>,
+    ) -> IngestionResult<Self> {
+        let mut clients: Vec<Arc<dyn IngestionClientTrait>> =
+            vec![Arc::new(RemoteIngestionClient::new(url)?)];
+        for url in fallbacks {
+            clients.push(Arc::new(RemoteIngestionClient::new(url)?));
+        }
+        let client = Arc::new(FailoverIngestionClient::new(clients));
+        Ok(Self::new_impl(client, metrics))
+    }
+
     pub(crate) fn new_local(path: PathBuf, metrics: Arc<IndexerMetrics>) -> Self {
         let client = Arc::new(LocalIngestionClient::new(path));
         Self::new_impl(client, metrics)
     }
 
+    pub(crate) fn new_bundle(path: PathBuf, metrics: Arc<IndexerMetrics>) -> Self {
+        let client = Arc::new(BundleIngestionClient::new(path));
+        Self::new_impl(client, metrics)
+    }
+
     pub(crate) fn new_rpc(
         url: Url,
         username: Option<String>,
@@ -17,6 +17,12 @@ pub enum Error {
     #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
 
+    #[error(
+        "No source to ingest checkpoints from: one of custom_client, remote_store_url, \
+        local_ingestion_path, checkpoint_bundle_path or rpc_api_url must be provided"
+    )]
+    NoSource,
+
     #[error("No subscribers for ingestion service")]
     NoSubscribers,
 
@@ -0,0 +1,141 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tracing::debug;
+
+use crate::ingestion::client::{FetchError, FetchResult, IngestionClientTrait};
+
+/// An ingestion client that fails over between several sources.
+///
+/// Each fetch starts from the source that most recently served a checkpoint successfully, and
+/// tries the remaining sources in order until one of them returns the checkpoint. The checkpoint
+/// is only reported as not found if every source reports it as not found, otherwise the last
+/// transient error is returned, so that the fetch is retried.
+pub struct FailoverIngestionClient {
+    clients: Vec<Arc<dyn IngestionClientTrait>>,
+    /// Index of the source that last served a checkpoint.
+    preferred: AtomicUsize,
+}
+
+impl FailoverIngestionClient {
+    /// Create a client that fails over between `clients`, in order of preference.
+    ///
+    /// ## Panics
+    ///
+    /// If `clients` is empty.
+    pub fn new(clients: Vec<Arc<dyn IngestionClientTrait>>) -> Self {
+        assert!(
+            !clients.is_empty(),
+            "At least one ingestion source required"
+        );
+        Self {
+            clients,
+            preferred: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl IngestionClientTrait for FailoverIngestionClient {
+    async fn fetch(&self, checkpoint: u64) -> FetchResult {
+        let preferred = self.preferred.load(Ordering::Relaxed);
+        let mut transient = None;
+
+        for offset in 0..self.clients.len() {
+            let index = (preferred + offset) % self.clients.len();
+            match self.clients[index].fetch(checkpoint).await {
+                Ok(data) => {
+                    if index != preferred {
+                        debug!(
+                            checkpoint,
+                            source = index,
+                            "Failed over to ingestion source"
+                        );
+                        self.preferred.store(index, Ordering::Relaxed);
+                    }
+                    return Ok(data);
+                }
+
+                Err(FetchError::NotFound) => continue,
+
+                Err(e @ FetchError::Transient { .. }) => {
+                    debug!(checkpoint, source = index, "Ingestion source failed: {e}");
+                    transient = Some(e);
+                }
+            }
+        }
+
+        Err(transient.unwrap_or(FetchError::NotFound))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_util::bytes::Bytes;
+
+    use crate::ingestion::client::FetchData;
+
+    use super::*;
+
+    /// A source that serves checkpoints up to `hi` or fails every fetch.
+    struct TestSource {
+        hi: u64,
+        failing: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl IngestionClientTrait for TestSource {
+        async fn fetch(&self, checkpoint: u64) -> FetchResult {
+            if self.failing {
+                Err(FetchError::Transient {
+                    reason: "test",
+                    error: anyhow::anyhow!("Source is down"),
+                })
+            } else if checkpoint > self.hi {
+                Err(FetchError::NotFound)
+            } else {
+                Ok(FetchData::Raw(Bytes::from(
+                    checkpoint.to_le_bytes().to_vec(),
+                )))
+            }
+        }
+    }
+
+    fn source(hi: u64, failing: bool) -> Arc<dyn IngestionClientTrait> {
+        Arc::new(TestSource { hi, failing })
+    }
+
+    #[tokio::test]
+    async fn test_failover_to_healthy_source() {
+        let client = FailoverIngestionClient::new(vec![source(10, true), source(10, false)]);
+        assert!(client.fetch(5).await.is_ok());
+        assert_eq!(client.preferred.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failover_to_source_with_checkpoint() {
+        let client = FailoverIngestionClient::new(vec![source(5, false), source(10, false)]);
+        assert!(client.fetch(3).await.is_ok());
+        assert_eq!(client.preferred.load(Ordering::Relaxed), 0);
+        assert!(client.fetch(8).await.is_ok());
+        assert_eq!(client.preferred.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_not_found_everywhere() {
+        let client = FailoverIngestionClient::new(vec![source(5, false), source(5, false)]);
+        assert!(matches!(client.fetch(8).await, Err(FetchError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_transient_error_wins_over_not_found() {
+        let client = FailoverIngestionClient::new(vec![source(5, false), source(10, true)]);
+        assert!(matches!(
+            client.fetch(8).await,
+            Err(FetchError::Transient { .. })
+        ));
+    }
+}
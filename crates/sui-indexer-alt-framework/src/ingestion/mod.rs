@@ -6,7 +6,7 @@
 // bound is hit, the indexer could deadlock.
 #![allow(clippy::disallowed_methods)]
 
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{fmt, path::PathBuf, sync::Arc, time::Duration};
 
 use serde::{Deserialize, Serialize};
 use tokio::{sync::mpsc, task::JoinHandle};
@@ -14,34 +14,49 @@ use tokio_util::sync::CancellationToken;
 use url::Url;
 
 use crate::ingestion::broadcaster::broadcaster;
-use crate::ingestion::client::IngestionClient;
+use crate::ingestion::client::{IngestionClient, IngestionClientTrait};
 use crate::ingestion::error::{Error, Result};
 use crate::ingestion::regulator::regulator;
 use crate::metrics::IndexerMetrics;
 use crate::types::full_checkpoint_content::CheckpointData;
 
 mod broadcaster;
+pub mod bundle_client;
 pub mod client;
 pub mod error;
+pub mod failover_client;
 mod local_client;
 mod regulator;
 mod remote_client;
 mod rpc_client;
+pub mod store_client;
 #[cfg(test)]
 mod test_utils;
 
+/// Where to fetch checkpoints from. Exactly one source is used, but none is required on the
+/// command-line, so that binaries can supply a [CustomClient] instead. Creating an
+/// [IngestionService] fails if no source was provided either way.
 #[derive(clap::Args, Clone, Debug, Default)]
-#[group(required = true)]
 pub struct ClientArgs {
     /// Remote Store to fetch checkpoints from.
     #[clap(long, group = "source")]
     pub remote_store_url: Option<Url>,
 
+    /// Remote Stores to fall back to, in order, when fetching a checkpoint from
+    /// remote_store_url fails. Can be specified multiple times.
+    #[clap(long = "fallback-remote-store-url", requires = "remote_store_url")]
+    pub fallback_remote_store_urls: Vec<Url>,
+
     /// Path to the local ingestion directory.
     /// If both remote_store_url and local_ingestion_path are provided, remote_store_url will be used.
     #[clap(long, group = "source")]
     pub local_ingestion_path: Option<PathBuf>,
 
+    /// Path to a checkpoint bundle: a tar archive, optionally compressed with zstd, of checkpoint
+    /// files named the same way as in a local ingestion directory.
+    #[clap(long, group = "source")]
+    pub checkpoint_bundle_path: Option<PathBuf>,
+
     /// Sui fullnode gRPC url to fetch checkpoints from.
     /// If all remote_store_url, local_ingestion_path and rpc_api_url are provided, remote_store_url will be used.
     #[clap(long, env, group = "source")]
@@ -54,8 +69,17 @@ pub struct ClientArgs {
     /// Optional password for the gRPC service.
     #[clap(long, env)]
     pub rpc_password: Option<String>,
+
+    /// A custom source to fetch checkpoints from, for sources that cannot be configured from the
+    /// command-line. Takes precedence over all other sources.
+    #[clap(skip)]
+    pub custom_client: Option<CustomClient>,
 }
 
+/// A user-provided implementation of [IngestionClientTrait], to plug into [ClientArgs].
+#[derive(Clone)]
+pub struct CustomClient(pub Arc<dyn IngestionClientTrait>);
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct IngestionConfig {
     /// Maximum size of checkpoint backlog across all workers downstream of the ingestion service.
@@ -77,6 +101,18 @@ pub struct IngestionService {
     cancel: CancellationToken,
 }
 
+impl CustomClient {
+    pub fn new(client: impl IngestionClientTrait + 'static) -> Self {
+        Self(Arc::new(client))
+    }
+}
+
+impl fmt::Debug for CustomClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CustomClient").finish_non_exhaustive()
+    }
+}
+
 impl IngestionConfig {
     pub fn retry_interval(&self) -> Duration {
         Duration::from_millis(self.retry_interval_ms)
@@ -93,10 +129,22 @@ impl IngestionService {
         cancel: CancellationToken,
     ) -> Result<Self> {
         // TODO: Potentially support a hybrid mode where we can fetch from both local and remote.
-        let client = if let Some(url) = args.remote_store_url.as_ref() {
-            IngestionClient::new_remote(url.clone(), metrics.clone())?
+        let client = if let Some(CustomClient(client)) = args.custom_client {
+            IngestionClient::new(client, metrics.clone())
+        } else if let Some(url) = args.remote_store_url.as_ref() {
+            if args.fallback_remote_store_urls.is_empty() {
+                IngestionClient::new_remote(url.clone(), metrics.clone())?
+            } else {
+                IngestionClient::new_remote_with_fallbacks(
+                    url.clone(),
+                    args.fallback_remote_store_urls,
+                    metrics.clone(),
+                )?
+            }
         } else if let Some(path) = args.local_ingestion_path.as_ref() {
             IngestionClient::new_local(path.clone(), metrics.clone())
+        } else if let Some(path) = args.checkpoint_bundle_path.as_ref() {
+            IngestionClient::new_bundle(path.clone(), metrics.clone())
         } else if let Some(rpc_api_url) = args.rpc_api_url.as_ref() {
             IngestionClient::new_rpc(
                 rpc_api_url.clone(),
@@ -105,7 +153,7 @@ impl IngestionService {
                 metrics.clone(),
             )?
         } else {
-            panic!("One of remote_store_url, local_ingestion_path or rpc_api_url must be provided");
+            return Err(Error::NoSource);
         };
 
         let subscribers = Vec::new();
@@ -225,10 +273,7 @@ mod tests {
         IngestionService::new(
             ClientArgs {
                 remote_store_url: Some(Url::parse(&uri).unwrap()),
-                local_ingestion_path: None,
-                rpc_api_url: None,
-                rpc_username: None,
-                rpc_password: None,
+                ..Default::default()
             },
             IngestionConfig {
                 checkpoint_buffer_size,
@@ -262,6 +307,53 @@ mod tests {
         })
     }
 
+    /// No source is required on the command-line, as one can be supplied programmatically.
+    #[test]
+    fn parse_no_source() {
+        #[derive(clap::Parser)]
+        struct Args {
+            #[clap(flatten)]
+            client_args: ClientArgs,
+        }
+
+        let args = <Args as clap::Parser>::try_parse_from(["test"]).unwrap();
+        assert!(args.client_args.remote_store_url.is_none());
+        assert!(args.client_args.custom_client.is_none());
+    }
+
+    /// The ingestion service can't be created without a source.
+    #[test]
+    fn fail_on_no_source() {
+        let err = IngestionService::new(
+            ClientArgs::default(),
+            IngestionConfig::default(),
+            test_metrics(),
+            CancellationToken::new(),
+        )
+        .err()
+        .unwrap();
+
+        assert!(matches!(err, Error::NoSource));
+    }
+
+    /// The service can be created from a custom client alone.
+    #[test]
+    fn custom_client_source() {
+        let store = Arc::new(std::sync::RwLock::new(simulacrum::Simulacrum::new()));
+        IngestionService::new(
+            ClientArgs {
+                custom_client: Some(CustomClient::new(
+                    crate::ingestion::store_client::StoreIngestionClient::new(store),
+                )),
+                ..Default::default()
+            },
+            IngestionConfig::default(),
+            test_metrics(),
+            CancellationToken::new(),
+        )
+        .unwrap();
+    }
+
     /// If the ingestion service has no subscribers, it will fail fast (before fetching any
     /// checkpoints).
     #[tokio::test]
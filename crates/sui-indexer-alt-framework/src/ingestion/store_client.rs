@@ -0,0 +1,90 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::{Arc, RwLock};
+
+use anyhow::anyhow;
+
+use crate::ingestion::client::{FetchData, FetchError, FetchResult, IngestionClientTrait};
+use crate::types::storage::ReadStore;
+
+/// An in-process ingestion client that reads checkpoints straight out of a [ReadStore], such as a
+/// `Simulacrum`, without going through the file system or the network.
+///
+/// The store is shared behind a lock, so that the caller can keep producing checkpoints (e.g. by
+/// executing transactions against the `Simulacrum`) while the indexer is running.
+pub struct StoreIngestionClient<S> {
+    store: Arc<RwLock<S>>,
+}
+
+impl<S> StoreIngestionClient<S> {
+    pub fn new(store: Arc<RwLock<S>>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: ReadStore + Send + Sync> IngestionClientTrait for StoreIngestionClient<S> {
+    async fn fetch(&self, checkpoint: u64) -> FetchResult {
+        let store = self.store.read().map_err(|_| FetchError::Transient {
+            reason: "store_poisoned",
+            error: anyhow!("Store lock poisoned"),
+        })?;
+
+        let Some(summary) = store.get_checkpoint_by_sequence_number(checkpoint) else {
+            return Err(FetchError::NotFound);
+        };
+
+        let Some(contents) = store.get_checkpoint_contents_by_digest(&summary.content_digest)
+        else {
+            return Err(FetchError::NotFound);
+        };
+
+        let data = store
+            .get_checkpoint_data(summary, contents)
+            .map_err(|error| FetchError::Transient {
+                reason: "store",
+                error,
+            })?;
+
+        Ok(FetchData::CheckpointData(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simulacrum::Simulacrum;
+
+    use crate::ingestion::client::IngestionClient;
+    use crate::ingestion::error::Error;
+    use crate::metrics::tests::test_metrics;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn store_test_fetch() {
+        let store = Arc::new(RwLock::new(Simulacrum::new()));
+        store.write().unwrap().create_checkpoint();
+
+        let client = IngestionClient::new(
+            Arc::new(StoreIngestionClient::new(store.clone())),
+            test_metrics(),
+        );
+
+        for cp in [0, 1] {
+            let checkpoint = client.fetch(cp).await.unwrap();
+            assert_eq!(checkpoint.checkpoint_summary.sequence_number, cp);
+        }
+
+        // The genesis checkpoint contains the genesis transaction.
+        let genesis = client.fetch(0).await.unwrap();
+        assert_eq!(genesis.transactions.len(), 1);
+
+        assert!(matches!(client.fetch(2).await, Err(Error::NotFound(2))));
+
+        // Checkpoints created while the client is in use are visible to it.
+        store.write().unwrap().create_checkpoint();
+        let checkpoint = client.fetch(2).await.unwrap();
+        assert_eq!(checkpoint.checkpoint_summary.sequence_number, 2);
+    }
+}
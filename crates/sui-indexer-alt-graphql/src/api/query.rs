@@ -3,7 +3,7 @@
 
 use async_graphql::{connection::Connection, Context, Object};
 use futures::future::try_join_all;
-use sui_types::digests::ChainIdentifier;
+use sui_types::{digests::ChainIdentifier, transaction_executor::TransactionChecks};
 
 use crate::{
     error::RpcError,
@@ -17,12 +17,14 @@ use super::{
         address::Address,
         checkpoint::{filter::CheckpointFilter, CCheckpoint, Checkpoint},
         epoch::Epoch,
+        event::{self, filter::EventFilter, CEvent, Event},
         move_package::{self, MovePackage, PackageCheckpointFilter, PackageKey},
         move_type::{self, MoveType},
         object::{self, Object, ObjectKey, VersionFilter},
         object_filter::{ObjectFilter, Validator as OFValidator},
         protocol_configs::ProtocolConfigs,
         service_config::ServiceConfig,
+        simulation_result::{self, SimulationResult},
         transaction::{filter::TransactionFilter, CTransaction, Transaction},
         transaction_effects::TransactionEffects,
     },
@@ -97,6 +99,28 @@ impl Query {
         Epoch::fetch(ctx, scope, epoch_id).await
     }
 
+    /// Paginate events that are emitted in the network, optionally filtered by event filters.
+    ///
+    /// Events are ordered by the transaction that emitted them, and then by their position within that transaction. At most one of `filter.module` and `filter.type` can be specified.
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CEvent>,
+        last: Option<u64>,
+        before: Option<CEvent>,
+        filter: Option<EventFilter>,
+    ) -> Result<Option<Connection<String, Event>>, RpcError<event::Error>> {
+        let scope = self.scope(ctx)?;
+        let pagination: &PaginationConfig = ctx.data()?;
+        let limits = pagination.limits("Query", "events");
+        let page = Page::from_params(limits, first, after, last, before)?;
+
+        Ok(Some(
+            Event::paginate(ctx, scope, page, filter.unwrap_or_default()).await?,
+        ))
+    }
+
     /// Fetch checkpoints by their sequence numbers.
     ///
     /// Returns a list of checkpoints that is guaranteed to be the same length as `keys`. If a checkpoint in `keys` could not be found in the store, its corresponding entry in the result will be `null`. This could be because the checkpoint does not exist yet, or because it was pruned.
@@ -400,6 +424,27 @@ impl Query {
         ServiceConfig
     }
 
+    /// Simulate executing a transaction, without committing its effects on chain.
+    ///
+    /// - `txBytes` is a `TransactionData` struct that has been BCS-encoded and then Base64-encoded.
+    /// - `checksEnabled` controls whether the transaction is checked like a transaction that is about to be executed (defaults to `true`).
+    ///
+    /// The transaction is simulated by a full node, against its latest state. With checks enabled, it must be a valid transaction (with a gas payment, gas budget, etc.) but does not need to be signed. With checks disabled, it is inspected instead: it can be sent from any address and does not need a gas payment (a gas coin is mocked if none is provided), like `devInspectTransactionBlock` in JSON-RPC. Its effects, events, balance changes, and the values returned by each of its commands are returned as they would be if the transaction was executed.
+    async fn simulate_transaction(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: String,
+        checks_enabled: Option<bool>,
+    ) -> Result<SimulationResult, RpcError<simulation_result::Error>> {
+        let checks = if checks_enabled.unwrap_or(true) {
+            TransactionChecks::Enabled
+        } else {
+            TransactionChecks::Disabled
+        };
+
+        SimulationResult::simulate(ctx, self.scope(ctx)?, tx_bytes, checks).await
+    }
+
     /// Fetch a transaction by its digest.
     ///
     /// Returns `null` if the transaction does not exist in the store, either because it never existed or because it was pruned.
@@ -7,6 +7,7 @@ pub(crate) mod cursor;
 pub(crate) mod date_time;
 pub(crate) mod digest;
 pub(crate) mod json;
+pub(crate) mod module_filter;
 pub(crate) mod owner_kind;
 pub(crate) mod sui_address;
 pub(crate) mod type_filter;
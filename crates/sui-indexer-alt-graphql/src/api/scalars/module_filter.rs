@@ -0,0 +1,93 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{fmt, str::FromStr};
+
+use sui_types::{parse_sui_address, parse_sui_module_id};
+
+use crate::api::scalars::{impl_string_input, sui_address::SuiAddress};
+
+/// GraphQL scalar containing a filter on modules. The filter can be one of:
+///
+/// - A package address: `0x2`,
+/// - A module: `0x2::coin`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ModuleFilter {
+    /// Filter by package address
+    Package(SuiAddress),
+    /// Filter by module (package and module name)
+    Module(SuiAddress, String),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Invalid filter format, expected: package[::module]")]
+pub(crate) struct ModuleFilterError;
+
+impl ModuleFilter {
+    /// The package that the filter selects modules from.
+    pub(crate) fn package(&self) -> SuiAddress {
+        match self {
+            ModuleFilter::Package(p) | ModuleFilter::Module(p, _) => *p,
+        }
+    }
+
+    /// The name of the module the filter selects, if it selects a single module.
+    pub(crate) fn module(&self) -> Option<&str> {
+        match self {
+            ModuleFilter::Package(_) => None,
+            ModuleFilter::Module(_, m) => Some(m.as_str()),
+        }
+    }
+}
+
+impl_string_input!(ModuleFilter);
+
+impl FromStr for ModuleFilter {
+    type Err = ModuleFilterError;
+
+    fn from_str(s: &str) -> Result<Self, ModuleFilterError> {
+        if let Ok(module) = parse_sui_module_id(s) {
+            Ok(ModuleFilter::Module(
+                SuiAddress::from(*module.address()),
+                module.name().to_string(),
+            ))
+        } else if let Ok(package) = parse_sui_address(s) {
+            Ok(ModuleFilter::Package(package.into()))
+        } else {
+            Err(ModuleFilterError)
+        }
+    }
+}
+
+impl fmt::Display for ModuleFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModuleFilter::Package(p) => write!(f, "{p}"),
+            ModuleFilter::Module(p, m) => write!(f, "{p}::{m}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_module_filter() {
+        let filter = ModuleFilter::from_str("0x2").unwrap();
+        assert!(matches!(filter, ModuleFilter::Package(_)));
+        assert_eq!(filter.module(), None);
+
+        let filter = ModuleFilter::from_str("0x2::coin").unwrap();
+        assert!(matches!(filter, ModuleFilter::Module(_, ref m) if m == "coin"));
+        assert_eq!(filter.module(), Some("coin"));
+    }
+
+    #[test]
+    fn test_invalid_module_filter() {
+        assert!(ModuleFilter::from_str("not_valid").is_err());
+        assert!(ModuleFilter::from_str("0x2::").is_err());
+        assert!(ModuleFilter::from_str("::module").is_err());
+        assert!(ModuleFilter::from_str("0x2::coin::Coin").is_err());
+    }
+}
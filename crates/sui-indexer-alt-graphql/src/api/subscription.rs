@@ -0,0 +1,337 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::Context as _;
+use async_graphql::{connection::CursorType, Context};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use tokio::time::timeout;
+
+use crate::{
+    api::scalars::uint53::UInt53,
+    config::Limits,
+    error::{bad_user_input, resource_exhausted, RpcError},
+    pagination::{Page, PaginationConfig},
+    scope::Scope,
+    task::watermark::WatermarksReceiver,
+};
+
+use super::types::{
+    checkpoint::Checkpoint,
+    event::{self, filter::EventFilter, CEvent, Event},
+    transaction::{filter::TransactionFilter, CTransaction, Transaction},
+};
+
+pub struct Subscription;
+
+/// Counts the subscriptions that are active on a single WebSocket connection, so that the number
+/// that can be open at once can be limited.
+#[derive(Default)]
+pub(crate) struct ConnectionSubscriptions(Arc<AtomicU32>);
+
+/// A subscription's claim on one of its connection's slots, released when it is dropped (when the
+/// subscription's stream ends or is closed).
+struct SubscriptionSlot(Arc<AtomicU32>);
+
+#[derive(thiserror::Error, Debug)]
+enum Error {
+    #[error(
+        "Cannot stream checkpoint {checkpoint}, the earliest checkpoint that can be streamed is \
+         {earliest}"
+    )]
+    CatchUp { checkpoint: u64, earliest: u64 },
+
+    #[error("Cannot have more than {0} active subscriptions on one connection")]
+    TooManySubscriptions(u32),
+}
+
+/// Subscriptions stream data from the network over a WebSocket connection, as soon as it has been indexed and can be read.
+#[async_graphql::Subscription]
+impl Subscription {
+    /// Stream checkpoints as they are indexed, starting with the checkpoint after the latest checkpoint at the time of subscribing.
+    ///
+    /// A checkpoint is only sent once its data can be read, and its fields are resolved as if it were the latest checkpoint.
+    async fn checkpoints(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = async_graphql::Result<Checkpoint>>, RpcError> {
+        Ok(checkpoint_scopes(ctx, None, None, None)?
+            .map_ok(|scope| Checkpoint {
+                sequence_number: scope.checkpoint_viewed_at(),
+                scope,
+            })
+            .map_err(async_graphql::Error::from))
+    }
+
+    /// Stream transactions as they are indexed, in the order they were finalized.
+    ///
+    /// By default, the stream starts with transactions in the checkpoint after the latest checkpoint at the time of subscribing, and continues indefinitely. `filter.afterCheckpoint` or `filter.atCheckpoint` can be used to start from an earlier checkpoint, and `filter.beforeCheckpoint` or `filter.atCheckpoint` can be used to end the stream.
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TransactionFilter>,
+    ) -> Result<impl Stream<Item = async_graphql::Result<Transaction>>, RpcError> {
+        let filter = filter.unwrap_or_default();
+        let (lo, hi) = checkpoint_range(
+            filter.after_checkpoint,
+            filter.at_checkpoint,
+            filter.before_checkpoint,
+        );
+
+        Ok(checkpoint_scopes(ctx, lo, hi, Some("tx_digests"))?
+            .and_then(move |scope| with_timeout(ctx, transactions_at(ctx, scope)))
+            .map_ok(|transactions| stream::iter(transactions.into_iter().map(Ok::<_, RpcError>)))
+            .try_flatten()
+            .map_err(async_graphql::Error::from))
+    }
+
+    /// Stream events matching the filter as they are indexed, in the order they were emitted.
+    ///
+    /// By default, the stream starts with events from the checkpoint after the latest checkpoint at the time of subscribing, and continues indefinitely. `filter.afterCheckpoint` or `filter.atCheckpoint` can be used to start from an earlier checkpoint, and `filter.beforeCheckpoint` or `filter.atCheckpoint` can be used to end the stream. At most one of `filter.module` and `filter.type` can be specified.
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<EventFilter>,
+    ) -> Result<impl Stream<Item = async_graphql::Result<Event>>, RpcError<event::Error>> {
+        let filter = filter.unwrap_or_default();
+        if filter.module.is_some() && filter.type_.is_some() {
+            return Err(bad_user_input(event::Error::ModuleAndType));
+        }
+
+        let (lo, hi) = checkpoint_range(
+            filter.after_checkpoint,
+            filter.at_checkpoint,
+            filter.before_checkpoint,
+        );
+
+        let pipeline = if filter.type_.is_some() {
+            "ev_struct_inst"
+        } else {
+            "ev_emit_mod"
+        };
+
+        Ok(checkpoint_scopes(ctx, lo, hi, Some(pipeline))
+            .map_err(RpcError::widen::<event::Error>)?
+            .map_err(RpcError::widen)
+            .and_then(move |scope| with_timeout(ctx, events_at(ctx, scope, filter.clone())))
+            .map_ok(|events| stream::iter(events.into_iter().map(Ok::<_, RpcError<event::Error>>)))
+            .try_flatten()
+            .map_err(async_graphql::Error::from))
+    }
+}
+
+/// Convert checkpoint bounds from a filter into an inclusive lower bound and an exclusive upper
+/// bound on the checkpoints to stream.
+fn checkpoint_range(
+    after: Option<UInt53>,
+    at: Option<UInt53>,
+    before: Option<UInt53>,
+) -> (Option<u64>, Option<u64>) {
+    let lo = [after.map(|a| u64::from(a) + 1), at.map(u64::from)]
+        .into_iter()
+        .flatten()
+        .max();
+
+    let hi = [before.map(u64::from), at.map(|a| u64::from(a) + 1)]
+        .into_iter()
+        .flatten()
+        .min();
+
+    (lo, hi)
+}
+
+/// A stream of scopes, one for each checkpoint in the range `[lo, hi)`, each viewing data as of
+/// its checkpoint. A scope is only produced once the watermark task reports that its checkpoint
+/// can be read.
+///
+/// If `lo` is not provided, the stream starts from the checkpoint after the latest checkpoint at
+/// the time the stream is first polled. If `hi` is not provided, the stream continues until the
+/// service shuts down.
+///
+/// The stream claims one of its connection's subscription slots for as long as it is open, and
+/// fails if it falls too far behind the latest checkpoint, or behind the reader low watermark of
+/// `pipeline` (the pipeline its data is read from), if there is one.
+fn checkpoint_scopes<'c>(
+    ctx: &'c Context<'_>,
+    lo: Option<u64>,
+    hi: Option<u64>,
+    pipeline: Option<&'static str>,
+) -> Result<impl Stream<Item = Result<Scope, RpcError>> + 'c, RpcError> {
+    let receiver: &WatermarksReceiver = ctx.data()?;
+    let subscriptions: &ConnectionSubscriptions = ctx.data()?;
+    let limits: &Limits = ctx.data()?;
+
+    let slot = subscriptions.claim(limits.max_subscriptions_per_connection)?;
+    let max_catch_up = limits.max_subscription_catch_up as u64;
+
+    Ok(stream::try_unfold(
+        (receiver.clone(), lo, slot),
+        move |(mut receiver, mut next, slot)| async move {
+            let scope = next_scope(ctx, &mut receiver, &mut next, hi, pipeline, max_catch_up).await;
+            scope.map(|scope| scope.map(|scope| (scope, (receiver, next, slot))))
+        },
+    ))
+}
+
+/// Wait for the checkpoint at `next` to become readable, and return a scope viewing data as of
+/// that checkpoint, advancing `next` past it. Returns `None` if the stream should end, either
+/// because it has reached `hi`, or because the service is shutting down.
+///
+/// Fails if `next` is more than `max_catch_up` checkpoints behind the latest checkpoint, or is
+/// below the reader low watermark for `pipeline`.
+async fn next_scope(
+    ctx: &Context<'_>,
+    receiver: &mut WatermarksReceiver,
+    next: &mut Option<u64>,
+    hi: Option<u64>,
+    pipeline: Option<&'static str>,
+    max_catch_up: u64,
+) -> Result<Option<Scope>, RpcError> {
+    loop {
+        let watermarks = receiver.borrow_and_update().clone();
+        if let Some(watermarks) = watermarks {
+            let latest = watermarks.high_watermark().checkpoint();
+            let checkpoint = *next.get_or_insert(latest + 1);
+
+            if hi.is_some_and(|hi| checkpoint >= hi) {
+                return Ok(None);
+            }
+
+            let reader_lo = pipeline
+                .map(|p| watermarks.pipeline_lo_watermark(p))
+                .transpose()?
+                .map_or(0, |w| w.checkpoint());
+
+            let earliest = reader_lo.max(latest.saturating_sub(max_catch_up));
+            if checkpoint < earliest {
+                return Err(resource_exhausted(Error::CatchUp {
+                    checkpoint,
+                    earliest,
+                }));
+            }
+
+            if checkpoint <= latest {
+                let scope = Scope::from_watermarks(ctx, watermarks)?
+                    .with_checkpoint_viewed_at(checkpoint)
+                    .context("Checkpoint in the future")?;
+
+                *next = Some(checkpoint + 1);
+                return Ok(Some(scope));
+            }
+        }
+
+        // The sender is only dropped when the watermark task shuts down, along with the service.
+        if receiver.changed().await.is_err() {
+            return Ok(None);
+        }
+    }
+}
+
+/// Limit the time spent fetching the data for a single checkpoint to the query timeout (the time
+/// spent waiting for the checkpoint to become readable is not counted).
+async fn with_timeout<T, E: std::error::Error>(
+    ctx: &Context<'_>,
+    f: impl Future<Output = Result<T, RpcError<E>>>,
+) -> Result<T, RpcError<E>> {
+    let limits: &Limits = ctx.data()?;
+    let limit = limits.timeouts().query;
+    timeout(limit, f)
+        .await
+        .unwrap_or(Err(RpcError::RequestTimeout {
+            kind: "Subscription",
+            limit,
+        }))
+}
+
+/// All the transactions in the checkpoint that `scope` is viewing data as of.
+async fn transactions_at(ctx: &Context<'_>, scope: Scope) -> Result<Vec<Transaction>, RpcError> {
+    let pagination: &PaginationConfig = ctx.data()?;
+    let limits = pagination.limits("Subscription", "transactions");
+    let filter = TransactionFilter {
+        at_checkpoint: Some(scope.checkpoint_viewed_at().into()),
+        ..Default::default()
+    };
+
+    let mut transactions = vec![];
+    let mut after = None;
+    loop {
+        let page = Page::from_params(limits, Some(limits.max as u64), after, None, None)?;
+        let conn = Transaction::paginate(ctx, scope.clone(), page, filter.clone()).await?;
+
+        let has_next_page = conn.has_next_page;
+        after = conn
+            .edges
+            .last()
+            .map(|edge| CTransaction::decode_cursor(&edge.cursor))
+            .transpose()
+            .context("Failed to decode transaction cursor")?;
+
+        transactions.extend(conn.edges.into_iter().map(|edge| edge.node));
+        if !has_next_page {
+            return Ok(transactions);
+        }
+    }
+}
+
+/// All the events matching `filter` in the checkpoint that `scope` is viewing data as of.
+async fn events_at(
+    ctx: &Context<'_>,
+    scope: Scope,
+    filter: EventFilter,
+) -> Result<Vec<Event>, RpcError<event::Error>> {
+    let pagination: &PaginationConfig = ctx.data()?;
+    let limits = pagination.limits("Subscription", "events");
+    let filter = EventFilter {
+        after_checkpoint: None,
+        at_checkpoint: Some(scope.checkpoint_viewed_at().into()),
+        before_checkpoint: None,
+        ..filter
+    };
+
+    let mut events = vec![];
+    let mut after = None;
+    loop {
+        let page = Page::from_params(limits, Some(limits.max as u64), after, None, None)?;
+        let conn = Event::paginate(ctx, scope.clone(), page, filter.clone()).await?;
+
+        let has_next_page = conn.has_next_page;
+        after = conn
+            .edges
+            .last()
+            .map(|edge| CEvent::decode_cursor(&edge.cursor))
+            .transpose()
+            .context("Failed to decode event cursor")?;
+
+        events.extend(conn.edges.into_iter().map(|edge| edge.node));
+        if !has_next_page {
+            return Ok(events);
+        }
+    }
+}
+
+impl ConnectionSubscriptions {
+    /// Claim a slot for a new subscription on this connection, failing if the connection already
+    /// has `max` active subscriptions.
+    fn claim(&self, max: u32) -> Result<SubscriptionSlot, RpcError> {
+        self.0
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .map_err(|_| resource_exhausted(Error::TooManySubscriptions(max)))?;
+
+        Ok(SubscriptionSlot(self.0.clone()))
+    }
+}
+
+impl Drop for SubscriptionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
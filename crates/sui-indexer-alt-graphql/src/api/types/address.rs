@@ -5,7 +5,7 @@ use async_graphql::{
     connection::{Connection, Edge},
     Context, Interface, Object,
 };
-use sui_types::base_types::SuiAddress as NativeSuiAddress;
+use sui_types::{base_types::SuiAddress as NativeSuiAddress, dynamic_field::DynamicFieldType};
 
 use crate::{
     api::scalars::{owner_kind::OwnerKind, sui_address::SuiAddress},
@@ -15,6 +15,7 @@ use crate::{
 };
 
 use super::{
+    dynamic_field::{DynamicField, DynamicFieldName},
     move_object::MoveObject,
     move_package::MovePackage,
     object::{self, Object},
@@ -62,6 +63,40 @@ impl Address {
         AddressableImpl::from(self).address()
     }
 
+    /// Access a dynamic field on this address, by its name. Returns `null` if the dynamic field does not exist.
+    pub(crate) async fn dynamic_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, RpcError<object::Error>> {
+        AddressableImpl::from(self).dynamic_field(ctx, name).await
+    }
+
+    /// Access a dynamic object field on this address, by its name. Returns `null` if the dynamic object field does not exist.
+    pub(crate) async fn dynamic_object_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, RpcError<object::Error>> {
+        AddressableImpl::from(self)
+            .dynamic_object_field(ctx, name)
+            .await
+    }
+
+    /// Dynamic fields and dynamic object fields owned by this address.
+    pub(crate) async fn dynamic_fields(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<object::CLive>,
+        last: Option<u64>,
+        before: Option<object::CLive>,
+    ) -> Result<Option<Connection<String, DynamicField>>, RpcError<object::Error>> {
+        AddressableImpl::from(self)
+            .dynamic_fields(ctx, first, after, last, before)
+            .await
+    }
+
     /// Objects owned by this address, optionally filtered by type.
     pub(crate) async fn objects(
         &self,
@@ -91,6 +126,54 @@ impl AddressableImpl<'_> {
         self.0.address.into()
     }
 
+    pub(crate) async fn dynamic_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, RpcError<object::Error>> {
+        DynamicField::by_name(
+            ctx,
+            self.0.scope.clone(),
+            self.0.address,
+            DynamicFieldType::DynamicField,
+            name,
+        )
+        .await
+    }
+
+    pub(crate) async fn dynamic_object_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, RpcError<object::Error>> {
+        DynamicField::by_name(
+            ctx,
+            self.0.scope.clone(),
+            self.0.address,
+            DynamicFieldType::DynamicObject,
+            name,
+        )
+        .await
+    }
+
+    pub(crate) async fn dynamic_fields(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<object::CLive>,
+        last: Option<u64>,
+        before: Option<object::CLive>,
+    ) -> Result<Option<Connection<String, DynamicField>>, RpcError<object::Error>> {
+        let pagination: &PaginationConfig = ctx.data()?;
+        let limits = pagination.limits("IAddressable", "dynamicFields");
+        let page = Page::from_params(limits, first, after, last, before)?;
+
+        let fields =
+            DynamicField::paginate(ctx, self.0.scope.clone(), page, self.0.address).await?;
+
+        Ok(Some(fields))
+    }
+
     pub(crate) async fn objects(
         &self,
         ctx: &Context<'_>,
@@ -0,0 +1,245 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use async_graphql::{
+    connection::{Connection, Edge},
+    Context, InputObject, Object, Union,
+};
+use futures::future::try_join_all;
+use move_core_types::{annotated_value as A, language_storage::StructTag};
+use sui_types::{
+    base_types::SuiAddress as NativeSuiAddress,
+    dynamic_field::{
+        derive_dynamic_field_id,
+        visitor::{FieldVisitor, ValueMetadata},
+        DynamicFieldInfo, DynamicFieldType, DYNAMIC_FIELD_FIELD_STRUCT_NAME,
+        DYNAMIC_FIELD_MODULE_NAME,
+    },
+    object::MoveObject as NativeMoveObject,
+    TypeTag, SUI_FRAMEWORK_ADDRESS,
+};
+
+use crate::{
+    api::scalars::{
+        base64::Base64,
+        owner_kind::OwnerKind,
+        sui_address::SuiAddress,
+        type_filter::{TypeFilter, TypeInput},
+        uint53::UInt53,
+    },
+    error::RpcError,
+    pagination::Page,
+    scope::Scope,
+};
+
+use super::{
+    move_object::MoveObject,
+    move_type::MoveType,
+    move_value::MoveValue,
+    object::{self, CLive, Object},
+    object_filter::ObjectFilter,
+};
+
+pub(crate) struct DynamicField {
+    /// Representation of the `0x2::dynamic_field::Field` object that stores this dynamic field.
+    super_: MoveObject,
+
+    /// The contents of the `0x2::dynamic_field::Field` object.
+    native: Arc<NativeMoveObject>,
+
+    scope: Scope,
+}
+
+/// The name of a dynamic field, identified by its type and BCS serialization.
+#[derive(InputObject, Debug, Clone)]
+pub(crate) struct DynamicFieldName {
+    /// The type of the dynamic field's name, like `u64` or `0x2::kiosk::Listing`.
+    pub(crate) type_: TypeInput,
+
+    /// The Base64-encoded BCS serialization of the dynamic field's name.
+    pub(crate) bcs: Base64,
+}
+
+/// The value of a dynamic field. For dynamic fields this is a Move value, and for dynamic object fields it is the object that the field points to.
+#[derive(Union)]
+pub(crate) enum DynamicFieldValue {
+    MoveObject(MoveObject),
+    MoveValue(MoveValue),
+}
+
+/// A dynamic field or dynamic object field, attached to an address or object.
+///
+/// Dynamic fields are stored in `0x2::dynamic_field::Field` objects, owned by the address or object they are attached to, which hold the field's name and value. The value of a dynamic object field is an object, owned by the `Field` object, which holds its ID.
+#[Object]
+impl DynamicField {
+    /// The address of the `0x2::dynamic_field::Field` object that stores this dynamic field.
+    async fn address(&self) -> SuiAddress {
+        self.super_.address().await
+    }
+
+    /// The version of the `0x2::dynamic_field::Field` object that stores this dynamic field.
+    async fn version(&self) -> UInt53 {
+        self.super_.version().await
+    }
+
+    /// 32-byte hash that identifies the contents of the `0x2::dynamic_field::Field` object that stores this dynamic field, encoded in Base58.
+    async fn digest(&self) -> String {
+        self.super_.digest().await
+    }
+
+    /// The dynamic field's name, as a Move value.
+    async fn name(&self) -> Result<Option<MoveValue>, RpcError<object::Error>> {
+        let Some(layout) = self.layout().await? else {
+            return Ok(None);
+        };
+
+        let field = FieldVisitor::deserialize(self.native.contents(), &layout)
+            .context("Failed to deserialize dynamic field")?;
+
+        let type_ = MoveType::from_native(TypeTag::from(field.name_layout), self.scope.clone());
+        Ok(Some(MoveValue::new(type_, field.name_bytes.to_owned())))
+    }
+
+    /// The dynamic field's value. For a dynamic field, this is the Move value stored in the field. For a dynamic object field, this is the object that the field points to, as of the checkpoint being viewed.
+    async fn value(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<DynamicFieldValue>, RpcError<object::Error>> {
+        let Some(layout) = self.layout().await? else {
+            return Ok(None);
+        };
+
+        let field = FieldVisitor::deserialize(self.native.contents(), &layout)
+            .context("Failed to deserialize dynamic field")?;
+
+        let metadata = field
+            .value_metadata()
+            .context("Failed to deserialize dynamic field value")?;
+
+        match metadata {
+            ValueMetadata::DynamicField(tag) => {
+                let type_ = MoveType::from_native(tag, self.scope.clone());
+                let value = MoveValue::new(type_, field.value_bytes.to_owned());
+                Ok(Some(DynamicFieldValue::MoveValue(value)))
+            }
+
+            ValueMetadata::DynamicObjectField(id) => {
+                let checkpoint = self.scope.checkpoint_viewed_at().into();
+                let Some(object) =
+                    Object::checkpoint_bounded(ctx, self.scope.clone(), id.into(), checkpoint)
+                        .await?
+                else {
+                    return Ok(None);
+                };
+
+                let object = MoveObject::from_object(&object, ctx).await?;
+                Ok(object.map(DynamicFieldValue::MoveObject))
+            }
+        }
+    }
+}
+
+impl DynamicField {
+    /// Fetch the dynamic field (or dynamic object field, depending on `kind`) attached to `parent`
+    /// with the given `name`, as of the checkpoint being viewed. Returns `None` if there is no
+    /// such field.
+    pub(crate) async fn by_name(
+        ctx: &Context<'_>,
+        scope: Scope,
+        parent: NativeSuiAddress,
+        kind: DynamicFieldType,
+        name: DynamicFieldName,
+    ) -> Result<Option<Self>, RpcError<object::Error>> {
+        let type_ = TypeTag::from(name.type_);
+        let type_ = match kind {
+            DynamicFieldType::DynamicField => type_,
+            DynamicFieldType::DynamicObject => TypeTag::Struct(Box::new(
+                DynamicFieldInfo::dynamic_object_field_wrapper(type_),
+            )),
+        };
+
+        let id = derive_dynamic_field_id(parent, &type_, &name.bcs.0)
+            .context("Failed to derive dynamic field ID")?;
+
+        let checkpoint = scope.checkpoint_viewed_at().into();
+        let Some(object) = Object::checkpoint_bounded(ctx, scope, id.into(), checkpoint).await?
+        else {
+            return Ok(None);
+        };
+
+        Self::from_object(&object, ctx).await
+    }
+
+    /// Paginate the dynamic fields attached to `parent` in the live object set.
+    pub(crate) async fn paginate(
+        ctx: &Context<'_>,
+        scope: Scope,
+        page: Page<CLive>,
+        parent: NativeSuiAddress,
+    ) -> Result<Connection<String, DynamicField>, RpcError<object::Error>> {
+        let field_type = StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            module: DYNAMIC_FIELD_MODULE_NAME.to_owned(),
+            name: DYNAMIC_FIELD_FIELD_STRUCT_NAME.to_owned(),
+            type_params: vec![],
+        };
+
+        let filter = ObjectFilter {
+            owner_kind: Some(OwnerKind::Object),
+            owner: Some(parent.into()),
+            type_: Some(TypeFilter::Type(field_type)),
+        };
+
+        let objects = Object::paginate_live(ctx, scope, page, filter).await?;
+        let fields = try_join_all(
+            objects
+                .edges
+                .iter()
+                .map(|edge| Self::from_object(&edge.node, ctx)),
+        )
+        .await?;
+
+        let mut conn = Connection::new(objects.has_previous_page, objects.has_next_page);
+        for (edge, field) in objects.edges.into_iter().zip(fields) {
+            if let Some(field) = field {
+                conn.edges.push(Edge::new(edge.cursor, field));
+            }
+        }
+
+        Ok(conn)
+    }
+
+    /// Try to downcast an `Object` to a `DynamicField`. Returns `None` if the object's contents
+    /// cannot be fetched, or it is not a `0x2::dynamic_field::Field`.
+    pub(crate) async fn from_object(
+        object: &Object,
+        ctx: &Context<'_>,
+    ) -> Result<Option<Self>, RpcError<object::Error>> {
+        let Some(super_) = MoveObject::from_object(object, ctx).await? else {
+            return Ok(None);
+        };
+
+        let Some(native) = super_.native(ctx).await?.clone() else {
+            return Ok(None);
+        };
+
+        if !native.type_().is_dynamic_field() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            super_,
+            native,
+            scope: object.super_.scope.clone(),
+        }))
+    }
+
+    /// The layout of the `0x2::dynamic_field::Field` object, if its type is valid.
+    async fn layout(&self) -> Result<Option<A::MoveTypeLayout>, RpcError<object::Error>> {
+        let type_ = MoveType::from_native(self.native.type_().clone().into(), self.scope.clone());
+        type_.layout_impl().await.map_err(RpcError::widen)
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::InputObject;
+use move_core_types::account_address::AccountAddress;
+use sui_types::{base_types::SuiAddress as NativeSuiAddress, event::Event as NativeEvent};
+
+use crate::api::scalars::{
+    module_filter::ModuleFilter, sui_address::SuiAddress, type_filter::TypeFilter, uint53::UInt53,
+};
+
+#[derive(InputObject, Debug, Default, Clone)]
+pub(crate) struct EventFilter {
+    /// Limit to events that occured strictly after the given checkpoint.
+    pub after_checkpoint: Option<UInt53>,
+
+    /// Limit to events in the given checkpoint.
+    pub at_checkpoint: Option<UInt53>,
+
+    /// Limit to events that occured strictly before the given checkpoint.
+    pub before_checkpoint: Option<UInt53>,
+
+    /// Limit to events emitted by transactions sent by this address.
+    pub sender: Option<SuiAddress>,
+
+    /// Limit to events emitted by a particular module. An event is emitted by the module containing the function that a programmable transaction called, which emitted the event (directly or indirectly). The filter can be one of:
+    ///
+    /// - A package address: `0x2`,
+    /// - A module: `0x2::coin`.
+    ///
+    /// Cannot be combined with a filter on the event's type.
+    pub module: Option<ModuleFilter>,
+
+    /// Limit to events whose type matches this filter. The filter can be one of:
+    ///
+    /// - A package address: `0x2`,
+    /// - A module: `0x2::coin`,
+    /// - A fully-qualified name: `0x2::coin::Coin`,
+    /// - A type instantiation: `0x2::coin::Coin<0x2::sui::SUI>`.
+    ///
+    /// Cannot be combined with a filter on the module that emitted the event.
+    pub type_: Option<TypeFilter>,
+}
+
+impl EventFilter {
+    /// Whether `event` matches this filter, ignoring checkpoint bounds. Transactions are selected
+    /// if they emitted any matching events, so the events they emitted still need to be filtered
+    /// individually.
+    pub(crate) fn matches(&self, event: &NativeEvent) -> bool {
+        if self
+            .sender
+            .is_some_and(|sender| event.sender != NativeSuiAddress::from(sender))
+        {
+            return false;
+        }
+
+        if let Some(module) = &self.module {
+            let package: AccountAddress = module.package().into();
+            if *event.package_id != package
+                || module
+                    .module()
+                    .is_some_and(|m| event.transaction_module.as_str() != m)
+            {
+                return false;
+            }
+        }
+
+        let Some(type_) = &self.type_ else {
+            return true;
+        };
+
+        let tag = &event.type_;
+        match type_ {
+            TypeFilter::Package(p) => tag.address == AccountAddress::from(*p),
+            TypeFilter::Module(p, m) => {
+                tag.address == AccountAddress::from(*p) && tag.module.as_str() == m
+            }
+            TypeFilter::Type(t) => {
+                tag.address == t.address
+                    && tag.module == t.module
+                    && tag.name == t.name
+                    && (t.type_params.is_empty() || tag.type_params == t.type_params)
+            }
+        }
+    }
+}
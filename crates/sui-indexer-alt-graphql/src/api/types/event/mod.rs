@@ -0,0 +1,369 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Context as _;
+use async_graphql::{
+    connection::{Connection, CursorType, Edge},
+    dataloader::DataLoader,
+    Context, Object,
+};
+use diesel::{ExpressionMethods, QueryDsl};
+use futures::future::try_join_all;
+use move_core_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+use sui_indexer_alt_reader::{kv_loader::KvLoader, pg_reader::PgReader, tx_digests::TxDigestKey};
+use sui_indexer_alt_schema::schema::{ev_emit_mod, ev_struct_inst};
+use sui_types::{
+    base_types::SuiAddress as NativeSuiAddress, digests::TransactionDigest,
+    event::Event as NativeEvent,
+};
+
+use crate::{
+    api::scalars::{
+        base64::Base64, cursor::BcsCursor, date_time::DateTime, type_filter::TypeFilter,
+        uint53::UInt53,
+    },
+    error::{bad_user_input, RpcError},
+    pagination::Page,
+    scope::Scope,
+};
+
+use super::{
+    address::Address,
+    checkpoint::filter::checkpoint_bounds,
+    move_type::MoveType,
+    move_value::MoveValue,
+    transaction::{tx_bounds, Transaction},
+};
+
+use filter::EventFilter;
+
+pub(crate) mod filter;
+
+#[derive(Clone)]
+pub(crate) struct Event {
+    pub(crate) scope: Scope,
+    pub(crate) native: NativeEvent,
+    /// Digest of the transaction that emitted this event
+    pub(crate) transaction_digest: TransactionDigest,
+    /// Position of this event within the transaction's events list (0-indexed)
+    pub(crate) sequence_number: u64,
+    /// Timestamp when the transaction containing this event was finalized (checkpoint time), if
+    /// it has been finalized.
+    pub(crate) timestamp_ms: Option<u64>,
+}
+
+/// Identifies an event by the sequence number of the transaction that emitted it, and its position
+/// among that transaction's events.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct EventCursor {
+    pub tx_sequence_number: u64,
+    pub ev_sequence_number: u64,
+}
+
+pub(crate) type CEvent = BcsCursor<EventCursor>;
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Events cannot be filtered by both the module that emitted them and their type")]
+    ModuleAndType,
+}
+
+// TODO(DVX-1200): Support sendingModule - MoveModule
+#[Object]
+impl Event {
+    /// The Move value emitted for this event.
+    async fn contents(&self) -> Option<MoveValue> {
+        let type_ = MoveType::from_native(self.native.type_.clone().into(), self.scope.clone());
+        Some(MoveValue::new(type_, self.native.contents.clone()))
+    }
+
+    /// The Base64 encoded BCS serialized bytes of the entire Event structure from sui-types.
+    /// This includes: package_id, transaction_module, sender, type, and contents (which itself contains the BCS-serialized Move struct data).
+    async fn event_bcs(&self) -> Result<Option<Base64>, RpcError> {
+        let bcs_bytes = bcs::to_bytes(&self.native).context("Failed to serialize event")?;
+        Ok(Some(Base64(bcs_bytes)))
+    }
+
+    /// Address of the sender of the transaction that emitted this event.
+    async fn sender(&self) -> Option<Address> {
+        if self.native.sender == NativeSuiAddress::ZERO {
+            return None;
+        }
+
+        Some(Address::with_address(
+            self.scope.clone(),
+            self.native.sender,
+        ))
+    }
+
+    /// The position of the event among the events from the same transaction.
+    async fn sequence_number(&self) -> UInt53 {
+        UInt53::from(self.sequence_number)
+    }
+
+    /// Timestamp corresponding to the checkpoint this event's transaction was finalized in.
+    /// All events from the same transaction share the same timestamp.
+    async fn timestamp(&self) -> Result<Option<DateTime>, RpcError> {
+        let Some(timestamp_ms) = self.timestamp_ms else {
+            return Ok(None);
+        };
+
+        Ok(Some(DateTime::from_ms(timestamp_ms as i64)?))
+    }
+
+    /// The transaction that emitted this event. This information is only available for events from indexed transactions, and not from transactions that have just been executed or dry-run.
+    async fn transaction(&self) -> Option<Transaction> {
+        Some(Transaction::with_id(
+            self.scope.clone(),
+            self.transaction_digest,
+        ))
+    }
+}
+
+impl Event {
+    /// Paginate events emitted by transactions in the network, filtered by `filter`.
+    ///
+    /// Events are ordered by the transaction that emitted them, and then by their position within
+    /// that transaction.
+    pub(crate) async fn paginate(
+        ctx: &Context<'_>,
+        scope: Scope,
+        page: Page<CEvent>,
+        filter: EventFilter,
+    ) -> Result<Connection<String, Event>, RpcError<Error>> {
+        let mut conn = Connection::new(false, false);
+
+        if filter.module.is_some() && filter.type_.is_some() {
+            return Err(bad_user_input(Error::ModuleAndType));
+        }
+
+        if page.limit() == 0 {
+            return Ok(conn);
+        }
+
+        let watermarks = scope.watermarks();
+
+        let pipeline = if filter.type_.is_some() {
+            "ev_struct_inst"
+        } else {
+            "ev_emit_mod"
+        };
+
+        let reader_lo = watermarks.pipeline_lo_watermark(pipeline)?.checkpoint();
+
+        let global_tx_hi = watermarks.high_watermark().transaction();
+
+        let Some(cp_bounds) = checkpoint_bounds(
+            filter.after_checkpoint.map(u64::from),
+            filter.at_checkpoint.map(u64::from),
+            filter.before_checkpoint.map(u64::from),
+            reader_lo,
+            scope.checkpoint_viewed_at(),
+        ) else {
+            return Ok(conn);
+        };
+
+        let tx_bounds = tx_bounds(ctx, &cp_bounds, global_tx_hi).await?;
+        let tx_sequence_numbers = tx_emitted(ctx, tx_bounds, &page, &filter).await?;
+
+        // Load the transaction digests for the matching tx_sequence_numbers, and then the
+        // transactions themselves, to get at their events.
+        let pg_loader: &Arc<DataLoader<PgReader>> = ctx.data()?;
+        let digests = pg_loader
+            .load_many(tx_sequence_numbers.iter().map(|t| TxDigestKey(*t)))
+            .await
+            .context("Failed to load transaction digests")?;
+
+        let kv_loader: &KvLoader = ctx.data()?;
+        let transactions = try_join_all(tx_sequence_numbers.iter().map(|t| async {
+            let Some(stored) = digests.get(&TxDigestKey(*t)) else {
+                return Ok(None);
+            };
+
+            let digest = TransactionDigest::try_from(stored.tx_digest.clone())
+                .context("Failed to deserialize transaction digest")?;
+
+            let transaction = kv_loader
+                .load_one_transaction(digest)
+                .await
+                .context("Failed to fetch transaction contents")?;
+
+            Ok::<_, RpcError<Error>>(transaction.map(|tx| (*t, digest, tx)))
+        }))
+        .await?;
+
+        // Transactions were selected because they emitted at least one matching event, but they
+        // may have emitted other events as well, and the transactions at either end of the page
+        // may contain events beyond the page's cursors.
+        let mut events = vec![];
+        for (tx_sequence_number, digest, transaction) in transactions.into_iter().flatten() {
+            let timestamp_ms = transaction.timestamp_ms();
+            for (ix, native) in transaction.events()?.into_iter().enumerate() {
+                let cursor = EventCursor {
+                    tx_sequence_number,
+                    ev_sequence_number: ix as u64,
+                };
+
+                if page.after().is_some_and(|a| cursor < **a)
+                    || page.before().is_some_and(|b| cursor > **b)
+                    || !filter.matches(&native)
+                {
+                    continue;
+                }
+
+                let event = Event {
+                    scope: scope.clone(),
+                    native,
+                    transaction_digest: digest,
+                    sequence_number: ix as u64,
+                    timestamp_ms,
+                };
+
+                events.push((cursor, event));
+            }
+        }
+
+        let (prev, next, results) = page.paginate_results(events, |(c, _)| BcsCursor::new(*c));
+
+        conn.has_previous_page = prev;
+        conn.has_next_page = next;
+
+        for (cursor, (_, event)) in results {
+            conn.edges.push(Edge::new(cursor.encode_cursor(), event));
+        }
+
+        Ok(conn)
+    }
+}
+
+/// The tx_sequence_numbers of transactions within `tx_bounds` that emitted events matching
+/// `filter`, with cursors applied inclusively (the transactions that emitted the events pointed to
+/// by the cursors are included, because they may have emitted other events in the page).
+///
+/// Every transaction returned emitted at least one matching event, so results are limited to
+/// `page.limit() + 2` transactions, which is enough to fill the page and detect whether there are
+/// previous or next pages.
+async fn tx_emitted(
+    ctx: &Context<'_>,
+    tx_bounds: Range<u64>,
+    page: &Page<CEvent>,
+    filter: &EventFilter,
+) -> Result<Vec<u64>, RpcError<Error>> {
+    // Inclusive cursor bounds
+    let pg_lo = page.after().map_or(tx_bounds.start, |c| {
+        c.tx_sequence_number.max(tx_bounds.start)
+    });
+    let pg_hi = page
+        .before()
+        .map(|c| c.tx_sequence_number.saturating_add(1))
+        .map_or(tx_bounds.end, |c| c.min(tx_bounds.end));
+
+    if pg_hi <= pg_lo {
+        return Ok(vec![]);
+    }
+
+    let pg_reader: &PgReader = ctx.data()?;
+    let mut conn = pg_reader
+        .connect()
+        .await
+        .context("Failed to connect to database")?;
+
+    let sender = filter.sender.map(|s| AccountAddress::from(s).to_vec());
+
+    let mut results: Vec<i64> = if let Some(type_) = &filter.type_ {
+        use ev_struct_inst::dsl as e;
+
+        let mut query = e::ev_struct_inst
+            .select(e::tx_sequence_number)
+            .filter(e::tx_sequence_number.ge(pg_lo as i64))
+            .filter(e::tx_sequence_number.lt(pg_hi as i64))
+            .limit(page.limit_with_overhead() as i64)
+            .into_boxed();
+
+        // A transaction can emit events of multiple types that match the filter, unless the filter
+        // is on an exact type instantiation.
+        query = match type_ {
+            TypeFilter::Package(p) => query
+                .filter(e::package.eq(AccountAddress::from(*p).to_vec()))
+                .distinct(),
+
+            TypeFilter::Module(p, m) => query
+                .filter(e::package.eq(AccountAddress::from(*p).to_vec()))
+                .filter(e::module.eq(m.clone()))
+                .distinct(),
+
+            TypeFilter::Type(t) if t.type_params.is_empty() => query
+                .filter(e::package.eq(t.address.to_vec()))
+                .filter(e::module.eq(t.module.to_string()))
+                .filter(e::name.eq(t.name.to_string()))
+                .distinct(),
+
+            TypeFilter::Type(t) => {
+                let instantiation =
+                    bcs::to_bytes(&t.type_params).context("Failed to serialize type parameters")?;
+
+                query
+                    .filter(e::package.eq(t.address.to_vec()))
+                    .filter(e::module.eq(t.module.to_string()))
+                    .filter(e::name.eq(t.name.to_string()))
+                    .filter(e::instantiation.eq(instantiation))
+            }
+        };
+
+        if let Some(sender) = sender {
+            query = query.filter(e::sender.eq(sender));
+        }
+
+        query = if page.is_from_front() {
+            query.order_by(e::tx_sequence_number)
+        } else {
+            query.order_by(e::tx_sequence_number.desc())
+        };
+
+        conn.results(query).await
+    } else {
+        use ev_emit_mod::dsl as e;
+
+        let mut query = e::ev_emit_mod
+            .select(e::tx_sequence_number)
+            .filter(e::tx_sequence_number.ge(pg_lo as i64))
+            .filter(e::tx_sequence_number.lt(pg_hi as i64))
+            .limit(page.limit_with_overhead() as i64)
+            .into_boxed();
+
+        if let Some(module) = &filter.module {
+            query = query.filter(e::package.eq(AccountAddress::from(module.package()).to_vec()));
+        }
+
+        // A transaction can emit events from multiple modules, unless the filter is on a single
+        // module.
+        query = match filter.module.as_ref().and_then(|m| m.module()) {
+            Some(name) => query.filter(e::module.eq(name.to_owned())),
+            None => query.distinct(),
+        };
+
+        if let Some(sender) = sender {
+            query = query.filter(e::sender.eq(sender));
+        }
+
+        query = if page.is_from_front() {
+            query.order_by(e::tx_sequence_number)
+        } else {
+            query.order_by(e::tx_sequence_number.desc())
+        };
+
+        conn.results(query).await
+    }
+    .context("Failed to fetch transactions that emitted events")?;
+
+    // Graphql last syntax expects results to be in ascending order. If we are paginating backwards,
+    // we reverse the results after applying limits.
+    if !page.is_from_front() {
+        results.reverse();
+    }
+
+    Ok(results.into_iter().map(|t| t as u64).collect())
+}
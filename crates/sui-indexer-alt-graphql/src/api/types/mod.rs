@@ -4,6 +4,7 @@
 pub(crate) mod address;
 pub(crate) mod balance_change;
 pub(crate) mod checkpoint;
+pub(crate) mod dynamic_field;
 pub(crate) mod epoch;
 pub(crate) mod event;
 pub(crate) mod execution_error;
@@ -21,6 +22,7 @@ pub(crate) mod object_filter;
 pub(crate) mod protocol_configs;
 pub(crate) mod safe_mode;
 pub(crate) mod service_config;
+pub(crate) mod simulation_result;
 mod stake_subsidy;
 pub(crate) mod storage_fund;
 pub(crate) mod system_parameters;
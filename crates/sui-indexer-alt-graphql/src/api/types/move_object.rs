@@ -15,6 +15,7 @@ use crate::{
 
 use super::{
     address::AddressableImpl,
+    dynamic_field::{DynamicField, DynamicFieldName},
     move_type::MoveType,
     move_value::MoveValue,
     object::{self, CLive, CVersion, Object, ObjectImpl, VersionFilter},
@@ -141,6 +142,42 @@ impl MoveObject {
             .await
     }
 
+    /// Access a dynamic field on this object, by its name. Returns `null` if the dynamic field does not exist.
+    pub(crate) async fn dynamic_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, RpcError<object::Error>> {
+        AddressableImpl::from(&self.super_.super_)
+            .dynamic_field(ctx, name)
+            .await
+    }
+
+    /// Access a dynamic object field on this object, by its name. Returns `null` if the dynamic object field does not exist.
+    pub(crate) async fn dynamic_object_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, RpcError<object::Error>> {
+        AddressableImpl::from(&self.super_.super_)
+            .dynamic_object_field(ctx, name)
+            .await
+    }
+
+    /// Dynamic fields and dynamic object fields owned by this object.
+    pub(crate) async fn dynamic_fields(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CLive>,
+        last: Option<u64>,
+        before: Option<CLive>,
+    ) -> Result<Option<Connection<String, DynamicField>>, RpcError<object::Error>> {
+        AddressableImpl::from(&self.super_.super_)
+            .dynamic_fields(ctx, first, after, last, before)
+            .await
+    }
+
     /// Objects owned by this object, optionally filtered by type.
     pub(crate) async fn objects(
         &self,
@@ -198,7 +235,7 @@ impl MoveObject {
     }
 
     /// Get the native MoveObject, loading it lazily if needed.
-    async fn native(
+    pub(crate) async fn native(
         &self,
         ctx: &Context<'_>,
     ) -> Result<&Option<Arc<NativeMoveObject>>, RpcError<object::Error>> {
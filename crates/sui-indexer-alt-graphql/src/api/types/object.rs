@@ -46,6 +46,7 @@ use crate::{
 
 use super::{
     address::{Address, AddressableImpl},
+    dynamic_field::{DynamicField, DynamicFieldName},
     move_object::MoveObject,
     move_package::MovePackage,
     object_filter::{ObjectFilter, Validator as OFValidator},
@@ -263,6 +264,42 @@ impl Object {
             .await
     }
 
+    /// Access a dynamic field on this object, by its name. Returns `null` if the dynamic field does not exist.
+    pub(crate) async fn dynamic_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, RpcError<Error>> {
+        AddressableImpl::from(&self.super_)
+            .dynamic_field(ctx, name)
+            .await
+    }
+
+    /// Access a dynamic object field on this object, by its name. Returns `null` if the dynamic object field does not exist.
+    pub(crate) async fn dynamic_object_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, RpcError<Error>> {
+        AddressableImpl::from(&self.super_)
+            .dynamic_object_field(ctx, name)
+            .await
+    }
+
+    /// Dynamic fields and dynamic object fields owned by this object.
+    pub(crate) async fn dynamic_fields(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CLive>,
+        last: Option<u64>,
+        before: Option<CLive>,
+    ) -> Result<Option<Connection<String, DynamicField>>, RpcError<Error>> {
+        AddressableImpl::from(&self.super_)
+            .dynamic_fields(ctx, first, after, last, before)
+            .await
+    }
+
     /// Objects owned by this object, optionally filtered by type.
     pub(crate) async fn objects(
         &self,
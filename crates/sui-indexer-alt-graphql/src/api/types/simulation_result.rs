@@ -0,0 +1,217 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_graphql::{Context, Object, SimpleObject};
+use fastcrypto::encoding::{Base64, Encoding};
+use sui_indexer_alt_reader::{
+    full_node_client::{self, FullNodeClient},
+    kv_loader::TransactionContents as NativeTransactionContents,
+};
+use sui_indexer_alt_schema::transactions::BalanceChange as NativeBalanceChange;
+use sui_rpc_api::client::{
+    CommandOutput as NativeCommandOutput, CommandResult as NativeCommandResult,
+    TransactionSimulationResponse,
+};
+use sui_types::{
+    object::Owner, transaction::TransactionData, transaction_executor::TransactionChecks,
+};
+
+use crate::{
+    error::{bad_user_input, feature_unavailable, RpcError},
+    scope::Scope,
+};
+
+use super::{
+    event::Event,
+    move_type::MoveType,
+    move_value::MoveValue,
+    transaction_effects::{EffectsContents, TransactionEffects},
+    transaction_kind::programmable::commands::TransactionArgument,
+};
+
+pub(crate) struct SimulationResult {
+    effects: Option<TransactionEffects>,
+    outputs: Option<Vec<CommandResult>>,
+    error: Option<String>,
+}
+
+/// The results of a single command in a simulated programmable transaction.
+#[derive(SimpleObject)]
+pub(crate) struct CommandResult {
+    /// The values returned by the command.
+    return_values: Option<Vec<CommandOutput>>,
+
+    /// The values of arguments that were passed to the command by mutable reference, after the command ran.
+    mutated_by_ref: Option<Vec<CommandOutput>>,
+}
+
+/// A value produced by a command in a simulated programmable transaction.
+#[derive(SimpleObject)]
+pub(crate) struct CommandOutput {
+    /// The argument this value was written back to, for arguments passed by mutable reference.
+    argument: Option<TransactionArgument>,
+
+    /// The value itself.
+    value: Option<MoveValue>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Invalid Base64 in txBytes: {0}")]
+    Base64(fastcrypto::error::FastCryptoError),
+
+    #[error("Failed to deserialize TransactionData from txBytes: {0}")]
+    Bcs(bcs::Error),
+}
+
+/// The result of simulating a transaction, including the predicted effects of executing it.
+#[Object]
+impl SimulationResult {
+    /// The predicted effects of executing the transaction, or `null` if the transaction could not be simulated.
+    async fn effects(&self) -> Option<TransactionEffects> {
+        self.effects.clone()
+    }
+
+    /// The events the transaction is predicted to emit, or `null` if the transaction could not be simulated.
+    async fn events(&self) -> Result<Option<Vec<Event>>, RpcError> {
+        let Some(effects) = &self.effects else {
+            return Ok(None);
+        };
+
+        let contents = &effects.contents;
+        let Some(content) = &contents.contents else {
+            return Ok(None);
+        };
+
+        let events = content
+            .events()?
+            .into_iter()
+            .enumerate()
+            .map(|(ix, native)| Event {
+                scope: contents.scope.clone(),
+                native,
+                transaction_digest: effects.digest,
+                sequence_number: ix as u64,
+                timestamp_ms: None,
+            })
+            .collect();
+
+        Ok(Some(events))
+    }
+
+    /// The results of each command in the transaction, if it is a programmable transaction that executed successfully (empty otherwise).
+    async fn outputs(&self) -> Option<&Vec<CommandResult>> {
+        self.outputs.as_ref()
+    }
+
+    /// The reason the transaction could not be simulated, if it was rejected before execution.
+    ///
+    /// Errors during execution (e.g. aborts) do not prevent simulation, and are reported in the effects instead.
+    async fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+}
+
+impl SimulationResult {
+    /// Simulate the transaction whose `TransactionData` is BCS-encoded and then Base64-encoded in
+    /// `tx_bytes`, using the full node, with or without transaction `checks`.
+    pub(crate) async fn simulate(
+        ctx: &Context<'_>,
+        scope: Scope,
+        tx_bytes: String,
+        checks: TransactionChecks,
+    ) -> Result<Self, RpcError<Error>> {
+        let bytes = Base64::decode(&tx_bytes).map_err(|e| bad_user_input(Error::Base64(e)))?;
+
+        let transaction: TransactionData =
+            bcs::from_bytes(&bytes).map_err(|e| bad_user_input(Error::Bcs(e)))?;
+
+        let full_node: &FullNodeClient = ctx.data()?;
+        let response = match full_node.simulate_transaction(&transaction, checks).await {
+            Ok(response) => response,
+
+            Err(full_node_client::Error::Rejected(status)) => {
+                return Ok(Self {
+                    effects: None,
+                    outputs: None,
+                    error: Some(status.message().to_owned()),
+                })
+            }
+
+            Err(full_node_client::Error::NotConfigured) => {
+                return Err(feature_unavailable("simulating transactions"))
+            }
+
+            Err(full_node_client::Error::Internal(error)) => return Err(error.into()),
+        };
+
+        Ok(Self::from_response(scope, transaction, response))
+    }
+
+    fn from_response(
+        scope: Scope,
+        transaction: TransactionData,
+        response: TransactionSimulationResponse,
+    ) -> Self {
+        let TransactionSimulationResponse {
+            effects,
+            events,
+            balance_changes,
+            outputs,
+        } = response;
+
+        let balance_changes = balance_changes
+            .into_iter()
+            .map(|change| NativeBalanceChange::V1 {
+                owner: Owner::AddressOwner(change.address.into()),
+                coin_type: change.coin_type.to_string(),
+                amount: change.amount,
+            })
+            .collect();
+
+        let outputs = outputs
+            .into_iter()
+            .map(|result| CommandResult::from_native(&scope, result))
+            .collect();
+
+        let digest = transaction.digest();
+        let contents = NativeTransactionContents::Executed {
+            data: Box::new(transaction),
+            signatures: vec![],
+            effects: Box::new(effects),
+            events,
+            balance_changes,
+        };
+
+        Self {
+            effects: Some(TransactionEffects {
+                digest,
+                contents: EffectsContents {
+                    scope,
+                    contents: Some(Arc::new(contents)),
+                },
+            }),
+            outputs: Some(outputs),
+            error: None,
+        }
+    }
+}
+
+impl CommandResult {
+    fn from_native(scope: &Scope, native: NativeCommandResult) -> Self {
+        let output = |native: NativeCommandOutput| CommandOutput {
+            argument: native.argument.map(TransactionArgument::from),
+            value: Some(MoveValue::new(
+                MoveType::from_native(native.type_, scope.clone()),
+                native.bcs,
+            )),
+        };
+
+        Self {
+            return_values: Some(native.return_values.into_iter().map(output).collect()),
+            mutated_by_ref: Some(native.mutated_by_ref.into_iter().map(output).collect()),
+        }
+    }
+}
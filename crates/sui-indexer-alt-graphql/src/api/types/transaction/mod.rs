@@ -1,7 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{ops::RangeInclusive, sync::Arc};
+use std::{
+    ops::{Range, RangeInclusive},
+    sync::Arc,
+};
 
 use anyhow::Context as _;
 use async_graphql::{
@@ -28,7 +31,6 @@ use crate::{
     error::RpcError,
     pagination::Page,
     scope::Scope,
-    task::watermark::Watermarks,
 };
 
 use super::{
@@ -210,7 +212,7 @@ impl Transaction {
             return Ok(Connection::new(false, false));
         }
 
-        let watermarks: &Arc<Watermarks> = ctx.data()?;
+        let watermarks = scope.watermarks();
 
         let reader_lo = watermarks.pipeline_lo_watermark("tx_digests")?.checkpoint();
 
@@ -264,6 +266,41 @@ impl Transaction {
 /// The tx_sequence_numbers within checkpoint bounds and with cursors applied inclusively.
 /// Results are limited to `page.limit() + 2` to allow has_previous_page and has_next_page calculations.
 ///
+/// NOTE: for consistency, assume that lowerbounds are inclusive and upperbounds are exclusive.
+/// Bounds that do not follow this convention will be annotated explicitly (e.g. `lo_exclusive` or
+/// `hi_inclusive`).
+async fn tx_unfiltered(
+    ctx: &Context<'_>,
+    cp_bounds: &RangeInclusive<u64>,
+    page: &Page<CTransaction>,
+    global_tx_hi: u64,
+) -> Result<Vec<u64>, RpcError> {
+    let Range {
+        start: tx_lo,
+        end: tx_hi,
+    } = tx_bounds(ctx, cp_bounds, global_tx_hi).await?;
+
+    // Inclusive cursor bounds
+    let pg_lo = page.after().map_or(tx_lo, |cursor| cursor.max(tx_lo));
+    let pg_hi = page
+        .before()
+        .map(|cursor| cursor.saturating_add(1))
+        .map_or(tx_hi, |cursor| cursor.min(tx_hi));
+
+    Ok(if page.is_from_front() {
+        (pg_lo..pg_hi).take(page.limit_with_overhead()).collect()
+    } else {
+        // Graphql last syntax expects results to be in ascending order. If we are paginating backwards,
+        // we reverse the results after applying limits.
+        let mut results: Vec<_> = (pg_lo..pg_hi)
+            .rev()
+            .take(page.limit_with_overhead())
+            .collect();
+        results.reverse();
+        results
+    })
+}
+
 /// The checkpoint lower and upper bounds are used to determine the inclusive lower (tx_lo) and exclusive
 /// upper (tx_hi) bounds of the sequence of tx_sequence_numbers to use in queries.
 ///
@@ -271,16 +308,11 @@ impl Transaction {
 /// tx_hi: The tx_lo of the checkpoint directly after the cp_bounds.end(). If it does not exists,
 ///      at cp_bounds.end() fallback to the maximum tx_sequence_number in the context's watermark
 ///      (global_tx_hi).
-///
-/// NOTE: for consistency, assume that lowerbounds are inclusive and upperbounds are exclusive.
-/// Bounds that do not follow this convention will be annotated explicitly (e.g. `lo_exclusive` or
-/// `hi_inclusive`).
-async fn tx_unfiltered(
+pub(crate) async fn tx_bounds<E: std::error::Error>(
     ctx: &Context<'_>,
     cp_bounds: &RangeInclusive<u64>,
-    page: &Page<CTransaction>,
     global_tx_hi: u64,
-) -> Result<Vec<u64>, RpcError> {
+) -> Result<Range<u64>, RpcError<E>> {
     let pg_reader: &PgReader = ctx.data()?;
     let query = query!(
         r#"
@@ -325,30 +357,8 @@ async fn tx_unfiltered(
         .await
         .context("Failed to execute query")?;
 
-    let (tx_lo, tx_hi) = results
-        .first()
-        .context("No valid checkpoints found")
-        .map(|bounds| (bounds.tx_lo as u64, bounds.tx_hi as u64))?;
-
-    // Inclusive cursor bounds
-    let pg_lo = page.after().map_or(tx_lo, |cursor| cursor.max(tx_lo));
-    let pg_hi = page
-        .before()
-        .map(|cursor| cursor.saturating_add(1))
-        .map_or(tx_hi, |cursor| cursor.min(tx_hi));
-
-    Ok(if page.is_from_front() {
-        (pg_lo..pg_hi).take(page.limit_with_overhead()).collect()
-    } else {
-        // Graphql last syntax expects results to be in ascending order. If we are paginating backwards,
-        // we reverse the results after applying limits.
-        let mut results: Vec<_> = (pg_lo..pg_hi)
-            .rev()
-            .take(page.limit_with_overhead())
-            .collect();
-        results.reverse();
-        results
-    })
+    let bounds = results.first().context("No valid checkpoints found")?;
+    Ok(bounds.tx_lo as u64..bounds.tx_hi as u64)
 }
 
 impl TransactionContents {
@@ -381,7 +391,10 @@ impl TransactionContents {
         };
 
         // Discard the loaded result if we are viewing it at a checkpoint before it existed.
-        if transaction.cp_sequence_number() > self.scope.checkpoint_viewed_at() {
+        if transaction
+            .cp_sequence_number()
+            .is_some_and(|cp| cp > self.scope.checkpoint_viewed_at())
+        {
             return Ok(self.clone());
         }
 
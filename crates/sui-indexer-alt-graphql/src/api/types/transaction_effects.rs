@@ -98,7 +98,7 @@ impl EffectsContents {
             return None;
         };
 
-        Checkpoint::with_sequence_number(self.scope.clone(), content.cp_sequence_number())
+        Checkpoint::with_sequence_number(self.scope.clone(), content.cp_sequence_number()?)
     }
 
     /// Whether the transaction executed successfully or not.
@@ -149,11 +149,11 @@ impl EffectsContents {
 
     /// Timestamp corresponding to the checkpoint this transaction was finalized in.
     async fn timestamp(&self) -> Result<Option<DateTime>, RpcError> {
-        let Some(content) = &self.contents else {
+        let Some(timestamp_ms) = self.contents.as_ref().and_then(|c| c.timestamp_ms()) else {
             return Ok(None);
         };
 
-        Ok(Some(DateTime::from_ms(content.timestamp_ms() as i64)?))
+        Ok(Some(DateTime::from_ms(timestamp_ms as i64)?))
     }
 
     /// The epoch this transaction was finalized in.
@@ -222,24 +222,31 @@ impl EffectsContents {
             return Ok(Some(Connection::new(false, false)));
         };
 
-        let transaction_digest = content.digest()?;
-
-        // Load balance changes from database using DataLoader
-        let pg_loader: &Arc<DataLoader<PgReader>> = ctx.data()?;
-        let key = TxBalanceChangeKey(transaction_digest);
-
-        let Some(stored_balance_changes) = pg_loader
-            .load_one(key)
-            .await
-            .context("Failed to load balance changes")?
-        else {
-            return Ok(Some(Connection::new(false, false)));
-        };
-
-        // Deserialize balance changes from BCS bytes
         let balance_changes: Vec<NativeBalanceChange> =
-            bcs::from_bytes(&stored_balance_changes.balance_changes)
-                .context("Failed to deserialize balance changes")?;
+            if let NativeTransactionContents::Executed {
+                balance_changes, ..
+            } = content.as_ref()
+            {
+                balance_changes.clone()
+            } else {
+                let transaction_digest = content.digest()?;
+
+                // Load balance changes from database using DataLoader
+                let pg_loader: &Arc<DataLoader<PgReader>> = ctx.data()?;
+                let key = TxBalanceChangeKey(transaction_digest);
+
+                let Some(stored_balance_changes) = pg_loader
+                    .load_one(key)
+                    .await
+                    .context("Failed to load balance changes")?
+                else {
+                    return Ok(Some(Connection::new(false, false)));
+                };
+
+                // Deserialize balance changes from BCS bytes
+                bcs::from_bytes(&stored_balance_changes.balance_changes)
+                    .context("Failed to deserialize balance changes")?
+            };
 
         let pagination: &PaginationConfig = ctx.data()?;
         let limits = pagination.limits("TransactionEffects", "balanceChanges");
@@ -344,7 +351,11 @@ impl EffectsContents {
 
         let mut conn = Connection::new(cursors.has_previous_page, cursors.has_next_page);
         for edge in cursors.edges {
-            let execution_checkpoint = content.cp_sequence_number();
+            // Transactions that have not been indexed yet are treated as executing at the latest
+            // checkpoint.
+            let execution_checkpoint = content
+                .cp_sequence_number()
+                .unwrap_or_else(|| self.scope.checkpoint_viewed_at());
             let unchanged_consensus_object = UnchangedConsensusObject::from_native(
                 self.scope.clone(),
                 unchanged_consensus_objects[*edge.cursor].clone(),
@@ -446,7 +457,10 @@ impl EffectsContents {
         };
 
         // Discard the loaded result if we are viewing it at a checkpoint before it existed.
-        if transaction.cp_sequence_number() > self.scope.checkpoint_viewed_at() {
+        if transaction
+            .cp_sequence_number()
+            .is_some_and(|cp| cp > self.scope.checkpoint_viewed_at())
+        {
             return Ok(self.clone());
         }
 
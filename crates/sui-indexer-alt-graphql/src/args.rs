@@ -31,11 +31,16 @@ pub enum Command {
         )]
         database_url: Url,
 
-        /// Bigtable instance ID to make KV store requests to. If this is not provided, KV store
-        /// requests will be made to the database.
+        /// Bigtable instance ID to make KV store requests to. If neither this nor
+        /// `--kv-rocksdb-path` is provided, KV store requests will be made to the database.
         #[clap(long)]
         bigtable_instance: Option<String>,
 
+        /// Path to a local RocksDB KV store (as written by `sui-kvstore --rocksdb-path`) to make KV
+        /// store requests to, instead of Bigtable.
+        #[clap(long, conflicts_with = "bigtable_instance")]
+        kv_rocksdb_path: Option<PathBuf>,
+
         #[command(flatten)]
         full_node: FullNodeArgs,
 
@@ -130,6 +130,15 @@ pub struct Limits {
 
     /// Maximum budget in bytes to spend when outputting a structured Move value.
     pub max_move_value_bound: usize,
+
+    /// Maximum number of checkpoints behind the latest checkpoint that a subscription can start
+    /// streaming from. Subscriptions that start further back than this will fail, rather than
+    /// catch up.
+    pub max_subscription_catch_up: u32,
+
+    /// Maximum number of subscriptions that can be active at once on a single WebSocket
+    /// connection.
+    pub max_subscriptions_per_connection: u32,
 }
 
 #[DefaultConfig]
@@ -152,6 +161,8 @@ pub struct LimitsLayer {
     pub max_type_nodes: Option<usize>,
     pub max_move_value_depth: Option<usize>,
     pub max_move_value_bound: Option<usize>,
+    pub max_subscription_catch_up: Option<u32>,
+    pub max_subscriptions_per_connection: Option<u32>,
 
     #[serde(flatten)]
     pub extra: toml::Table,
@@ -306,6 +317,12 @@ impl LimitsLayer {
             max_move_value_bound: self
                 .max_move_value_bound
                 .unwrap_or(base.max_move_value_bound),
+            max_subscription_catch_up: self
+                .max_subscription_catch_up
+                .unwrap_or(base.max_subscription_catch_up),
+            max_subscriptions_per_connection: self
+                .max_subscriptions_per_connection
+                .unwrap_or(base.max_subscriptions_per_connection),
         }
     }
 }
@@ -351,6 +368,8 @@ impl From<Limits> for LimitsLayer {
             max_type_nodes: Some(value.max_type_nodes),
             max_move_value_depth: Some(value.max_move_value_depth),
             max_move_value_bound: Some(value.max_move_value_bound),
+            max_subscription_catch_up: Some(value.max_subscription_catch_up),
+            max_subscriptions_per_connection: Some(value.max_subscriptions_per_connection),
             extra: Default::default(),
         }
     }
@@ -418,6 +437,8 @@ impl Default for Limits {
             max_type_nodes,
             max_move_value_depth,
             max_move_value_bound: 1024 * 1024,
+            max_subscription_catch_up: 1000,
+            max_subscriptions_per_connection: 10,
         }
     }
 }
@@ -100,6 +100,22 @@ impl<E: std::error::Error> From<RpcError<E>> for async_graphql::Error {
     }
 }
 
+impl RpcError {
+    /// Convert an error that cannot be the user's fault into an error that is compatible with
+    /// fields whose user errors are of type `E`.
+    pub(crate) fn widen<E: std::error::Error>(self) -> RpcError<E> {
+        match self {
+            RpcError::BadUserInput(err) => match *err {},
+            RpcError::FeatureUnavailable { what } => RpcError::FeatureUnavailable { what },
+            RpcError::GraphQlError(err) => RpcError::GraphQlError(err),
+            RpcError::InternalError(err) => RpcError::InternalError(err),
+            RpcError::Pagination(err) => RpcError::Pagination(err),
+            RpcError::RequestTimeout { kind, limit } => RpcError::RequestTimeout { kind, limit },
+            RpcError::ResourceExhausted(err) => RpcError::ResourceExhausted(err),
+        }
+    }
+}
+
 // Cannot use `#[from]` for this conversion because [`async_graphql::Error`] does not implement
 // `std::error::Error`, so it cannot participate in the source/chaining APIs.
 impl<E: std::error::Error> From<async_graphql::Error> for RpcError<E> {
@@ -54,6 +54,7 @@ struct QueryLimitsCheckerExt {
 struct ParsedDocument {
     var: Variables,
     doc: ExecutableDocument,
+    length: u64,
 }
 
 struct Usage {
@@ -100,7 +101,12 @@ impl Extension for QueryLimitsCheckerExt {
         variables: &Variables,
         next: NextParseQuery<'_>,
     ) -> ServerResult<ExecutableDocument> {
-        let &ContentLength(length) = ctx.data_unchecked();
+        // Subscriptions are sent over a WebSocket, rather than in an HTTP request with a
+        // `Content-Length`, so the query's length is used as the size of their payload instead.
+        let length = ctx
+            .data_opt::<ContentLength>()
+            .map_or(query.len() as u64, |&ContentLength(length)| length);
+
         if length > self.limits.max_payload_size() as u64 {
             Err(Error::new_global(ErrorKind::PayloadSizeOverall {
                 limit: self.limits.max_payload_size(),
@@ -115,6 +121,7 @@ impl Extension for QueryLimitsCheckerExt {
         *self.doc.lock().unwrap() = Some(ParsedDocument {
             var: variables.clone(),
             doc: doc.clone(),
+            length,
         });
 
         Ok(doc)
@@ -137,11 +144,10 @@ impl Extension for QueryLimitsCheckerExt {
     ) -> Result<ValidationResult, Vec<ServerError>> {
         let res = next.run(ctx).await?;
 
-        let Some(ParsedDocument { doc, var }) = self.doc.lock().unwrap().take() else {
+        let Some(ParsedDocument { doc, var, length }) = self.doc.lock().unwrap().take() else {
             return Ok(res);
         };
 
-        let &ContentLength(length) = ctx.data_unchecked();
         let pagination_config: &PaginationConfig = ctx.data_unchecked();
 
         let _guard = self.metrics.limits_validation_latency.start_timer();
@@ -10,9 +10,12 @@ use std::{
 };
 
 use async_graphql::{
-    extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute, NextParseQuery},
+    extensions::{
+        Extension, ExtensionContext, ExtensionFactory, NextExecute, NextParseQuery, NextResolve,
+        ResolveInfo,
+    },
     parser::types::{ExecutableDocument, OperationType},
-    Response, ServerError, ServerResult, Variables,
+    Response, ServerError, ServerResult, Value, Variables,
 };
 use tokio::time::timeout;
 
@@ -27,11 +30,15 @@ pub(crate) struct TimeoutConfig {
 /// The timeout extension is responsible for limiting the amount of time spent serving any single
 /// request. It is configured by [RpcConfig] which it expects to find in its context. Timeout
 /// durations are configured separately for mutations and for queries.
+///
+/// Subscriptions are not bounded as a whole (they can stream indefinitely), instead each event
+/// they produce is subject to the query timeout.
 pub(crate) struct Timeout(Arc<TimeoutConfig>);
 
 struct TimeoutExt {
     config: Arc<TimeoutConfig>,
     is_mutation: AtomicBool,
+    is_subscription: AtomicBool,
 }
 
 impl Timeout {
@@ -45,6 +52,7 @@ impl ExtensionFactory for Timeout {
         Arc::new(TimeoutExt {
             config: self.0.clone(),
             is_mutation: AtomicBool::new(false),
+            is_subscription: AtomicBool::new(false),
         })
     }
 }
@@ -68,6 +76,14 @@ impl Extension for TimeoutExt {
             Ordering::Relaxed,
         );
 
+        self.is_subscription.store(
+            document
+                .operations
+                .iter()
+                .any(|(_, op)| op.node.ty == OperationType::Subscription),
+            Ordering::Relaxed,
+        );
+
         Ok(document)
     }
 
@@ -91,11 +107,31 @@ impl Extension for TimeoutExt {
                 Response::from_errors(vec![ServerError::from(request_timeout(kind, limit))])
             })
     }
+
+    /// Subscriptions do not go through `execute`. Instead, each event is resolved as its own
+    /// root field, which is where the timeout is applied. Time spent waiting for the next event is
+    /// not counted.
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<Value>> {
+        if !self.is_subscription.load(Ordering::Relaxed) || info.path_node.parent.is_some() {
+            return next.run(ctx, info).await;
+        }
+
+        let limit = self.config.query;
+        timeout(limit, next.run(ctx, info))
+            .await
+            .unwrap_or_else(|_| Err(ServerError::from(request_timeout("Subscription", limit))))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema, Value};
+    use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema, Subscription};
+    use futures::{stream, Stream, StreamExt};
 
     use crate::error::code;
 
@@ -103,6 +139,12 @@ mod tests {
 
     struct Root(Duration);
 
+    /// Produces a single event after waiting `wait`, which takes `delay` to resolve.
+    struct Events {
+        wait: Duration,
+        delay: Duration,
+    }
+
     #[Object]
     impl Root {
         async fn op(&self) -> bool {
@@ -111,6 +153,17 @@ mod tests {
         }
     }
 
+    #[Subscription]
+    impl Events {
+        async fn ops(&self) -> impl Stream<Item = Root> {
+            let (wait, delay) = (self.wait, self.delay);
+            stream::once(async move {
+                tokio::time::sleep(wait).await;
+                Root(delay)
+            })
+        }
+    }
+
     /// The request takes less than the timeout to handle, so it should pass.
     #[tokio::test]
     async fn test_query_timeout_pass() {
@@ -217,4 +270,59 @@ mod tests {
             Some(&Value::String(code::REQUEST_TIMEOUT.into()))
         )
     }
+
+    /// Waiting for a subscription's next event does not count towards the timeout.
+    #[tokio::test]
+    async fn test_subscription_wait_pass() {
+        let zero = Duration::from_millis(0);
+        let delay = Duration::from_millis(200);
+        let events = Events {
+            wait: delay * 2,
+            delay: zero,
+        };
+
+        let response = Schema::build(Root(zero), EmptyMutation, events)
+            .extension(Timeout::new(TimeoutConfig {
+                query: delay,
+                mutation: zero,
+            }))
+            .finish()
+            .execute_stream("subscription { ops { op } }")
+            .next()
+            .await
+            .unwrap();
+
+        assert!(response.is_ok());
+    }
+
+    /// Resolving a subscription's event takes longer than the timeout, so it should fail.
+    #[tokio::test]
+    async fn test_subscription_timeout_fail() {
+        let zero = Duration::from_millis(0);
+        let delay = Duration::from_millis(200);
+        let events = Events {
+            wait: zero,
+            delay: delay * 2,
+        };
+
+        let response = Schema::build(Root(zero), EmptyMutation, events)
+            .extension(Timeout::new(TimeoutConfig {
+                query: delay,
+                mutation: zero,
+            }))
+            .finish()
+            .execute_stream("subscription { ops { op } }")
+            .next()
+            .await
+            .unwrap();
+
+        assert!(response.is_err());
+
+        let error = &response.errors[0];
+        assert!(error.message.contains("Subscription"));
+        assert_eq!(
+            error.extensions.as_ref().unwrap().get("code"),
+            Some(&Value::String(code::REQUEST_TIMEOUT.into()))
+        )
+    }
 }
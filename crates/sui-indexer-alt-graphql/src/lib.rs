@@ -1,19 +1,20 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{any::Any, net::SocketAddr, sync::Arc};
+use std::{any::Any, net::SocketAddr, path::PathBuf, sync::Arc};
 
 use anyhow::{self, Context};
 use api::types::{address::IAddressable, move_object::IMoveObject, object::IObject};
 use async_graphql::{
-    extensions::ExtensionFactory, http::GraphiQLSource, EmptySubscription, ObjectType, Schema,
-    SchemaBuilder, SubscriptionType,
+    extensions::ExtensionFactory,
+    http::{GraphiQLSource, ALL_WEBSOCKET_PROTOCOLS},
+    Data, ObjectType, Schema, SchemaBuilder, SubscriptionType,
 };
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
 use axum::{
-    extract::{ConnectInfo, MatchedPath},
+    extract::{ws::WebSocketUpgrade, ConnectInfo, MatchedPath},
     http::Method,
-    response::Html,
+    response::{Html, Response},
     routing::{get, post, MethodRouter},
     Extension, Router,
 };
@@ -33,8 +34,10 @@ use sui_indexer_alt_reader::{
     consistent_reader::{ConsistentReader, ConsistentReaderArgs},
     full_node_client::{FullNodeArgs, FullNodeClient},
     kv_loader::KvLoader,
+    kv_reader::KvStoreReader,
     package_resolver::{DbPackageStore, PackageCache},
     pg_reader::PgReader,
+    rocksdb_reader::RocksDbReader,
 };
 use task::{
     chain_identifier,
@@ -46,7 +49,11 @@ use tower_http::cors;
 use tracing::{error, info};
 use url::Url;
 
-use crate::api::{mutation::Mutation, query::Query};
+use crate::api::{
+    mutation::Mutation,
+    query::Query,
+    subscription::{ConnectionSubscriptions, Subscription},
+};
 use crate::extensions::logging::{Logging, Session};
 use crate::metrics::RpcMetrics;
 use crate::middleware::version::Version;
@@ -242,8 +249,8 @@ impl Default for RpcArgs {
 }
 
 /// The GraphQL schema this service will serve, without any extensions or context added.
-pub fn schema() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
-    Schema::build(Query::default(), Mutation, EmptySubscription)
+pub fn schema() -> SchemaBuilder<Query, Mutation, Subscription> {
+    Schema::build(Query::default(), Mutation, Subscription)
         .register_output_type::<IAddressable>()
         .register_output_type::<IObject>()
         .register_output_type::<IMoveObject>()
@@ -255,9 +262,9 @@ pub fn schema() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
 ///
 /// Access to most reads is controlled by the `database_url` -- if it is `None`, those reads will
 /// not work. KV queries can optionally be served by a Bigtable instance, if `bigtable_instance` is
-/// provided, otherwise these requests are served by the database. If a `bigtable_instance` is
-/// provided, the `GOOGLE_APPLICATION_CREDENTIALS` environment variable must point to the
-/// credentials JSON file.
+/// provided, or a local RocksDB KV store, if `kv_rocksdb_path` is provided, otherwise these
+/// requests are served by the database. If a `bigtable_instance` is provided, the
+/// `GOOGLE_APPLICATION_CREDENTIALS` environment variable must point to the credentials JSON file.
 ///
 /// `version` is the version string reported in response headers by the service as part of every
 /// request.
@@ -267,6 +274,7 @@ pub fn schema() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
 pub async fn start_rpc(
     database_url: Option<Url>,
     bigtable_instance: Option<String>,
+    kv_rocksdb_path: Option<PathBuf>,
     full_node_args: FullNodeArgs,
     db_args: DbArgs,
     bigtable_args: BigtableArgs,
@@ -294,7 +302,7 @@ pub async fn start_rpc(
     )
     .await?;
 
-    let bigtable_reader = if let Some(instance_id) = bigtable_instance {
+    let kv_reader = if let Some(instance_id) = bigtable_instance {
         let reader = BigtableReader::new(
             instance_id,
             "indexer-alt-graphql".to_owned(),
@@ -303,9 +311,9 @@ pub async fn start_rpc(
         )
         .await?;
 
-        Some(reader)
+        Some(KvStoreReader::new(reader))
     } else {
-        None
+        kv_rocksdb_path.map(|path| KvStoreReader::new(RocksDbReader::new(path)))
     };
 
     let consistent_reader = ConsistentReader::new(
@@ -317,8 +325,8 @@ pub async fn start_rpc(
     .await?;
 
     let pg_loader = Arc::new(pg_reader.as_data_loader());
-    let kv_loader = if let Some(reader) = bigtable_reader.as_ref() {
-        KvLoader::new_with_bigtable(Arc::new(reader.as_data_loader()))
+    let kv_loader = if let Some(reader) = kv_reader.as_ref() {
+        KvLoader::new_with_kv(Arc::new(reader.as_data_loader()))
     } else {
         KvLoader::new_with_pg(pg_loader.clone())
     };
@@ -344,7 +352,7 @@ pub async fn start_rpc(
         config.watermark,
         pg_pipelines,
         pg_reader.clone(),
-        bigtable_reader,
+        kv_reader,
         consistent_reader.clone(),
         metrics.clone(),
         cancel.child_token(),
@@ -353,6 +361,7 @@ pub async fn start_rpc(
     let rpc = rpc
         .route("/graphql", post(graphql))
         .route("/graphql/health", get(health::check))
+        .route("/graphql/subscriptions", get(subscriptions))
         .layer(watermark_task.watermarks())
         .layer(config.health)
         .layer(DbProbe(database_url))
@@ -361,6 +370,7 @@ pub async fn start_rpc(
             config.limits.query_limits(),
             metrics,
         ))
+        .data(watermark_task.subscribe())
         .data(config.limits.pagination())
         .data(config.limits)
         .data(chain_identifier)
@@ -386,7 +396,7 @@ pub async fn start_rpc(
 /// Handler for RPC requests (POST requests making GraphQL queries).
 async fn graphql(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    Extension(schema): Extension<Schema<Query, Mutation, EmptySubscription>>,
+    Extension(schema): Extension<Schema<Query, Mutation, Subscription>>,
     Extension(watermark): Extension<WatermarksLock>,
     TypedHeader(content_length): TypedHeader<ContentLength>,
     show_usage: Option<TypedHeader<ShowUsage>>,
@@ -405,10 +415,41 @@ async fn graphql(
     schema.execute(request).await.into()
 }
 
+/// Handler for subscription requests (GraphQL over WebSocket). Unlike queries, subscriptions do
+/// not view data as of a fixed snapshot of the watermarks, so none is added to the request:
+/// subscriptions track the watermarks as they change instead.
+///
+/// Each connection tracks its own active subscriptions, so that they can be limited per
+/// connection. Timeouts apply to resolving each event a subscription produces, rather than to the
+/// connection as a whole.
+async fn subscriptions(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Extension(schema): Extension<Schema<Query, Mutation, Subscription>>,
+    protocol: GraphQLProtocol,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    upgrade
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| {
+            let mut data = Data::default();
+            data.insert(Session::new(addr));
+            data.insert(ConnectionSubscriptions::default());
+
+            GraphQLWebSocket::new(stream, schema, protocol)
+                .with_data(data)
+                .serve()
+        })
+}
+
 /// Handler for GET requests for the online IDE. GraphQL requests are forwarded to the POST handler
 /// at the same path.
 async fn graphiql(path: MatchedPath) -> Html<String> {
-    Html(GraphiQLSource::build().endpoint(path.as_str()).finish())
+    Html(
+        GraphiQLSource::build()
+            .endpoint(path.as_str())
+            .subscription_endpoint(&format!("{}/subscriptions", path.as_str()))
+            .finish(),
+    )
 }
 
 #[cfg(test)]
@@ -40,6 +40,7 @@ async fn main() -> anyhow::Result<()> {
         Command::Rpc {
             database_url,
             bigtable_instance,
+            kv_rocksdb_path,
             full_node,
             db_args,
             bigtable_args,
@@ -104,6 +105,7 @@ async fn main() -> anyhow::Result<()> {
             let h_rpc = start_rpc(
                 Some(database_url),
                 bigtable_instance,
+                kv_rocksdb_path,
                 full_node,
                 db_args,
                 bigtable_args,
@@ -20,6 +20,10 @@ pub(crate) struct Scope {
     /// are relative to this checkpoint.
     checkpoint_viewed_at: u64,
 
+    /// Snapshot of the watermarks this scope was created with. Bounds on the data that is
+    /// available to be read are derived from these watermarks.
+    watermarks: Arc<Watermarks>,
+
     /// Access to packages for type resolution.
     package_store: Arc<dyn PackageStore>,
 
@@ -31,12 +35,22 @@ impl Scope {
     /// Create a new scope at the top-level (initialized by information we have at the root of a
     /// request).
     pub(crate) fn new<E: std::error::Error>(ctx: &Context<'_>) -> Result<Self, RpcError<E>> {
-        let watermark: &Arc<Watermarks> = ctx.data()?;
+        let watermarks: &Arc<Watermarks> = ctx.data()?;
+        Self::from_watermarks(ctx, watermarks.clone())
+    }
+
+    /// Create a new top-level scope from a snapshot of the watermarks other than the one taken at
+    /// the start of the request (e.g. for each update received by a long-lived subscription).
+    pub(crate) fn from_watermarks<E: std::error::Error>(
+        ctx: &Context<'_>,
+        watermarks: Arc<Watermarks>,
+    ) -> Result<Self, RpcError<E>> {
         let package_store: &Arc<PackageCache> = ctx.data()?;
         let limits: &Limits = ctx.data()?;
 
         Ok(Self {
-            checkpoint_viewed_at: watermark.high_watermark().checkpoint(),
+            checkpoint_viewed_at: watermarks.high_watermark().checkpoint(),
+            watermarks,
             package_store: package_store.clone(),
             resolver_limits: limits.package_resolver(),
         })
@@ -47,6 +61,7 @@ impl Scope {
     pub(crate) fn with_checkpoint_viewed_at(&self, checkpoint_viewed_at: u64) -> Option<Self> {
         (checkpoint_viewed_at <= self.checkpoint_viewed_at).then(|| Self {
             checkpoint_viewed_at,
+            watermarks: self.watermarks.clone(),
             package_store: self.package_store.clone(),
             resolver_limits: self.resolver_limits.clone(),
         })
@@ -62,6 +77,11 @@ impl Scope {
         self.checkpoint_viewed_at + 1
     }
 
+    /// The watermarks this scope was created with.
+    pub(crate) fn watermarks(&self) -> &Watermarks {
+        &self.watermarks
+    }
+
     /// A package resolver with access to the packages known at this scope.
     pub(crate) fn package_resolver(&self) -> Resolver<Arc<dyn PackageStore>> {
         Resolver::new_with_limits(self.package_store.clone(), self.resolver_limits.clone())
@@ -15,12 +15,17 @@ use diesel::{
 };
 use futures::future::OptionFuture;
 use sui_indexer_alt_reader::{
-    bigtable_reader::BigtableReader,
     consistent_reader::{self, proto::AvailableRangeResponse, ConsistentReader},
+    kv_reader::KvStoreReader,
     pg_reader::PgReader,
 };
 use sui_sql_macro::query;
-use tokio::{join, sync::RwLock, task::JoinHandle, time};
+use tokio::{
+    join,
+    sync::{watch, RwLock},
+    task::JoinHandle,
+    time,
+};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
@@ -36,11 +41,14 @@ pub(crate) struct WatermarkTask {
     /// efficiently swap in new watermark values.
     watermarks: WatermarksLock,
 
+    /// Notifies subscribers every time the watermarks are updated.
+    sender: watch::Sender<Option<Arc<Watermarks>>>,
+
     /// Access to the Postgres DB
     pg_reader: PgReader,
 
-    /// Access to Bigtable.
-    bigtable_reader: Option<BigtableReader>,
+    /// Access to the KV store (Bigtable or RocksDB), if one is configured.
+    kv_reader: Option<KvStoreReader>,
 
     /// Access to the Consistent Store
     consistent_reader: ConsistentReader,
@@ -109,12 +117,16 @@ struct WatermarkRow {
 
 pub(crate) type WatermarksLock = Arc<RwLock<Arc<Watermarks>>>;
 
+/// Receives the latest watermarks every time they are updated. Holds `None` until the watermarks
+/// have been read for the first time.
+pub(crate) type WatermarksReceiver = watch::Receiver<Option<Arc<Watermarks>>>;
+
 impl WatermarkTask {
     pub(crate) fn new(
         config: WatermarkConfig,
         pg_pipelines: Vec<String>,
         pg_reader: PgReader,
-        bigtable_reader: Option<BigtableReader>,
+        kv_reader: Option<KvStoreReader>,
         consistent_reader: ConsistentReader,
         metrics: Arc<RpcMetrics>,
         cancel: CancellationToken,
@@ -125,8 +137,9 @@ impl WatermarkTask {
 
         Self {
             watermarks: Default::default(),
+            sender: watch::Sender::new(None),
             pg_reader,
-            bigtable_reader,
+            kv_reader,
             consistent_reader,
             interval: watermark_polling_interval,
             pg_pipelines,
@@ -140,6 +153,12 @@ impl WatermarkTask {
         self.watermarks.clone()
     }
 
+    /// A receiver that is notified every time this task updates the watermarks, for streaming
+    /// data as soon as it can be read.
+    pub(crate) fn subscribe(&self) -> WatermarksReceiver {
+        self.sender.subscribe()
+    }
+
     /// Start a new task that regularly polls the database for watermarks.
     ///
     /// This operation consume the `self` and returns a handle to the spawned tokio task. The task
@@ -148,8 +167,9 @@ impl WatermarkTask {
         tokio::spawn(async move {
             let Self {
                 watermarks,
+                sender,
                 pg_reader,
-                bigtable_reader,
+                kv_reader,
                 consistent_reader,
                 interval,
                 pg_pipelines,
@@ -169,7 +189,7 @@ impl WatermarkTask {
                     }
 
                     _ = interval.tick() => {
-                        let rows = match WatermarkRow::read(&pg_reader, bigtable_reader.as_ref(), &pg_pipelines).await {
+                        let rows = match WatermarkRow::read(&pg_reader, kv_reader.as_ref(), &pg_pipelines).await {
                             Ok(rows) => rows,
                             Err(e) => {
                                 warn!("Failed to read watermarks: {e:#}");
@@ -205,7 +225,9 @@ impl WatermarkTask {
                             "Watermark updated"
                         );
 
-                        *watermarks.write().await = Arc::new(w);
+                        let w = Arc::new(w);
+                        *watermarks.write().await = w.clone();
+                        sender.send_replace(Some(w));
                     }
                 }
             }
@@ -265,17 +287,17 @@ impl Watermark {
 impl WatermarkRow {
     async fn read(
         pg_reader: &PgReader,
-        bigtable_reader: Option<&BigtableReader>,
+        kv_reader: Option<&KvStoreReader>,
         pg_pipelines: &[String],
     ) -> anyhow::Result<Vec<WatermarkRow>> {
         let rows = watermarks_from_pg(pg_reader, pg_pipelines);
-        let last: OptionFuture<_> = bigtable_reader.map(watermark_from_bigtable).into();
+        let last: OptionFuture<_> = kv_reader.map(watermark_from_kv).into();
 
         let (rows, last) = join!(rows, last);
         let mut rows = rows.context("Failed to read watermarks from Postgres")?;
         let last = last
             .transpose()
-            .context("Failed to read watermarks from Bigtable")?;
+            .context("Failed to read watermarks from KV store")?;
 
         rows.extend(last);
         Ok(rows)
@@ -333,15 +355,15 @@ impl Default for Watermarks {
     }
 }
 
-async fn watermark_from_bigtable(bigtable_reader: &BigtableReader) -> anyhow::Result<WatermarkRow> {
-    let summary = bigtable_reader
+async fn watermark_from_kv(kv_reader: &KvStoreReader) -> anyhow::Result<WatermarkRow> {
+    let summary = kv_reader
         .checkpoint_watermark()
         .await
         .context("Failed to get checkpoint watermark")?
         .context("Checkpoint watermark not found")?;
 
     Ok(WatermarkRow {
-        pipeline: "bigtable".to_owned(),
+        pipeline: kv_reader.name().to_owned(),
         epoch_hi_inclusive: summary.epoch as i64,
         checkpoint_hi_inclusive: summary.sequence_number as i64,
         tx_hi: summary.network_total_transactions as i64,
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use anyhow::Context as _;
@@ -11,6 +12,7 @@ use jsonrpsee::{core::RpcResult, http_client::HttpClient, proc_macros::rpc};
 use move_core_types::language_storage::{StructTag, TypeTag};
 use serde::{Deserialize, Serialize};
 use sui_indexer_alt_reader::coin_metadata::CoinMetadataKey;
+use sui_indexer_alt_reader::consistent_reader::proto::owner::OwnerKind;
 use sui_indexer_alt_schema::objects::StoredCoinOwnerKind;
 use sui_indexer_alt_schema::schema::coin_balance_buckets;
 use sui_json_rpc_types::{Balance, Coin, Page as PageResponse, SuiCoinMetadata};
@@ -25,6 +27,7 @@ use sui_types::{
 
 use crate::{
     config::NodeConfig,
+    consistency::consistent_error,
     context::Context,
     data::load_live,
     error::{client_error_to_error_object, invalid_params, InternalContext, RpcError},
@@ -34,7 +37,7 @@ use crate::{
 use super::rpc_module::RpcModule;
 
 #[open_rpc(namespace = "suix", tag = "Coin API")]
-#[rpc(server, namespace = "suix")]
+#[rpc(server, client, namespace = "suix")]
 trait CoinsApi {
     /// Return Coin objects owned by an address with a specified coin type.
     /// If no coin type is specified, SUI coins are returned.
@@ -60,6 +63,24 @@ trait CoinsApi {
         /// type name for the coin (e.g., 0x168da5bf1f48dafc111b0a488fa454aca95e0b5e::usdc::USDC)
         coin_type: String,
     ) -> RpcResult<Option<SuiCoinMetadata>>;
+
+    /// Return the total coin balance for one coin type, owned by the address.
+    /// If no coin type is specified, SUI coin balance is returned.
+    ///
+    /// If `atCheckpoint` is provided, the balance is read as of the end of that checkpoint from
+    /// the consistent store. The checkpoint must be one that the consistent store holds a
+    /// snapshot for, otherwise the request will fail. Otherwise, the latest balance is fetched
+    /// from the fullnode, which fails if the RPC is not configured with one.
+    #[method(name = "getBalance")]
+    async fn get_balance(
+        &self,
+        /// the owner's Sui address
+        owner: SuiAddress,
+        /// optional type names for the coin (e.g., 0x168da5bf1f48dafc111b0a488fa454aca95e0b5e::usdc::USDC), default to 0x2::sui::SUI if not specified.
+        coin_type: Option<String>,
+        /// optional checkpoint to read the balance at, defaults to the latest checkpoint
+        at_checkpoint: Option<u64>,
+    ) -> RpcResult<Balance>;
 }
 
 /// Delegation Coin API for endpoints that are delegated to FN RPC
@@ -73,29 +94,27 @@ trait DelegationCoinsApi {
         /// the owner's Sui address
         owner: SuiAddress,
     ) -> RpcResult<Vec<Balance>>;
-
-    /// Return the total coin balance for one coin type, owned by the address.
-    /// If no coin type is specified, SUI coin balance is returned.
-    #[method(name = "getBalance")]
-    async fn get_balance(
-        &self,
-        /// the owner's Sui address
-        owner: SuiAddress,
-        /// optional type names for the coin (e.g., 0x168da5bf1f48dafc111b0a488fa454aca95e0b5e::usdc::USDC), default to 0x2::sui::SUI if not specified.
-        coin_type: Option<String>,
-    ) -> RpcResult<Balance>;
 }
 
-pub(crate) struct Coins(pub Context);
+pub(crate) struct Coins(Context, Option<HttpClient>);
 pub(crate) struct DelegationCoins(HttpClient);
 
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum Error {
+    #[error(transparent)]
+    Consistency(#[from] crate::consistency::Error),
+
     #[error("Pagination issue: {0}")]
     Pagination(#[from] crate::paginate::Error),
 
     #[error("Failed to parse type {0:?}: {1}")]
     BadType(String, anyhow::Error),
+
+    #[error(
+        "Latest balances require a fullnode, which this RPC is not configured with. Provide a \
+         checkpoint to read the balance at instead"
+    )]
+    NoFullnode,
 }
 
 #[derive(Queryable, Debug, Serialize, Deserialize)]
@@ -108,6 +127,17 @@ struct BalanceCursor {
 
 type Cursor = BcsCursor<BalanceCursor>;
 
+impl Coins {
+    /// Latest balances are fetched from the fullnode at `fullnode_rpc_url`, if it is provided.
+    pub fn new(fullnode_rpc_url: Option<url::Url>, ctx: Context) -> anyhow::Result<Self> {
+        let client = fullnode_rpc_url
+            .map(|url| ctx.config().node.client(url))
+            .transpose()?;
+
+        Ok(Self(ctx, client))
+    }
+}
+
 impl DelegationCoins {
     pub fn new(fullnode_rpc_url: url::Url, config: NodeConfig) -> anyhow::Result<Self> {
         let client = config.client(fullnode_rpc_url)?;
@@ -131,7 +161,7 @@ impl CoinsApiServer for Coins {
             GAS::type_tag()
         };
 
-        let Self(ctx) = self;
+        let Self(ctx, _) = self;
         let config = &ctx.config().coins;
 
         let page: Page<Cursor> = Page::from_params::<Error>(
@@ -162,34 +192,47 @@ impl CoinsApiServer for Coins {
     }
 
     async fn get_coin_metadata(&self, coin_type: String) -> RpcResult<Option<SuiCoinMetadata>> {
-        let Self(ctx) = self;
+        let Self(ctx, _) = self;
 
         Ok(coin_metadata_response(ctx, &coin_type)
             .await
             .with_internal_context(|| format!("Failed to fetch CoinMetadata for {coin_type:?}"))?)
     }
-}
 
-#[async_trait::async_trait]
-impl DelegationCoinsApiServer for DelegationCoins {
-    async fn get_all_balances(&self, owner: SuiAddress) -> RpcResult<Vec<Balance>> {
-        let Self(client) = self;
+    async fn get_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        at_checkpoint: Option<u64>,
+    ) -> RpcResult<Balance> {
+        let Self(ctx, client) = self;
+
+        if let Some(checkpoint) = at_checkpoint {
+            return Ok(balance_at_checkpoint(ctx, owner, coin_type, checkpoint)
+                .await
+                .with_internal_context(|| {
+                    format!("Failed to get balance for {owner} at checkpoint {checkpoint}")
+                })?);
+        }
+
+        let Some(client) = client else {
+            return Err(invalid_params(Error::NoFullnode).into());
+        };
 
         client
-            .get_all_balances(owner)
+            .get_balance(owner, coin_type, None)
             .await
             .map_err(client_error_to_error_object)
     }
+}
 
-    async fn get_balance(
-        &self,
-        owner: SuiAddress,
-        coin_type: Option<String>,
-    ) -> RpcResult<Balance> {
+#[async_trait::async_trait]
+impl DelegationCoinsApiServer for DelegationCoins {
+    async fn get_all_balances(&self, owner: SuiAddress) -> RpcResult<Vec<Balance>> {
         let Self(client) = self;
 
         client
-            .get_balance(owner, coin_type)
+            .get_all_balances(owner)
             .await
             .map_err(client_error_to_error_object)
     }
@@ -382,6 +425,66 @@ async fn coin_metadata_response(
     Ok(Some(coin_metadata))
 }
 
+/// Read the balance of `coin_type` coins owned by `owner`, as of checkpoint `checkpoint`, from the
+/// consistent store. The consistent store does not track how many coins make up the balance, so
+/// they are counted by paginating through the owner's coins at that checkpoint, up to the
+/// configured maximum (after which the count saturates).
+async fn balance_at_checkpoint(
+    ctx: &Context,
+    owner: SuiAddress,
+    coin_type: Option<String>,
+    checkpoint: u64,
+) -> Result<Balance, RpcError<Error>> {
+    let coin_type_tag = if let Some(coin_type) = coin_type {
+        sui_types::parse_sui_type_tag(&coin_type)
+            .map_err(|e| invalid_params(Error::BadType(coin_type, e)))?
+    } else {
+        GAS::type_tag()
+    };
+
+    let coin_type = coin_type_tag.to_canonical_string(/* with_prefix */ true);
+    let reader = ctx.consistent_reader();
+
+    let balance = reader
+        .get_balance(checkpoint, owner.to_string(), coin_type.clone())
+        .await
+        .map_err(|e| consistent_error(checkpoint, e))?;
+
+    let coin_struct = sui_types::coin::Coin::type_(coin_type_tag)
+        .to_canonical_string(/* with_prefix */ true);
+    let max_count = ctx.config().coins.max_coins_counted_at_checkpoint;
+    let mut coin_object_count = 0;
+    let mut after_token = None;
+    while coin_object_count < max_count {
+        let page = reader
+            .list_owned_objects(
+                checkpoint,
+                OwnerKind::Address,
+                Some(owner.to_string()),
+                Some(coin_struct.clone()),
+                None,
+                after_token,
+                None,
+                /* is_from_front */ true,
+            )
+            .await
+            .map_err(|e| consistent_error(checkpoint, e))?;
+
+        coin_object_count += page.results.len();
+        after_token = page.results.last().map(|edge| edge.token.clone());
+        if !page.has_next_page || after_token.is_none() {
+            break;
+        }
+    }
+
+    Ok(Balance {
+        coin_type,
+        coin_object_count: coin_object_count.min(max_count),
+        total_balance: balance.balance() as u128,
+        locked_balance: HashMap::new(),
+    })
+}
+
 async fn object_with_coin_data(
     ctx: &Context,
     id: ObjectID,
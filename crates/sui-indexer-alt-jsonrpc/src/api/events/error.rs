@@ -0,0 +1,16 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_types::digests::TransactionDigest;
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Transaction {0} not found")]
+    NotFound(TransactionDigest),
+
+    #[error("Cursor points to transaction {0}, which was not found")]
+    CursorNotFound(TransactionDigest),
+
+    #[error("Pagination issue: {0}")]
+    Pagination(#[from] crate::paginate::Error),
+}
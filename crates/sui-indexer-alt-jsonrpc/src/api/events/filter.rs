@@ -0,0 +1,450 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context as _;
+use diesel::{
+    expression::{
+        is_aggregate::{Never, No},
+        MixedAggregates, ValidGrouping,
+    },
+    pg::Pg,
+    query_builder::{BoxedSelectStatement, FromClause, QueryFragment},
+    sql_types::BigInt as SqlBigInt,
+    AppearsOnTable, Column, Expression, ExpressionMethods, QueryDsl, QuerySource,
+};
+use futures::future;
+use move_core_types::{account_address::AccountAddress, language_storage::StructTag};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use sui_indexer_alt_reader::tx_digests::TxDigestKey;
+use sui_indexer_alt_schema::schema::{ev_emit_mod, ev_struct_inst, tx_digests};
+use sui_json_rpc_types::{EventPage, Page as PageResponse};
+use sui_sql_macro::sql;
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    digests::TransactionDigest,
+    event::{Event, EventID},
+    sui_serde::SuiStructTag,
+    Identifier,
+};
+
+use crate::{
+    context::Context,
+    error::{invalid_params, RpcError},
+    paginate::Error as PaginationError,
+};
+
+use super::{error::Error, response};
+
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+pub(crate) enum EventFilter {
+    /// Query by sender address.
+    Sender(SuiAddress),
+    /// Return events emitted by the given transaction.
+    Transaction(TransactionDigest),
+    /// Return events emitted in a specified Move module. If the event is defined in Module A but
+    /// emitted in a transaction that called into Module B, querying `MoveModule` by module B
+    /// returns the event.
+    MoveModule {
+        /// The Move package ID.
+        package: ObjectID,
+        /// The module name.
+        #[schemars(with = "String")]
+        #[serde_as(as = "DisplayFromStr")]
+        module: Identifier,
+    },
+    /// Return events with the given Move event struct name (struct tag). If the struct tag has no
+    /// type parameters, events of all instantiations of the struct are returned.
+    MoveEventType(
+        #[schemars(with = "String")]
+        #[serde_as(as = "SuiStructTag")]
+        StructTag,
+    ),
+    /// Return events with the given Move module name where the event struct is defined. If the
+    /// event is defined in Module A but emitted in a transaction that called into Module B,
+    /// querying `MoveEventModule` by module A returns the event.
+    MoveEventModule {
+        /// The Move package ID.
+        package: ObjectID,
+        /// The module name.
+        #[schemars(with = "String")]
+        #[serde_as(as = "DisplayFromStr")]
+        module: Identifier,
+    },
+}
+
+/// Fetch a page of events that satisfy the given `filter` and pagination parameters.
+///
+/// Events are ordered by the transaction that emitted them, and then by their position in that
+/// transaction. Like on fullnodes, the cursor is the ID of the last event on the previous page,
+/// and the next page starts from the event after it (in the requested order).
+pub(super) async fn events(
+    ctx: &Context,
+    filter: &EventFilter,
+    cursor: Option<EventID>,
+    limit: Option<usize>,
+    descending_order: Option<bool>,
+) -> Result<EventPage, RpcError<Error>> {
+    let config = &ctx.config().events;
+    let limit = limit.unwrap_or(config.default_page_size);
+    if limit > config.max_page_size {
+        return Err(invalid_params(Error::Pagination(
+            PaginationError::ExceededMaxPageSize {
+                requested: limit,
+                max: config.max_page_size,
+            },
+        )));
+    }
+
+    let descending = descending_order.unwrap_or(false);
+
+    // Transactions are scanned starting from the one the cursor points to, because it may have
+    // matching events after the cursor.
+    let cursor_tx = match &cursor {
+        None => None,
+        Some(EventID { tx_digest, .. }) => Some(
+            tx_sequence_number(ctx, tx_digest)
+                .await?
+                .ok_or_else(|| invalid_params(Error::CursorNotFound(*tx_digest)))?,
+        ),
+    };
+
+    // Every transaction selected by the filter emitted at least one matching event, so fetching
+    // two transactions more than the limit is enough to fill the page and detect whether there is
+    // a next page, even if the cursor points to the last matching event of its transaction.
+    let page = TxPage {
+        cursor: cursor_tx,
+        limit: limit as i64 + 2,
+        descending,
+    };
+
+    let tx_sequence_numbers = transactions(ctx, filter, &page).await?;
+    let digests = digests(ctx, &tx_sequence_numbers).await?;
+
+    let transactions = future::join_all(
+        digests
+            .iter()
+            .map(|digest| ctx.kv_loader().load_one_transaction(*digest)),
+    )
+    .await;
+
+    let mut data = Vec::with_capacity(limit);
+    let mut has_next_page = false;
+    'transactions: for (digest, tx) in digests.into_iter().zip(transactions) {
+        let tx = tx
+            .context("Failed to fetch transaction from store")?
+            .ok_or_else(|| invalid_params(Error::NotFound(digest)))?;
+
+        let mut events: Vec<_> = tx
+            .events()?
+            .into_iter()
+            .enumerate()
+            .filter(|(_, event)| filter.matches(event))
+            .collect();
+
+        if descending {
+            events.reverse();
+        }
+
+        for (ix, event) in events {
+            if let Some(cursor) = &cursor {
+                let seq = ix as u64;
+                let seen = if descending {
+                    seq >= cursor.event_seq
+                } else {
+                    seq <= cursor.event_seq
+                };
+
+                if cursor.tx_digest == digest && seen {
+                    continue;
+                }
+            }
+
+            if data.len() == limit {
+                has_next_page = true;
+                break 'transactions;
+            }
+
+            data.push(response::event(ctx, digest, &tx, ix, event).await?);
+        }
+    }
+
+    let next_cursor = data.last().map(|event| event.id).or(cursor);
+    Ok(PageResponse {
+        data,
+        next_cursor,
+        has_next_page,
+    })
+}
+
+impl EventFilter {
+    /// Whether `event`, emitted by one of the transactions the filter selected, matches the
+    /// filter. Transactions are selected if they emitted any matching event, so the events they
+    /// emitted still need to be filtered individually.
+    fn matches(&self, event: &Event) -> bool {
+        use EventFilter as F;
+        match self {
+            F::Sender(sender) => event.sender == *sender,
+
+            F::Transaction(_) => true,
+
+            F::MoveModule { package, module } => {
+                event.package_id == *package && event.transaction_module == *module
+            }
+
+            F::MoveEventType(tag) => {
+                event.type_.address == tag.address
+                    && event.type_.module == tag.module
+                    && event.type_.name == tag.name
+                    && (tag.type_params.is_empty() || event.type_.type_params == tag.type_params)
+            }
+
+            F::MoveEventModule { package, module } => {
+                event.type_.address == AccountAddress::from(*package)
+                    && event.type_.module == *module
+            }
+        }
+    }
+}
+
+/// Description of a page of transactions to scan for events. Unlike when paginating transactions,
+/// the cursor is inclusive, because the transaction it points to may have more events to return.
+struct TxPage {
+    cursor: Option<i64>,
+    limit: i64,
+    descending: bool,
+}
+
+/// Fetch the sequence numbers of transactions that emitted events matching `filter`, for the
+/// given `page`.
+async fn transactions(
+    ctx: &Context,
+    filter: &EventFilter,
+    page: &TxPage,
+) -> Result<Vec<i64>, RpcError<Error>> {
+    use EventFilter as F;
+
+    let results: Vec<i64> = match filter {
+        // The transaction is known, so there is no need to query an index.
+        F::Transaction(digest) => {
+            let Some(tx) = tx_sequence_number(ctx, digest).await? else {
+                return Ok(vec![]);
+            };
+
+            let in_page = page.cursor.is_none_or(|cursor| {
+                if page.descending {
+                    tx <= cursor
+                } else {
+                    tx >= cursor
+                }
+            });
+
+            return Ok(if in_page { vec![tx] } else { vec![] });
+        }
+
+        F::Sender(sender) => {
+            use ev_emit_mod::dsl as e;
+
+            // A transaction can emit events from multiple modules.
+            let query = e::ev_emit_mod
+                .select(e::tx_sequence_number)
+                .filter(e::sender.eq(sender.to_vec()))
+                .distinct()
+                .into_boxed();
+
+            ctx.pg_reader()
+                .connect()
+                .await
+                .context("Failed to connect to the database")?
+                .results(paginate(page, "ev_emit_mod", e::tx_sequence_number, query))
+                .await
+        }
+
+        F::MoveModule { package, module } => {
+            use ev_emit_mod::dsl as e;
+
+            let query = e::ev_emit_mod
+                .select(e::tx_sequence_number)
+                .filter(e::package.eq(package.to_vec()))
+                .filter(e::module.eq(module.as_str()))
+                .into_boxed();
+
+            ctx.pg_reader()
+                .connect()
+                .await
+                .context("Failed to connect to the database")?
+                .results(paginate(page, "ev_emit_mod", e::tx_sequence_number, query))
+                .await
+        }
+
+        F::MoveEventType(tag) => {
+            use ev_struct_inst::dsl as e;
+
+            let mut query = e::ev_struct_inst
+                .select(e::tx_sequence_number)
+                .filter(e::package.eq(tag.address.to_vec()))
+                .filter(e::module.eq(tag.module.as_str()))
+                .filter(e::name.eq(tag.name.as_str()))
+                .into_boxed();
+
+            if tag.type_params.is_empty() {
+                // A transaction can emit events of multiple instantiations of the same type.
+                query = query.distinct();
+            } else {
+                let instantiation = bcs::to_bytes(&tag.type_params)
+                    .context("Failed to serialize type parameters")?;
+                query = query.filter(e::instantiation.eq(instantiation));
+            }
+
+            ctx.pg_reader()
+                .connect()
+                .await
+                .context("Failed to connect to the database")?
+                .results(paginate(
+                    page,
+                    "ev_struct_inst",
+                    e::tx_sequence_number,
+                    query,
+                ))
+                .await
+        }
+
+        F::MoveEventModule { package, module } => {
+            use ev_struct_inst::dsl as e;
+
+            // A transaction can emit events of multiple types from the same module.
+            let query = e::ev_struct_inst
+                .select(e::tx_sequence_number)
+                .filter(e::package.eq(package.to_vec()))
+                .filter(e::module.eq(module.as_str()))
+                .distinct()
+                .into_boxed();
+
+            ctx.pg_reader()
+                .connect()
+                .await
+                .context("Failed to connect to the database")?
+                .results(paginate(
+                    page,
+                    "ev_struct_inst",
+                    e::tx_sequence_number,
+                    query,
+                ))
+                .await
+        }
+    }
+    .context("Failed to fetch transaction sequence numbers")?;
+
+    Ok(results)
+}
+
+/// Look up the sequence number of the transaction with the given `digest`, if it exists.
+async fn tx_sequence_number(
+    ctx: &Context,
+    digest: &TransactionDigest,
+) -> Result<Option<i64>, RpcError<Error>> {
+    use tx_digests::dsl as d;
+
+    let query = d::tx_digests
+        .select(d::tx_sequence_number)
+        .filter(d::tx_digest.eq(digest.inner().to_vec()))
+        .limit(1);
+
+    let results: Vec<i64> = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to the database")?
+        .results(query)
+        .await
+        .context("Failed to fetch transaction sequence number")?;
+
+    Ok(results.into_iter().next())
+}
+
+/// Load the digests of the transactions with the given sequence numbers, preserving their order.
+async fn digests(
+    ctx: &Context,
+    tx_sequence_numbers: &[i64],
+) -> Result<Vec<TransactionDigest>, RpcError<Error>> {
+    let keys = tx_sequence_numbers
+        .iter()
+        .map(|seq| TxDigestKey(*seq as u64));
+
+    let stored = ctx
+        .pg_loader()
+        .load_many(keys)
+        .await
+        .context("Failed to load transaction digests")?;
+
+    let mut digests = Vec::with_capacity(tx_sequence_numbers.len());
+    for seq in tx_sequence_numbers {
+        let bytes = stored
+            .get(&TxDigestKey(*seq as u64))
+            .with_context(|| format!("Missing transaction digest for transaction {seq}"))?
+            .tx_digest
+            .as_slice();
+
+        let digest = TransactionDigest::try_from(bytes)
+            .context("Failed to deserialize transaction digest")?;
+
+        digests.push(digest);
+    }
+
+    Ok(digests)
+}
+
+/// Modify `query` to be paginated according to `page`, using `tx_sequence_number` as the column
+/// containing the sequence number. Like when querying transactions, results are also limited by
+/// the reader low watermark of the `pipeline` the table belongs to, to avoid scanning pruned rows.
+fn paginate<'q, TX, ST, QS>(
+    page: &TxPage,
+    pipeline: &'static str,
+    tx_sequence_number: TX,
+    mut query: BoxedSelectStatement<'q, ST, FromClause<QS>, Pg>,
+) -> BoxedSelectStatement<'q, ST, FromClause<QS>, Pg>
+where
+    QS: QuerySource,
+    TX: Copy + Send + Sync + 'q,
+    TX: ValidGrouping<()> + QueryFragment<Pg>,
+    TX: Column<Table = QS> + AppearsOnTable<QS>,
+    TX: ExpressionMethods + Expression<SqlType = SqlBigInt>,
+    TX::IsAggregate: MixedAggregates<Never, Output = No>,
+{
+    query = query.filter(tx_sequence_number.ge(sql!(as SqlBigInt,
+        "COALESCE(
+            (
+                SELECT
+                    MAX(tx_lo)
+                FROM
+                    watermarks w
+                INNER JOIN
+                    cp_sequence_numbers c
+                ON
+                    w.reader_lo = c.cp_sequence_number
+                WHERE
+                    w.pipeline IN ({Text}, 'tx_digests')
+            ),
+            0
+        )",
+        pipeline,
+    )));
+
+    if let Some(tx) = page.cursor {
+        if page.descending {
+            query = query.filter(tx_sequence_number.le(tx));
+        } else {
+            query = query.filter(tx_sequence_number.ge(tx));
+        }
+    }
+
+    if page.descending {
+        query = query.order(tx_sequence_number.desc());
+    } else {
+        query = query.order(tx_sequence_number.asc());
+    }
+
+    query.limit(page.limit)
+}
@@ -0,0 +1,62 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context as _;
+use move_core_types::annotated_value::{MoveDatatypeLayout, MoveTypeLayout};
+use sui_indexer_alt_reader::kv_loader::TransactionContents;
+use sui_json_rpc_types::SuiEvent;
+use sui_types::{digests::TransactionDigest, event::Event};
+
+use crate::{
+    context::Context,
+    error::{rpc_bail, RpcError},
+};
+
+/// Resolve the layout of `event` (the `ix`-th event emitted by the transaction with `digest`), to
+/// present it as a JSON-RPC response.
+pub(crate) async fn event<E: std::error::Error>(
+    ctx: &Context,
+    digest: TransactionDigest,
+    tx: &TransactionContents,
+    ix: usize,
+    event: Event,
+) -> Result<SuiEvent, RpcError<E>> {
+    let layout = match ctx
+        .package_resolver()
+        .type_layout(event.type_.clone().into())
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to resolve layout for {}",
+                event.type_.to_canonical_display(/* with_prefix */ true)
+            )
+        })? {
+        MoveTypeLayout::Struct(s) => MoveDatatypeLayout::Struct(s),
+        MoveTypeLayout::Enum(e) => MoveDatatypeLayout::Enum(e),
+        _ => rpc_bail!(
+            "Event {ix} is not a struct or enum: {}",
+            event.type_.to_canonical_string(/* with_prefix */ true)
+        ),
+    };
+
+    Ok(
+        SuiEvent::try_from(event, digest, ix as u64, tx.timestamp_ms(), layout)
+            .with_context(|| format!("Failed to convert Event {ix} into response"))?,
+    )
+}
+
+/// Extract all the events emitted by the transaction with `digest`, from its stored form.
+pub(crate) async fn events<E: std::error::Error>(
+    ctx: &Context,
+    digest: TransactionDigest,
+    tx: &TransactionContents,
+) -> Result<Vec<SuiEvent>, RpcError<E>> {
+    let events: Vec<Event> = tx.events()?;
+    let mut sui_events = Vec::with_capacity(events.len());
+
+    for (ix, e) in events.into_iter().enumerate() {
+        sui_events.push(event(ctx, digest, tx, ix, e).await?);
+    }
+
+    Ok(sui_events)
+}
@@ -4,6 +4,7 @@
 pub(crate) mod checkpoints;
 pub(crate) mod coin;
 pub(crate) mod dynamic_fields;
+pub(crate) mod events;
 pub(crate) mod governance;
 pub(crate) mod move_utils;
 pub(crate) mod name_service;
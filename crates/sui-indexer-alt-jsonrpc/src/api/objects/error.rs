@@ -3,6 +3,12 @@
 
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum Error {
+    #[error(transparent)]
+    Consistency(#[from] crate::consistency::Error),
+
+    #[error("Compound object filters are not supported when reading at a checkpoint")]
+    FilterAtCheckpoint,
+
     #[error("Object filter contains more than the maximum {max} type filters")]
     FilterTooBig { max: usize },
 
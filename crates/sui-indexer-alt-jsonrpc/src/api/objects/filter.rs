@@ -7,6 +7,7 @@ use move_core_types::language_storage::StructTag;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
+use sui_indexer_alt_reader::consistent_reader::proto::owner::OwnerKind;
 use sui_indexer_alt_schema::{
     objects::{StoredObjInfo, StoredOwnerKind},
     schema::obj_info,
@@ -14,13 +15,14 @@ use sui_indexer_alt_schema::{
 use sui_json_rpc_types::{Page as PageResponse, SuiObjectDataOptions};
 use sui_sql_macro::sql;
 use sui_types::{
-    base_types::{ObjectID, SuiAddress},
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
     dynamic_field::{DYNAMIC_FIELD_FIELD_STRUCT_NAME, DYNAMIC_FIELD_MODULE_NAME},
     sui_serde::SuiStructTag,
     Identifier, TypeTag, SUI_FRAMEWORK_ADDRESS,
 };
 
 use crate::{
+    consistency::consistent_error,
     context::Context,
     error::{invalid_params, RpcError},
     paginate::{BcsCursor, Cursor as _, Page},
@@ -82,6 +84,10 @@ pub(crate) struct ObjectCursor {
 pub(crate) type Cursor = BcsCursor<ObjectCursor>;
 pub(crate) type ObjectIDs = PageResponse<ObjectID, String>;
 
+/// Cursors for pages read from the consistent store wrap the store's own page token.
+pub(crate) type TokenCursor = BcsCursor<Vec<u8>>;
+pub(crate) type ObjectVersions = PageResponse<(ObjectID, SequenceNumber), String>;
+
 impl SuiObjectDataFilter {
     /// Whether this is a compound filter (which is implemented using sequential scan), or a simple
     /// type filter, which can leverage indices on the database.
@@ -130,6 +136,18 @@ impl SuiObjectDataFilter {
         }
     }
 
+    /// Represent this filter as a type filter understood by the consistent store. This operation
+    /// fails for compound filters, which the consistent store does not support.
+    fn to_type_filter(&self) -> Result<String, RpcError<Error>> {
+        use SuiObjectDataFilter as F;
+        Ok(match self {
+            F::MatchNone(_) => return Err(invalid_params(Error::FilterAtCheckpoint)),
+            F::Package(package) => package.to_string(),
+            F::MoveModule { package, module } => format!("{package}::{module}"),
+            F::StructType(tag) => tag.to_canonical_string(/* with_prefix */ true),
+        })
+    }
+
     /// Convert this filter into a raw filter which can be matched against a row from the database.
     /// This operation can fail if the filter exceeds limits (too many type filters or too deep).
     fn to_raw(&self, ctx: &Context) -> Result<RawFilter, RpcError<Error>> {
@@ -268,6 +286,62 @@ pub(super) async fn owned_objects(
     }
 }
 
+/// Fetch references for a page of objects owned by `owner` as of checkpoint `checkpoint`, that
+/// satisfy the given `filter` and pagination parameters. The page is served by the consistent
+/// store, so `checkpoint` must be one that the store holds a snapshot for. Returns the IDs and
+/// versions of the objects, and a cursor pointing to the last result (if there are any results).
+pub(super) async fn owned_objects_at_checkpoint(
+    ctx: &Context,
+    owner: SuiAddress,
+    filter: &Option<SuiObjectDataFilter>,
+    cursor: Option<String>,
+    limit: Option<usize>,
+    checkpoint: u64,
+) -> Result<ObjectVersions, RpcError<Error>> {
+    let config = &ctx.config().objects;
+    let page: Page<TokenCursor> = Page::from_params(
+        config.default_page_size,
+        config.max_page_size,
+        cursor,
+        limit,
+        None,
+    )?;
+
+    let object_type = filter.as_ref().map(|f| f.to_type_filter()).transpose()?;
+
+    let refs = ctx
+        .consistent_reader()
+        .list_owned_objects(
+            checkpoint,
+            OwnerKind::Address,
+            Some(owner.to_string()),
+            object_type,
+            Some(page.limit as u32),
+            page.cursor.map(|c| c.0),
+            None,
+            /* is_from_front */ true,
+        )
+        .await
+        .map_err(|e| consistent_error(checkpoint, e))?;
+
+    let next_cursor = refs
+        .results
+        .last()
+        .map(|edge| BcsCursor(edge.token.clone()).encode())
+        .transpose()
+        .context("Failed to encode next cursor")?;
+
+    Ok(PageResponse {
+        data: refs
+            .results
+            .into_iter()
+            .map(|edge| (edge.value.0, edge.value.1))
+            .collect(),
+        next_cursor,
+        has_next_page: refs.has_next_page,
+    })
+}
+
 /// Fetch ObjectIDs for a page of dynamic fields owned by parent object `owner`. The returned IDs
 /// all point to `sui::dynamic_field::Field<K, V>` objects. Returns the IDs and a cursor pointing
 /// to the last result (if there are any results).
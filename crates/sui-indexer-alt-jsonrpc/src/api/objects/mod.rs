@@ -12,6 +12,7 @@ use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
 
 use crate::{
+    consistency::check_in_range,
     context::Context,
     error::{invalid_params, InternalContext},
 };
@@ -28,6 +29,10 @@ pub(crate) mod response;
 #[rpc(server, namespace = "sui")]
 trait ObjectsApi {
     /// Return the object information for the latest version of an object.
+    ///
+    /// If `atCheckpoint` is provided, the object is returned as it was at the end of that
+    /// checkpoint instead. The checkpoint must be within the range that the RPC has object
+    /// version history for, otherwise the request will fail.
     #[method(name = "getObject")]
     async fn get_object(
         &self,
@@ -35,6 +40,8 @@ trait ObjectsApi {
         object_id: ObjectID,
         /// Options for specifying the content to be returned
         options: Option<SuiObjectDataOptions>,
+        /// The checkpoint to read the object at, defaults to the latest checkpoint
+        at_checkpoint: Option<u64>,
     ) -> RpcResult<SuiObjectResponse>;
 
     /// Return the object information for the latest versions of multiple objects.
@@ -94,6 +101,11 @@ trait QueryObjectsApi {
     /// address owned at a single point in time).
     ///
     /// The size of each page is controlled by the `limit` parameter.
+    ///
+    /// If `atCheckpoint` is provided, objects are returned as they were owned at the end of that
+    /// checkpoint, which makes pagination consistent. The checkpoint must be one that the
+    /// consistent store holds a snapshot for, compound filters are not supported, and cursors
+    /// from requests at different checkpoints (or without a checkpoint) cannot be mixed.
     #[method(name = "getOwnedObjects")]
     async fn get_owned_objects(
         &self,
@@ -105,6 +117,8 @@ trait QueryObjectsApi {
         cursor: Option<String>,
         /// Maximum number of objects to return per page.
         limit: Option<usize>,
+        /// The checkpoint to read owned objects at, defaults to the latest checkpoint.
+        at_checkpoint: Option<u64>,
     ) -> RpcResult<Page<SuiObjectResponse, String>>;
 }
 
@@ -118,9 +132,22 @@ impl ObjectsApiServer for Objects {
         &self,
         object_id: ObjectID,
         options: Option<SuiObjectDataOptions>,
+        at_checkpoint: Option<u64>,
     ) -> RpcResult<SuiObjectResponse> {
         let Self(ctx) = self;
         let options = options.unwrap_or_default();
+
+        if let Some(checkpoint) = at_checkpoint {
+            check_in_range::<Error>(ctx, "obj_versions", checkpoint).await?;
+            return Ok(
+                response::object_at_checkpoint(ctx, object_id, checkpoint, &options)
+                    .await
+                    .with_internal_context(|| {
+                        format!("Failed to get object {object_id} at checkpoint {checkpoint}")
+                    })?,
+            );
+        }
+
         Ok(response::live_object(ctx, object_id, &options)
             .await
             .with_internal_context(|| {
@@ -219,11 +246,17 @@ impl QueryObjectsApiServer for QueryObjects {
         query: Option<SuiObjectResponseQuery>,
         cursor: Option<String>,
         limit: Option<usize>,
+        at_checkpoint: Option<u64>,
     ) -> RpcResult<Page<SuiObjectResponse, String>> {
         let Self(ctx) = self;
 
         let query = query.unwrap_or_default();
 
+        if let Some(checkpoint) = at_checkpoint {
+            return owned_objects_at_checkpoint(ctx, address, query, cursor, limit, checkpoint)
+                .await;
+        }
+
         let Page {
             data: object_ids,
             next_cursor,
@@ -253,6 +286,47 @@ impl QueryObjectsApiServer for QueryObjects {
     }
 }
 
+/// Like `getOwnedObjects`, but reading the objects owned by `address` as of checkpoint
+/// `checkpoint`, from the consistent store.
+async fn owned_objects_at_checkpoint(
+    ctx: &Context,
+    address: SuiAddress,
+    query: SuiObjectResponseQuery,
+    cursor: Option<String>,
+    limit: Option<usize>,
+    checkpoint: u64,
+) -> RpcResult<Page<SuiObjectResponse, String>> {
+    let Page {
+        data: object_refs,
+        next_cursor,
+        has_next_page,
+    } = filter::owned_objects_at_checkpoint(ctx, address, &query.filter, cursor, limit, checkpoint)
+        .await?;
+
+    let options = query.options.unwrap_or_default();
+
+    let obj_futures = object_refs
+        .iter()
+        .map(|(id, v)| response::versioned_object(ctx, *id, *v, &options));
+
+    let data = future::join_all(obj_futures)
+        .await
+        .into_iter()
+        .zip(object_refs)
+        .map(|(r, (id, _))| {
+            r.with_internal_context(|| {
+                format!("Failed to get object {id} at checkpoint {checkpoint}")
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Page {
+        data,
+        next_cursor,
+        has_next_page,
+    })
+}
+
 impl RpcModule for Objects {
     fn schema(&self) -> Module {
         ObjectsApiOpenRpc::module_doc()
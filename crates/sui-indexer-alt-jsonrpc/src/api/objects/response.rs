@@ -23,7 +23,7 @@ use tokio::join;
 
 use crate::{
     context::Context,
-    data::load_live,
+    data::{load_at_checkpoint, load_live},
     error::{rpc_bail, InternalContext, RpcError},
 };
 
@@ -48,6 +48,54 @@ pub(super) async fn live_object(
     ))
 }
 
+/// Fetch the necessary data from the stores in `ctx` and transform it to build a response for the
+/// version of an object that was live as of checkpoint `checkpoint`, according to the response
+/// `options`.
+pub(super) async fn object_at_checkpoint(
+    ctx: &Context,
+    object_id: ObjectID,
+    checkpoint: u64,
+    options: &SuiObjectDataOptions,
+) -> Result<SuiObjectResponse, RpcError> {
+    let Some(object) = load_at_checkpoint(ctx, object_id, checkpoint)
+        .await
+        .context("Failed to load object at checkpoint")?
+    else {
+        return Ok(SuiObjectResponse::new_with_error(
+            SuiObjectResponseError::NotExists { object_id },
+        ));
+    };
+
+    Ok(SuiObjectResponse::new_with_data(
+        object_data_with_options(ctx, object, options).await?,
+    ))
+}
+
+/// Fetch the necessary data from the stores in `ctx` and transform it to build a response for an
+/// object at a version that is known to exist (e.g. because it was returned by the consistent
+/// store), according to the response `options`.
+pub(super) async fn versioned_object(
+    ctx: &Context,
+    object_id: ObjectID,
+    version: SequenceNumber,
+    options: &SuiObjectDataOptions,
+) -> Result<SuiObjectResponse, RpcError> {
+    let Some(object) = ctx
+        .kv_loader()
+        .load_one_object(object_id, version.value())
+        .await
+        .context("Failed to load object from store")?
+    else {
+        return Ok(SuiObjectResponse::new_with_error(
+            SuiObjectResponseError::NotExists { object_id },
+        ));
+    };
+
+    Ok(SuiObjectResponse::new_with_data(
+        object_data_with_options(ctx, object, options).await?,
+    ))
+}
+
 /// Fetch the necessary data from the stores in `ctx` and transform it to build a response for a
 /// past object identified by its ID and version, according to the response `options`.
 pub(super) async fn past_object(
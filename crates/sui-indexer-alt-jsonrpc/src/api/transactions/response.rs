@@ -5,22 +5,20 @@ use std::str::FromStr;
 
 use anyhow::Context as _;
 use futures::future::OptionFuture;
-use move_core_types::annotated_value::{MoveDatatypeLayout, MoveTypeLayout};
 use sui_indexer_alt_reader::{
     kv_loader::TransactionContents, objects::VersionedObjectKey,
     tx_balance_changes::TxBalanceChangeKey,
 };
 use sui_indexer_alt_schema::transactions::{BalanceChange, StoredTxBalanceChange};
 use sui_json_rpc_types::{
-    BalanceChange as SuiBalanceChange, ObjectChange as SuiObjectChange, SuiEvent,
-    SuiTransactionBlock, SuiTransactionBlockData, SuiTransactionBlockEffects,
-    SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    BalanceChange as SuiBalanceChange, ObjectChange as SuiObjectChange, SuiTransactionBlock,
+    SuiTransactionBlockData, SuiTransactionBlockEffects, SuiTransactionBlockEvents,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_types::{
     base_types::{ObjectID, SequenceNumber},
     digests::{ObjectDigest, TransactionDigest},
     effects::{IDOperation, ObjectChange, TransactionEffects, TransactionEffectsAPI},
-    event::Event,
     object::Object,
     signature::GenericSignature,
     transaction::{TransactionData, TransactionDataAPI},
@@ -29,6 +27,7 @@ use sui_types::{
 use tokio::join;
 
 use crate::{
+    api::events,
     context::Context,
     error::{invalid_params, rpc_bail, RpcError},
 };
@@ -69,8 +68,8 @@ pub(super) async fn transaction(
 
     let mut response = SuiTransactionBlockResponse::new(digest);
 
-    response.timestamp_ms = Some(tx.timestamp_ms());
-    response.checkpoint = Some(tx.cp_sequence_number());
+    response.timestamp_ms = tx.timestamp_ms();
+    response.checkpoint = tx.cp_sequence_number();
 
     if options.show_input {
         response.transaction = Some(input(ctx, &tx).await?);
@@ -133,36 +132,9 @@ async fn events(
     digest: TransactionDigest,
     tx: &TransactionContents,
 ) -> Result<SuiTransactionBlockEvents, RpcError<Error>> {
-    let events: Vec<Event> = tx.events()?;
-    let mut sui_events = Vec::with_capacity(events.len());
-
-    for (ix, event) in events.into_iter().enumerate() {
-        let layout = match ctx
-            .package_resolver()
-            .type_layout(event.type_.clone().into())
-            .await
-            .with_context(|| {
-                format!(
-                    "Failed to resolve layout for {}",
-                    event.type_.to_canonical_display(/* with_prefix */ true)
-                )
-            })? {
-            MoveTypeLayout::Struct(s) => MoveDatatypeLayout::Struct(s),
-            MoveTypeLayout::Enum(e) => MoveDatatypeLayout::Enum(e),
-            _ => rpc_bail!(
-                "Event {ix} is not a struct or enum: {}",
-                event.type_.to_canonical_string(/* with_prefix */ true)
-            ),
-        };
-
-        let sui_event =
-            SuiEvent::try_from(event, digest, ix as u64, Some(tx.timestamp_ms()), layout)
-                .with_context(|| format!("Failed to convert Event {ix} into response"))?;
-
-        sui_events.push(sui_event)
-    }
-
-    Ok(SuiTransactionBlockEvents { data: sui_events })
+    Ok(SuiTransactionBlockEvents {
+        data: events::response::events(ctx, digest, tx).await?,
+    })
 }
 
 /// Extract the transaction's balance changes from their stored form.
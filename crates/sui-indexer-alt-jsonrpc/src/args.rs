@@ -5,6 +5,7 @@ use std::path::PathBuf;
 
 use sui_indexer_alt_metrics::MetricsArgs;
 use sui_indexer_alt_reader::bigtable_reader::BigtableArgs;
+use sui_indexer_alt_reader::consistent_reader::ConsistentReaderArgs;
 use sui_indexer_alt_reader::pg_reader::db::DbArgs;
 use url::Url;
 
@@ -30,17 +31,25 @@ pub enum Command {
         )]
         database_url: Url,
 
-        /// Bigtable instance ID to make KV store requests to. If this is not provided, KV store
-        /// requests will be made to the database.
+        /// Bigtable instance ID to make KV store requests to. If neither this nor
+        /// `--kv-rocksdb-path` is provided, KV store requests will be made to the database.
         #[clap(long)]
         bigtable_instance: Option<String>,
 
+        /// Path to a local RocksDB KV store (as written by `sui-kvstore --rocksdb-path`) to make KV
+        /// store requests to, instead of Bigtable.
+        #[clap(long, conflicts_with = "bigtable_instance")]
+        kv_rocksdb_path: Option<PathBuf>,
+
         #[command(flatten)]
         db_args: DbArgs,
 
         #[command(flatten)]
         bigtable_args: BigtableArgs,
 
+        #[command(flatten)]
+        consistent_reader_args: ConsistentReaderArgs,
+
         #[command(flatten)]
         rpc_args: RpcArgs,
 
@@ -25,6 +25,9 @@ pub struct RpcConfig {
     /// Configuration for transaction-related RPC methods.
     pub transactions: TransactionsConfig,
 
+    /// Configuration for event-related RPC methods.
+    pub events: EventsConfig,
+
     /// Configuration for SuiNS related RPC methods.
     pub name_service: NameServiceConfig,
 
@@ -45,6 +48,7 @@ pub struct RpcLayer {
     pub objects: ObjectsLayer,
     pub dynamic_fields: DynamicFieldsLayer,
     pub transactions: TransactionsLayer,
+    pub events: EventsLayer,
     pub name_service: NameServiceLayer,
     pub coins: CoinsLayer,
     pub node: NodeLayer,
@@ -157,6 +161,26 @@ pub struct TransactionsLayer {
     pub extra: toml::Table,
 }
 
+#[derive(Debug, Clone)]
+pub struct EventsConfig {
+    /// The default page size limit when querying events, if none is provided.
+    pub default_page_size: usize,
+
+    /// The largest acceptable page size when querying events. Requesting a page larger than this
+    /// is a user error.
+    pub max_page_size: usize,
+}
+
+#[DefaultConfig]
+#[derive(Clone, Default, Debug)]
+pub struct EventsLayer {
+    pub default_page_size: Option<usize>,
+    pub max_page_size: Option<usize>,
+
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
 #[DefaultConfig]
 #[derive(Clone, Default, Debug)]
 pub struct NameServiceLayer {
@@ -176,6 +200,11 @@ pub struct CoinsConfig {
     /// The largest acceptable page size when querying coins. Requesting a page larger than
     /// this is a user error.
     pub max_page_size: usize,
+
+    /// The maximum number of coins counted towards `coinObjectCount` when reading a balance at a
+    /// past checkpoint. Coins are counted by listing them from the consistent store, so the count
+    /// is capped at this value to bound the work done per request.
+    pub max_coins_counted_at_checkpoint: usize,
 }
 
 #[DefaultConfig]
@@ -183,6 +212,7 @@ pub struct CoinsConfig {
 pub struct CoinsLayer {
     pub default_page_size: Option<usize>,
     pub max_page_size: Option<usize>,
+    pub max_coins_counted_at_checkpoint: Option<usize>,
 
     #[serde(flatten)]
     pub extra: toml::Table,
@@ -226,6 +256,7 @@ impl RpcLayer {
             objects: ObjectsConfig::default().into(),
             dynamic_fields: DynamicFieldsConfig::default().into(),
             transactions: TransactionsConfig::default().into(),
+            events: EventsConfig::default().into(),
             name_service: NameServiceConfig::default().into(),
             coins: CoinsConfig::default().into(),
             package_resolver: PackageResolverLayer::default(),
@@ -240,6 +271,7 @@ impl RpcLayer {
             objects: self.objects.finish(ObjectsConfig::default()),
             dynamic_fields: self.dynamic_fields.finish(DynamicFieldsConfig::default()),
             transactions: self.transactions.finish(TransactionsConfig::default()),
+            events: self.events.finish(EventsConfig::default()),
             name_service: self.name_service.finish(NameServiceConfig::default()),
             coins: self.coins.finish(CoinsConfig::default()),
             node: self.node.finish(NodeConfig::default()),
@@ -298,6 +330,16 @@ impl TransactionsLayer {
     }
 }
 
+impl EventsLayer {
+    pub fn finish(self, base: EventsConfig) -> EventsConfig {
+        check_extra("events", self.extra);
+        EventsConfig {
+            default_page_size: self.default_page_size.unwrap_or(base.default_page_size),
+            max_page_size: self.max_page_size.unwrap_or(base.max_page_size),
+        }
+    }
+}
+
 impl NameServiceLayer {
     pub fn finish(self, base: NameServiceConfig) -> NameServiceConfig {
         check_extra("name service", self.extra);
@@ -315,6 +357,9 @@ impl CoinsLayer {
         CoinsConfig {
             default_page_size: self.default_page_size.unwrap_or(base.default_page_size),
             max_page_size: self.max_page_size.unwrap_or(base.max_page_size),
+            max_coins_counted_at_checkpoint: self
+                .max_coins_counted_at_checkpoint
+                .unwrap_or(base.max_coins_counted_at_checkpoint),
         }
     }
 }
@@ -363,6 +408,7 @@ impl Default for RpcConfig {
             objects: ObjectsConfig::default(),
             dynamic_fields: DynamicFieldsConfig::default(),
             transactions: TransactionsConfig::default(),
+            events: EventsConfig::default(),
             name_service: NameServiceConfig::default(),
             coins: CoinsConfig::default(),
             node: NodeConfig::default(),
@@ -408,11 +454,21 @@ impl Default for TransactionsConfig {
     }
 }
 
+impl Default for EventsConfig {
+    fn default() -> Self {
+        Self {
+            default_page_size: 50,
+            max_page_size: 100,
+        }
+    }
+}
+
 impl Default for CoinsConfig {
     fn default() -> Self {
         Self {
             default_page_size: 50,
             max_page_size: 100,
+            max_coins_counted_at_checkpoint: 1000,
         }
     }
 }
@@ -484,6 +540,16 @@ impl From<TransactionsConfig> for TransactionsLayer {
     }
 }
 
+impl From<EventsConfig> for EventsLayer {
+    fn from(config: EventsConfig) -> Self {
+        Self {
+            default_page_size: Some(config.default_page_size),
+            max_page_size: Some(config.max_page_size),
+            extra: Default::default(),
+        }
+    }
+}
+
 impl From<NameServiceConfig> for NameServiceLayer {
     fn from(config: NameServiceConfig) -> Self {
         Self {
@@ -500,6 +566,7 @@ impl From<CoinsConfig> for CoinsLayer {
         Self {
             default_page_size: Some(config.default_page_size),
             max_page_size: Some(config.max_page_size),
+            max_coins_counted_at_checkpoint: Some(config.max_coins_counted_at_checkpoint),
             extra: Default::default(),
         }
     }
@@ -0,0 +1,95 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context as _;
+use diesel::{ExpressionMethods, QueryDsl};
+use sui_indexer_alt_reader::consistent_reader;
+use sui_indexer_alt_schema::schema::watermarks;
+
+use crate::{
+    context::Context,
+    error::{invalid_params, RpcError},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Checkpoint {checkpoint} is outside the available range for {pipeline}: [{lo}, {hi}]")]
+    OutOfRange {
+        checkpoint: u64,
+        pipeline: &'static str,
+        lo: u64,
+        hi: u64,
+    },
+
+    #[error("Checkpoint {0} is not available to read from: {1}")]
+    NoSnapshot(u64, String),
+
+    #[error("Reading at a checkpoint is not supported by this RPC")]
+    NotConfigured,
+}
+
+/// Check that reads from the table written by `pipeline` at `checkpoint` are consistent: The
+/// checkpoint must be at or after the pipeline's reader low watermark (so that data has not been
+/// pruned) and at or before its high watermark (so that all the data for that checkpoint has been
+/// written). Requests for checkpoints outside this range are treated as user errors.
+pub(crate) async fn check_in_range<E>(
+    ctx: &Context,
+    pipeline: &'static str,
+    checkpoint: u64,
+) -> Result<(), RpcError<E>>
+where
+    E: From<Error> + std::error::Error,
+{
+    use watermarks::dsl as w;
+
+    let mut conn = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to database")?;
+
+    let query = w::watermarks
+        .select((w::reader_lo, w::checkpoint_hi_inclusive))
+        .filter(w::pipeline.eq(pipeline));
+
+    let watermarks: Vec<(i64, i64)> = conn
+        .results(query)
+        .await
+        .with_context(|| format!("Failed to fetch watermarks for {pipeline}"))?;
+
+    // If the pipeline has not written a watermark yet, then there is no data to read.
+    let Some((lo, hi)) = watermarks.first().map(|(lo, hi)| (*lo as u64, *hi as u64)) else {
+        return Err(invalid_params(E::from(Error::NoSnapshot(
+            checkpoint,
+            format!("{pipeline} has not been indexed yet"),
+        ))));
+    };
+
+    if !(lo..=hi).contains(&checkpoint) {
+        return Err(invalid_params(E::from(Error::OutOfRange {
+            checkpoint,
+            pipeline,
+            lo,
+            hi,
+        })));
+    }
+
+    Ok(())
+}
+
+/// Convert an error from a consistent store request at `checkpoint` into an RPC error. Requests
+/// for checkpoints the store does not hold a snapshot for, or requests made when the store is not
+/// configured, are treated as user errors, while all other failures are internal errors.
+pub(crate) fn consistent_error<E>(checkpoint: u64, err: consistent_reader::Error) -> RpcError<E>
+where
+    E: From<Error> + std::error::Error,
+{
+    use consistent_reader::Error as CE;
+    match err {
+        CE::NotConfigured => invalid_params(E::from(Error::NotConfigured)),
+        CE::OutOfRange(_) => {
+            invalid_params(E::from(Error::NoSnapshot(checkpoint, err.to_string())))
+        }
+        CE::Internal(e) => e.context("Failed to read from consistent store").into(),
+    }
+}
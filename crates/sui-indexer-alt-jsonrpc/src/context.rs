@@ -1,16 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use async_graphql::dataloader::DataLoader;
 use prometheus::Registry;
 use sui_indexer_alt_reader::{
     bigtable_reader::{BigtableArgs, BigtableReader},
+    consistent_reader::{ConsistentReader, ConsistentReaderArgs},
     kv_loader::KvLoader,
+    kv_reader::KvStoreReader,
     package_resolver::{DbPackageStore, PackageCache},
     pg_reader::db::DbArgs,
     pg_reader::PgReader,
+    rocksdb_reader::RocksDbReader,
 };
 use sui_package_resolver::Resolver;
 use tokio_util::sync::CancellationToken;
@@ -29,10 +32,14 @@ pub(crate) struct Context {
     /// query.
     pg_loader: Arc<DataLoader<PgReader>>,
 
-    /// Access to the kv store for performing point look-ups. This may either be backed by Bigtable
-    /// or Postgres db, depending on the configuration.
+    /// Access to the kv store for performing point look-ups. This may either be backed by Bigtable,
+    /// a local RocksDB or Postgres db, depending on the configuration.
     kv_loader: KvLoader,
 
+    /// Access to the consistent store, for reading the live object set and balances as of a past
+    /// checkpoint.
+    consistent_reader: ConsistentReader,
+
     /// Access to the database for accessing information about types from their packages (again
     /// through the same connection pool as `reader`).
     package_resolver: Arc<Resolver<Arc<PackageCache>>>,
@@ -46,20 +53,25 @@ pub(crate) struct Context {
 
 impl Context {
     /// Set-up access to the stores through all the interfaces available in the context. If
-    /// `bigtable_instance` is set, KV lookups will be sent to it, otherwise they will be sent to
+    /// `bigtable_instance` is set, KV lookups will be sent to it, or if `kv_rocksdb_path` is set,
+    /// they will be sent to the RocksDB KV store at that path, otherwise they will be sent to
     /// the `database. If `database_url` is `None`, the interfaces will be set-up but will fail to
-    /// accept any connections.
+    /// accept any connections. Similarly, if `consistent_reader_args` does not point to a
+    /// consistent store, requests to it will fail.
     pub(crate) async fn new(
         database_url: Option<Url>,
         bigtable_instance: Option<String>,
+        kv_rocksdb_path: Option<PathBuf>,
         db_args: DbArgs,
         bigtable_args: BigtableArgs,
+        consistent_reader_args: ConsistentReaderArgs,
         config: RpcConfig,
         metrics: Arc<RpcMetrics>,
         registry: &Registry,
         cancel: CancellationToken,
     ) -> Result<Self, anyhow::Error> {
-        let pg_reader = PgReader::new(None, database_url, db_args, registry, cancel).await?;
+        let pg_reader =
+            PgReader::new(None, database_url, db_args, registry, cancel.clone()).await?;
         let pg_loader = Arc::new(pg_reader.as_data_loader());
 
         let kv_loader = if let Some(instance_id) = bigtable_instance {
@@ -71,11 +83,18 @@ impl Context {
             )
             .await?;
 
-            KvLoader::new_with_bigtable(Arc::new(bigtable_reader.as_data_loader()))
+            let kv_reader = KvStoreReader::new(bigtable_reader);
+            KvLoader::new_with_kv(Arc::new(kv_reader.as_data_loader()))
+        } else if let Some(path) = kv_rocksdb_path {
+            let kv_reader = KvStoreReader::new(RocksDbReader::new(path));
+            KvLoader::new_with_kv(Arc::new(kv_reader.as_data_loader()))
         } else {
             KvLoader::new_with_pg(pg_loader.clone())
         };
 
+        let consistent_reader =
+            ConsistentReader::new(None, consistent_reader_args, registry, cancel).await?;
+
         let store = Arc::new(PackageCache::new(DbPackageStore::new(pg_loader.clone())));
         let package_resolver = Arc::new(Resolver::new_with_limits(
             store,
@@ -86,6 +105,7 @@ impl Context {
             pg_reader,
             pg_loader,
             kv_loader,
+            consistent_reader,
             package_resolver,
             metrics,
             config: Arc::new(config),
@@ -104,11 +124,16 @@ impl Context {
 
     /// For performing point look-ups on the kv store.
     /// Depends on the configuration of the indexer, the kv store may be backed by
-    /// eitherBigtable or Postgres.
+    /// either Bigtable, RocksDB or Postgres.
     pub(crate) fn kv_loader(&self) -> &KvLoader {
         &self.kv_loader
     }
 
+    /// For reading from the consistent store, at a given checkpoint.
+    pub(crate) fn consistent_reader(&self) -> &ConsistentReader {
+        &self.consistent_reader
+    }
+
     /// For querying type and function signature information.
     pub(crate) fn package_resolver(&self) -> &Resolver<Arc<PackageCache>> {
         self.package_resolver.as_ref()
@@ -4,7 +4,9 @@ use std::time::Duration;
 
 use anyhow::Context as _;
 use serde::de::DeserializeOwned;
-use sui_indexer_alt_reader::object_versions::LatestObjectVersionKey;
+use sui_indexer_alt_reader::object_versions::{
+    CheckpointBoundedObjectVersionKey, LatestObjectVersionKey,
+};
 use sui_types::base_types::ObjectID;
 use sui_types::object::Object;
 
@@ -62,6 +64,34 @@ pub(crate) async fn load_live(
     Ok(object)
 }
 
+/// Load the contents of the version of an object that was live as of checkpoint `checkpoint`, and
+/// deserialize it as an `Object`. Returns `None` if the object was deleted or wrapped as of that
+/// checkpoint, or did not exist yet. The caller is responsible for checking that `checkpoint` is
+/// within the range of checkpoints that `obj_versions` holds data for.
+pub(crate) async fn load_at_checkpoint(
+    ctx: &Context,
+    object_id: ObjectID,
+    checkpoint: u64,
+) -> Result<Option<Object>, anyhow::Error> {
+    let Some(stored) = ctx
+        .pg_loader()
+        .load_one(CheckpointBoundedObjectVersionKey(object_id, checkpoint))
+        .await
+        .context("Failed to load version at checkpoint")?
+    else {
+        return Ok(None);
+    };
+
+    if stored.object_digest.is_none() {
+        return Ok(None);
+    }
+
+    ctx.kv_loader()
+        .load_one_object(object_id, stored.object_version as u64)
+        .await
+        .context("Failed to load object at checkpoint")
+}
+
 /// Fetch the latest version of the object at ID `object_id`, and deserialize its contents as a
 /// Rust type `T`, assuming that it exists and is a Move object (not a package).
 pub(crate) async fn load_live_deserialized<T: DeserializeOwned>(
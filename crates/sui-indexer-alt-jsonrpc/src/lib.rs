@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -9,6 +10,7 @@ use anyhow::Context as _;
 use api::checkpoints::Checkpoints;
 use api::coin::{Coins, DelegationCoins};
 use api::dynamic_fields::DynamicFields;
+use api::events::{Events, QueryEvents};
 use api::move_utils::MoveUtils;
 use api::name_service::NameService;
 use api::objects::{Objects, QueryObjects};
@@ -22,6 +24,7 @@ use metrics::RpcMetrics;
 use prometheus::Registry;
 use serde_json::json;
 use sui_indexer_alt_reader::bigtable_reader::BigtableArgs;
+use sui_indexer_alt_reader::consistent_reader::ConsistentReaderArgs;
 use sui_indexer_alt_reader::pg_reader::db::DbArgs;
 use sui_indexer_alt_reader::system_package_task::{SystemPackageTask, SystemPackageTaskArgs};
 use sui_open_rpc::Project;
@@ -38,6 +41,7 @@ use crate::context::Context;
 pub mod api;
 pub mod args;
 pub mod config;
+mod consistency;
 mod context;
 pub mod data;
 mod error;
@@ -248,12 +252,17 @@ pub struct NodeArgs {
 ///
 /// Access to most reads is controlled by the `database_url` -- if it is `None`, reads will not work.
 /// The only exceptions are the `DelegationCoins` and `DelegationGovernance` modules, which are controlled
-/// by `node_args.fullnode_rpc_url`, which can be omitted to disable reads from this RPC.
+/// by `node_args.fullnode_rpc_url`, which can be omitted to disable reads from this RPC. Latest
+/// balances (`suix_getBalance` without a checkpoint) are also read from this RPC.
 ///
-/// KV queries can optionally be served by a Bigtable instance, if `bigtable_instance` is provided.
-/// Otherwise these requests are served by the database. If a `bigtable_instance` is provided, the
+/// KV queries can optionally be served by a Bigtable instance, if `bigtable_instance` is provided,
+/// or a local RocksDB KV store, if `kv_rocksdb_path` is provided. Otherwise these requests are
+/// served by the database. If a `bigtable_instance` is provided, the
 /// `GOOGLE_APPLICATION_CREDENTIALS` environment variable must point to the credentials JSON file.
 ///
+/// Reads at a past checkpoint are served from the consistent store, if `consistent_reader_args`
+/// points to one. Otherwise requests that supply a checkpoint to read at will fail.
+///
 /// Access to writes (executing and dry-running transactions) is controlled by `node_args.fullnode_rpc_url`,
 /// which can be omitted to disable writes from this RPC.
 ///
@@ -262,8 +271,10 @@ pub struct NodeArgs {
 pub async fn start_rpc(
     database_url: Option<Url>,
     bigtable_instance: Option<String>,
+    kv_rocksdb_path: Option<PathBuf>,
     db_args: DbArgs,
     bigtable_args: BigtableArgs,
+    consistent_reader_args: ConsistentReaderArgs,
     rpc_args: RpcArgs,
     node_args: NodeArgs,
     system_package_task_args: SystemPackageTaskArgs,
@@ -277,8 +288,10 @@ pub async fn start_rpc(
     let context = Context::new(
         database_url,
         bigtable_instance,
+        kv_rocksdb_path,
         db_args,
         bigtable_args,
+        consistent_reader_args,
         rpc_config,
         rpc.metrics(),
         registry,
@@ -294,12 +307,17 @@ pub async fn start_rpc(
     );
 
     rpc.add_module(Checkpoints(context.clone()))?;
-    rpc.add_module(Coins(context.clone()))?;
+    rpc.add_module(Coins::new(
+        node_args.fullnode_rpc_url.clone(),
+        context.clone(),
+    )?)?;
     rpc.add_module(DynamicFields(context.clone()))?;
+    rpc.add_module(Events(context.clone()))?;
     rpc.add_module(Governance(context.clone()))?;
     rpc.add_module(MoveUtils(context.clone()))?;
     rpc.add_module(NameService(context.clone()))?;
     rpc.add_module(Objects(context.clone()))?;
+    rpc.add_module(QueryEvents(context.clone()))?;
     rpc.add_module(QueryObjects(context.clone()))?;
     rpc.add_module(QueryTransactions(context.clone()))?;
     rpc.add_module(Transactions(context.clone()))?;
@@ -40,8 +40,10 @@ async fn main() -> anyhow::Result<()> {
         Command::Rpc {
             database_url,
             bigtable_instance,
+            kv_rocksdb_path,
             db_args,
             bigtable_args,
+            consistent_reader_args,
             rpc_args,
             system_package_task_args,
             metrics_args,
@@ -87,8 +89,10 @@ async fn main() -> anyhow::Result<()> {
             let h_rpc = start_rpc(
                 Some(database_url),
                 bigtable_instance,
+                kv_rocksdb_path,
                 db_args,
                 bigtable_args,
+                consistent_reader_args,
                 rpc_args,
                 node_args,
                 system_package_task_args,
@@ -6,7 +6,6 @@ use std::future::Future;
 use std::time::Duration;
 
 use anyhow::{bail, Context};
-use async_graphql::dataloader::DataLoader;
 use prometheus::Registry;
 use sui_kvstore::{BigTableClient, Checkpoint, KeyValueStoreReader, TransactionData};
 use sui_types::digests::TransactionDigest;
@@ -15,6 +14,8 @@ use sui_types::object::Object;
 use sui_types::storage::ObjectKey;
 use tracing::warn;
 
+use crate::kv_reader::KvReader;
+
 #[derive(clap::Args, Debug, Clone, Default)]
 pub struct BigtableArgs {
     /// Time spent waiting for a request to Bigtable to complete, in milliseconds.
@@ -66,14 +67,15 @@ impl BigtableReader {
             .context("Failed to create BigTable client")?,
         ))
     }
+}
 
-    /// Create a data loader backed by this reader.
-    pub fn as_data_loader(&self) -> DataLoader<Self> {
-        DataLoader::new(self.clone(), tokio::spawn)
+#[async_trait::async_trait]
+impl KvReader for BigtableReader {
+    fn name(&self) -> &'static str {
+        "bigtable"
     }
 
-    /// Get the summary for the latest checkpoint known to Bigtable.
-    pub async fn checkpoint_watermark(&self) -> anyhow::Result<Option<CheckpointSummary>> {
+    async fn checkpoint_watermark(&self) -> anyhow::Result<Option<CheckpointSummary>> {
         measure(
             "watermark",
             &(),
@@ -82,24 +84,21 @@ impl BigtableReader {
         .await
     }
 
-    /// Multi-get checkpoints by sequence number.
-    pub(crate) async fn checkpoints(
+    async fn checkpoints(
         &self,
         keys: &[CheckpointSequenceNumber],
     ) -> anyhow::Result<Vec<Checkpoint>> {
         measure("checkpoints", &keys, self.0.clone().get_checkpoints(keys)).await
     }
 
-    /// Multi-get transactions by transaction digest.
-    pub(crate) async fn transactions(
+    async fn transactions(
         &self,
         keys: &[TransactionDigest],
     ) -> anyhow::Result<Vec<TransactionData>> {
         measure("transactions", &keys, self.0.clone().get_transactions(keys)).await
     }
 
-    /// Multi-get objects by object ID and version.
-    pub(crate) async fn objects(&self, keys: &[ObjectKey]) -> anyhow::Result<Vec<Object>> {
+    async fn objects(&self, keys: &[ObjectKey]) -> anyhow::Result<Vec<Object>> {
         measure("objects", &keys, self.0.clone().get_objects(keys)).await
     }
 }
@@ -11,7 +11,7 @@ use sui_types::{
     messages_checkpoint::{CheckpointContents, CheckpointSummary},
 };
 
-use crate::{bigtable_reader::BigtableReader, error::Error, pg_reader::PgReader};
+use crate::{error::Error, kv_reader::KvStoreReader, pg_reader::PgReader};
 
 /// Key for fetching a checkpoint's content by its sequence number.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -47,7 +47,7 @@ impl Loader<CheckpointKey> for PgReader {
 }
 
 #[async_trait::async_trait]
-impl Loader<CheckpointKey> for BigtableReader {
+impl Loader<CheckpointKey> for KvStoreReader {
     type Value = (
         CheckpointSummary,
         CheckpointContents,
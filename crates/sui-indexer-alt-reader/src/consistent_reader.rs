@@ -9,8 +9,8 @@ use anyhow::{anyhow, Context};
 use prometheus::Registry;
 use sui_indexer_alt_consistent_api::proto::rpc::consistent::v1alpha::{
     consistent_service_client::ConsistentServiceClient, owner::OwnerKind, AvailableRangeRequest,
-    AvailableRangeResponse, End, ListObjectsByTypeRequest, ListOwnedObjectsRequest, Object, Owner,
-    CHECKPOINT_METADATA,
+    AvailableRangeResponse, Balance, End, GetBalanceRequest, ListObjectsByTypeRequest,
+    ListOwnedObjectsRequest, Object, Owner, CHECKPOINT_METADATA,
 };
 use sui_types::base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber};
 use tokio_util::sync::CancellationToken;
@@ -132,6 +132,27 @@ impl ConsistentReader {
         .await
     }
 
+    /// Get the total balance of coins with marker type `coin_type` owned by `owner`, at checkpoint
+    /// `checkpoint`.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_balance(
+        &self,
+        checkpoint: u64,
+        owner: String,
+        coin_type: String,
+    ) -> Result<Balance, Error> {
+        self.request(
+            "get_balance",
+            Some(checkpoint),
+            |mut client, request| async move { client.get_balance(request).await },
+            GetBalanceRequest {
+                owner: Some(owner),
+                coin_type: Some(coin_type),
+            },
+        )
+        .await
+    }
+
     /// Paginate live objects with type filter `object_type`, at checkpoint `checkpoint`.
     #[instrument(skip(self), level = "debug")]
     pub async fn list_objects_by_type(
@@ -1,8 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
+use sui_rpc_api::client::TransactionSimulationResponse;
+use sui_types::transaction::TransactionData;
+use sui_types::transaction_executor::TransactionChecks;
 use tokio_util::sync::CancellationToken;
+use tracing::instrument;
 
 #[derive(clap::Args, Debug, Clone, Default)]
 pub struct FullNodeArgs {
@@ -14,12 +18,23 @@ pub struct FullNodeArgs {
 /// A reader backed by the full node gRPC service.
 #[derive(Clone)]
 pub struct FullNodeClient {
-    #[allow(dead_code)]
     client: Option<sui_rpc_api::client::Client>,
-    #[allow(dead_code)]
     cancel: CancellationToken,
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+
+    /// The full node refused to process the request, because of a problem with its input.
+    #[error("{}", .0.message())]
+    Rejected(#[source] tonic::Status),
+
+    #[error("Full node client not configured")]
+    NotConfigured,
+}
+
 impl FullNodeClient {
     pub async fn new(args: FullNodeArgs, cancel: CancellationToken) -> anyhow::Result<Self> {
         let client = if let Some(url) = &args.full_node_rpc_url {
@@ -30,4 +45,34 @@ impl FullNodeClient {
 
         Ok(Self { client, cancel })
     }
+
+    /// Simulate executing `transaction` against the full node's latest state, without committing
+    /// its effects, with or without transaction `checks`.
+    #[instrument(skip_all, level = "debug")]
+    pub async fn simulate_transaction(
+        &self,
+        transaction: &TransactionData,
+        checks: TransactionChecks,
+    ) -> Result<TransactionSimulationResponse, Error> {
+        let Some(client) = &self.client else {
+            return Err(Error::NotConfigured);
+        };
+
+        tokio::select! {
+            _ = self.cancel.cancelled() => {
+                Err(Error::Internal(anyhow!("Request cancelled")))
+            }
+
+            r = client.simulate_transaction(transaction, checks) => {
+                r.map_err(|status| match status.code() {
+                    tonic::Code::InvalidArgument
+                    | tonic::Code::NotFound
+                    | tonic::Code::FailedPrecondition => Error::Rejected(status),
+                    _ => Error::Internal(
+                        anyhow::Error::from(status).context("Failed to simulate transaction"),
+                    ),
+                })
+            }
+        }
+    }
 }
@@ -6,13 +6,13 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use async_graphql::dataloader::DataLoader;
-use sui_indexer_alt_schema::transactions::StoredTransaction;
+use sui_indexer_alt_schema::transactions::{BalanceChange, StoredTransaction};
 use sui_kvstore::TransactionData as KVTransactionData;
 use sui_types::{
     base_types::ObjectID,
     crypto::AuthorityQuorumSignInfo,
     digests::{TransactionDigest, TransactionEffectsDigest},
-    effects::TransactionEffects,
+    effects::{TransactionEffects, TransactionEvents},
     event::Event,
     message_envelope::Message,
     messages_checkpoint::{CheckpointContents, CheckpointSummary},
@@ -22,30 +22,41 @@ use sui_types::{
 };
 
 use crate::{
-    bigtable_reader::BigtableReader, checkpoints::CheckpointKey, error::Error,
+    checkpoints::CheckpointKey, error::Error, kv_reader::KvStoreReader,
     objects::VersionedObjectKey, pg_reader::PgReader, transactions::TransactionKey,
 };
 
-/// A loader for point lookups in kv stores backed by either Bigtable or Postgres.
+/// A loader for point lookups in kv stores backed by either a [KvStoreReader] (e.g. Bigtable or
+/// RocksDB) or Postgres.
 /// Supported lookups:
 /// - Objects by id and version
 /// - Checkpoints by sequence number
 /// - Transactions by digest
 #[derive(Clone)]
 pub enum KvLoader {
-    Bigtable(Arc<DataLoader<BigtableReader>>),
+    Kv(Arc<DataLoader<KvStoreReader>>),
     Pg(Arc<DataLoader<PgReader>>),
 }
 
-/// A wrapper for the contents of a transaction, either from Bigtable or Postgres.
+/// A wrapper for the contents of a transaction, either from a KV store or Postgres, or returned
+/// directly by a full node that executed or simulated it.
 pub enum TransactionContents {
-    Bigtable(KVTransactionData),
+    Kv(KVTransactionData),
     Pg(StoredTransaction),
+
+    /// A transaction that has not been indexed, so it is not associated with a checkpoint.
+    Executed {
+        data: Box<TransactionData>,
+        signatures: Vec<GenericSignature>,
+        effects: Box<TransactionEffects>,
+        events: Option<TransactionEvents>,
+        balance_changes: Vec<BalanceChange>,
+    },
 }
 
 impl KvLoader {
-    pub fn new_with_bigtable(bigtable_loader: Arc<DataLoader<BigtableReader>>) -> Self {
-        Self::Bigtable(bigtable_loader)
+    pub fn new_with_kv(kv_loader: Arc<DataLoader<KvStoreReader>>) -> Self {
+        Self::Kv(kv_loader)
     }
 
     pub fn new_with_pg(pg_loader: Arc<DataLoader<PgReader>>) -> Self {
@@ -59,7 +70,7 @@ impl KvLoader {
     ) -> Result<Option<Object>, Error> {
         let key = VersionedObjectKey(id, version);
         match self {
-            Self::Bigtable(loader) => loader.load_one(key).await,
+            Self::Kv(loader) => loader.load_one(key).await,
             Self::Pg(loader) => loader
                 .load_one(key)
                 .await?
@@ -80,7 +91,7 @@ impl KvLoader {
         keys: Vec<VersionedObjectKey>,
     ) -> Result<HashMap<VersionedObjectKey, Object>, Error> {
         match self {
-            Self::Bigtable(loader) => loader.load_many(keys).await,
+            Self::Kv(loader) => loader.load_many(keys).await,
             Self::Pg(loader) => {
                 let stored_objects = loader.load_many(keys).await?;
                 let mut results = HashMap::new();
@@ -111,7 +122,7 @@ impl KvLoader {
     > {
         let key = CheckpointKey(sequence_number);
         match self {
-            Self::Bigtable(loader) => loader.load_one(key).await,
+            Self::Kv(loader) => loader.load_one(key).await,
             Self::Pg(loader) => loader
                 .load_one(key)
                 .await?
@@ -138,10 +149,7 @@ impl KvLoader {
     ) -> Result<Option<TransactionContents>, Error> {
         let key = TransactionKey(digest);
         match self {
-            Self::Bigtable(loader) => Ok(loader
-                .load_one(key)
-                .await?
-                .map(TransactionContents::Bigtable)),
+            Self::Kv(loader) => Ok(loader.load_one(key).await?.map(TransactionContents::Kv)),
             Self::Pg(loader) => Ok(loader.load_one(key).await?.map(TransactionContents::Pg)),
         }
     }
@@ -152,7 +160,8 @@ impl TransactionContents {
         match self {
             Self::Pg(stored) => bcs::from_bytes(&stored.raw_transaction)
                 .context("Failed to deserialize transaction data"),
-            Self::Bigtable(kv) => Ok(kv.transaction.data().transaction_data().clone()),
+            Self::Kv(kv) => Ok(kv.transaction.data().transaction_data().clone()),
+            Self::Executed { data, .. } => Ok(data.as_ref().clone()),
         }
     }
 
@@ -160,7 +169,8 @@ impl TransactionContents {
         match self {
             Self::Pg(stored) => TransactionDigest::try_from(stored.tx_digest.clone())
                 .context("Failed to deserialize transaction digest"),
-            Self::Bigtable(kv) => Ok(*kv.transaction.digest()),
+            Self::Kv(kv) => Ok(*kv.transaction.digest()),
+            Self::Executed { data, .. } => Ok(data.digest()),
         }
     }
 
@@ -172,7 +182,8 @@ impl TransactionContents {
 
                 Ok(effects.digest())
             }
-            Self::Bigtable(kv) => Ok(kv.effects.digest()),
+            Self::Kv(kv) => Ok(kv.effects.digest()),
+            Self::Executed { effects, .. } => Ok(effects.digest()),
         }
     }
 
@@ -181,7 +192,8 @@ impl TransactionContents {
             Self::Pg(stored) => {
                 bcs::from_bytes(&stored.user_signatures).context("Failed to deserialize signatures")
             }
-            Self::Bigtable(kv) => Ok(kv.transaction.tx_signatures().to_vec()),
+            Self::Kv(kv) => Ok(kv.transaction.tx_signatures().to_vec()),
+            Self::Executed { signatures, .. } => Ok(signatures.clone()),
         }
     }
 
@@ -190,7 +202,8 @@ impl TransactionContents {
             Self::Pg(stored) => {
                 bcs::from_bytes(&stored.raw_effects).context("Failed to deserialize effects")
             }
-            Self::Bigtable(kv) => Ok(kv.effects.clone()),
+            Self::Kv(kv) => Ok(kv.effects.clone()),
+            Self::Executed { effects, .. } => Ok(effects.as_ref().clone()),
         }
     }
 
@@ -199,36 +212,48 @@ impl TransactionContents {
             Self::Pg(stored) => {
                 bcs::from_bytes(&stored.events).context("Failed to deserialize events")
             }
-            Self::Bigtable(kv) => Ok(kv.events.clone().unwrap_or_default().data),
+            Self::Kv(kv) => Ok(kv.events.clone().unwrap_or_default().data),
+            Self::Executed { events, .. } => Ok(events.clone().unwrap_or_default().data),
         }
     }
 
     pub fn raw_transaction(&self) -> anyhow::Result<Vec<u8>> {
         match self {
             Self::Pg(stored) => Ok(stored.raw_transaction.clone()),
-            Self::Bigtable(kv) => bcs::to_bytes(kv.transaction.data().transaction_data())
+            Self::Kv(kv) => bcs::to_bytes(kv.transaction.data().transaction_data())
                 .context("Failed to serialize transaction"),
+            Self::Executed { data, .. } => {
+                bcs::to_bytes(data.as_ref()).context("Failed to serialize transaction")
+            }
         }
     }
 
     pub fn raw_effects(&self) -> anyhow::Result<Vec<u8>> {
         match self {
             Self::Pg(stored) => Ok(stored.raw_effects.clone()),
-            Self::Bigtable(kv) => bcs::to_bytes(&kv.effects).context("Failed to serialize effects"),
+            Self::Kv(kv) => bcs::to_bytes(&kv.effects).context("Failed to serialize effects"),
+            Self::Executed { effects, .. } => {
+                bcs::to_bytes(effects.as_ref()).context("Failed to serialize effects")
+            }
         }
     }
 
-    pub fn timestamp_ms(&self) -> u64 {
+    /// The timestamp of the checkpoint this transaction was included in, or `None` if it has not
+    /// been indexed.
+    pub fn timestamp_ms(&self) -> Option<u64> {
         match self {
-            Self::Pg(stored) => stored.timestamp_ms as u64,
-            Self::Bigtable(kv) => kv.timestamp,
+            Self::Pg(stored) => Some(stored.timestamp_ms as u64),
+            Self::Kv(kv) => Some(kv.timestamp),
+            Self::Executed { .. } => None,
         }
     }
 
-    pub fn cp_sequence_number(&self) -> u64 {
+    /// The checkpoint this transaction was included in, or `None` if it has not been indexed.
+    pub fn cp_sequence_number(&self) -> Option<u64> {
         match self {
-            Self::Pg(stored) => stored.cp_sequence_number as u64,
-            Self::Bigtable(kv) => kv.checkpoint_number,
+            Self::Pg(stored) => Some(stored.cp_sequence_number as u64),
+            Self::Kv(kv) => Some(kv.checkpoint_number),
+            Self::Executed { .. } => None,
         }
     }
 }
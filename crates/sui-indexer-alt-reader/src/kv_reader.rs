@@ -0,0 +1,83 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_graphql::dataloader::DataLoader;
+use sui_kvstore::{Checkpoint, TransactionData};
+use sui_types::{
+    digests::TransactionDigest,
+    messages_checkpoint::{CheckpointSequenceNumber, CheckpointSummary},
+    object::Object,
+    storage::ObjectKey,
+};
+
+/// A store of full checkpoint, transaction and object data, laid out like the KV store written by
+/// `sui-kvstore`. Implementations can be plugged into a [KvStoreReader] to serve point look-ups
+/// through data loaders.
+#[async_trait::async_trait]
+pub trait KvReader: Send + Sync + 'static {
+    /// A name for this kind of store, used to label its watermark.
+    fn name(&self) -> &'static str;
+
+    /// Get the summary for the latest checkpoint known to the store.
+    async fn checkpoint_watermark(&self) -> anyhow::Result<Option<CheckpointSummary>>;
+
+    /// Multi-get checkpoints by sequence number.
+    async fn checkpoints(
+        &self,
+        keys: &[CheckpointSequenceNumber],
+    ) -> anyhow::Result<Vec<Checkpoint>>;
+
+    /// Multi-get transactions by transaction digest.
+    async fn transactions(
+        &self,
+        keys: &[TransactionDigest],
+    ) -> anyhow::Result<Vec<TransactionData>>;
+
+    /// Multi-get objects by object ID and version.
+    async fn objects(&self, keys: &[ObjectKey]) -> anyhow::Result<Vec<Object>>;
+}
+
+/// A reader backed by any [KvReader] implementation (e.g. Bigtable, or a local RocksDB).
+#[derive(Clone)]
+pub struct KvStoreReader(Arc<dyn KvReader>);
+
+impl KvStoreReader {
+    pub fn new(reader: impl KvReader) -> Self {
+        Self(Arc::new(reader))
+    }
+
+    /// Create a data loader backed by this reader.
+    pub fn as_data_loader(&self) -> DataLoader<Self> {
+        DataLoader::new(self.clone(), tokio::spawn)
+    }
+
+    /// A name for the underlying store, used to label its watermark.
+    pub fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    /// Get the summary for the latest checkpoint known to the underlying store.
+    pub async fn checkpoint_watermark(&self) -> anyhow::Result<Option<CheckpointSummary>> {
+        self.0.checkpoint_watermark().await
+    }
+
+    pub(crate) async fn checkpoints(
+        &self,
+        keys: &[CheckpointSequenceNumber],
+    ) -> anyhow::Result<Vec<Checkpoint>> {
+        self.0.checkpoints(keys).await
+    }
+
+    pub(crate) async fn transactions(
+        &self,
+        keys: &[TransactionDigest],
+    ) -> anyhow::Result<Vec<TransactionData>> {
+        self.0.transactions(keys).await
+    }
+
+    pub(crate) async fn objects(&self, keys: &[ObjectKey]) -> anyhow::Result<Vec<Object>> {
+        self.0.objects(keys).await
+    }
+}
@@ -11,12 +11,14 @@ pub mod epochs;
 pub mod error;
 pub mod full_node_client;
 pub mod kv_loader;
+pub mod kv_reader;
 pub(crate) mod metrics;
 pub mod object_versions;
 pub mod objects;
 pub mod package_resolver;
 pub mod packages;
 pub mod pg_reader;
+pub mod rocksdb_reader;
 pub mod system_package_task;
 pub mod transactions;
 pub mod tx_balance_changes;
@@ -8,7 +8,7 @@ use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl};
 use sui_indexer_alt_schema::{objects::StoredObject, schema::kv_objects};
 use sui_types::{base_types::ObjectID, object::Object, storage::ObjectKey};
 
-use crate::{bigtable_reader::BigtableReader, error::Error, pg_reader::PgReader};
+use crate::{error::Error, kv_reader::KvStoreReader, pg_reader::PgReader};
 
 /// Key for fetching the contents a particular version of an object.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -64,7 +64,7 @@ impl Loader<VersionedObjectKey> for PgReader {
 }
 
 #[async_trait::async_trait]
-impl Loader<VersionedObjectKey> for BigtableReader {
+impl Loader<VersionedObjectKey> for KvStoreReader {
     type Value = Object;
     type Error = Error;
 
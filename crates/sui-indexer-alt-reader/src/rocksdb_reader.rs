@@ -0,0 +1,70 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use sui_kvstore::{Checkpoint, KeyValueStoreReader, RocksDbKvStore, TransactionData};
+use sui_types::digests::TransactionDigest;
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, CheckpointSummary};
+use sui_types::object::Object;
+use sui_types::storage::ObjectKey;
+
+use crate::kv_reader::KvReader;
+
+/// A reader backed by a KV store in a local RocksDB database. The database is opened as a
+/// secondary instance, so that it can be read while it is being written to by the ingestion
+/// process.
+#[derive(Clone)]
+pub struct RocksDbReader(RocksDbKvStore);
+
+impl RocksDbReader {
+    pub fn new(path: PathBuf) -> Self {
+        Self(RocksDbKvStore::open_secondary(path))
+    }
+}
+
+#[async_trait::async_trait]
+impl KvReader for RocksDbReader {
+    fn name(&self) -> &'static str {
+        "rocksdb"
+    }
+
+    async fn checkpoint_watermark(&self) -> anyhow::Result<Option<CheckpointSummary>> {
+        self.0
+            .clone()
+            .get_latest_checkpoint_summary()
+            .await
+            .context("RocksDB read error for method: watermark")
+    }
+
+    async fn checkpoints(
+        &self,
+        keys: &[CheckpointSequenceNumber],
+    ) -> anyhow::Result<Vec<Checkpoint>> {
+        self.0
+            .clone()
+            .get_checkpoints(keys)
+            .await
+            .context("RocksDB read error for method: checkpoints")
+    }
+
+    async fn transactions(
+        &self,
+        keys: &[TransactionDigest],
+    ) -> anyhow::Result<Vec<TransactionData>> {
+        self.0
+            .clone()
+            .get_transactions(keys)
+            .await
+            .context("RocksDB read error for method: transactions")
+    }
+
+    async fn objects(&self, keys: &[ObjectKey]) -> anyhow::Result<Vec<Object>> {
+        self.0
+            .clone()
+            .get_objects(keys)
+            .await
+            .context("RocksDB read error for method: objects")
+    }
+}
@@ -9,7 +9,7 @@ use sui_indexer_alt_schema::{schema::kv_transactions, transactions::StoredTransa
 use sui_kvstore::TransactionData;
 use sui_types::digests::TransactionDigest;
 
-use crate::{bigtable_reader::BigtableReader, error::Error, pg_reader::PgReader};
+use crate::{error::Error, kv_reader::KvStoreReader, pg_reader::PgReader};
 
 /// Key for fetching transaction contents (TransactionData, Effects, and Events) by digest.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -53,7 +53,7 @@ impl Loader<TransactionKey> for PgReader {
 }
 
 #[async_trait::async_trait]
-impl Loader<TransactionKey> for BigtableReader {
+impl Loader<TransactionKey> for KvStoreReader {
     type Value = TransactionData;
     type Error = Error;
 
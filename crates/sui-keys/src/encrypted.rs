@@ -0,0 +1,689 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(unix)]
+use crate::keystore::set_reduced_file_permissions;
+use crate::keystore::{
+    validate_alias, AccountKeystore, Alias, FileBasedKeystore, ALIASES_FILE_EXTENSION,
+};
+use crate::random_names::random_name;
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail, ensure, Context};
+use async_trait::async_trait;
+use fastcrypto::encoding::{Base64, Encoding};
+use rand::RngCore;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Environment variable holding the passphrase used to unlock an encrypted keystore on first use,
+/// when it has not been explicitly unlocked.
+pub const SUI_KEYSTORE_PASSPHRASE_ENV: &str = "SUI_KEYSTORE_PASSPHRASE";
+
+/// Reads the passphrase of a locked keystore (given its path) when its keys are first needed, for
+/// example by prompting the user.
+pub type PassphrasePrompt = Box<dyn Fn(&Path) -> Result<String, anyhow::Error> + Send + Sync>;
+
+const ENCRYPTED_KEYSTORE_VERSION: u8 = 1;
+const KDF_SCRYPT: &str = "scrypt";
+const CIPHER_AES_256_GCM: &str = "aes-256-gcm";
+
+const SALT_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+/// Parameters of the password-based key derivation function used to turn a passphrase into the
+/// encryption key of the keystore.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    pub name: String,
+    /// Base 2 logarithm of the scrypt CPU/memory cost parameter `N`.
+    pub log_n: u8,
+    /// scrypt block size parameter.
+    pub r: u32,
+    /// scrypt parallelization parameter.
+    pub p: u32,
+    /// Base64 encoded random salt.
+    pub salt: String,
+}
+
+impl KdfParams {
+    /// scrypt with `N = 2^17, r = 8, p = 1` and a fresh random salt.
+    pub fn new_scrypt() -> Self {
+        Self::new_scrypt_with_cost(17, 8, 1)
+    }
+
+    /// scrypt with the given cost parameters and a fresh random salt. Lower costs are only
+    /// appropriate for tests.
+    pub fn new_scrypt_with_cost(log_n: u8, r: u32, p: u32) -> Self {
+        let mut salt = [0u8; SALT_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Self {
+            name: KDF_SCRYPT.to_string(),
+            log_n,
+            r,
+            p,
+            salt: Base64::encode(salt),
+        }
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<Zeroizing<[u8; KEY_LENGTH]>, anyhow::Error> {
+        ensure!(
+            self.name == KDF_SCRYPT,
+            "Unsupported key derivation function in keystore: {}",
+            self.name
+        );
+        let salt =
+            Base64::decode(&self.salt).map_err(|e| anyhow!("Invalid salt in keystore: {e}"))?;
+        let params = scrypt::Params::new(self.log_n, self.r, self.p)
+            .map_err(|e| anyhow!("Invalid scrypt parameters in keystore: {e}"))?;
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        scrypt::scrypt(passphrase.as_bytes(), &salt, &params, key.as_mut())
+            .map_err(|e| anyhow!("Cannot derive keystore encryption key: {e}"))?;
+        Ok(key)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct CipherParams {
+    name: String,
+    /// Base64 encoded nonce, freshly generated on every write.
+    nonce: String,
+}
+
+/// On-disk format of an encrypted keystore. The ciphertext decrypts to the same JSON array of
+/// Base64 encoded `flag || privkey` strings that [`FileBasedKeystore`] stores in plaintext.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct EncryptedKeystoreFile {
+    version: u8,
+    kdf: KdfParams,
+    cipher: CipherParams,
+    /// Base64 encoded AES-256-GCM ciphertext, including the authentication tag.
+    ciphertext: String,
+}
+
+impl EncryptedKeystoreFile {
+    /// The envelope header is authenticated along with the ciphertext, so that tampering with the
+    /// KDF parameters is detected on decryption rather than silently weakening the keystore.
+    fn associated_data(version: u8, kdf: &KdfParams, cipher_name: &str) -> Vec<u8> {
+        let mut aad = vec![version];
+        aad.extend_from_slice(
+            &bcs::to_bytes(&(&kdf.name, kdf.log_n, kdf.r, kdf.p, &kdf.salt, cipher_name))
+                .expect("serializing keystore header cannot fail"),
+        );
+        aad
+    }
+
+    fn seal(
+        keys: &BTreeMap<SuiAddress, SuiKeyPair>,
+        kdf: &KdfParams,
+        encryption_key: &[u8; KEY_LENGTH],
+    ) -> Result<Self, anyhow::Error> {
+        let plaintext = Zeroizing::new(
+            serde_json::to_vec(&keys.values().map(|k| k.encode_base64()).collect::<Vec<_>>())
+                .context("Cannot serialize keystore keys")?,
+        );
+
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let aad = Self::associated_data(ENCRYPTED_KEYSTORE_VERSION, kdf, CIPHER_AES_256_GCM);
+        let cipher = Aes256Gcm::new_from_slice(encryption_key)
+            .map_err(|_| anyhow!("Invalid keystore encryption key length"))?;
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext.as_slice(),
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| anyhow!("Cannot encrypt keystore"))?;
+
+        Ok(Self {
+            version: ENCRYPTED_KEYSTORE_VERSION,
+            kdf: kdf.clone(),
+            cipher: CipherParams {
+                name: CIPHER_AES_256_GCM.to_string(),
+                nonce: Base64::encode(nonce),
+            },
+            ciphertext: Base64::encode(ciphertext),
+        })
+    }
+
+    fn open(
+        &self,
+        encryption_key: &[u8; KEY_LENGTH],
+    ) -> Result<BTreeMap<SuiAddress, SuiKeyPair>, anyhow::Error> {
+        ensure!(
+            self.version == ENCRYPTED_KEYSTORE_VERSION,
+            "Unsupported encrypted keystore version: {}",
+            self.version
+        );
+        ensure!(
+            self.cipher.name == CIPHER_AES_256_GCM,
+            "Unsupported cipher in keystore: {}",
+            self.cipher.name
+        );
+
+        let nonce = Base64::decode(&self.cipher.nonce)
+            .map_err(|e| anyhow!("Invalid nonce in keystore: {e}"))?;
+        ensure!(
+            nonce.len() == NONCE_LENGTH,
+            "Invalid nonce length in keystore"
+        );
+        let ciphertext = Base64::decode(&self.ciphertext)
+            .map_err(|e| anyhow!("Invalid ciphertext in keystore: {e}"))?;
+
+        let aad = Self::associated_data(self.version, &self.kdf, &self.cipher.name);
+        let cipher = Aes256Gcm::new_from_slice(encryption_key)
+            .map_err(|_| anyhow!("Invalid keystore encryption key length"))?;
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: &ciphertext,
+                        aad: &aad,
+                    },
+                )
+                .map_err(|_| anyhow!("Incorrect passphrase, or the keystore file is corrupted"))?,
+        );
+
+        let mut kp_strings: Vec<String> = serde_json::from_slice(&plaintext)
+            .context("Cannot deserialize the decrypted keystore")?;
+        let keys = kp_strings
+            .iter()
+            .map(|kpstr| {
+                let key = SuiKeyPair::decode_base64(kpstr);
+                key.map(|k| (SuiAddress::from(&k.public()), k))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| anyhow!("Invalid key in encrypted keystore: {e}"));
+        kp_strings.zeroize();
+        keys
+    }
+}
+
+/// Key material that is only available while the keystore is unlocked.
+struct UnlockedKeys {
+    keys: BTreeMap<SuiAddress, SuiKeyPair>,
+    kdf: KdfParams,
+    encryption_key: Zeroizing<[u8; KEY_LENGTH]>,
+}
+
+/// A keystore that keeps its private keys encrypted at rest with a key derived from a passphrase.
+///
+/// Aliases and public keys are stored in plaintext in the `.aliases` file next to the keystore, so
+/// addresses can be listed while the keystore is locked. Signing and exporting keys requires the
+/// keystore to be unlocked, either explicitly with [`EncryptedFileBasedKeystore::unlock`] or, on
+/// first use, with the passphrase in the `SUI_KEYSTORE_PASSPHRASE` environment variable or from
+/// the keystore's [`PassphrasePrompt`].
+pub struct EncryptedFileBasedKeystore {
+    aliases: BTreeMap<SuiAddress, Alias>,
+    /// The encrypted keys as last read from or written to disk, `None` for a new keystore.
+    envelope: Option<EncryptedKeystoreFile>,
+    unlocked: OnceLock<UnlockedKeys>,
+    prompt: Option<PassphrasePrompt>,
+    path: PathBuf,
+}
+
+impl Serialize for EncryptedFileBasedKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.path.to_str().unwrap_or(""))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedFileBasedKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        EncryptedFileBasedKeystore::load(&PathBuf::from(String::deserialize(deserializer)?))
+            .map_err(D::Error::custom)
+    }
+}
+
+#[async_trait]
+impl AccountKeystore for EncryptedFileBasedKeystore {
+    async fn sign_hashed(
+        &self,
+        address: &SuiAddress,
+        msg: &[u8],
+    ) -> Result<Signature, signature::Error> {
+        Ok(Signature::new_hashed(msg, self.signing_key(address)?))
+    }
+
+    async fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize + Sync,
+    {
+        Ok(Signature::new_secure(
+            &IntentMessage::new(intent, msg),
+            self.signing_key(address)?,
+        ))
+    }
+
+    async fn import(
+        &mut self,
+        alias: Option<String>,
+        keypair: SuiKeyPair,
+    ) -> Result<(), anyhow::Error> {
+        let address: SuiAddress = (&keypair.public()).into();
+        let alias = self.create_alias(alias)?;
+        let public_key_base64 = keypair.public().encode_base64();
+        self.unlocked_mut()?.keys.insert(address, keypair);
+        self.aliases.insert(
+            address,
+            Alias {
+                alias,
+                public_key_base64,
+            },
+        );
+        self.save().await
+    }
+
+    async fn remove(&mut self, address: SuiAddress) -> Result<(), anyhow::Error> {
+        self.unlocked_mut()?.keys.remove(&address);
+        self.aliases.remove(&address);
+        self.save().await
+    }
+
+    /// Return the public keys of every stored key. This does not require the keystore to be
+    /// unlocked.
+    fn entries(&self) -> Vec<PublicKey> {
+        self.aliases
+            .values()
+            .filter_map(|alias| PublicKey::decode_base64(&alias.public_key_base64).ok())
+            .collect()
+    }
+
+    fn export(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        match self.unlocked()?.keys.get(address) {
+            Some(key) => Ok(key),
+            None => Err(anyhow!("Cannot find key for address: [{address}]")),
+        }
+    }
+
+    fn addresses_with_alias(&self) -> Vec<(&SuiAddress, &Alias)> {
+        self.aliases.iter().collect::<Vec<_>>()
+    }
+
+    fn aliases(&self) -> Vec<&Alias> {
+        self.aliases.values().collect()
+    }
+
+    fn aliases_mut(&mut self) -> Vec<&mut Alias> {
+        self.aliases.values_mut().collect()
+    }
+
+    fn get_alias(&self, address: &SuiAddress) -> Result<String, anyhow::Error> {
+        match self.aliases.get(address) {
+            Some(alias) => Ok(alias.alias.clone()),
+            None => bail!("Cannot find alias for address {address}"),
+        }
+    }
+
+    /// This function returns an error if the provided alias already exists. If the alias
+    /// has not already been used, then it returns the alias.
+    /// If no alias has been passed, it will generate a new alias.
+    fn create_alias(&self, alias: Option<String>) -> Result<String, anyhow::Error> {
+        match alias {
+            Some(a) if self.alias_exists(&a) => {
+                bail!("Alias {a} already exists. Please choose another alias.")
+            }
+            Some(a) => validate_alias(&a),
+            None => Ok(random_name(
+                &self
+                    .aliases()
+                    .into_iter()
+                    .map(|x| x.alias.to_string())
+                    .collect::<HashSet<_>>(),
+            )),
+        }
+    }
+
+    /// Updates an old alias to the new alias and saves it to the alias file. Aliases are not
+    /// encrypted, so this does not require the keystore to be unlocked.
+    async fn update_alias(
+        &mut self,
+        old_alias: &str,
+        new_alias: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        let new_alias_name = self.update_alias_value(old_alias, new_alias)?;
+        self.save_aliases().await?;
+        Ok(new_alias_name)
+    }
+}
+
+impl EncryptedFileBasedKeystore {
+    /// Load a locked keystore from `path`. If no file exists yet, the keystore is empty and its
+    /// passphrase is set by the first call to [`Self::unlock`].
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let envelope = if path.exists() {
+            #[cfg(unix)]
+            let _ = set_reduced_file_permissions(path);
+
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("Cannot open the keystore file: {}", path.display()))?;
+            Some(
+                serde_json::from_str::<EncryptedKeystoreFile>(&contents).with_context(|| {
+                    format!(
+                        "Cannot deserialize the encrypted keystore file: {}",
+                        path.display()
+                    )
+                })?,
+            )
+        } else {
+            None
+        };
+
+        let mut aliases_path = path.to_path_buf();
+        aliases_path.set_extension(ALIASES_FILE_EXTENSION);
+        let aliases = if aliases_path.exists() {
+            let contents = std::fs::read_to_string(&aliases_path).with_context(|| {
+                format!(
+                    "Cannot open aliases file in keystore: {}",
+                    aliases_path.display()
+                )
+            })?;
+            let aliases: Vec<Alias> = serde_json::from_str(&contents).with_context(|| {
+                format!(
+                    "Cannot deserialize aliases file in keystore: {}",
+                    aliases_path.display()
+                )
+            })?;
+            aliases
+                .into_iter()
+                .map(|alias| {
+                    let key = PublicKey::decode_base64(&alias.public_key_base64);
+                    key.map(|k| (Into::<SuiAddress>::into(&k), alias))
+                })
+                .collect::<Result<BTreeMap<_, _>, _>>()
+                .map_err(|e| {
+                    anyhow!(
+                        "Invalid aliases file in keystore: {}. {}",
+                        aliases_path.display(),
+                        e
+                    )
+                })?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            aliases,
+            envelope,
+            unlocked: OnceLock::new(),
+            prompt: None,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Create a new, empty, unlocked keystore at `path` protected by `passphrase`. Fails if a
+    /// keystore already exists at `path`.
+    pub async fn create(
+        path: &Path,
+        passphrase: &str,
+        kdf: KdfParams,
+    ) -> Result<Self, anyhow::Error> {
+        ensure!(
+            !path.exists(),
+            "A keystore already exists at {}",
+            path.display()
+        );
+        let encryption_key = kdf.derive_key(passphrase)?;
+        let mut keystore = Self {
+            aliases: BTreeMap::new(),
+            envelope: None,
+            unlocked: OnceLock::from(UnlockedKeys {
+                keys: BTreeMap::new(),
+                kdf,
+                encryption_key,
+            }),
+            prompt: None,
+            path: path.to_path_buf(),
+        };
+        keystore.save_keystore().await?;
+        Ok(keystore)
+    }
+
+    /// Returns true if the file at `path` is an encrypted keystore, as opposed to a plaintext
+    /// [`FileBasedKeystore`] or a missing file.
+    pub fn is_encrypted_keystore(path: &Path) -> bool {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<EncryptedKeystoreFile>(&contents).ok())
+            .is_some()
+    }
+
+    /// Migrate a plaintext keystore to an encrypted one, in place. The plaintext keystore file is
+    /// overwritten with its encrypted form, and the aliases file is kept as is.
+    pub async fn migrate_from_plaintext(
+        keystore: FileBasedKeystore,
+        passphrase: &str,
+        kdf: KdfParams,
+    ) -> Result<Self, anyhow::Error> {
+        let (keys, aliases, path) = keystore.into_parts();
+        let path = path.ok_or_else(|| anyhow!("Cannot migrate a keystore without a path"))?;
+        let encryption_key = kdf.derive_key(passphrase)?;
+        let mut keystore = Self {
+            aliases,
+            envelope: None,
+            unlocked: OnceLock::from(UnlockedKeys {
+                keys,
+                kdf,
+                encryption_key,
+            }),
+            prompt: None,
+            path,
+        };
+        keystore.save().await?;
+        Ok(keystore)
+    }
+
+    /// Decrypt the keystore and write it back to disk as a plaintext [`FileBasedKeystore`]. The
+    /// keystore must be unlocked.
+    pub async fn migrate_to_plaintext(mut self) -> Result<FileBasedKeystore, anyhow::Error> {
+        self.unlocked()?;
+        let unlocked = self.unlocked.take().expect("keystore was unlocked above");
+        let keystore =
+            FileBasedKeystore::from_parts(unlocked.keys, self.aliases, Some(self.path.clone()));
+        keystore.save().await?;
+        Ok(keystore)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.unlocked.get().is_none()
+    }
+
+    /// Decrypt the keys with `passphrase` and keep them in memory until [`Self::lock`] is called
+    /// or the keystore is dropped. Unlocking a keystore that has never been written sets its
+    /// passphrase.
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), anyhow::Error> {
+        if self.is_locked() {
+            let unlocked = self.decrypt(passphrase)?;
+            let _ = self.unlocked.set(unlocked);
+        }
+        Ok(())
+    }
+
+    /// Drop the decrypted keys and the derived encryption key from memory.
+    pub fn lock(&mut self) {
+        self.unlocked.take();
+    }
+
+    /// Read the passphrase with `prompt` if the keystore's keys are needed while it is locked, and
+    /// the passphrase is not set in the `SUI_KEYSTORE_PASSPHRASE` environment variable.
+    pub fn set_passphrase_prompt(&mut self, prompt: PassphrasePrompt) {
+        self.prompt = Some(prompt);
+    }
+
+    /// Re-encrypt the keystore under `new_passphrase`, with a fresh salt. `old_passphrase` must
+    /// match the current passphrase, even if the keystore is already unlocked.
+    pub async fn change_passphrase(
+        &mut self,
+        old_passphrase: &str,
+        new_passphrase: &str,
+        kdf: KdfParams,
+    ) -> Result<(), anyhow::Error> {
+        let mut unlocked = self.decrypt(old_passphrase)?;
+        unlocked.encryption_key = kdf.derive_key(new_passphrase)?;
+        unlocked.kdf = kdf;
+        self.unlocked = OnceLock::from(unlocked);
+        self.save_keystore().await
+    }
+
+    pub async fn save(&mut self) -> Result<(), anyhow::Error> {
+        self.save_aliases().await?;
+        self.save_keystore().await?;
+        Ok(())
+    }
+
+    pub async fn save_aliases(&self) -> Result<(), anyhow::Error> {
+        let aliases_store = serde_json::to_string_pretty(
+            &self.aliases.values().collect::<Vec<_>>(),
+        )
+        .with_context(|| {
+            format!(
+                "Cannot serialize aliases to file in keystore: {}",
+                self.path.display()
+            )
+        })?;
+
+        let mut aliases_path = self.path.clone();
+        aliases_path.set_extension(ALIASES_FILE_EXTENSION);
+        // no reactor for tokio::fs::write in simtest, so we use spawn_blocking
+        tokio::task::spawn_blocking(move || std::fs::write(aliases_path, aliases_store))
+            .await?
+            .with_context(|| format!("Cannot write aliases to file: {}", self.path.display()))?;
+        Ok(())
+    }
+
+    /// Encrypt the keys under a fresh nonce and write them to disk. The keystore must be unlocked.
+    pub async fn save_keystore(&mut self) -> Result<(), anyhow::Error> {
+        let envelope = {
+            let unlocked = self.unlocked()?;
+            EncryptedKeystoreFile::seal(&unlocked.keys, &unlocked.kdf, &unlocked.encryption_key)?
+        };
+        let store = serde_json::to_string_pretty(&envelope).with_context(|| {
+            format!("Cannot serialize keystore to file: {}", self.path.display())
+        })?;
+
+        let keystore_path = self.path.clone();
+        // Write to a temporary file first, so that a crash never leaves a truncated keystore.
+        // no reactor for tokio::fs::write in simtest, so we use spawn_blocking
+        tokio::task::spawn_blocking(move || {
+            let mut tmp_path = keystore_path.clone();
+            tmp_path.set_extension("tmp");
+            std::fs::write(&tmp_path, store)?;
+            #[cfg(unix)]
+            let _ = set_reduced_file_permissions(&tmp_path).inspect_err(|error| {
+                eprintln!(
+                    "While attempting to set reduced file permissions on '{}'. Cannot set \
+                        permissions for keystore file. Error: {error}",
+                    tmp_path.display()
+                );
+            });
+            std::fs::rename(&tmp_path, &keystore_path)
+        })
+        .await?
+        .with_context(|| format!("Cannot write keystore to file: {}", self.path.display()))?;
+        self.envelope = Some(envelope);
+        Ok(())
+    }
+
+    fn decrypt(&self, passphrase: &str) -> Result<UnlockedKeys, anyhow::Error> {
+        let Some(envelope) = &self.envelope else {
+            // Nothing has been written yet, so this passphrase becomes the keystore's passphrase.
+            let kdf = KdfParams::new_scrypt();
+            let encryption_key = kdf.derive_key(passphrase)?;
+            return Ok(UnlockedKeys {
+                keys: BTreeMap::new(),
+                kdf,
+                encryption_key,
+            });
+        };
+
+        let encryption_key = envelope.kdf.derive_key(passphrase)?;
+        let keys = envelope.open(&encryption_key)?;
+        Ok(UnlockedKeys {
+            keys,
+            kdf: envelope.kdf.clone(),
+            encryption_key,
+        })
+    }
+
+    /// Access the unlocked keys, unlocking the keystore with the passphrase from the
+    /// `SUI_KEYSTORE_PASSPHRASE` environment variable, or from the passphrase prompt, if it is
+    /// still locked.
+    fn unlocked(&self) -> Result<&UnlockedKeys, anyhow::Error> {
+        if let Some(unlocked) = self.unlocked.get() {
+            return Ok(unlocked);
+        }
+
+        let passphrase = match (passphrase_from_env()?, &self.prompt) {
+            (Some(passphrase), _) => passphrase,
+            (None, Some(prompt)) => Zeroizing::new(prompt(&self.path)?),
+            (None, None) => bail!(
+                "Keystore {} is locked. Unlock it with its passphrase, or set the \
+                {SUI_KEYSTORE_PASSPHRASE_ENV} environment variable",
+                self.path.display()
+            ),
+        };
+
+        let unlocked = self.decrypt(&passphrase)?;
+        Ok(self.unlocked.get_or_init(|| unlocked))
+    }
+
+    fn unlocked_mut(&mut self) -> Result<&mut UnlockedKeys, anyhow::Error> {
+        self.unlocked()?;
+        Ok(self
+            .unlocked
+            .get_mut()
+            .expect("keystore was unlocked above"))
+    }
+
+    fn signing_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, signature::Error> {
+        self.unlocked()
+            .map_err(|e| signature::Error::from_source(e.to_string()))?
+            .keys
+            .get(address)
+            .ok_or_else(|| {
+                signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
+            })
+    }
+}
+
+/// The keystore passphrase from the `SUI_KEYSTORE_PASSPHRASE` environment variable, if it is set.
+/// The variable being set but empty is an error, rather than an empty passphrase.
+pub fn passphrase_from_env() -> Result<Option<Zeroizing<String>>, anyhow::Error> {
+    let Ok(passphrase) = std::env::var(SUI_KEYSTORE_PASSPHRASE_ENV) else {
+        return Ok(None);
+    };
+    ensure!(
+        !passphrase.is_empty(),
+        "The {SUI_KEYSTORE_PASSPHRASE_ENV} environment variable is set, but empty"
+    );
+    Ok(Some(Zeroizing::new(passphrase)))
+}
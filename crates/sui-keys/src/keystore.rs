@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub use crate::encrypted::EncryptedFileBasedKeystore;
 pub use crate::external::External;
 use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
 use crate::key_identity::KeyIdentity;
@@ -38,6 +39,7 @@ pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
     External(External),
+    Encrypted(EncryptedFileBasedKeystore),
 }
 
 pub struct LocalGenerate {
@@ -228,6 +230,20 @@ impl Display for Keystore {
             Keystore::External(_external) => {
                 writeln!(writer, "Keystore Type : External")
             }
+            Keystore::Encrypted(encrypted) => {
+                writeln!(writer, "Keystore Type : Encrypted")?;
+                writeln!(writer, "Keystore Path : {:?}", encrypted.path())?;
+                write!(
+                    writer,
+                    "Keystore State: {}",
+                    if encrypted.is_locked() {
+                        "Locked"
+                    } else {
+                        "Unlocked"
+                    }
+                )?;
+                write!(f, "{}", writer)
+            }
         }
     }
 }
@@ -564,10 +580,32 @@ impl FileBasedKeystore {
     pub fn key_pairs(&self) -> Vec<&SuiKeyPair> {
         self.keys.values().collect()
     }
+
+    pub(crate) fn from_parts(
+        keys: BTreeMap<SuiAddress, SuiKeyPair>,
+        aliases: BTreeMap<SuiAddress, Alias>,
+        path: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            keys,
+            aliases,
+            path,
+        }
+    }
+
+    pub(crate) fn into_parts(
+        self,
+    ) -> (
+        BTreeMap<SuiAddress, SuiKeyPair>,
+        BTreeMap<SuiAddress, Alias>,
+        Option<PathBuf>,
+    ) {
+        (self.keys, self.aliases, self.path)
+    }
 }
 
 #[cfg(unix)]
-fn set_reduced_file_permissions(path: impl AsRef<Path>) -> Result<(), anyhow::Error> {
+pub(crate) fn set_reduced_file_permissions(path: impl AsRef<Path>) -> Result<(), anyhow::Error> {
     let path = path.as_ref();
     let metadata = fs::metadata(path)?;
     let mode = metadata.permissions().mode();
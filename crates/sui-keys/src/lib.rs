@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod encrypted;
 pub mod external;
 pub mod key_derive;
 pub mod key_identity;
@@ -3,15 +3,18 @@
 
 use std::fs;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use fastcrypto::hash::HashFunction;
 use fastcrypto::traits::EncodeDecodeBase64;
 use sui_keys::key_derive::generate_new_key;
 use tempfile::TempDir;
 
+use sui_keys::encrypted::KdfParams;
 use sui_keys::keystore::{
-    AccountKeystore, Alias, FileBasedKeystore, GenerateOptions, GeneratedKey, InMemKeystore,
-    Keystore, ALIASES_FILE_EXTENSION,
+    AccountKeystore, Alias, EncryptedFileBasedKeystore, FileBasedKeystore, GenerateOptions,
+    GeneratedKey, InMemKeystore, Keystore, ALIASES_FILE_EXTENSION,
 };
 use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignatureInner};
 use sui_types::{
@@ -366,3 +369,150 @@ async fn keystore_file_permissions_test() {
         "Keystore file permissions should remain 0o600 after operations"
     );
 }
+
+/// Cheap scrypt parameters, so that tests do not spend their time deriving keys.
+fn test_kdf() -> KdfParams {
+    KdfParams::new_scrypt_with_cost(4, 8, 1)
+}
+
+#[tokio::test]
+async fn encrypted_keystore_lock_unlock_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+
+    let mut keystore = Keystore::from(
+        EncryptedFileBasedKeystore::create(&keystore_path, "passphrase", test_kdf())
+            .await
+            .unwrap(),
+    );
+    let GeneratedKey { address, .. } = keystore
+        .generate(Some("my_key".to_string()), GenerateOptions::default())
+        .await
+        .unwrap();
+    let exported = keystore.export(&address).unwrap().encode_base64();
+
+    // The private key never hits the disk in plaintext.
+    assert!(EncryptedFileBasedKeystore::is_encrypted_keystore(
+        &keystore_path
+    ));
+    assert!(!fs::read_to_string(&keystore_path)
+        .unwrap()
+        .contains(&exported));
+
+    // A freshly loaded keystore is locked, but its addresses and aliases are readable.
+    let mut keystore = EncryptedFileBasedKeystore::load(&keystore_path).unwrap();
+    assert!(keystore.is_locked());
+    assert_eq!(keystore.addresses(), vec![address]);
+    assert_eq!(keystore.get_alias(&address).unwrap(), "my_key");
+    assert!(keystore.export(&address).is_err());
+
+    assert!(keystore.unlock("wrong passphrase").is_err());
+    assert!(keystore.is_locked());
+
+    keystore.unlock("passphrase").unwrap();
+    assert_eq!(keystore.export(&address).unwrap().encode_base64(), exported);
+
+    keystore.lock();
+    assert!(keystore.is_locked());
+    assert!(keystore.export(&address).is_err());
+}
+
+#[tokio::test]
+async fn encrypted_keystore_passphrase_prompt_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+
+    let mut keystore = EncryptedFileBasedKeystore::create(&keystore_path, "passphrase", test_kdf())
+        .await
+        .unwrap();
+    let GeneratedKey { address, .. } = keystore
+        .generate(None, GenerateOptions::default())
+        .await
+        .unwrap();
+
+    let prompts = Arc::new(AtomicUsize::new(0));
+    let mut keystore = EncryptedFileBasedKeystore::load(&keystore_path).unwrap();
+    keystore.set_passphrase_prompt(Box::new({
+        let prompts = prompts.clone();
+        move |_| {
+            prompts.fetch_add(1, Ordering::Relaxed);
+            Ok("passphrase".to_string())
+        }
+    }));
+
+    // Reading addresses and aliases does not need the passphrase.
+    assert_eq!(keystore.addresses(), vec![address]);
+    assert_eq!(prompts.load(Ordering::Relaxed), 0);
+
+    // The passphrase is only prompted for once the keys are needed, and only once.
+    keystore.sign_hashed(&address, b"message").await.unwrap();
+    keystore.sign_hashed(&address, b"message").await.unwrap();
+    assert!(!keystore.is_locked());
+    assert_eq!(prompts.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test]
+async fn encrypted_keystore_change_passphrase_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+
+    let mut keystore = EncryptedFileBasedKeystore::create(&keystore_path, "old", test_kdf())
+        .await
+        .unwrap();
+    let GeneratedKey { address, .. } = keystore
+        .generate(None, GenerateOptions::default())
+        .await
+        .unwrap();
+
+    assert!(keystore
+        .change_passphrase("wrong", "new", test_kdf())
+        .await
+        .is_err());
+    keystore
+        .change_passphrase("old", "new", test_kdf())
+        .await
+        .unwrap();
+
+    let mut keystore = EncryptedFileBasedKeystore::load(&keystore_path).unwrap();
+    assert!(keystore.unlock("old").is_err());
+    keystore.unlock("new").unwrap();
+    assert!(keystore.export(&address).is_ok());
+}
+
+#[tokio::test]
+async fn encrypted_keystore_migration_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+
+    let mut plaintext = FileBasedKeystore::load_or_create(&keystore_path).unwrap();
+    let GeneratedKey { address, .. } = plaintext
+        .generate(Some("migrated".to_string()), GenerateOptions::default())
+        .await
+        .unwrap();
+    let exported = plaintext.export(&address).unwrap().encode_base64();
+    assert!(!EncryptedFileBasedKeystore::is_encrypted_keystore(
+        &keystore_path
+    ));
+
+    EncryptedFileBasedKeystore::migrate_from_plaintext(plaintext, "passphrase", test_kdf())
+        .await
+        .unwrap();
+    assert!(EncryptedFileBasedKeystore::is_encrypted_keystore(
+        &keystore_path
+    ));
+    assert!(FileBasedKeystore::load_or_create(&keystore_path).is_err());
+
+    let mut keystore = EncryptedFileBasedKeystore::load(&keystore_path).unwrap();
+    assert_eq!(keystore.get_alias(&address).unwrap(), "migrated");
+    keystore.unlock("passphrase").unwrap();
+    assert_eq!(keystore.export(&address).unwrap().encode_base64(), exported);
+
+    // Migrating back restores the original plaintext keystore.
+    keystore.migrate_to_plaintext().await.unwrap();
+    let plaintext = FileBasedKeystore::load_or_create(&keystore_path).unwrap();
+    assert_eq!(
+        plaintext.export(&address).unwrap().encode_base64(),
+        exported
+    );
+    assert_eq!(plaintext.get_alias(&address).unwrap(), "migrated");
+}
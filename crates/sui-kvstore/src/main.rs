@@ -1,12 +1,18 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::{Parser, Subcommand};
 use prometheus::Registry;
 use std::io::{self, Write};
+use std::path::PathBuf;
 use std::str::FromStr;
-use sui_data_ingestion_core::{DataIngestionMetrics, IndexerExecutor, ReaderOptions, WorkerPool};
-use sui_kvstore::{BigTableClient, BigTableProgressStore, KeyValueStoreReader, KvWorker};
+use sui_data_ingestion_core::{
+    DataIngestionMetrics, IndexerExecutor, ProgressStore, ReaderOptions, WorkerPool,
+};
+use sui_kvstore::{
+    BigTableClient, BigTableProgressStore, KeyValueStoreReader, KeyValueStoreWriter, KvWorker,
+    RocksDbKvStore,
+};
 use sui_types::base_types::ObjectID;
 use sui_types::digests::TransactionDigest;
 use sui_types::storage::ObjectKey;
@@ -15,7 +21,11 @@ use tokio::sync::oneshot;
 
 #[derive(Parser)]
 struct App {
-    instance_id: String,
+    /// Bigtable instance ID to read from and write to.
+    instance_id: Option<String>,
+    /// Path to a local RocksDB store to read from and write to, instead of Bigtable.
+    #[arg(long, conflicts_with = "instance_id")]
+    rocksdb_path: Option<PathBuf>,
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -44,10 +54,12 @@ pub enum Entry {
 async fn main() -> Result<()> {
     let _guard = TelemetryConfig::new().with_env().init();
     let app = App::parse();
-    match app.command {
-        Some(Command::Ingestion { network }) => {
+    match (app.command, app.instance_id, app.rocksdb_path) {
+        (None, _, _) => println!("no command provided"),
+        (Some(_), None, None) => bail!("either an instance ID or --rocksdb-path is required"),
+        (Some(Command::Ingestion { network }), Some(instance_id), _) => {
             let client = BigTableClient::new_remote(
-                app.instance_id,
+                instance_id,
                 false,
                 None,
                 "ingestion".to_string(),
@@ -55,64 +67,79 @@ async fn main() -> Result<()> {
                 None,
             )
             .await?;
-            let (_exit_sender, exit_receiver) = oneshot::channel();
-            let mut executor = IndexerExecutor::new(
-                BigTableProgressStore::new(client.clone()),
-                1,
-                DataIngestionMetrics::new(&Registry::new()),
-            );
-            let worker_pool = WorkerPool::new(KvWorker { client }, "bigtable".to_string(), 50);
-            executor.register(worker_pool).await?;
-            executor
-                .run(
-                    tempfile::tempdir()?.keep(),
-                    Some(format!("https://checkpoints.{}.sui.io", network)),
-                    vec![],
-                    ReaderOptions::default(),
-                    exit_receiver,
-                )
+            let progress_store = BigTableProgressStore::new(client.clone());
+            ingestion(client, progress_store, "bigtable", network).await?;
+        }
+        (Some(Command::Ingestion { network }), None, Some(path)) => {
+            let client = RocksDbKvStore::open(path);
+            ingestion(client.clone(), client, "rocksdb", network).await?;
+        }
+        (Some(Command::Fetch { entry }), Some(instance_id), _) => {
+            let client =
+                BigTableClient::new_remote(instance_id, true, None, "cli".to_string(), None, None)
+                    .await?;
+            fetch(client, entry).await?;
+        }
+        (Some(Command::Fetch { entry }), None, Some(path)) => {
+            fetch(RocksDbKvStore::open_secondary(path), entry).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn ingestion<C, P>(client: C, progress_store: P, name: &str, network: String) -> Result<()>
+where
+    C: KeyValueStoreReader + KeyValueStoreWriter + Clone + Send + Sync + 'static,
+    P: ProgressStore,
+{
+    let (_exit_sender, exit_receiver) = oneshot::channel();
+    let mut executor = IndexerExecutor::new(
+        progress_store,
+        1,
+        DataIngestionMetrics::new(&Registry::new()),
+    );
+    let worker_pool = WorkerPool::new(KvWorker { client }, name.to_string(), 50);
+    executor.register(worker_pool).await?;
+    executor
+        .run(
+            tempfile::tempdir()?.keep(),
+            Some(format!("https://checkpoints.{}.sui.io", network)),
+            vec![],
+            ReaderOptions::default(),
+            exit_receiver,
+        )
+        .await?;
+    Ok(())
+}
+
+async fn fetch<C: KeyValueStoreReader>(mut client: C, entry: Entry) -> Result<()> {
+    let result = match entry {
+        Entry::Epoch { id } => client.get_epoch(id).await?.map(|e| bcs::to_bytes(&e)),
+        Entry::Object { id, version } => {
+            let objects = client
+                .get_objects(&[ObjectKey(ObjectID::from_str(&id)?, version.into())])
                 .await?;
+            objects.first().map(bcs::to_bytes)
         }
-        Some(Command::Fetch { entry }) => {
-            let mut client = BigTableClient::new_remote(
-                app.instance_id,
-                true,
-                None,
-                "cli".to_string(),
-                None,
-                None,
-            )
-            .await?;
-            let result = match entry {
-                Entry::Epoch { id } => client.get_epoch(id).await?.map(|e| bcs::to_bytes(&e)),
-                Entry::Object { id, version } => {
-                    let objects = client
-                        .get_objects(&[ObjectKey(ObjectID::from_str(&id)?, version.into())])
-                        .await?;
-                    objects.first().map(bcs::to_bytes)
-                }
-                Entry::Checkpoint { id } => {
-                    let checkpoints = client.get_checkpoints(&[id]).await?;
-                    checkpoints.first().map(bcs::to_bytes)
-                }
-                Entry::Transaction { id } => {
-                    let transactions = client
-                        .get_transactions(&[TransactionDigest::from_str(&id)?])
-                        .await?;
-                    transactions.first().map(bcs::to_bytes)
-                }
-                Entry::Watermark => {
-                    let watermark = client.get_latest_checkpoint().await?;
-                    println!("watermark is {}", watermark);
-                    return Ok(());
-                }
-            };
-            match result {
-                Some(bytes) => io::stdout().write_all(&bytes?)?,
-                None => println!("not found"),
-            }
+        Entry::Checkpoint { id } => {
+            let checkpoints = client.get_checkpoints(&[id]).await?;
+            checkpoints.first().map(bcs::to_bytes)
+        }
+        Entry::Transaction { id } => {
+            let transactions = client
+                .get_transactions(&[TransactionDigest::from_str(&id)?])
+                .await?;
+            transactions.first().map(bcs::to_bytes)
+        }
+        Entry::Watermark => {
+            let watermark = client.get_latest_checkpoint().await?;
+            println!("watermark is {}", watermark);
+            return Ok(());
         }
-        None => println!("no command provided"),
+    };
+    match result {
+        Some(bytes) => io::stdout().write_all(&bytes?)?,
+        None => println!("not found"),
     }
     Ok(())
 }
@@ -0,0 +1,407 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::PathBuf;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use crate::{Checkpoint, KeyValueStoreReader, KeyValueStoreWriter, TransactionData};
+use anyhow::Result;
+use async_trait::async_trait;
+use sui_data_ingestion_core::ProgressStore;
+use sui_types::base_types::ObjectID;
+use sui_types::committee::EpochId;
+use sui_types::digests::{CheckpointDigest, TransactionDigest};
+use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, CheckpointSummary};
+use sui_types::messages_consensus::TimestampMs;
+use sui_types::object::Object;
+use sui_types::storage::{EpochInfo, ObjectKey};
+use tokio::time::MissedTickBehavior;
+use tracing::warn;
+use typed_store::rocks::{DBMap, MetricConf};
+use typed_store::{DBMapUtils, Map};
+
+/// A key-value store holding the same data as the Bigtable KV store, in a local RocksDB database.
+/// This allows services that read from the KV store to be self-hosted.
+///
+/// A store can be opened by one writer (typically the ingestion worker), and any number of readers
+/// that follow the writer as secondary instances.
+#[derive(Clone)]
+pub struct RocksDbKvStore {
+    tables: Arc<RocksDbKvTables>,
+}
+
+#[derive(DBMapUtils)]
+struct RocksDbKvTables {
+    objects: DBMap<ObjectKey, Object>,
+    transactions: DBMap<TransactionDigest, TransactionData>,
+    checkpoints: DBMap<CheckpointSequenceNumber, Checkpoint>,
+    checkpoint_digests: DBMap<CheckpointDigest, CheckpointSequenceNumber>,
+    epochs: DBMap<EpochId, EpochInfo>,
+    /// The sequence number of the next checkpoint to be ingested.
+    watermark: DBMap<(), CheckpointSequenceNumber>,
+}
+
+/// How often secondary instances catch up with writes made by the primary.
+const CATCH_UP_INTERVAL: Duration = Duration::from_millis(100);
+
+impl RocksDbKvStore {
+    /// Open the store at `path` for reading and writing. Only one process can do this at a time.
+    pub fn open(path: PathBuf) -> Self {
+        let tables =
+            RocksDbKvTables::open_tables_read_write(path, MetricConf::new("kvstore"), None, None);
+
+        Self {
+            tables: Arc::new(tables),
+        }
+    }
+
+    /// Open the store at `path` as a secondary instance, for reading only. Writes made by the
+    /// primary instance become visible to reads once the secondary catches up with them, which it
+    /// does in the background every `CATCH_UP_INTERVAL`, until all clones of the store have been
+    /// dropped.
+    ///
+    /// This function must be called from within a tokio runtime.
+    pub fn open_secondary(path: PathBuf) -> Self {
+        let RocksDbKvTablesReadOnly {
+            objects,
+            transactions,
+            checkpoints,
+            checkpoint_digests,
+            epochs,
+            watermark,
+        } = RocksDbKvTables::get_read_only_handle(path, None, None, MetricConf::new("kvstore"));
+
+        let tables = Arc::new(RocksDbKvTables {
+            objects,
+            transactions,
+            checkpoints,
+            checkpoint_digests,
+            epochs,
+            watermark,
+        });
+
+        tokio::spawn(catch_up(Arc::downgrade(&tables)));
+        Self { tables }
+    }
+
+    /// Run `f` against the store's tables on a thread where blocking is acceptable, as RocksDB
+    /// reads can block.
+    async fn read<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&RocksDbKvTables) -> Result<T> + Send + 'static,
+    {
+        let tables = self.tables.clone();
+        tokio::task::spawn_blocking(move || f(&tables)).await?
+    }
+}
+
+/// Periodically catch the secondary instance up with its primary, until the tables are dropped.
+/// All tables share a database, so catching up on one catches up on all of them.
+async fn catch_up(tables: Weak<RocksDbKvTables>) {
+    let mut interval = tokio::time::interval(CATCH_UP_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        let Some(tables) = tables.upgrade() else {
+            break;
+        };
+
+        match tokio::task::spawn_blocking(move || tables.watermark.try_catch_up_with_primary())
+            .await
+        {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!("Failed to catch up with primary: {e}"),
+            Err(e) => warn!("Catch up task failed: {e}"),
+        }
+    }
+}
+
+#[async_trait]
+impl KeyValueStoreWriter for RocksDbKvStore {
+    async fn save_objects(&mut self, objects: &[&Object], _: TimestampMs) -> Result<()> {
+        let mut batch = self.tables.objects.batch();
+        batch.insert_batch(
+            &self.tables.objects,
+            objects.iter().map(|o| (ObjectKey(o.id(), o.version()), *o)),
+        )?;
+        batch.write()?;
+        Ok(())
+    }
+
+    async fn save_transactions(&mut self, transactions: &[TransactionData]) -> Result<()> {
+        let mut batch = self.tables.transactions.batch();
+        batch.insert_batch(
+            &self.tables.transactions,
+            transactions.iter().map(|tx| (*tx.transaction.digest(), tx)),
+        )?;
+        batch.write()?;
+        Ok(())
+    }
+
+    async fn save_checkpoint(&mut self, checkpoint: &CheckpointData) -> Result<()> {
+        let summary = &checkpoint.checkpoint_summary;
+        let sequence_number = summary.sequence_number;
+        let checkpoint = Checkpoint {
+            summary: summary.data().clone(),
+            contents: checkpoint.checkpoint_contents.clone(),
+            signatures: summary.auth_sig().clone(),
+        };
+
+        let mut batch = self.tables.checkpoints.batch();
+        batch.insert_batch(&self.tables.checkpoints, [(sequence_number, checkpoint)])?;
+        batch.insert_batch(
+            &self.tables.checkpoint_digests,
+            [(*summary.digest(), sequence_number)],
+        )?;
+        batch.write()?;
+        Ok(())
+    }
+
+    async fn save_watermark(&mut self, watermark: CheckpointSequenceNumber) -> Result<()> {
+        self.tables.watermark.insert(&(), &watermark)?;
+        Ok(())
+    }
+
+    async fn save_epoch(&mut self, epoch: EpochInfo) -> Result<()> {
+        self.tables.epochs.insert(&epoch.epoch, &epoch)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KeyValueStoreReader for RocksDbKvStore {
+    async fn get_objects(&mut self, objects: &[ObjectKey]) -> Result<Vec<Object>> {
+        let objects = objects.to_vec();
+        self.read(move |tables| {
+            Ok(tables
+                .objects
+                .multi_get(&objects)?
+                .into_iter()
+                .flatten()
+                .collect())
+        })
+        .await
+    }
+
+    async fn get_transactions(
+        &mut self,
+        transactions: &[TransactionDigest],
+    ) -> Result<Vec<TransactionData>> {
+        let transactions = transactions.to_vec();
+        self.read(move |tables| {
+            Ok(tables
+                .transactions
+                .multi_get(&transactions)?
+                .into_iter()
+                .flatten()
+                .collect())
+        })
+        .await
+    }
+
+    async fn get_checkpoints(
+        &mut self,
+        sequence_numbers: &[CheckpointSequenceNumber],
+    ) -> Result<Vec<Checkpoint>> {
+        let sequence_numbers = sequence_numbers.to_vec();
+        self.read(move |tables| {
+            Ok(tables
+                .checkpoints
+                .multi_get(&sequence_numbers)?
+                .into_iter()
+                .flatten()
+                .collect())
+        })
+        .await
+    }
+
+    async fn get_checkpoint_by_digest(
+        &mut self,
+        digest: CheckpointDigest,
+    ) -> Result<Option<Checkpoint>> {
+        self.read(move |tables| {
+            let Some(sequence_number) = tables.checkpoint_digests.get(&digest)? else {
+                return Ok(None);
+            };
+
+            Ok(tables.checkpoints.get(&sequence_number)?)
+        })
+        .await
+    }
+
+    async fn get_latest_checkpoint(&mut self) -> Result<CheckpointSequenceNumber> {
+        self.read(|tables| Ok(tables.watermark.get(&())?.unwrap_or(0)))
+            .await
+    }
+
+    async fn get_latest_checkpoint_summary(&mut self) -> Result<Option<CheckpointSummary>> {
+        self.read(|tables| {
+            let sequence_number = tables.watermark.get(&())?.unwrap_or(0);
+            if sequence_number == 0 {
+                return Ok(None);
+            }
+
+            Ok(tables
+                .checkpoints
+                .get(&(sequence_number - 1))?
+                .map(|checkpoint| checkpoint.summary))
+        })
+        .await
+    }
+
+    async fn get_latest_object(&mut self, object_id: &ObjectID) -> Result<Option<Object>> {
+        let object_id = *object_id;
+        self.read(move |tables| {
+            Ok(tables
+                .objects
+                .reversed_safe_iter_with_bounds(
+                    Some(ObjectKey::min_for_id(&object_id)),
+                    Some(ObjectKey::max_for_id(&object_id)),
+                )?
+                .next()
+                .transpose()?
+                .map(|(_, object)| object))
+        })
+        .await
+    }
+
+    async fn get_epoch(&mut self, epoch_id: EpochId) -> Result<Option<EpochInfo>> {
+        self.read(move |tables| Ok(tables.epochs.get(&epoch_id)?))
+            .await
+    }
+
+    async fn get_latest_epoch(&mut self) -> Result<Option<EpochInfo>> {
+        self.read(|tables| {
+            Ok(tables
+                .epochs
+                .reversed_safe_iter_with_bounds(None, None)?
+                .next()
+                .transpose()?
+                .map(|(_, epoch)| epoch))
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl ProgressStore for RocksDbKvStore {
+    async fn load(&mut self, _: String) -> Result<CheckpointSequenceNumber> {
+        self.get_latest_checkpoint().await
+    }
+
+    async fn save(&mut self, _: String, checkpoint_number: CheckpointSequenceNumber) -> Result<()> {
+        self.save_watermark(checkpoint_number).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use sui_data_ingestion_core::Worker;
+    use sui_types::message_envelope::Message;
+    use sui_types::test_checkpoint_data_builder::TestCheckpointDataBuilder;
+
+    use crate::KvWorker;
+
+    use super::*;
+
+    /// Data written through the primary instance can be read back from a secondary instance, once
+    /// it has caught up.
+    #[tokio::test]
+    async fn test_write_then_secondary_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let primary = RocksDbKvStore::open(dir.path().to_owned());
+        let mut secondary = RocksDbKvStore::open_secondary(dir.path().to_owned());
+
+        let worker = KvWorker {
+            client: primary.clone(),
+        };
+
+        let mut builder = TestCheckpointDataBuilder::new(1)
+            .start_transaction(0)
+            .create_owned_object(0)
+            .finish_transaction();
+
+        let cp1 = builder.build_checkpoint();
+        worker.process_checkpoint(&cp1).await.unwrap();
+        primary.clone().save_watermark(2).await.unwrap();
+        wait_for_watermark(&mut secondary, 2).await;
+
+        let summary = &cp1.checkpoint_summary;
+        let latest = secondary.get_latest_checkpoint_summary().await.unwrap();
+        assert_eq!(latest.map(|s| s.digest()), Some(*summary.digest()));
+
+        let checkpoints = secondary.get_checkpoints(&[1, 2]).await.unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        assert_eq!(checkpoints[0].summary.digest(), *summary.digest());
+
+        let by_digest = secondary
+            .get_checkpoint_by_digest(*summary.digest())
+            .await
+            .unwrap();
+        assert_eq!(by_digest.map(|c| c.summary.sequence_number), Some(1));
+
+        let tx = &cp1.transactions[0];
+        let transactions = secondary
+            .get_transactions(&[*tx.transaction.digest()])
+            .await
+            .unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].effects, tx.effects);
+        assert_eq!(transactions[0].checkpoint_number, 1);
+
+        let id = TestCheckpointDataBuilder::derive_object_id(0);
+        let created = tx.output_objects.iter().find(|o| o.id() == id).unwrap();
+        let objects = secondary
+            .get_objects(&[ObjectKey(id, created.version())])
+            .await
+            .unwrap();
+        assert_eq!(objects, vec![created.clone()]);
+
+        // Modify the object in a later checkpoint. The secondary picks up the new version of the
+        // object without being re-opened.
+        let mut builder = builder
+            .start_transaction(0)
+            .mutate_owned_object(0)
+            .finish_transaction();
+
+        let cp2 = builder.build_checkpoint();
+        worker.process_checkpoint(&cp2).await.unwrap();
+        primary.clone().save_watermark(3).await.unwrap();
+        wait_for_watermark(&mut secondary, 3).await;
+
+        let mutated = cp2.transactions[0]
+            .output_objects
+            .iter()
+            .find(|o| o.id() == id)
+            .unwrap();
+
+        let latest = secondary.get_latest_object(&id).await.unwrap();
+        assert_eq!(latest.as_ref(), Some(mutated));
+        assert!(mutated.version() > created.version());
+
+        // The earlier version is still available.
+        let objects = secondary
+            .get_objects(&[ObjectKey(id, created.version())])
+            .await
+            .unwrap();
+        assert_eq!(objects, vec![created.clone()]);
+    }
+
+    /// Wait for `store` to report that `watermark` is the next checkpoint to be ingested.
+    async fn wait_for_watermark(store: &mut RocksDbKvStore, watermark: CheckpointSequenceNumber) {
+        let start = Instant::now();
+        while store.get_latest_checkpoint().await.unwrap() < watermark {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "Timed out waiting for secondary to catch up"
+            );
+            tokio::time::sleep(CATCH_UP_INTERVAL).await;
+        }
+    }
+}
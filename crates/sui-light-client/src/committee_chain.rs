@@ -0,0 +1,396 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sync of the committee chain from a checkpoint archive or a local ingestion directory.
+//!
+//! End-of-epoch checkpoints are found by searching the checkpoints of each epoch for the last
+//! one, which carries `end_of_epoch_data`, so no GraphQL service is needed. Each end-of-epoch
+//! summary is verified with the committee of its epoch and appended to a binary cache file,
+//! so an interrupted sync resumes from the last epoch it verified.
+
+use crate::checkpoint::{read_checkpoint, read_checkpoint_list};
+use crate::committee::extract_new_committee_info;
+use crate::config::Config;
+use crate::object_store::SuiObjectStore;
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use sui_config::genesis::Genesis;
+use sui_types::committee::Committee;
+use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber};
+use tracing::info;
+use url::Url;
+
+/// Name of the committee chain cache file, in the checkpoint summary directory.
+pub const COMMITTEE_CHAIN_FILE: &str = "committee_chain.bin";
+
+/// The synced end-of-epoch checkpoint summaries, in epoch order, starting from epoch 0.
+///
+/// The cache file is a sequence of records, each a little-endian `u32` length followed by a
+/// BCS-serialized `CertifiedCheckpointSummary`. A partially written trailing record (from an
+/// interrupted sync) is ignored on load.
+#[derive(Debug, Default)]
+pub struct CommitteeChain {
+    pub summaries: Vec<CertifiedCheckpointSummary>,
+    // Length of the valid records in the cache file.
+    len: u64,
+}
+
+impl CommitteeChain {
+    pub fn path(config: &Config) -> PathBuf {
+        config.checkpoint_summary_dir.join(COMMITTEE_CHAIN_FILE)
+    }
+
+    /// Load the cache file, or an empty chain if it does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let mut buffer = Vec::new();
+        fs::File::open(path)?.read_to_end(&mut buffer)?;
+
+        let mut chain = Self::default();
+        let mut rest = buffer.as_slice();
+        while rest.len() >= 4 {
+            let len = u32::from_le_bytes(rest[..4].try_into().unwrap()) as usize;
+            let Some(record) = rest.get(4..4 + len) else {
+                break;
+            };
+            let summary: CertifiedCheckpointSummary = bcs::from_bytes(record)
+                .map_err(|e| anyhow!("Unable to parse committee chain file: {e}"))?;
+            chain.summaries.push(summary);
+            chain.len += 4 + len as u64;
+            rest = &rest[4 + len..];
+        }
+
+        Ok(chain)
+    }
+
+    /// Append `summary` to the chain and to the cache file.
+    pub fn append(&mut self, path: &Path, summary: CertifiedCheckpointSummary) -> Result<()> {
+        let bytes = bcs::to_bytes(&summary)
+            .map_err(|_| anyhow!("Unable to serialize checkpoint summary"))?;
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        // Drop any partially written record before appending.
+        file.set_len(self.len)?;
+        file.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        file.write_all(&bytes)?;
+        file.sync_data()?;
+
+        self.len += 4 + bytes.len() as u64;
+        self.summaries.push(summary);
+        Ok(())
+    }
+
+    /// The end-of-epoch summary of `epoch`, if synced.
+    pub fn end_of_epoch(&self, epoch: u64) -> Option<&CertifiedCheckpointSummary> {
+        self.summaries.get(epoch as usize)
+    }
+}
+
+/// Sync the committee chain by searching the checkpoints at `url` (a checkpoint archive, or a
+/// `file://` URL of a local ingestion directory) for end-of-epoch checkpoints.
+pub async fn sync_committee_chain(config: &Config, url: &Url) -> Result<CommitteeChain> {
+    let path = CommitteeChain::path(config);
+    let mut chain = CommitteeChain::load(&path)?;
+    let store = SuiObjectStore::from_url(url)?;
+
+    let mut committee = match chain.summaries.last() {
+        Some(last) => extract_new_committee_info(last)?,
+        None => genesis_committee(config)?,
+    };
+    let mut first = chain
+        .summaries
+        .last()
+        .map_or(0, |last| last.sequence_number + 1);
+    let mut epoch_len = None;
+
+    info!(
+        "Syncing committee chain from epoch {} (checkpoint {})",
+        committee.epoch, first
+    );
+
+    while let Some(summary) = find_end_of_epoch(&store, committee.epoch, first, epoch_len).await? {
+        summary.clone().try_into_verified(&committee)?;
+        info!(
+            "Epoch: {} Checkpoint: {} Digest: {}",
+            summary.epoch(),
+            summary.sequence_number,
+            summary.digest()
+        );
+
+        epoch_len = Some(summary.sequence_number + 1 - first);
+        first = summary.sequence_number + 1;
+        committee = extract_new_committee_info(&summary)?;
+        chain.append(&path, summary)?;
+    }
+
+    info!("Committee chain synced to epoch {}", committee.epoch);
+    Ok(chain)
+}
+
+/// Find the end-of-epoch checkpoint of `epoch`, whose first checkpoint is `first`. Returns
+/// `None` if the epoch has not ended yet in the store.
+///
+/// The last checkpoint of the epoch is found with an exponential search from `first` (or from
+/// the expected end of the epoch, given the length of the previous one) followed by a binary
+/// search, downloading a logarithmic number of checkpoints per epoch.
+async fn find_end_of_epoch(
+    store: &SuiObjectStore,
+    epoch: u64,
+    first: CheckpointSequenceNumber,
+    epoch_len_hint: Option<u64>,
+) -> Result<Option<CertifiedCheckpointSummary>> {
+    // `in_epoch(seq)` holds for the checkpoints of the epoch, and for no checkpoint after them.
+    let in_epoch = |seq| async move {
+        Ok::<_, anyhow::Error>(
+            store
+                .get_checkpoint_summary_if_exists(seq)
+                .await?
+                .filter(|summary| summary.epoch() == epoch),
+        )
+    };
+
+    let Some(mut last) = in_epoch(first).await? else {
+        return Ok(None);
+    };
+    let mut lo = first;
+
+    // Probe the expected end of the epoch first.
+    if let Some(len) = epoch_len_hint.filter(|len| *len > 1) {
+        let probe = first + len - 1;
+        match in_epoch(probe).await? {
+            Some(summary) => {
+                lo = probe;
+                last = summary;
+            }
+            None => return binary_search(in_epoch, lo, probe, last).await,
+        }
+    }
+
+    // Exponential search for a checkpoint after the epoch.
+    let mut step = 1;
+    let hi = loop {
+        let probe = lo + step;
+        match in_epoch(probe).await? {
+            Some(summary) => {
+                lo = probe;
+                last = summary;
+                step *= 2;
+            }
+            None => break probe,
+        }
+    };
+
+    binary_search(in_epoch, lo, hi, last).await
+}
+
+// Binary search for the last checkpoint of the epoch in `[lo, hi)`, where `last` is the
+// summary at `lo`.
+async fn binary_search<F, Fut>(
+    in_epoch: F,
+    mut lo: CheckpointSequenceNumber,
+    mut hi: CheckpointSequenceNumber,
+    mut last: CertifiedCheckpointSummary,
+) -> Result<Option<CertifiedCheckpointSummary>>
+where
+    F: Fn(CheckpointSequenceNumber) -> Fut,
+    Fut: std::future::Future<Output = Result<Option<CertifiedCheckpointSummary>>>,
+{
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        match in_epoch(mid).await? {
+            Some(summary) => {
+                lo = mid;
+                last = summary;
+            }
+            None => hi = mid,
+        }
+    }
+
+    // The last checkpoint found is the end of the epoch only if it says so, otherwise the epoch
+    // is still in progress (or the store has not caught up with it yet).
+    Ok(last.end_of_epoch_data.is_some().then_some(last))
+}
+
+/// Committee of `epoch`, from the genesis blob for epoch 0, or from the synced end-of-epoch
+/// checkpoint of the previous epoch, found in the committee chain cache or the per-checkpoint
+/// files listed in `checkpoints.yaml`.
+pub fn read_committee(config: &Config, epoch: u64) -> Result<Committee> {
+    if epoch == 0 {
+        return genesis_committee(config);
+    }
+
+    let summaries = read_end_of_epoch_summaries(config, epoch - 1, epoch)?;
+    let summary = summaries.first().ok_or_else(|| {
+        anyhow!(
+            "No end of epoch checkpoint for epoch {}. Need to Sync.",
+            epoch - 1
+        )
+    })?;
+    Ok(extract_new_committee_info(summary)?)
+}
+
+/// The synced end-of-epoch checkpoint summaries for epochs `from_epoch..to_epoch`, in epoch
+/// order. Summaries are read from both the committee chain cache and the per-checkpoint files
+/// listed in `checkpoints.yaml` (written by syncs before the cache existed), preferring the cache
+/// for epochs found in both.
+pub fn read_end_of_epoch_summaries(
+    config: &Config,
+    from_epoch: u64,
+    to_epoch: u64,
+) -> Result<Vec<CertifiedCheckpointSummary>> {
+    let chain_path = CommitteeChain::path(config);
+    let list_path = config.checkpoint_list_path();
+
+    let mut summaries = BTreeMap::new();
+    if list_path.exists() || !chain_path.exists() {
+        for ckp_id in read_checkpoint_list(config)?.checkpoints {
+            let summary = read_checkpoint(config, ckp_id)?;
+            summaries.insert(summary.epoch(), summary);
+        }
+    }
+
+    for summary in CommitteeChain::load(&chain_path)?.summaries {
+        summaries.insert(summary.epoch(), summary);
+    }
+
+    Ok(summaries
+        .into_values()
+        .filter(|summary| (from_epoch..to_epoch).contains(&summary.epoch()))
+        .collect())
+}
+
+fn genesis_committee(config: &Config) -> Result<Committee> {
+    Genesis::load(config.genesis_path())?
+        .committee()
+        .map_err(|e| anyhow!(format!("Cannot load Genesis: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkpoint::{write_checkpoint, write_checkpoint_list, CheckpointsList};
+    use roaring::RoaringBitmap;
+    use sui_types::{
+        crypto::AuthorityQuorumSignInfo,
+        gas::GasCostSummary,
+        message_envelope::Envelope,
+        messages_checkpoint::{CheckpointContents, CheckpointSummary},
+        supported_protocol_versions::ProtocolConfig,
+    };
+    use tempfile::TempDir;
+
+    fn test_summary(epoch: u64, seq: u64) -> CertifiedCheckpointSummary {
+        let contents = CheckpointContents::new_with_digests_only_for_tests(vec![]);
+        let summary = CheckpointSummary::new(
+            &ProtocolConfig::get_for_max_version_UNSAFE(),
+            epoch,
+            seq,
+            0,
+            &contents,
+            None,
+            GasCostSummary::default(),
+            None,
+            0,
+            Vec::new(),
+        );
+        let info = AuthorityQuorumSignInfo::<true> {
+            epoch,
+            signature: Default::default(),
+            signers_map: RoaringBitmap::new(),
+        };
+        Envelope::new_from_data_and_sig(summary, info)
+    }
+
+    #[test]
+    fn test_committee_chain_append_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(COMMITTEE_CHAIN_FILE);
+
+        let mut chain = CommitteeChain::load(&path).unwrap();
+        assert!(chain.summaries.is_empty());
+
+        chain.append(&path, test_summary(0, 10)).unwrap();
+        chain.append(&path, test_summary(1, 20)).unwrap();
+
+        let chain = CommitteeChain::load(&path).unwrap();
+        assert_eq!(chain.summaries.len(), 2);
+        assert_eq!(chain.end_of_epoch(1).unwrap().sequence_number, 20);
+        assert!(chain.end_of_epoch(2).is_none());
+    }
+
+    #[test]
+    fn test_committee_chain_resumes_after_partial_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(COMMITTEE_CHAIN_FILE);
+
+        let mut chain = CommitteeChain::load(&path).unwrap();
+        chain.append(&path, test_summary(0, 10)).unwrap();
+
+        // Simulate a sync interrupted while writing the next record.
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&100u32.to_le_bytes()).unwrap();
+        file.write_all(&[0u8; 10]).unwrap();
+
+        let mut chain = CommitteeChain::load(&path).unwrap();
+        assert_eq!(chain.summaries.len(), 1);
+
+        chain.append(&path, test_summary(1, 20)).unwrap();
+        let chain = CommitteeChain::load(&path).unwrap();
+        assert_eq!(chain.summaries.len(), 2);
+        assert_eq!(chain.end_of_epoch(1).unwrap().sequence_number, 20);
+    }
+
+    #[test]
+    fn test_read_end_of_epoch_summaries_merges_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            checkpoint_summary_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        // Epochs 0 and 1 were synced into per-checkpoint files, before the cache existed.
+        write_checkpoint(&config, &test_summary(0, 10)).unwrap();
+        write_checkpoint(&config, &test_summary(1, 20)).unwrap();
+        write_checkpoint_list(
+            &config,
+            &CheckpointsList {
+                checkpoints: vec![10, 20],
+            },
+        )
+        .unwrap();
+
+        // The cache holds epochs 0 to 2.
+        let path = CommitteeChain::path(&config);
+        let mut chain = CommitteeChain::load(&path).unwrap();
+        chain.append(&path, test_summary(0, 10)).unwrap();
+        chain.append(&path, test_summary(1, 20)).unwrap();
+        chain.append(&path, test_summary(2, 30)).unwrap();
+
+        // Epoch 3 is only in the per-checkpoint files.
+        write_checkpoint(&config, &test_summary(3, 40)).unwrap();
+        write_checkpoint_list(
+            &config,
+            &CheckpointsList {
+                checkpoints: vec![10, 20, 40],
+            },
+        )
+        .unwrap();
+
+        let summaries = read_end_of_epoch_summaries(&config, 0, 10).unwrap();
+        let epochs: Vec<_> = summaries.iter().map(|s| s.epoch()).collect();
+        assert_eq!(epochs, vec![0, 1, 2, 3]);
+
+        let summaries = read_end_of_epoch_summaries(&config, 1, 3).unwrap();
+        let seqs: Vec<_> = summaries.iter().map(|s| s.sequence_number).collect();
+        assert_eq!(seqs, vec![20, 30]);
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::base::{ProofBuilder, ProofTarget};
+use crate::bundle::ProofBundle;
+use crate::committee_chain::read_end_of_epoch_summaries;
+use crate::config::Config;
+use crate::object_store::SuiObjectStore;
+use anyhow::{anyhow, Result};
+use std::io::{Read, Write};
+use std::{fs, path::Path};
+use sui_json_rpc_types::{SuiObjectDataOptions, SuiTransactionBlockResponseOptions};
+use sui_sdk::SuiClientBuilder;
+use sui_types::base_types::{ObjectID, TransactionDigest};
+use sui_types::event::EventID;
+use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber};
+use sui_types::object::Object;
+use tracing::info;
+
+/// Build a proof bundle for the current version of an object. The committee chain starts at
+/// `from_epoch`, the epoch of the committee the bundle is verified from (0 for genesis).
+pub async fn export_object_proof(
+    config: &Config,
+    id: ObjectID,
+    from_epoch: u64,
+) -> Result<ProofBundle> {
+    let sui_client = SuiClientBuilder::default()
+        .build(config.full_node_url.as_str())
+        .await?;
+
+    info!("Getting object: {}", id);
+    let object: Object = sui_client
+        .read_api()
+        .get_object_with_options(id, SuiObjectDataOptions::bcs_lossless())
+        .await?
+        .into_object()
+        .map_err(|e| anyhow!(format!("Cannot get object {id}: {e}")))?
+        .try_into()?;
+
+    let seq = transaction_checkpoint(config, object.previous_transaction).await?;
+    let checkpoint = SuiObjectStore::new(config)?
+        .get_full_checkpoint(seq)
+        .await
+        .map_err(|e| anyhow!(format!("Cannot get full checkpoint: {e}")))?;
+
+    let target = ProofTarget::new_objects(vec![(object.compute_object_reference(), object)]);
+    let proof = target.construct(&checkpoint)?;
+    let committee_chain =
+        load_committee_chain(config, from_epoch, proof.checkpoint_summary.epoch())?;
+
+    Ok(ProofBundle {
+        committee_chain,
+        proof,
+    })
+}
+
+/// Build a proof bundle for the events emitted by a transaction. The committee chain starts at
+/// `from_epoch`, the epoch of the committee the bundle is verified from (0 for genesis).
+pub async fn export_transaction_proof(
+    config: &Config,
+    tid: TransactionDigest,
+    from_epoch: u64,
+) -> Result<ProofBundle> {
+    let seq = transaction_checkpoint(config, tid).await?;
+    let checkpoint = SuiObjectStore::new(config)?
+        .get_full_checkpoint(seq)
+        .await
+        .map_err(|e| anyhow!(format!("Cannot get full checkpoint: {e}")))?;
+
+    let events = checkpoint
+        .transactions
+        .iter()
+        .find(|t| t.transaction.digest() == &tid)
+        .ok_or(anyhow!("Transaction not found in checkpoint"))?
+        .events
+        .as_ref()
+        .map(|events| {
+            events
+                .data
+                .iter()
+                .enumerate()
+                .map(|(seq, event)| (EventID::from((tid, seq as u64)), event.clone()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    anyhow::ensure!(
+        !events.is_empty(),
+        "Transaction {tid} emitted no events to prove"
+    );
+
+    let proof = ProofTarget::new_events(events).construct(&checkpoint)?;
+    let committee_chain =
+        load_committee_chain(config, from_epoch, proof.checkpoint_summary.epoch())?;
+
+    Ok(ProofBundle {
+        committee_chain,
+        proof,
+    })
+}
+
+/// Load the synced end-of-epoch checkpoint summaries for epochs `from_epoch..to_epoch`,
+/// which establish the committee of `to_epoch` from the committee of `from_epoch`.
+pub fn load_committee_chain(
+    config: &Config,
+    from_epoch: u64,
+    to_epoch: u64,
+) -> Result<Vec<CertifiedCheckpointSummary>> {
+    let chain = read_end_of_epoch_summaries(config, from_epoch, to_epoch)?;
+    anyhow::ensure!(
+        chain.len() as u64 == to_epoch.saturating_sub(from_epoch),
+        "Missing end of epoch checkpoints between epochs {from_epoch} and {to_epoch}. Need to Sync."
+    );
+    Ok(chain)
+}
+
+pub fn read_proof_bundle(path: &Path) -> Result<ProofBundle> {
+    let mut reader = fs::File::open(path)?;
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer)?;
+    bcs::from_bytes(&buffer).map_err(|e| anyhow!("Unable to parse proof bundle: {e}"))
+}
+
+pub fn write_proof_bundle(path: &Path, bundle: &ProofBundle) -> Result<()> {
+    let mut writer = fs::File::create(path)?;
+    let bytes = bcs::to_bytes(bundle).map_err(|_| anyhow!("Unable to serialize proof bundle"))?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Lookup the checkpoint a transaction was executed in.
+async fn transaction_checkpoint(
+    config: &Config,
+    tid: TransactionDigest,
+) -> Result<CheckpointSequenceNumber> {
+    let sui_client = SuiClientBuilder::default()
+        .build(config.full_node_url.as_str())
+        .await?;
+    sui_client
+        .read_api()
+        .get_transaction_with_options(tid, SuiTransactionBlockResponseOptions::new())
+        .await
+        .map_err(|e| anyhow!(format!("Cannot get transaction: {e}")))?
+        .checkpoint
+        .ok_or(anyhow!("Transaction not found"))
+}
@@ -4,6 +4,7 @@
 pub mod proof;
 
 pub mod checkpoint;
+pub mod committee_chain;
 
 pub mod config;
 
@@ -14,5 +15,7 @@ pub mod graphql;
 
 pub mod verifier;
 
+pub mod export;
+
 #[doc(inline)]
 pub use proof::*;
@@ -4,19 +4,29 @@
 use sui_types::{
     base_types::ObjectID,
     digests::TransactionDigest,
+    messages_checkpoint::CertifiedCheckpointSummary,
     object::{bounded_visitor::BoundedVisitor, Data},
 };
 
 use sui_package_resolver::Resolver;
 
-use clap::{Parser, Subcommand};
+use anyhow::anyhow;
+use clap::{ArgGroup, Parser, Subcommand};
 use std::{fs, path::PathBuf, str::FromStr};
+use sui_config::genesis::Genesis;
+use sui_light_client::base::ProofVerifier;
 use sui_light_client::checkpoint::check_and_sync_checkpoints;
+use sui_light_client::committee::extract_new_committee_info;
+use sui_light_client::committee_chain::{sync_committee_chain, CommitteeChain};
 use sui_light_client::config::Config;
+use sui_light_client::export::{
+    export_object_proof, export_transaction_proof, read_proof_bundle, write_proof_bundle,
+};
 use sui_light_client::package_store::RemotePackageStore;
 use sui_light_client::verifier::{get_verified_effects_and_events, get_verified_object};
 
 use tracing::info;
+use url::Url;
 
 /// A light client for the Sui blockchain
 #[derive(Parser, Debug)]
@@ -33,7 +43,16 @@ struct Args {
 #[derive(Subcommand, Debug)]
 enum SCommands {
     /// Sync all end-of-epoch checkpoints
-    Sync {},
+    Sync {
+        /// Sync the committee chain by scanning the checkpoint archive (`object_store_url`)
+        /// for end-of-epoch checkpoints, without GraphQL, into a binary cache file
+        #[arg(long)]
+        from_archive: bool,
+
+        /// Like `--from-archive`, but scanning a local ingestion directory of checkpoints
+        #[arg(long, value_name = "DIR", conflicts_with = "from_archive")]
+        ingestion_dir: Option<PathBuf>,
+    },
 
     /// Checks a specific transaction using the light client
     Transaction {
@@ -48,6 +67,49 @@ enum SCommands {
         #[arg(short, long, value_name = "OID")]
         oid: String,
     },
+
+    /// Exports a self-contained proof of an object or of the events of a transaction,
+    /// that can be verified offline with `verify-proof`
+    #[command(group(ArgGroup::new("target").required(true).args(["oid", "tid"])))]
+    ExportProof {
+        /// Object ID, to prove the current version of the object
+        #[arg(short, long, value_name = "OID")]
+        oid: Option<String>,
+
+        /// Transaction hash, to prove the events emitted by the transaction
+        #[arg(short, long, value_name = "TID")]
+        tid: Option<String>,
+
+        /// Epoch of the committee the proof is verified from (0 for genesis)
+        #[arg(long, value_name = "EPOCH", default_value_t = 0)]
+        from_epoch: u64,
+
+        /// File to write the proof to
+        #[arg(long, value_name = "FILE")]
+        output: PathBuf,
+    },
+
+    /// Verifies a proof exported with `export-proof`, without network access
+    #[command(group(ArgGroup::new("trust").required(true).args(["genesis", "trusted_checkpoint"])))]
+    VerifyProof {
+        /// The proof file
+        #[arg(long, value_name = "FILE")]
+        proof: PathBuf,
+
+        /// Genesis blob to verify the proof from
+        #[arg(long, value_name = "FILE")]
+        genesis: Option<PathBuf>,
+
+        /// A trusted end-of-epoch checkpoint summary (as stored by `sync`), whose next
+        /// epoch committee the proof is verified from
+        #[arg(long, value_name = "FILE")]
+        trusted_checkpoint: Option<PathBuf>,
+
+        /// Read the trusted checkpoint as the end-of-epoch summary of this epoch from a
+        /// committee chain file (`committee_chain.bin`, as stored by `sync --from-archive`)
+        #[arg(long, value_name = "EPOCH", requires = "trusted_checkpoint")]
+        trusted_epoch: Option<u64>,
+    },
 }
 
 #[tokio::main]
@@ -57,6 +119,19 @@ pub async fn main() {
     // Command line arguments and config loading
     let args = Args::parse();
 
+    // Proofs are verified offline and need no config
+    if let Some(SCommands::VerifyProof {
+        proof,
+        genesis,
+        trusted_checkpoint,
+        trusted_epoch,
+    }) = args.command
+    {
+        verify_proof(proof, genesis, trusted_checkpoint, trusted_epoch)
+            .expect("Failed to verify proof");
+        return;
+    }
+
     let path = args
         .config
         .unwrap_or_else(|| panic!("Need a config file path"));
@@ -141,13 +216,94 @@ pub async fn main() {
             }
         }
 
-        Some(SCommands::Sync {}) => {
-            check_and_sync_checkpoints(&config)
-                .await
-                .expect("Failed to sync checkpoints");
+        Some(SCommands::Sync {
+            from_archive,
+            ingestion_dir,
+        }) => {
+            let url = if let Some(dir) = ingestion_dir {
+                let dir = fs::canonicalize(&dir).expect("Cannot find ingestion directory");
+                Some(Url::from_directory_path(dir).expect("Invalid ingestion directory"))
+            } else if from_archive {
+                Some(Url::parse(&config.object_store_url).expect("Invalid object store URL"))
+            } else {
+                None
+            };
+
+            if let Some(url) = url {
+                sync_committee_chain(&config, &url)
+                    .await
+                    .expect("Failed to sync committee chain");
+            } else {
+                check_and_sync_checkpoints(&config)
+                    .await
+                    .expect("Failed to sync checkpoints");
+            }
+        }
+
+        Some(SCommands::ExportProof {
+            oid,
+            tid,
+            from_epoch,
+            output,
+        }) => {
+            let bundle = if let Some(oid) = oid {
+                let oid = ObjectID::from_str(&oid).unwrap();
+                export_object_proof(&config, oid, from_epoch).await
+            } else {
+                let tid = TransactionDigest::from_str(&tid.unwrap()).unwrap();
+                export_transaction_proof(&config, tid, from_epoch).await
+            }
+            .expect("Failed to export proof");
+
+            write_proof_bundle(&output, &bundle).expect("Failed to write proof");
+            println!(
+                "Exported proof for checkpoint {} (epoch {}) with {} committee checkpoints to {}",
+                bundle.proof.checkpoint_summary.sequence_number(),
+                bundle.proof.checkpoint_summary.epoch(),
+                bundle.committee_chain.len(),
+                output.display()
+            );
         }
         _ => {
             println!("No command...");
         }
     }
 }
+
+fn verify_proof(
+    proof: PathBuf,
+    genesis: Option<PathBuf>,
+    trusted_checkpoint: Option<PathBuf>,
+    trusted_epoch: Option<u64>,
+) -> anyhow::Result<()> {
+    let committee = if let Some(genesis) = genesis {
+        Genesis::load(&genesis)?
+            .committee()
+            .map_err(|e| anyhow!(format!("Cannot load Genesis: {e}")))?
+    } else {
+        let path = trusted_checkpoint.expect("either genesis or trusted checkpoint is required");
+        let summary: CertifiedCheckpointSummary = match trusted_epoch {
+            Some(epoch) => CommitteeChain::load(&path)?
+                .end_of_epoch(epoch)
+                .cloned()
+                .ok_or_else(|| {
+                    anyhow!("No end of epoch checkpoint for epoch {epoch} in {path:?}")
+                })?,
+            None => bcs::from_bytes(&fs::read(&path)?)
+                .map_err(|_| anyhow!("Unable to parse checkpoint file"))?,
+        };
+        extract_new_committee_info(&summary)?
+    };
+
+    let bundle = read_proof_bundle(&proof)?;
+    let summary = bundle.proof.checkpoint_summary.clone();
+    bundle.verify(&committee)?;
+
+    println!(
+        "Verified proof for checkpoint {} (epoch {}) from the committee of epoch {}",
+        summary.sequence_number(),
+        summary.epoch(),
+        committee.epoch
+    );
+    Ok(())
+}
@@ -17,7 +17,13 @@ pub struct SuiObjectStore {
 impl SuiObjectStore {
     pub fn new(config: &Config) -> Result<Self> {
         let url = Url::parse(&config.object_store_url)?;
-        let (store, _) = object_store::parse_url(&url)?;
+        Self::from_url(&url)
+    }
+
+    /// Create a store for the checkpoints at `url`, a remote checkpoint bucket or a `file://`
+    /// URL of a local ingestion directory.
+    pub fn from_url(url: &Url) -> Result<Self> {
+        let (store, _) = object_store::parse_url(url)?;
         Ok(Self { store })
     }
 
@@ -35,6 +41,24 @@ impl SuiObjectStore {
         Ok(blob.checkpoint_summary)
     }
 
+    /// Like `download_checkpoint_summary`, but returns `None` if the checkpoint is not in the
+    /// store.
+    pub async fn get_checkpoint_summary_if_exists(
+        &self,
+        checkpoint_number: u64,
+    ) -> Result<Option<CertifiedCheckpointSummary>> {
+        let path = Path::from(format!("{}.chk", checkpoint_number));
+        let response = match self.store.get(&path).await {
+            Ok(response) => response,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let bytes = response.bytes().await?;
+
+        let (_, blob) = bcs::from_bytes::<(u8, CheckpointData)>(&bytes)?;
+        Ok(Some(blob.checkpoint_summary))
+    }
+
     pub async fn get_full_checkpoint(&self, checkpoint_number: u64) -> Result<CheckpointData> {
         let path = Path::from(format!("{}.chk", checkpoint_number));
         info!("Request full checkpoint: {}", path);
@@ -0,0 +1,63 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use sui_types::{committee::Committee, messages_checkpoint::CertifiedCheckpointSummary};
+
+use crate::proof::{
+    base::{Proof, ProofVerifier},
+    committee::extract_new_committee_info,
+    error::{ProofError, ProofResult},
+};
+
+/// A self-contained proof that can be verified offline. It carries the end-of-epoch
+/// checkpoint summaries needed to go from a trusted committee (e.g. genesis) to the
+/// committee that signed the checkpoint of the proof.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProofBundle {
+    /// End-of-epoch checkpoint summaries, in epoch order. Each summary is signed by the
+    /// committee of its epoch and defines the committee of the next epoch.
+    pub committee_chain: Vec<CertifiedCheckpointSummary>,
+
+    /// The proof to verify with the committee at the end of the chain.
+    pub proof: Proof,
+}
+
+impl ProofBundle {
+    /// Walk the committee chain from `committee` and return the committee of the epoch of the
+    /// proof. Summaries for epochs before the epoch of `committee` are skipped, so a bundle
+    /// exported from genesis can also be verified from a later trusted committee.
+    pub fn verify_committee_chain(&self, committee: &Committee) -> ProofResult<Committee> {
+        let mut committee = committee.clone();
+        for summary in &self.committee_chain {
+            if summary.epoch() < committee.epoch {
+                continue;
+            }
+
+            if summary.epoch() != committee.epoch {
+                return Err(ProofError::CommitteeChainGap {
+                    expected: committee.epoch,
+                    found: summary.epoch(),
+                });
+            }
+
+            summary
+                .verify_authority_signatures(&committee)
+                .map_err(|e| ProofError::SummaryVerificationFailed(e.to_string()))?;
+            committee = extract_new_committee_info(summary)?;
+        }
+
+        if committee.epoch != self.proof.checkpoint_summary.epoch() {
+            return Err(ProofError::EpochMismatch);
+        }
+
+        Ok(committee)
+    }
+}
+
+impl ProofVerifier for ProofBundle {
+    fn verify(self, committee: &Committee) -> ProofResult<()> {
+        let committee = self.verify_committee_chain(committee)?;
+        self.proof.verify(&committee)
+    }
+}
@@ -64,4 +64,7 @@ pub enum ProofError {
 
     #[error("Checkpoint summary verification failed: {0}")]
     SummaryVerificationFailed(String),
+
+    #[error("Committee chain has a gap: expected epoch {expected}, found {found}")]
+    CommitteeChainGap { expected: u64, found: u64 },
 }
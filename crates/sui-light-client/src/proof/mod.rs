@@ -11,5 +11,8 @@ pub mod objects;
 // Proofs
 pub mod transaction_proof;
 
+// Offline proof bundles
+pub mod bundle;
+
 // Error types
 pub mod error;
@@ -1,13 +1,11 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::checkpoint::{read_checkpoint, read_checkpoint_list, CheckpointsList};
-use crate::committee::extract_new_committee_info;
+use crate::committee_chain::read_committee;
 use crate::config::Config;
 use crate::object_store::SuiObjectStore;
 use anyhow::{anyhow, Result};
 use std::sync::Arc;
-use sui_config::genesis::Genesis;
 use sui_json_rpc_types::{SuiObjectDataOptions, SuiTransactionBlockResponseOptions};
 use sui_sdk::SuiClientBuilder;
 use sui_types::base_types::{ObjectID, TransactionDigest};
@@ -119,36 +117,8 @@ pub async fn get_verified_effects_and_events(
         .await
         .map_err(|e| anyhow!(format!("Cannot get full checkpoint: {e}")))?;
 
-    // Load the list of stored checkpoints
-    let checkpoints_list: CheckpointsList = read_checkpoint_list(config)?;
-
-    // find the stored checkpoint before the seq checkpoint
-    let prev_ckp_id = checkpoints_list
-        .checkpoints
-        .iter()
-        .filter(|ckp_id| **ckp_id < seq)
-        .last();
-
-    let committee = if let Some(prev_ckp_id) = prev_ckp_id {
-        // Read it from the store
-        let prev_ckp = read_checkpoint(config, *prev_ckp_id)?;
-
-        // Check we have the right checkpoint
-        anyhow::ensure!(
-            prev_ckp.epoch().checked_add(1).unwrap() == full_check_point.checkpoint_summary.epoch(),
-            "Checkpoint sequence number does not match. Need to Sync."
-        );
-
-        // Get the committee from the previous checkpoint
-        extract_new_committee_info(&prev_ckp)?
-    } else {
-        // Since we did not find a small committee checkpoint we use the genesis
-        let mut genesis_path = config.checkpoint_summary_dir.clone();
-        genesis_path.push(&config.genesis_filename);
-        Genesis::load(&genesis_path)?
-            .committee()
-            .map_err(|e| anyhow!(format!("Cannot load Genesis: {e}")))?
-    };
+    // Get the committee from the synced end of epoch checkpoint of the previous epoch
+    let committee = read_committee(config, full_check_point.checkpoint_summary.epoch())?;
 
     info!("Extracting effects and events for TID: {}", tid);
     extract_verified_effects_and_events(&full_check_point, &committee, tid)
@@ -210,36 +180,8 @@ pub async fn get_verified_checkpoint(
         .await
         .map_err(|e| anyhow!(format!("Cannot get full checkpoint: {e}")))?;
 
-    // Load the list of stored checkpoints
-    let checkpoints_list: CheckpointsList = read_checkpoint_list(config)?;
-
-    // find the stored checkpoint before the seq checkpoint
-    let prev_ckp_id = checkpoints_list
-        .checkpoints
-        .iter()
-        .filter(|ckp_id| **ckp_id < seq)
-        .last();
-
-    let committee = if let Some(prev_ckp_id) = prev_ckp_id {
-        // Read it from the store
-        let prev_ckp = read_checkpoint(config, *prev_ckp_id)?;
-
-        // Check we have the right checkpoint
-        anyhow::ensure!(
-            prev_ckp.epoch().checked_add(1).unwrap() == full_check_point.checkpoint_summary.epoch(),
-            "Checkpoint sequence number does not match. Need to Sync."
-        );
-
-        // Get the committee from the previous checkpoint
-        extract_new_committee_info(&prev_ckp)?
-    } else {
-        // Since we did not find a small committee checkpoint we use the genesis
-        let mut genesis_path = config.checkpoint_summary_dir.clone();
-        genesis_path.push(&config.genesis_filename);
-        Genesis::load(&genesis_path)?
-            .committee()
-            .map_err(|e| anyhow!(format!("Cannot load Genesis: {e}")))?
-    };
+    // Get the committee from the synced end of epoch checkpoint of the previous epoch
+    let committee = read_committee(config, full_check_point.checkpoint_summary.epoch())?;
 
     // Verify that committee signed this checkpoint and checkpoint contents with digest
     full_check_point
@@ -265,6 +207,7 @@ mod tests {
     use sui_types::messages_checkpoint::{CheckpointSummary, FullCheckpointContents};
 
     use super::*;
+    use crate::committee::extract_new_committee_info;
     use std::path::{Path, PathBuf};
     use std::str::FromStr;
     use sui_types::crypto::AuthorityQuorumSignInfo;
@@ -5,7 +5,9 @@ use anyhow::anyhow;
 
 use sui_light_client::proof::{
     base::{Proof, ProofBuilder, ProofContents, ProofTarget, ProofVerifier},
+    bundle::ProofBundle,
     committee::{extract_new_committee_info, CommitteeProof},
+    error::ProofError,
     objects::ObjectsTarget,
 };
 
@@ -213,3 +215,87 @@ async fn test_event_target_fail_bad_event() {
 
     assert!(event_proof.verify(&committee).is_err());
 }
+
+#[tokio::test]
+async fn test_bundle_success() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+
+    let sample_object: Object = full_checkpoint.transactions[0].output_objects[0].clone();
+    let sample_ref = sample_object.compute_object_reference();
+    let proof = ProofTarget::new_objects(vec![(sample_ref, sample_object)])
+        .construct(&full_checkpoint)
+        .unwrap();
+
+    let bundle = ProofBundle {
+        committee_chain: vec![],
+        proof,
+    };
+
+    // The bundle survives a round trip through its file format
+    let bytes = bcs::to_bytes(&bundle).unwrap();
+    let bundle: ProofBundle = bcs::from_bytes(&bytes).unwrap();
+
+    assert!(bundle.verify(&committee).is_ok());
+}
+
+// Summaries from before the trusted committee's epoch are skipped
+#[tokio::test]
+async fn test_bundle_skips_earlier_epochs() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+    let (_, committee_checkpoint) = read_data(15918264, 15918264).await;
+
+    let new_committee = extract_new_committee_info(&full_checkpoint.checkpoint_summary).unwrap();
+    let proof = ProofTarget::new_committee(new_committee)
+        .construct(&full_checkpoint)
+        .unwrap();
+
+    let bundle = ProofBundle {
+        committee_chain: vec![committee_checkpoint.checkpoint_summary],
+        proof,
+    };
+
+    assert!(bundle.verify(&committee).is_ok());
+}
+
+// Fail if the chain moves past the epoch of the proof
+#[tokio::test]
+async fn test_bundle_fail_epoch_mismatch() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+
+    let new_committee = extract_new_committee_info(&full_checkpoint.checkpoint_summary).unwrap();
+    let proof = ProofTarget::new_committee(new_committee)
+        .construct(&full_checkpoint)
+        .unwrap();
+
+    let bundle = ProofBundle {
+        committee_chain: vec![full_checkpoint.checkpoint_summary.clone()],
+        proof,
+    };
+
+    assert!(matches!(
+        bundle.verify(&committee),
+        Err(ProofError::EpochMismatch)
+    ));
+}
+
+// Fail if the chain does not start at the epoch of the trusted committee
+#[tokio::test]
+async fn test_bundle_fail_chain_gap() {
+    let (mut committee, full_checkpoint) = read_data(15918264, 16005062).await;
+
+    let new_committee = extract_new_committee_info(&full_checkpoint.checkpoint_summary).unwrap();
+    let proof = ProofTarget::new_committee(new_committee)
+        .construct(&full_checkpoint)
+        .unwrap();
+
+    let bundle = ProofBundle {
+        committee_chain: vec![full_checkpoint.checkpoint_summary.clone()],
+        proof,
+    };
+
+    committee.epoch -= 1; // WRONG
+    assert!(matches!(
+        bundle.verify(&committee),
+        Err(ProofError::CommitteeChainGap { .. })
+    ));
+}
@@ -11,6 +11,7 @@ use sui_package_management::system_package_versions::latest_system_packages;
 pub mod build;
 pub mod coverage;
 pub mod disassemble;
+pub mod fmt;
 pub mod manage_package;
 pub mod migrate;
 pub mod new;
@@ -22,6 +23,7 @@ pub enum Command {
     Build(build::Build),
     Coverage(coverage::Coverage),
     Disassemble(disassemble::Disassemble),
+    Fmt(fmt::Fmt),
     ManagePackage(manage_package::ManagePackage),
     Migrate(migrate::Migrate),
     New(new::New),
@@ -52,6 +54,7 @@ pub fn execute_move_command(
         Command::Build(c) => c.execute(package_path, build_config),
         Command::Coverage(c) => c.execute(package_path, build_config),
         Command::Disassemble(c) => c.execute(package_path, build_config),
+        Command::Fmt(c) => c.execute(package_path, build_config),
         Command::ManagePackage(c) => c.execute(package_path, build_config),
         Command::Migrate(c) => c.execute(package_path, build_config),
         Command::New(c) => c.execute(package_path),
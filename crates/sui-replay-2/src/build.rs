@@ -10,8 +10,14 @@ use move_package::{
     BuildConfig as MoveBuildConfig,
 };
 
+use crate::data_stores::package_override_store::PackageOverride;
 use clap::Parser;
-use std::{fs::File, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    path::{Path, PathBuf},
+};
+use sui_types::base_types::ObjectID;
 
 /// Arguments for the (optional) build sub-command.
 #[derive(Parser, Clone, Debug)]
@@ -93,3 +99,299 @@ pub fn handle_build_command(config: BuildCmdConfig) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Build the package at `package_path` to be used in place of its on-chain version in replay.
+/// The package replaces the on-chain package at its `published-at` address, or at the address
+/// of its modules if `published-at` is not set.
+pub fn build_package_override(
+    package_path: &Path,
+    chain_id: Option<String>,
+) -> anyhow::Result<PackageOverride> {
+    let mut move_build_config = MoveBuildConfig::default();
+    if let Some(err_msg) = set_sui_flavor(&mut move_build_config) {
+        anyhow::bail!(err_msg);
+    }
+    move_build_config.implicit_dependencies = implicit_deps(latest_system_packages());
+
+    let pkg = BuildConfig {
+        config: move_build_config,
+        run_bytecode_verifier: true,
+        print_diags_to_stderr: true,
+        chain_id,
+    }
+    .build(package_path)
+    .map_err(|e| anyhow::anyhow!("Failed to build package {}: {e}", package_path.display()))?;
+
+    let package_id = match &pkg.published_at {
+        Ok(published_at) => *published_at,
+        Err(_) => pkg
+            .published_root_module()
+            .map(|m| ObjectID::from(*m.self_id().address()))
+            .unwrap_or(ObjectID::ZERO),
+    };
+    if package_id == ObjectID::ZERO {
+        anyhow::bail!(
+            "Cannot override package {}: it has no `published-at` or named address",
+            package_path.display()
+        );
+    }
+
+    let mut modules = BTreeMap::new();
+    let mut datatypes = vec![];
+    for module in pkg.get_modules() {
+        let module_name = module.name().to_string();
+        let struct_handles = module.struct_defs().iter().map(|def| def.struct_handle);
+        let enum_handles = module.enum_defs().iter().map(|def| def.enum_handle);
+        for handle in struct_handles.chain(enum_handles) {
+            let name = module.identifier_at(module.datatype_handle_at(handle).name);
+            datatypes.push((module_name.clone(), name.to_string()));
+        }
+
+        let mut bytes = vec![];
+        module
+            .serialize_with_version(module.version, &mut bytes)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize module {module_name}: {e}"))?;
+        modules.insert(module_name, bytes);
+    }
+
+    Ok(PackageOverride {
+        package_id,
+        modules,
+        datatypes,
+        dependencies: pkg.get_dependency_storage_package_ids(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data_stores::package_override_store::PackageOverrideStore,
+        execution::execute_transaction_to_effects,
+        replay_interface::{
+            EpochData, EpochStore, ObjectKey, ObjectStore, TransactionInfo, TransactionStore,
+            VersionQuery,
+        },
+        replay_txn::ReplayTransaction,
+    };
+    use move_core_types::identifier::Identifier;
+    use simulacrum::Simulacrum;
+    use sui_types::{
+        base_types::SequenceNumber,
+        committee::ProtocolVersion,
+        effects::{TransactionEffects, TransactionEffectsAPI},
+        execution_status::{ExecutionFailureStatus, ExecutionStatus},
+        object::{Object, Owner},
+        programmable_transaction_builder::ProgrammableTransactionBuilder,
+        sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait,
+        supported_protocol_versions::{Chain, ProtocolConfig},
+        transaction::{Transaction, TransactionData},
+    };
+
+    const GAS_BUDGET: u64 = 5_000_000_000;
+
+    /// Serves the transactions and objects of a `Simulacrum` to replay. Only epoch 0 is known.
+    struct SimulacrumStore(Simulacrum);
+
+    impl TransactionStore for SimulacrumStore {
+        fn transaction_data_and_effects(
+            &self,
+            tx_digest: &str,
+        ) -> Result<Option<TransactionInfo>, anyhow::Error> {
+            let digest = tx_digest.parse()?;
+            let store = self.0.store();
+            let (Some(transaction), Some(effects)) = (
+                store.get_transaction(&digest),
+                store.get_transaction_effects(&digest),
+            ) else {
+                return Ok(None);
+            };
+            Ok(Some(TransactionInfo {
+                data: transaction.data().transaction_data().clone(),
+                effects,
+                checkpoint: 0,
+            }))
+        }
+    }
+
+    impl EpochStore for SimulacrumStore {
+        fn epoch_info(&self, epoch: u64) -> Result<Option<EpochData>, anyhow::Error> {
+            let state = self.0.epoch_start_state();
+            Ok((epoch == 0).then(|| EpochData {
+                epoch_id: 0,
+                protocol_version: state.protocol_version().as_u64(),
+                rgp: self.0.reference_gas_price(),
+                start_timestamp: state.epoch_start_timestamp_ms(),
+            }))
+        }
+
+        fn protocol_config(&self, epoch: u64) -> Result<Option<ProtocolConfig>, anyhow::Error> {
+            Ok(self.epoch_info(epoch)?.map(|epoch| {
+                ProtocolConfig::get_for_version(
+                    ProtocolVersion::new(epoch.protocol_version),
+                    Chain::Unknown,
+                )
+            }))
+        }
+    }
+
+    impl ObjectStore for SimulacrumStore {
+        fn get_objects(
+            &self,
+            keys: &[ObjectKey],
+        ) -> Result<Vec<Option<(Object, u64)>>, anyhow::Error> {
+            let store = self.0.store();
+            Ok(keys
+                .iter()
+                .map(|key| {
+                    let object = match key.version_query {
+                        VersionQuery::Version(version) => store.get_object_at_version(
+                            &key.object_id,
+                            SequenceNumber::from_u64(version),
+                        ),
+                        // Only packages and objects that were not modified since are queried
+                        // without an exact version, so the latest version is correct.
+                        VersionQuery::RootVersion(_) | VersionQuery::AtCheckpoint(_) => {
+                            store.get_object(&key.object_id)
+                        }
+                    }?;
+                    let version = object.version().value();
+                    Some((object, version))
+                })
+                .collect())
+        }
+    }
+
+    /// Write a package with a single module, `override_test::m`, defining `value`, with the
+    /// given body. The package depends on the standard library from this repository, so it can
+    /// be built without fetching the system packages.
+    fn write_package(root: &Path, address: ObjectID, body: &str) {
+        let stdlib =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../sui-framework/packages/move-stdlib");
+        let published_at = if address == ObjectID::ZERO {
+            String::new()
+        } else {
+            format!("published-at = \"{address}\"\n")
+        };
+
+        std::fs::create_dir_all(root.join("sources")).unwrap();
+        std::fs::write(
+            root.join("Move.toml"),
+            format!(
+                "[package]\n\
+                 name = \"override_test\"\n\
+                 edition = \"2024\"\n\
+                 {published_at}\n\
+                 [dependencies]\n\
+                 MoveStdlib = {{ local = \"{}\" }}\n\n\
+                 [addresses]\n\
+                 override_test = \"{address}\"\n",
+                stdlib.display(),
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("sources/m.move"),
+            format!("module override_test::m;\n\npublic fun value(): u64 {{ {body} }}\n"),
+        )
+        .unwrap();
+    }
+
+    /// Replay the transaction `digest` from `store`, returning the on-chain and the replayed
+    /// effects.
+    fn replay(
+        digest: &str,
+        store: &(impl TransactionStore + EpochStore + ObjectStore),
+    ) -> (TransactionEffects, TransactionEffects) {
+        let txn = ReplayTransaction::load(digest, store, store, store).unwrap();
+        let (_, context_and_effects) =
+            execute_transaction_to_effects(txn, store, store, &mut None).unwrap();
+        (
+            context_and_effects.expected_effects,
+            context_and_effects.execution_effects,
+        )
+    }
+
+    #[test]
+    fn replay_against_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("original");
+        let modified = dir.path().join("modified");
+
+        // Publish the original package, and call it.
+        let mut simulacrum = Simulacrum::new();
+        write_package(&original, ObjectID::ZERO, "1");
+        let pkg = sui_move_build::BuildConfig::new_for_testing()
+            .build(&original)
+            .unwrap();
+
+        let (sender, key, gas) = simulacrum.funded_account(GAS_BUDGET * 2).unwrap();
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.publish_immutable(
+            pkg.get_package_bytes(false),
+            pkg.get_dependency_storage_package_ids(),
+        );
+        let data = TransactionData::new_programmable(
+            sender,
+            vec![gas],
+            builder.finish(),
+            GAS_BUDGET,
+            simulacrum.reference_gas_price(),
+        );
+        let (fx, _) = simulacrum
+            .execute_transaction(Transaction::from_data_and_signer(data, vec![&key]))
+            .unwrap();
+        let package_id = fx
+            .created()
+            .into_iter()
+            .find_map(|((id, _, _), owner)| matches!(owner, Owner::Immutable).then_some(id))
+            .unwrap();
+
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.programmable_move_call(
+            package_id,
+            Identifier::new("m").unwrap(),
+            Identifier::new("value").unwrap(),
+            vec![],
+            vec![],
+        );
+        let data = TransactionData::new_programmable(
+            sender,
+            vec![fx.gas_object().0],
+            builder.finish(),
+            GAS_BUDGET,
+            simulacrum.reference_gas_price(),
+        );
+        let (fx, _) = simulacrum
+            .execute_transaction(Transaction::from_data_and_signer(data, vec![&key]))
+            .unwrap();
+        assert!(fx.status().is_ok());
+        let digest = fx.transaction_digest().to_string();
+
+        // Without overrides, the replay reproduces the on-chain effects.
+        let store = SimulacrumStore(simulacrum);
+        let (expected, actual) = replay(&digest, &store);
+        assert_eq!(expected, actual);
+
+        // The override replaces the package at its published address, and links against the
+        // on-chain standard library, so the call runs the modified code.
+        write_package(&modified, package_id, "abort 42");
+        let package_override = build_package_override(&modified, None).unwrap();
+        assert_eq!(package_override.package_id, package_id);
+        assert!(package_override.datatypes.is_empty());
+        assert!(package_override.modules.contains_key("m"));
+
+        let store = PackageOverrideStore::new(store, vec![package_override]);
+        let (expected, actual) = replay(&digest, &store);
+        assert!(expected.status().is_ok());
+        let ExecutionStatus::Failure {
+            error: ExecutionFailureStatus::MoveAbort(location, code),
+            ..
+        } = actual.status()
+        else {
+            panic!("expected the override to abort, got {:?}", actual.status());
+        };
+        assert_eq!(*location.module.address(), package_id.into());
+        assert_eq!(*code, 42);
+    }
+}
@@ -8,8 +8,8 @@
 use crate::{
     data_stores::gql_queries,
     replay_interface::{
-        EpochData, EpochStore, ObjectKey, ObjectStore, SetupStore, StoreSummary, TransactionInfo,
-        TransactionStore, VersionQuery,
+        CheckpointStore, EpochData, EpochStore, ObjectKey, ObjectStore, SetupStore, StoreSummary,
+        TransactionInfo, TransactionStore, VersionQuery,
     },
     Node,
 };
@@ -108,6 +108,15 @@ impl TransactionStore for DataStore {
     }
 }
 
+impl CheckpointStore for DataStore {
+    fn checkpoint_transactions(
+        &self,
+        checkpoint: u64,
+    ) -> Result<Option<Vec<String>>, anyhow::Error> {
+        block_on!(self.checkpoint(checkpoint))
+    }
+}
+
 impl EpochStore for DataStore {
     fn epoch_info(&self, epoch: u64) -> Result<Option<EpochData>, anyhow::Error> {
         if let Some(epoch_data) = self.epoch_map.read().unwrap().get(&epoch) {
@@ -299,6 +308,13 @@ impl DataStore {
         data
     }
 
+    async fn checkpoint(&self, checkpoint: u64) -> Result<Option<Vec<String>>, anyhow::Error> {
+        debug!("Start checkpoint transactions query");
+        let data = gql_queries::checkpoint_txns_query::query(checkpoint, self).await;
+        debug!("End checkpoint transactions query");
+        data
+    }
+
     async fn epoch(&self, epoch_id: u64) -> Result<Option<EpochData>, anyhow::Error> {
         debug!("Start epoch query");
         let data = gql_queries::epoch_query::query(epoch_id, self).await;
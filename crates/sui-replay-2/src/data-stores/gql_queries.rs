@@ -3,7 +3,7 @@
 
 //! GQL Queries
 //! Interface to the rpc for the gql schema defined in `crates\sui-indexer-alt-graphql/schema.graphql`.
-//! Built in 3 modules: epoch_query, txn_query, object_query.
+//! Built in 4 modules: epoch_query, txn_query, checkpoint_txns_query, object_query.
 //! No GQL type escapes this module. From here we return structures defined by the replay tool
 //! or bcs encoded data of runtime structures.
 //!
@@ -204,6 +204,85 @@ pub(crate) mod txn_query {
     }
 }
 
+pub(crate) mod checkpoint_txns_query {
+    use super::*;
+    use anyhow::Context;
+
+    #[derive(cynic::QueryVariables)]
+    pub(crate) struct CheckpointTransactionsArgs {
+        pub checkpoint: Option<u64>,
+        pub after: Option<String>,
+    }
+
+    #[derive(cynic::QueryFragment)]
+    #[cynic(variables = "CheckpointTransactionsArgs")]
+    pub(crate) struct Query {
+        #[arguments(sequenceNumber: $checkpoint)]
+        checkpoint: Option<Checkpoint>,
+    }
+
+    #[derive(cynic::QueryFragment)]
+    #[cynic(variables = "CheckpointTransactionsArgs")]
+    pub(crate) struct Checkpoint {
+        #[arguments(after: $after)]
+        transactions: Option<TransactionConnection>,
+    }
+
+    #[derive(cynic::QueryFragment)]
+    pub(crate) struct TransactionConnection {
+        nodes: Vec<Transaction>,
+        page_info: PageInfo,
+    }
+
+    #[derive(cynic::QueryFragment)]
+    pub(crate) struct Transaction {
+        digest: String,
+    }
+
+    #[derive(cynic::QueryFragment)]
+    pub(crate) struct PageInfo {
+        has_next_page: bool,
+        end_cursor: Option<String>,
+    }
+
+    // Page through the transactions of a checkpoint, collecting their digests in execution
+    // order.
+    pub(crate) async fn query(
+        checkpoint: u64,
+        data_store: &DataStore,
+    ) -> Result<Option<Vec<String>>, anyhow::Error> {
+        let mut digests = vec![];
+        let mut after = None;
+        loop {
+            let query = Query::build(CheckpointTransactionsArgs {
+                checkpoint: Some(checkpoint),
+                after,
+            });
+            let response = data_store
+                .run_query(&query)
+                .await
+                .context("Failed to run checkpoint transactions query")?;
+
+            let Some(checkpoint_frag) = response.data.and_then(|data| data.checkpoint) else {
+                return Ok(None);
+            };
+            let connection = checkpoint_frag.transactions.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Missing transactions in checkpoint query response for checkpoint {}",
+                    checkpoint
+                )
+            })?;
+
+            digests.extend(connection.nodes.into_iter().map(|txn| txn.digest));
+            if !connection.page_info.has_next_page {
+                break;
+            }
+            after = connection.page_info.end_cursor;
+        }
+        Ok(Some(digests))
+    }
+}
+
 pub(crate) mod object_query {
     use sui_types::object::Object;
 
@@ -8,4 +8,5 @@ pub mod file_system_store;
 pub mod gql_queries;
 pub mod in_memory_store;
 pub mod lru_mem_store;
+pub mod package_override_store;
 pub mod read_through_store;
@@ -0,0 +1,379 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A store that replaces the modules of some packages with locally built ones.
+//!
+//! `PackageOverrideStore` wraps another store and passes all queries through to it.
+//! When a package in the override set is loaded, the on-chain package is loaded from the
+//! wrapped store and its modules are swapped with the locally built modules, keeping the
+//! on-chain ID and version, so that the transaction executes against the modified code as if
+//! it had been deployed at the time.
+//! The linkage table is built from the dependencies of the locally built package: dependencies
+//! the on-chain package already links against keep their on-chain linkage, and new ones are
+//! loaded from the wrapped store, at the checkpoint the package is loaded at.
+//! Effects of such a replay are expected to differ from the on-chain effects, and the
+//! difference is what the user is after ("what would have happened if...").
+
+use crate::replay_interface::{
+    CheckpointStore, EpochData, EpochStore, ObjectKey, ObjectStore, ReadDataStore, SetupStore,
+    StoreSummary, TransactionInfo, TransactionStore, VersionQuery,
+};
+use anyhow::{anyhow, bail, Context};
+use std::collections::BTreeMap;
+use sui_types::{
+    base_types::ObjectID,
+    move_package::{MovePackage, TypeOrigin, UpgradeInfo},
+    object::{Data, Object},
+    supported_protocol_versions::ProtocolConfig,
+};
+
+/// Overrides are built locally and never published, so they are not subject to the on-chain
+/// package size limit (similarly to system packages).
+const MAX_OVERRIDE_PACKAGE_SIZE: u64 = u64::MAX;
+
+/// A locally built package used in place of an on-chain package.
+#[derive(Clone, Debug)]
+pub struct PackageOverride {
+    /// The on-chain (storage) ID of the package to replace.
+    pub package_id: ObjectID,
+    /// Serialized modules, by module name.
+    pub modules: BTreeMap<String, Vec<u8>>,
+    /// Datatypes defined by the modules, as (module name, datatype name).
+    pub datatypes: Vec<(String, String)>,
+    /// Storage IDs of the published packages the override depends on (directly or transitively).
+    pub dependencies: Vec<ObjectID>,
+}
+
+/// A store that swaps the modules of the packages in `overrides` on load.
+/// With no overrides the store is a pass-through.
+pub struct PackageOverrideStore<S> {
+    store: S,
+    overrides: BTreeMap<ObjectID, PackageOverride>,
+}
+
+impl<S> PackageOverrideStore<S> {
+    pub fn new(store: S, overrides: Vec<PackageOverride>) -> Self {
+        let overrides = overrides
+            .into_iter()
+            .map(|package| (package.package_id, package))
+            .collect();
+        Self { store, overrides }
+    }
+}
+
+impl<S: ObjectStore> PackageOverrideStore<S> {
+    // Replace the modules of an on-chain package with the ones in `package_override`.
+    // Types that are new in the override are recorded as originating in the overridden package.
+    // The package was loaded with `version_query`, which is used to load any new dependencies.
+    fn override_package(
+        &self,
+        object: Object,
+        package_override: &PackageOverride,
+        version_query: &VersionQuery,
+    ) -> Result<Object, anyhow::Error> {
+        let Data::Package(package) = &object.data else {
+            return Err(anyhow!(
+                "Object {} is not a package and cannot be overridden",
+                object.id()
+            ));
+        };
+
+        let mut type_origin_table = package.type_origin_table().clone();
+        let type_origin_map = package.type_origin_map();
+        for (module_name, datatype_name) in &package_override.datatypes {
+            if !type_origin_map.contains_key(&(module_name.clone(), datatype_name.clone())) {
+                type_origin_table.push(TypeOrigin {
+                    module_name: module_name.clone(),
+                    datatype_name: datatype_name.clone(),
+                    package: package.id(),
+                });
+            }
+        }
+
+        let linkage_table = self.linkage_table(package, package_override, version_query)?;
+        let package = MovePackage::new(
+            package.id(),
+            package.version(),
+            package_override.modules.clone(),
+            MAX_OVERRIDE_PACKAGE_SIZE,
+            type_origin_table,
+            linkage_table,
+        )
+        .map_err(|e| anyhow!("Failed to create package override for {}: {e}", object.id()))?;
+        Ok(Object::new_from_package(
+            package,
+            object.previous_transaction,
+        ))
+    }
+
+    // Build the linkage table of the override from its dependencies. Dependencies that the
+    // on-chain `package` links against keep their linkage, the others are loaded from the wrapped
+    // store to find their original ID and version.
+    fn linkage_table(
+        &self,
+        package: &MovePackage,
+        package_override: &PackageOverride,
+        version_query: &VersionQuery,
+    ) -> Result<BTreeMap<ObjectID, UpgradeInfo>, anyhow::Error> {
+        let mut linkage_table = BTreeMap::new();
+        let mut new_dependencies = vec![];
+        for dependency in &package_override.dependencies {
+            let linked = package
+                .linkage_table()
+                .iter()
+                .find(|(_, info)| info.upgraded_id == *dependency);
+            match linked {
+                Some((original_id, info)) => {
+                    linkage_table.insert(*original_id, info.clone());
+                }
+                None => new_dependencies.push(*dependency),
+            }
+        }
+
+        if new_dependencies.is_empty() {
+            return Ok(linkage_table);
+        }
+
+        // Packages are immutable, so any checkpoint after a dependency was published finds it.
+        let VersionQuery::AtCheckpoint(checkpoint) = version_query else {
+            bail!(
+                "Cannot resolve new dependencies {new_dependencies:?} of package override {} \
+                 when it is loaded by version",
+                package.id(),
+            );
+        };
+        let keys: Vec<_> = new_dependencies
+            .iter()
+            .map(|object_id| ObjectKey {
+                object_id: *object_id,
+                version_query: VersionQuery::AtCheckpoint(*checkpoint),
+            })
+            .collect();
+        let objects = self.store.get_objects(&keys)?;
+        for (object_id, object) in new_dependencies.iter().zip(objects) {
+            let Some((object, _)) = object else {
+                bail!(
+                    "Dependency {object_id} of package override {} not found",
+                    package.id()
+                );
+            };
+            let Data::Package(dependency) = &object.data else {
+                bail!(
+                    "Dependency {object_id} of package override {} is not a package",
+                    package.id()
+                );
+            };
+            linkage_table.insert(
+                dependency.original_package_id(),
+                UpgradeInfo {
+                    upgraded_id: dependency.id(),
+                    upgraded_version: dependency.version(),
+                },
+            );
+        }
+
+        Ok(linkage_table)
+    }
+}
+
+impl<S: TransactionStore> TransactionStore for PackageOverrideStore<S> {
+    fn transaction_data_and_effects(
+        &self,
+        tx_digest: &str,
+    ) -> Result<Option<TransactionInfo>, anyhow::Error> {
+        self.store.transaction_data_and_effects(tx_digest)
+    }
+}
+
+impl<S: CheckpointStore> CheckpointStore for PackageOverrideStore<S> {
+    fn checkpoint_transactions(
+        &self,
+        checkpoint: u64,
+    ) -> Result<Option<Vec<String>>, anyhow::Error> {
+        self.store.checkpoint_transactions(checkpoint)
+    }
+}
+
+impl<S: EpochStore> EpochStore for PackageOverrideStore<S> {
+    fn epoch_info(&self, epoch: u64) -> Result<Option<EpochData>, anyhow::Error> {
+        self.store.epoch_info(epoch)
+    }
+
+    fn protocol_config(&self, epoch: u64) -> Result<Option<ProtocolConfig>, anyhow::Error> {
+        self.store.protocol_config(epoch)
+    }
+}
+
+impl<S: ObjectStore> ObjectStore for PackageOverrideStore<S> {
+    fn get_objects(&self, keys: &[ObjectKey]) -> Result<Vec<Option<(Object, u64)>>, anyhow::Error> {
+        let objects = self.store.get_objects(keys)?;
+        if self.overrides.is_empty() {
+            return Ok(objects);
+        }
+
+        keys.iter()
+            .zip(objects)
+            .map(|(key, object)| {
+                let Some((object, version)) = object else {
+                    return Ok(None);
+                };
+                match self.overrides.get(&object.id()) {
+                    Some(package_override) => {
+                        let id = object.id();
+                        let object = self
+                            .override_package(object, package_override, &key.version_query)
+                            .with_context(|| format!("Failed to override package {id}"))?;
+                        Ok(Some((object, version)))
+                    }
+                    None => Ok(Some((object, version))),
+                }
+            })
+            .collect()
+    }
+}
+
+impl<S: ReadDataStore + SetupStore> SetupStore for PackageOverrideStore<S> {
+    fn setup(&self, chain_id: Option<String>) -> Result<Option<String>, anyhow::Error> {
+        self.store.setup(chain_id)
+    }
+}
+
+impl<S: ReadDataStore + StoreSummary> StoreSummary for PackageOverrideStore<S> {
+    fn summary<W: std::io::Write>(&self, w: &mut W) -> anyhow::Result<()> {
+        if !self.overrides.is_empty() {
+            writeln!(w, "PackageOverrideStore summary")?;
+            for package_id in self.overrides.keys() {
+                writeln!(w, "  Overridden package: {}", package_id)?;
+            }
+        }
+        self.store.summary(w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_framework::BuiltInFramework;
+    use sui_types::{
+        base_types::SequenceNumber, digests::TransactionDigest, MOVE_STDLIB_PACKAGE_ID,
+        SUI_SYSTEM_PACKAGE_ID,
+    };
+
+    /// Serves every object it holds, whatever the version query.
+    struct Objects(BTreeMap<ObjectID, Object>);
+
+    impl ObjectStore for Objects {
+        fn get_objects(
+            &self,
+            keys: &[ObjectKey],
+        ) -> Result<Vec<Option<(Object, u64)>>, anyhow::Error> {
+            Ok(keys
+                .iter()
+                .map(|key| {
+                    let object = self.0.get(&key.object_id)?.clone();
+                    let version = object.version().value();
+                    Some((object, version))
+                })
+                .collect())
+        }
+    }
+
+    fn upgrade_info(id: ObjectID, version: u64) -> UpgradeInfo {
+        UpgradeInfo {
+            upgraded_id: id,
+            upgraded_version: SequenceNumber::from_u64(version),
+        }
+    }
+
+    /// A store with the system packages, and an on-chain package at `0xa`, version 5, linking
+    /// against the standard library at version 7 and a package at `0xb`.
+    fn store() -> Objects {
+        let package_id = ObjectID::from_single_byte(0xa);
+        let dependency_id = ObjectID::from_single_byte(0xb);
+        let package = MovePackage::new(
+            package_id,
+            SequenceNumber::from_u64(5),
+            BTreeMap::from([("m".to_string(), vec![0])]),
+            MAX_OVERRIDE_PACKAGE_SIZE,
+            vec![],
+            BTreeMap::from([
+                (
+                    MOVE_STDLIB_PACKAGE_ID,
+                    upgrade_info(MOVE_STDLIB_PACKAGE_ID, 7),
+                ),
+                (dependency_id, upgrade_info(dependency_id, 2)),
+            ]),
+        )
+        .unwrap();
+
+        let mut objects: BTreeMap<_, _> = BuiltInFramework::genesis_objects()
+            .map(|object| (object.id(), object))
+            .collect();
+        objects.insert(
+            package_id,
+            Object::new_from_package(package, TransactionDigest::genesis_marker()),
+        );
+        Objects(objects)
+    }
+
+    /// An override of `0xa` that no longer depends on `0xb`, and newly depends on the system
+    /// package.
+    fn package_override() -> PackageOverride {
+        PackageOverride {
+            package_id: ObjectID::from_single_byte(0xa),
+            modules: BTreeMap::from([("m".to_string(), vec![1])]),
+            datatypes: vec![],
+            dependencies: vec![MOVE_STDLIB_PACKAGE_ID, SUI_SYSTEM_PACKAGE_ID],
+        }
+    }
+
+    #[test]
+    fn override_with_changed_dependencies() {
+        let store = PackageOverrideStore::new(store(), vec![package_override()]);
+        let key = ObjectKey {
+            object_id: ObjectID::from_single_byte(0xa),
+            version_query: VersionQuery::AtCheckpoint(10),
+        };
+
+        let (object, version) = store.get_objects(&[key]).unwrap().pop().unwrap().unwrap();
+        let Data::Package(package) = &object.data else {
+            panic!("expected a package");
+        };
+
+        assert_eq!(version, 5);
+        assert_eq!(package.version(), SequenceNumber::from_u64(5));
+        assert_eq!(
+            package.serialized_module_map(),
+            &BTreeMap::from([("m".to_string(), vec![1])]),
+        );
+
+        // The standard library keeps its on-chain linkage, the system package is linked at the
+        // version found in the store, and the dropped dependency is no longer linked.
+        let system = BuiltInFramework::get_package_by_id(&SUI_SYSTEM_PACKAGE_ID).genesis_object();
+        assert_eq!(
+            package.linkage_table(),
+            &BTreeMap::from([
+                (
+                    MOVE_STDLIB_PACKAGE_ID,
+                    upgrade_info(MOVE_STDLIB_PACKAGE_ID, 7)
+                ),
+                (
+                    SUI_SYSTEM_PACKAGE_ID,
+                    upgrade_info(SUI_SYSTEM_PACKAGE_ID, system.version().value()),
+                ),
+            ]),
+        );
+    }
+
+    #[test]
+    fn override_new_dependency_by_version() {
+        let store = PackageOverrideStore::new(store(), vec![package_override()]);
+        let key = ObjectKey {
+            object_id: ObjectID::from_single_byte(0xa),
+            version_query: VersionQuery::Version(5),
+        };
+
+        // New dependencies can only be found at a checkpoint.
+        assert!(store.get_objects(&[key]).is_err());
+    }
+}
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::replay_interface::{
-    EpochData, EpochStore, ObjectKey, ObjectStore, ReadDataStore, ReadWriteDataStore, SetupStore,
-    StoreSummary, TransactionInfo, TransactionStore,
+    CheckpointStore, EpochData, EpochStore, ObjectKey, ObjectStore, ReadDataStore,
+    ReadWriteDataStore, SetupStore, StoreSummary, TransactionInfo, TransactionStore,
 };
 use sui_types::{object::Object, supported_protocol_versions::ProtocolConfig};
 
@@ -53,6 +53,20 @@ where
     }
 }
 
+// Checkpoint contents are not cached, the list of transactions is always read from the secondary.
+impl<P, S> CheckpointStore for ReadThroughStore<P, S>
+where
+    P: ReadWriteDataStore,
+    S: ReadDataStore + CheckpointStore,
+{
+    fn checkpoint_transactions(
+        &self,
+        checkpoint: u64,
+    ) -> Result<Option<Vec<String>>, anyhow::Error> {
+        self.secondary.checkpoint_transactions(checkpoint)
+    }
+}
+
 impl<P, S> EpochStore for ReadThroughStore<P, S>
 where
     P: ReadWriteDataStore,
@@ -13,7 +13,7 @@
 
 use crate::{
     replay_interface::{EpochStore, ObjectKey, ObjectStore, VersionQuery},
-    replay_txn::{get_input_objects_for_replay, ReplayTransaction},
+    replay_txn::{get_input_objects_for_replay, load_new_transaction_objects, ReplayTransaction},
 };
 use anyhow::Context;
 use move_core_types::{language_storage::ModuleId, resolver::ModuleResolver};
@@ -38,7 +38,7 @@ use sui_types::{
     object::Object,
     storage::{BackingPackageStore, ChildObjectResolver, PackageObject, ParentSync},
     supported_protocol_versions::ProtocolConfig,
-    transaction::{CheckedInputObjects, TransactionDataAPI},
+    transaction::{CheckedInputObjects, TransactionData, TransactionDataAPI},
 };
 use tracing::{debug, trace};
 
@@ -77,9 +77,6 @@ pub fn execute_transaction_to_effects(
     anyhow::Error,
 > {
     debug!("Start execution");
-    // TODO: Hook up...
-    let config_certificate_deny_set: HashSet<TransactionDigest> = HashSet::new();
-
     let ReplayTransaction {
         digest,
         checkpoint,
@@ -89,9 +86,97 @@ pub fn execute_transaction_to_effects(
         object_cache,
     } = txn;
 
-    let input_objects = get_input_objects_for_replay(&txn_data, &digest, &object_cache)?;
-    let protocol_config = &executor.protocol_config;
     let epoch = expected_effects.executed_epoch();
+    let (result, inner_store, gas_status, effects, object_cache) = execute_with_object_cache(
+        &executor,
+        &digest,
+        &txn_data,
+        checkpoint,
+        epoch,
+        object_cache,
+        epoch_store,
+        object_store,
+        trace_builder_opt,
+    )?;
+    debug!("End execution");
+    Ok((
+        result,
+        TxnContextAndEffects {
+            execution_effects: effects,
+            expected_effects,
+            gas_status,
+            object_cache,
+            inner_store,
+        },
+    ))
+}
+
+// Executes a transaction that was not executed on chain (e.g. a dry run) on top of the
+// state of the chain at `checkpoint`, in `epoch`.
+// Shared objects are read as they were at `checkpoint`.
+// The returned gas status holds the gas profile of the execution if the executor was
+// created with the profiler enabled.
+#[allow(clippy::type_complexity)]
+pub fn execute_new_transaction_to_effects(
+    txn_data: TransactionData,
+    checkpoint: u64,
+    epoch: EpochId,
+    executor: &ReplayExecutor,
+    epoch_store: &dyn EpochStore,
+    object_store: &dyn ObjectStore,
+) -> Result<
+    (
+        Result<(), ExecutionError>, // transaction result
+        TransactionEffects,         // effects of the execution
+        SuiGasStatus,               // gas status of the execution
+    ),
+    anyhow::Error,
+> {
+    debug!("Start execution");
+    let digest = txn_data.digest();
+    let object_cache = load_new_transaction_objects(&txn_data, checkpoint, object_store)?;
+    let (result, _inner_store, gas_status, effects, _object_cache) = execute_with_object_cache(
+        executor,
+        &digest,
+        &txn_data,
+        checkpoint,
+        epoch,
+        object_cache,
+        epoch_store,
+        object_store,
+        &mut None,
+    )?;
+    debug!("End execution");
+    Ok((result, effects, gas_status))
+}
+
+// Executes a transaction whose input objects and packages are in `object_cache`.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn execute_with_object_cache(
+    executor: &ReplayExecutor,
+    digest: &TransactionDigest,
+    txn_data: &TransactionData,
+    checkpoint: u64,
+    epoch: EpochId,
+    object_cache: BTreeMap<ObjectID, BTreeMap<u64, Object>>,
+    epoch_store: &dyn EpochStore,
+    object_store: &dyn ObjectStore,
+    trace_builder_opt: &mut Option<MoveTraceBuilder>,
+) -> Result<
+    (
+        Result<(), ExecutionError>,
+        InnerTemporaryStore,
+        SuiGasStatus,
+        TransactionEffects,
+        BTreeMap<ObjectID, BTreeMap<u64, Object>>,
+    ),
+    anyhow::Error,
+> {
+    // TODO: Hook up...
+    let config_certificate_deny_set: HashSet<TransactionDigest> = HashSet::new();
+
+    let input_objects = get_input_objects_for_replay(txn_data, digest, &object_cache)?;
+    let protocol_config = &executor.protocol_config;
     let epoch_data = epoch_store
         .epoch_info(epoch)?
         .ok_or_else(|| anyhow::anyhow!(format!("Epoch {} not found", epoch)))?;
@@ -115,7 +200,7 @@ pub fn execute_transaction_to_effects(
     let input_objects = CheckedInputObjects::new_for_replay(input_objects);
     // TODO(address-balances): Get withdraw status from effects.
     let early_execution_error = get_early_execution_error(
-        &digest,
+        digest,
         &input_objects,
         &config_certificate_deny_set,
         // TODO(address-balances): Support balance withdraw status for replay
@@ -139,7 +224,7 @@ pub fn execute_transaction_to_effects(
             gas_status,
             txn_data.kind().clone(),
             txn_data.sender(),
-            digest,
+            *digest,
             trace_builder_opt,
         );
     let ReplayStore {
@@ -147,17 +232,12 @@ pub fn execute_transaction_to_effects(
         checkpoint: _,
         store: _,
     } = store;
-    let object_cache = object_cache.into_inner();
-    debug!("End execution");
     Ok((
         result,
-        TxnContextAndEffects {
-            execution_effects: effects,
-            expected_effects,
-            gas_status,
-            object_cache,
-            inner_store,
-        },
+        inner_store,
+        gas_status,
+        effects,
+        object_cache.into_inner(),
     ))
 }
 
@@ -0,0 +1,77 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Forking a `Simulacrum` from the state of a network, read through the replay data stores.
+//!
+//! ```ignore
+//! let store = ReadThroughStore::new(LruMemoryStore::new(node.clone()), DataStore::new(node, version)?);
+//! let simulacrum = Simulacrum::new_forked(ReplayForkSource::new(store, checkpoint), checkpoint);
+//! ```
+//!
+//! This is a library API: the replay tool has no command that forks a `Simulacrum`, so callers
+//! (tests, or tools embedding a `Simulacrum`) construct the source and the fork themselves.
+
+use crate::replay_interface::{ObjectKey, ObjectStore, VersionQuery};
+use simulacrum::ForkSource;
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber},
+    object::Object,
+};
+
+/// A `ForkSource` that reads objects as of `checkpoint` from a replay store.
+/// Fronting a remote store with a cache (e.g. `LruMemoryStore` in a `ReadThroughStore`) is up
+/// to the caller; the forked store caches every object it fetches in any case.
+///
+/// Replay stores only look up objects by ID, so this source does not implement
+/// `ForkSource::owned_objects`: objects an address owned at the fork are not listed as owned by
+/// the forked `Simulacrum`, although they can still be read and used by ID.
+pub struct ReplayForkSource<S> {
+    store: S,
+    checkpoint: u64,
+}
+
+impl<S> ReplayForkSource<S> {
+    pub fn new(store: S, checkpoint: u64) -> Self {
+        Self { store, checkpoint }
+    }
+
+    pub fn checkpoint(&self) -> u64 {
+        self.checkpoint
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<S: ObjectStore> ReplayForkSource<S> {
+    fn get_object_by_key(&self, key: ObjectKey) -> anyhow::Result<Option<Object>> {
+        Ok(self
+            .store
+            .get_objects(&[key])?
+            .into_iter()
+            .next()
+            .flatten()
+            .map(|(object, _)| object))
+    }
+}
+
+impl<S: ObjectStore> ForkSource for ReplayForkSource<S> {
+    fn get_object(&self, id: &ObjectID) -> anyhow::Result<Option<Object>> {
+        self.get_object_by_key(ObjectKey {
+            object_id: *id,
+            version_query: VersionQuery::AtCheckpoint(self.checkpoint),
+        })
+    }
+
+    fn get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> anyhow::Result<Option<Object>> {
+        self.get_object_by_key(ObjectKey {
+            object_id: *id,
+            version_query: VersionQuery::Version(version.value()),
+        })
+    }
+}
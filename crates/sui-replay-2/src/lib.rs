@@ -3,12 +3,17 @@
 
 use crate::{
     artifacts::{Artifact, ArtifactManager},
-    build::BuildCmdConfig,
+    build::{build_package_override, BuildCmdConfig},
     data_stores::{
-        data_store::DataStore, file_system_store::FileSystemStore, in_memory_store::InMemoryStore,
+        data_store::DataStore,
+        file_system_store::FileSystemStore,
+        in_memory_store::InMemoryStore,
+        lru_mem_store::LruMemoryStore,
+        package_override_store::{PackageOverride, PackageOverrideStore},
         read_through_store::ReadThroughStore,
     },
     displays::Pretty,
+    replay_checkpoint::{replay_checkpoints, CheckpointRange},
     replay_interface::{ReadDataStore, SetupStore, StoreSummary},
     replay_txn::replay_transaction,
 };
@@ -21,7 +26,11 @@ use std::{
     str::FromStr,
 };
 use sui_json_rpc_types::SuiTransactionBlockEffects;
-use sui_types::{effects::TransactionEffects, supported_protocol_versions::Chain};
+use sui_types::{
+    digests::{get_mainnet_chain_identifier, get_testnet_chain_identifier},
+    effects::TransactionEffects,
+    supported_protocol_versions::Chain,
+};
 
 pub mod artifacts;
 pub mod build;
@@ -29,6 +38,8 @@ pub mod build;
 pub mod data_stores;
 pub mod displays;
 pub mod execution;
+pub mod fork;
+pub mod replay_checkpoint;
 pub mod replay_interface;
 pub mod replay_txn;
 pub mod tracing;
@@ -74,6 +85,14 @@ pub struct ReplayConfig {
     /// File containing a list of digests, one per line.
     #[arg(long)]
     pub digests_path: Option<PathBuf>,
+    /// Replay all transactions in a checkpoint (`<checkpoint>`) or in an inclusive range of
+    /// checkpoints (`<start>-<end>`), in order, and print a summary of effects mismatches.
+    #[arg(long, conflicts_with_all = ["digest", "digests_path"])]
+    pub checkpoints: Option<CheckpointRange>,
+    /// Number of transactions of a checkpoint to replay in parallel when replaying checkpoints.
+    /// Defaults to the number of available CPUs.
+    #[arg(long, requires = "checkpoints")]
+    pub concurrency: Option<usize>,
     /// RPC of the fullnode used to replay the transaction.
     #[arg(long, short, default_value = "mainnet")]
     pub node: Node,
@@ -105,6 +124,12 @@ pub struct ReplayConfig {
     /// - inmem-fs-gql: InMemory -> FileSystem -> GraphQL (default)
     #[arg(long = "store-mode", value_enum, default_value_t = StoreMode::GqlOnly)]
     pub store_mode: StoreMode,
+    /// Path to a Move package to build and use in place of its on-chain version, e.g. a patched
+    /// `sui-framework`. The package replaces the on-chain package at its `published-at` address.
+    /// Can be repeated. Effects that differ from the on-chain effects are reported as forks but
+    /// are not treated as errors.
+    #[arg(long = "package-override", value_name = "PATH")]
+    pub package_overrides: Vec<PathBuf>,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -178,6 +203,8 @@ pub async fn handle_replay_config(config: &ReplayConfig, version: &str) -> anyho
         node,
         digest,
         digests_path,
+        checkpoints,
+        concurrency: _,
         trace,
         mut terminate_early,
         output_dir,
@@ -185,6 +212,7 @@ pub async fn handle_replay_config(config: &ReplayConfig, version: &str) -> anyho
         overwrite_existing,
         verbose,
         store_mode,
+        package_overrides: _,
     } = config;
 
     let output_root_dir = if let Some(dir) = output_dir {
@@ -205,6 +233,11 @@ pub async fn handle_replay_config(config: &ReplayConfig, version: &str) -> anyho
         );
     }
 
+    if let Some(checkpoints) = checkpoints {
+        handle_checkpoint_replay(config, *checkpoints, &output_root_dir, version)?;
+        return Ok(output_root_dir);
+    }
+
     // If a file is specified it is read and the digest ignored.
     // Once we decide on the options we want this is likely to change.
     let digests = if let Some(digests_path) = digests_path {
@@ -225,24 +258,29 @@ pub async fn handle_replay_config(config: &ReplayConfig, version: &str) -> anyho
         // single digest provided
         vec![tx_digest.clone()]
     } else {
-        bail!("either --digest or --digests-path must be provided");
+        bail!("either --digest, --digests-path or --checkpoints must be provided");
     };
 
     ::tracing::debug!("Binary version: {version}");
 
+    let overrides = build_package_overrides(config)?;
+    let overridden = !overrides.is_empty();
+
     // Build the selected data store and run replay
     match store_mode {
         StoreMode::GqlOnly => {
             let gql_store = DataStore::new(node.clone(), version)
                 .map_err(|e| anyhow!("Failed to create data store: {:?}", e))?;
+            let store = PackageOverrideStore::new(gql_store, overrides);
             run_replay(
-                &gql_store,
+                &store,
                 &output_root_dir,
                 &digests,
                 *overwrite_existing,
                 *trace,
                 *verbose,
                 terminate_early,
+                overridden,
             )
             .await?;
         }
@@ -252,6 +290,7 @@ pub async fn handle_replay_config(config: &ReplayConfig, version: &str) -> anyho
             let gql_store = DataStore::new(node.clone(), version)
                 .map_err(|e| anyhow!("Failed to create data store: {:?}", e))?;
             let store = ReadThroughStore::new(fs_store, gql_store);
+            let store = PackageOverrideStore::new(store, overrides);
             run_replay(
                 &store,
                 &output_root_dir,
@@ -260,20 +299,23 @@ pub async fn handle_replay_config(config: &ReplayConfig, version: &str) -> anyho
                 *trace,
                 *verbose,
                 terminate_early,
+                overridden,
             )
             .await?;
         }
         StoreMode::FsOnly => {
             let fs_store = FileSystemStore::new(node.clone())
                 .map_err(|e| anyhow!("Failed to create file system store: {:?}", e))?;
+            let store = PackageOverrideStore::new(fs_store, overrides);
             run_replay(
-                &fs_store,
+                &store,
                 &output_root_dir,
                 &digests,
                 *overwrite_existing,
                 *trace,
                 *verbose,
                 terminate_early,
+                overridden,
             )
             .await?;
         }
@@ -285,6 +327,7 @@ pub async fn handle_replay_config(config: &ReplayConfig, version: &str) -> anyho
             let secondary_store = ReadThroughStore::new(fs_store, gql_store);
             let in_memory_store = InMemoryStore::new(node.clone());
             let store = ReadThroughStore::new(in_memory_store, secondary_store);
+            let store = PackageOverrideStore::new(store, overrides);
             run_replay(
                 &store,
                 &output_root_dir,
@@ -293,6 +336,7 @@ pub async fn handle_replay_config(config: &ReplayConfig, version: &str) -> anyho
                 *trace,
                 *verbose,
                 terminate_early,
+                overridden,
             )
             .await?;
         }
@@ -301,6 +345,56 @@ pub async fn handle_replay_config(config: &ReplayConfig, version: &str) -> anyho
     Ok(output_root_dir)
 }
 
+// Replay checkpoints. Remote stores are fronted by an LRU cache shared by all the transactions
+// replayed, `inmem-fs-gql` already has an in-memory store in front.
+fn handle_checkpoint_replay(
+    config: &ReplayConfig,
+    checkpoints: CheckpointRange,
+    output_root_dir: &Path,
+    version: &str,
+) -> anyhow::Result<()> {
+    let node = &config.node;
+    let overrides = build_package_overrides(config)?;
+    match config.store_mode {
+        StoreMode::GqlOnly => {
+            let gql_store = DataStore::new(node.clone(), version)
+                .map_err(|e| anyhow!("Failed to create data store: {:?}", e))?;
+            let cache = LruMemoryStore::new(node.clone());
+            let store = ReadThroughStore::new(cache, gql_store);
+            let store = PackageOverrideStore::new(store, overrides);
+            replay_checkpoints(&store, output_root_dir, checkpoints, config)?;
+        }
+        StoreMode::FsThenGql => {
+            let fs_store = FileSystemStore::new(node.clone())
+                .map_err(|e| anyhow!("Failed to create file system store: {:?}", e))?;
+            let gql_store = DataStore::new(node.clone(), version)
+                .map_err(|e| anyhow!("Failed to create data store: {:?}", e))?;
+            let cache = LruMemoryStore::new(node.clone());
+            let store = ReadThroughStore::new(cache, ReadThroughStore::new(fs_store, gql_store));
+            let store = PackageOverrideStore::new(store, overrides);
+            replay_checkpoints(&store, output_root_dir, checkpoints, config)?;
+        }
+        StoreMode::FsOnly => {
+            bail!(
+                "Replaying checkpoints requires a GraphQL store to list the transactions \
+                in a checkpoint, use a different `--store-mode`"
+            );
+        }
+        StoreMode::InmemFsGql => {
+            let fs_store = FileSystemStore::new(node.clone())
+                .map_err(|e| anyhow!("Failed to create file system store: {:?}", e))?;
+            let gql_store = DataStore::new(node.clone(), version)
+                .map_err(|e| anyhow!("Failed to create data store: {:?}", e))?;
+            let secondary_store = ReadThroughStore::new(fs_store, gql_store);
+            let in_memory_store = InMemoryStore::new(node.clone());
+            let store = ReadThroughStore::new(in_memory_store, secondary_store);
+            let store = PackageOverrideStore::new(store, overrides);
+            replay_checkpoints(&store, output_root_dir, checkpoints, config)?;
+        }
+    }
+    Ok(())
+}
+
 async fn run_replay<S>(
     data_store: &S,
     output_root_dir: &Path,
@@ -309,6 +403,7 @@ async fn run_replay<S>(
     trace: bool,
     verbose: bool,
     terminate_early: bool,
+    allow_fork: bool,
 ) -> anyhow::Result<()>
 where
     S: ReadDataStore + StoreSummary + SetupStore,
@@ -317,7 +412,8 @@ where
     for tx_digest in digests {
         let tx_dir = output_root_dir.join(tx_digest);
         let artifact_manager = ArtifactManager::new(&tx_dir, overwrite_existing)?;
-        match replay_transaction(&artifact_manager, tx_digest, data_store, trace).await {
+        match replay_transaction(&artifact_manager, tx_digest, data_store, trace, allow_fork).await
+        {
             Err(e) if terminate_early => {
                 ::tracing::error!("Error while replaying transaction {}: {:?}", tx_digest, e);
                 bail!("Replay terminated due to error: {}", e);
@@ -341,6 +437,28 @@ where
     Ok(())
 }
 
+// Build the packages in `--package-override`, for the chain of the node replayed against.
+fn build_package_overrides(config: &ReplayConfig) -> anyhow::Result<Vec<PackageOverride>> {
+    let chain_id = match config.node.chain() {
+        Chain::Mainnet => Some(get_mainnet_chain_identifier().to_string()),
+        Chain::Testnet => Some(get_testnet_chain_identifier().to_string()),
+        Chain::Unknown => None,
+    };
+    config
+        .package_overrides
+        .iter()
+        .map(|path| {
+            let package = build_package_override(path, chain_id.clone())?;
+            ::tracing::info!(
+                "Replaying with package {} built from {}",
+                package.package_id,
+                path.display()
+            );
+            Ok(package)
+        })
+        .collect()
+}
+
 pub fn print_effects_or_fork<W: Write>(
     digest: &str,
     output_root: &Path,
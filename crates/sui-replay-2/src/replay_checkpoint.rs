@@ -0,0 +1,368 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replay of whole checkpoints.
+//!
+//! All the transactions in a checkpoint (or in a range of checkpoints) are replayed, checkpoint
+//! by checkpoint, against a single data store, so that objects, packages and epoch data loaded
+//! for one transaction are available to all the others.
+//!
+//! Transactions are replayed against the object versions recorded in their effects, so they do
+//! not depend on the replay of any other transaction and the transactions in a checkpoint can be
+//! replayed in parallel.
+//! Effects that do not match the ones executed on chain are collected and reported in a summary
+//! at the end of the replay. When replaying against overridden packages these are the effects
+//! of the modified code.
+
+use crate::{
+    artifacts::ArtifactManager,
+    diff_effects,
+    replay_interface::{CheckpointStore, ReadDataStore, SetupStore, StoreSummary},
+    replay_txn::{replay_transaction_effects, save_forked_effects},
+    ReplayConfig,
+};
+use anyhow::{anyhow, bail};
+use std::{
+    fmt,
+    io::Write,
+    path::Path,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// An inclusive range of checkpoints to replay.
+/// Parsed from `<start>` for a single checkpoint, or `<start>-<end>` for a range.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CheckpointRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Outcome of the replay of a single transaction.
+#[derive(Debug)]
+pub enum ReplayOutcome {
+    /// Replay effects match the effects executed on chain.
+    Matched,
+    /// Replay effects differ from the effects executed on chain.
+    /// Holds the lines that differ, as reported by `diff_effects`.
+    Forked(String),
+    /// The transaction could not be replayed.
+    Failed(String),
+}
+
+/// Summary of a checkpoint replay.
+#[derive(Debug, Default)]
+pub struct CheckpointReplaySummary {
+    /// Number of checkpoints replayed.
+    pub checkpoints: u64,
+    /// Number of transactions replayed with effects matching the ones executed on chain.
+    pub matched: u64,
+    /// Transactions (checkpoint, digest, diff) whose effects did not match.
+    pub forked: Vec<(u64, String, String)>,
+    /// Transactions (checkpoint, digest, error) that could not be replayed.
+    pub failed: Vec<(u64, String, String)>,
+}
+
+impl FromStr for CheckpointRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<u64>()
+                .map_err(|e| anyhow!("Invalid checkpoint '{n}': {e}"))
+        };
+        let (start, end) = match s.split_once('-') {
+            Some((start, end)) => (parse(start)?, parse(end)?),
+            None => (parse(s)?, parse(s)?),
+        };
+        if start > end {
+            bail!("Invalid checkpoint range {s}: start is after end");
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl fmt::Display for CheckpointRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+impl CheckpointReplaySummary {
+    pub fn transactions(&self) -> u64 {
+        self.matched + self.forked.len() as u64 + self.failed.len() as u64
+    }
+
+    pub fn write<W: Write>(&self, w: &mut W) -> anyhow::Result<()> {
+        writeln!(w, "\nCheckpoint replay summary")?;
+        writeln!(w, "  Checkpoints:  {}", self.checkpoints)?;
+        writeln!(w, "  Transactions: {}", self.transactions())?;
+        writeln!(w, "  Matched:      {}", self.matched)?;
+        writeln!(w, "  Forked:       {}", self.forked.len())?;
+        writeln!(w, "  Failed:       {}", self.failed.len())?;
+
+        for (checkpoint, digest, diff) in &self.forked {
+            writeln!(w, "\nTransaction {digest} (checkpoint {checkpoint}) forked")?;
+            write!(w, "{diff}")?;
+        }
+
+        for (checkpoint, digest, error) in &self.failed {
+            writeln!(
+                w,
+                "\nTransaction {digest} (checkpoint {checkpoint}) failed: {error}"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Replay all the transactions in `checkpoints`, in checkpoint order, running up to
+/// `config.concurrency` transactions of a checkpoint in parallel.
+/// Artifacts are saved under `output_root_dir/<digest>` as for single transaction replays.
+///
+/// Must be called from within a multi-threaded tokio runtime.
+pub(crate) fn replay_checkpoints<S>(
+    data_store: &S,
+    output_root_dir: &Path,
+    checkpoints: CheckpointRange,
+    config: &ReplayConfig,
+) -> anyhow::Result<CheckpointReplaySummary>
+where
+    S: ReadDataStore + CheckpointStore + StoreSummary + SetupStore + Sync,
+{
+    data_store.setup(None)?;
+    let concurrency = config
+        .concurrency
+        .unwrap_or_else(default_concurrency)
+        .max(1);
+    let mut summary = CheckpointReplaySummary::default();
+
+    for checkpoint in checkpoints.start..=checkpoints.end {
+        let digests = data_store
+            .checkpoint_transactions(checkpoint)?
+            .ok_or_else(|| anyhow!("Checkpoint {checkpoint} not found"))?;
+        ::tracing::info!(
+            "Replaying {} transactions in checkpoint {}",
+            digests.len(),
+            checkpoint
+        );
+
+        let outcomes = tokio::task::block_in_place(|| {
+            replay_in_parallel(data_store, output_root_dir, &digests, concurrency, config)
+        });
+
+        summary.checkpoints += 1;
+        let mut errors = false;
+        for (digest, outcome) in digests.into_iter().zip(outcomes) {
+            match outcome {
+                ReplayOutcome::Matched => summary.matched += 1,
+                ReplayOutcome::Forked(diff) => {
+                    // Forks are expected when replaying against overridden packages.
+                    errors |= config.package_overrides.is_empty();
+                    summary.forked.push((checkpoint, digest, diff));
+                }
+                ReplayOutcome::Failed(error) => {
+                    errors = true;
+                    summary.failed.push((checkpoint, digest, error));
+                }
+            }
+        }
+
+        if errors && config.terminate_early {
+            ::tracing::error!("Replay terminated at checkpoint {checkpoint} due to errors");
+            break;
+        }
+    }
+
+    let mut out = std::io::stdout().lock();
+    summary.write(&mut out)?;
+    if config.verbose {
+        let _ = writeln!(out, "\nData store summary:");
+        if let Err(e) = data_store.summary(&mut out) {
+            ::tracing::warn!("Failed to write data store summary: {:?}", e);
+        }
+    }
+
+    Ok(summary)
+}
+
+// Replay `digests` on up to `concurrency` threads, returning the outcomes in the same order as
+// the digests.
+fn replay_in_parallel<S>(
+    data_store: &S,
+    output_root_dir: &Path,
+    digests: &[String],
+    concurrency: usize,
+    config: &ReplayConfig,
+) -> Vec<ReplayOutcome>
+where
+    S: ReadDataStore + Sync,
+{
+    in_parallel(digests, concurrency, |digest| {
+        replay_one(data_store, output_root_dir, digest, config)
+    })
+}
+
+// Apply `f` to each of `items` on up to `concurrency` threads, returning the results in the same
+// order as the items, regardless of the order in which they finish.
+fn in_parallel<T, R, F>(items: &[T], concurrency: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    // Data stores block on their queries, which need the tokio runtime to make progress.
+    let runtime = tokio::runtime::Handle::current();
+    let next = AtomicUsize::new(0);
+
+    let mut results: Vec<(usize, R)> = std::thread::scope(|s| {
+        let workers: Vec<_> = (0..concurrency.min(items.len()))
+            .map(|_| {
+                s.spawn(|| {
+                    let _guard = runtime.enter();
+                    let mut results = vec![];
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(i) else {
+                            break;
+                        };
+                        results.push((i, f(item)));
+                    }
+                    results
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("Replay worker panicked"))
+            .collect()
+    });
+
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn replay_one<S: ReadDataStore>(
+    data_store: &S,
+    output_root_dir: &Path,
+    tx_digest: &str,
+    config: &ReplayConfig,
+) -> ReplayOutcome {
+    let tx_dir = output_root_dir.join(tx_digest);
+    let artifact_manager = match ArtifactManager::new(&tx_dir, config.overwrite_existing) {
+        Ok(manager) => manager,
+        Err(e) => return ReplayOutcome::Failed(e.to_string()),
+    };
+
+    match replay_transaction_effects(&artifact_manager, tx_digest, data_store, config.trace) {
+        Ok((expected, effects)) if expected == effects => {
+            ::tracing::info!("Successfully replayed transaction {}", tx_digest);
+            ReplayOutcome::Matched
+        }
+        Ok((expected, effects)) => {
+            ::tracing::error!(
+                "Transaction effects do not match expected effects for transaction {}",
+                tx_digest
+            );
+            if let Err(e) = save_forked_effects(&artifact_manager, &effects) {
+                ::tracing::warn!("Failed to save forked effects for {}: {:?}", tx_digest, e);
+            }
+            ReplayOutcome::Forked(changed_lines(&diff_effects(&expected, &effects)))
+        }
+        Err(e) => {
+            ::tracing::error!("Failed to replay transaction {}: {:?}", tx_digest, e);
+            ReplayOutcome::Failed(e.to_string())
+        }
+    }
+}
+
+// Keep only the lines of a `diff_effects` output that differ.
+fn changed_lines(diff: &str) -> String {
+    diff.lines()
+        .filter(|line| line.starts_with("---") || line.starts_with("+++"))
+        .fold(String::new(), |mut acc, line| {
+            acc.push_str(line);
+            acc.push('\n');
+            acc
+        })
+}
+
+fn default_concurrency() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn parse_single_checkpoint() {
+        let range: CheckpointRange = "42".parse().unwrap();
+        assert_eq!(range, CheckpointRange { start: 42, end: 42 });
+        assert_eq!(range.to_string(), "42");
+    }
+
+    #[test]
+    fn parse_checkpoint_range() {
+        let range: CheckpointRange = "10-20".parse().unwrap();
+        assert_eq!(range, CheckpointRange { start: 10, end: 20 });
+        assert_eq!(range.to_string(), "10-20");
+
+        // Whitespace around the bounds is ignored, and a range can cover a single checkpoint.
+        let range: CheckpointRange = " 7 - 7 ".parse().unwrap();
+        assert_eq!(range, CheckpointRange { start: 7, end: 7 });
+        assert_eq!(range.to_string(), "7");
+    }
+
+    #[test]
+    fn parse_invalid_checkpoint_range() {
+        assert!("".parse::<CheckpointRange>().is_err());
+        assert!("x".parse::<CheckpointRange>().is_err());
+        assert!("-5".parse::<CheckpointRange>().is_err());
+        assert!("5-".parse::<CheckpointRange>().is_err());
+        assert!("1-2-3".parse::<CheckpointRange>().is_err());
+
+        let err = "20-10".parse::<CheckpointRange>().unwrap_err();
+        assert!(err.to_string().contains("start is after end"), "{err}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn parallel_results_in_order() {
+        let items: Vec<u64> = (0..32).collect();
+
+        // Earlier items take longer, so they tend to finish after later ones.
+        let results = tokio::task::block_in_place(|| {
+            in_parallel(&items, 8, |i| {
+                std::thread::sleep(Duration::from_millis(32 - i));
+                i * 10
+            })
+        });
+
+        assert_eq!(results, items.iter().map(|i| i * 10).collect::<Vec<_>>());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn parallel_concurrency_bounds() {
+        let items: Vec<u64> = (0..4).collect();
+
+        // More threads than items, and a single thread.
+        for concurrency in [1, 16] {
+            let results =
+                tokio::task::block_in_place(|| in_parallel(&items, concurrency, |i| i + 1));
+            assert_eq!(results, vec![1, 2, 3, 4]);
+        }
+
+        let results: Vec<u64> =
+            tokio::task::block_in_place(|| in_parallel(&[] as &[u64], 4, |i| *i));
+        assert!(results.is_empty());
+    }
+}
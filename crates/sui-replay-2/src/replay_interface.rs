@@ -5,6 +5,7 @@
 //! Those stores are loosely modeled after the GQL schema in
 //! `crates/sui-indexer-alt-graphql/schema.graphql`.
 //! A `TransactionStore` is used to retrieve transaction data and effects by digest.
+//! A `CheckpointStore` is used to list the transactions in a checkpoint.
 //! An `EpochStore` is used to retrieve epoch information and protocol configuration.
 //! An `ObjectStore` is used to retrieve objects by their keys, with different query options.
 //!
@@ -49,6 +50,18 @@ pub trait TransactionStore {
     ) -> Result<Option<TransactionInfo>, anyhow::Error>;
 }
 
+/// A `CheckpointStore` lists the transactions executed in a checkpoint.
+/// It is used to replay whole checkpoints rather than individual digests.
+pub trait CheckpointStore {
+    /// Given a checkpoint sequence number, return the digests of the transactions in that
+    /// checkpoint, in execution order.
+    /// Returns `None` if the checkpoint is not found.
+    fn checkpoint_transactions(
+        &self,
+        checkpoint: u64,
+    ) -> Result<Option<Vec<String>>, anyhow::Error>;
+}
+
 /// Epoch data required to reaplay a transaction.
 #[derive(Clone, Debug)]
 pub struct EpochData {
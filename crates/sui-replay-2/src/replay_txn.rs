@@ -56,14 +56,42 @@ pub struct ReplayTransaction {
 }
 
 //
-// Run a single transaction and print results to stdout
+// Run a single transaction and print results to stdout.
+// When `allow_fork` is set, effects that do not match the expected effects are saved
+// but not reported as an error (e.g. when replaying against modified packages).
 //
 pub(crate) async fn replay_transaction<S: ReadDataStore>(
     artifact_manager: &ArtifactManager<'_>,
     tx_digest: &str,
     data_store: &S,
     trace: bool,
+    allow_fork: bool,
 ) -> anyhow::Result<()> {
+    let (expected_effects, effects) =
+        replay_transaction_effects(artifact_manager, tx_digest, data_store, trace)?;
+    if allow_fork {
+        if effects != expected_effects {
+            tracing::info!(
+                "Transaction effects differ from the on-chain effects for transaction {}",
+                tx_digest
+            );
+            save_forked_effects(artifact_manager, &effects)?;
+        }
+        return Ok(());
+    }
+    verify_txn_and_save_forked_effects(artifact_manager, &expected_effects, &effects)
+}
+
+//
+// Run a single transaction and save its artifacts, without checking the effects.
+// Returns the expected effects (as executed on chain) and the effects of the replay.
+//
+pub(crate) fn replay_transaction_effects<S: ReadDataStore>(
+    artifact_manager: &ArtifactManager<'_>,
+    tx_digest: &str,
+    data_store: &S,
+    trace: bool,
+) -> anyhow::Result<(TransactionEffects, TransactionEffects)> {
     // load a `ReplayTransaction`
     let replay_txn = match ReplayTransaction::load(tx_digest, data_store, data_store, data_store) {
         Ok(replay_txn) => replay_txn,
@@ -109,13 +137,10 @@ pub(crate) async fn replay_transaction<S: ReadDataStore>(
         .transpose()?
         .unwrap();
 
-    verify_txn_and_save_forked_effects(
-        artifact_manager,
-        &context_and_effects.expected_effects,
-        &context_and_effects.execution_effects,
-    )?;
-
-    Ok(())
+    Ok((
+        context_and_effects.expected_effects,
+        context_and_effects.execution_effects,
+    ))
 }
 
 fn verify_txn_and_save_forked_effects(
@@ -128,11 +153,7 @@ fn verify_txn_and_save_forked_effects(
             "Transaction effects do not match expected effects for transaction {}. Saving to ",
             effects.transaction_digest()
         );
-        artifact_manager
-            .member(Artifact::ForkedTransactionEffects)
-            .serialize_artifact(effects)
-            .transpose()?
-            .unwrap();
+        save_forked_effects(artifact_manager, effects)?;
         bail!(
             "Transaction effects do not match expected effects for transaction {}",
             effects.transaction_digest()
@@ -142,6 +163,19 @@ fn verify_txn_and_save_forked_effects(
     }
 }
 
+// Save the effects of a replay that do not match the expected effects.
+pub(crate) fn save_forked_effects(
+    artifact_manager: &ArtifactManager<'_>,
+    effects: &TransactionEffects,
+) -> anyhow::Result<()> {
+    artifact_manager
+        .member(Artifact::ForkedTransactionEffects)
+        .serialize_artifact(effects)
+        .transpose()?
+        .unwrap();
+    Ok(())
+}
+
 impl ReplayTransaction {
     // Load a transaction and builds a `ReplayTransaction` instance.
     pub fn load(
@@ -222,14 +256,47 @@ fn load_transaction_objects(
     effects: &TransactionEffects,
     checkpoint: u64,
     object_store: &dyn ObjectStore,
+) -> Result<BTreeMap<ObjectID, BTreeMap<ObjectVersion, Object>>, anyhow::Error> {
+    // get the ids and versions of the input objects to load
+    let object_keys = get_txn_object_keys(txn_data, effects)?;
+    load_objects_and_packages(txn_data, &object_keys, checkpoint, object_store)
+}
+
+// Load the objects and packages used by a transaction that was not executed on chain
+// (e.g. a dry run), so it has no effects to find the versions of shared objects in.
+// Shared objects are loaded as they were at `checkpoint` instead.
+pub(crate) fn load_new_transaction_objects(
+    txn_data: &TransactionData,
+    checkpoint: u64,
+    object_store: &dyn ObjectStore,
+) -> Result<BTreeMap<ObjectID, BTreeMap<ObjectVersion, Object>>, anyhow::Error> {
+    let mut object_keys = get_input_ids(txn_data)?;
+    if let TransactionKind::ProgrammableTransaction(ptb) = txn_data.kind() {
+        object_keys.extend(ptb.inputs.iter().filter_map(|input| match input {
+            CallArg::Object(ObjectArg::SharedObject { id, .. }) => Some(ObjectKey {
+                object_id: *id,
+                version_query: VersionQuery::AtCheckpoint(checkpoint),
+            }),
+            _ => None,
+        }));
+    }
+    let object_keys = object_keys.into_iter().collect::<Vec<_>>();
+    load_objects_and_packages(txn_data, &object_keys, checkpoint, object_store)
+}
+
+// Load the given objects, and the packages used by the transaction and by the types
+// of the objects.
+fn load_objects_and_packages(
+    txn_data: &TransactionData,
+    object_keys: &[ObjectKey],
+    checkpoint: u64,
+    object_store: &dyn ObjectStore,
 ) -> Result<BTreeMap<ObjectID, BTreeMap<ObjectVersion, Object>>, anyhow::Error> {
     // collect all package ids required by the transaction
     let mut packages = get_packages(txn_data)?;
 
-    // get the ids and versions of the input objects to load
     // load the objects and collect the package ids of the type parameters
-    let object_keys = get_txn_object_keys(txn_data, effects)?;
-    let (mut object_cache, tp_pkgs) = load_objects(&object_keys, object_store)?;
+    let (mut object_cache, tp_pkgs) = load_objects(object_keys, object_store)?;
     packages.extend(&tp_pkgs);
 
     // load the packages and add them to the object cache
@@ -10,6 +10,7 @@ use prost_types::FieldMask;
 use sui_rpc::field::FieldMaskUtil;
 use sui_rpc::proto::sui::rpc::v2beta2 as proto;
 use sui_rpc::proto::sui::rpc::v2beta2::ledger_service_client::LedgerServiceClient;
+use sui_rpc::proto::sui::rpc::v2beta2::live_data_service_client::LiveDataServiceClient;
 use sui_rpc::proto::sui::rpc::v2beta2::transaction_execution_service_client::TransactionExecutionServiceClient;
 use sui_rpc::proto::TryFromProtoError;
 use sui_types::base_types::{ObjectID, SequenceNumber};
@@ -17,7 +18,9 @@ use sui_types::effects::{TransactionEffects, TransactionEvents};
 use sui_types::full_checkpoint_content::CheckpointData;
 use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber};
 use sui_types::object::Object;
-use sui_types::transaction::Transaction;
+use sui_types::transaction::{Argument, Transaction, TransactionData};
+use sui_types::transaction_executor::TransactionChecks;
+use sui_types::TypeTag;
 
 pub use sui_rpc::client::ResponseExt;
 
@@ -85,6 +88,14 @@ impl Client {
         TransactionExecutionServiceClient::with_interceptor(self.channel.clone(), self.auth.clone())
     }
 
+    pub fn live_data_client(
+        &self,
+    ) -> LiveDataServiceClient<
+        tonic::service::interceptor::InterceptedService<tonic::transport::Channel, AuthInterceptor>,
+    > {
+        LiveDataServiceClient::with_interceptor(self.channel.clone(), self.auth.clone())
+    }
+
     pub async fn get_latest_checkpoint(&self) -> Result<CertifiedCheckpointSummary> {
         self.get_checkpoint_internal(None).await
     }
@@ -229,6 +240,46 @@ impl Client {
         execute_transaction_response_try_from_proto(&response)
             .map_err(|e| status_from_error_with_metadata(e, metadata))
     }
+
+    /// Simulate executing `transaction` on top of the full node's latest state, without
+    /// committing its effects. With `checks` enabled, the transaction must be valid (e.g. have a
+    /// gas payment and budget) to be simulated. With them disabled, it is simulated like a dev
+    /// inspect: it can be sent from any address, and a gas coin is mocked if it has no gas
+    /// payment.
+    pub async fn simulate_transaction(
+        &self,
+        transaction: &TransactionData,
+        checks: TransactionChecks,
+    ) -> Result<TransactionSimulationResponse> {
+        let request = proto::SimulateTransactionRequest {
+            transaction: Some(proto::Transaction {
+                bcs: Some(
+                    proto::Bcs::serialize(transaction).map_err(|e| Status::from_error(e.into()))?,
+                ),
+                ..Default::default()
+            }),
+            read_mask: FieldMask::from_paths([
+                "transaction.effects.bcs",
+                "transaction.events.bcs",
+                "transaction.balance_changes",
+                "outputs",
+            ])
+            .pipe(Some),
+            checks: Some(
+                proto::simulate_transaction_request::TransactionChecks::from(checks).into(),
+            ),
+            ..Default::default()
+        };
+
+        let (metadata, response, _extentions) = self
+            .live_data_client()
+            .simulate_transaction(request)
+            .await?
+            .into_parts();
+
+        simulate_transaction_response_try_from_proto(&response)
+            .map_err(|e| status_from_error_with_metadata(e, metadata))
+    }
 }
 
 #[derive(Debug)]
@@ -240,6 +291,35 @@ pub struct TransactionExecutionResponse {
     pub balance_changes: Vec<sui_sdk_types::BalanceChange>,
 }
 
+#[derive(Debug)]
+pub struct TransactionSimulationResponse {
+    pub effects: TransactionEffects,
+    pub events: Option<TransactionEvents>,
+    pub balance_changes: Vec<sui_sdk_types::BalanceChange>,
+
+    /// The results of each command in the transaction, if it is a programmable transaction that
+    /// executed successfully.
+    pub outputs: Vec<CommandResult>,
+}
+
+#[derive(Debug)]
+pub struct CommandResult {
+    /// Values returned by the command.
+    pub return_values: Vec<CommandOutput>,
+
+    /// Values of the command's arguments that were passed by mutable reference, after the
+    /// command ran.
+    pub mutated_by_ref: Vec<CommandOutput>,
+}
+
+#[derive(Debug)]
+pub struct CommandOutput {
+    /// The argument that this value was written back to, for mutable reference arguments.
+    pub argument: Option<Argument>,
+    pub type_: TypeTag,
+    pub bcs: Vec<u8>,
+}
+
 /// Attempts to parse `CertifiedCheckpointSummary` from a proto::Checkpoint
 #[allow(clippy::result_large_err)]
 fn certified_checkpoint_summary_try_from_proto(
@@ -409,6 +489,125 @@ fn execute_transaction_response_try_from_proto(
     .pipe(Ok)
 }
 
+/// Attempts to parse `TransactionSimulationResponse` from the fields in `SimulateTransactionResponse`
+#[allow(clippy::result_large_err)]
+fn simulate_transaction_response_try_from_proto(
+    response: &proto::SimulateTransactionResponse,
+) -> Result<TransactionSimulationResponse, TryFromProtoError> {
+    let executed_transaction = response
+        .transaction
+        .as_ref()
+        .ok_or_else(|| TryFromProtoError::missing("transaction"))?;
+
+    let effects = executed_transaction
+        .effects
+        .as_ref()
+        .and_then(|effects| effects.bcs.as_ref())
+        .ok_or_else(|| TryFromProtoError::missing("effects_bcs"))?
+        .deserialize()
+        .map_err(|e| TryFromProtoError::invalid("effects.bcs", e))?;
+    let events = executed_transaction
+        .events
+        .as_ref()
+        .and_then(|events| events.bcs.as_ref())
+        .map(|bcs| bcs.deserialize())
+        .transpose()
+        .map_err(|e| TryFromProtoError::invalid("events.bcs", e))?;
+
+    let balance_changes = executed_transaction
+        .balance_changes
+        .iter()
+        .map(TryInto::try_into)
+        .collect::<Result<_, _>>()?;
+
+    let outputs = response
+        .outputs
+        .iter()
+        .map(|result| {
+            Ok(CommandResult {
+                return_values: result
+                    .return_values
+                    .iter()
+                    .map(command_output_try_from_proto)
+                    .collect::<Result<_, _>>()?,
+                mutated_by_ref: result
+                    .mutated_by_ref
+                    .iter()
+                    .map(command_output_try_from_proto)
+                    .collect::<Result<_, _>>()?,
+            })
+        })
+        .collect::<Result<_, TryFromProtoError>>()?;
+
+    TransactionSimulationResponse {
+        effects,
+        events,
+        balance_changes,
+        outputs,
+    }
+    .pipe(Ok)
+}
+
+/// Attempts to parse `CommandOutput` from a proto::CommandOutput
+#[allow(clippy::result_large_err)]
+fn command_output_try_from_proto(
+    output: &proto::CommandOutput,
+) -> Result<CommandOutput, TryFromProtoError> {
+    let argument = output
+        .argument
+        .as_ref()
+        .map(argument_try_from_proto)
+        .transpose()?;
+
+    let value = output
+        .value
+        .as_ref()
+        .ok_or_else(|| TryFromProtoError::missing("value"))?;
+
+    let type_ = value
+        .name
+        .as_deref()
+        .ok_or_else(|| TryFromProtoError::missing("value.name"))?
+        .parse::<TypeTag>()
+        .map_err(|e| TryFromProtoError::invalid("value.name", e))?;
+
+    let bcs = value
+        .value
+        .as_ref()
+        .ok_or_else(|| TryFromProtoError::missing("value.value"))?
+        .to_vec();
+
+    Ok(CommandOutput {
+        argument,
+        type_,
+        bcs,
+    })
+}
+
+/// Attempts to parse `Argument` from a proto::Argument
+#[allow(clippy::result_large_err)]
+fn argument_try_from_proto(argument: &proto::Argument) -> Result<Argument, TryFromProtoError> {
+    use proto::argument::ArgumentKind;
+
+    let index = |field: &'static str, ix: Option<u32>| {
+        let ix = ix.ok_or_else(|| TryFromProtoError::missing(field))?;
+        u16::try_from(ix).map_err(|e| TryFromProtoError::invalid(field, e))
+    };
+
+    Ok(match argument.kind() {
+        ArgumentKind::Gas => Argument::GasCoin,
+        ArgumentKind::Input => Argument::Input(index("input", argument.input)?),
+        ArgumentKind::Result => {
+            let result = index("result", argument.result)?;
+            match argument.subresult {
+                None => Argument::Result(result),
+                Some(_) => Argument::NestedResult(result, index("subresult", argument.subresult)?),
+            }
+        }
+        _ => return Err(TryFromProtoError::missing("kind")),
+    })
+}
+
 fn status_from_error_with_metadata<T: Into<BoxError>>(err: T, metadata: MetadataMap) -> Status {
     let mut status = Status::from_error(err.into());
     *status.metadata_mut() = metadata;
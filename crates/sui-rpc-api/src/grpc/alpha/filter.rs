@@ -0,0 +1,475 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ErrorReason;
+use crate::RpcError;
+use move_core_types::language_storage::StructTag;
+use sui_rpc::proto::google::rpc::bad_request::FieldViolation;
+use sui_types::base_types::SuiAddress;
+use sui_types::event::Event;
+use sui_types::full_checkpoint_content::CheckpointTransaction;
+use sui_types::object::Object;
+use sui_types::object::Owner;
+use sui_types::transaction::TransactionDataAPI;
+use sui_types::{parse_sui_address, parse_sui_fq_name, parse_sui_module_id, parse_sui_struct_tag};
+
+use super::proto;
+
+/// Parsed form of a `sui.rpc.alpha.TransactionFilter`.
+#[derive(Debug, Default)]
+pub(crate) struct TransactionFilter {
+    sender: Option<SuiAddress>,
+    affected_address: Option<SuiAddress>,
+    move_call: Option<FunctionFilter>,
+    event_type: Option<TypeFilter>,
+}
+
+/// Parsed form of a `sui.rpc.alpha.EventFilter`.
+#[derive(Debug, Default)]
+pub(crate) struct EventFilter {
+    sender: Option<SuiAddress>,
+    event_type: Option<TypeFilter>,
+    emitting_module: Option<FunctionFilter>,
+}
+
+/// A filter on Move types that could be just a package, a module, an uninstantiated type, or a
+/// fully qualified type with generics.
+#[derive(Debug, PartialEq, Eq)]
+enum TypeFilter {
+    Package(SuiAddress),
+    Module(SuiAddress, String),
+    Type(StructTag),
+}
+
+/// A filter on Move functions that could be just a package, a module, or a fully qualified
+/// function name.
+#[derive(Debug, PartialEq, Eq)]
+enum FunctionFilter {
+    Package(SuiAddress),
+    Module(SuiAddress, String),
+    Function(SuiAddress, String, String),
+}
+
+impl TransactionFilter {
+    pub(crate) fn from_proto(filter: Option<&proto::TransactionFilter>) -> Result<Self, RpcError> {
+        let Some(filter) = filter else {
+            return Ok(Self::default());
+        };
+
+        Ok(Self {
+            sender: filter
+                .sender
+                .as_deref()
+                .map(|s| parse_address("filter.sender", s))
+                .transpose()?,
+            affected_address: filter
+                .affected_address
+                .as_deref()
+                .map(|s| parse_address("filter.affected_address", s))
+                .transpose()?,
+            move_call: filter
+                .move_call
+                .as_deref()
+                .map(|s| FunctionFilter::parse("filter.move_call", s))
+                .transpose()?,
+            event_type: filter
+                .event_type
+                .as_deref()
+                .map(|s| TypeFilter::parse("filter.event_type", s))
+                .transpose()?,
+        })
+    }
+
+    pub(crate) fn matches(&self, transaction: &CheckpointTransaction) -> bool {
+        let data = transaction.transaction.transaction_data();
+        let sender = data.sender();
+
+        if self.sender.is_some_and(|s| s != sender) {
+            return false;
+        }
+
+        if let Some(address) = self.affected_address {
+            let owned_by = |obj: &Object| match &obj.owner {
+                Owner::AddressOwner(owner) | Owner::ConsensusAddressOwner { owner, .. } => {
+                    *owner == address
+                }
+                Owner::ObjectOwner(_) | Owner::Shared { .. } | Owner::Immutable => false,
+            };
+
+            if sender != address
+                && !transaction.input_objects.iter().any(owned_by)
+                && !transaction.output_objects.iter().any(owned_by)
+            {
+                return false;
+            }
+        }
+
+        if let Some(filter) = &self.move_call {
+            let calls = data.move_calls();
+            if !calls.into_iter().any(|(package, module, function)| {
+                filter.matches((*package).into(), module, Some(function))
+            }) {
+                return false;
+            }
+        }
+
+        if let Some(filter) = &self.event_type {
+            let mut events = transaction.events.iter().flat_map(|e| &e.data);
+            if !events.any(|e| filter.matches(&e.type_)) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl EventFilter {
+    pub(crate) fn from_proto(filter: Option<&proto::EventFilter>) -> Result<Self, RpcError> {
+        let Some(filter) = filter else {
+            return Ok(Self::default());
+        };
+
+        let emitting_module = filter
+            .emitting_module
+            .as_deref()
+            .map(|s| FunctionFilter::parse("filter.emitting_module", s))
+            .transpose()?;
+
+        if let Some(FunctionFilter::Function(..)) = emitting_module {
+            return Err(FieldViolation::new("filter.emitting_module")
+                .with_description("expected package[::module]")
+                .with_reason(ErrorReason::FieldInvalid)
+                .into());
+        }
+
+        Ok(Self {
+            sender: filter
+                .sender
+                .as_deref()
+                .map(|s| parse_address("filter.sender", s))
+                .transpose()?,
+            event_type: filter
+                .event_type
+                .as_deref()
+                .map(|s| TypeFilter::parse("filter.event_type", s))
+                .transpose()?,
+            emitting_module,
+        })
+    }
+
+    pub(crate) fn matches(&self, event: &Event) -> bool {
+        self.sender.is_none_or(|s| s == event.sender)
+            && self
+                .event_type
+                .as_ref()
+                .is_none_or(|f| f.matches(&event.type_))
+            && self.emitting_module.as_ref().is_none_or(|f| {
+                f.matches(
+                    event.package_id.into(),
+                    event.transaction_module.as_str(),
+                    None,
+                )
+            })
+    }
+}
+
+impl TypeFilter {
+    fn parse(field: &str, s: &str) -> Result<Self, RpcError> {
+        if let Ok(tag) = parse_sui_struct_tag(s) {
+            Ok(TypeFilter::Type(tag))
+        } else if let Ok(module) = parse_sui_module_id(s) {
+            Ok(TypeFilter::Module(
+                SuiAddress::from(*module.address()),
+                module.name().to_string(),
+            ))
+        } else if let Ok(package) = parse_sui_address(s) {
+            Ok(TypeFilter::Package(package))
+        } else {
+            Err(FieldViolation::new(field)
+                .with_description("expected package[::module[::name[<type, ...>]]]")
+                .with_reason(ErrorReason::FieldInvalid)
+                .into())
+        }
+    }
+
+    /// A type filter without type parameters matches all instantiations of the type.
+    fn matches(&self, tag: &StructTag) -> bool {
+        match self {
+            TypeFilter::Package(package) => SuiAddress::from(tag.address) == *package,
+            TypeFilter::Module(package, module) => {
+                SuiAddress::from(tag.address) == *package && tag.module.as_str() == module
+            }
+            TypeFilter::Type(filter) if filter.type_params.is_empty() => {
+                filter.address == tag.address
+                    && filter.module == tag.module
+                    && filter.name == tag.name
+            }
+            TypeFilter::Type(filter) => filter == tag,
+        }
+    }
+}
+
+impl FunctionFilter {
+    fn parse(field: &str, s: &str) -> Result<Self, RpcError> {
+        if let Ok((module, function)) = parse_sui_fq_name(s) {
+            Ok(FunctionFilter::Function(
+                SuiAddress::from(*module.address()),
+                module.name().to_string(),
+                function,
+            ))
+        } else if let Ok(module) = parse_sui_module_id(s) {
+            Ok(FunctionFilter::Module(
+                SuiAddress::from(*module.address()),
+                module.name().to_string(),
+            ))
+        } else if let Ok(package) = parse_sui_address(s) {
+            Ok(FunctionFilter::Package(package))
+        } else {
+            Err(FieldViolation::new(field)
+                .with_description("expected package[::module[::function]]")
+                .with_reason(ErrorReason::FieldInvalid)
+                .into())
+        }
+    }
+
+    /// If `function` is `None`, only the package and module are checked, and filters on a specific
+    /// function never match.
+    fn matches(&self, package: SuiAddress, module: &str, function: Option<&str>) -> bool {
+        match self {
+            FunctionFilter::Package(p) => *p == package,
+            FunctionFilter::Module(p, m) => *p == package && m == module,
+            FunctionFilter::Function(p, m, f) => {
+                *p == package && m == module && function.is_some_and(|function| f == function)
+            }
+        }
+    }
+}
+
+fn parse_address(field: &str, s: &str) -> Result<SuiAddress, RpcError> {
+    parse_sui_address(s).map_err(|e| {
+        FieldViolation::new(field)
+            .with_description(format!("invalid address: {e}"))
+            .with_reason(ErrorReason::FieldInvalid)
+            .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::ObjectID;
+    use sui_types::full_checkpoint_content::CheckpointData;
+    use sui_types::test_checkpoint_data_builder::TestCheckpointDataBuilder;
+    use sui_types::Identifier;
+
+    fn event(package: &str, module: &str, type_: &str) -> Event {
+        Event {
+            package_id: ObjectID::from(parse_sui_address(package).unwrap()),
+            transaction_module: Identifier::new(module).unwrap(),
+            sender: SuiAddress::ZERO,
+            type_: parse_sui_struct_tag(type_).unwrap(),
+            contents: vec![],
+        }
+    }
+
+    /// A checkpoint with three transactions:
+    ///
+    /// - Address 0 creates object 0.
+    /// - Address 1 calls `0x2::coin::split`, emitting a `0x2::coin::CoinEvent<0x2::sui::SUI>`.
+    /// - Address 0 transfers object 0 to address 3.
+    fn checkpoint() -> CheckpointData {
+        TestCheckpointDataBuilder::new(0)
+            .start_transaction(0)
+            .create_owned_object(0)
+            .finish_transaction()
+            .start_transaction(1)
+            .add_move_call(ObjectID::from_hex_literal("0x2").unwrap(), "coin", "split")
+            .with_events(vec![event(
+                "0x2",
+                "coin",
+                "0x2::coin::CoinEvent<0x2::sui::SUI>",
+            )])
+            .finish_transaction()
+            .start_transaction(0)
+            .transfer_object(0, 3)
+            .finish_transaction()
+            .build_checkpoint()
+    }
+
+    /// Which of the transactions in [checkpoint] match `filter`.
+    fn transactions_matching(filter: proto::TransactionFilter) -> Vec<bool> {
+        let filter = TransactionFilter::from_proto(Some(&filter)).unwrap();
+        checkpoint()
+            .transactions
+            .iter()
+            .map(|tx| filter.matches(tx))
+            .collect()
+    }
+
+    fn address(idx: u8) -> Option<String> {
+        Some(TestCheckpointDataBuilder::derive_address(idx).to_string())
+    }
+
+    #[test]
+    fn test_transaction_sender_filter() {
+        let matches = transactions_matching(proto::TransactionFilter {
+            sender: address(1),
+            ..Default::default()
+        });
+
+        assert_eq!(matches, vec![false, true, false]);
+    }
+
+    #[test]
+    fn test_transaction_affected_address_filter() {
+        // The sender of a transaction is affected by it.
+        let matches = transactions_matching(proto::TransactionFilter {
+            affected_address: address(0),
+            ..Default::default()
+        });
+
+        assert_eq!(matches, vec![true, false, true]);
+
+        // So is the recipient of an object.
+        let matches = transactions_matching(proto::TransactionFilter {
+            affected_address: address(3),
+            ..Default::default()
+        });
+
+        assert_eq!(matches, vec![false, false, true]);
+    }
+
+    #[test]
+    fn test_transaction_move_call_filter() {
+        for (filter, expect) in [
+            ("0x2", vec![false, true, false]),
+            ("0x2::coin", vec![false, true, false]),
+            ("0x2::coin::split", vec![false, true, false]),
+            ("0x2::coin::join", vec![false, false, false]),
+            ("0x2::pay", vec![false, false, false]),
+        ] {
+            let matches = transactions_matching(proto::TransactionFilter {
+                move_call: Some(filter.to_owned()),
+                ..Default::default()
+            });
+
+            assert_eq!(matches, expect, "{filter}");
+        }
+    }
+
+    #[test]
+    fn test_transaction_event_type_filter() {
+        for (filter, expect) in [
+            ("0x2::coin::CoinEvent", vec![false, true, false]),
+            (
+                "0x2::coin::CoinEvent<0x3::sui::SUI>",
+                vec![false, false, false],
+            ),
+        ] {
+            let matches = transactions_matching(proto::TransactionFilter {
+                event_type: Some(filter.to_owned()),
+                ..Default::default()
+            });
+
+            assert_eq!(matches, expect, "{filter}");
+        }
+    }
+
+    #[test]
+    fn test_transaction_filters_combine() {
+        let matches = transactions_matching(proto::TransactionFilter {
+            sender: address(0),
+            affected_address: address(3),
+            ..Default::default()
+        });
+
+        assert_eq!(matches, vec![false, false, true]);
+
+        let matches = transactions_matching(proto::TransactionFilter {
+            sender: address(0),
+            move_call: Some("0x2::coin".to_owned()),
+            ..Default::default()
+        });
+
+        assert_eq!(matches, vec![false, false, false]);
+    }
+
+    #[test]
+    fn test_parse_type_filter() {
+        assert_eq!(
+            TypeFilter::parse("f", "0x2").unwrap(),
+            TypeFilter::Package(parse_sui_address("0x2").unwrap()),
+        );
+
+        assert_eq!(
+            TypeFilter::parse("f", "0x2::coin").unwrap(),
+            TypeFilter::Module(parse_sui_address("0x2").unwrap(), "coin".to_owned()),
+        );
+
+        assert_eq!(
+            TypeFilter::parse("f", "0x2::coin::Coin<0x2::sui::SUI>").unwrap(),
+            TypeFilter::Type(parse_sui_struct_tag("0x2::coin::Coin<0x2::sui::SUI>").unwrap()),
+        );
+
+        TypeFilter::parse("f", "not a type").unwrap_err();
+    }
+
+    #[test]
+    fn test_parse_function_filter() {
+        assert_eq!(
+            FunctionFilter::parse("f", "0x2::coin::split").unwrap(),
+            FunctionFilter::Function(
+                parse_sui_address("0x2").unwrap(),
+                "coin".to_owned(),
+                "split".to_owned(),
+            ),
+        );
+
+        FunctionFilter::parse("f", "0x2::coin::split<u64>").unwrap_err();
+    }
+
+    #[test]
+    fn test_event_type_filter() {
+        let coin = event("0x2", "pay", "0x2::coin::CoinEvent<0x2::sui::SUI>");
+
+        for (filter, expect) in [
+            ("0x2", true),
+            ("0x3", false),
+            ("0x2::coin", true),
+            ("0x2::pay", false),
+            ("0x2::coin::CoinEvent", true),
+            ("0x2::coin::CoinEvent<0x2::sui::SUI>", true),
+            ("0x2::coin::CoinEvent<0x3::sui::SUI>", false),
+        ] {
+            let filter = EventFilter::from_proto(Some(&proto::EventFilter {
+                event_type: Some(filter.to_owned()),
+                ..Default::default()
+            }))
+            .unwrap();
+
+            assert_eq!(filter.matches(&coin), expect, "{filter:?}");
+        }
+    }
+
+    #[test]
+    fn test_event_emitting_module_filter() {
+        let coin = event("0x2", "pay", "0x2::coin::CoinEvent<0x2::sui::SUI>");
+
+        for (filter, expect) in [("0x2", true), ("0x2::pay", true), ("0x2::coin", false)] {
+            let filter = EventFilter::from_proto(Some(&proto::EventFilter {
+                emitting_module: Some(filter.to_owned()),
+                ..Default::default()
+            }))
+            .unwrap();
+
+            assert_eq!(filter.matches(&coin), expect, "{filter:?}");
+        }
+
+        EventFilter::from_proto(Some(&proto::EventFilter {
+            emitting_module: Some("0x2::pay::split".to_owned()),
+            ..Default::default()
+        }))
+        .unwrap_err();
+    }
+}
@@ -0,0 +1,374 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::error::CheckpointNotFoundError;
+use crate::ErrorReason;
+use crate::RpcError;
+use crate::RpcService;
+use prost_types::FieldMask;
+use sui_rpc::field::FieldMaskTree;
+use sui_rpc::field::FieldMaskUtil;
+use sui_rpc::merge::Merge;
+use sui_rpc::proto::google::rpc::bad_request::FieldViolation;
+use sui_rpc::proto::sui::rpc::v2beta2::Event;
+use sui_rpc::proto::sui::rpc::v2beta2::ExecutedTransaction;
+use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio_stream::Stream;
+use tokio_stream::StreamExt;
+
+use super::filter::EventFilter;
+use super::filter::TransactionFilter;
+use super::proto::subscription_service_server::SubscriptionService;
+use super::proto::Cursor;
+use super::proto::SubscribeEventsRequest;
+use super::proto::SubscribeEventsResponse;
+use super::proto::SubscribeTransactionsRequest;
+use super::proto::SubscribeTransactionsResponse;
+
+pub const TRANSACTION_READ_MASK_DEFAULT: &str = "digest";
+pub const EVENT_READ_MASK_DEFAULT: &str = "package_id,module,sender,event_type";
+
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send>>;
+
+#[tonic::async_trait]
+impl SubscriptionService for RpcService {
+    type SubscribeTransactionsStream = ResponseStream<SubscribeTransactionsResponse>;
+    type SubscribeEventsStream = ResponseStream<SubscribeEventsResponse>;
+
+    async fn subscribe_transactions(
+        &self,
+        request: tonic::Request<SubscribeTransactionsRequest>,
+    ) -> Result<tonic::Response<Self::SubscribeTransactionsStream>, tonic::Status> {
+        subscribe_transactions(self, request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(Into::into)
+    }
+
+    async fn subscribe_events(
+        &self,
+        request: tonic::Request<SubscribeEventsRequest>,
+    ) -> Result<tonic::Response<Self::SubscribeEventsStream>, tonic::Status> {
+        subscribe_events(self, request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(Into::into)
+    }
+}
+
+#[tracing::instrument(skip(service))]
+async fn subscribe_transactions(
+    service: &RpcService,
+    request: SubscribeTransactionsRequest,
+) -> Result<ResponseStream<SubscribeTransactionsResponse>, RpcError> {
+    let filter = TransactionFilter::from_proto(request.filter.as_ref())?;
+    let read_mask = {
+        let read_mask = request
+            .read_mask
+            .unwrap_or_else(|| FieldMask::from_str(TRANSACTION_READ_MASK_DEFAULT));
+        read_mask
+            .validate::<ExecutedTransaction>()
+            .map_err(|path| {
+                FieldViolation::new("read_mask")
+                    .with_description(format!("invalid read_mask path: {path}"))
+                    .with_reason(ErrorReason::FieldInvalid)
+            })?;
+        FieldMaskTree::from(read_mask)
+    };
+
+    // A cursor without a transaction index refers to the whole checkpoint.
+    let after = request
+        .after
+        .map(|c| (c.checkpoint(), c.transaction.unwrap_or(u64::MAX)));
+
+    let mut checkpoints = Box::pin(checkpoints(service, request.after.as_ref()).await?);
+
+    Ok(Box::pin(async_stream::stream! {
+        while let Some(checkpoint) = checkpoints.next().await {
+            let checkpoint = match checkpoint {
+                Ok(checkpoint) => checkpoint,
+                Err(e) => {
+                    yield Err(e.into());
+                    break;
+                }
+            };
+
+            let sequence_number = checkpoint.checkpoint_summary.sequence_number;
+            let timestamp_ms = checkpoint.checkpoint_summary.timestamp_ms;
+
+            for (index, transaction) in checkpoint.transactions.iter().enumerate() {
+                let index = index as u64;
+                if after.is_some_and(|after| (sequence_number, index) <= after)
+                    || !filter.matches(transaction)
+                {
+                    continue;
+                }
+
+                let mut message = ExecutedTransaction::merge_from(transaction.clone(), &read_mask);
+                message.checkpoint = read_mask
+                    .contains(ExecutedTransaction::CHECKPOINT_FIELD)
+                    .then_some(sequence_number);
+                message.timestamp = read_mask
+                    .contains(ExecutedTransaction::TIMESTAMP_FIELD)
+                    .then(|| sui_rpc::proto::timestamp_ms_to_proto(timestamp_ms));
+
+                yield Ok(SubscribeTransactionsResponse {
+                    cursor: Some(Cursor {
+                        checkpoint: Some(sequence_number),
+                        transaction: Some(index),
+                        event: None,
+                    }),
+                    transaction: Some(message),
+                });
+            }
+        }
+    }))
+}
+
+#[tracing::instrument(skip(service))]
+async fn subscribe_events(
+    service: &RpcService,
+    request: SubscribeEventsRequest,
+) -> Result<ResponseStream<SubscribeEventsResponse>, RpcError> {
+    let filter = EventFilter::from_proto(request.filter.as_ref())?;
+    let read_mask = {
+        let read_mask = request
+            .read_mask
+            .unwrap_or_else(|| FieldMask::from_str(EVENT_READ_MASK_DEFAULT));
+        read_mask.validate::<Event>().map_err(|path| {
+            FieldViolation::new("read_mask")
+                .with_description(format!("invalid read_mask path: {path}"))
+                .with_reason(ErrorReason::FieldInvalid)
+        })?;
+        FieldMaskTree::from(read_mask)
+    };
+
+    // A cursor without a transaction or event index refers to the whole checkpoint or
+    // transaction.
+    let after = request.after.map(|c| {
+        (
+            c.checkpoint(),
+            c.transaction.unwrap_or(u64::MAX),
+            c.event.unwrap_or(u64::MAX),
+        )
+    });
+
+    let mut checkpoints = Box::pin(checkpoints(service, request.after.as_ref()).await?);
+    let service = service.clone();
+
+    Ok(Box::pin(async_stream::stream! {
+        while let Some(checkpoint) = checkpoints.next().await {
+            let checkpoint = match checkpoint {
+                Ok(checkpoint) => checkpoint,
+                Err(e) => {
+                    yield Err(e.into());
+                    break;
+                }
+            };
+
+            let sequence_number = checkpoint.checkpoint_summary.sequence_number;
+
+            for (tx_index, transaction) in checkpoint.transactions.iter().enumerate() {
+                let Some(events) = &transaction.events else {
+                    continue;
+                };
+
+                let tx_index = tx_index as u64;
+                let digest = transaction.transaction.digest().to_string();
+
+                let sdk_events = match sui_sdk_types::TransactionEvents::try_from(events.clone()) {
+                    Ok(events) => events,
+                    Err(e) => {
+                        yield Err(RpcError::from(anyhow::Error::from(e)).into());
+                        return;
+                    }
+                };
+
+                let events = events.data.iter().zip(sdk_events.0);
+                for (ev_index, (event, sdk_event)) in events.enumerate() {
+                    let ev_index = ev_index as u64;
+                    if after.is_some_and(|after| (sequence_number, tx_index, ev_index) <= after)
+                        || !filter.matches(event)
+                    {
+                        continue;
+                    }
+
+                    let mut message = Event::merge_from(sdk_event, &read_mask);
+                    if read_mask.contains(Event::JSON_FIELD.name) {
+                        message.json = crate::grpc::v2beta2::render_json(
+                            &service,
+                            &event.type_,
+                            &event.contents,
+                        )
+                        .map(Box::new);
+                    }
+
+                    yield Ok(SubscribeEventsResponse {
+                        cursor: Some(Cursor {
+                            checkpoint: Some(sequence_number),
+                            transaction: Some(tx_index),
+                            event: Some(ev_index),
+                        }),
+                        transaction_digest: Some(digest.clone()),
+                        event: Some(message),
+                    });
+                }
+            }
+        }
+    }))
+}
+
+/// Stream checkpoints, starting from the checkpoint in `after`, if it is provided, or the next
+/// checkpoint to be executed, otherwise.
+///
+/// The subscription is registered before any historical checkpoints are read, so that there is no
+/// gap between the checkpoints that are replayed from the store, and the ones that are received
+/// live. The stream ends if the subscriber falls too far behind the tip of the chain, at which
+/// point the client is expected to reconnect, resuming from its last cursor.
+///
+/// Resuming from a checkpoint that is more than the configured maximum catch-up behind the latest
+/// checkpoint is rejected, to bound the amount of history a single subscription replays.
+async fn checkpoints(
+    service: &RpcService,
+    after: Option<&Cursor>,
+) -> Result<impl Stream<Item = Result<Arc<CheckpointData>, RpcError>> + Send + 'static, RpcError> {
+    let Some(handle) = &service.subscription_service_handle else {
+        return Err(RpcError::new(
+            tonic::Code::Unimplemented,
+            "subscriptions are not enabled on this node",
+        ));
+    };
+
+    let start = after
+        .map(|after| {
+            after.checkpoint.ok_or_else(|| {
+                FieldViolation::new("after.checkpoint")
+                    .with_description("missing checkpoint")
+                    .with_reason(ErrorReason::FieldMissing)
+            })
+        })
+        .transpose()?;
+
+    let Some(mut receiver) = handle.register_subscription().await else {
+        return Err(RpcError::new(
+            tonic::Code::Unavailable,
+            "too many existing subscriptions",
+        ));
+    };
+
+    if let Some(start) = start {
+        // Checkpoint data includes objects, so it is only available if neither checkpoints nor
+        // objects have been pruned.
+        let reader = service.reader.inner();
+        let lowest_available = reader
+            .get_lowest_available_checkpoint()?
+            .max(reader.get_lowest_available_checkpoint_objects()?);
+
+        if start < lowest_available {
+            return Err(RpcError::new(
+                tonic::Code::OutOfRange,
+                format!(
+                    "checkpoint {start} has been pruned, lowest available checkpoint is \
+                     {lowest_available}"
+                ),
+            ));
+        }
+
+        let latest = *reader.get_latest_checkpoint()?.sequence_number();
+        let max_catch_up = service.config.max_subscription_catch_up();
+        if latest.saturating_sub(start) > max_catch_up {
+            return Err(RpcError::new(
+                tonic::Code::OutOfRange,
+                format!(
+                    "checkpoint {start} is more than {max_catch_up} checkpoints behind the latest \
+                     checkpoint {latest}"
+                ),
+            ));
+        }
+    }
+
+    let service = service.clone();
+    Ok(async_stream::stream! {
+        let mut next = start;
+
+        // Catch up to the tip of the chain from the store.
+        if let Some(next) = &mut next {
+            loop {
+                match checkpoint_data(&service, *next).await {
+                    Ok(Some(checkpoint)) => {
+                        *next += 1;
+                        yield Ok(checkpoint);
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        yield Err(e);
+                        return;
+                    }
+                }
+            }
+        }
+
+        while let Some(checkpoint) = receiver.recv().await {
+            let sequence_number = checkpoint.checkpoint_summary.sequence_number;
+
+            // Skip checkpoints that were already replayed from the store, and fill in any that
+            // were executed after catching up, but before the subscription received them.
+            if let Some(next) = &mut next {
+                if sequence_number < *next {
+                    continue;
+                }
+
+                while *next < sequence_number {
+                    match checkpoint_data(&service, *next).await {
+                        Ok(Some(checkpoint)) => {
+                            *next += 1;
+                            yield Ok(checkpoint);
+                        }
+                        Ok(None) => {
+                            yield Err(CheckpointNotFoundError::sequence_number(*next).into());
+                            return;
+                        }
+                        Err(e) => {
+                            yield Err(e);
+                            return;
+                        }
+                    }
+                }
+
+                *next = sequence_number + 1;
+            }
+
+            yield Ok(checkpoint);
+        }
+    })
+}
+
+/// Load the full contents of checkpoint `sequence_number` from the store, or `None` if it has not
+/// been executed yet. The store is read on a blocking thread, because checkpoint data includes all
+/// the objects the checkpoint's transactions touched.
+async fn checkpoint_data(
+    service: &RpcService,
+    sequence_number: CheckpointSequenceNumber,
+) -> Result<Option<Arc<CheckpointData>>, RpcError> {
+    let reader = service.reader.inner().clone();
+
+    tokio::task::spawn_blocking(move || -> Result<_, RpcError> {
+        let Some(summary) = reader.get_checkpoint_by_sequence_number(sequence_number) else {
+            return Ok(None);
+        };
+
+        let Some(contents) = reader.get_checkpoint_contents_by_sequence_number(sequence_number)
+        else {
+            return Ok(None);
+        };
+
+        let checkpoint = reader.get_checkpoint_data(summary, contents)?;
+        Ok(Some(Arc::new(checkpoint)))
+    })
+    .await
+    .map_err(|e| RpcError::from(anyhow::Error::from(e)))?
+}
@@ -25,6 +25,7 @@ pub use config::Config;
 pub use error::{
     CheckpointNotFoundError, ErrorDetails, ErrorReason, ObjectNotFoundError, Result, RpcError,
 };
+pub use grpc::alpha;
 pub use grpc::v2beta2::ledger_service;
 pub use metrics::{RpcMetrics, RpcMetricsMakeCallbackHandler};
 pub use reader::TransactionNotFoundError;
@@ -135,6 +136,7 @@ impl RpcService {
                 .register_encoded_file_descriptor_set(
                     sui_rpc::proto::sui::rpc::v2beta2::FILE_DESCRIPTOR_SET,
                 )
+                .register_encoded_file_descriptor_set(grpc::alpha::proto::FILE_DESCRIPTOR_SET)
                 .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
                 .build_v1()
                 .unwrap();
@@ -149,6 +151,7 @@ impl RpcService {
                 .register_encoded_file_descriptor_set(
                     sui_rpc::proto::sui::rpc::v2beta2::FILE_DESCRIPTOR_SET,
                 )
+                .register_encoded_file_descriptor_set(grpc::alpha::proto::FILE_DESCRIPTOR_SET)
                 .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
                 .build_v1alpha()
                 .unwrap();
@@ -190,6 +193,18 @@ sui_rpc::proto::sui::rpc::v2beta2::subscription_service_server::SubscriptionServ
                     )
                     .await;
                 services = services.add_service(subscription_service2);
+
+                let subscription_service_alpha =
+                    grpc::alpha::proto::subscription_service_server::SubscriptionServiceServer::new(
+                        self.clone(),
+                    );
+                health_reporter
+                    .set_service_status(
+                        service_name(&subscription_service_alpha),
+                        tonic_health::ServingStatus::Serving,
+                    )
+                    .await;
+                services = services.add_service(subscription_service_alpha);
             }
 
             services.add_service(health_service).into_router()
@@ -0,0 +1,102 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use protox::file::{
+    ChainFileResolver, DescriptorSetFileResolver, GoogleFileResolver, IncludeFileResolver,
+};
+use protox::prost::Message as _;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use walkdir::WalkDir;
+
+/// Regenerates the Rust types and file descriptor sets for the protos in this crate, and checks
+/// that they match what has been committed.
+///
+/// The protos in this crate import definitions from `sui.rpc.v2beta2`, which live in the `sui-rpc`
+/// crate, so those imports are resolved using the file descriptor set that it exports, and the
+/// generated code refers to its types rather than generating them again.
+#[test]
+fn bootstrap() {
+    let root_dir = PathBuf::from(std::env!("CARGO_MANIFEST_DIR"));
+    let proto_dir = root_dir.join("proto");
+    let proto_ext = OsStr::new("proto");
+
+    let mut proto_files = vec![];
+    for entry in WalkDir::new(&proto_dir) {
+        let entry = entry.unwrap();
+        if entry.file_type().is_dir() {
+            continue;
+        }
+
+        let path = entry.into_path();
+        if path.extension() == Some(proto_ext) {
+            proto_files.push(path)
+        }
+    }
+
+    let out_dir = root_dir
+        .join("src")
+        .join("grpc")
+        .join("alpha")
+        .join("generated");
+
+    let mut resolver = ChainFileResolver::new();
+    resolver.add(IncludeFileResolver::new(proto_dir.clone()));
+    resolver.add(
+        DescriptorSetFileResolver::decode(sui_rpc::proto::sui::rpc::v2beta2::FILE_DESCRIPTOR_SET)
+            .unwrap(),
+    );
+    resolver.add(GoogleFileResolver::new());
+
+    let mut fds = protox::Compiler::with_file_resolver(resolver)
+        .include_source_info(true)
+        .include_imports(false)
+        .open_files(&proto_files)
+        .unwrap()
+        .file_descriptor_set();
+
+    // Sort files by name to have deterministic codegen output
+    fds.file.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if let Err(error) = tonic_build::configure()
+        .build_client(true)
+        .build_server(true)
+        .extern_path(".sui.rpc.v2beta2", "::sui_rpc::proto::sui::rpc::v2beta2")
+        .out_dir(&out_dir)
+        .compile_fds(fds.clone())
+    {
+        panic!("failed to compile protos: {}", error);
+    }
+
+    // Group the files by their package, in order to have a single fds file per package, and have
+    // the files in the package sorted by their filename in order have a stable serialized format.
+    let mut packages: HashMap<_, prost_types::FileDescriptorSet> = HashMap::new();
+    for mut file in fds.file {
+        // Clear out the source code info as its not required for reflection
+        file.source_code_info = None;
+        packages
+            .entry(file.package().to_owned())
+            .or_default()
+            .file
+            .push(file);
+    }
+
+    for (package, fds) in packages {
+        let file_name = format!("{package}.fds.bin");
+        let file_descriptor_set_path = out_dir.join(&file_name);
+        std::fs::write(file_descriptor_set_path, fds.encode_to_vec()).unwrap();
+    }
+
+    let status = std::process::Command::new("git")
+        .arg("diff")
+        .arg("--exit-code")
+        .arg("--")
+        .arg(out_dir)
+        .status();
+    match status {
+        Ok(status) if !status.success() => panic!("You should commit the protobuf files"),
+        Err(error) => panic!("failed to run `git diff`: {}", error),
+        Ok(_) => {}
+    }
+}
@@ -44,10 +44,72 @@ impl MockCheckpointBuilder {
         }
     }
 
+    /// Create a builder that continues a chain this builder did not start, e.g. when forking the
+    /// state of another network. The previous checkpoint is a placeholder for checkpoint
+    /// `sequence_number` of `epoch`, with no transactions, certified by `validator_keys`.
+    pub fn new_at_checkpoint(
+        epoch: u64,
+        sequence_number: u64,
+        timestamp_ms: u64,
+        validator_keys: &impl ValidatorKeypairProvider,
+    ) -> Self {
+        let contents =
+            CheckpointContents::new_with_causally_ordered_execution_data(std::iter::empty::<
+                &VerifiedExecutionData,
+            >());
+        let summary = CheckpointSummary {
+            epoch,
+            sequence_number,
+            network_total_transactions: 0,
+            content_digest: *contents.digest(),
+            previous_digest: None,
+            epoch_rolling_gas_cost_summary: GasCostSummary::default(),
+            end_of_epoch_data: None,
+            timestamp_ms,
+            version_specific_data: bcs::to_bytes(&CheckpointVersionSpecificData::empty_for_tests())
+                .unwrap(),
+            checkpoint_commitments: Default::default(),
+        };
+
+        Self::new(Self::create_certified_checkpoint(validator_keys, summary))
+    }
+
+    /// Create a builder that resumes from a previously captured state, e.g. when restoring a
+    /// saved simulation.
+    pub fn new_with_state(
+        previous_checkpoint: Option<VerifiedCheckpoint>,
+        epoch: u64,
+        epoch_rolling_gas_cost_summary: GasCostSummary,
+        transactions: Vec<VerifiedExecutionData>,
+    ) -> Self {
+        Self {
+            previous_checkpoint,
+            transactions,
+            epoch_rolling_gas_cost_summary,
+            epoch,
+        }
+    }
+
     pub fn size(&self) -> usize {
         self.transactions.len()
     }
 
+    /// The last checkpoint built, which the next checkpoint will follow.
+    pub fn previous_checkpoint(&self) -> Option<&VerifiedCheckpoint> {
+        self.previous_checkpoint.as_ref()
+    }
+
+    /// The epoch of the next checkpoint to build.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Transactions buffered for the next checkpoint.
+    pub fn transactions(&self) -> &[VerifiedExecutionData] {
+        &self.transactions
+    }
+
+    /// Gas cost summary of the epoch up to the previous checkpoint.
     pub fn epoch_rolling_gas_cost_summary(&self) -> &GasCostSummary {
         &self.epoch_rolling_gas_cost_summary
     }
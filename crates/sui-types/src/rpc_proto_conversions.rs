@@ -2852,3 +2852,14 @@ impl From<simulate_transaction_request::TransactionChecks>
         }
     }
 }
+
+impl From<crate::transaction_executor::TransactionChecks>
+    for simulate_transaction_request::TransactionChecks
+{
+    fn from(value: crate::transaction_executor::TransactionChecks) -> Self {
+        match value {
+            crate::transaction_executor::TransactionChecks::Enabled => Self::Enabled,
+            crate::transaction_executor::TransactionChecks::Disabled => Self::Disabled,
+        }
+    }
+}
@@ -79,7 +79,7 @@ use sui_types::{
     message_envelope::Envelope,
     metrics::BytecodeVerifierMetrics,
     move_package::{MovePackage, UpgradeCap},
-    object::Owner,
+    object::{Object, Owner},
     parse_sui_type_tag,
     programmable_transaction_builder::ProgrammableTransactionBuilder,
     signature::GenericSignature,
@@ -108,6 +108,9 @@ use sui_keys::key_derive;
 use sui_types::digests::ChainIdentifier;
 use tracing::{debug, info};
 
+/// Directory that gas profiles of dry runs are written to, with `--profile-gas`.
+const GAS_PROFILES_DIR: &str = "gas_profiles";
+
 static USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
 /// Only to be used within CLI
@@ -745,6 +748,11 @@ pub struct TxProcessingArgs {
     /// Perform a dev inspect
     #[arg(long)]
     pub dev_inspect: bool,
+    /// Together with `--dry-run`, also execute the transaction locally with the gas profiler
+    /// enabled, and write its gas profile to the `gas_profiles` directory in the folded stack
+    /// format accepted by flamegraph tools.
+    #[arg(long)]
+    pub profile_gas: bool,
     /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
     /// (TransactionData) using base64 encoding, and print out the string <TX_BYTES>. The string can
     /// be used to execute transaction with `sui client execute-signed-tx --tx-bytes <TX_BYTES>`.
@@ -788,6 +796,8 @@ impl SuiClientCommands {
                 let cmd2 = SR2::ReplayConfig {
                     digest: Some(tx_digest.clone()),
                     digests_path: None,
+                    checkpoints: None,
+                    concurrency: None,
                     node,
                     trace,
                     terminate_early: false,
@@ -796,6 +806,7 @@ impl SuiClientCommands {
                     overwrite_existing,
                     verbose: false,
                     store_mode: SR2::StoreMode::GqlOnly,
+                    package_overrides: vec![],
                 };
 
                 let artifact_path = SR2::handle_replay_config(&cmd2, USER_AGENT).await?;
@@ -822,6 +833,8 @@ impl SuiClientCommands {
                 let cmd2 = SR2::ReplayConfig {
                     digest: None,
                     digests_path: Some(path),
+                    checkpoints: None,
+                    concurrency: None,
                     node,
                     trace,
                     terminate_early,
@@ -830,6 +843,7 @@ impl SuiClientCommands {
                     overwrite_existing,
                     verbose: false,
                     store_mode: SR2::StoreMode::GqlOnly,
+                    package_overrides: vec![],
                 };
 
                 let artifact_path = SR2::handle_replay_config(&cmd2, USER_AGENT).await?;
@@ -3293,6 +3307,7 @@ pub(crate) async fn dry_run_or_execute_or_serialize(
         tx_digest,
         dry_run,
         dev_inspect,
+        profile_gas,
         serialize_unsigned_transaction,
         serialize_signed_transaction,
         sender,
@@ -3302,6 +3317,10 @@ pub(crate) async fn dry_run_or_execute_or_serialize(
         !serialize_unsigned_transaction || !serialize_signed_transaction,
         "Cannot specify both flags: --serialize-unsigned-transaction and --serialize-signed-transaction."
     );
+    ensure!(
+        !profile_gas || dry_run,
+        "The --profile-gas flag can only be used together with --dry-run."
+    );
 
     let gas_price = if let Some(gas_price) = gas_price {
         gas_price
@@ -3328,16 +3347,29 @@ pub(crate) async fn dry_run_or_execute_or_serialize(
     }
 
     if dry_run {
-        return execute_dry_run(
+        let result = execute_dry_run(
             context,
             signer,
-            tx_kind,
+            tx_kind.clone(),
             gas_budget,
             gas_price,
             gas_payment.clone(),
             None,
         )
-        .await;
+        .await?;
+        if let (true, SuiClientCommandResult::DryRun(response)) = (profile_gas, &result) {
+            // The dry run succeeded, so failing to profile it should not hide its result.
+            match save_dry_run_gas_profile(context, signer, tx_kind, response).await {
+                Ok(path) => eprintln!("Gas profile written to {}", path.display()),
+                Err(e) => eprintln!(
+                    "{}",
+                    format!("[warning] Failed to profile gas for the dry run: {e}")
+                        .yellow()
+                        .bold()
+                ),
+            }
+        }
+        return Ok(result);
     }
 
     let gas_budget = match gas_budget {
@@ -3451,6 +3483,197 @@ pub(crate) async fn dry_run_or_execute_or_serialize(
     }
 }
 
+/// Execute the transaction of a dry run locally, against the objects of the fullnode the CLI is
+/// connected to, with the gas profiler enabled, and save its gas profile in the folded stack
+/// format. Gas used by native functions is attributed to the natives themselves.
+///
+/// Objects are read at their latest versions, which is the state that dry runs execute against.
+/// The profile is only saved if local execution used the same amount of gas as the dry run, as
+/// otherwise it does not describe the transaction that was dry run (e.g. because the chain moved
+/// on between the two executions).
+async fn save_dry_run_gas_profile(
+    context: &mut WalletContext,
+    signer: SuiAddress,
+    tx_kind: TransactionKind,
+    dry_run: &DryRunTransactionBlockResponse,
+) -> Result<PathBuf, anyhow::Error> {
+    use move_vm_profiler::FOLDED_STACKS_FILE_EXTENSION;
+    use sui_json_rpc_types::SuiTransactionBlockDataAPI;
+    use sui_types::{effects::TransactionEffectsAPI, gas::SuiGasStatusAPI, transaction::GasData};
+
+    // Use the gas payment, budget and price picked for the dry run, so that the transaction
+    // executed locally is the one that was dry run.
+    let gas_data = dry_run.input.gas_data();
+    let tx_data = TransactionData::new_with_gas_data(
+        tx_kind,
+        signer,
+        GasData {
+            payment: gas_data.payment.iter().map(|r| r.to_object_ref()).collect(),
+            owner: gas_data.owner,
+            price: gas_data.price,
+            budget: gas_data.budget,
+        },
+    );
+
+    let client = context.get_client().await?;
+    let store = DryRunStore::new(client).await?;
+    let checkpoint = store
+        .client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+    let profile_dir = PathBuf::from(GAS_PROFILES_DIR);
+
+    // Local execution is synchronous and reads objects through `store`, which blocks on the
+    // fullnode's responses, so it must not run on the runtime's worker threads.
+    let executor_profile_dir = profile_dir.clone();
+    let (result, effects, profile) = tokio::task::spawn_blocking(move || {
+        let epoch = store.epoch.epoch_id;
+        let executor = SR2::execution::ReplayExecutor::new(
+            store.protocol_config.clone(),
+            Some(executor_profile_dir),
+        )?;
+        let (result, effects, mut gas_status) = SR2::execution::execute_new_transaction_to_effects(
+            tx_data, checkpoint, epoch, &executor, &store, &store,
+        )?;
+        let profile = gas_status
+            .move_gas_status_mut()
+            .profiler
+            .as_mut()
+            .map(|profiler| profiler.finish_to_folded_stacks());
+        Ok::<_, anyhow::Error>((result, effects, profile))
+    })
+    .await??;
+
+    if let (Err(e), true) = (&result, dry_run.effects.status().is_ok()) {
+        bail!("The dry run succeeded, but executing it locally failed: {e:?}");
+    }
+
+    let local_gas = effects.gas_cost_summary();
+    let dry_run_gas = dry_run.effects.gas_cost_summary();
+    ensure!(
+        local_gas == dry_run_gas,
+        "Executing the dry run locally used different gas from the dry run: {local_gas:?} \
+        locally vs. {dry_run_gas:?} in the dry run"
+    );
+
+    let profile =
+        profile.ok_or_else(|| anyhow!("No gas profile was recorded for the transaction"))?;
+    let path = profile_dir.join(format!(
+        "{}.{}",
+        effects.transaction_digest(),
+        FOLDED_STACKS_FILE_EXTENSION
+    ));
+    std::fs::create_dir_all(&profile_dir)?;
+    std::fs::write(&path, profile)?;
+    Ok(path)
+}
+
+/// Epoch and object stores for executing dry runs locally, reading from the fullnode the CLI is
+/// connected to. Reads block on the fullnode's responses, so the stores must only be used off
+/// the runtime's worker threads.
+struct DryRunStore {
+    client: SuiClient,
+    runtime: tokio::runtime::Handle,
+    epoch: SR2::replay_interface::EpochData,
+    protocol_config: ProtocolConfig,
+}
+
+impl DryRunStore {
+    async fn new(client: SuiClient) -> Result<Self, anyhow::Error> {
+        let system_state = client
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await?;
+        let chain_id = client.read_api().get_chain_identifier().await.ok();
+        let chain = match chain_id
+            .as_ref()
+            .and_then(ChainIdentifier::from_chain_short_id)
+        {
+            Some(chain_id) => chain_id.chain(),
+            None => Chain::Unknown,
+        };
+        let protocol_config = ProtocolConfig::get_for_version(
+            ProtocolVersion::new(system_state.protocol_version),
+            chain,
+        );
+        Ok(Self {
+            client,
+            runtime: tokio::runtime::Handle::current(),
+            epoch: SR2::replay_interface::EpochData {
+                epoch_id: system_state.epoch,
+                protocol_version: system_state.protocol_version,
+                rgp: system_state.reference_gas_price,
+                start_timestamp: system_state.epoch_start_timestamp_ms,
+            },
+            protocol_config,
+        })
+    }
+
+    async fn get_object(
+        &self,
+        key: &SR2::replay_interface::ObjectKey,
+    ) -> Result<Option<(Object, u64)>, anyhow::Error> {
+        use SR2::replay_interface::VersionQuery;
+
+        let read_api = self.client.read_api();
+        let options = SuiObjectDataOptions::bcs_lossless();
+        let data = match key.version_query {
+            VersionQuery::Version(version) => read_api
+                .try_get_parsed_past_object(
+                    key.object_id,
+                    SequenceNumber::from_u64(version),
+                    options,
+                )
+                .await?
+                .into_object()
+                .ok(),
+            // Dry runs execute against the latest versions of objects, so those are the
+            // versions that the dry run read.
+            VersionQuery::RootVersion(_) | VersionQuery::AtCheckpoint(_) => {
+                read_api
+                    .get_object_with_options(key.object_id, options)
+                    .await?
+                    .data
+            }
+        };
+        let Some(data) = data else {
+            return Ok(None);
+        };
+        let object = data.try_into_object(&self.protocol_config)?;
+        let version = object.version().value();
+        Ok(Some((object, version)))
+    }
+}
+
+impl SR2::replay_interface::EpochStore for DryRunStore {
+    fn epoch_info(
+        &self,
+        epoch: u64,
+    ) -> Result<Option<SR2::replay_interface::EpochData>, anyhow::Error> {
+        Ok((epoch == self.epoch.epoch_id).then(|| self.epoch.clone()))
+    }
+
+    fn protocol_config(&self, epoch: u64) -> Result<Option<ProtocolConfig>, anyhow::Error> {
+        Ok((epoch == self.epoch.epoch_id).then(|| self.protocol_config.clone()))
+    }
+}
+
+impl SR2::replay_interface::ObjectStore for DryRunStore {
+    fn get_objects(
+        &self,
+        keys: &[SR2::replay_interface::ObjectKey],
+    ) -> Result<Vec<Option<(Object, u64)>>, anyhow::Error> {
+        self.runtime.block_on(async {
+            let mut objects = Vec::with_capacity(keys.len());
+            for key in keys {
+                objects.push(self.get_object(key).await?);
+            }
+            Ok(objects)
+        })
+    }
+}
+
 async fn execute_dev_inspect(
     context: &mut WalletContext,
     signer: SuiAddress,
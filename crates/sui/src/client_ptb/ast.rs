@@ -40,6 +40,7 @@ pub const JSON: &str = "json";
 pub const TX_DIGEST: &str = "tx-digest";
 pub const DRY_RUN: &str = "dry-run";
 pub const DEV_INSPECT: &str = "dev-inspect";
+pub const PROFILE_GAS: &str = "profile-gas";
 pub const SERIALIZE_UNSIGNED: &str = "serialize-unsigned-transaction";
 pub const SERIALIZE_SIGNED: &str = "serialize-signed-transaction";
 pub const SENDER: &str = "sender";
@@ -82,6 +83,7 @@ pub const COMMANDS: &[&str] = &[
     JSON,
     DRY_RUN,
     DEV_INSPECT,
+    PROFILE_GAS,
     SERIALIZE_UNSIGNED,
     SERIALIZE_SIGNED,
     SENDER,
@@ -122,6 +124,7 @@ pub struct ProgramMetadata {
     pub tx_digest_set: bool,
     pub dry_run_set: bool,
     pub dev_inspect_set: bool,
+    pub profile_gas_set: bool,
     pub gas_budget: Option<Spanned<u64>>,
     pub gas_price: Option<Spanned<u64>>,
     pub gas_sponsor: Option<Spanned<NumericalAddress>>,
@@ -44,6 +44,7 @@ struct ProgramParsingState {
     tx_digest_set: bool,
     dry_run_set: bool,
     dev_inspect_set: bool,
+    profile_gas_set: bool,
     gas_object_ids: Option<Vec<Spanned<ObjectID>>>,
     gas_budget: Option<Spanned<u64>>,
     gas_price: Option<Spanned<u64>>,
@@ -78,6 +79,7 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
                 tx_digest_set: false,
                 dry_run_set: false,
                 dev_inspect_set: false,
+                profile_gas_set: false,
                 gas_object_ids: None,
                 gas_budget: None,
                 gas_price: None,
@@ -130,6 +132,7 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
                 L(T::Command, A::TX_DIGEST) => flag!(tx_digest_set),
                 L(T::Command, A::DRY_RUN) => flag!(dry_run_set),
                 L(T::Command, A::DEV_INSPECT) => flag!(dev_inspect_set),
+                L(T::Command, A::PROFILE_GAS) => flag!(profile_gas_set),
                 L(T::Command, A::PREVIEW) => flag!(preview_set),
                 L(T::Command, A::WARN_SHADOWS) => flag!(warn_shadows_set),
                 L(T::Command, A::GAS_COIN) => {
@@ -257,6 +260,7 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
                     tx_digest_set: self.state.tx_digest_set,
                     dry_run_set: self.state.dry_run_set,
                     dev_inspect_set: self.state.dev_inspect_set,
+                    profile_gas_set: self.state.profile_gas_set,
                     gas_budget: self.state.gas_budget,
                     gas_price: self.state.gas_price,
                     gas_sponsor: self.state.gas_sponsor,
@@ -215,6 +215,7 @@ impl PTB {
             tx_digest: program_metadata.tx_digest_set,
             dry_run: program_metadata.dry_run_set,
             dev_inspect: program_metadata.dev_inspect_set,
+            profile_gas: program_metadata.profile_gas_set,
             serialize_unsigned_transaction: program_metadata.serialize_unsigned_set,
             serialize_signed_transaction: program_metadata.serialize_signed_set,
             sender: program_metadata.sender.map(|x| x.value.into_inner().into()),
@@ -365,6 +366,12 @@ pub fn ptb_description() -> clap::Command {
             --"dev-inspect"
             "Perform a dev-inspect of the PTB instead of executing it."
         ))
+        .arg(arg!(
+            --"profile-gas"
+            "Used with --dry-run. Also execute the PTB locally with the gas profiler enabled, and \
+            write its gas profile to the gas_profiles directory, in the folded stack format \
+            accepted by flamegraph tools."
+        ))
         .arg(arg!(
             --"gas-coin" <ID> ...
             "The object ID of the gas coin to use. If not specified, it will try to use the first \
@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::zklogin_commands_util::{perform_zk_login_test_tx, read_cli_line};
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use aws_sdk_kms::{
     primitives::Blob,
     types::{MessageType, SigningAlgorithmSpec},
@@ -35,13 +35,14 @@ use std::fmt::{Debug, Display, Formatter};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use sui_keys::encrypted::{passphrase_from_env, KdfParams};
 use sui_keys::key_derive::generate_new_key;
 use sui_keys::key_identity::KeyIdentity;
 use sui_keys::keypair_file::{
     read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
     write_keypair_to_file,
 };
-use sui_keys::keystore::{AccountKeystore, Keystore};
+use sui_keys::keystore::{AccountKeystore, EncryptedFileBasedKeystore, InMemKeystore, Keystore};
 use sui_types::base_types::SuiAddress;
 use sui_types::committee::EpochId;
 use sui_types::crypto::{
@@ -76,6 +77,10 @@ pub enum KeyToolCommand {
         /// The alias must start with a letter and can contain only letters, digits, dots, hyphens (-), or underscores (_).
         new_alias: Option<String>,
     },
+    /// Change the passphrase of an encrypted keystore. The current passphrase is read from the
+    /// `SUI_KEYSTORE_PASSPHRASE` environment variable if set, otherwise both passphrases are
+    /// prompted for.
+    ChangePassphrase,
     /// Convert private key in Hex or Base64 to new format (Bech32
     /// encoded 33 byte flag || private key starting with "suiprivkey").
     /// Hex private key format import and export are both deprecated in
@@ -102,6 +107,10 @@ pub enum KeyToolCommand {
         #[clap(long, default_value = "0")]
         cur_epoch: u64,
     },
+    /// Decrypt an encrypted keystore and write it back to disk in plaintext, reverting `sui keytool
+    /// lock`. The passphrase is read from the `SUI_KEYSTORE_PASSPHRASE` environment variable if
+    /// set, otherwise it is prompted for.
+    Decrypt,
     /// Generate a new keypair with key scheme flag {ed25519 | secp256k1 | secp256r1}
     /// with optional derivation path, default to m/44'/784'/0'/0'/0' for ed25519 or
     /// m/54'/784'/0'/0/0 for secp256k1 or m/74'/784'/0'/0/0 for secp256r1. Word
@@ -150,6 +159,11 @@ pub enum KeyToolCommand {
     /// (Base64 encoded `privkey`). This prints out the account keypair as Base64 encoded `flag || privkey`,
     /// the network keypair, worker keypair, protocol keypair as Base64 encoded `privkey`.
     LoadKeypair { file: PathBuf },
+    /// Encrypt the plaintext keystore at rest with a passphrase. The keystore file is rewritten in
+    /// place, and the passphrase is then required to sign with or export any of its keys. The
+    /// passphrase is read from the `SUI_KEYSTORE_PASSPHRASE` environment variable if set, otherwise
+    /// it is prompted for.
+    Lock,
     /// To MultiSig Sui Address. Pass in a list of all public keys `flag || pk` in Base64.
     /// See `keytool list` for example public keys.
     MultiSigAddress {
@@ -222,6 +236,11 @@ pub enum KeyToolCommand {
         #[clap(long)]
         base64pk: String,
     },
+    /// Unlock an encrypted keystore with its passphrase, to check the passphrase. The keys are only
+    /// decrypted in memory, and the keystore stays encrypted on disk. Commands that sign with or
+    /// export keys unlock the keystore when they first need to, with the passphrase from the
+    /// `SUI_KEYSTORE_PASSPHRASE` environment variable if set, or by prompting for it otherwise.
+    Unlock,
     /// This takes [enum SuiKeyPair] of Base64 encoded of 33-byte `flag || privkey`). It
     /// outputs the keypair into a file at the current directory where the address is the filename,
     /// and prints out its Sui address, Base64 encoded public key, the key scheme, and the key scheme flag.
@@ -304,6 +323,14 @@ pub struct AliasUpdate {
     new_alias: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystoreEncryption {
+    keystore_path: PathBuf,
+    encrypted: bool,
+    num_keys: usize,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DecodedMultiSig {
@@ -458,15 +485,18 @@ pub struct ZkLoginInsecureSignPersonalMessage {
 #[serde(untagged)]
 pub enum CommandOutput {
     Alias(AliasUpdate),
+    ChangePassphrase(KeystoreEncryption),
     Convert(ConvertOutput),
     DecodeMultiSig(DecodedMultiSigOutput),
     DecodeOrVerifyTx(DecodeOrVerifyTxOutput),
+    Decrypt(KeystoreEncryption),
     Error(String),
     Generate(Key),
     Import(Key),
     Export(ExportedKey),
     List(Vec<Key>),
     LoadKeypair(KeypairData),
+    Lock(KeystoreEncryption),
     MultiSigAddress(MultiSigAddress),
     MultiSigCombinePartialSig(MultiSigCombinePartialSig),
     MultiSigCombinePartialSigLegacy(MultiSigCombinePartialSigLegacyOutput),
@@ -474,6 +504,7 @@ pub enum CommandOutput {
     Show(Key),
     Sign(SignData),
     SignKMS(SerializedSig),
+    Unlock(KeystoreEncryption),
     ZkLoginSignAndExecuteTx(ZkLoginSignAndExecuteTx),
     ZkLoginInsecureSignPersonalMessage(ZkLoginInsecureSignPersonalMessage),
     ZkLoginSigVerify(ZkLoginSigVerifyResponse),
@@ -494,6 +525,21 @@ impl KeyToolCommand {
                     new_alias,
                 })
             }
+            KeyToolCommand::ChangePassphrase => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    bail!("The keystore is not encrypted, use `sui keytool lock` to encrypt it");
+                };
+                let old_passphrase = read_keystore_passphrase("Current keystore passphrase:")?;
+                let new_passphrase = prompt_new_keystore_passphrase()?;
+                encrypted
+                    .change_passphrase(&old_passphrase, &new_passphrase, KdfParams::new_scrypt())
+                    .await?;
+                CommandOutput::ChangePassphrase(KeystoreEncryption {
+                    keystore_path: encrypted.path().to_path_buf(),
+                    encrypted: true,
+                    num_keys: encrypted.entries().len(),
+                })
+            }
             KeyToolCommand::Convert { value } => {
                 let result = convert_private_key_to_bech32(value)?;
                 CommandOutput::Convert(result)
@@ -700,6 +746,29 @@ impl KeyToolCommand {
                 CommandOutput::List(keys)
             }
 
+            KeyToolCommand::Lock => {
+                let Keystore::File(file) = keystore else {
+                    bail!("Only a plaintext file keystore can be encrypted");
+                };
+                let passphrase = match passphrase_from_env()? {
+                    Some(passphrase) => passphrase.to_string(),
+                    None => prompt_new_keystore_passphrase()?,
+                };
+                let encrypted = EncryptedFileBasedKeystore::migrate_from_plaintext(
+                    std::mem::take(file),
+                    &passphrase,
+                    KdfParams::new_scrypt(),
+                )
+                .await?;
+                let output = KeystoreEncryption {
+                    keystore_path: encrypted.path().to_path_buf(),
+                    encrypted: true,
+                    num_keys: encrypted.entries().len(),
+                };
+                *keystore = Keystore::Encrypted(encrypted);
+                CommandOutput::Lock(output)
+            }
+
             KeyToolCommand::LoadKeypair { file } => {
                 let output = match read_keypair_from_file(&file) {
                     Ok(keypair) => {
@@ -918,6 +987,39 @@ impl KeyToolCommand {
                 })
             }
 
+            KeyToolCommand::Unlock => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    bail!("The keystore is not encrypted");
+                };
+                unlock_keystore(encrypted)?;
+                CommandOutput::Unlock(KeystoreEncryption {
+                    keystore_path: encrypted.path().to_path_buf(),
+                    encrypted: true,
+                    num_keys: encrypted.entries().len(),
+                })
+            }
+
+            KeyToolCommand::Decrypt => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    bail!("The keystore is not encrypted");
+                };
+                unlock_keystore(encrypted)?;
+                let keystore_path = encrypted.path().to_path_buf();
+                let Keystore::Encrypted(encrypted) =
+                    std::mem::replace(keystore, Keystore::InMem(InMemKeystore::default()))
+                else {
+                    unreachable!("keystore was checked to be encrypted above");
+                };
+                let plaintext = encrypted.migrate_to_plaintext().await?;
+                let output = KeystoreEncryption {
+                    keystore_path,
+                    encrypted: false,
+                    num_keys: plaintext.entries().len(),
+                };
+                *keystore = Keystore::File(plaintext);
+                CommandOutput::Decrypt(output)
+            }
+
             KeyToolCommand::Unpack { keypair } => {
                 let keypair = SuiKeyPair::decode_base64(&keypair)
                     .map_err(|_| anyhow!("Invalid Base64 encode keypair"))?;
@@ -1451,3 +1553,48 @@ fn anemo_styling(pk: &PublicKey) -> Option<String> {
         None
     }
 }
+
+/// Prompt for the passphrase of an encrypted keystore when its keys are first needed, rather than
+/// when it is loaded, so that commands that don't sign don't ask for it.
+pub fn set_keystore_passphrase_prompt(keystore: &mut Keystore) {
+    if let Keystore::Encrypted(keystore) = keystore {
+        keystore.set_passphrase_prompt(Box::new(|path| {
+            read_keystore_passphrase(&format!("Passphrase for keystore {}:", path.display()))
+        }));
+    }
+}
+
+/// Unlock an encrypted keystore if it is locked, with the passphrase from the
+/// `SUI_KEYSTORE_PASSPHRASE` environment variable if set, or prompting for it otherwise.
+fn unlock_keystore(keystore: &mut EncryptedFileBasedKeystore) -> Result<(), anyhow::Error> {
+    if keystore.is_locked() {
+        let passphrase = read_keystore_passphrase(&format!(
+            "Passphrase for keystore {}:",
+            keystore.path().display()
+        ))?;
+        keystore.unlock(&passphrase)?;
+    }
+    Ok(())
+}
+
+fn read_keystore_passphrase(prompt: &str) -> Result<String, anyhow::Error> {
+    if let Some(passphrase) = passphrase_from_env()? {
+        return Ok(passphrase.to_string());
+    }
+    inquire::Password::new(prompt)
+        .without_confirmation()
+        .prompt()
+        .map_err(|e| anyhow!("Cannot read keystore passphrase: {e}"))
+}
+
+fn prompt_new_keystore_passphrase() -> Result<String, anyhow::Error> {
+    let passphrase = inquire::Password::new("New keystore passphrase:")
+        .with_custom_confirmation_message("Confirm the new passphrase:")
+        .with_custom_confirmation_error_message("The passphrases don't match.")
+        .prompt()
+        .map_err(|e| anyhow!("Cannot read keystore passphrase: {e}"))?;
+    if passphrase.is_empty() {
+        bail!("The keystore passphrase cannot be empty");
+    }
+    Ok(passphrase)
+}
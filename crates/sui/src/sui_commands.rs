@@ -6,7 +6,7 @@ use crate::client_commands::{
 };
 use crate::fire_drill::{run_fire_drill, FireDrill};
 use crate::genesis_ceremony::{run, Ceremony};
-use crate::keytool::KeyToolCommand;
+use crate::keytool::{set_keystore_passphrase_prompt, KeyToolCommand};
 use crate::validator_commands::SuiValidatorCommand;
 use anyhow::{anyhow, bail, ensure, Context};
 use clap::*;
@@ -57,7 +57,9 @@ use move_core_types::account_address::AccountAddress;
 use serde_json::json;
 use sui_keys::key_derive::generate_new_key;
 use sui_keys::keypair_file::read_key;
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_keys::keystore::{
+    AccountKeystore, EncryptedFileBasedKeystore, FileBasedKeystore, Keystore,
+};
 use sui_move::manage_package::resolve_lock_file_path;
 use sui_move::{self, execute_move_command};
 use sui_move_build::{
@@ -450,8 +452,22 @@ impl SuiCommand {
                 let keystore_path =
                     keystore_path.unwrap_or(sui_config_dir()?.join(SUI_KEYSTORE_FILENAME));
                 let mut keystore =
-                    Keystore::from(FileBasedKeystore::load_or_create(&keystore_path)?);
+                    if EncryptedFileBasedKeystore::is_encrypted_keystore(&keystore_path) {
+                        Keystore::from(EncryptedFileBasedKeystore::load(&keystore_path)?)
+                    } else {
+                        Keystore::from(FileBasedKeystore::load_or_create(&keystore_path)?)
+                    };
+                set_keystore_passphrase_prompt(&mut keystore);
+                let changes_encryption =
+                    matches!(cmd, KeyToolCommand::Lock | KeyToolCommand::Decrypt);
                 cmd.execute(&mut keystore).await?.print(!json);
+                if changes_encryption {
+                    update_client_config_keystore(
+                        &sui_config_dir()?.join(SUI_CLIENT_CONFIG),
+                        &keystore_path,
+                        &keystore,
+                    )?;
+                }
                 Ok(())
             }
             SuiCommand::Client {
@@ -466,6 +482,7 @@ impl SuiCommand {
                 prompt_if_no_config(&config_path, accept_defaults).await?;
                 if let Some(cmd) = cmd {
                     let mut context = WalletContext::new(&config_path)?;
+                    set_keystore_passphrase_prompt(&mut context.config.keystore);
                     if let Some(env_override) = config.env {
                         context = context.with_env_override(env_override);
                     }
@@ -1344,6 +1361,43 @@ async fn genesis(
     Ok(())
 }
 
+/// After `sui keytool lock` or `sui keytool decrypt` re-encodes the keystore file, point the client
+/// config's keystore entry at the new keystore kind, if it refers to the same file. The config is
+/// edited as plain YAML because it can no longer be loaded with its stale keystore entry.
+fn update_client_config_keystore(
+    config_path: &Path,
+    keystore_path: &Path,
+    keystore: &Keystore,
+) -> Result<(), anyhow::Error> {
+    if !config_path.exists() {
+        return Ok(());
+    }
+
+    let mut config: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(config_path)?)?;
+    let Some(entry) = config
+        .get_mut("keystore")
+        .and_then(serde_yaml::Value::as_mapping_mut)
+    else {
+        return Ok(());
+    };
+
+    let refers_to_keystore = entry
+        .values()
+        .next()
+        .and_then(serde_yaml::Value::as_str)
+        .is_some_and(|path| Path::new(path) == keystore_path);
+    if !refers_to_keystore {
+        return Ok(());
+    }
+
+    let serde_yaml::Value::Mapping(updated) = serde_yaml::to_value(keystore)? else {
+        bail!("Unexpected keystore serialization");
+    };
+    *entry = updated;
+    fs::write(config_path, serde_yaml::to_string(&config)?)?;
+    Ok(())
+}
+
 async fn prompt_if_no_config(
     wallet_conf_path: &Path,
     accept_defaults: bool,
@@ -631,6 +631,69 @@ async fn test_ptb_publish() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_ptb_dry_run_profile_gas() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+
+    let ptb_string = format!(
+        r#"
+         --split-coins gas [1000]
+         --assign new_coin
+         --move-call "sui::coin::value<sui::sui::SUI>" new_coin
+         --transfer-objects [new_coin] @{address}
+         --gas-budget 50000000
+         --profile-gas
+        "#
+    );
+
+    // Profiling gas is only supported for dry runs.
+    let args = shlex::split(&ptb_string).unwrap();
+    let err = PTB { args }.execute(context).await.unwrap_err();
+    assert!(err.to_string().contains("--dry-run"), "{err}");
+
+    // Profiles are written relative to the working directory.
+    let profile_dir = Path::new("gas_profiles");
+    let _ = fs::remove_dir_all(profile_dir);
+
+    let mut args = shlex::split(&ptb_string).unwrap();
+    args.push("--dry-run".to_string());
+    PTB { args }.execute(context).await?;
+
+    let profiles = read_dir(profile_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    fs::remove_dir_all(profile_dir)?;
+    let [profile] = profiles.as_slice() else {
+        panic!("Expected a single gas profile, found {profiles:?}");
+    };
+    assert_eq!(profile.extension().unwrap(), "folded");
+
+    // Every line is a stack of frames under the transaction's root frame, followed by the gas
+    // used in that stack, and the Move call shows up as a frame of its own.
+    let folded = fs::read_to_string(profile)?;
+    let stacks = folded
+        .lines()
+        .map(|line| {
+            let (stack, gas) = line
+                .rsplit_once(' ')
+                .unwrap_or_else(|| panic!("Malformed folded stack: {line}"));
+            assert!(stack.starts_with("root"), "{line}");
+            assert!(gas.parse::<u64>().is_ok(), "{line}");
+            stack
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        stacks
+            .iter()
+            .any(|stack| stack.starts_with("root;") && stack.contains("::coin::value")),
+        "{folded}"
+    );
+
+    Ok(())
+}
+
 #[sim_test]
 async fn test_custom_genesis() -> Result<(), anyhow::Error> {
     // Create and save genesis config file
@@ -7,10 +7,10 @@ use crossbeam::channel::{bounded, select};
 use lsp_server::{Connection, Message, Notification, Request, Response};
 use lsp_types::{
     CodeActionKind, CodeActionOptions, CodeActionProviderCapability, CompletionOptions, Diagnostic,
-    HoverProviderCapability, InlayHintOptions, InlayHintServerCapabilities, OneOf, SaveOptions,
-    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
-    TypeDefinitionProviderCapability, WorkDoneProgressOptions, notification::Notification as _,
-    request::Request as _,
+    HoverProviderCapability, InlayHintOptions, InlayHintServerCapabilities, OneOf, RenameOptions,
+    SaveOptions, SignatureHelpOptions, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextDocumentSyncOptions, TypeDefinitionProviderCapability, WorkDoneProgressOptions,
+    notification::Notification as _, request::Request as _,
 };
 use move_compiler::linters::LintLevel;
 use move_package::source_package::parsed_manifest::Dependencies;
@@ -24,13 +24,16 @@ use crate::{
     code_action,
     completions::on_completion_request,
     context::Context,
+    formatting::on_formatting_request,
     inlay_hints,
+    signature_help::on_signature_help_request,
     symbols::{
         self,
         compilation::PrecomputedPkgInfo,
         requests::{
             on_document_symbol_request, on_go_to_def_request, on_go_to_type_def_request,
-            on_hover_request, on_references_request,
+            on_hover_request, on_prepare_rename_request, on_references_request, on_rename_request,
+            on_workspace_symbol_request,
         },
         runner::SymbolicatorRunner,
     },
@@ -111,6 +114,24 @@ pub fn run(implicit_deps: Dependencies) {
         type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
         references_provider: Some(OneOf::Left(true)),
         document_symbol_provider: Some(OneOf::Left(true)),
+        workspace_symbol_provider: Some(OneOf::Left(true)),
+        rename_provider: Some(OneOf::Right(RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        })),
+        // Signature help is shown when a call's argument list is opened, and updated as each
+        // argument is typed.
+        signature_help_provider: Some(SignatureHelpOptions {
+            trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+            retrigger_characters: None,
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        document_range_formatting_provider: Some(OneOf::Left(true)),
         inlay_hint_provider: Some(OneOf::Right(InlayHintServerCapabilities::Options(
             InlayHintOptions {
                 work_done_progress_options: WorkDoneProgressOptions {
@@ -352,6 +373,21 @@ fn on_request(
         lsp_types::request::DocumentSymbolRequest::METHOD => {
             on_document_symbol_request(context, request);
         }
+        lsp_types::request::WorkspaceSymbolRequest::METHOD => {
+            on_workspace_symbol_request(context, request);
+        }
+        lsp_types::request::PrepareRenameRequest::METHOD => {
+            on_prepare_rename_request(context, request);
+        }
+        lsp_types::request::Rename::METHOD => {
+            on_rename_request(context, request);
+        }
+        lsp_types::request::SignatureHelpRequest::METHOD => {
+            on_signature_help_request(context, request, ide_files_root.clone());
+        }
+        lsp_types::request::Formatting::METHOD | lsp_types::request::RangeFormatting::METHOD => {
+            on_formatting_request(context, request, ide_files_root.clone());
+        }
         lsp_types::request::InlayHintRequest::METHOD => {
             inlay_hints::on_inlay_hint_request(context, request);
         }
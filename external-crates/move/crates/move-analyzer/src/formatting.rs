@@ -0,0 +1,118 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{context::Context, symbols::runner::SymbolicatorRunner};
+use lsp_server::{Message, Request, Response};
+use lsp_types::{
+    DocumentFormattingParams, DocumentRangeFormattingParams, FormattingOptions, Position, Range,
+    TextEdit, request::Request as _,
+};
+use move_compiler::{
+    editions::Edition,
+    formatter::{FormatOptions, format_source, format_source_range},
+};
+use move_package::source_package::manifest_parser::parse_move_manifest_from_file;
+use std::path::{Path, PathBuf};
+use vfs::VfsPath;
+
+/// Handles document and range formatting requests of the language server
+pub fn on_formatting_request(context: &Context, request: &Request, ide_files_root: VfsPath) {
+    let (fpath, options, lines) = match request.method.as_str() {
+        lsp_types::request::Formatting::METHOD => {
+            let parameters =
+                serde_json::from_value::<DocumentFormattingParams>(request.params.clone())
+                    .expect("could not deserialize formatting request");
+            let fpath = parameters.text_document.uri.to_file_path().unwrap();
+            (fpath, parameters.options, None)
+        }
+        lsp_types::request::RangeFormatting::METHOD => {
+            let parameters =
+                serde_json::from_value::<DocumentRangeFormattingParams>(request.params.clone())
+                    .expect("could not deserialize range formatting request");
+            let fpath = parameters.text_document.uri.to_file_path().unwrap();
+            let Range { start, end } = parameters.range;
+            // A range ending at the start of a line does not include that line.
+            let end_line = if end.character == 0 && end.line > start.line {
+                end.line
+            } else {
+                end.line + 1
+            };
+            (
+                fpath,
+                parameters.options,
+                Some(start.line as usize..end_line as usize),
+            )
+        }
+        _ => unreachable!("unexpected formatting request: {}", request.method),
+    };
+
+    eprintln!("formatting request: {:?}", fpath);
+    let edits = formatting_edits(&ide_files_root, &fpath, &options, lines).unwrap_or_default();
+
+    let response = Response::new_ok(request.id.clone(), edits);
+    if let Err(err) = context.connection.sender.send(Message::Response(response)) {
+        eprintln!("could not send formatting response: {:?}", err);
+    }
+}
+
+/// Computes the edits that format the file at `fpath` (or just the given range of its `lines`).
+/// The file's contents are read from the IDE's view of the file, if it is open, and from disk
+/// otherwise. Files that do not parse are left as-is.
+fn formatting_edits(
+    ide_files_root: &VfsPath,
+    fpath: &Path,
+    options: &FormattingOptions,
+    lines: Option<std::ops::Range<usize>>,
+) -> Option<Vec<TextEdit>> {
+    let source = ide_files_root
+        .join(fpath.to_string_lossy())
+        .and_then(|p| p.read_to_string())
+        .ok()
+        .or_else(|| std::fs::read_to_string(fpath).ok())?;
+
+    let edition = file_edition(fpath);
+    let options = FormatOptions {
+        indent_width: options.tab_size as usize,
+        ..FormatOptions::default()
+    };
+
+    let result = match lines {
+        Some(lines) => format_source_range(&source, edition, &options, lines),
+        None => format_source(&source, edition, &options),
+    };
+
+    let formatted = match result {
+        Ok(formatted) => formatted,
+        Err(_) => {
+            eprintln!("could not format {:?}: it does not parse", fpath);
+            return None;
+        }
+    };
+
+    if formatted == source {
+        return Some(vec![]);
+    }
+
+    // Replace the whole file, which is simpler than computing a minimal set of edits, and is
+    // handled well by editors.
+    let end = Position {
+        line: source.lines().count() as u32 + 1,
+        character: 0,
+    };
+    Some(vec![TextEdit {
+        range: Range {
+            start: Position::default(),
+            end,
+        },
+        new_text: formatted,
+    }])
+}
+
+/// The edition of the package containing `fpath`, or the default edition if the package's
+/// manifest can't be found.
+fn file_edition(fpath: &Path) -> Edition {
+    SymbolicatorRunner::root_dir(fpath)
+        .and_then(|root: PathBuf| parse_move_manifest_from_file(&root).ok())
+        .and_then(|manifest| manifest.package.edition)
+        .unwrap_or_default()
+}
@@ -12,7 +12,9 @@ pub mod compiler_info;
 pub mod completions;
 pub mod context;
 pub mod diagnostics;
+pub mod formatting;
 pub mod inlay_hints;
+pub mod signature_help;
 pub mod symbols;
 pub mod utils;
 pub mod vfs;
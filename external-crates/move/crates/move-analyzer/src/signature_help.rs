@@ -0,0 +1,272 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    symbols::{
+        Symbols,
+        def_info::DefInfo,
+        ide_strings::{ret_type_to_ide_str, type_args_to_ide_string, type_to_ide_string},
+        requests::def_info_doc_string,
+        runner::SymbolicatorRunner,
+    },
+};
+use lsp_server::{Message, Request, Response};
+use lsp_types::{
+    Documentation, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, Position,
+    SignatureHelp, SignatureHelpParams, SignatureInformation,
+};
+use std::path::Path;
+use vfs::VfsPath;
+
+/// Handles signature help request of the language server, which shows the signature of the
+/// function being called at the cursor, and which of its parameters is being typed
+pub fn on_signature_help_request(context: &Context, request: &Request, ide_files_root: VfsPath) {
+    let parameters = serde_json::from_value::<SignatureHelpParams>(request.params.clone())
+        .expect("could not deserialize signature help request");
+
+    let fpath = parameters
+        .text_document_position_params
+        .text_document
+        .uri
+        .to_file_path()
+        .unwrap();
+    let pos = parameters.text_document_position_params.position;
+    eprintln!("signature help request: {:?}", fpath);
+
+    let help = signature_help(context, &ide_files_root, &fpath, pos);
+    let response = Response::new_ok(request.id.clone(), help);
+    if let Err(err) = context.connection.sender.send(Message::Response(response)) {
+        eprintln!("could not send signature help response: {:?}", err);
+    }
+}
+
+fn signature_help(
+    context: &Context,
+    ide_files_root: &VfsPath,
+    fpath: &Path,
+    pos: Position,
+) -> Option<SignatureHelp> {
+    // The file being edited is read from the IDE's view of the file, as the call being typed is
+    // unlikely to be in the last version of the file that was successfully symbolicated.
+    let source = ide_files_root
+        .join(fpath.to_string_lossy())
+        .and_then(|p| p.read_to_string())
+        .ok()
+        .or_else(|| std::fs::read_to_string(fpath).ok())?;
+
+    let symbols_map = &context.symbols.lock().ok()?;
+    let symbols =
+        SymbolicatorRunner::root_dir(fpath).and_then(|pkg_path| symbols_map.get(&pkg_path))?;
+    signature_help_with_symbols(symbols, fpath, &source, pos)
+}
+
+/// Computes signature help for position `pos` in file `fpath`, whose current contents are
+/// `source`, using already computed symbols
+pub fn signature_help_with_symbols(
+    symbols: &Symbols,
+    fpath: &Path,
+    source: &str,
+    pos: Position,
+) -> Option<SignatureHelp> {
+    let call = enclosing_call(source, pos)?;
+    let signature = call_signature(symbols, fpath, &call)?;
+
+    let active_parameter = call.arg_idx + if call.dot_call { 1 } else { 0 };
+    Some(SignatureHelp {
+        signatures: vec![signature],
+        active_signature: Some(0),
+        active_parameter: Some(active_parameter as u32),
+    })
+}
+
+/// A call that is (possibly) still being typed
+struct EnclosingCall {
+    /// Position of the start of the name of the called function
+    name_start: Position,
+    /// Is it a dot call?
+    dot_call: bool,
+    /// Index of the argument at the cursor (not counting the receiver of a dot call)
+    arg_idx: usize,
+}
+
+/// Finds the innermost call whose argument list encloses position `pos` in `source`, by scanning
+/// backwards for an unmatched opening parenthesis, ignoring comments and string literals. This
+/// does not require the source to parse.
+fn enclosing_call(source: &str, pos: Position) -> Option<EnclosingCall> {
+    let line_start: usize = source
+        .split_inclusive('\n')
+        .take(pos.line as usize)
+        .map(str::len)
+        .sum();
+    let line = source[line_start..].split('\n').next()?;
+    let col = line
+        .char_indices()
+        .nth(pos.character as usize)
+        .map_or(line.len(), |(idx, _)| idx);
+    let before = &source[..line_start + col];
+
+    // Byte offsets in `masked` are the same as in `before`.
+    let masked = mask_comments_and_strings(before);
+
+    let mut depth = 0;
+    let mut arg_idx = 0;
+    let mut open_paren = None;
+    for (idx, c) in masked.char_indices().rev() {
+        match c {
+            ')' | ']' | '}' => depth += 1,
+            '(' if depth == 0 => {
+                open_paren = Some(idx);
+                break;
+            }
+            // the cursor is in a block, or a vector literal
+            '[' | '{' | ';' if depth == 0 => return None,
+            '(' | '[' | '{' => depth -= 1,
+            ',' if depth == 0 => arg_idx += 1,
+            _ => (),
+        }
+    }
+
+    // skip over whitespace, `!` of macro calls, and type arguments between the name and the
+    // argument list
+    let mut name_end = masked[..open_paren?].trim_end();
+    name_end = name_end.strip_suffix('!').unwrap_or(name_end).trim_end();
+    if name_end.ends_with('>') {
+        let mut depth = 0;
+        for (idx, c) in name_end.char_indices().rev() {
+            match c {
+                '>' => depth += 1,
+                '<' => depth -= 1,
+                _ => (),
+            }
+            if depth == 0 {
+                name_end = name_end[..idx].trim_end();
+                break;
+            }
+        }
+    }
+
+    let name_len = name_end
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '$')
+        .count();
+    if name_len == 0 {
+        return None;
+    }
+    let name_start = name_end.len() - name_len;
+    let dot_call = name_end[..name_start].trim_end().ends_with('.');
+
+    // Characters are counted in the original source, as masking can change the number of
+    // characters in a line (but not the number of bytes).
+    let name_line_start = before[..name_start].rfind('\n').map_or(0, |idx| idx + 1);
+    let name_start = Position {
+        line: before[..name_start].matches('\n').count() as u32,
+        character: before[name_line_start..name_start].chars().count() as u32,
+    };
+    Some(EnclosingCall {
+        name_start,
+        dot_call,
+        arg_idx,
+    })
+}
+
+/// Replaces comments and string literals in `source` with spaces (keeping line breaks), so that
+/// the parentheses and commas in them are not mistaken for those of a call. Every byte is replaced
+/// with a single byte, so byte offsets into the result are the same as in `source`.
+fn mask_comments_and_strings(source: &str) -> String {
+    let bytes = source.as_bytes();
+    let find_from = |start: usize, pat: &str| {
+        source
+            .get(start..)
+            .and_then(|rest| rest.find(pat))
+            .map(|idx| start + idx)
+    };
+
+    let mut masked = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let end = match (bytes[idx], bytes.get(idx + 1)) {
+            (b'/', Some(b'/')) => find_from(idx, "\n").unwrap_or(bytes.len()),
+            (b'/', Some(b'*')) => find_from(idx + 2, "*/").map_or(bytes.len(), |end| end + 2),
+            (b'"', _) => {
+                let mut end = idx + 1;
+                while end < bytes.len() && bytes[end] != b'"' {
+                    // skip over escaped characters, including escaped quotes
+                    end += if bytes[end] == b'\\' { 2 } else { 1 };
+                }
+                (end + 1).min(bytes.len())
+            }
+            (b, _) => {
+                masked.push(b);
+                idx += 1;
+                continue;
+            }
+        };
+        masked.extend(
+            bytes[idx..end]
+                .iter()
+                .map(|b| if *b == b'\n' { b'\n' } else { b' ' }),
+        );
+        idx = end;
+    }
+
+    // Only whole comments and strings are replaced, and they start and end at ASCII characters,
+    // so the result is still valid UTF-8.
+    String::from_utf8(masked).expect("masking preserves UTF-8")
+}
+
+/// Computes the signature of the function called in `call`, if it can be resolved.
+fn call_signature(
+    symbols: &Symbols,
+    fpath: &Path,
+    call: &EnclosingCall,
+) -> Option<SignatureInformation> {
+    let use_def = symbols
+        .line_uses(fpath, call.name_start.line)
+        .into_iter()
+        .find(|u| u.col_start == call.name_start.character)?;
+    let info = symbols.def_info(&use_def.def_loc)?;
+    let DefInfo::Function(_, _, _, name, type_args, arg_names, arg_types, ret_type, _) = info
+    else {
+        return None;
+    };
+
+    let mut label = format!(
+        "fun {name}{}(",
+        type_args_to_ide_string(
+            type_args, /* separate_lines */ false, /* verbose */ false
+        )
+    );
+    let mut parameters = vec![];
+    for (idx, (arg_name, arg_type)) in arg_names.iter().zip(arg_types).enumerate() {
+        if idx > 0 {
+            label.push_str(", ");
+        }
+        let start = label.len() as u32;
+        label.push_str(&format!(
+            "{}: {}",
+            arg_name.value,
+            type_to_ide_string(arg_type, /* verbose */ false)
+        ));
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start, label.len() as u32]),
+            documentation: None,
+        });
+    }
+    label.push(')');
+    label.push_str(&ret_type_to_ide_str(ret_type, /* verbose */ false));
+
+    let documentation = def_info_doc_string(info).map(|doc| {
+        Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: doc,
+        })
+    });
+    Some(SignatureInformation {
+        label,
+        documentation,
+        parameters: Some(parameters),
+        active_parameter: None,
+    })
+}
@@ -10,26 +10,37 @@ use crate::{
         def_info::DefInfo,
         mod_defs::{MemberDef, MemberDefInfo, ModuleDefs},
         runner::SymbolicatorRunner,
-        use_def::UseDef,
+        use_def::{UseDef, UseLoc},
     },
     utils::lsp_position_to_loc,
 };
 
-use lsp_server::{Message, Request, RequestId, Response};
+use lsp_server::{ErrorCode, Message, Request, RequestId, Response};
 use lsp_types::{
     DocumentSymbol, DocumentSymbolParams, GotoDefinitionParams, Hover, HoverContents, HoverParams,
-    Location, MarkupContent, MarkupKind, Position, Range, ReferenceParams, SymbolKind,
+    Location, MarkupContent, MarkupKind, Position, PrepareRenameResponse, Range, ReferenceParams,
+    RenameParams, SymbolInformation, SymbolKind, TextDocumentPositionParams, TextEdit,
+    WorkspaceEdit, WorkspaceSymbolParams, WorkspaceSymbolResponse,
     request::GotoTypeDefinitionParams,
 };
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashMap},
     path::{Path, PathBuf},
     vec,
 };
 use url::Url;
 
-use move_compiler::naming::ast::Type_;
+use move_command_line_common::files::FileHash;
+use move_compiler::{
+    expansion::ast::ModuleIdent_,
+    naming::ast::Type_,
+    parser::{
+        ast::Var,
+        keywords::{CONTEXTUAL_KEYWORDS, KEYWORDS, PRIMITIVE_TYPES},
+    },
+};
 use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
 
 /// Handles go-to-def request of the language server
 pub fn on_go_to_def_request(context: &Context, request: &Request) {
@@ -483,3 +494,479 @@ fn enum_variant_symbols(enum_def: &MemberDef, symbols: &Symbols) -> Vec<Document
     }
     variants
 }
+
+/// Handles prepare rename request of the language server, which checks if the identifier at a
+/// given position can be renamed
+pub fn on_prepare_rename_request(context: &Context, request: &Request) {
+    let symbols_map = &context.symbols.lock().unwrap();
+    let parameters = serde_json::from_value::<TextDocumentPositionParams>(request.params.clone())
+        .expect("could not deserialize prepare rename request");
+
+    let fpath = parameters.text_document.uri.to_file_path().unwrap();
+    let line = parameters.position.line;
+    let col = parameters.position.character;
+    eprintln!("on_prepare_rename_request: {:?}", fpath);
+
+    let response = match prepare_rename(symbols_map, &fpath, line, col) {
+        Ok(prepared) => Response::new_ok(request.id.clone(), prepared),
+        Err(msg) => Response::new_err(request.id.clone(), ErrorCode::RequestFailed as i32, msg),
+    };
+    if let Err(err) = context.connection.sender.send(Message::Response(response)) {
+        eprintln!("could not send prepare rename response: {:?}", err);
+    }
+}
+
+/// Computes the range of the identifier to rename at a given position, and its current name.
+pub fn prepare_rename(
+    symbols_map: &BTreeMap<PathBuf, Symbols>,
+    use_fpath: &Path,
+    use_line: u32,
+    use_col: u32,
+) -> Result<PrepareRenameResponse, String> {
+    let (u, symbols) = renameable_use(symbols_map, use_fpath, use_line, use_col)?;
+    let range = Range {
+        start: Position {
+            line: use_line,
+            character: u.col_start,
+        },
+        end: Position {
+            line: use_line,
+            character: u.col_end,
+        },
+    };
+    let placeholder = def_info_name(symbols.def_info(&u.def_loc).unwrap())
+        .unwrap()
+        .to_string();
+    Ok(PrepareRenameResponse::RangeWithPlaceholder { range, placeholder })
+}
+
+/// Handles rename request of the language server. All references to the renamed definition are
+/// updated, including those in other packages open in the IDE that depend on the package
+/// containing the definition.
+pub fn on_rename_request(context: &Context, request: &Request) {
+    let symbols_map = &context.symbols.lock().unwrap();
+    let parameters = serde_json::from_value::<RenameParams>(request.params.clone())
+        .expect("could not deserialize rename request");
+
+    let fpath = parameters
+        .text_document_position
+        .text_document
+        .uri
+        .to_file_path()
+        .unwrap();
+    let loc = parameters.text_document_position.position;
+    eprintln!("on_rename_request: {:?}", fpath);
+
+    let response = match rename_edits(
+        symbols_map,
+        &fpath,
+        loc.line,
+        loc.character,
+        &parameters.new_name,
+    ) {
+        Ok(edit) => Response::new_ok(request.id.clone(), edit),
+        Err(msg) => Response::new_err(request.id.clone(), ErrorCode::RequestFailed as i32, msg),
+    };
+    if let Err(err) = context.connection.sender.send(Message::Response(response)) {
+        eprintln!("could not send rename response: {:?}", err);
+    }
+}
+
+/// Computes the workspace edit renaming the definition used at a given position to `new_name`.
+/// Uses inside macro bodies are not symbolicated, so they are not renamed.
+pub fn rename_edits(
+    symbols_map: &BTreeMap<PathBuf, Symbols>,
+    use_fpath: &Path,
+    use_line: u32,
+    use_col: u32,
+    new_name: &str,
+) -> Result<WorkspaceEdit, String> {
+    let (u, symbols) = renameable_use(symbols_map, use_fpath, use_line, use_col)?;
+    let def_loc = u.def_loc;
+    let info = symbols.def_info(&def_loc).unwrap();
+    check_new_name(symbols, &def_loc, info, new_name)?;
+
+    // The same file can be part of multiple packages (e.g., when both a package and its
+    // dependency are open), so edits are keyed by their position to avoid duplicates.
+    let mut file_edits: BTreeMap<PathBuf, BTreeMap<(u32, u32), TextEdit>> = BTreeMap::new();
+    for pkg_symbols in symbols_map.values() {
+        let Some(refs) = pkg_symbols.references.get(&def_loc) else {
+            continue;
+        };
+        for ref_loc in refs {
+            let path = pkg_symbols.files.file_path(&ref_loc.fhash);
+            let range = Range {
+                start: ref_loc.start,
+                end: Position {
+                    line: ref_loc.start.line,
+                    character: ref_loc.col_end,
+                },
+            };
+            let new_text = match shorthand_field_local(pkg_symbols, ref_loc, &def_loc) {
+                // `S { f }` binds (or packs) a local with the same name as the field, so renaming
+                // either of them needs to spell out both names
+                Some(other_name) if matches!(info, DefInfo::Field(..)) => {
+                    format!("{new_name}: {other_name}")
+                }
+                Some(other_name) => format!("{other_name}: {new_name}"),
+                None => new_name.to_string(),
+            };
+            file_edits.entry(path.clone()).or_default().insert(
+                (range.start.line, range.start.character),
+                TextEdit { range, new_text },
+            );
+        }
+    }
+
+    let changes = file_edits
+        .into_iter()
+        .map(|(path, edits)| {
+            (
+                Url::from_file_path(path).unwrap(),
+                edits.into_values().collect(),
+            )
+        })
+        .collect::<HashMap<_, Vec<_>>>();
+    Ok(WorkspaceEdit {
+        changes: Some(changes),
+        document_changes: None,
+        change_annotations: None,
+    })
+}
+
+/// Finds the use at a given position, if it refers to a definition that can be renamed, along
+/// with the symbols of the package containing it.
+fn renameable_use<'a>(
+    symbols_map: &'a BTreeMap<PathBuf, Symbols>,
+    use_fpath: &Path,
+    use_line: u32,
+    use_col: u32,
+) -> Result<(UseDef, &'a Symbols), String> {
+    let symbols = SymbolicatorRunner::root_dir(use_fpath)
+        .and_then(|pkg_path| symbols_map.get(&pkg_path))
+        .ok_or_else(|| "No symbols available for this file".to_string())?;
+    let u = symbols
+        .line_uses(use_fpath, use_line)
+        .into_iter()
+        .rev()
+        .find(|u| use_col >= u.col_start && use_col <= u.col_end)
+        .ok_or_else(|| "No symbol to rename at this position".to_string())?;
+
+    let info = symbols
+        .def_info(&u.def_loc)
+        .ok_or_else(|| "No definition found for this symbol".to_string())?;
+    match info {
+        DefInfo::Function(..)
+        | DefInfo::Struct(..)
+        | DefInfo::Enum(..)
+        | DefInfo::Variant(..)
+        | DefInfo::Const(..) => (),
+        // macro bodies are not symbolicated, so uses of macro parameters are not known
+        DefInfo::Local(name, ..) if name.starts_with('$') => {
+            return Err(
+                "Macro parameters cannot be renamed, as their uses in the macro body are not \
+                 tracked"
+                    .to_string(),
+            );
+        }
+        DefInfo::Local(..) => (),
+        DefInfo::Field(_, _, name, _, _) if name.parse::<usize>().is_ok() => {
+            return Err("Positional fields cannot be renamed".to_string());
+        }
+        DefInfo::Field(..) => (),
+        DefInfo::Module(..) => return Err("Modules cannot be renamed".to_string()),
+        DefInfo::Type(_) => return Err("This symbol cannot be renamed".to_string()),
+    }
+
+    // Only definitions in packages open in the IDE can be renamed, as the references to them
+    // from other packages are not known.
+    let def_path = symbols.files.file_path(&u.def_loc.file_hash());
+    if !symbols_map
+        .keys()
+        .any(|pkg_path| def_path.starts_with(pkg_path))
+    {
+        return Err(format!(
+            "Cannot rename a symbol defined outside of the workspace (in {})",
+            def_path.display()
+        ));
+    }
+
+    Ok((u, symbols))
+}
+
+/// If the reference at `ref_loc` is a field shorthand, where a field and a local of the same name
+/// are used at the same location, returns the name of the one that is not defined at `def_loc`.
+/// Uses on a line are keyed by their starting column, so only one of them is kept in the use-def
+/// map, and the other one has to be found among the references.
+fn shorthand_field_local(symbols: &Symbols, ref_loc: &UseLoc, def_loc: &Loc) -> Option<Symbol> {
+    let first_at_start = UseLoc {
+        col_end: 0,
+        ..*ref_loc
+    };
+    symbols
+        .references
+        .iter()
+        .filter(|(loc, refs)| {
+            *loc != def_loc
+                && refs
+                    .range(first_at_start..)
+                    .next()
+                    .is_some_and(|r| r.fhash == ref_loc.fhash && r.start == ref_loc.start)
+        })
+        .find_map(|(loc, _)| match symbols.def_info(loc)? {
+            DefInfo::Field(_, _, name, _, _) | DefInfo::Local(name, ..) => Some(*name),
+            _ => None,
+        })
+}
+
+/// Checks that `new_name` is a valid name for the definition at `def_loc`, and that it does not
+/// collide with the name of another definition in the same scope.
+fn check_new_name(
+    symbols: &Symbols,
+    def_loc: &Loc,
+    info: &DefInfo,
+    new_name: &str,
+) -> Result<(), String> {
+    let first = new_name.chars().next();
+    let valid_ident = first.is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && new_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid_ident
+        || KEYWORDS.contains(&new_name)
+        || CONTEXTUAL_KEYWORDS.contains(&new_name)
+        || PRIMITIVE_TYPES.contains(&new_name)
+    {
+        return Err(format!("'{new_name}' is not a valid identifier"));
+    }
+
+    match info {
+        DefInfo::Local(..) if !Var::is_valid_name(Symbol::from(new_name)) => {
+            Err("Local variable names must start with a lowercase letter or '_'".to_string())
+        }
+        DefInfo::Const(..) if !first.is_some_and(|c| c.is_ascii_uppercase()) => {
+            Err("Constant names must start with an uppercase letter".to_string())
+        }
+        _ => check_name_collision(symbols, def_loc, info, Symbol::from(new_name)),
+    }
+}
+
+/// Checks that no other definition named `new_name` is in the scope of the definition at
+/// `def_loc`, as renaming it would then either fail to compile, or silently change what some of
+/// the references to either definition refer to.
+fn check_name_collision(
+    symbols: &Symbols,
+    def_loc: &Loc,
+    info: &DefInfo,
+    new_name: Symbol,
+) -> Result<(), String> {
+    let mod_defs = |mod_ident: &ModuleIdent_| symbols.mod_defs(&def_loc.file_hash(), *mod_ident);
+    match info {
+        // functions, types and constants all share the namespace of the module's members
+        DefInfo::Function(mod_ident, ..)
+        | DefInfo::Struct(mod_ident, ..)
+        | DefInfo::Enum(mod_ident, ..)
+        | DefInfo::Const(mod_ident, ..) => {
+            let Some(mod_def) = mod_defs(mod_ident) else {
+                return Ok(());
+            };
+            let collides = [
+                &mod_def.functions,
+                &mod_def.structs,
+                &mod_def.enums,
+                &mod_def.constants,
+            ]
+            .iter()
+            .filter_map(|members| members.get(&new_name))
+            .any(|member| member.name_loc != *def_loc);
+            if collides {
+                return Err(format!(
+                    "A module member named '{new_name}' already exists in this module"
+                ));
+            }
+        }
+        DefInfo::Variant(mod_ident, enum_name, ..) => {
+            let variants = mod_defs(mod_ident)
+                .and_then(|mod_def| mod_def.enums.get(enum_name))
+                .and_then(|enum_def| match &enum_def.info {
+                    MemberDefInfo::Enum { variants_info } => Some(variants_info),
+                    _ => None,
+                });
+            if variants
+                .and_then(|variants| variants.get(&new_name))
+                .is_some_and(|(loc, _, _)| loc != def_loc)
+            {
+                return Err(format!(
+                    "Enum '{enum_name}' already has a variant named '{new_name}'"
+                ));
+            }
+        }
+        DefInfo::Field(mod_ident, struct_name, ..) => {
+            let Some(mod_def) = mod_defs(mod_ident) else {
+                return Ok(());
+            };
+            // the fields of the struct or enum variant declaring the renamed field
+            let struct_fields = mod_def.structs.values().filter_map(|def| match &def.info {
+                MemberDefInfo::Struct { field_defs, .. } => Some(field_defs),
+                _ => None,
+            });
+            let variant_fields = mod_def.enums.values().flat_map(|def| match &def.info {
+                MemberDefInfo::Enum { variants_info } => variants_info
+                    .values()
+                    .map(|(_, fields, _)| fields)
+                    .collect(),
+                _ => vec![],
+            });
+            let siblings = struct_fields
+                .chain(variant_fields)
+                .find(|fields| fields.iter().any(|f| f.loc == *def_loc));
+            if siblings.is_some_and(|fields| {
+                fields
+                    .iter()
+                    .any(|f| f.name == new_name && f.loc != *def_loc)
+            }) {
+                return Err(format!(
+                    "'{struct_name}' already has a field named '{new_name}'"
+                ));
+            }
+        }
+        DefInfo::Local(..) => {
+            // Scopes are not recorded in the symbols, so a local is considered to be in scope of
+            // another one if the source spans from their definitions to their last uses overlap.
+            let Some(span) = local_span(symbols, def_loc) else {
+                return Ok(());
+            };
+            let collides = symbols.def_info.iter().any(|(loc, other)| {
+                loc != def_loc
+                    && matches!(other, DefInfo::Local(name, ..) if *name == new_name)
+                    && local_span(symbols, loc).is_some_and(|other_span| {
+                        span.0 == other_span.0 && span.1 <= other_span.2 && other_span.1 <= span.2
+                    })
+            });
+            if collides {
+                return Err(format!(
+                    "A local variable named '{new_name}' is already in scope"
+                ));
+            }
+        }
+        DefInfo::Type(_) | DefInfo::Module(..) => (),
+    }
+    Ok(())
+}
+
+/// Returns the file, and the first and last positions (as line and column) of the definition and
+/// uses of the local defined at `def_loc`.
+fn local_span(symbols: &Symbols, def_loc: &Loc) -> Option<(FileHash, (u32, u32), (u32, u32))> {
+    let refs = symbols.references.get(def_loc)?;
+    let positions = refs
+        .iter()
+        .filter(|r| r.fhash == def_loc.file_hash())
+        .map(|r| (r.start.line, r.start.character));
+    let first = positions.clone().min()?;
+    let last = positions.max()?;
+    Some((def_loc.file_hash(), first, last))
+}
+
+/// Returns the name of a definition, if it has one.
+fn def_info_name(def_info: &DefInfo) -> Option<Symbol> {
+    match def_info {
+        DefInfo::Function(_, _, _, name, ..)
+        | DefInfo::Struct(_, name, ..)
+        | DefInfo::Enum(_, name, ..)
+        | DefInfo::Variant(_, _, name, ..)
+        | DefInfo::Field(_, _, name, ..)
+        | DefInfo::Local(name, ..)
+        | DefInfo::Const(_, name, ..) => Some(*name),
+        DefInfo::Type(_) | DefInfo::Module(..) => None,
+    }
+}
+
+/// Handles workspace symbol request of the language server
+pub fn on_workspace_symbol_request(context: &Context, request: &Request) {
+    let symbols_map = &context.symbols.lock().unwrap();
+    let parameters = serde_json::from_value::<WorkspaceSymbolParams>(request.params.clone())
+        .expect("could not deserialize workspace symbol request");
+    eprintln!("on_workspace_symbol_request: {:?}", parameters.query);
+
+    let response = Response::new_ok(
+        request.id.clone(),
+        WorkspaceSymbolResponse::Flat(workspace_symbols(symbols_map, &parameters.query)),
+    );
+    if let Err(err) = context.connection.sender.send(Message::Response(response)) {
+        eprintln!("could not send workspace symbol response: {:?}", err);
+    }
+}
+
+/// Returns the module-level definitions in packages open in the IDE whose names contain `query`
+/// (ignoring case)
+#[allow(deprecated)]
+pub fn workspace_symbols(
+    symbols_map: &BTreeMap<PathBuf, Symbols>,
+    query: &str,
+) -> Vec<SymbolInformation> {
+    let query = query.to_lowercase();
+
+    // Files of a package are also part of the symbols of the packages depending on it, so
+    // definitions are keyed by their location to avoid duplicates.
+    let mut defs: BTreeMap<Loc, SymbolInformation> = BTreeMap::new();
+    for symbols in symbols_map.values() {
+        for (fpath, mods) in &symbols.file_mods {
+            if !symbols_map
+                .keys()
+                .any(|pkg_path| fpath.starts_with(pkg_path))
+            {
+                continue;
+            }
+            for mod_def in mods {
+                let container_name = Some(mod_def.ident.to_string());
+                let mut add_def = |name: &Symbol, loc: &Loc, kind: SymbolKind, in_module: bool| {
+                    if !name.to_lowercase().contains(&query) || defs.contains_key(loc) {
+                        return;
+                    }
+                    let Some(range) = symbols.files.lsp_range_opt(loc) else {
+                        return;
+                    };
+                    defs.insert(
+                        *loc,
+                        SymbolInformation {
+                            name: name.to_string(),
+                            kind,
+                            tags: None,
+                            deprecated: None,
+                            location: Location {
+                                uri: Url::from_file_path(fpath).unwrap(),
+                                range,
+                            },
+                            container_name: if in_module {
+                                container_name.clone()
+                            } else {
+                                None
+                            },
+                        },
+                    );
+                };
+
+                add_def(
+                    &mod_def.ident.module.0.value,
+                    &mod_def.name_loc,
+                    SymbolKind::MODULE,
+                    false,
+                );
+                for (sym, def) in &mod_def.constants {
+                    add_def(sym, &def.name_loc, SymbolKind::CONSTANT, true);
+                }
+                for (sym, def) in &mod_def.structs {
+                    add_def(sym, &def.name_loc, SymbolKind::STRUCT, true);
+                }
+                for (sym, def) in &mod_def.enums {
+                    add_def(sym, &def.name_loc, SymbolKind::ENUM, true);
+                }
+                for (sym, def) in &mod_def.functions {
+                    add_def(sym, &def.name_loc, SymbolKind::FUNCTION, true);
+                }
+            }
+        }
+    }
+
+    defs.into_values().collect()
+}
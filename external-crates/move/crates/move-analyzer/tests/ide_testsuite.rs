@@ -10,16 +10,23 @@ use std::{
 };
 
 use json_comments::StripComments;
-use lsp_types::{InlayHintKind, InlayHintLabel, InlayHintTooltip, Position};
+use lsp_types::{
+    InlayHintKind, InlayHintLabel, InlayHintTooltip, ParameterLabel, Position,
+    PrepareRenameResponse, Range,
+};
 use move_analyzer::{
     code_action::access_chain_autofix_actions_for_error,
     completions::{compute_completions_with_symbols, utils::compute_cursor},
     inlay_hints::inlay_hints_internal,
+    signature_help::signature_help_with_symbols,
     symbols::{
         Symbols,
         compilation::{CompiledPkgInfo, SymbolsComputationData, get_compiled_pkg},
         compute_symbols, compute_symbols_parsed_program, compute_symbols_pre_process,
-        requests::{def_info_doc_string, maybe_convert_for_guard},
+        requests::{
+            def_info_doc_string, maybe_convert_for_guard, prepare_rename, rename_edits,
+            workspace_symbols,
+        },
         use_def::UseDefMap,
     },
 };
@@ -59,6 +66,20 @@ enum TestSuite {
         project: String,
         file_tests: BTreeMap<String, Vec<AccessChainQuickFixTest>>,
     },
+    Rename {
+        project: String,
+        // other packages open in the IDE, whose references are also renamed
+        other_projects: Vec<String>,
+        file_tests: BTreeMap<String, Vec<RenameTest>>,
+    },
+    SignatureHelp {
+        project: String,
+        file_tests: BTreeMap<String, Vec<SignatureHelpTest>>,
+    },
+    WorkspaceSymbol {
+        projects: Vec<String>,
+        queries: Vec<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize)]
@@ -99,6 +120,19 @@ struct AccessChainQuickFixTest {
     err_msg: String,
 }
 
+#[derive(Serialize, Deserialize)]
+struct RenameTest {
+    use_line: u32,
+    use_col: u32,
+    new_name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SignatureHelpTest {
+    line: u32,
+    col: u32,
+}
+
 //**************************************************************************************************
 // Test Impls
 //**************************************************************************************************
@@ -381,6 +415,126 @@ impl AccessChainQuickFixTest {
     }
 }
 
+impl RenameTest {
+    fn test(
+        &self,
+        test_idx: usize,
+        symbols_map: &BTreeMap<PathBuf, Symbols>,
+        output: &mut dyn std::io::Write,
+        base_path: &Path,
+        use_file_path: &Path,
+    ) -> anyhow::Result<()> {
+        let RenameTest {
+            use_line,
+            use_col,
+            new_name,
+        } = self;
+        let lsp_use_line = use_line - 1; // 0th-based
+        let lsp_use_col = use_col - 1; // 0th-based
+
+        writeln!(output, "-- test {test_idx} -------------------")?;
+        writeln!(
+            output,
+            "use line: {use_line}, use_col: {use_col}, new name: '{new_name}'"
+        )?;
+        match prepare_rename(symbols_map, use_file_path, lsp_use_line, lsp_use_col) {
+            Ok(PrepareRenameResponse::RangeWithPlaceholder { range, placeholder }) => {
+                writeln!(output, "PREPARE: '{placeholder}' at {}", range_str(&range))?
+            }
+            Ok(_) => writeln!(output, "PREPARE: unexpected response")?,
+            Err(msg) => {
+                writeln!(output, "ERROR: {msg}")?;
+                return Ok(());
+            }
+        }
+
+        let edit = match rename_edits(
+            symbols_map,
+            use_file_path,
+            lsp_use_line,
+            lsp_use_col,
+            new_name,
+        ) {
+            Ok(edit) => edit,
+            Err(msg) => {
+                writeln!(output, "ERROR: {msg}")?;
+                return Ok(());
+            }
+        };
+        // edits are grouped by file in a hash map, so sort them for a stable output
+        let mut edits = vec![];
+        for (uri, file_edits) in edit.changes.unwrap_or_default() {
+            let path = uri.to_file_path().unwrap();
+            for e in file_edits {
+                edits.push((path.clone(), e));
+            }
+        }
+        edits.sort_by_key(|(path, e)| (path.clone(), e.range.start.line, e.range.start.character));
+        for (path, e) in edits {
+            writeln!(
+                output,
+                "EDIT: {} {} -> '{}'",
+                path.strip_prefix(base_path).unwrap_or(&path).display(),
+                range_str(&e.range),
+                e.new_text
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl SignatureHelpTest {
+    fn test(
+        &self,
+        test_idx: usize,
+        symbols: &Symbols,
+        output: &mut dyn std::io::Write,
+        use_file_path: &Path,
+    ) -> anyhow::Result<()> {
+        let SignatureHelpTest { line, col } = self;
+        let pos = Position {
+            line: line - 1,     // 0th-based
+            character: col - 1, // 0th-based
+        };
+
+        writeln!(
+            output,
+            "-- test {test_idx} @ {line}:{col} -------------------"
+        )?;
+        let source = std::fs::read_to_string(use_file_path)?;
+        let Some(help) = signature_help_with_symbols(symbols, use_file_path, &source, pos) else {
+            writeln!(output, "NO SIGNATURE HELP")?;
+            return Ok(());
+        };
+        let signature = &help.signatures[0];
+        writeln!(output, "SIGNATURE: {}", signature.label)?;
+        let active = help.active_parameter.unwrap() as usize;
+        let param = signature
+            .parameters
+            .as_ref()
+            .and_then(|params| params.get(active))
+            .map(|p| match p.label {
+                ParameterLabel::LabelOffsets([start, end]) => {
+                    signature.label[start as usize..end as usize].to_string()
+                }
+                ParameterLabel::Simple(ref s) => s.clone(),
+            });
+        writeln!(
+            output,
+            "ACTIVE PARAMETER: {active} ({})",
+            param.as_deref().unwrap_or("none")
+        )?;
+        Ok(())
+    }
+}
+
+fn range_str(range: &Range) -> String {
+    format!(
+        "{}:{}-{}:{}",
+        range.start.line, range.start.character, range.end.line, range.end.character
+    )
+}
+
 fn completion_test(
     use_line: u32,
     use_col: u32,
@@ -473,6 +627,31 @@ fn initial_symbols(
     Ok((project_path, compiled_pkg_info, symbols))
 }
 
+/// Computes symbols for each of the projects, keyed by their (canonical) path, as the IDE does for
+/// packages open in it.
+fn projects_symbols(projects: &[String]) -> datatest_stable::Result<BTreeMap<PathBuf, Symbols>> {
+    let base_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let ide_files_root: VfsPath = MemoryFS::new().into();
+    let pkg_deps = Arc::new(Mutex::new(BTreeMap::new()));
+
+    let mut symbols_map = BTreeMap::new();
+    for project in projects {
+        let project_path = dunce::canonicalize(base_path.join(project))?;
+        let (compiled_pkg_info_opt, _) = get_compiled_pkg(
+            pkg_deps.clone(),
+            ide_files_root.clone(),
+            project_path.as_path(),
+            None,
+            LintLevel::None,
+            BTreeMap::new(),
+        )?;
+        let compiled_pkg_info = compiled_pkg_info_opt.ok_or("PACKAGE COMPILATION FAILED")?;
+        let symbols = compute_symbols(pkg_deps.clone(), compiled_pkg_info, None);
+        symbols_map.insert(project_path, symbols);
+    }
+    Ok(symbols_map)
+}
+
 fn use_def_test_suite(
     project: String,
     file_tests: BTreeMap<String, Vec<UseDefTest>>,
@@ -655,6 +834,113 @@ fn access_chain_quick_fix_test_suite(
     Ok(result)
 }
 
+fn rename_test_suite(
+    project: String,
+    other_projects: Vec<String>,
+    file_tests: BTreeMap<String, Vec<RenameTest>>,
+) -> datatest_stable::Result<String> {
+    let base_path = dunce::canonicalize(env!("CARGO_MANIFEST_DIR"))?;
+    let project_path = base_path.join(&project);
+    let mut projects = other_projects;
+    projects.push(project);
+    let symbols_map = projects_symbols(&projects)?;
+
+    let mut output: BufWriter<_> = BufWriter::new(Vec::new());
+    let writer: &mut dyn io::Write = output.get_mut();
+
+    for (file, tests) in file_tests {
+        writeln!(
+            writer,
+            "== {file} ========================================================"
+        )?;
+
+        let mut fpath = project_path.clone();
+
+        fpath.push(format!("sources/{file}"));
+        let cpath = dunce::canonicalize(&fpath).unwrap();
+
+        for (idx, test) in tests.iter().enumerate() {
+            test.test(idx, &symbols_map, writer, &base_path, &cpath)?;
+            writeln!(writer)?;
+        }
+    }
+
+    let result: String = String::from_utf8(output.into_inner().unwrap()).unwrap();
+    Ok(result)
+}
+
+fn signature_help_test_suite(
+    project: String,
+    file_tests: BTreeMap<String, Vec<SignatureHelpTest>>,
+) -> datatest_stable::Result<String> {
+    let (project_path, _, symbols) = initial_symbols(project, &file_tests.keys().collect())?;
+
+    let mut output: BufWriter<_> = BufWriter::new(Vec::new());
+    let writer: &mut dyn io::Write = output.get_mut();
+
+    for (file, tests) in file_tests {
+        writeln!(
+            writer,
+            "== {file} ========================================================"
+        )?;
+
+        let mut fpath = project_path.clone();
+
+        fpath.push(format!("sources/{file}"));
+        let cpath = dunce::canonicalize(&fpath).unwrap();
+
+        for (idx, test) in tests.iter().enumerate() {
+            test.test(idx, &symbols, writer, &cpath)?;
+        }
+    }
+
+    let result: String = String::from_utf8(output.into_inner().unwrap()).unwrap();
+    Ok(result)
+}
+
+#[allow(deprecated)]
+fn workspace_symbol_test_suite(
+    projects: Vec<String>,
+    queries: Vec<String>,
+) -> datatest_stable::Result<String> {
+    let base_path = dunce::canonicalize(env!("CARGO_MANIFEST_DIR"))?;
+    let symbols_map = projects_symbols(&projects)?;
+
+    let mut output: BufWriter<_> = BufWriter::new(Vec::new());
+    let writer: &mut dyn io::Write = output.get_mut();
+
+    for query in queries {
+        writeln!(
+            writer,
+            "== '{query}' ========================================================"
+        )?;
+        let mut symbols = workspace_symbols(&symbols_map, &query)
+            .into_iter()
+            .map(|info| (info.location.uri.to_file_path().unwrap(), info))
+            .collect::<Vec<_>>();
+        symbols.sort_by_key(|(path, info)| (path.clone(), info.location.range.start));
+        for (path, info) in symbols {
+            let start = info.location.range.start;
+            writeln!(
+                writer,
+                "{:?} '{}'{} at {}:{}:{}",
+                info.kind,
+                info.name,
+                info.container_name
+                    .map(|c| format!(" in {c}"))
+                    .unwrap_or_default(),
+                path.strip_prefix(&base_path).unwrap_or(&path).display(),
+                start.line,
+                start.character
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
+    let result: String = String::from_utf8(output.into_inner().unwrap()).unwrap();
+    Ok(result)
+}
+
 fn move_ide_testsuite(test_path: &Path) -> datatest_stable::Result<()> {
     let suite_file = io::BufReader::new(File::open(test_path)?);
     let stripped = StripComments::new(suite_file);
@@ -685,6 +971,18 @@ fn move_ide_testsuite(test_path: &Path) -> datatest_stable::Result<()> {
             project,
             file_tests,
         } => access_chain_quick_fix_test_suite(project, file_tests),
+        TestSuite::Rename {
+            project,
+            other_projects,
+            file_tests,
+        } => rename_test_suite(project, other_projects, file_tests),
+        TestSuite::SignatureHelp {
+            project,
+            file_tests,
+        } => signature_help_test_suite(project, file_tests),
+        TestSuite::WorkspaceSymbol { projects, queries } => {
+            workspace_symbol_test_suite(projects, queries)
+        }
     }?;
 
     insta_assert! {
@@ -0,0 +1,22 @@
+module RenameDep::use_rename;
+
+use Rename::rename::{Self, Point};
+
+public fun origin(): Point {
+    rename::new(0, 0)
+}
+
+public fun total(p: &Point): u64 {
+    rename::sum(p) + p.scale(2)
+}
+
+public fun tagged(a: u64, b: u64): u64 {
+    rename::tag(b"(,", /* ) , ( */ a) + rename::tag(
+        x"28", // (
+        b,
+    )
+}
+
+public fun applied(p: Point): u64 {
+    rename::apply!(p, |q| rename::get_x(&q))
+}
@@ -0,0 +1,42 @@
+module Rename::rename;
+
+public struct Point has copy, drop {
+    x: u64,
+    y: u64,
+}
+
+const ORIGIN_X: u64 = 0;
+
+public fun new(x: u64, y: u64): Point {
+    Point { x, y }
+}
+
+public fun get_x(p: &Point): u64 {
+    let Point { x, y: _ } = *p;
+    x
+}
+
+public fun sum(p: &Point): u64 {
+    let total = p.x + p.y;
+    total + ORIGIN_X
+}
+
+public fun scale(p: &Point, by: u64): u64 {
+    sum(p) * by
+}
+
+public fun tag(label: vector<u8>, n: u64): u64 {
+    let _ = label;
+    n
+}
+
+public macro fun apply($p: Point, $f: |Point| -> u64): u64 {
+    let p = $p;
+    $f(p)
+}
+
+public fun add(): u64 {
+    let b = 1;
+    let a = 2;
+    b + a
+}
@@ -0,0 +1,166 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::reroot_path;
+use anyhow::{Context, bail};
+use clap::*;
+use move_command_line_common::files::{FileHash, find_move_filenames};
+use move_compiler::{
+    diagnostics::report_diagnostics_to_buffer_with_env_color,
+    formatter::{FormatOptions, format_source, format_source_range},
+    shared::files::MappedFiles,
+};
+use move_package::{
+    BuildConfig,
+    source_package::{layout::SourcePackageLayout, manifest_parser::parse_move_manifest_from_file},
+};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// Format the Move source files of the package at `path`. If no path is provided defaults to
+/// current directory.
+#[derive(Parser)]
+#[clap(name = "fmt")]
+pub struct Fmt {
+    /// Don't write formatted files. Instead, print a diff for each file that is not formatted, and
+    /// exit with an error if there were any (for use in CI).
+    #[clap(long = "check")]
+    pub check: bool,
+
+    /// Only format the lines in this range of the file (1-based and inclusive, e.g. `10:20`).
+    /// Requires exactly one file to be provided.
+    #[clap(long = "lines", value_parser = parse_line_range)]
+    pub lines: Option<Range<usize>>,
+
+    /// Number of spaces in each level of indentation.
+    #[clap(long = "indent-width", default_value_t = 4)]
+    pub indent_width: usize,
+
+    /// Width that lines are kept within, where possible, by breaking long lists and expressions.
+    #[clap(long = "max-width", default_value_t = 100)]
+    pub max_width: usize,
+
+    /// The files to format. If none are provided, formats all the Move files in the package's
+    /// `sources`, `tests`, `scripts` and `examples` directories.
+    pub files: Vec<PathBuf>,
+}
+
+impl Fmt {
+    pub fn execute(self, path: Option<&Path>, config: BuildConfig) -> anyhow::Result<()> {
+        let Self {
+            check,
+            lines,
+            indent_width,
+            max_width,
+            files,
+        } = self;
+
+        // Resolve files relative to the current directory, before it is changed to the package
+        // root.
+        let files = files
+            .iter()
+            .map(|f| {
+                f.canonicalize()
+                    .with_context(|| format!("Cannot find file {}", f.display()))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let rerooted_path = reroot_path(path)?;
+        let edition = parse_move_manifest_from_file(&rerooted_path)?
+            .package
+            .edition
+            .or(config.default_edition)
+            .unwrap_or_default();
+
+        let files: Vec<String> = if files.is_empty() {
+            let dirs: Vec<_> = [
+                SourcePackageLayout::Sources,
+                SourcePackageLayout::Tests,
+                SourcePackageLayout::Scripts,
+                SourcePackageLayout::Examples,
+            ]
+            .iter()
+            .map(|dir| rerooted_path.join(dir.path()))
+            .filter(|dir| dir.is_dir())
+            .collect();
+            find_move_filenames(&dirs, false)?
+        } else {
+            find_move_filenames(&files, true)?
+        };
+
+        // Checked after expansion, because a single directory can contain many files.
+        if lines.is_some() && files.len() != 1 {
+            bail!("--lines can only be used when formatting a single file");
+        }
+
+        let options = FormatOptions {
+            indent_width,
+            max_width,
+            ..FormatOptions::default()
+        };
+
+        let mut unformatted = 0;
+        let mut failed = 0;
+        for file in &files {
+            let source =
+                std::fs::read_to_string(file).with_context(|| format!("Failed to read {file}"))?;
+
+            let result = match &lines {
+                Some(lines) => format_source_range(&source, edition, &options, lines.clone()),
+                None => format_source(&source, edition, &options),
+            };
+
+            let formatted = match result {
+                Ok(formatted) => formatted,
+                Err(diags) => {
+                    let mut mapped_files = MappedFiles::empty();
+                    mapped_files.add(FileHash::new(&source), file.as_str().into(), source.into());
+                    let buffer = report_diagnostics_to_buffer_with_env_color(&mapped_files, diags);
+                    eprint!("{}", String::from_utf8_lossy(&buffer));
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            if formatted == source {
+                continue;
+            }
+
+            unformatted += 1;
+            if check {
+                let diff = similar::TextDiff::from_lines(&source, &formatted);
+                print!("{}", diff.unified_diff().header(file, file));
+            } else {
+                std::fs::write(file, formatted)
+                    .with_context(|| format!("Failed to write {file}"))?;
+            }
+        }
+
+        if failed > 0 {
+            bail!("Failed to format {failed} file(s)");
+        }
+
+        if check && unformatted > 0 {
+            bail!("{unformatted} file(s) are not formatted, run `fmt` to format them");
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a 1-based, inclusive range of lines like `10:20` into a 0-based, exclusive range.
+fn parse_line_range(s: &str) -> anyhow::Result<Range<usize>> {
+    let Some((start, end)) = s.split_once(':') else {
+        bail!("Expected a range of lines like '10:20'");
+    };
+
+    let start: usize = start.parse().context("Invalid start line")?;
+    let end: usize = end.parse().context("Invalid end line")?;
+    if start == 0 || end < start {
+        bail!("Invalid range of lines: {s}");
+    }
+
+    Ok(start - 1..end)
+}
@@ -17,7 +17,11 @@ use move_coverage::coverage_map::{CoverageMap, output_map_to_file};
 use move_package::{BuildConfig, compilation::build_plan::BuildPlan};
 use move_unit_test::UnitTestingConfig;
 use move_vm_test_utils::gas_schedule::CostTable;
-use std::{io::Write, path::Path, process::ExitStatus};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::ExitStatus,
+};
 // if windows
 #[cfg(target_family = "windows")]
 use std::os::windows::process::ExitStatusExt;
@@ -72,6 +76,42 @@ pub struct Test {
     // Enable tracing for tests
     #[clap(long = "trace-execution")]
     pub trace_execution: bool,
+
+    /// Write a gas profile of each test to the `gas_profiles` directory, in the folded stack
+    /// format accepted by flamegraph tools (e.g. `inferno-flamegraph` or `flamegraph.pl`). Gas
+    /// used by native functions is attributed to them.
+    #[clap(long = "profile-gas")]
+    pub profile_gas: bool,
+
+    /// Fuzz tests that use generated values (only used with #[random_test]): bias generated values
+    /// towards edge cases, and mutate values that reach new instructions (in builds with the
+    /// `tracing` feature). Failing inputs are shrunk to a minimal example.
+    #[clap(long = "fuzz")]
+    pub fuzz: bool,
+
+    /// Directory to save failing inputs of tests that use generated values in, and to replay them
+    /// from on later runs.
+    #[clap(long = "fuzz-corpus-dir")]
+    pub fuzz_corpus_dir: Option<PathBuf>,
+
+    /// Write a JUnit XML report of the test results to this file.
+    #[clap(long = "report-junit")]
+    pub junit_report: Option<PathBuf>,
+
+    /// Write a JSON report of the test results (status, gas used, duration and failure details of
+    /// each test) to this file.
+    #[clap(long = "report-json")]
+    pub json_report: Option<PathBuf>,
+
+    /// A JSON report of an earlier run (see `--report-json`) to compare the gas used by each test
+    /// against. Testing fails if a test uses more gas than in the baseline. Tests with generated
+    /// arguments (`#[random_test]`) are not compared, as their gas use depends on their inputs.
+    #[clap(long = "gas-baseline")]
+    pub gas_baseline: Option<PathBuf>,
+
+    /// The percentage by which a test may use more gas than in the gas baseline.
+    #[clap(long = "gas-regression-threshold", default_value = "0")]
+    pub gas_regression_threshold: f64,
 }
 
 impl Test {
@@ -116,6 +156,13 @@ impl Test {
             seed,
             rand_num_iters,
             trace_execution,
+            profile_gas,
+            fuzz,
+            fuzz_corpus_dir,
+            junit_report,
+            json_report,
+            gas_baseline,
+            gas_regression_threshold,
         } = self;
         UnitTestingConfig {
             gas_limit,
@@ -127,6 +174,13 @@ impl Test {
             seed,
             rand_num_iters,
             trace_execution,
+            profile_gas,
+            fuzz,
+            fuzz_corpus_dir,
+            junit_report,
+            json_report,
+            gas_baseline,
+            gas_regression_threshold,
             ..UnitTestingConfig::default_with_bound(None)
         }
     }
@@ -3,8 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use base::{
-    build::Build, coverage::Coverage, disassemble::Disassemble, docgen::Docgen, info::Info,
-    migrate::Migrate, new::New, summary::Summary, test::Test,
+    build::Build, coverage::Coverage, disassemble::Disassemble, docgen::Docgen, fmt::Fmt,
+    info::Info, migrate::Migrate, new::New, summary::Summary, test::Test,
 };
 use move_package::{BuildConfig, resolution::resolution_graph::ResolvedGraph};
 
@@ -60,6 +60,7 @@ pub enum Command {
     Coverage(Coverage),
     Disassemble(Disassemble),
     Docgen(Docgen),
+    Fmt(Fmt),
     Info(Info),
     Migrate(Migrate),
     New(New),
@@ -95,6 +96,7 @@ pub fn run_cli(
             c.execute(move_args.package_path.as_deref(), move_args.build_config)
         }
         Command::Docgen(c) => c.execute(move_args.package_path.as_deref(), move_args.build_config),
+        Command::Fmt(c) => c.execute(move_args.package_path.as_deref(), move_args.build_config),
         Command::Info(c) => c.execute(move_args.package_path.as_deref(), move_args.build_config),
         Command::Migrate(c) => c.execute(move_args.package_path.as_deref(), move_args.build_config),
         Command::New(c) => c.execute_with_defaults(move_args.package_path.as_deref()),
@@ -0,0 +1,11 @@
+module 0x1::profile {
+    fun double(x: u64): u64 {
+        x * 2
+    }
+
+    #[test]
+    fun calls_native() {
+        let bytes = std::bcs::to_bytes(&double(1));
+        assert!(bytes.length() == 8);
+    }
+}
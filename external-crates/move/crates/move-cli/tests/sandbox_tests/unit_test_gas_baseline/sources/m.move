@@ -0,0 +1,20 @@
+module 0x7::m {
+    #[test]
+    fun cheap() {
+        let x = 1;
+        assert!(x == 1);
+    }
+
+    #[test]
+    fun expensive() {
+        let mut i = 0;
+        while (i < 100) i = i + 1;
+    }
+
+    // Not compared against the baseline, as its gas use depends on the generated input
+    #[random_test]
+    fun varies_with_input(n: u64) {
+        let mut i = 0;
+        while (i < n % 100) i = i + 1;
+    }
+}
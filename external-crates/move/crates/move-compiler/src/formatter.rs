@@ -0,0 +1,1916 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A source formatter for Move, built on the compiler's lexer and parser.
+//!
+//! The layout is derived from the parser's AST: modules, members, blocks, expressions, patterns
+//! and bindings are walked to build a document of groups, indentation and potential line breaks,
+//! which is then printed, breaking a group over several lines only when it does not fit within
+//! the maximum line width. The AST does not hold comments or the exact text of the source, so the
+//! walk is driven by a cursor over the lexer's tokens: every node re-emits the source tokens it
+//! spans, and comments (found in the gaps between tokens) are attached to the token that follows
+//! them, or to the token they trail on the same line. Knowing what each token belongs to also
+//! decides its spacing, e.g. `<` is spaced as a comparison but not as a list of type arguments,
+//! and `|` as a bitwise or but not as the bounds of a lambda's parameters.
+//!
+//! Formatting only ever changes whitespace (and adds or removes the trailing commas of lists,
+//! depending on whether they are broken over several lines): it never changes the meaning of a
+//! file, and never loses a comment. As a safety net, the output is re-lexed and checked against
+//! the input's tokens before it is returned.
+
+use crate::{
+    diagnostics::{Diagnostics, codes::Severity},
+    editions::Edition,
+    ice,
+    parser::{
+        ast::{
+            Attributes, BinOp_, Bind, Bind_, BindList, Definition, Ellipsis, Exp, Exp_, Field,
+            FieldBindings, Function, FunctionBody_, MatchArm, MatchPattern, MatchPattern_,
+            ModuleDefinition, ModuleDefinitionMode, ModuleMember, Sequence, SequenceItem,
+            SequenceItem_, StructFields, Type, VariantDefinition, VariantFields,
+        },
+        comments::verify_string,
+        lexer::{Lexer, Tok},
+        syntax::parse_file_string,
+    },
+    shared::{CompilationEnv, Flags, PackageConfig},
+};
+use move_command_line_common::files::FileHash;
+use move_ir_types::location::Loc;
+use std::{collections::BTreeMap, ops::Range};
+
+/// Options controlling the layout of formatted source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Number of spaces in each level of indentation.
+    pub indent_width: usize,
+    /// Width that lines are kept within, where possible, by breaking lists and expressions.
+    pub max_width: usize,
+    /// Maximum number of consecutive blank lines to preserve.
+    pub max_blank_lines: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            max_width: 100,
+            max_blank_lines: 1,
+        }
+    }
+}
+
+/// Format the entirety of `source`, a Move file written in `edition`. Fails with the parser's
+/// diagnostics if `source` does not parse.
+pub fn format_source(
+    source: &str,
+    edition: Edition,
+    options: &FormatOptions,
+) -> Result<String, Diagnostics> {
+    Ok(Formatted::new(source, edition, options)?.output)
+}
+
+/// Format only the module members (functions, types, constants, uses, ...) that overlap the
+/// (0-based, end-exclusive) range of `lines` in `source`, leaving all other lines untouched. If
+/// the range only covers the lines of a module outside of its members, the whole module is
+/// formatted. Fails with the parser's diagnostics if `source` does not parse.
+pub fn format_source_range(
+    source: &str,
+    edition: Edition,
+    options: &FormatOptions,
+    lines: Range<usize>,
+) -> Result<String, Diagnostics> {
+    let formatted = Formatted::new(source, edition, options)?;
+
+    let in_range = |p: &&Placed| p.input_lines.start < lines.end && lines.start < p.input_lines.end;
+    let mut placed: Vec<_> = formatted.members.iter().filter(in_range).collect();
+    if placed.is_empty() {
+        placed = formatted.definitions.iter().filter(in_range).collect();
+    }
+    let (Some(first), Some(last)) = (placed.first(), placed.last()) else {
+        return Ok(source.to_string());
+    };
+
+    let input = line_span(source, first.input_lines.start..last.input_lines.end);
+    let output = line_span(
+        &formatted.output,
+        first.output_lines.start..last.output_lines.end,
+    );
+
+    let mut result = String::with_capacity(source.len());
+    result.push_str(&source[..input.start]);
+    result.push_str(&formatted.output[output]);
+    result.push_str(&source[input.end..]);
+    Ok(result)
+}
+
+//**************************************************************************************************
+// Lexing
+//**************************************************************************************************
+
+#[derive(Debug)]
+struct Token<'a> {
+    tok: Tok,
+    text: &'a str,
+    /// Byte offset of the start of this token in the source.
+    start: usize,
+    /// Number of line breaks between this token and the token or comment before it.
+    newlines_before: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommentKind {
+    Line,
+    Block,
+}
+
+#[derive(Debug)]
+struct Comment<'a> {
+    kind: CommentKind,
+    text: &'a str,
+    /// Byte offset of the start of this comment in the source.
+    start: usize,
+    /// Number of line breaks between this comment and the token or comment before it.
+    newlines_before: usize,
+}
+
+/// The tokens of a file, and its comments, keyed by the token they are attached to.
+struct Source<'a> {
+    text: &'a str,
+    tokens: Vec<Token<'a>>,
+    /// The comments on the lines before each token (and, in the last entry, before the end of
+    /// the file).
+    leading: Vec<Vec<Comment<'a>>>,
+    /// The comments that follow each token on the same line.
+    trailing: Vec<Vec<Comment<'a>>>,
+    /// For each opening delimiter, the index of its closing delimiter.
+    matching: Vec<Option<usize>>,
+}
+
+impl<'a> Source<'a> {
+    fn lex(text: &'a str, file_hash: FileHash, edition: Edition) -> Result<Self, Diagnostics> {
+        let mut lexer = Lexer::new(text, file_hash, edition);
+        let mut tokens: Vec<Token> = vec![];
+        let mut leading = vec![];
+        let mut trailing = vec![];
+        let mut prev_end = 0;
+
+        loop {
+            lexer
+                .advance()
+                .map_err(|diag| Diagnostics::from(vec![*diag]))?;
+
+            let start = lexer.start_loc();
+            let (mut comments, newlines_before) = split_gap(text, prev_end..start);
+
+            // Comments that start on the same line as the previous token trail it.
+            let trails = if tokens.is_empty() {
+                0
+            } else {
+                comments
+                    .iter()
+                    .take_while(|c| c.newlines_before == 0)
+                    .count()
+            };
+            let rest = comments.split_off(trails);
+            if !tokens.is_empty() {
+                trailing.push(comments);
+            }
+            leading.push(rest);
+
+            let tok = lexer.peek();
+            if tok == Tok::EOF {
+                break;
+            }
+
+            // `&mut` is lexed along with the whitespace that follows it.
+            let content = lexer.content().trim_end();
+            tokens.push(Token {
+                tok,
+                text: content,
+                start,
+                newlines_before,
+            });
+            prev_end = start + content.len();
+        }
+
+        let mut matching = vec![None; tokens.len()];
+        let mut open = vec![];
+        for (i, token) in tokens.iter().enumerate() {
+            match token.tok {
+                Tok::LParen | Tok::LBracket | Tok::LBrace => open.push(i),
+                Tok::RParen | Tok::RBracket | Tok::RBrace => {
+                    if let Some(o) = open.pop() {
+                        matching[o] = Some(i);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        Ok(Self {
+            text,
+            tokens,
+            leading,
+            trailing,
+            matching,
+        })
+    }
+
+    /// The number of line breaks before the token at `idx`, or before its leading comments.
+    fn newlines_before(&self, idx: usize) -> usize {
+        match self.leading[idx].first() {
+            Some(comment) => comment.newlines_before,
+            None => self.tokens.get(idx).map_or(0, |t| t.newlines_before),
+        }
+    }
+
+    /// The (end-exclusive) range of lines spanned by the tokens in `tokens`, along with their
+    /// leading and trailing comments.
+    fn lines(&self, tokens: Range<usize>) -> Range<usize> {
+        let first = &self.tokens[tokens.start];
+        let start = self.leading[tokens.start]
+            .first()
+            .map_or(first.start, |c| c.start);
+
+        let last = tokens.end - 1;
+        let end = match self.trailing[last].last() {
+            Some(c) => c.start + c.text.len(),
+            None => self.tokens[last].start + self.tokens[last].text.len(),
+        };
+
+        let line_of = |offset: usize| self.text[..offset].matches('\n').count();
+        line_of(start)..line_of(end) + 1
+    }
+}
+
+/// Find the comments in the whitespace and comments between two tokens (`gap`). Returns the
+/// comments, and the number of line breaks between the last comment (or the start of the gap) and
+/// the next token.
+fn split_gap(source: &str, gap: Range<usize>) -> (Vec<Comment<'_>>, usize) {
+    let mut comments = vec![];
+    let mut newlines = 0;
+    let mut offset = gap.start;
+
+    while offset < gap.end {
+        let rest = &source[offset..gap.end];
+        let kind_and_len = if rest.starts_with("//") {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            Some((CommentKind::Line, rest[..len].trim_end().len(), len))
+        } else if rest.starts_with("/*") {
+            let len = block_comment_len(rest);
+            Some((CommentKind::Block, len, len))
+        } else {
+            None
+        };
+
+        if let Some((kind, text_len, len)) = kind_and_len {
+            comments.push(Comment {
+                kind,
+                text: &rest[..text_len],
+                start: offset,
+                newlines_before: newlines,
+            });
+            newlines = 0;
+            offset += len;
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap();
+        if c == '\n' {
+            newlines += 1;
+        }
+        offset += c.len_utf8();
+    }
+
+    (comments, newlines)
+}
+
+/// The length of the (possibly nested) block comment at the start of `text`. The lexer has already
+/// checked that the comment is closed.
+fn block_comment_len(text: &str) -> usize {
+    let mut depth = 0;
+    let mut offset = 0;
+    while offset < text.len() {
+        let rest = &text[offset..];
+        if rest.starts_with("/*") {
+            depth += 1;
+            offset += 2;
+        } else if rest.starts_with("*/") {
+            depth -= 1;
+            offset += 2;
+            if depth == 0 {
+                return offset;
+            }
+        } else {
+            offset += rest.chars().next().unwrap().len_utf8();
+        }
+    }
+    text.len()
+}
+
+//**************************************************************************************************
+// Formatting
+//**************************************************************************************************
+
+/// Where a module member or definition ended up: the (end-exclusive) ranges of lines it spans in
+/// the input and output.
+struct Placed {
+    input_lines: Range<usize>,
+    output_lines: Range<usize>,
+}
+
+struct Formatted {
+    output: String,
+    members: Vec<Placed>,
+    definitions: Vec<Placed>,
+}
+
+impl Formatted {
+    fn new(source: &str, edition: Edition, options: &FormatOptions) -> Result<Self, Diagnostics> {
+        let file_hash = FileHash::new(source);
+        let defs = parse(source, file_hash, edition)?;
+        let src = Source::lex(source, file_hash, edition)?;
+
+        let mut builder = Builder::new(&src, options);
+        for (i, def) in defs.iter().enumerate() {
+            if i > 0 {
+                builder.item_break();
+            }
+            builder.definition(def);
+        }
+        builder.rest();
+
+        let doc = Doc::Group(builder.frames.pop().unwrap());
+        let mut printer = Printer::new(options, builder.marks);
+        printer.print(&doc);
+
+        let placed = |units: Vec<(Range<usize>, usize)>| -> Vec<Placed> {
+            units
+                .into_iter()
+                .map(|(input_lines, mark)| Placed {
+                    input_lines,
+                    output_lines: printer.starts[mark]..printer.ends[mark],
+                })
+                .collect()
+        };
+        let members = placed(builder.members);
+        let definitions = placed(builder.definitions);
+        let output = printer.finish();
+
+        // The formatter only changes whitespace and trailing commas, so the tokens must otherwise
+        // survive unchanged.
+        let reformatted = Source::lex(&output, file_hash, edition)?;
+        let tokens = |s: &Source| -> Vec<String> {
+            let toks = &s.tokens;
+            toks.iter()
+                .enumerate()
+                .filter(|(i, t)| {
+                    let closes = toks.get(i + 1).is_some_and(|n| {
+                        matches!(n.tok, Tok::RParen | Tok::RBracket | Tok::RBrace)
+                    });
+                    !(t.tok == Tok::Comma && closes)
+                })
+                .map(|(_, t)| t.text.to_string())
+                .collect()
+        };
+        let comments =
+            |s: &Source| -> usize { s.leading.iter().chain(&s.trailing).map(Vec::len).sum() };
+
+        if tokens(&src) != tokens(&reformatted) || comments(&src) != comments(&reformatted) {
+            let loc = Loc::new(file_hash, 0, source.len() as u32);
+            let msg = "Formatting changed the tokens in this file";
+            return Err(Diagnostics::from(vec![ice!((loc, msg))]));
+        }
+
+        Ok(Self {
+            output,
+            members,
+            definitions,
+        })
+    }
+}
+
+fn parse(
+    source: &str,
+    file_hash: FileHash,
+    edition: Edition,
+) -> Result<Vec<Definition>, Diagnostics> {
+    verify_string(file_hash, source)?;
+
+    let env = CompilationEnv::new(
+        Flags::empty(),
+        vec![],
+        vec![],
+        None,
+        BTreeMap::new(),
+        Some(PackageConfig {
+            edition,
+            ..PackageConfig::default()
+        }),
+        None,
+    );
+
+    let defs = parse_file_string(&env, file_hash, source, None)?;
+    env.check_diags_at_or_above_severity(Severity::NonblockingError)?;
+    Ok(defs)
+}
+
+/// The byte range covering the (end-exclusive) range of `lines` in `text`, including the final
+/// line break.
+fn line_span(text: &str, lines: Range<usize>) -> Range<usize> {
+    let mut starts = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .chain(std::iter::once(text.len()));
+    let start = starts.nth(lines.start).unwrap_or(text.len());
+    let end = starts
+        .nth(lines.end - lines.start - 1)
+        .unwrap_or(text.len());
+    start..end
+}
+
+//**************************************************************************************************
+// Documents
+//**************************************************************************************************
+
+/// The layout of a file, before it is fitted to the maximum line width.
+#[derive(Debug)]
+enum Doc<'a> {
+    Text(&'a str),
+    /// A comment, which may span several lines, and the column it started at in the source.
+    Comment(&'a str, usize),
+    /// A space, or a line break if the enclosing group is broken.
+    Line,
+    /// Nothing, or a line break if the enclosing group is broken.
+    SoftLine,
+    /// Text that is only printed if the enclosing group is broken (e.g. a trailing comma).
+    IfBroken(&'a str),
+    /// A line break, followed by this many blank lines. Breaks every enclosing group.
+    Break(usize),
+    /// Laid out on one line if it fits, and otherwise with all its `Line`s and `SoftLine`s broken.
+    Group(Vec<Doc<'a>>),
+    /// Indents the lines that start within it by one more level.
+    Indent(Vec<Doc<'a>>),
+    /// Records the output lines of what is printed between the start and end of a mark.
+    StartMark(usize),
+    EndMark(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Broken,
+}
+
+/// Prints a `Doc`, deciding which groups to break.
+struct Printer<'o> {
+    options: &'o FormatOptions,
+    output: String,
+    /// Number of lines written so far (the line the next text will be written to, if no line
+    /// break is pending).
+    line: usize,
+    column: usize,
+    /// Line breaks that have been requested but not yet written (they are only written before
+    /// the next text, so lines never have trailing whitespace), and the indentation of the line
+    /// that follows them.
+    pending_breaks: usize,
+    pending_indent: usize,
+    /// Marks waiting for the next text to be written, to record its line.
+    pending_marks: Vec<usize>,
+    starts: Vec<usize>,
+    ends: Vec<usize>,
+}
+
+impl<'o> Printer<'o> {
+    fn new(options: &'o FormatOptions, marks: usize) -> Self {
+        Self {
+            options,
+            output: String::new(),
+            line: 0,
+            column: 0,
+            pending_breaks: 0,
+            pending_indent: 0,
+            pending_marks: vec![],
+            starts: vec![0; marks],
+            ends: vec![0; marks],
+        }
+    }
+
+    fn print(&mut self, doc: &Doc) {
+        let mut stack = vec![(0, Mode::Broken, doc)];
+        while let Some((indent, mode, doc)) = stack.pop() {
+            match doc {
+                Doc::Text(text) => self.write(text),
+                Doc::IfBroken(text) if mode == Mode::Broken => self.write(text),
+                Doc::IfBroken(_) => (),
+                Doc::Comment(text, column) => self.write_comment(text, *column),
+                Doc::Line if mode == Mode::Flat => self.write(" "),
+                Doc::SoftLine if mode == Mode::Flat => (),
+                Doc::Line | Doc::SoftLine => self.line_break(indent, 0),
+                Doc::Break(blank_lines) => self.line_break(indent, *blank_lines),
+                Doc::Group(docs) => {
+                    let mode = if mode == Mode::Flat || self.fits(doc, &stack) {
+                        Mode::Flat
+                    } else {
+                        Mode::Broken
+                    };
+                    stack.extend(docs.iter().rev().map(|d| (indent, mode, d)));
+                }
+                Doc::Indent(docs) => stack.extend(docs.iter().rev().map(|d| (indent + 1, mode, d))),
+                Doc::StartMark(mark) => self.pending_marks.push(*mark),
+                Doc::EndMark(mark) => self.ends[*mark] = self.line + 1,
+            }
+        }
+    }
+
+    /// Whether `group` fits on the rest of the current line, laid out flat, along with whatever
+    /// follows it (in `rest`) up to the next line break.
+    fn fits(&self, group: &Doc, rest: &[(usize, Mode, &Doc)]) -> bool {
+        let column = if self.pending_breaks > 0 {
+            self.pending_indent * self.options.indent_width
+        } else {
+            self.column
+        };
+        let mut remaining = self.options.max_width as isize - column as isize;
+
+        let mut rest = rest.iter().rev();
+        let mut stack = vec![(Mode::Flat, group)];
+        while remaining >= 0 {
+            let (mode, doc) = match stack.pop() {
+                Some(next) => next,
+                None => match rest.next() {
+                    Some((_, mode, doc)) => (*mode, *doc),
+                    None => return true,
+                },
+            };
+
+            match doc {
+                Doc::Text(text) => remaining -= text.chars().count() as isize,
+                Doc::IfBroken(text) if mode == Mode::Broken => {
+                    remaining -= text.chars().count() as isize
+                }
+                Doc::IfBroken(_) => (),
+                // A line comment ends the line, so it doesn't need to fit if it follows the group.
+                Doc::Comment(text, _) if text.contains('\n') || text.starts_with("//") => {
+                    return mode == Mode::Broken;
+                }
+                Doc::Comment(text, _) => remaining -= text.chars().count() as isize,
+                Doc::Line if mode == Mode::Flat => remaining -= 1,
+                Doc::SoftLine if mode == Mode::Flat => (),
+                Doc::Line | Doc::SoftLine | Doc::Break(_) => return mode == Mode::Broken,
+                Doc::Group(docs) | Doc::Indent(docs) => {
+                    stack.extend(docs.iter().rev().map(|d| (mode, d)))
+                }
+                Doc::StartMark(_) | Doc::EndMark(_) => (),
+            }
+        }
+
+        false
+    }
+
+    fn line_break(&mut self, indent: usize, blank_lines: usize) {
+        self.pending_breaks = self.pending_breaks.max(1 + blank_lines);
+        self.pending_indent = indent;
+    }
+
+    fn write(&mut self, text: &str) {
+        let at_line_start = self.pending_breaks > 0 || self.output.is_empty();
+        if at_line_start && text.trim().is_empty() {
+            return;
+        }
+
+        if self.pending_breaks > 0 {
+            if !self.output.is_empty() {
+                let breaks = self.pending_breaks.min(1 + self.options.max_blank_lines);
+                self.output.extend(std::iter::repeat_n('\n', breaks));
+                self.line += breaks;
+                let indent = self.pending_indent * self.options.indent_width;
+                self.output.extend(std::iter::repeat_n(' ', indent));
+                self.column = indent;
+            }
+            self.pending_breaks = 0;
+        }
+
+        for mark in self.pending_marks.drain(..) {
+            self.starts[mark] = self.line;
+        }
+
+        self.output.push_str(text);
+        self.column += text.chars().count();
+    }
+
+    /// Write a comment that may span several lines, shifting its continuation lines by the same
+    /// amount that its first line moved (from `column` in the source), so that its internal
+    /// alignment is kept.
+    fn write_comment(&mut self, text: &str, column: usize) {
+        let mut lines = text.split('\n');
+        self.write(lines.next().unwrap_or_default());
+        let to = self.column - text.split('\n').next().unwrap_or_default().chars().count();
+
+        for l in lines {
+            self.output.push('\n');
+            self.line += 1;
+            self.column = 0;
+
+            let l = l.trim_end();
+            if l.is_empty() {
+                continue;
+            }
+
+            let l = if to >= column {
+                self.output.extend(std::iter::repeat_n(' ', to - column));
+                self.column = to - column;
+                l
+            } else {
+                let leading = l.len() - l.trim_start().len();
+                &l[leading.min(column - to)..]
+            };
+            self.output.push_str(l);
+            self.column += l.chars().count();
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+        self.output
+    }
+}
+
+//**************************************************************************************************
+// Layout
+//**************************************************************************************************
+
+/// How the items of a delimited list are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum List {
+    /// As written, e.g. attributes, types and tuples.
+    Plain,
+    /// With a trailing comma when broken over several lines, e.g. arguments and parameters.
+    Items,
+    /// Like `Items`, but separated from the braces by spaces, and kept broken if the source
+    /// breaks after the opening brace, e.g. struct literals and patterns.
+    Fields,
+    /// A block that only holds an expression, separated from the braces by spaces, and kept
+    /// broken if the source breaks after the opening brace.
+    Block,
+}
+
+/// How a token is emitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Emit {
+    Token,
+    /// A binary operator (in particular, a `|` that does not delimit lambda parameters).
+    Operator,
+    /// A trailing comma, which is dropped if its list fits on one line.
+    IfBroken,
+    /// A trailing comma, which is dropped.
+    Omit,
+}
+
+/// Builds the `Doc` for a file by walking its AST, while re-emitting its tokens (and the comments
+/// attached to them) in order.
+struct Builder<'s, 'a> {
+    src: &'s Source<'a>,
+    options: &'s FormatOptions,
+    /// The index of the next token to emit.
+    next: usize,
+    /// The contents of the groups (and indented blocks) currently being built.
+    frames: Vec<Vec<Doc<'a>>>,
+    prev: Option<Tok>,
+    /// Whether the space that would otherwise precede the next token should be left out (because
+    /// it follows a space or a line break that was already added).
+    no_space: bool,
+    /// Whether the next token should be preceded by a space regardless of the tokens around it
+    /// (because it follows a block comment).
+    space: bool,
+    /// Whether a `|` opening a list of lambda parameters has been emitted but not closed.
+    in_pipes: bool,
+    /// Whether the leading comments of each token have been emitted.
+    flushed: Vec<bool>,
+    marks: usize,
+    members: Vec<(Range<usize>, usize)>,
+    definitions: Vec<(Range<usize>, usize)>,
+}
+
+impl<'s, 'a> Builder<'s, 'a> {
+    fn new(src: &'s Source<'a>, options: &'s FormatOptions) -> Self {
+        Self {
+            src,
+            options,
+            next: 0,
+            frames: vec![vec![]],
+            prev: None,
+            no_space: false,
+            space: false,
+            in_pipes: false,
+            flushed: vec![false; src.leading.len()],
+            marks: 0,
+            members: vec![],
+            definitions: vec![],
+        }
+    }
+
+    //**********************************************************************************************
+    // Documents
+    //**********************************************************************************************
+
+    fn push(&mut self, doc: Doc<'a>) {
+        self.frames.last_mut().unwrap().push(doc);
+    }
+
+    fn nested(&mut self, f: impl FnOnce(&mut Self)) -> Vec<Doc<'a>> {
+        self.frames.push(vec![]);
+        f(self);
+        self.frames.pop().unwrap()
+    }
+
+    fn group(&mut self, f: impl FnOnce(&mut Self)) {
+        let docs = self.nested(f);
+        self.push(Doc::Group(docs));
+    }
+
+    fn indent(&mut self, f: impl FnOnce(&mut Self)) {
+        let docs = self.nested(f);
+        self.push(Doc::Indent(docs));
+    }
+
+    fn space(&mut self) {
+        self.push(Doc::Text(" "));
+        self.no_space = true;
+    }
+
+    fn line(&mut self) {
+        self.push(Doc::Line);
+        self.no_space = true;
+    }
+
+    fn softline(&mut self) {
+        self.push(Doc::SoftLine);
+        self.no_space = true;
+    }
+
+    fn hardline(&mut self) {
+        self.line_break(0);
+    }
+
+    fn line_break(&mut self, blank_lines: usize) {
+        self.push(Doc::Break(blank_lines.min(self.options.max_blank_lines)));
+        self.no_space = true;
+        self.space = false;
+    }
+
+    /// A line break between two items of a list (e.g. two members of a module, or two statements
+    /// in a block), keeping the blank lines that separated them in the source.
+    fn item_break(&mut self) {
+        let newlines = self.src.newlines_before(self.next);
+        self.line_break(newlines.saturating_sub(1));
+    }
+
+    /// Lays out whatever `f` emits as a member of a module (or a definition in a file), to find
+    /// the lines it occupies when formatting a range of lines.
+    fn unit(&mut self, f: impl FnOnce(&mut Self)) -> Option<(Range<usize>, usize)> {
+        let mark = self.marks;
+        self.marks += 1;
+
+        let first = self.next;
+        self.push(Doc::StartMark(mark));
+        f(self);
+        self.push(Doc::EndMark(mark));
+
+        (first < self.next).then(|| (self.src.lines(first..self.next), mark))
+    }
+
+    //**********************************************************************************************
+    // Tokens
+    //**********************************************************************************************
+
+    fn peek(&self) -> Option<Tok> {
+        self.src.tokens.get(self.next).map(|t| t.tok)
+    }
+
+    fn next_starts_before(&self, offset: usize) -> bool {
+        self.src
+            .tokens
+            .get(self.next)
+            .is_some_and(|t| t.start < offset)
+    }
+
+    /// Emit the next token.
+    fn token(&mut self) {
+        self.emit(Emit::Token)
+    }
+
+    /// Emit the next token as a binary operator, with a space before it.
+    fn operator(&mut self) {
+        self.space();
+        self.emit(Emit::Operator)
+    }
+
+    fn emit(&mut self, how: Emit) {
+        let src = self.src;
+        let idx = self.next;
+        let Some(token) = src.tokens.get(idx) else {
+            return;
+        };
+
+        self.leading_comments(idx);
+        if let Some(prev) = self.prev {
+            let punctuation = matches!(
+                token.tok,
+                Tok::Comma | Tok::Semicolon | Tok::RParen | Tok::RBracket
+            );
+            if !self.no_space
+                && ((self.space && !punctuation) || spaced(prev, token.tok, self.in_pipes))
+            {
+                self.push(Doc::Text(" "));
+            }
+        }
+
+        match how {
+            Emit::Token | Emit::Operator => self.push(Doc::Text(token.text)),
+            Emit::IfBroken => self.push(Doc::IfBroken(token.text)),
+            Emit::Omit => (),
+        }
+        if token.tok == Tok::Pipe && how == Emit::Token {
+            self.in_pipes = !self.in_pipes;
+        }
+
+        self.prev = Some(token.tok);
+        self.no_space = false;
+        self.space = false;
+        self.next += 1;
+
+        for comment in &src.trailing[idx] {
+            self.push(Doc::Text(" "));
+            self.comment(comment);
+        }
+    }
+
+    /// Emit the comments on the lines before the token at `idx`, if they haven't been already.
+    fn leading_comments(&mut self, idx: usize) {
+        if std::mem::replace(&mut self.flushed[idx], true) {
+            return;
+        }
+
+        let src = self.src;
+        let comments = &src.leading[idx];
+        for (i, comment) in comments.iter().enumerate() {
+            let at_start = i == 0 && self.prev.is_none();
+            if comment.newlines_before > 0 && !at_start {
+                // Blank lines before the first comment are kept by the list the token is in.
+                let newlines = if i == 0 { 1 } else { comment.newlines_before };
+                self.line_break(newlines - 1);
+            } else if !at_start && !self.no_space {
+                self.space();
+            }
+            self.comment(comment);
+        }
+
+        let newlines = src.tokens.get(idx).map_or(1, |t| t.newlines_before);
+        if !comments.is_empty() && newlines > 0 {
+            self.line_break(newlines - 1);
+        }
+    }
+
+    fn comment(&mut self, comment: &Comment<'a>) {
+        let column = column_of(self.src.text, comment.start);
+        self.push(Doc::Comment(comment.text, column));
+        match comment.kind {
+            CommentKind::Line => self.hardline(),
+            CommentKind::Block => {
+                self.no_space = false;
+                self.space = true;
+            }
+        }
+    }
+
+    /// Emit the tokens that start before `offset`, laying out any lists they contain.
+    fn tokens_until(&mut self, offset: usize) {
+        while self.next_starts_before(offset) {
+            self.flat(offset, false);
+        }
+    }
+
+    /// Emit the tokens up to (but not including) the next `tok`, as long as they start before
+    /// `offset`.
+    fn tokens_until_tok(&mut self, tok: Tok, offset: usize) {
+        while self.next_starts_before(offset) && self.peek() != Some(tok) {
+            self.flat(offset, false);
+        }
+    }
+
+    /// Emit the next token, or if it opens a list that closes before `offset`, the whole list.
+    /// Within a list (`in_list`), the items are separated by potential line breaks.
+    fn flat(&mut self, offset: usize, in_list: bool) {
+        let idx = self.next;
+        match self.src.matching[idx] {
+            Some(close) if self.src.tokens[close].start < offset => {
+                let close_start = self.src.tokens[close].start;
+                self.delimited(List::Plain, |b| {
+                    while b.next_starts_before(close_start) {
+                        b.flat(close_start, true);
+                    }
+                })
+            }
+            _ => {
+                let comma = self.peek() == Some(Tok::Comma);
+                self.token();
+                if comma && in_list && self.next_starts_before(offset) {
+                    self.line();
+                }
+            }
+        }
+    }
+
+    /// Emit the rest of the file.
+    fn rest(&mut self) {
+        self.tokens_until(usize::MAX);
+        self.leading_comments(self.src.tokens.len());
+    }
+
+    //**********************************************************************************************
+    // Lists
+    //**********************************************************************************************
+
+    /// Lay out a delimited list, starting at its opening delimiter, on one line if it fits, and
+    /// otherwise with one item per line. The contents of the list are emitted by `f`.
+    fn delimited(&mut self, list: List, f: impl FnOnce(&mut Self)) {
+        let open = self.next;
+        let Some(close) = self.src.matching.get(open).copied().flatten() else {
+            return;
+        };
+        let close_start = self.src.tokens[close].start;
+
+        let spaced = matches!(list, List::Fields | List::Block);
+        let force_break = spaced && self.breaks_after_open();
+        let line = |b: &mut Self| {
+            if force_break {
+                b.hardline()
+            } else if spaced {
+                b.line()
+            } else {
+                b.softline()
+            }
+        };
+
+        self.group(|b| {
+            b.token();
+            if b.next == close && b.src.leading[close].is_empty() {
+                b.token();
+                return;
+            }
+
+            b.indent(|b| {
+                line(b);
+                f(b);
+                if matches!(list, List::Items | List::Fields) && b.next > open + 1 {
+                    if b.peek() == Some(Tok::Comma) && b.next + 1 == close {
+                        b.emit(Emit::IfBroken);
+                    } else if b.next == close {
+                        b.push(Doc::IfBroken(","));
+                    }
+                }
+                b.tokens_until(close_start);
+                b.leading_comments(close);
+            });
+            line(b);
+            b.token();
+        });
+    }
+
+    /// Whether the source breaks the line after the next token (an opening delimiter). Lists of
+    /// fields and blocks are kept broken over several lines if they were in the source.
+    fn breaks_after_open(&self) -> bool {
+        self.src.newlines_before(self.next + 1) > 0
+    }
+
+    /// Lay out a delimited list, starting at its opening delimiter, with one item per line.
+    fn block_list<T>(
+        &mut self,
+        trailing_comma: bool,
+        items: &[T],
+        start: impl Fn(&T) -> usize,
+        mut item: impl FnMut(&mut Self, &T),
+    ) {
+        let Some(close) = self.src.matching.get(self.next).copied().flatten() else {
+            return;
+        };
+        let close_start = self.src.tokens[close].start;
+
+        self.token();
+        if self.next == close && self.src.leading[close].is_empty() {
+            self.token();
+            return;
+        }
+
+        self.indent(|b| {
+            for (i, it) in items.iter().enumerate() {
+                b.tokens_until(start(it));
+                if i == 0 {
+                    b.hardline()
+                } else {
+                    b.item_break()
+                }
+                item(b, it);
+            }
+            if trailing_comma && !items.is_empty() && b.next == close {
+                b.push(Doc::Text(","));
+            }
+            b.tokens_until(close_start);
+            b.leading_comments(close);
+        });
+        self.hardline();
+        self.token();
+    }
+
+    /// Emit the items of a comma-separated list, starting at the first item, separated by
+    /// potential line breaks.
+    fn items<T>(
+        &mut self,
+        items: &[T],
+        start: impl Fn(&T) -> usize,
+        mut item: impl FnMut(&mut Self, &T),
+    ) {
+        for (i, it) in items.iter().enumerate() {
+            if i > 0 {
+                self.tokens_until(start(it));
+                self.line();
+            }
+            item(self, it);
+        }
+    }
+
+    /// Lay out a list of expressions, starting at its opening delimiter.
+    fn exps(&mut self, list: List, exps: &[Exp]) {
+        let hugs = match exps.split_last() {
+            Some((last, init)) if list == List::Items => match &last.value {
+                Exp_::Pack(..) | Exp_::Vector(..) | Exp_::Block(_) => init.is_empty(),
+                Exp_::Lambda(_, _, body) => {
+                    matches!(body.value, Exp_::Block(_)) && init.iter().all(is_simple)
+                }
+                _ => false,
+            },
+            _ => false,
+        };
+
+        if hugs {
+            self.hug(exps);
+        } else {
+            self.delimited(list, |b| b.items(exps, |e| start(e.loc), |b, e| b.exp(e)));
+        }
+    }
+
+    /// Lay out a list of arguments that ends in a struct, vector, block or lambda on the same
+    /// lines as its delimiters, so that only the last argument is broken over several lines, e.g.
+    /// `f(S {` and `})`, or `v.do!(|x| {` and `})`.
+    fn hug(&mut self, exps: &[Exp]) {
+        let Some(close) = self.src.matching.get(self.next).copied().flatten() else {
+            return;
+        };
+        let close_start = self.src.tokens[close].start;
+
+        self.token();
+        for e in exps {
+            self.tokens_until(start(e.loc));
+            self.exp(e);
+        }
+        if self.peek() == Some(Tok::Comma) && self.next + 1 == close {
+            self.emit(Emit::Omit);
+        }
+        self.tokens_until(close_start);
+        self.token();
+    }
+
+    //**********************************************************************************************
+    // Definitions
+    //**********************************************************************************************
+
+    fn definition(&mut self, def: &Definition) {
+        let unit = self.unit(|b| match def {
+            Definition::Module(module) => b.module(module),
+            Definition::Address(address) => {
+                b.attributes(&address.attributes);
+                b.tokens_until_tok(Tok::LBrace, end(address.loc));
+                b.block_list(
+                    false,
+                    &address.modules,
+                    |m| attributes_start(&m.attributes, m.loc),
+                    |b, m| b.module(m),
+                );
+                b.tokens_until(end(address.loc));
+            }
+        });
+        self.definitions.extend(unit);
+    }
+
+    fn attributes(&mut self, attributes: &[Attributes]) {
+        for attribute in attributes {
+            self.tokens_until(end(attribute.loc));
+            self.hardline();
+        }
+    }
+
+    fn module(&mut self, module: &ModuleDefinition) {
+        self.attributes(&module.attributes);
+        match module.definition_mode {
+            ModuleDefinitionMode::Braces => {
+                self.tokens_until_tok(Tok::LBrace, end(module.loc));
+                self.block_list(false, &module.members, member_start, |b, m| b.member(m));
+            }
+            ModuleDefinitionMode::Semicolon => {
+                self.tokens_until_tok(Tok::Semicolon, end(module.loc));
+                self.token();
+                for member in &module.members {
+                    self.tokens_until(member_start(member));
+                    self.item_break();
+                    self.member(member);
+                }
+            }
+        }
+        self.tokens_until(end(module.loc));
+    }
+
+    fn member(&mut self, member: &ModuleMember) {
+        let unit = self.unit(|b| match member {
+            ModuleMember::Function(function) => b.function(function),
+            ModuleMember::Struct(struct_) => {
+                b.attributes(&struct_.attributes);
+                match &struct_.fields {
+                    StructFields::Named(fields) => {
+                        b.tokens_until_tok(Tok::LBrace, end(struct_.loc));
+                        let field_start = |(_, f, _): &(_, Field, _)| start(f.0.loc);
+                        let field =
+                            |b: &mut Self, (_, _, ty): &(_, _, Type)| b.tokens_until(end(ty.loc));
+                        if b.breaks_after_open() {
+                            b.block_list(true, fields, field_start, field);
+                        } else {
+                            b.delimited(List::Fields, |b| b.items(fields, field_start, field));
+                        }
+                    }
+                    StructFields::Positional(fields) => {
+                        b.tokens_until_tok(Tok::LParen, end(struct_.loc));
+                        b.delimited(List::Items, |b| {
+                            b.items(
+                                fields,
+                                |(_, ty)| start(ty.loc),
+                                |b, (_, ty)| b.tokens_until(end(ty.loc)),
+                            )
+                        });
+                    }
+                    StructFields::Native(_) => (),
+                }
+                b.tokens_until(end(struct_.loc));
+            }
+            ModuleMember::Enum(enum_) => {
+                b.attributes(&enum_.attributes);
+                b.tokens_until_tok(Tok::LBrace, end(enum_.loc));
+                b.block_list(true, &enum_.variants, |v| start(v.loc), |b, v| b.variant(v));
+                b.tokens_until(end(enum_.loc));
+            }
+            ModuleMember::Constant(constant) => {
+                b.attributes(&constant.attributes);
+                b.tokens_until(start(constant.value.loc));
+                b.rhs(&constant.value);
+                b.tokens_until(end(constant.loc));
+            }
+            ModuleMember::Use(use_) => {
+                b.attributes(&use_.attributes);
+                b.tokens_until(end(use_.loc));
+            }
+            ModuleMember::Friend(friend) => {
+                b.attributes(&friend.attributes);
+                b.tokens_until(end(friend.loc));
+            }
+            ModuleMember::Spec(spec) => b.tokens_until(end(spec.loc)),
+        });
+        self.members.extend(unit);
+    }
+
+    fn variant(&mut self, variant: &VariantDefinition) {
+        match &variant.fields {
+            VariantFields::Named(fields) => {
+                self.tokens_until_tok(Tok::LBrace, end(variant.loc));
+                self.delimited(List::Fields, |b| {
+                    b.items(
+                        fields,
+                        |(_, f, _)| start(f.0.loc),
+                        |b, (_, _, ty)| b.tokens_until(end(ty.loc)),
+                    )
+                });
+            }
+            VariantFields::Positional(fields) => {
+                self.tokens_until_tok(Tok::LParen, end(variant.loc));
+                self.delimited(List::Items, |b| {
+                    b.items(
+                        fields,
+                        |(_, ty)| start(ty.loc),
+                        |b, (_, ty)| b.tokens_until(end(ty.loc)),
+                    )
+                });
+            }
+            VariantFields::Empty => (),
+        }
+        self.tokens_until(end(variant.loc));
+    }
+
+    fn function(&mut self, function: &Function) {
+        self.attributes(&function.attributes);
+        self.tokens_until(end(function.name.0.loc));
+        self.tokens_until_tok(Tok::LParen, end(function.loc));
+
+        let params = &function.signature.parameters;
+        self.delimited(List::Items, |b| {
+            b.items(
+                params,
+                |(mut_, var, _)| start(mut_.unwrap_or(var.0.loc)),
+                |b, (_, _, ty)| b.tokens_until(end(ty.loc)),
+            )
+        });
+
+        // The return type is kept on one line, so that the parameters are broken instead.
+        if let FunctionBody_::Defined(seq) = &function.body.value {
+            while self.next_starts_before(start(function.body.loc)) {
+                self.token();
+            }
+            self.block(function.body.loc, seq);
+        }
+        self.tokens_until(end(function.loc));
+    }
+
+    //**********************************************************************************************
+    // Sequences
+    //**********************************************************************************************
+
+    /// Lay out a block. Blocks that only contain an expression are laid out on one line if they
+    /// fit (and were on one line in the source), and otherwise with one statement per line.
+    fn block(&mut self, loc: Loc, seq: &Sequence) {
+        self.tokens_until(start(loc));
+        if self.peek() != Some(Tok::LBrace) {
+            self.tokens_until(end(loc));
+            return;
+        }
+
+        let (uses, items, _, last) = seq;
+        match &**last {
+            Some(last) if uses.is_empty() && items.is_empty() => {
+                self.delimited(List::Block, |b| b.exp(last));
+            }
+            _ => {
+                enum Statement<'e> {
+                    Use(Loc),
+                    Item(&'e SequenceItem),
+                    Exp(&'e Exp),
+                }
+                let statements: Vec<_> = uses
+                    .iter()
+                    .map(|u| Statement::Use(u.loc))
+                    .chain(items.iter().map(Statement::Item))
+                    .chain(last.iter().map(Statement::Exp))
+                    .collect();
+                let loc_of = |s: &Statement| match s {
+                    Statement::Use(loc) => *loc,
+                    Statement::Item(item) => item.loc,
+                    Statement::Exp(e) => e.loc,
+                };
+
+                self.block_list(
+                    false,
+                    &statements,
+                    |s| start(loc_of(s)),
+                    |b, s| match s {
+                        Statement::Use(loc) => b.tokens_until(end(*loc)),
+                        Statement::Item(item) => b.sequence_item(item),
+                        Statement::Exp(e) => b.exp(e),
+                    },
+                );
+            }
+        }
+        self.tokens_until(end(loc));
+    }
+
+    fn sequence_item(&mut self, item: &SequenceItem) {
+        match &item.value {
+            SequenceItem_::Seq(e) => self.exp(e),
+            SequenceItem_::Declare(binds, _) => self.bind_list(binds),
+            SequenceItem_::Bind(binds, _, e) => {
+                self.bind_list(binds);
+                self.tokens_until(start(e.loc));
+                self.rhs(e);
+            }
+        }
+        self.tokens_until(end(item.loc));
+    }
+
+    /// Lay out the right-hand side of a `let`, assignment or constant, after its `=`. If it
+    /// doesn't fit on the same line, it is moved to the next line, unless it is a list or block
+    /// that can itself be broken over several lines.
+    fn rhs(&mut self, e: &Exp) {
+        use Exp_ as E;
+        match &e.value {
+            E::Call(..)
+            | E::Pack(..)
+            | E::Vector(..)
+            | E::Block(_)
+            | E::IfElse(..)
+            | E::Match(..)
+            | E::While(..)
+            | E::Loop(_)
+            | E::Lambda(..)
+            | E::ExpList(_)
+            | E::DotCall(..) => self.exp(e),
+            _ => self.group(|b| {
+                b.indent(|b| {
+                    b.line();
+                    b.exp(e)
+                })
+            }),
+        }
+    }
+
+    //**********************************************************************************************
+    // Bindings and patterns
+    //**********************************************************************************************
+
+    fn bind_list(&mut self, binds: &BindList) {
+        self.tokens_until(start(binds.loc));
+        if binds.value.len() != 1 && self.peek() == Some(Tok::LParen) {
+            self.delimited(List::Plain, |b| {
+                b.items(&binds.value, |bind| start(bind.loc), |b, bind| b.bind(bind))
+            });
+        } else {
+            for bind in &binds.value {
+                self.bind(bind);
+            }
+        }
+        self.tokens_until(end(binds.loc));
+    }
+
+    fn bind(&mut self, bind: &Bind) {
+        self.tokens_until(start(bind.loc));
+        if let Bind_::Unpack(_, fields) = &bind.value {
+            match fields {
+                FieldBindings::Named(fields) => {
+                    self.tokens_until_tok(Tok::LBrace, end(bind.loc));
+                    self.delimited(List::Fields, |b| {
+                        b.items(
+                            fields,
+                            |f| match f {
+                                Ellipsis::Binder((field, _)) => start(field.0.loc),
+                                Ellipsis::Ellipsis(loc) => start(*loc),
+                            },
+                            |b, f| match f {
+                                Ellipsis::Binder((_, bind)) => b.bind(bind),
+                                Ellipsis::Ellipsis(loc) => b.tokens_until(end(*loc)),
+                            },
+                        )
+                    });
+                }
+                FieldBindings::Positional(binds) => {
+                    self.tokens_until_tok(Tok::LParen, end(bind.loc));
+                    self.delimited(List::Items, |b| {
+                        b.items(
+                            binds,
+                            |f| match f {
+                                Ellipsis::Binder(bind) => start(bind.loc),
+                                Ellipsis::Ellipsis(loc) => start(*loc),
+                            },
+                            |b, f| match f {
+                                Ellipsis::Binder(bind) => b.bind(bind),
+                                Ellipsis::Ellipsis(loc) => b.tokens_until(end(*loc)),
+                            },
+                        )
+                    });
+                }
+            }
+        }
+        self.tokens_until(end(bind.loc));
+    }
+
+    fn match_arm(&mut self, arm: &MatchArm) {
+        self.pattern(&arm.value.pattern);
+        if let Some(guard) = &arm.value.guard {
+            self.tokens_until(start(guard.loc));
+            self.exp(guard);
+        }
+        self.tokens_until(start(arm.value.rhs.loc));
+        self.exp(&arm.value.rhs);
+        self.tokens_until(end(arm.loc));
+    }
+
+    fn pattern(&mut self, pattern: &MatchPattern) {
+        use MatchPattern_ as P;
+        self.tokens_until(start(pattern.loc));
+        match &pattern.value {
+            P::PositionalConstructor(_, pats) => {
+                self.tokens_until_tok(Tok::LParen, end(pattern.loc));
+                self.delimited(List::Items, |b| {
+                    b.items(
+                        &pats.value,
+                        |p| match p {
+                            Ellipsis::Binder(p) => start(p.loc),
+                            Ellipsis::Ellipsis(loc) => start(*loc),
+                        },
+                        |b, p| match p {
+                            Ellipsis::Binder(p) => b.pattern(p),
+                            Ellipsis::Ellipsis(loc) => b.tokens_until(end(*loc)),
+                        },
+                    )
+                });
+            }
+            P::FieldConstructor(_, fields) => {
+                self.tokens_until_tok(Tok::LBrace, end(pattern.loc));
+                self.delimited(List::Fields, |b| {
+                    b.items(
+                        &fields.value,
+                        |f| match f {
+                            Ellipsis::Binder((field, _)) => start(field.0.loc),
+                            Ellipsis::Ellipsis(loc) => start(*loc),
+                        },
+                        |b, f| match f {
+                            Ellipsis::Binder((_, p)) => b.pattern(p),
+                            Ellipsis::Ellipsis(loc) => b.tokens_until(end(*loc)),
+                        },
+                    )
+                });
+            }
+            P::Or(lhs, rhs) => {
+                self.pattern(lhs);
+                self.tokens_until(end(lhs.loc));
+                self.operator();
+                self.space();
+                self.pattern(rhs);
+            }
+            P::At(var, pat) => {
+                self.tokens_until(end(var.0.loc));
+                self.operator();
+                self.space();
+                self.pattern(pat);
+            }
+            P::Name(..) | P::Literal(_) => (),
+        }
+        self.tokens_until(end(pattern.loc));
+    }
+
+    //**********************************************************************************************
+    // Expressions
+    //**********************************************************************************************
+
+    fn exp(&mut self, e: &Exp) {
+        use Exp_ as E;
+        self.tokens_until(start(e.loc));
+        match &e.value {
+            E::Call(_, args) => {
+                self.tokens_until_tok(Tok::LParen, end(e.loc));
+                self.exps(List::Items, &args.value);
+            }
+            E::Pack(_, fields) => {
+                self.tokens_until_tok(Tok::LBrace, end(e.loc));
+                self.delimited(List::Fields, |b| {
+                    b.items(
+                        fields,
+                        |(field, _)| start(field.0.loc),
+                        |b, (field, e)| {
+                            b.tokens_until(end(field.0.loc));
+                            b.exp(e)
+                        },
+                    )
+                });
+            }
+            E::Vector(_, _, args) => {
+                self.tokens_until_tok(Tok::LBracket, end(e.loc));
+                self.exps(List::Items, &args.value);
+            }
+            E::IfElse(cond, then, else_) => self.group(|b| {
+                b.tokens_until(start(cond.loc));
+                b.exp(cond);
+                b.tokens_until(start(then.loc));
+                b.body(then);
+                if let Some(else_) = else_ {
+                    if !matches!(then.value, E::Block(_)) {
+                        b.line();
+                    }
+                    b.tokens_until(start(else_.loc));
+                    b.body(else_);
+                }
+            }),
+            E::Match(subject, arms) => {
+                self.tokens_until(start(subject.loc));
+                self.exp(subject);
+                self.tokens_until_tok(Tok::LBrace, end(e.loc));
+                self.block_list(
+                    false,
+                    &arms.value,
+                    |arm| start(arm.loc),
+                    |b, arm| b.match_arm(arm),
+                );
+            }
+            E::While(cond, body) => {
+                self.tokens_until(start(cond.loc));
+                self.exp(cond);
+                self.tokens_until(start(body.loc));
+                self.exp(body);
+            }
+            E::Loop(body) | E::Labeled(_, body) => {
+                self.tokens_until(start(body.loc));
+                self.exp(body);
+            }
+            E::Block(seq) => self.block(e.loc, seq),
+            E::Lambda(binds, _, body) => {
+                for (binds, _) in &binds.value {
+                    self.bind_list(binds);
+                }
+                self.tokens_until(start(body.loc));
+                self.exp(body);
+            }
+            E::ExpList(exps) => {
+                if self.peek() == Some(Tok::LParen) {
+                    self.exps(List::Plain, exps);
+                }
+            }
+            E::Assign(lhs, rhs) => {
+                self.exp(lhs);
+                self.tokens_until(start(rhs.loc));
+                self.rhs(rhs);
+            }
+            E::Parens(inner)
+            | E::Move(_, inner)
+            | E::Copy(_, inner)
+            | E::Abort(Some(inner))
+            | E::Return(_, Some(inner))
+            | E::Break(_, Some(inner))
+            | E::Dereference(inner)
+            | E::UnaryExp(_, inner)
+            | E::Borrow(_, inner)
+            | E::Cast(inner, _)
+            | E::Annotate(inner, _) => {
+                self.tokens_until(start(inner.loc));
+                self.exp(inner);
+            }
+            E::BinopExp(..) => self.binop(e),
+            E::Dot(..) | E::DotCall(..) => self.dot_chain(e),
+            E::Index(inner, args) => {
+                self.exp(inner);
+                self.tokens_until_tok(Tok::LBracket, end(e.loc));
+                self.exps(List::Plain, &args.value);
+            }
+            E::Value(_)
+            | E::Name(_)
+            | E::Unit
+            | E::Abort(None)
+            | E::Return(_, None)
+            | E::Break(_, None)
+            | E::Continue(_)
+            | E::Quant(..)
+            | E::Spec(_)
+            | E::UnresolvedError
+            | E::DotUnresolved(..) => (),
+        }
+        self.tokens_until(end(e.loc));
+    }
+
+    /// Lay out the body of an `if` or `else`: blocks (and `else if`) on the same line, and any
+    /// other expression on the next line if it doesn't fit.
+    fn body(&mut self, e: &Exp) {
+        if matches!(e.value, Exp_::Block(_) | Exp_::IfElse(..)) {
+            self.exp(e);
+        } else {
+            self.group(|b| {
+                b.indent(|b| {
+                    b.line();
+                    b.exp(e);
+                })
+            });
+        }
+    }
+
+    /// Lay out a chain of binary operators, e.g. `a + b + c`, on one line if it fits, and
+    /// otherwise breaking after every operator.
+    fn binop(&mut self, e: &Exp) {
+        let Exp_::BinopExp(_, op, _) = &e.value else {
+            return self.exp(e);
+        };
+
+        // Operators of the same precedence are laid out as one chain, e.g. `a + b - c`.
+        let mut operands = vec![];
+        let mut lhs = e;
+        while let Exp_::BinopExp(l, o, r) = &lhs.value {
+            if precedence(o.value) != precedence(op.value) {
+                break;
+            }
+            operands.push((o.loc, &**r));
+            lhs = l;
+        }
+        operands.reverse();
+
+        self.group(|b| {
+            b.exp(lhs);
+            b.indent(|b| {
+                for (op, rhs) in &operands {
+                    b.tokens_until(start(*op));
+                    b.operator();
+                    b.line();
+                    b.exp(rhs);
+                }
+            });
+        });
+    }
+
+    /// Lay out a chain of field accesses and method calls, e.g. `a.b().c().d()`, breaking before
+    /// each `.` if it contains more than two calls and doesn't fit on one line.
+    fn dot_chain(&mut self, e: &Exp) {
+        let mut links = vec![];
+        let mut root = e;
+        while let Exp_::Dot(r, _, _) | Exp_::DotCall(r, _, _, _, _, _) = &root.value {
+            links.push(root);
+            root = r;
+        }
+        links.reverse();
+
+        let calls = links
+            .iter()
+            .filter(|l| matches!(l.value, Exp_::DotCall(..)))
+            .count();
+
+        let link = |b: &mut Self, link: &Exp| {
+            if let Exp_::DotCall(_, _, _, _, _, args) = &link.value {
+                b.tokens_until_tok(Tok::LParen, end(link.loc));
+                b.exps(List::Items, &args.value);
+            }
+            b.tokens_until(end(link.loc));
+        };
+
+        if calls < 3 {
+            self.exp(root);
+            for l in links {
+                link(self, l);
+            }
+            return;
+        }
+
+        self.group(|b| {
+            b.exp(root);
+            b.indent(|b| {
+                for l in links {
+                    if matches!(l.value, Exp_::DotCall(..)) {
+                        b.tokens_until(start(l.loc).max(end(root.loc)));
+                        b.softline();
+                    }
+                    link(b, l);
+                }
+            });
+        });
+    }
+}
+
+/// Whether `e` is short and simple enough to precede a lambda that hugs the delimiters of an
+/// argument list.
+fn is_simple(e: &Exp) -> bool {
+    use Exp_ as E;
+    match &e.value {
+        E::Value(_) | E::Name(_) | E::Unit => true,
+        E::Move(_, e) | E::Copy(_, e) | E::Borrow(_, e) | E::Dereference(e) | E::Dot(e, _, _) => {
+            is_simple(e)
+        }
+        _ => false,
+    }
+}
+
+fn precedence(op: BinOp_) -> u8 {
+    use BinOp_ as B;
+    match op {
+        B::Range => 0,
+        B::Implies | B::Iff => 1,
+        B::Or => 2,
+        B::And => 3,
+        B::Eq | B::Neq | B::Lt | B::Gt | B::Le | B::Ge => 4,
+        B::BitOr => 5,
+        B::Xor => 6,
+        B::BitAnd => 7,
+        B::Shl | B::Shr => 8,
+        B::Add | B::Sub => 9,
+        B::Mul | B::Div | B::Mod => 10,
+    }
+}
+
+fn start(loc: Loc) -> usize {
+    loc.start() as usize
+}
+
+fn end(loc: Loc) -> usize {
+    loc.end() as usize
+}
+
+fn attributes_start(attributes: &[Attributes], loc: Loc) -> usize {
+    attributes.first().map_or(start(loc), |a| start(a.loc))
+}
+
+fn member_start(member: &ModuleMember) -> usize {
+    match member {
+        ModuleMember::Function(f) => attributes_start(&f.attributes, f.loc),
+        ModuleMember::Struct(s) => attributes_start(&s.attributes, s.loc),
+        ModuleMember::Enum(e) => attributes_start(&e.attributes, e.loc),
+        ModuleMember::Use(u) => attributes_start(&u.attributes, u.loc),
+        ModuleMember::Friend(f) => attributes_start(&f.attributes, f.loc),
+        ModuleMember::Constant(c) => attributes_start(&c.attributes, c.loc),
+        ModuleMember::Spec(s) => start(s.loc),
+    }
+}
+
+fn column_of(source: &str, offset: usize) -> usize {
+    let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+    source[line_start..offset].chars().count()
+}
+
+//**************************************************************************************************
+// Spacing
+//**************************************************************************************************
+
+fn is_name(tok: Tok) -> bool {
+    matches!(
+        tok,
+        Tok::Identifier | Tok::RestrictedIdentifier | Tok::SyntaxIdentifier
+    )
+}
+
+/// Whether `next` should be separated from `prev` by a space, when neither is laid out as part of
+/// an expression (binary operators are spaced when the expression is laid out). In this position
+/// `<` and `>` are always the delimiters of type arguments or parameters, `&` and `*` are always
+/// prefixes, and `|` always delimits the parameters of a lambda (or function type).
+fn spaced(prev: Tok, next: Tok, in_pipes: bool) -> bool {
+    use Tok::*;
+    match (prev, next) {
+        (_, Comma | Semicolon | RParen | RBracket | Colon) => false,
+        (LParen | LBracket, _) => false,
+        (Period | ColonColon, _) | (_, Period | ColonColon) => false,
+        (NumSign | AtSign | Exclaim | Amp | Star, _) => false,
+        (Less, _) | (_, Less | Greater | GreaterGreater) => false,
+        (Pipe, _) if in_pipes => false,
+        (_, Pipe) if in_pipes => false,
+        (p, Exclaim | LParen) if is_name(p) => false,
+        (Greater | GreaterGreater, LParen) => false,
+        (p, LBracket) if is_name(p) => false,
+        (Greater | GreaterGreater | RParen | RBracket, LBracket) => false,
+        (Public, LParen) => false,
+        (LBrace, _) | (_, RBrace) => false,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(source: &str) -> String {
+        format_source(source, Edition::E2024, &FormatOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_indentation_and_spacing() {
+        let source = "module 0x1::m{\nfun f(x:u64,y : u64):u64{\nlet z=x+y ;\n  z\n}\n}\n";
+        let expected = "\
+module 0x1::m {
+    fun f(x: u64, y: u64): u64 {
+        let z = x + y;
+        z
+    }
+}
+";
+        assert_eq!(fmt(source), expected);
+    }
+
+    #[test]
+    fn test_comments_preserved() {
+        let source = "\
+module 0x1::m {
+  // leading
+      /// doc
+  fun f() { // trailing
+    /* inline */ abort 0
+  }
+}
+";
+        let expected = "\
+module 0x1::m {
+    // leading
+    /// doc
+    fun f() { // trailing
+        /* inline */ abort 0
+    }
+}
+";
+        assert_eq!(fmt(source), expected);
+    }
+
+    #[test]
+    fn test_blank_lines_collapsed() {
+        let source = "module 0x1::m {\n\n\nfun f() {}\n\n\n\nfun g() {}\n\n}\n";
+        let expected = "module 0x1::m {\n    fun f() {}\n\n    fun g() {}\n}\n";
+        assert_eq!(fmt(source), expected);
+    }
+
+    #[test]
+    fn test_reflow() {
+        let source = "\
+module 0x1::m {
+fun f(v: vector<vector<u8>>): bool {
+let b = v.length() > 0 &&
+v[0].length() < 10;
+g(first_argument, second_argument, third_argument) && h(fourth_argument, fifth_argument, sixth_argument)
+}
+}
+";
+        let expected = "\
+module 0x1::m {
+    fun f(v: vector<vector<u8>>): bool {
+        let b = v.length() > 0 && v[0].length() < 10;
+        g(first_argument, second_argument, third_argument) &&
+            h(fourth_argument, fifth_argument, sixth_argument)
+    }
+}
+";
+        assert_eq!(fmt(source), expected);
+
+        let narrow = FormatOptions {
+            max_width: 40,
+            ..FormatOptions::default()
+        };
+        let formatted = format_source(source, Edition::E2024, &narrow).unwrap();
+        let expected = "\
+module 0x1::m {
+    fun f(v: vector<vector<u8>>): bool {
+        let b =
+            v.length() > 0 &&
+                v[0].length() < 10;
+        g(
+            first_argument,
+            second_argument,
+            third_argument,
+        ) &&
+            h(
+                fourth_argument,
+                fifth_argument,
+                sixth_argument,
+            )
+    }
+}
+";
+        assert_eq!(formatted, expected);
+    }
+
+    #[test]
+    fn test_operator_spacing() {
+        let source = "\
+module 0x1::m {
+fun f(v: vector < u64 >, x: u64): bool {
+let y = x-1;
+let g = |a|a|1;
+let h = | a : u64 , b | -> u64 { a-b };
+v.length()<y && g(y)>h(x,y)
+}
+}
+";
+        let expected = "\
+module 0x1::m {
+    fun f(v: vector<u64>, x: u64): bool {
+        let y = x - 1;
+        let g = |a| a | 1;
+        let h = |a: u64, b| -> u64 { a - b };
+        v.length() < y && g(y) > h(x, y)
+    }
+}
+";
+        assert_eq!(fmt(source), expected);
+    }
+
+    #[test]
+    fn test_idempotent() {
+        let source = "\
+module 0x1::m {
+    public(package) fun f(x: &mut u64) {
+        *x = *x + 1;
+        if (*x > 10) { abort 0 } else { assert!(!(*x == 0), 1) };
+    }
+}
+";
+        assert_eq!(fmt(source), source);
+    }
+
+    #[test]
+    fn test_range() {
+        let source = "module 0x1::m {\nfun f( ) {}\nfun g( ) {}\n}\n";
+        let formatted =
+            format_source_range(source, Edition::E2024, &FormatOptions::default(), 2..3).unwrap();
+        assert_eq!(
+            formatted,
+            "module 0x1::m {\nfun f( ) {}\n    fun g() {}\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = "module 0x1::m { fun f( }";
+        assert!(format_source(source, Edition::E2024, &FormatOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,634 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for fuzzing tests whose arguments are generated (`#[random_test]`s): generation of
+//! inputs biased towards interesting values, coverage-guided mutation of inputs, shrinking of
+//! failing inputs to minimal counterexamples, and a corpus of failing inputs that is persisted
+//! between runs.
+
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::TypeTag,
+    runtime_value::{MoveTypeLayout, MoveValue},
+    u256::U256,
+};
+use move_coverage::coverage_map::ExecCoverageMap;
+use move_trace_format::{
+    format::TraceEvent,
+    interface::{Tracer, Writer},
+};
+use rand::{Rng, rngs::StdRng};
+use std::{
+    fmt::Write as _,
+    io::Write as _,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// The maximum number of times a test is executed while shrinking one of its failing inputs.
+const MAX_SHRINK_EXECUTIONS: usize = 1000;
+
+/// The maximum length of generated vectors.
+const MAX_VECTOR_LEN: usize = 1024;
+
+/// The maximum length of vectors generated when biasing towards small vectors.
+const SMALL_VECTOR_LEN: usize = 8;
+
+//**************************************************************************************************
+// Generation
+//**************************************************************************************************
+
+/// Generate a value of type `ty`, biased towards values that are likely to hit edge cases (zero,
+/// maximum values, powers of two, empty and small vectors, etc.)
+pub(crate) fn generate_value(rng: &mut StdRng, ty: &TypeTag) -> MoveValue {
+    match ty {
+        TypeTag::Bool => MoveValue::Bool(rng.r#gen::<bool>()),
+        TypeTag::Address => MoveValue::Address(generate_address(rng)),
+        TypeTag::Vector(ty) => {
+            let len = if rng.r#gen::<bool>() {
+                rng.gen_range(0..=SMALL_VECTOR_LEN)
+            } else {
+                rng.gen_range(0..MAX_VECTOR_LEN)
+            };
+            MoveValue::Vector((0..len).map(|_| generate_value(rng, ty)).collect())
+        }
+        TypeTag::U8
+        | TypeTag::U16
+        | TypeTag::U32
+        | TypeTag::U64
+        | TypeTag::U128
+        | TypeTag::U256 => {
+            let bits = int_bits(ty).unwrap();
+            let value = if rng.r#gen::<bool>() {
+                interesting_int(rng, bits)
+            } else {
+                rng.r#gen::<U256>() & int_max(bits)
+            };
+            int_value(value, bits)
+        }
+        TypeTag::Struct(_) => {
+            unreachable!(
+                "Structs are not supported as generated values in unit tests and cannot get to this point"
+            )
+        }
+        TypeTag::Signer => unreachable!("Signer arguments not allowed"),
+    }
+}
+
+/// Mutate one of the generated arguments (those with a type in `generated_types`) of a test.
+pub(crate) fn mutate_arguments(
+    rng: &mut StdRng,
+    arguments: &[MoveValue],
+    generated_types: &[Option<TypeTag>],
+) -> Vec<MoveValue> {
+    let mut arguments = arguments.to_vec();
+    let generated: Vec<_> = generated_types
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, ty)| Some((idx, ty.as_ref()?)))
+        .collect();
+    if generated.is_empty() {
+        return arguments;
+    }
+
+    let (idx, ty) = generated[rng.gen_range(0..generated.len())];
+    arguments[idx] = mutate_value(rng, &arguments[idx], ty);
+    arguments
+}
+
+fn mutate_value(rng: &mut StdRng, value: &MoveValue, ty: &TypeTag) -> MoveValue {
+    match (value, ty) {
+        (MoveValue::Bool(b), _) => MoveValue::Bool(!b),
+        (MoveValue::Address(_), _) => MoveValue::Address(generate_address(rng)),
+        (MoveValue::Vector(elems), TypeTag::Vector(ty)) => {
+            let mut elems = elems.clone();
+            match rng.gen_range(0..3) {
+                _ if elems.is_empty() => elems.push(generate_value(rng, ty)),
+                0 => elems.insert(rng.gen_range(0..=elems.len()), generate_value(rng, ty)),
+                1 => {
+                    elems.remove(rng.gen_range(0..elems.len()));
+                }
+                _ => {
+                    let idx = rng.gen_range(0..elems.len());
+                    elems[idx] = mutate_value(rng, &elems[idx], ty);
+                }
+            }
+            MoveValue::Vector(elems)
+        }
+        _ => {
+            let Some((value, bits)) = as_int(value) else {
+                return value.clone();
+            };
+            let max = int_max(bits);
+            let mutated = match rng.gen_range(0..4) {
+                0 => interesting_int(rng, bits),
+                1 => value.wrapping_add(U256::from(rng.gen_range(1u8..=16))) & max,
+                2 => value.wrapping_sub(U256::from(rng.gen_range(1u8..=16))) & max,
+                _ => value ^ (U256::one() << rng.gen_range(0..bits)),
+            };
+            int_value(mutated, bits)
+        }
+    }
+}
+
+fn generate_address(rng: &mut StdRng) -> AccountAddress {
+    match rng.gen_range(0..4) {
+        0 => AccountAddress::ZERO,
+        1 => {
+            let mut bytes = [0u8; AccountAddress::LENGTH];
+            bytes[AccountAddress::LENGTH - 1] = rng.gen_range(1..=16);
+            AccountAddress::new(bytes)
+        }
+        _ => AccountAddress::from_bytes(rng.r#gen::<[u8; 32]>()).unwrap(),
+    }
+}
+
+/// An integer of the given width that is likely to hit edge cases.
+fn interesting_int(rng: &mut StdRng, bits: u32) -> U256 {
+    let max = int_max(bits);
+    let power = U256::one() << rng.gen_range(0..bits);
+    match rng.gen_range(0..6) {
+        0 => U256::zero(),
+        1 => U256::from(rng.gen_range(1u8..=16)),
+        2 => max,
+        3 => max - U256::from(rng.gen_range(1u8..=16)),
+        4 => power,
+        _ => power - U256::one(),
+    }
+}
+
+//**************************************************************************************************
+// Shrinking
+//**************************************************************************************************
+
+/// Shrink a failing input of a test to a minimal one, for which `still_fails` still holds. Only
+/// the arguments marked as `generated` are shrunk, one simplification at a time, until no
+/// simplification of any of them fails the test (or until the test has been run too many times).
+pub(crate) fn shrink_arguments(
+    arguments: Vec<MoveValue>,
+    generated: &[bool],
+    mut still_fails: impl FnMut(&[MoveValue]) -> bool,
+) -> Vec<MoveValue> {
+    let mut current = arguments;
+    let mut executions = 0;
+    'shrink: loop {
+        for idx in (0..current.len()).filter(|idx| generated[*idx]) {
+            let mut simpler = None;
+            for candidate in shrink_value(&current[idx]) {
+                if executions == MAX_SHRINK_EXECUTIONS {
+                    break 'shrink;
+                }
+                executions += 1;
+
+                let mut arguments = current.clone();
+                arguments[idx] = candidate;
+                if still_fails(&arguments) {
+                    simpler = Some(arguments);
+                    break;
+                }
+            }
+            if let Some(arguments) = simpler {
+                current = arguments;
+                continue 'shrink;
+            }
+        }
+        break;
+    }
+    current
+}
+
+/// Simplifications of a value, simplest first.
+fn shrink_value(value: &MoveValue) -> Box<dyn Iterator<Item = MoveValue> + '_> {
+    match value {
+        MoveValue::Bool(true) => Box::new(std::iter::once(MoveValue::Bool(false))),
+        MoveValue::Address(addr) if *addr != AccountAddress::ZERO => {
+            Box::new(std::iter::once(MoveValue::Address(AccountAddress::ZERO)))
+        }
+        MoveValue::Vector(elems) => {
+            let len = elems.len();
+            let empty = (len > 0).then(Vec::new);
+            let halves = (len > 1)
+                .then(|| [elems[..len / 2].to_vec(), elems[len / 2..].to_vec()])
+                .into_iter()
+                .flatten();
+            let removals = (0..len).map(move |idx| {
+                let mut elems = elems.clone();
+                elems.remove(idx);
+                elems
+            });
+            let elem_shrinks = (0..len).flat_map(move |idx| {
+                shrink_value(&elems[idx]).map(move |elem| {
+                    let mut elems = elems.clone();
+                    elems[idx] = elem;
+                    elems
+                })
+            });
+            Box::new(
+                empty
+                    .into_iter()
+                    .chain(halves)
+                    .chain(removals)
+                    .chain(elem_shrinks)
+                    .map(MoveValue::Vector),
+            )
+        }
+        _ => {
+            let Some((value, bits)) = as_int(value) else {
+                return Box::new(std::iter::empty());
+            };
+            if value == U256::zero() {
+                return Box::new(std::iter::empty());
+            }
+            // Try zero, and then move towards zero in decreasing steps: `value - value / 2`,
+            // `value - value / 4`, ..., `value - 1`.
+            let steps = (1..256)
+                .map_while(move |shift| value.checked_shr(shift))
+                .take_while(|step| *step != U256::zero())
+                .map(move |step| value - step);
+            Box::new(
+                std::iter::once(U256::zero())
+                    .chain(steps)
+                    .chain(std::iter::once(value - U256::one()))
+                    .map(move |value| int_value(value, bits)),
+            )
+        }
+    }
+}
+
+//**************************************************************************************************
+// Coverage
+//**************************************************************************************************
+
+/// A tracer that records the instructions executed by a test in a coverage map.
+pub(crate) struct CoverageTracer {
+    coverage: Arc<Mutex<ExecCoverageMap>>,
+    // The module address, module name and function name of each open frame.
+    frames: Vec<(AccountAddress, Identifier, Identifier)>,
+}
+
+impl CoverageTracer {
+    pub(crate) fn new(coverage: Arc<Mutex<ExecCoverageMap>>) -> Self {
+        Self {
+            coverage,
+            frames: vec![],
+        }
+    }
+}
+
+impl Tracer for CoverageTracer {
+    fn notify(&mut self, event: &TraceEvent, _writer: Writer<'_>) {
+        match event {
+            TraceEvent::OpenFrame { frame, .. } => self.frames.push((
+                *frame.module.address(),
+                frame.module.name().to_owned(),
+                Identifier::new(frame.function_name.as_str()).unwrap(),
+            )),
+            TraceEvent::CloseFrame { .. } => {
+                self.frames.pop();
+            }
+            TraceEvent::Instruction { pc, .. } => {
+                if let Some((module_addr, module_name, func_name)) = self.frames.last() {
+                    self.coverage.lock().unwrap().insert(
+                        *module_addr,
+                        module_name.clone(),
+                        func_name.clone(),
+                        *pc as u64,
+                    );
+                }
+            }
+            TraceEvent::Effect(_) | TraceEvent::External(_) => (),
+        }
+    }
+}
+
+/// Merge the coverage of one execution of a test into the coverage of all of its executions so
+/// far. Returns `true` if the execution covered instructions that were not covered before.
+pub(crate) fn merge_coverage(total: &mut ExecCoverageMap, execution: ExecCoverageMap) -> bool {
+    let mut new_coverage = false;
+    for ((module_addr, module_name), module_map) in execution.module_maps {
+        for (func_name, pcs) in module_map.function_maps {
+            for (pc, count) in pcs {
+                new_coverage |= !total
+                    .module_maps
+                    .get(&(module_addr, module_name.clone()))
+                    .and_then(|module_map| module_map.get_function_coverage(&func_name))
+                    .is_some_and(|func_map| func_map.contains_key(&pc));
+                total.insert_multi(
+                    module_addr,
+                    module_name.clone(),
+                    func_name.clone(),
+                    pc,
+                    count,
+                );
+            }
+        }
+    }
+    new_coverage
+}
+
+//**************************************************************************************************
+// Corpus
+//**************************************************************************************************
+
+/// The failing inputs found for a test, persisted to a file so that they are replayed (as
+/// regression tests) before any new inputs are generated. Each line of the file holds the
+/// BCS-encoded, hex-encoded generated arguments of one input, separated by spaces, followed by a
+/// comment showing their values.
+pub(crate) struct Corpus {
+    path: PathBuf,
+}
+
+impl Corpus {
+    /// The corpus for test `test_name` in directory `dir`.
+    pub(crate) fn new(dir: &Path, test_name: &str) -> Self {
+        Self {
+            path: dir.join(format!("{test_name}.corpus")),
+        }
+    }
+
+    /// Load the inputs in the corpus, skipping any that don't match the test's `generated_types`
+    /// (e.g. because the test's signature changed since they were saved).
+    pub(crate) fn load(&self, generated_types: &[&TypeTag]) -> Vec<Vec<MoveValue>> {
+        let Ok(contents) = std::fs::read_to_string(&self.path) else {
+            return vec![];
+        };
+        let layouts: Option<Vec<_>> = generated_types.iter().map(|ty| type_layout(ty)).collect();
+        let Some(layouts) = layouts else {
+            return vec![];
+        };
+
+        contents
+            .lines()
+            .filter_map(|line| {
+                let encoded: Vec<_> = line.split('#').next()?.split_whitespace().collect();
+                if encoded.is_empty() || encoded.len() != layouts.len() {
+                    return None;
+                }
+                encoded
+                    .iter()
+                    .zip(&layouts)
+                    .map(|(hex, layout)| {
+                        MoveValue::simple_deserialize(&decode_hex(hex)?, layout).ok()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Add an input (the values of the generated arguments of a test) to the corpus, unless it
+    /// is already there.
+    pub(crate) fn add(&self, generated: &[MoveValue]) -> std::io::Result<()> {
+        let encoded = generated
+            .iter()
+            .map(|value| {
+                value
+                    .simple_serialize()
+                    .unwrap()
+                    .iter()
+                    .fold(String::new(), |mut hex, byte| {
+                        write!(hex, "{byte:02x}").unwrap();
+                        hex
+                    })
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let existing = std::fs::read_to_string(&self.path).unwrap_or_default();
+        if existing
+            .lines()
+            .any(|line| line.split('#').next().unwrap_or_default().trim() == encoded)
+        {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let values = generated
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{encoded} # {values}")
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(hex.get(idx..idx + 2)?, 16).ok())
+        .collect()
+}
+
+fn type_layout(ty: &TypeTag) -> Option<MoveTypeLayout> {
+    Some(match ty {
+        TypeTag::Bool => MoveTypeLayout::Bool,
+        TypeTag::U8 => MoveTypeLayout::U8,
+        TypeTag::U16 => MoveTypeLayout::U16,
+        TypeTag::U32 => MoveTypeLayout::U32,
+        TypeTag::U64 => MoveTypeLayout::U64,
+        TypeTag::U128 => MoveTypeLayout::U128,
+        TypeTag::U256 => MoveTypeLayout::U256,
+        TypeTag::Address => MoveTypeLayout::Address,
+        TypeTag::Vector(ty) => MoveTypeLayout::Vector(Box::new(type_layout(ty)?)),
+        TypeTag::Signer | TypeTag::Struct(_) => return None,
+    })
+}
+
+//**************************************************************************************************
+// Integers
+//**************************************************************************************************
+
+fn int_bits(ty: &TypeTag) -> Option<u32> {
+    Some(match ty {
+        TypeTag::U8 => 8,
+        TypeTag::U16 => 16,
+        TypeTag::U32 => 32,
+        TypeTag::U64 => 64,
+        TypeTag::U128 => 128,
+        TypeTag::U256 => 256,
+        _ => return None,
+    })
+}
+
+fn int_max(bits: u32) -> U256 {
+    U256::max_value().checked_shr(256 - bits).unwrap()
+}
+
+fn as_int(value: &MoveValue) -> Option<(U256, u32)> {
+    Some(match value {
+        MoveValue::U8(v) => (U256::from(*v), 8),
+        MoveValue::U16(v) => (U256::from(*v), 16),
+        MoveValue::U32(v) => (U256::from(*v), 32),
+        MoveValue::U64(v) => (U256::from(*v), 64),
+        MoveValue::U128(v) => (U256::from(*v), 128),
+        MoveValue::U256(v) => (*v, 256),
+        _ => return None,
+    })
+}
+
+fn int_value(value: U256, bits: u32) -> MoveValue {
+    match bits {
+        8 => MoveValue::U8(value.unchecked_as_u8()),
+        16 => MoveValue::U16(value.unchecked_as_u16()),
+        32 => MoveValue::U32(value.unchecked_as_u32()),
+        64 => MoveValue::U64(value.unchecked_as_u64()),
+        128 => MoveValue::U128(value.unchecked_as_u128()),
+        _ => MoveValue::U256(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn u64_vec(values: &[u64]) -> MoveValue {
+        MoveValue::Vector(values.iter().copied().map(MoveValue::U64).collect())
+    }
+
+    /// Generated values have the requested type, and generation hits edge cases.
+    #[test]
+    fn test_generate_value() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..100 {
+            assert!(matches!(
+                generate_value(&mut rng, &TypeTag::U8),
+                MoveValue::U8(_)
+            ));
+            assert!(matches!(
+                generate_value(&mut rng, &TypeTag::U256),
+                MoveValue::U256(_)
+            ));
+            assert!(matches!(
+                generate_value(&mut rng, &TypeTag::Address),
+                MoveValue::Address(_)
+            ));
+
+            let MoveValue::Vector(elems) =
+                generate_value(&mut rng, &TypeTag::Vector(Box::new(TypeTag::U16)))
+            else {
+                panic!("Expected a vector");
+            };
+            assert!(elems.len() < MAX_VECTOR_LEN);
+            assert!(elems.iter().all(|elem| matches!(elem, MoveValue::U16(_))));
+        }
+
+        let values: Vec<_> = (0..1000)
+            .map(|_| generate_value(&mut rng, &TypeTag::U64))
+            .collect();
+        assert!(values.contains(&MoveValue::U64(0)));
+        assert!(values.contains(&MoveValue::U64(u64::MAX)));
+    }
+
+    /// Mutation only changes generated arguments, and preserves their types.
+    #[test]
+    fn test_mutate_arguments() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let arguments = vec![MoveValue::U64(42), MoveValue::U32(7)];
+        let generated_types = vec![None, Some(TypeTag::U32)];
+
+        for _ in 0..100 {
+            let mutated = mutate_arguments(&mut rng, &arguments, &generated_types);
+            assert_eq!(mutated[0], MoveValue::U64(42));
+            assert!(matches!(mutated[1], MoveValue::U32(_)));
+        }
+
+        // Without generated arguments, there is nothing to mutate.
+        let mutated = mutate_arguments(&mut rng, &arguments, &[None, None]);
+        assert_eq!(mutated, arguments);
+    }
+
+    /// Integers are shrunk to the smallest value that still fails.
+    #[test]
+    fn test_shrink_int() {
+        let shrunk = shrink_arguments(
+            vec![MoveValue::U64(1000)],
+            &[true],
+            |args| matches!(args[0], MoveValue::U64(x) if x >= 100),
+        );
+        assert_eq!(shrunk, vec![MoveValue::U64(100)]);
+    }
+
+    /// Vectors are shrunk by removing elements, and by shrinking the elements that remain.
+    #[test]
+    fn test_shrink_vector() {
+        let shrunk = shrink_arguments(vec![u64_vec(&[3, 42, 7])], &[true], |args| {
+            let MoveValue::Vector(elems) = &args[0] else {
+                return false;
+            };
+            elems
+                .iter()
+                .any(|elem| matches!(elem, MoveValue::U64(x) if *x >= 10))
+        });
+        assert_eq!(shrunk, vec![u64_vec(&[10])]);
+    }
+
+    /// Arguments that were not generated are left as they are, and arguments that don't affect the
+    /// failure are shrunk to their simplest values.
+    #[test]
+    fn test_shrink_generated_only() {
+        let arguments = vec![
+            MoveValue::U64(42),
+            MoveValue::Bool(true),
+            MoveValue::Address(AccountAddress::ONE),
+        ];
+        let shrunk = shrink_arguments(arguments, &[false, true, true], |_| true);
+        assert_eq!(
+            shrunk,
+            vec![
+                MoveValue::U64(42),
+                MoveValue::Bool(false),
+                MoveValue::Address(AccountAddress::ZERO),
+            ]
+        );
+    }
+
+    /// Inputs added to the corpus are loaded back, once each, and only for matching types.
+    #[test]
+    fn test_corpus_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let corpus = Corpus::new(dir.path(), "0x6__test__foo");
+
+        let types = [
+            TypeTag::U64,
+            TypeTag::Vector(Box::new(TypeTag::U8)),
+            TypeTag::Address,
+        ];
+        let types: Vec<_> = types.iter().collect();
+        assert!(corpus.load(&types).is_empty());
+
+        let first = vec![
+            MoveValue::U64(5),
+            MoveValue::Vector(vec![MoveValue::U8(1), MoveValue::U8(2)]),
+            MoveValue::Address(AccountAddress::ONE),
+        ];
+        let second = vec![
+            MoveValue::U64(0),
+            MoveValue::Vector(vec![]),
+            MoveValue::Address(AccountAddress::ZERO),
+        ];
+
+        corpus.add(&first).unwrap();
+        corpus.add(&second).unwrap();
+        corpus.add(&first).unwrap();
+        assert_eq!(corpus.load(&types), vec![first, second]);
+
+        // Inputs that don't match the test's signature are skipped.
+        assert!(corpus.load(&[&TypeTag::U64]).is_empty());
+        assert!(
+            corpus
+                .load(&[&TypeTag::Bool, &TypeTag::Bool, &TypeTag::Bool])
+                .is_empty()
+        );
+    }
+}
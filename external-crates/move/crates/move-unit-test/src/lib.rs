@@ -4,11 +4,12 @@
 
 pub mod cargo_runner;
 pub mod extensions;
+mod fuzz;
 pub mod test_reporter;
 pub mod test_runner;
 
-use crate::test_runner::TestRunner;
-use anyhow::{Result, bail};
+use crate::{test_reporter::TestReport, test_runner::TestRunner};
+use anyhow::{Context, Result, bail};
 use clap::*;
 use move_binary_format::CompiledModule;
 use move_command_line_common::files::verify_and_create_named_address_mapping;
@@ -22,7 +23,7 @@ use move_compiler::{
 use move_core_types::language_storage::ModuleId;
 use move_vm_runtime::native_functions::NativeFunctionTable;
 use move_vm_test_utils::gas_schedule::CostTable;
-use std::{collections::BTreeMap, io::Write, marker::Send, sync::Mutex};
+use std::{collections::BTreeMap, io::Write, marker::Send, path::PathBuf, sync::Mutex};
 
 /// The default value bounding the amount of gas consumed in a test.
 const DEFAULT_EXECUTION_BOUND: u64 = 1_000_000;
@@ -32,7 +33,11 @@ const DEFAULT_RAND_ITERS: u64 = 10;
 
 const RAND_NUM_ITERS_FLAG: &str = "rand-num-iters";
 const SEED_FLAG: &str = "seed";
+const FUZZ_FLAG: &str = "fuzz";
+const FUZZ_CORPUS_DIR_FLAG: &str = "fuzz-corpus-dir";
 const TRACE_FLAG: &str = "trace-execution";
+const PROFILE_GAS_FLAG: &str = "profile-gas";
+const GAS_REGRESSION_THRESHOLD_FLAG: &str = "gas-regression-threshold";
 
 #[derive(Debug, Parser, Clone)]
 #[clap(author, version, about)]
@@ -125,6 +130,39 @@ pub struct UnitTestingConfig {
     // Enable tracing for tests
     #[clap(long = TRACE_FLAG)]
     pub trace_execution: bool,
+
+    /// Write a gas profile of each test, in the folded stack format used by flamegraph tools
+    #[clap(long = PROFILE_GAS_FLAG)]
+    pub profile_gas: bool,
+
+    /// Fuzz tests with generated arguments: bias generated arguments towards edge cases, mutate
+    /// arguments that cover new instructions into new arguments (in builds with the `tracing`
+    /// feature), and shrink failing arguments to minimal ones
+    #[clap(long = FUZZ_FLAG)]
+    pub fuzz: bool,
+
+    /// Directory where the inputs that tests with generated arguments fail with are saved, to be
+    /// replayed first on later runs
+    #[clap(long = FUZZ_CORPUS_DIR_FLAG)]
+    pub fuzz_corpus_dir: Option<PathBuf>,
+
+    /// Write a JUnit XML report of the test results to this file
+    #[clap(long = "report-junit")]
+    pub junit_report: Option<PathBuf>,
+
+    /// Write a JSON report of the test results to this file
+    #[clap(long = "report-json")]
+    pub json_report: Option<PathBuf>,
+
+    /// JSON report of an earlier run to compare the gas used by tests against. The run fails if a
+    /// test uses more gas than in the baseline (by more than the regression threshold). Tests with
+    /// generated arguments are not compared
+    #[clap(long = "gas-baseline")]
+    pub gas_baseline: Option<PathBuf>,
+
+    /// Percentage by which a test may use more gas than in the gas baseline
+    #[clap(long = GAS_REGRESSION_THRESHOLD_FLAG, default_value = "0")]
+    pub gas_regression_threshold: f64,
 }
 
 fn format_module_id(
@@ -157,6 +195,13 @@ impl UnitTestingConfig {
             seed: None,
             deterministic_generation: false,
             trace_execution: false,
+            profile_gas: false,
+            fuzz: false,
+            fuzz_corpus_dir: None,
+            junit_report: None,
+            json_report: None,
+            gas_baseline: None,
+            gas_regression_threshold: 0.0,
         }
     }
 
@@ -253,6 +298,21 @@ impl UnitTestingConfig {
             None => DEFAULT_RAND_ITERS,
         };
 
+        if self.gas_regression_threshold < 0.0 {
+            bail!(format!(
+                "Invalid argument -- '{GAS_REGRESSION_THRESHOLD_FLAG}' set to a negative value. \
+                '{GAS_REGRESSION_THRESHOLD_FLAG}' must be a non-negative percentage."
+            ))
+        }
+        let gas_baseline = self
+            .gas_baseline
+            .as_ref()
+            .map(|path| {
+                TestReport::load_json(path)
+                    .with_context(|| format!("Unable to read gas baseline from {}", path.display()))
+            })
+            .transpose()?;
+
         if self.list {
             for (module_id, module_test_plan) in &test_plan.module_tests {
                 for test_name in module_test_plan.tests.keys() {
@@ -273,6 +333,11 @@ impl UnitTestingConfig {
         } else {
             None
         };
+        let gas_profile_location = if self.profile_gas {
+            Some("gas_profiles".to_string())
+        } else {
+            None
+        };
         let mut test_runner = TestRunner::new(
             self.gas_limit.unwrap_or(DEFAULT_EXECUTION_BOUND),
             self.num_threads,
@@ -281,6 +346,9 @@ impl UnitTestingConfig {
             rand_num_iters,
             self.deterministic_generation,
             trace_location,
+            gas_profile_location,
+            self.fuzz,
+            self.fuzz_corpus_dir.clone(),
             test_plan,
             native_function_table,
             cost_table,
@@ -296,7 +364,22 @@ impl UnitTestingConfig {
             test_results.report_statistics(&shared_writer, report_type)?;
         }
 
-        let ok = test_results.summarize(&shared_writer)?;
+        let report = test_results.report();
+        if let Some(path) = &self.junit_report {
+            report.save_junit(path)?;
+        }
+        if let Some(path) = &self.json_report {
+            report.save_json(path)?;
+        }
+
+        let mut ok = test_results.summarize(&shared_writer)?;
+        if let Some(baseline) = &gas_baseline {
+            ok &= report.check_gas_baseline(
+                &shared_writer,
+                baseline,
+                self.gas_regression_threshold,
+            )?;
+        }
 
         let writer = shared_writer.into_inner().unwrap();
         Ok((writer, ok))
@@ -5,17 +5,20 @@
 use crate::format_module_id;
 use colored::{Colorize, control};
 use move_binary_format::errors::{ExecutionState, Location, VMError};
+use move_command_line_common::display::{RenderResult, try_render_constant};
 use move_command_line_common::error_bitset::ErrorBitset;
 use move_compiler::{
     diagnostics::{self, Diagnostic, Diagnostics},
-    unit_test::{ModuleTestPlan, MoveErrorType, TestPlan},
+    unit_test::{ModuleTestPlan, MoveErrorType, TestArgument, TestPlan},
 };
 use move_core_types::{
     language_storage::ModuleId,
+    runtime_value::MoveValue,
     vm_status::{StatusCode, StatusType},
 };
 use move_ir_types::location::Loc;
 use move_trace_format::format::MoveTrace;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, BTreeSet},
     io::{Result, Write},
@@ -48,6 +51,10 @@ pub struct TestFailure {
     pub vm_error: Option<VMError>,
     pub failure_reason: FailureReason,
     pub prng_seed: Option<u64>,
+    /// The (generated) arguments the test failed with, after shrinking if the test was fuzzed
+    pub arguments: Option<Vec<MoveValue>>,
+    /// Whether the test was fuzzed (run with `--fuzz`)
+    pub fuzzed: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -55,6 +62,8 @@ pub struct TestRunInfo {
     pub elapsed_time: Duration,
     pub instructions_executed: u64,
     pub trace: Option<Vec<u8>>,
+    /// Gas profile in the folded stack format, if gas profiling is enabled
+    pub gas_profile: Option<String>,
 }
 
 type TestRuns<T> = BTreeMap<String, Vec<T>>;
@@ -71,8 +80,73 @@ pub struct TestResults {
     test_plan: TestPlan,
 }
 
-fn write_bytes_to_file(filepath: &str, content: &[u8]) -> std::io::Result<()> {
-    let path = Path::new(filepath);
+/// Outcome of a test in a machine-readable test report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Timeout,
+}
+
+/// Machine-readable report of the results of a test run. Written as JSON, it can also be read
+/// back as the gas baseline of a later run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestReport {
+    pub tests: Vec<TestReportEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestReportEntry {
+    /// Fully qualified name of the test (`<address>::<module>::<function>`)
+    pub name: String,
+    pub module: String,
+    pub function: String,
+    /// Whether the test runs with generated arguments (e.g., a `#[random_test]`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub generated_arguments: bool,
+    pub status: TestStatus,
+    /// Gas used, summed over all runs of the test (tests with generated arguments run repeatedly)
+    pub gas_used: u64,
+    /// Time taken, summed over all runs of the test
+    pub duration_secs: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<TestReportFailure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestReportFailure {
+    /// The failure as rendered in the human-readable summary, without colors
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abort_location: Option<AbortLocation>,
+    /// The rendered clever error the test aborted with, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clever_error: Option<String>,
+    /// The seed the arguments of a test with generated arguments can be regenerated from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+/// Source location a test failure is rooted at (1-indexed)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbortLocation {
+    pub module: String,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A test that used more gas than its baseline allows
+#[derive(Debug, Clone)]
+pub struct GasRegression {
+    pub name: String,
+    pub baseline: u64,
+    pub gas_used: u64,
+}
+
+fn write_bytes_to_file(filepath: impl AsRef<Path>, content: &[u8]) -> std::io::Result<()> {
+    let path = filepath.as_ref();
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)?;
     }
@@ -86,11 +160,13 @@ impl TestRunInfo {
         elapsed_time: Duration,
         instructions_executed: u64,
         trace: Option<MoveTrace>,
+        gas_profile: Option<String>,
     ) -> Self {
         Self {
             elapsed_time,
             instructions_executed,
             trace: trace.map(|t| t.into_compressed_json_bytes()),
+            gas_profile,
         }
     }
 
@@ -101,6 +177,14 @@ impl TestRunInfo {
             Ok(())
         }
     }
+
+    pub fn save_gas_profile(&self, path: &str) -> Result<()> {
+        if let Some(gas_profile) = &self.gas_profile {
+            write_bytes_to_file(path, gas_profile.as_bytes())
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl FailureReason {
@@ -162,6 +246,71 @@ fn clever_error_line_number_to_loc(test_plan: &TestPlan, vm_error: &VMError) ->
     }
 }
 
+/// The module a VM error originated in, the source location it is rooted at, and the location of
+/// the function it is in -- if the module's source map is available.
+fn error_locs<'a>(test_plan: &TestPlan, vm_error: &'a VMError) -> Option<(&'a ModuleId, Loc, Loc)> {
+    let Location::Module(module_id) = vm_error.location() else {
+        return None;
+    };
+    let (fdef_idx, offset) = vm_error.offsets().first()?;
+    let function_source_map = test_plan
+        .module_info
+        .get(module_id)?
+        .source_map
+        .get_function_source_map(*fdef_idx)
+        .ok()?;
+    let loc = function_source_map.get_code_location(*offset).unwrap();
+
+    let alternate_location_opt = clever_error_line_number_to_loc(test_plan, vm_error);
+    let loc = if alternate_location_opt.is_some_and(|alt_loc| !loc.overlaps(&alt_loc)) {
+        alternate_location_opt.unwrap()
+    } else {
+        loc
+    };
+    Some((module_id, loc, function_source_map.definition_location))
+}
+
+/// Render the clever error a VM error aborted with, e.g.
+/// `error code 3 -- 'ENotFound' -- 'The object was not found'`
+fn render_clever_error(test_plan: &TestPlan, vm_error: &VMError) -> Option<String> {
+    let abort_code = match (vm_error.major_status(), vm_error.sub_status()) {
+        (StatusCode::ABORTED, Some(abort_code)) => abort_code,
+        _ => return None,
+    };
+    let Location::Module(module_id) = vm_error.location() else {
+        return None;
+    };
+    let bitset = ErrorBitset::from_u64(abort_code)?;
+    let module = &test_plan.module_info.get(module_id)?.module;
+
+    let identifier_constant = module
+        .constant_pool()
+        .get(bitset.identifier_index()? as usize)?;
+    let value_constant = module
+        .constant_pool()
+        .get(bitset.constant_index()? as usize)?;
+    let identifier = bcs::from_bytes::<Vec<u8>>(&identifier_constant.data)
+        .ok()
+        .and_then(|x| String::from_utf8(x).ok())?;
+    let value = match try_render_constant(value_constant) {
+        RenderResult::AsString(s) => format!("'{s}'"),
+        RenderResult::AsValue(v) => v,
+        RenderResult::NotRendered => format!(
+            "0x{}",
+            value_constant
+                .data
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<String>()
+        ),
+    };
+
+    Some(match bitset.error_code() {
+        Some(code) => format!("error code {code} -- '{identifier}' -- {value}"),
+        None => format!("'{identifier}' -- {value}"),
+    })
+}
+
 impl TestFailure {
     pub fn new(
         failure_reason: FailureReason,
@@ -174,10 +323,27 @@ impl TestFailure {
             vm_error,
             failure_reason,
             prng_seed,
+            arguments: None,
+            fuzzed: false,
         }
     }
 
+    pub fn with_arguments(mut self, arguments: Vec<MoveValue>) -> Self {
+        self.arguments = Some(arguments);
+        self
+    }
+
+    pub fn fuzzed(mut self) -> Self {
+        self.fuzzed = true;
+        self
+    }
+
     pub fn render_error(&self, test_plan: &TestPlan) -> String {
+        self.render_error_with_colors(test_plan, control::SHOULD_COLORIZE.should_colorize())
+    }
+
+    /// Render the error, using colors in the rendered diagnostics only if `colorize` is set
+    pub fn render_error_with_colors(&self, test_plan: &TestPlan, colorize: bool) -> String {
         match &self.failure_reason {
             FailureReason::NoError(message) => message.to_string(),
             FailureReason::Timeout(message) => message.to_string(),
@@ -189,7 +355,7 @@ impl TestFailure {
                         .present_tense(),
                     actual.with_context(&test_plan.module_info).past_tense(),
                 );
-                Self::report_error_with_location(test_plan, base_message, &self.vm_error)
+                Self::report_error_with_location(test_plan, base_message, &self.vm_error, colorize)
             }
             FailureReason::WrongAbortDEPRECATED(message, expected_code, actual) => {
                 let base_message = format!(
@@ -199,7 +365,7 @@ impl TestFailure {
                     expected_code,
                     actual.with_context(&test_plan.module_info).past_tense(),
                 );
-                Self::report_error_with_location(test_plan, base_message, &self.vm_error)
+                Self::report_error_with_location(test_plan, base_message, &self.vm_error, colorize)
             }
             FailureReason::UnexpectedError(message, error) => {
                 let prefix = match error.0.status_type() {
@@ -223,7 +389,7 @@ impl TestFailure {
                     message,
                     error.with_context(&test_plan.module_info).past_tense(),
                 );
-                Self::report_error_with_location(test_plan, base_message, &self.vm_error)
+                Self::report_error_with_location(test_plan, base_message, &self.vm_error, colorize)
             }
             FailureReason::Property(message) => message.clone(),
         }
@@ -281,12 +447,13 @@ impl TestFailure {
         test_plan: &TestPlan,
         base_message: String,
         vm_error: &Option<VMError>,
+        colorize: bool,
     ) -> String {
         let report_diagnostics = |mapped_files, diags| {
             diagnostics::report_diagnostics_to_buffer_with_mapped_files(
                 mapped_files,
                 diags,
-                control::SHOULD_COLORIZE.should_colorize(),
+                colorize,
             )
         };
 
@@ -295,47 +462,26 @@ impl TestFailure {
             Some(vm_error) => vm_error,
         };
 
-        let diags = match vm_error.location() {
-            Location::Module(module_id) => {
-                let diag_opt = vm_error.offsets().first().and_then(|(fdef_idx, offset)| {
-                    let function_source_map = test_plan
-                        .module_info
-                        .get(module_id)?
-                        .source_map
-                        .get_function_source_map(*fdef_idx)
-                        .ok()?;
-                    let loc = function_source_map.get_code_location(*offset).unwrap();
-
-                    let alternate_location_opt =
-                        clever_error_line_number_to_loc(test_plan, vm_error);
-                    let loc =
-                        if alternate_location_opt.is_some_and(|alt_loc| !loc.overlaps(&alt_loc)) {
-                            alternate_location_opt.unwrap()
-                        } else {
-                            loc
-                        };
-                    let msg = format!(
-                        "In this function in {}",
-                        format_module_id(&test_plan.module_info, module_id)
-                    );
-                    // TODO(tzakian) maybe migrate off of move-langs diagnostics?
-                    Some(Diagnostic::new(
-                        diagnostics::codes::Tests::TestFailed,
-                        (loc, base_message.clone()),
-                        vec![(function_source_map.definition_location, msg)],
-                        std::iter::empty::<String>(),
-                    ))
-                });
-                match diag_opt {
-                    None => base_message,
-                    Some(diag) => String::from_utf8(report_diagnostics(
-                        &test_plan.mapped_files,
-                        Diagnostics::from(vec![diag]),
-                    ))
-                    .unwrap(),
-                }
+        let diags = match error_locs(test_plan, vm_error) {
+            Some((module_id, loc, function_loc)) => {
+                let msg = format!(
+                    "In this function in {}",
+                    format_module_id(&test_plan.module_info, module_id)
+                );
+                // TODO(tzakian) maybe migrate off of move-langs diagnostics?
+                let diag = Diagnostic::new(
+                    diagnostics::codes::Tests::TestFailed,
+                    (loc, base_message.clone()),
+                    vec![(function_loc, msg)],
+                    std::iter::empty::<String>(),
+                );
+                String::from_utf8(report_diagnostics(
+                    &test_plan.mapped_files,
+                    Diagnostics::from(vec![diag]),
+                ))
+                .unwrap()
             }
-            _ => base_message,
+            None => base_message,
         };
 
         match vm_error.exec_state() {
@@ -567,6 +713,103 @@ impl TestResults {
         writeln!(writer.lock().unwrap())
     }
 
+    /// Build the machine-readable report of the results, with one entry per test
+    pub fn report(&self) -> TestReport {
+        let mut entries: BTreeMap<(&ModuleId, &String), TestReportEntry> = BTreeMap::new();
+        for (module_id, test_results) in &self.final_statistics.passed {
+            for (function_name, test_results) in test_results {
+                let (time, instrs_executed) = calculate_run_statistics(test_results);
+                entries.insert(
+                    (module_id, function_name),
+                    self.report_entry(module_id, function_name, time, instrs_executed),
+                );
+            }
+        }
+
+        for (module_id, test_failures) in &self.final_statistics.failed {
+            for (function_name, test_failures) in test_failures {
+                let (time, instrs_executed) =
+                    calculate_run_statistics(test_failures.iter().map(|f| &f.test_run_info));
+                // A #[random_test] may have both passed and failed runs, the failure is reported
+                let entry = entries
+                    .entry((module_id, function_name))
+                    .or_insert_with(|| {
+                        self.report_entry(module_id, function_name, Duration::ZERO, 0)
+                    });
+                entry.gas_used += instrs_executed;
+                entry.duration_secs += time.as_secs_f64();
+                if let Some(test_failure) = test_failures.first() {
+                    entry.status = match test_failure.failure_reason {
+                        FailureReason::Timeout(_) => TestStatus::Timeout,
+                        _ => TestStatus::Failed,
+                    };
+                    entry.failure = Some(self.report_failure(test_failure));
+                }
+            }
+        }
+
+        TestReport {
+            tests: entries.into_values().collect(),
+        }
+    }
+
+    fn report_entry(
+        &self,
+        module_id: &ModuleId,
+        function_name: &str,
+        time: Duration,
+        instrs_executed: u64,
+    ) -> TestReportEntry {
+        let module = format_module_id(&self.test_plan.module_info, module_id);
+        let generated_arguments = self
+            .test_plan
+            .module_tests
+            .get(module_id)
+            .and_then(|plan| plan.tests.get(function_name))
+            .is_some_and(|test| {
+                test.arguments
+                    .iter()
+                    .any(|arg| matches!(arg, TestArgument::Generate { .. }))
+            });
+        TestReportEntry {
+            name: format!("{module}::{function_name}"),
+            module,
+            function: function_name.to_string(),
+            generated_arguments,
+            status: TestStatus::Passed,
+            gas_used: instrs_executed,
+            duration_secs: time.as_secs_f64(),
+            failure: None,
+        }
+    }
+
+    fn report_failure(&self, test_failure: &TestFailure) -> TestReportFailure {
+        let test_plan = &self.test_plan;
+        let abort_location = test_failure.vm_error.as_ref().and_then(|vm_error| {
+            let (module_id, loc, _) = error_locs(test_plan, vm_error)?;
+            let position = test_plan.mapped_files.position_opt(&loc)?;
+            Some(AbortLocation {
+                module: format_module_id(&test_plan.module_info, module_id),
+                file: test_plan
+                    .mapped_files
+                    .file_path(&loc.file_hash())
+                    .to_string_lossy()
+                    .to_string(),
+                line: position.start.user_line(),
+                column: position.start.user_column(),
+            })
+        });
+        TestReportFailure {
+            message: test_failure.render_error_with_colors(test_plan, /* colorize */ false),
+            abort_location,
+            clever_error: test_failure
+                .vm_error
+                .as_ref()
+                .and_then(|vm_error| render_clever_error(test_plan, vm_error)),
+            seed: test_failure.prng_seed,
+        }
+    }
+
     /// Returns `true` if all tests passed, `false` if there was a test failure/timeout
     pub fn summarize<W: Write>(self, writer: &Mutex<W>) -> Result<bool> {
         let num_failed_tests = self
@@ -606,14 +849,36 @@ impl TestResults {
                                 .render_error(&self.test_plan)
                                 .replace('\n', "\n│ ")
                         )?;
+                        if let Some(arguments) = &test_failure.arguments {
+                            writeln!(
+                                writer.lock().unwrap(),
+                                "│ {}: {}",
+                                if test_failure.fuzzed {
+                                    "Minimal failing input"
+                                } else {
+                                    "Failing input"
+                                },
+                                format!(
+                                    "{}({})",
+                                    test_name,
+                                    arguments
+                                        .iter()
+                                        .map(|arg| arg.to_string())
+                                        .collect::<Vec<_>>()
+                                        .join(", ")
+                                )
+                                .bold()
+                            )?;
+                        }
                         if let Some(seed) = test_failure.prng_seed {
                             writeln!(writer.lock().unwrap(),
                             "│ {}",
                             format!(
                                 "This test uses randomly generated inputs. Rerun with `{}` to recreate this test failure.\n",
-                                format!("test {} --seed {}",
+                                format!("test {} --seed {}{}",
                                     test_name,
-                                    seed
+                                    seed,
+                                    if test_failure.fuzzed { " --fuzz" } else { "" }
                                 ).bright_red().bold()
                             ).replace('\n', "\n│ ")
                         )?;
@@ -639,3 +904,192 @@ impl TestResults {
         Ok(num_failed_tests == 0)
     }
 }
+
+impl TestReport {
+    /// Read a report previously saved with `save_json`
+    pub fn load_json(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+
+    pub fn save_json(&self, path: &Path) -> Result<()> {
+        write_bytes_to_file(path, &serde_json::to_vec_pretty(self)?)
+    }
+
+    pub fn save_junit(&self, path: &Path) -> Result<()> {
+        write_bytes_to_file(path, self.to_junit_xml().as_bytes())
+    }
+
+    /// Render the report in the JUnit XML format, with a test suite per module
+    pub fn to_junit_xml(&self) -> String {
+        let mut modules: BTreeMap<&str, Vec<&TestReportEntry>> = BTreeMap::new();
+        for entry in &self.tests {
+            modules.entry(&entry.module).or_default().push(entry);
+        }
+        let summary = |entries: &[&TestReportEntry]| {
+            let failures = entries
+                .iter()
+                .filter(|e| e.status != TestStatus::Passed)
+                .count();
+            let time: f64 = entries.iter().map(|e| e.duration_secs).sum();
+            format!(
+                "tests=\"{}\" failures=\"{failures}\" errors=\"0\" time=\"{time:.3}\"",
+                entries.len()
+            )
+        };
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let all: Vec<_> = self.tests.iter().collect();
+        xml.push_str(&format!(
+            "<testsuites name=\"Move unit tests\" {}>\n",
+            summary(&all[..])
+        ));
+        for (module, entries) in modules {
+            xml.push_str(&format!(
+                "  <testsuite name=\"{}\" {}>\n",
+                xml_escape(module),
+                summary(&entries[..])
+            ));
+            for entry in entries {
+                xml.push_str(&format!(
+                    "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\">\n",
+                    xml_escape(&entry.function),
+                    xml_escape(&entry.module),
+                    entry.duration_secs
+                ));
+                xml.push_str("      <properties>\n");
+                xml.push_str(&format!(
+                    "        <property name=\"gas_used\" value=\"{}\"/>\n",
+                    entry.gas_used
+                ));
+                if let Some(failure) = &entry.failure {
+                    if let Some(loc) = &failure.abort_location {
+                        xml.push_str(&format!(
+                            "        <property name=\"abort_location\" value=\"{}:{}:{}\"/>\n",
+                            xml_escape(&loc.file),
+                            loc.line,
+                            loc.column
+                        ));
+                    }
+                    if let Some(clever_error) = &failure.clever_error {
+                        xml.push_str(&format!(
+                            "        <property name=\"clever_error\" value=\"{}\"/>\n",
+                            xml_escape(clever_error)
+                        ));
+                    }
+                    if let Some(seed) = failure.seed {
+                        xml.push_str(&format!(
+                            "        <property name=\"seed\" value=\"{seed}\"/>\n"
+                        ));
+                    }
+                }
+                xml.push_str("      </properties>\n");
+                if let Some(failure) = &entry.failure {
+                    let message = failure.message.lines().next().unwrap_or_default();
+                    xml.push_str(&format!(
+                        "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                        match entry.status {
+                            TestStatus::Timeout => "timeout",
+                            _ => "failure",
+                        },
+                        xml_escape(message),
+                        xml_escape(&failure.message)
+                    ));
+                }
+                xml.push_str("    </testcase>\n");
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+
+    /// Tests that passed in both this report and `baseline`, and used more than `threshold`
+    /// percent more gas than in `baseline`. Tests with generated arguments are not compared, as the
+    /// gas they use depends on the inputs generated for them (and on shrinking under `--fuzz`).
+    pub fn gas_regressions(&self, baseline: &TestReport, threshold: f64) -> Vec<GasRegression> {
+        let comparable =
+            |e: &&TestReportEntry| e.status == TestStatus::Passed && !e.generated_arguments;
+        let baseline: BTreeMap<_, _> = baseline
+            .tests
+            .iter()
+            .filter(comparable)
+            .map(|e| (&e.name, e.gas_used))
+            .collect();
+        self.tests
+            .iter()
+            .filter(comparable)
+            .filter_map(|e| {
+                let baseline = *baseline.get(&e.name)?;
+                let allowed = baseline as f64 * (1.0 + threshold / 100.0);
+                (e.gas_used as f64 > allowed).then(|| GasRegression {
+                    name: e.name.clone(),
+                    baseline,
+                    gas_used: e.gas_used,
+                })
+            })
+            .collect()
+    }
+
+    /// Compare the gas used by tests against `baseline`, reporting the tests that regressed.
+    /// Returns `true` if no test regressed.
+    pub fn check_gas_baseline<W: Write>(
+        &self,
+        writer: &Mutex<W>,
+        baseline: &TestReport,
+        threshold: f64,
+    ) -> Result<bool> {
+        let regressions = self.gas_regressions(baseline, threshold);
+        if regressions.is_empty() {
+            return Ok(true);
+        }
+
+        writeln!(writer.lock().unwrap(), "\nGas regressions:\n")?;
+        for GasRegression {
+            name,
+            baseline,
+            gas_used,
+        } in &regressions
+        {
+            let increase = if *baseline == 0 {
+                "".to_string()
+            } else {
+                format!(
+                    " (+{:.2}%)",
+                    (*gas_used as f64 / *baseline as f64 - 1.0) * 100.0
+                )
+            };
+            writeln!(
+                writer.lock().unwrap(),
+                "[ {} ] {name}: {gas_used} gas, baseline {baseline} gas{increase}",
+                "REGRESSED".bold().bright_red(),
+            )?;
+        }
+        writeln!(
+            writer.lock().unwrap(),
+            "Gas check result: {}. {} test(s) used more than {threshold}% more gas than the baseline",
+            "FAILED".bold().bright_red(),
+            regressions.len(),
+        )?;
+        Ok(false)
+    }
+}
+
+/// Escape text for use in XML attribute values and character data, dropping the control
+/// characters XML does not allow
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -4,6 +4,9 @@
 
 use crate::{
     extensions, format_module_id,
+    fuzz::{
+        Corpus, CoverageTracer, generate_value, merge_coverage, mutate_arguments, shrink_arguments,
+    },
     test_reporter::{
         FailureReason, MoveError, TestFailure, TestResults, TestRunInfo, TestStatistics,
     },
@@ -13,7 +16,7 @@ use colored::*;
 
 use move_binary_format::{
     binary_config::BinaryConfig,
-    errors::{Location, VMResult},
+    errors::{Location, VMError, VMResult},
     file_format::CompiledModule,
 };
 use move_bytecode_utils::Modules;
@@ -31,16 +34,27 @@ use move_core_types::{
     u256::U256,
     vm_status::StatusCode,
 };
+use move_coverage::coverage_map::ExecCoverageMap;
 use move_trace_format::format::{MoveTraceBuilder, TRACE_FILE_EXTENSION};
+use move_vm_config::runtime::VMProfilerConfig;
+use move_vm_profiler::{FOLDED_STACKS_FILE_EXTENSION, GasProfiler};
 use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
 use move_vm_test_utils::{
     InMemoryStorage,
     gas_schedule::{CostTable, Gas, GasStatus, unit_cost_schedule},
 };
+use move_vm_types::gas::GasMeter;
 use rand::{Rng, SeedableRng, rngs::StdRng};
 use rayon::prelude::*;
 use regex::Regex;
-use std::{collections::BTreeMap, io::Write, marker::Send, sync::Mutex, time::Instant};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    marker::Send,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
 use move_vm_runtime::native_extensions::NativeContextExtensions;
 
@@ -55,6 +69,9 @@ pub struct SharedTestingConfig {
     num_iters: u64,
     deterministic_generation: bool,
     trace_location: Option<String>,
+    gas_profile_location: Option<String>,
+    fuzz: bool,
+    corpus_dir: Option<PathBuf>,
 }
 
 pub struct TestRunner {
@@ -63,6 +80,24 @@ pub struct TestRunner {
     tests: TestPlan,
 }
 
+/// The arguments of one execution of a test
+struct TestInput {
+    arguments: Vec<MoveValue>,
+    /// The seed the arguments were generated from, if they can be regenerated from it
+    prng_seed: Option<u64>,
+    /// Whether (some of) the arguments were generated, in which case they are reported if the
+    /// test fails
+    generated: bool,
+}
+
+/// The result of one execution of a test
+struct TestExecution {
+    result: Result<(), (FailureReason, Option<VMError>)>,
+    test_run_info: TestRunInfo,
+    /// The instructions executed by the test, if they were collected
+    coverage: Option<ExecCoverageMap>,
+}
+
 /// Setup storage state with the set of modules that will be needed for all tests
 fn setup_test_storage<'a>(
     modules: impl Iterator<Item = &'a CompiledModule>,
@@ -117,6 +152,9 @@ impl TestRunner {
         num_iters: u64,
         deterministic_generation: bool,
         trace_location: Option<String>,
+        gas_profile_location: Option<String>,
+        fuzz: bool,
+        corpus_dir: Option<PathBuf>,
         tests: TestPlan,
         // TODO: maybe we should require the clients to always pass in a list of native functions so
         // we don't have to make assumptions about their gas parameters.
@@ -160,6 +198,9 @@ impl TestRunner {
                 num_iters,
                 deterministic_generation,
                 trace_location,
+                gas_profile_location,
+                fuzz,
+                corpus_dir,
             },
             num_threads,
             tests,
@@ -261,6 +302,7 @@ impl SharedTestingConfig {
         test_plan: &ModuleTestPlan,
         function_name: &str,
         arguments: Vec<MoveValue>,
+        coverage: Option<Arc<Mutex<ExecCoverageMap>>>,
     ) -> (
         VMResult<ChangeSet>,
         VMResult<NativeContextExtensions>,
@@ -276,8 +318,14 @@ impl SharedTestingConfig {
         let move_vm = MoveVM::new_with_config(natives, vm_config).unwrap();
         let extensions = extensions::new_extensions();
 
-        let mut move_tracer = MoveTraceBuilder::new();
-        let tracer = if self.trace_location.is_some() {
+        let collect_coverage = coverage.is_some();
+        let mut move_tracer = match coverage {
+            Some(coverage) => {
+                MoveTraceBuilder::new_with_tracer(Box::new(CoverageTracer::new(coverage)))
+            }
+            None => MoveTraceBuilder::new(),
+        };
+        let tracer = if self.trace_location.is_some() || collect_coverage {
             Some(&mut move_tracer)
         } else {
             None
@@ -286,14 +334,27 @@ impl SharedTestingConfig {
         let mut session =
             move_vm.new_session_with_extensions(&self.starting_storage_state, extensions);
         let mut gas_meter = GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
-        move_vm_profiler::tracing_feature_enabled! {
-            use move_vm_profiler::GasProfiler;
-            use move_vm_types::gas::GasMeter;
-            gas_meter.set_profiler(GasProfiler::init_default_cfg(
+        if self.gas_profile_location.is_some() {
+            let config = VMProfilerConfig {
+                full_path: PathBuf::from(function_name),
+                track_bytecode_instructions: false,
+                use_long_function_name: true,
+            };
+            gas_meter.set_profiler(GasProfiler::init(
+                &Some(config),
                 function_name.to_owned(),
                 self.execution_bound,
             ));
         }
+        move_vm_profiler::tracing_feature_enabled! {
+            // With tracing, gas can also be profiled through the environment.
+            if self.gas_profile_location.is_none() {
+                gas_meter.set_profiler(GasProfiler::init_default_cfg(
+                    function_name.to_owned(),
+                    self.execution_bound,
+                ));
+            }
+        }
 
         // TODO: collect VM logs if the verbose flag (i.e, `self.verbose`) is set
         let now = Instant::now();
@@ -321,6 +382,13 @@ impl SharedTestingConfig {
         } else {
             None
         };
+        let gas_profile = if self.gas_profile_location.is_some() {
+            gas_meter
+                .get_profiler_mut()
+                .map(|profiler| profiler.finish_to_folded_stacks())
+        } else {
+            None
+        };
         let test_run_info = TestRunInfo::new(
             now.elapsed(),
             // TODO(Gas): This doesn't look quite right...
@@ -330,6 +398,7 @@ impl SharedTestingConfig {
                 .unwrap()
                 .into(),
             trace,
+            gas_profile,
         );
         match session.finish_with_extensions().0 {
             Ok((cs, extensions)) => (Ok(cs), Ok(extensions), return_result, test_run_info),
@@ -340,18 +409,17 @@ impl SharedTestingConfig {
     fn exec_module_tests_with_move_vm(
         &self,
         test_plan: &ModuleTestPlan,
-        global_test_context: &BTreeMap<ModuleId, NamedCompiledModule>,
         output: &TestOutput<impl Write>,
     ) -> TestStatistics {
         let mut stats = TestStatistics::new();
 
         for (function_name, test_info) in &test_plan.tests {
-            let arguments = if test_info
+            if test_info
                 .arguments
                 .iter()
                 .all(|arg| matches!(arg, TestArgument::Value(_)))
             {
-                let test_arguments = test_info
+                let arguments = test_info
                     .arguments
                     .iter()
                     .map(|arg| match arg {
@@ -359,53 +427,203 @@ impl SharedTestingConfig {
                         _ => unreachable!(),
                     })
                     .collect::<Vec<_>>();
-                vec![(None, test_arguments)]
+                let input = TestInput {
+                    arguments,
+                    prng_seed: None,
+                    generated: false,
+                };
+                let execution = self.exec_test_once(
+                    output,
+                    function_name,
+                    test_info,
+                    &input.arguments,
+                    /* collect_coverage */ false,
+                );
+                self.record_execution(output, &mut stats, function_name, &input, execution, true);
             } else {
-                let mut args = vec![];
-                for i in 0..self.num_iters {
-                    let mut iter_args = vec![];
-                    let prng_seed = self.prng_seed.unwrap_or_else(|| {
-                        if self.deterministic_generation {
-                            i
-                        } else {
-                            rand::random::<u64>()
-                        }
-                    });
-                    let mut rng = StdRng::seed_from_u64(prng_seed);
-                    for arg in test_info.arguments.iter() {
-                        match arg {
-                            TestArgument::Value(v) => iter_args.push(v.clone()),
-                            TestArgument::Generate { generated_type } => {
-                                iter_args.push(Self::generate_value_for_typetag(
-                                    &mut rng,
-                                    generated_type,
-                                ));
-                            }
-                        }
+                self.exec_generated_test(output, &mut stats, function_name, test_info);
+            }
+        }
+
+        stats
+    }
+
+    /// Execute a test with generated arguments: first with the inputs in the test's corpus (if
+    /// there is one), and then with `num_iters` new inputs. The first input the test fails with
+    /// is shrunk to a minimal one when fuzzing, added to the corpus, and reported.
+    fn exec_generated_test(
+        &self,
+        output: &TestOutput<impl Write>,
+        stats: &mut TestStatistics,
+        function_name: &str,
+        test_info: &TestCase,
+    ) {
+        let generated_types: Vec<_> = test_info
+            .arguments
+            .iter()
+            .map(|arg| match arg {
+                TestArgument::Value(_) => None,
+                TestArgument::Generate { generated_type } => Some(generated_type.clone()),
+            })
+            .collect();
+
+        let corpus = self.corpus_dir.as_ref().map(|dir| {
+            let test_name = format!(
+                "{}__{}",
+                format_module_id(output.test_info, &output.test_plan.module_id).replace("::", "__"),
+                function_name
+            );
+            Corpus::new(dir, &test_name)
+        });
+        let mut replayed = corpus
+            .as_ref()
+            .map(|corpus| corpus.load(&generated_types.iter().flatten().collect::<Vec<_>>()))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|generated| {
+                let mut generated = generated.into_iter();
+                let arguments = test_info
+                    .arguments
+                    .iter()
+                    .map(|arg| match arg {
+                        TestArgument::Value(v) => v.clone(),
+                        TestArgument::Generate { .. } => generated.next().unwrap(),
+                    })
+                    .collect();
+                TestInput {
+                    arguments,
+                    prng_seed: None,
+                    generated: true,
+                }
+            });
+
+        // When fuzzing, inputs that cover new instructions are kept, to be mutated into new inputs.
+        // The VM only reports the instructions an input covers when it is built with tracing, so
+        // without it, fuzzing falls back to generating every input at random.
+        let mut coverage = ExecCoverageMap::new(function_name.to_string());
+        let mut interesting_inputs = vec![];
+        let mut iter = 0;
+        loop {
+            let input = match replayed.next() {
+                Some(input) => input,
+                None if iter < self.num_iters => {
+                    iter += 1;
+                    self.generate_input(test_info, &generated_types, iter - 1, &interesting_inputs)
+                }
+                None => break,
+            };
+
+            let mut execution = self.exec_test_once(
+                output,
+                function_name,
+                test_info,
+                &input.arguments,
+                /* collect_coverage */ self.fuzz,
+            );
+            let Err((failure_reason, _)) = &execution.result else {
+                if let Some(execution_coverage) = execution.coverage.take() {
+                    if merge_coverage(&mut coverage, execution_coverage) {
+                        interesting_inputs.push(input.arguments.clone());
                     }
-                    args.push((Some(prng_seed), iter_args));
                 }
-                args
+                self.record_execution(output, stats, function_name, &input, execution, false);
+                continue;
             };
-            let len = arguments.len();
-            for (i, (prng_seed, args)) in arguments.into_iter().enumerate() {
-                if !self.exec_test_once(
-                    test_plan,
-                    global_test_context,
+
+            // Failing inputs are only shrunk when fuzzing, otherwise the failure is reported as-is,
+            // so that it can be recreated from its seed.
+            let generated: Vec<_> = generated_types.iter().map(Option::is_some).collect();
+            let arguments = if self.fuzz {
+                shrink_arguments(input.arguments.clone(), &generated, |arguments| {
+                    let execution = self.exec_test_once(
+                        output,
+                        function_name,
+                        test_info,
+                        arguments,
+                        /* collect_coverage */ false,
+                    );
+                    matches!(&execution.result, Err((reason, _)) if reason == failure_reason)
+                })
+            } else {
+                input.arguments.clone()
+            };
+            if arguments != input.arguments {
+                execution = self.exec_test_once(
                     output,
                     function_name,
                     test_info,
-                    args,
-                    &mut stats,
-                    prng_seed,
-                    i == len - 1,
-                ) {
-                    break;
+                    &arguments,
+                    /* collect_coverage */ false,
+                );
+            }
+
+            if let Some(corpus) = &corpus {
+                let generated_arguments: Vec<_> = arguments
+                    .iter()
+                    .zip(&generated)
+                    .filter(|(_, generated)| **generated)
+                    .map(|(arg, _)| arg.clone())
+                    .collect();
+                if let Err(e) = corpus.add(&generated_arguments) {
+                    eprintln!("Unable to save failing input of {function_name} to corpus -- {e:?}");
                 }
             }
+
+            let input = TestInput { arguments, ..input };
+            self.record_execution(output, stats, function_name, &input, execution, false);
+            return;
         }
 
-        stats
+        output.pass(function_name);
+    }
+
+    /// Generate the arguments for iteration `iter` of a test with generated arguments.
+    fn generate_input(
+        &self,
+        test_info: &TestCase,
+        generated_types: &[Option<TypeTag>],
+        iter: u64,
+        interesting_inputs: &[Vec<MoveValue>],
+    ) -> TestInput {
+        let prng_seed = self.prng_seed.unwrap_or_else(|| {
+            if self.deterministic_generation {
+                iter
+            } else {
+                rand::random::<u64>()
+            }
+        });
+        let mut rng = StdRng::seed_from_u64(prng_seed);
+
+        // When fuzzing, half of the inputs are mutations of inputs that covered new instructions.
+        // These can't be regenerated from their seed, so no seed is reported for them.
+        let mutate = self.fuzz && rng.r#gen::<bool>();
+        if mutate && !interesting_inputs.is_empty() {
+            let parent = &interesting_inputs[rng.gen_range(0..interesting_inputs.len())];
+            return TestInput {
+                arguments: mutate_arguments(&mut rng, parent, generated_types),
+                prng_seed: None,
+                generated: true,
+            };
+        }
+
+        let arguments = test_info
+            .arguments
+            .iter()
+            .map(|arg| match arg {
+                TestArgument::Value(v) => v.clone(),
+                TestArgument::Generate { generated_type } if self.fuzz => {
+                    generate_value(&mut rng, generated_type)
+                }
+                TestArgument::Generate { generated_type } => {
+                    Self::generate_value_for_typetag(&mut rng, generated_type)
+                }
+            })
+            .collect();
+        TestInput {
+            arguments,
+            prng_seed: Some(prng_seed),
+            generated: true,
+        }
     }
 
     fn generate_value_for_typetag(rng: &mut StdRng, ty: &TypeTag) -> MoveValue {
@@ -438,19 +656,91 @@ impl SharedTestingConfig {
 
     fn exec_test_once(
         &self,
-        test_plan: &ModuleTestPlan,
-        global_test_context: &BTreeMap<ModuleId, NamedCompiledModule>,
         output: &TestOutput<impl Write>,
         function_name: &str,
         test_info: &TestCase,
-        arguments: Vec<MoveValue>,
-        stats: &mut TestStatistics,
-        prng_seed: Option<u64>,
-        is_last_execution_of_test: bool,
-    ) -> bool {
-        let (_cs_result, _ext_result, exec_result, test_run_info) =
-            self.execute_via_move_vm(test_plan, function_name, arguments);
+        arguments: &[MoveValue],
+        collect_coverage: bool,
+    ) -> TestExecution {
+        let coverage = collect_coverage
+            .then(|| Arc::new(Mutex::new(ExecCoverageMap::new(function_name.to_string()))));
+        let (_cs_result, _ext_result, exec_result, test_run_info) = self.execute_via_move_vm(
+            output.test_plan,
+            function_name,
+            arguments.to_vec(),
+            coverage.clone(),
+        );
 
+        TestExecution {
+            result: self.check_result(output.test_info, test_info, exec_result),
+            test_run_info,
+            coverage: coverage
+                .and_then(|coverage| Arc::try_unwrap(coverage).ok())
+                .map(|coverage| coverage.into_inner().unwrap()),
+        }
+    }
+
+    /// Check the result of executing a test against its expected outcome.
+    fn check_result(
+        &self,
+        global_test_context: &BTreeMap<ModuleId, NamedCompiledModule>,
+        test_info: &TestCase,
+        exec_result: VMResult<Vec<Vec<u8>>>,
+    ) -> Result<(), (FailureReason, Option<VMError>)> {
+        let err = match exec_result {
+            // Expected the test to fail, but it executed
+            Ok(_) if test_info.expected_failure.is_some() => {
+                return Err((FailureReason::no_error(), None));
+            }
+            // Expected the test to execute fully and it did
+            Ok(_) => return Ok(()),
+            Err(err) => err,
+        };
+
+        let sub_status = err.sub_status().and_then(|status| {
+            convert_clever_move_abort_error(status, err.location(), global_test_context)
+        });
+        let actual_err = MoveError(err.major_status(), sub_status, err.location().clone());
+        assert!(err.major_status() != StatusCode::EXECUTED);
+        let failure_reason = match test_info.expected_failure.as_ref() {
+            Some(ExpectedFailure::Expected) => return Ok(()),
+            Some(ExpectedFailure::ExpectedWithError(expected_err))
+                if expected_err == &actual_err =>
+            {
+                return Ok(());
+            }
+            Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
+                if actual_err.0 == StatusCode::ABORTED
+                    && actual_err.1.is_some()
+                    && actual_err.1.as_ref().unwrap() == code =>
+            {
+                return Ok(());
+            }
+            // incorrect cases
+            Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
+                FailureReason::wrong_error(expected_err.clone(), actual_err)
+            }
+            Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
+                FailureReason::wrong_abort_deprecated(expected_code.clone(), actual_err)
+            }
+            // Ran out of ticks, report a test timeout
+            None if err.major_status() == StatusCode::OUT_OF_GAS => FailureReason::timeout(),
+            None => FailureReason::unexpected_error(actual_err),
+        };
+        Err((failure_reason, Some(err)))
+    }
+
+    /// Record the result of executing a test with `input`, and report it if it failed (or, if
+    /// `report_pass` is set, if it passed).
+    fn record_execution(
+        &self,
+        output: &TestOutput<impl Write>,
+        stats: &mut TestStatistics,
+        function_name: &str,
+        input: &TestInput,
+        execution: TestExecution,
+        report_pass: bool,
+    ) {
         // Save the trace -- one per test -- for each test that we have traced (and if tracing is
         // enabled).
         if let Some(location) = &self.trace_location {
@@ -459,125 +749,75 @@ impl SharedTestingConfig {
                 location,
                 format_module_id(output.test_info, &output.test_plan.module_id).replace("::", "__"),
                 function_name,
-                if let Some(seed) = prng_seed {
+                if let Some(seed) = input.prng_seed {
                     format!("_seed_{}", seed)
                 } else {
                     "".to_string()
                 },
                 TRACE_FILE_EXTENSION,
             );
-            if let Err(e) = test_run_info.save_trace(&trace_file_location) {
+            if let Err(e) = execution.test_run_info.save_trace(&trace_file_location) {
                 eprintln!("Unable to save trace to {trace_file_location} -- {:?}", e);
             }
         }
 
-        match exec_result {
-            Err(err) => {
-                let sub_status = err.sub_status().and_then(|status| {
-                    convert_clever_move_abort_error(status, err.location(), global_test_context)
-                });
-                let actual_err = MoveError(err.major_status(), sub_status, err.location().clone());
-                assert!(err.major_status() != StatusCode::EXECUTED);
-                match test_info.expected_failure.as_ref() {
-                    Some(ExpectedFailure::Expected) => {
-                        if is_last_execution_of_test {
-                            output.pass(function_name);
-                        }
-                        stats.test_success(function_name.to_string(), test_run_info, test_plan)
-                    }
-                    Some(ExpectedFailure::ExpectedWithError(expected_err))
-                        if expected_err == &actual_err =>
-                    {
-                        if is_last_execution_of_test {
-                            output.pass(function_name);
-                        }
-                        stats.test_success(function_name.to_string(), test_run_info, test_plan)
-                    }
-                    Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
-                        if actual_err.0 == StatusCode::ABORTED
-                            && actual_err.1.is_some()
-                            && actual_err.1.as_ref().unwrap() == code =>
-                    {
-                        if is_last_execution_of_test {
-                            output.pass(function_name);
-                        }
-                        stats.test_success(function_name.to_string(), test_run_info, test_plan)
-                    }
-                    // incorrect cases
-                    Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
-                        output.fail(function_name);
-                        stats.test_failure(
-                            function_name.to_string(),
-                            TestFailure::new(
-                                FailureReason::wrong_error(expected_err.clone(), actual_err),
-                                test_run_info,
-                                Some(err),
-                                prng_seed,
-                            ),
-                            test_plan,
-                        )
-                    }
-                    Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
-                        output.fail(function_name);
-                        stats.test_failure(
-                            function_name.to_string(),
-                            TestFailure::new(
-                                FailureReason::wrong_abort_deprecated(
-                                    expected_code.clone(),
-                                    actual_err,
-                                ),
-                                test_run_info,
-                                Some(err),
-                                prng_seed,
-                            ),
-                            test_plan,
-                        )
-                    }
-                    None if err.major_status() == StatusCode::OUT_OF_GAS => {
-                        // Ran out of ticks, report a test timeout and log a test failure
-                        output.timeout(function_name);
-                        stats.test_failure(
-                            function_name.to_string(),
-                            TestFailure::new(
-                                FailureReason::timeout(),
-                                test_run_info,
-                                Some(err),
-                                prng_seed,
-                            ),
-                            test_plan,
-                        )
-                    }
-                    None => {
-                        output.fail(function_name);
-                        stats.test_failure(
-                            function_name.to_string(),
-                            TestFailure::new(
-                                FailureReason::unexpected_error(actual_err),
-                                test_run_info,
-                                Some(err),
-                                prng_seed,
-                            ),
-                            test_plan,
-                        )
-                    }
+        // Likewise save the gas profile of each test that we have profiled.
+        if let Some(location) = &self.gas_profile_location {
+            let gas_profile_file_location = format!(
+                "{}/{}__{}{}.{}",
+                location,
+                format_module_id(output.test_info, &output.test_plan.module_id).replace("::", "__"),
+                function_name,
+                if let Some(seed) = input.prng_seed {
+                    format!("_seed_{}", seed)
+                } else {
+                    "".to_string()
+                },
+                FOLDED_STACKS_FILE_EXTENSION,
+            );
+            if let Err(e) = execution
+                .test_run_info
+                .save_gas_profile(&gas_profile_file_location)
+            {
+                eprintln!(
+                    "Unable to save gas profile to {gas_profile_file_location} -- {:?}",
+                    e
+                );
+            }
+        }
+
+        match execution.result {
+            Ok(()) => {
+                if report_pass {
+                    output.pass(function_name);
                 }
+                stats.test_success(
+                    function_name.to_string(),
+                    execution.test_run_info,
+                    output.test_plan,
+                );
             }
-            Ok(_) => {
-                // Expected the test to fail, but it executed
-                if test_info.expected_failure.is_some() {
-                    output.fail(function_name);
-                    stats.test_failure(
-                        function_name.to_string(),
-                        TestFailure::new(FailureReason::no_error(), test_run_info, None, prng_seed),
-                        test_plan,
-                    )
+            Err((failure_reason, vm_error)) => {
+                if matches!(failure_reason, FailureReason::Timeout(_)) {
+                    output.timeout(function_name);
                 } else {
-                    // Expected the test to execute fully and it did
-                    if is_last_execution_of_test {
-                        output.pass(function_name);
-                    }
-                    stats.test_success(function_name.to_string(), test_run_info, test_plan)
+                    output.fail(function_name);
+                }
+                let mut failure = TestFailure::new(
+                    failure_reason,
+                    execution.test_run_info,
+                    vm_error,
+                    input.prng_seed,
+                );
+                if self.fuzz {
+                    failure = failure.fuzzed();
+                }
+                // Report the arguments of inputs that were shrunk, or that were replayed from the
+                // corpus, as these can't be recreated from a seed.
+                if input.generated && (self.fuzz || input.prng_seed.is_none()) {
+                    failure = failure.with_arguments(input.arguments.clone());
                 }
+                stats.test_failure(function_name.to_string(), failure, output.test_plan);
             }
         }
     }
@@ -596,6 +836,6 @@ impl SharedTestingConfig {
             test_info,
         };
 
-        self.exec_module_tests_with_move_vm(test_plan, test_info, &output)
+        self.exec_module_tests_with_move_vm(test_plan, &output)
     }
 }
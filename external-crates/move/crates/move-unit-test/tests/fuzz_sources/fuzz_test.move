@@ -0,0 +1,6 @@
+module 0x6::fuzz_test {
+    #[random_test]
+    fun should_fail_shrink(_x: u64, _v: vector<u8>, _b: bool) {
+        abort 0
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_command_line_common::testing::insta_assert;
+use move_unit_test::{self, UnitTestingConfig, test_reporter::TestReport};
+use regex::Regex;
+use std::path::Path;
+
+// Runs all tests under the test/report_sources directory, writing JSON and JUnit reports, and then
+// again against gas baselines: the JSON report of the first run, and the same report with no gas
+// used by any test (so that every test that is compared regresses).
+fn run_test_impl(path: &Path) -> anyhow::Result<()> {
+    unsafe { std::env::set_var("NO_COLOR", "1") };
+    let reports_dir = tempfile::tempdir()?;
+    let json_report = reports_dir.path().join("report.json");
+    let junit_report = reports_dir.path().join("report.xml");
+    let no_gas_baseline = reports_dir.path().join("no_gas.json");
+
+    let run = |config: UnitTestingConfig| -> anyhow::Result<String> {
+        let source_files = vec![path.to_str().unwrap().to_owned()];
+        let unit_test_config = UnitTestingConfig {
+            num_threads: 1,
+            gas_limit: Some(1000),
+            source_files,
+            dep_files: move_stdlib::source_files(),
+            named_address_values: move_stdlib::named_addresses().into_iter().collect(),
+            deterministic_generation: true,
+            ..config
+        };
+        let Some(test_plan) = unit_test_config.build_test_plan() else {
+            anyhow::bail!("No test plan constructed for {:?}", path);
+        };
+        let (buffer, ok) =
+            unit_test_config.run_and_report_unit_tests(test_plan, None, None, vec![])?;
+        Ok(format!("{}ok: {ok}\n", String::from_utf8(buffer)?))
+    };
+
+    let output = run(UnitTestingConfig {
+        json_report: Some(json_report.clone()),
+        junit_report: Some(junit_report.clone()),
+        ..UnitTestingConfig::default_with_bound(None)
+    })?;
+    let json = std::fs::read_to_string(&json_report)?;
+    let junit = std::fs::read_to_string(&junit_report)?;
+
+    let own_baseline_output = run(UnitTestingConfig {
+        gas_baseline: Some(json_report.clone()),
+        ..UnitTestingConfig::default_with_bound(None)
+    })?;
+
+    let mut no_gas = TestReport::load_json(&json_report)?;
+    for entry in &mut no_gas.tests {
+        entry.gas_used = 0;
+    }
+    no_gas.save_json(&no_gas_baseline)?;
+    let no_gas_output = run(UnitTestingConfig {
+        gas_baseline: Some(no_gas_baseline),
+        ..UnitTestingConfig::default_with_bound(None)
+    })?;
+
+    let contents = format!(
+        "== test run ==\n{output}\n\
+         == JSON report ==\n{json}\n\n\
+         == JUnit report ==\n{junit}\n\
+         == test run against its own report ==\n{own_baseline_output}\n\
+         == test run against a report with no gas used ==\n{no_gas_output}"
+    );
+
+    // Gas used depends on the cost schedule, and durations on the machine running the tests.
+    // Paths to source files are made relative.
+    let cleaned = [
+        (r"(\d+) gas,", "<gas> gas,"),
+        (r#""gas_used": \d+"#, r#""gas_used": <gas>"#),
+        (
+            r#"name="gas_used" value="\d+""#,
+            r#"name="gas_used" value="<gas>""#,
+        ),
+        (
+            r#""duration_secs": [0-9.e-]+"#,
+            r#""duration_secs": <duration>"#,
+        ),
+        (r#"time="[0-9.]+""#, r#"time="<duration>""#),
+        (r#"[^\s"]*/([^/\s"]+\.move)"#, "$1"),
+    ]
+    .into_iter()
+    .fold(contents, |contents, (pattern, replacement)| {
+        Regex::new(pattern)
+            .unwrap()
+            .replace_all(&contents, replacement)
+            .into_owned()
+    });
+
+    insta_assert! {
+        input_path: path,
+        contents: cleaned,
+    };
+    Ok(())
+}
+
+fn run_test(path: &Path) -> datatest_stable::Result<()> {
+    run_test_impl(path)?;
+    Ok(())
+}
+
+datatest_stable::harness!(run_test, "tests/report_sources", r".*\.move$");
@@ -0,0 +1,13 @@
+module 0x6::gas_regression {
+    #[test]
+    fun sum() {
+        let x = 1 + 2;
+        assert!(x == 3, 0);
+    }
+
+    #[random_test]
+    fun random_sum(x: u8) {
+        let y = (x as u64) + 1;
+        assert!(y > 0, 0);
+    }
+}
@@ -0,0 +1,12 @@
+module 0x6::report {
+    #[test]
+    fun pass() {}
+
+    #[test]
+    fun fail() {
+        abort 0
+    }
+
+    #[random_test]
+    fun random_pass(_x: u64) {}
+}
@@ -7,6 +7,9 @@ use std::collections::BTreeMap;
 #[cfg(feature = "tracing")]
 use tracing::info;
 
+/// Extension of gas profiles written in the folded stack format.
+pub const FOLDED_STACKS_FILE_EXTENSION: &str = "folded";
+
 #[derive(Debug, Clone, Serialize)]
 pub struct FrameName {
     name: String,
@@ -62,7 +65,9 @@ pub struct GasProfiler {
     finished: bool,
 }
 
-#[cfg(feature = "tracing")]
+// Profiles are recorded whenever a profiler is attached to a gas meter, so that they can be
+// collected in builds without the `tracing` feature. Only profiles that are enabled through the
+// environment, and dumped to files when the profiler is dropped, need `tracing`.
 impl GasProfiler {
     // Used by profiler viz tool
     const OPEN_FRAME_IDENT: &'static str = "O";
@@ -70,7 +75,6 @@ impl GasProfiler {
 
     const TOP_LEVEL_FRAME_NAME: &'static str = "root";
 
-    #[cfg(feature = "tracing")]
     pub fn init(config: &Option<VMProfilerConfig>, name: String, start_gas: u64) -> Self {
         let mut prof = GasProfiler {
             exporter: "speedscope@1.15.2".to_string(),
@@ -101,31 +105,14 @@ impl GasProfiler {
         prof
     }
 
-    #[cfg(feature = "tracing")]
-    pub fn init_default_cfg(name: String, start_gas: u64) -> Self {
-        Self::init(
-            &VMProfilerConfig::get_default_config_if_enabled(),
-            name,
-            start_gas,
-        )
-    }
-
-    #[cfg(feature = "tracing")]
-    pub fn short_name(s: &String) -> String {
+    pub fn short_name(s: &str) -> String {
         s.split("::").last().unwrap_or(s).to_string()
     }
 
-    #[cfg(feature = "tracing")]
-    fn is_metered(&self) -> bool {
-        (self.profiles[0].end_value != 0) && (self.start_gas != 0)
-    }
-
-    #[cfg(feature = "tracing")]
     fn start_gas(&self) -> u64 {
         self.start_gas
     }
 
-    #[cfg(feature = "tracing")]
     fn add_frame(
         &mut self,
         frame_name: String,
@@ -146,7 +133,6 @@ impl GasProfiler {
         }
     }
 
-    #[cfg(feature = "tracing")]
     pub fn open_frame(&mut self, frame_name: String, metadata: String, gas_start: u64) {
         if self.config.is_none() || self.start_gas == 0 {
             return;
@@ -162,7 +148,6 @@ impl GasProfiler {
         });
     }
 
-    #[cfg(feature = "tracing")]
     pub fn close_frame(&mut self, frame_name: String, metadata: String, gas_end: u64) {
         if self.config.is_none() || self.start_gas == 0 {
             return;
@@ -178,7 +163,71 @@ impl GasProfiler {
         self.profiles[0].end_value = start - gas_end;
     }
 
-    #[cfg(feature = "tracing")]
+    /// Render the profile in the folded stack format accepted by flamegraph tools
+    /// (`root;caller;callee <gas>` per line), attributing to each stack the gas used while its
+    /// innermost frame was executing. Frames are named by their fully qualified name, so that
+    /// functions with the same name in different modules are told apart.
+    pub fn to_folded_stacks(&self) -> String {
+        let mut stacks: BTreeMap<String, u64> = BTreeMap::new();
+        let mut stack: Vec<u64> = vec![];
+        let mut last_at = 0;
+        for event in &self.profiles[0].events {
+            let gas = event.at.saturating_sub(last_at);
+            if gas > 0 && !stack.is_empty() {
+                let folded = stack
+                    .iter()
+                    .map(|idx| self.shared.frames[*idx as usize].file.replace(';', ":"))
+                    .collect::<Vec<_>>()
+                    .join(";");
+                *stacks.entry(folded).or_default() += gas;
+            }
+            last_at = event.at;
+            if event.ty == Self::OPEN_FRAME_IDENT {
+                stack.push(event.frame);
+            } else if let Some(pos) = stack.iter().rposition(|idx| *idx == event.frame) {
+                // Frames left open by an abort are closed along with their caller.
+                stack.truncate(pos);
+            }
+        }
+        stacks
+            .into_iter()
+            .map(|(folded, gas)| format!("{folded} {gas}\n"))
+            .collect()
+    }
+
+    /// Finish profiling and return the profile in the folded stack format, instead of dumping it
+    /// to a speedscope file.
+    pub fn finish_to_folded_stacks(&mut self) -> String {
+        self.close_top_level_frame();
+        self.to_folded_stacks()
+    }
+
+    fn close_top_level_frame(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+        self.finished = true;
+        let end_gas = self.start_gas() - self.profiles[0].end_value;
+        let mut q = Some(self);
+        profile_close_frame_impl!(&mut q, Self::TOP_LEVEL_FRAME_NAME.to_string(), end_gas);
+        true
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl GasProfiler {
+    pub fn init_default_cfg(name: String, start_gas: u64) -> Self {
+        Self::init(
+            &VMProfilerConfig::get_default_config_if_enabled(),
+            name,
+            start_gas,
+        )
+    }
+
+    fn is_metered(&self) -> bool {
+        (self.profiles[0].end_value != 0) && (self.start_gas != 0)
+    }
+
     pub fn to_file(&self) {
         use std::ffi::{OsStr, OsString};
         use std::fs::File;
@@ -218,16 +267,10 @@ impl GasProfiler {
         info!("Gas profile written to file: {}", p.display());
     }
 
-    #[cfg(feature = "tracing")]
     pub fn finish(&mut self) {
-        if self.finished {
-            return;
+        if self.close_top_level_frame() {
+            profile_dump_file!(self);
         }
-        self.finished = true;
-        let end_gas = self.start_gas() - self.profiles[0].end_value;
-        let mut q = Some(self);
-        profile_close_frame_impl!(&mut q, Self::TOP_LEVEL_FRAME_NAME.to_string(), end_gas);
-        profile_dump_file!(q.unwrap());
     }
 }
 
@@ -253,23 +296,23 @@ macro_rules! profile_open_frame {
     };
 }
 
+/// Record that the frame `$frame_name` was opened in `$profiler` (an `Option<&mut GasProfiler>`).
+/// Unlike `profile_open_frame!`, this records the frame in builds without the `tracing` feature.
 #[macro_export]
 macro_rules! profile_open_frame_impl {
-    ($profiler:expr, $frame_name:expr, $gas_rem:expr) => {
-        #[cfg(feature = "tracing")]
-        {
-            if let Some(profiler) = $profiler {
-                if let Some(config) = &profiler.config {
-                    let name = if !config.use_long_function_name {
-                        $crate::GasProfiler::short_name(&$frame_name)
-                    } else {
-                        $frame_name
-                    };
-                    profiler.open_frame(name, $frame_name, $gas_rem)
-                }
+    ($profiler:expr, $frame_name:expr, $gas_rem:expr) => {{
+        if let Some(profiler) = $profiler {
+            if let Some(config) = &profiler.config {
+                let frame_name: String = $frame_name;
+                let name = if !config.use_long_function_name {
+                    $crate::GasProfiler::short_name(&frame_name)
+                } else {
+                    frame_name.clone()
+                };
+                profiler.open_frame(name, frame_name, $gas_rem)
             }
         }
-    };
+    }};
 }
 
 #[macro_export]
@@ -287,23 +330,23 @@ macro_rules! profile_close_frame {
     };
 }
 
+/// Record that the frame `$frame_name` was closed in `$profiler` (an `Option<&mut GasProfiler>`).
+/// Unlike `profile_close_frame!`, this records the frame in builds without the `tracing` feature.
 #[macro_export]
 macro_rules! profile_close_frame_impl {
-    ($profiler:expr, $frame_name:expr, $gas_rem:expr) => {
-        #[cfg(feature = "tracing")]
-        {
-            if let Some(profiler) = $profiler {
-                if let Some(config) = &profiler.config {
-                    let name = if !config.use_long_function_name {
-                        $crate::GasProfiler::short_name(&$frame_name)
-                    } else {
-                        $frame_name
-                    };
-                    profiler.close_frame(name, $frame_name, $gas_rem)
-                }
+    ($profiler:expr, $frame_name:expr, $gas_rem:expr) => {{
+        if let Some(profiler) = $profiler {
+            if let Some(config) = &profiler.config {
+                let frame_name: String = $frame_name;
+                let name = if !config.use_long_function_name {
+                    $crate::GasProfiler::short_name(&frame_name)
+                } else {
+                    frame_name.clone()
+                };
+                profiler.close_frame(name, frame_name, $gas_rem)
             }
         }
-    };
+    }};
 }
 
 #[macro_export]
@@ -20,7 +20,15 @@ macro_rules! open_initial_frame {
                     $link_context,
                 )
             });
-            move_vm_profiler::profile_open_frame!($gas_meter, $function.pretty_string());
+        }
+        // Frames are profiled whenever the gas meter has a profiler, even without tracing.
+        {
+            let gas_rem = $gas_meter.remaining_gas().into();
+            move_vm_profiler::profile_open_frame_impl!(
+                $gas_meter.get_profiler_mut(),
+                $function.pretty_string(),
+                gas_rem
+            );
         }
     };
 }
@@ -32,7 +40,15 @@ macro_rules! close_initial_native_frame {
             $tracer.as_mut().map(|tracer| {
                 tracer.close_initial_native_frame($return_values, $gas_meter.remaining_gas().into())
             });
-            move_vm_profiler::profile_close_frame!($gas_meter, $function.pretty_string());
+        }
+        // Frames are profiled whenever the gas meter has a profiler, even without tracing.
+        {
+            let gas_rem = $gas_meter.remaining_gas().into();
+            move_vm_profiler::profile_close_frame_impl!(
+                $gas_meter.get_profiler_mut(),
+                $function.pretty_string(),
+                gas_rem
+            );
         }
     };
 }
@@ -52,7 +68,15 @@ macro_rules! close_frame {
                     $call_err,
                 )
             });
-            move_vm_profiler::profile_close_frame!($gas_meter, $function.pretty_string());
+        }
+        // Frames are profiled whenever the gas meter has a profiler, even without tracing.
+        {
+            let gas_rem = $gas_meter.remaining_gas().into();
+            move_vm_profiler::profile_close_frame_impl!(
+                $gas_meter.get_profiler_mut(),
+                $function.pretty_string(),
+                gas_rem
+            );
         }
     };
 }
@@ -72,7 +96,15 @@ macro_rules! open_frame {
                     $link_context,
                 )
             });
-            move_vm_profiler::profile_open_frame!($gas_meter, $function.pretty_string());
+        }
+        // Frames are profiled whenever the gas meter has a profiler, even without tracing.
+        {
+            let gas_rem = $gas_meter.remaining_gas().into();
+            move_vm_profiler::profile_open_frame_impl!(
+                $gas_meter.get_profiler_mut(),
+                $function.pretty_string(),
+                gas_rem
+            );
         }
     };
 }
@@ -4,7 +4,6 @@
 pub use checked::*;
 #[sui_macros::with_checked_arithmetic]
 mod checked {
-    #[cfg(feature = "tracing")]
     use move_vm_config::runtime::VMProfilerConfig;
     use std::cell::RefCell;
     use std::path::PathBuf;
@@ -44,12 +43,9 @@ mod checked {
     pub fn new_move_vm(
         natives: NativeFunctionTable,
         protocol_config: &ProtocolConfig,
-        _enable_profiler: Option<PathBuf>,
+        enable_profiler: Option<PathBuf>,
     ) -> Result<MoveVM, SuiError> {
-        #[cfg(not(feature = "tracing"))]
-        let vm_profiler_config = None;
-        #[cfg(feature = "tracing")]
-        let vm_profiler_config = _enable_profiler.clone().map(|path| VMProfilerConfig {
+        let vm_profiler_config = enable_profiler.map(|path| VMProfilerConfig {
             full_path: path,
             track_bytecode_instructions: false,
             use_long_function_name: false,
@@ -224,23 +224,22 @@ mod checked {
             );
 
             // Set the profiler if in CLI
-            #[skip_checked_arithmetic]
-            move_vm_profiler::tracing_feature_enabled! {
+            if vm.config().profiler_config.is_some() {
+                use crate::gas_meter::SuiGasMeter;
                 use move_vm_profiler::GasProfiler;
                 use move_vm_types::gas::GasMeter;
-                use crate::gas_meter::SuiGasMeter;
 
                 let ref_context: &RefCell<TxContext> = tx_context.borrow();
                 let tx_digest = ref_context.borrow().digest();
                 let remaining_gas: u64 = move_vm_types::gas::GasMeter::remaining_gas(&SuiGasMeter(
                     gas_charger.move_gas_status_mut(),
                 ))
-                        .into();
+                .into();
                 SuiGasMeter(gas_charger.move_gas_status_mut()).set_profiler(GasProfiler::init(
-                        &vm.config().profiler_config,
-                        format!("{}", tx_digest),
-                        remaining_gas,
-                    ));
+                    &vm.config().profiler_config,
+                    format!("{}", tx_digest),
+                    remaining_gas,
+                ));
             }
 
             Ok(Self {